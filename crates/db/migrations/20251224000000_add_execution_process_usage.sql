@@ -0,0 +1,14 @@
+-- Token usage reported by coding agents, cumulative per execution process and model
+CREATE TABLE execution_process_usage (
+    id                          BLOB PRIMARY KEY,
+    execution_process_id        BLOB NOT NULL,
+    model                       TEXT NOT NULL,
+    input_tokens                INTEGER NOT NULL DEFAULT 0,
+    output_tokens               INTEGER NOT NULL DEFAULT 0,
+    cache_creation_input_tokens INTEGER NOT NULL DEFAULT 0,
+    cache_read_input_tokens     INTEGER NOT NULL DEFAULT 0,
+    created_at                  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at                  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    UNIQUE (execution_process_id, model)
+);
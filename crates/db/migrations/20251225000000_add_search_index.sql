@@ -0,0 +1,111 @@
+-- Full-text search over task text, coding agent turns and assistant messages.
+-- search_documents holds what each indexed row belongs to; its id is the rowid of
+-- the matching search_index row. Tasks and turns are kept in sync by triggers,
+-- assistant messages are written by the log streamer as agents produce them.
+CREATE TABLE search_documents (
+    id                   INTEGER PRIMARY KEY,
+    source               TEXT NOT NULL CHECK (source IN ('task', 'turn', 'message')),
+    -- Task id, coding agent turn id or execution process id, depending on source
+    source_id            BLOB NOT NULL,
+    -- Conversation entry index for messages, 0 otherwise
+    entry_index          INTEGER NOT NULL DEFAULT 0,
+    task_id              BLOB NOT NULL,
+    workspace_id         BLOB,
+    execution_process_id BLOB,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    UNIQUE (source, source_id, entry_index)
+);
+
+CREATE INDEX idx_search_documents_task_id ON search_documents(task_id);
+CREATE INDEX idx_search_documents_execution_process_id ON search_documents(execution_process_id);
+
+CREATE VIRTUAL TABLE search_index USING fts5(
+    title,
+    body,
+    tokenize = 'porter unicode61'
+);
+
+-- Documents are removed with their task or execution process; drop their text too
+CREATE TRIGGER trg_search_documents_delete
+AFTER DELETE ON search_documents
+FOR EACH ROW
+BEGIN
+    DELETE FROM search_index WHERE rowid = OLD.id;
+END;
+
+CREATE TRIGGER trg_search_tasks_insert
+AFTER INSERT ON tasks
+FOR EACH ROW
+BEGIN
+    INSERT INTO search_documents (source, source_id, task_id, created_at)
+    VALUES ('task', NEW.id, NEW.id, NEW.created_at);
+    INSERT INTO search_index (rowid, title, body)
+    SELECT id, NEW.title, COALESCE(NEW.description, '')
+    FROM search_documents
+    WHERE source = 'task' AND source_id = NEW.id AND entry_index = 0;
+END;
+
+CREATE TRIGGER trg_search_tasks_update
+AFTER UPDATE OF title, description ON tasks
+FOR EACH ROW
+BEGIN
+    UPDATE search_index
+    SET title = NEW.title, body = COALESCE(NEW.description, '')
+    WHERE rowid = (
+        SELECT id FROM search_documents
+        WHERE source = 'task' AND source_id = NEW.id AND entry_index = 0
+    );
+END;
+
+CREATE TRIGGER trg_search_coding_agent_turns_insert
+AFTER INSERT ON coding_agent_turns
+FOR EACH ROW
+BEGIN
+    INSERT INTO search_documents (source, source_id, task_id, workspace_id, execution_process_id, created_at)
+    SELECT 'turn', NEW.id, w.task_id, w.id, ep.id, NEW.created_at
+    FROM execution_processes ep
+    JOIN sessions s ON s.id = ep.session_id
+    JOIN workspaces w ON w.id = s.workspace_id
+    WHERE ep.id = NEW.execution_process_id;
+    INSERT INTO search_index (rowid, title, body)
+    SELECT id, COALESCE(NEW.prompt, ''), COALESCE(NEW.summary, '')
+    FROM search_documents
+    WHERE source = 'turn' AND source_id = NEW.id AND entry_index = 0;
+END;
+
+CREATE TRIGGER trg_search_coding_agent_turns_update
+AFTER UPDATE OF prompt, summary ON coding_agent_turns
+FOR EACH ROW
+BEGIN
+    UPDATE search_index
+    SET title = COALESCE(NEW.prompt, ''), body = COALESCE(NEW.summary, '')
+    WHERE rowid = (
+        SELECT id FROM search_documents
+        WHERE source = 'turn' AND source_id = NEW.id AND entry_index = 0
+    );
+END;
+
+-- Index existing tasks and turns
+INSERT INTO search_documents (source, source_id, task_id, created_at)
+SELECT 'task', id, id, created_at FROM tasks;
+
+INSERT INTO search_documents (source, source_id, task_id, workspace_id, execution_process_id, created_at)
+SELECT 'turn', cat.id, w.task_id, w.id, ep.id, cat.created_at
+FROM coding_agent_turns cat
+JOIN execution_processes ep ON ep.id = cat.execution_process_id
+JOIN sessions s ON s.id = ep.session_id
+JOIN workspaces w ON w.id = s.workspace_id;
+
+INSERT INTO search_index (rowid, title, body)
+SELECT d.id, t.title, COALESCE(t.description, '')
+FROM search_documents d
+JOIN tasks t ON t.id = d.source_id
+WHERE d.source = 'task';
+
+INSERT INTO search_index (rowid, title, body)
+SELECT d.id, COALESCE(cat.prompt, ''), COALESCE(cat.summary, '')
+FROM search_documents d
+JOIN coding_agent_turns cat ON cat.id = d.source_id
+WHERE d.source = 'turn';
@@ -0,0 +1,2 @@
+-- Optional sandbox for executor processes of a project (JSON SandboxConfig), NULL when unset
+ALTER TABLE projects ADD COLUMN sandbox_config TEXT;
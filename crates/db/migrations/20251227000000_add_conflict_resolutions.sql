@@ -0,0 +1,17 @@
+-- Coding agent runs dispatched to resolve rebase/merge conflicts in a workspace repo.
+-- When the run finishes the interrupted git operation is continued automatically,
+-- provided no conflict markers remain in the files that were conflicted.
+CREATE TABLE conflict_resolutions (
+    id                   BLOB PRIMARY KEY,
+    execution_process_id BLOB NOT NULL UNIQUE,
+    repo_id              BLOB NOT NULL,
+    -- JSON array of the files that were conflicted when the run was dispatched
+    conflicted_files     TEXT NOT NULL DEFAULT '[]',
+    status               TEXT NOT NULL DEFAULT 'pending'
+                            CHECK (status IN ('pending', 'resolved', 'failed')),
+    error                TEXT,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
@@ -0,0 +1,11 @@
+-- Reusable multi-step pipelines (e.g. plan → implement → review). Each step runs one
+-- executor profile with its own prompt template; starting a task with a pipeline chains
+-- the steps as coding agent actions.
+CREATE TABLE pipeline_templates (
+    id         BLOB PRIMARY KEY,
+    name       TEXT NOT NULL UNIQUE,
+    -- JSON array of steps: { name, executor_profile_id, prompt }
+    steps      TEXT NOT NULL DEFAULT '[]',
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
@@ -0,0 +1,76 @@
+-- Per-repo verify scripts, run after each coding agent turn
+ALTER TABLE project_repos ADD COLUMN verify_script TEXT;
+ALTER TABLE project_repos ADD COLUMN verify_max_retries INTEGER NOT NULL DEFAULT 0;
+
+-- Add 'verify' to execution_processes.run_reason
+-- SQLite can't alter CHECK constraints, so rebuild the table
+
+-- sqlx workaround: end auto-transaction to allow PRAGMA to take effect
+-- https://github.com/launchbadge/sqlx/issues/2085#issuecomment-1499859906
+COMMIT;
+
+PRAGMA foreign_keys = OFF;
+
+BEGIN TRANSACTION;
+
+CREATE TABLE execution_processes_new (
+    id              BLOB PRIMARY KEY,
+    session_id      BLOB NOT NULL,
+    run_reason      TEXT NOT NULL DEFAULT 'setupscript'
+                       CHECK (run_reason IN ('setupscript','codingagent','devserver','cleanupscript','verify')),
+    executor_action TEXT NOT NULL DEFAULT '{}',
+    status          TEXT NOT NULL DEFAULT 'running'
+                       CHECK (status IN ('queued','running','completed','failed','killed')),
+    exit_code       INTEGER,
+    dropped         INTEGER NOT NULL DEFAULT 0,
+    started_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    completed_at    TEXT,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
+INSERT INTO execution_processes_new (id, session_id, run_reason, executor_action, status, exit_code, dropped, started_at, completed_at, created_at, updated_at)
+SELECT id, session_id, run_reason, executor_action, status, exit_code, dropped, started_at, completed_at, created_at, updated_at
+FROM execution_processes;
+
+-- This trigger reads execution_processes, which must exist for the rename to succeed
+DROP TRIGGER trg_search_coding_agent_turns_insert;
+
+DROP TABLE execution_processes;
+ALTER TABLE execution_processes_new RENAME TO execution_processes;
+
+CREATE INDEX idx_execution_processes_session_id ON execution_processes(session_id);
+CREATE INDEX idx_execution_processes_status ON execution_processes(status);
+CREATE INDEX idx_execution_processes_run_reason ON execution_processes(run_reason);
+
+CREATE INDEX idx_execution_processes_session_status_run_reason
+ON execution_processes (session_id, status, run_reason);
+
+CREATE INDEX idx_execution_processes_session_run_reason_created
+ON execution_processes (session_id, run_reason, created_at DESC);
+
+CREATE TRIGGER trg_search_coding_agent_turns_insert
+AFTER INSERT ON coding_agent_turns
+FOR EACH ROW
+BEGIN
+    INSERT INTO search_documents (source, source_id, task_id, workspace_id, execution_process_id, created_at)
+    SELECT 'turn', NEW.id, w.task_id, w.id, ep.id, NEW.created_at
+    FROM execution_processes ep
+    JOIN sessions s ON s.id = ep.session_id
+    JOIN workspaces w ON w.id = s.workspace_id
+    WHERE ep.id = NEW.execution_process_id;
+    INSERT INTO search_index (rowid, title, body)
+    SELECT id, COALESCE(NEW.prompt, ''), COALESCE(NEW.summary, '')
+    FROM search_documents
+    WHERE source = 'turn' AND source_id = NEW.id AND entry_index = 0;
+END;
+
+PRAGMA foreign_key_check;
+
+COMMIT;
+
+PRAGMA foreign_keys = ON;
+
+-- sqlx workaround: start empty transaction for sqlx to close gracefully
+BEGIN TRANSACTION;
@@ -0,0 +1,27 @@
+-- Best-of-N: several attempts of one task started together with different executor
+-- profiles so their results can be compared and a winner picked.
+CREATE TABLE attempt_groups (
+    id                  BLOB PRIMARY KEY,
+    task_id             BLOB NOT NULL,
+    winner_workspace_id BLOB,
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (winner_workspace_id) REFERENCES workspaces(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_attempt_groups_task_id ON attempt_groups(task_id);
+
+CREATE TABLE attempt_group_members (
+    attempt_group_id    BLOB NOT NULL,
+    workspace_id        BLOB NOT NULL UNIQUE,
+    -- JSON ExecutorProfileId the attempt was started with
+    executor_profile_id TEXT NOT NULL,
+    -- Set once another attempt won; the workspace is then cleaned up without waiting
+    -- for it to expire
+    discarded_at        TEXT,
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (attempt_group_id, workspace_id),
+    FOREIGN KEY (attempt_group_id) REFERENCES attempt_groups(id) ON DELETE CASCADE,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE
+);
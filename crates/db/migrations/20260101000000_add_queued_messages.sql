@@ -0,0 +1,14 @@
+-- Follow-up messages queued for a session, sent one at a time whenever the agent
+-- finishes. Replaces the in-memory single message queue so queues survive restarts.
+CREATE TABLE queued_messages (
+    id         BLOB PRIMARY KEY,
+    session_id BLOB NOT NULL,
+    -- JSON DraftFollowUpData: the message and the executor variant to send it with
+    data       TEXT NOT NULL,
+    position   INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_queued_messages_session_id_position ON queued_messages(session_id, position);
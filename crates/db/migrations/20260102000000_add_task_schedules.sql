@@ -0,0 +1,26 @@
+-- Tasks created and started automatically, once at a given time or on a cron schedule
+CREATE TABLE task_schedules (
+    id                   BLOB PRIMARY KEY,
+    project_id           BLOB NOT NULL,
+    name                 TEXT NOT NULL,
+    -- Five-field cron expression in the server's local time; NULL for a one-off run
+    cron_expression      TEXT,
+    title_template       TEXT NOT NULL,
+    description_template TEXT,
+    -- JSON ExecutorProfileId the created tasks are started with
+    executor_profile_id  TEXT NOT NULL,
+    -- JSON array of {repo_id, target_branch}; empty uses every project repo at its
+    -- current branch
+    repos                TEXT NOT NULL DEFAULT '[]',
+    enabled              INTEGER NOT NULL DEFAULT 1,
+    next_run_at          TEXT,
+    last_run_at          TEXT,
+    last_task_id         BLOB,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (last_task_id) REFERENCES tasks(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_task_schedules_project_id ON task_schedules(project_id);
+CREATE INDEX idx_task_schedules_next_run_at ON task_schedules(enabled, next_run_at);
@@ -0,0 +1,6 @@
+-- How task branches land on their target branch, and whether commits use Conventional Commit messages
+ALTER TABLE projects ADD COLUMN merge_mode TEXT NOT NULL DEFAULT 'squash'
+    CHECK (merge_mode IN ('squash', 'merge_commit', 'rebase'));
+ALTER TABLE projects ADD COLUMN conventional_commits INTEGER NOT NULL DEFAULT 0;
+-- Executor profile (JSON ExecutorProfileId) that writes commit messages; NULL uses the session's executor
+ALTER TABLE projects ADD COLUMN commit_message_executor_profile_id TEXT;
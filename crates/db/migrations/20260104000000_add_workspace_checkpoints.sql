@@ -0,0 +1,13 @@
+-- Named snapshots of every repo worktree of a workspace, uncommitted changes included
+CREATE TABLE workspace_checkpoints (
+    id           BLOB PRIMARY KEY,
+    workspace_id BLOB NOT NULL,
+    name         TEXT NOT NULL,
+    -- JSON array of {repo_id, head_commit, snapshot_commit}; the snapshot commits are kept
+    -- alive by refs/vibe-kanban/checkpoints/<id> in each repo
+    repo_states  TEXT NOT NULL DEFAULT '[]',
+    created_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_workspace_checkpoints_workspace_id ON workspace_checkpoints(workspace_id);
@@ -0,0 +1,22 @@
+-- Per-project rules deciding tool call approvals before a human is asked. Enabled rules are
+-- checked in position order and the first one matching the call decides.
+CREATE TABLE approval_rules (
+    id            BLOB PRIMARY KEY,
+    project_id    BLOB NOT NULL,
+    name          TEXT NOT NULL,
+    position      INTEGER NOT NULL DEFAULT 0,
+    -- Conditions, all of which must match; NULL matches anything. tool_name and path_glob
+    -- are globs, command_regex an unanchored regex.
+    tool_name     TEXT,
+    path_glob     TEXT,
+    command_regex TEXT,
+    decision      TEXT NOT NULL CHECK (decision IN ('approve', 'deny', 'ask')),
+    -- Sent to the agent when the rule denies a call
+    reason        TEXT,
+    enabled       INTEGER NOT NULL DEFAULT 1,
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_approval_rules_project_id ON approval_rules(project_id, position);
@@ -0,0 +1,10 @@
+-- Spooled execution processes that keep running when the server stops, so they can be
+-- re-attached to on startup
+CREATE TABLE execution_process_spools (
+    execution_process_id BLOB PRIMARY KEY,
+    -- Process group the process runs in
+    pgid                 INTEGER NOT NULL,
+    spool_dir            TEXT NOT NULL,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
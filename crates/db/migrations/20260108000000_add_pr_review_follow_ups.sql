@@ -0,0 +1,25 @@
+-- Coding agent runs dispatched to address review comments on a workspace repo's pull request.
+-- When the run finishes the branch is pushed, and the addressed comments are optionally
+-- answered with the commit that addressed them.
+CREATE TABLE pr_review_follow_ups (
+    id                   BLOB PRIMARY KEY,
+    execution_process_id BLOB NOT NULL UNIQUE,
+    workspace_id         BLOB NOT NULL,
+    repo_id              BLOB NOT NULL,
+    pr_number            INTEGER NOT NULL,
+    pr_url               TEXT NOT NULL,
+    -- JSON array of the keys of the comments the run addresses, e.g. "review:123"
+    comment_keys         TEXT NOT NULL DEFAULT '[]',
+    reply_to_comments    INTEGER NOT NULL DEFAULT 0,
+    status               TEXT NOT NULL DEFAULT 'pending'
+                            CHECK (status IN ('pending', 'pushed', 'failed')),
+    commit_sha           TEXT,
+    error                TEXT,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_pr_review_follow_ups_workspace_repo ON pr_review_follow_ups(workspace_id, repo_id);
@@ -0,0 +1,10 @@
+-- CI state of the head commit of a PR, as last seen by the PR monitor. NULL until checks report.
+ALTER TABLE merges ADD COLUMN pr_ci_status TEXT
+    CHECK (pr_ci_status IN ('pending', 'success', 'failure'));
+ALTER TABLE merges ADD COLUMN pr_ci_head_sha TEXT;
+-- JSON array of the names of the failed checks
+ALTER TABLE merges ADD COLUMN pr_ci_failed_checks TEXT;
+ALTER TABLE merges ADD COLUMN pr_ci_updated_at TEXT;
+-- Coding agent runs dispatched to fix failing checks, and the head commit the last one was for
+ALTER TABLE merges ADD COLUMN pr_ci_fix_attempts INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE merges ADD COLUMN pr_ci_fix_head_sha TEXT;
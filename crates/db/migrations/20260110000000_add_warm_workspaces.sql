@@ -0,0 +1,34 @@
+-- Worktrees kept ready per project with the setup scripts already run, so that new attempts
+-- don't have to wait for them. 0 disables the pool.
+ALTER TABLE projects ADD COLUMN warm_workspace_count INTEGER NOT NULL DEFAULT 0;
+
+CREATE TABLE warm_workspaces (
+    id            BLOB PRIMARY KEY,
+    project_id    BLOB NOT NULL,
+    -- Directory holding a worktree per project repo, like a workspace's container_ref
+    container_ref TEXT NOT NULL,
+    -- Branch checked out in every worktree, renamed to the workspace's branch when claimed
+    branch        TEXT NOT NULL,
+    status        TEXT NOT NULL DEFAULT 'preparing'
+                     CHECK (status IN ('preparing', 'ready', 'failed', 'claimed')),
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_warm_workspaces_project_id ON warm_workspaces(project_id);
+
+CREATE TABLE warm_workspace_repos (
+    warm_workspace_id BLOB NOT NULL,
+    repo_id           BLOB NOT NULL,
+    target_branch     TEXT NOT NULL,
+    -- Commit of the target branch the worktree is at
+    base_commit       TEXT NOT NULL,
+    -- Setup script and lockfile hash of the last setup run, NULL without a setup script
+    setup_key         TEXT,
+    setup_exit_code   INTEGER,
+    setup_output      TEXT,
+    PRIMARY KEY (warm_workspace_id, repo_id),
+    FOREIGN KEY (warm_workspace_id) REFERENCES warm_workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
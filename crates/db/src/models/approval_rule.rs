@@ -0,0 +1,246 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// What happens to a tool call matched by an approval rule
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "approval_rule_decision", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalRuleDecision {
+    Approve,
+    Deny,
+    /// Wait for a human, e.g. to carve an exception out of a broader rule further down
+    Ask,
+}
+
+/// Decides tool call approvals of a project's agents before a human is asked. Enabled rules
+/// are checked in `position` order and the first one matching the call decides.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ApprovalRule {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub name: String,
+    pub position: i64,
+    /// Glob matched against the tool name, case-insensitively; `None` matches every tool
+    pub tool_name: Option<String>,
+    /// Glob matched against the path of file reads and edits; other calls never match
+    pub path_glob: Option<String>,
+    /// Regex searched for in the command of command runs; other calls never match
+    pub command_regex: Option<String>,
+    pub decision: ApprovalRuleDecision,
+    /// Sent to the agent when the rule denies a call
+    pub reason: Option<String>,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateApprovalRule {
+    pub project_id: Uuid,
+    pub name: String,
+    pub tool_name: Option<String>,
+    pub path_glob: Option<String>,
+    pub command_regex: Option<String>,
+    pub decision: ApprovalRuleDecision,
+    pub reason: Option<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateApprovalRule {
+    pub name: Option<String>,
+    /// Moves the rule; rules with equal positions keep their creation order
+    pub position: Option<i64>,
+    /// An empty string removes the condition
+    pub tool_name: Option<String>,
+    /// An empty string removes the condition
+    pub path_glob: Option<String>,
+    /// An empty string removes the condition
+    pub command_regex: Option<String>,
+    pub decision: Option<ApprovalRuleDecision>,
+    /// An empty string removes the reason
+    pub reason: Option<String>,
+    pub enabled: Option<bool>,
+}
+
+/// `update` if set, an empty string clearing it, `existing` otherwise
+fn updated_text<'a>(update: &'a Option<String>, existing: &'a Option<String>) -> Option<&'a str> {
+    match update {
+        Some(value) => Some(value.as_str()).filter(|value| !value.is_empty()),
+        None => existing.as_deref(),
+    }
+}
+
+impl ApprovalRule {
+    /// Reason given to the agent when this rule denies a call
+    pub fn denial_reason(&self) -> String {
+        match self.reason.as_deref() {
+            Some(reason) => format!("{reason} (approval rule \"{}\")", self.name),
+            None => format!("Denied by approval rule \"{}\"", self.name),
+        }
+    }
+
+    /// Rules of the project in the order they are checked
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApprovalRule,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      name,
+                      position as "position!: i64",
+                      tool_name,
+                      path_glob,
+                      command_regex,
+                      decision as "decision!: ApprovalRuleDecision",
+                      reason,
+                      enabled as "enabled!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM approval_rules
+               WHERE project_id = $1
+               ORDER BY position ASC, created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApprovalRule,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      name,
+                      position as "position!: i64",
+                      tool_name,
+                      path_glob,
+                      command_regex,
+                      decision as "decision!: ApprovalRuleDecision",
+                      reason,
+                      enabled as "enabled!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM approval_rules
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Create a rule checked after the project's existing rules
+    pub async fn create(pool: &SqlitePool, data: &CreateApprovalRule) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let tool_name = data.tool_name.as_deref().filter(|value| !value.is_empty());
+        let path_glob = data.path_glob.as_deref().filter(|value| !value.is_empty());
+        let command_regex = data
+            .command_regex
+            .as_deref()
+            .filter(|value| !value.is_empty());
+        let reason = data.reason.as_deref().filter(|value| !value.is_empty());
+        sqlx::query_as!(
+            ApprovalRule,
+            r#"INSERT INTO approval_rules (
+                id, project_id, name, position, tool_name, path_glob, command_regex, decision,
+                reason, enabled
+               )
+               VALUES (
+                $1, $2, $3,
+                (SELECT COALESCE(MAX(position) + 1, 0) FROM approval_rules WHERE project_id = $2),
+                $4, $5, $6, $7, $8, $9
+               )
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         name,
+                         position as "position!: i64",
+                         tool_name,
+                         path_glob,
+                         command_regex,
+                         decision as "decision!: ApprovalRuleDecision",
+                         reason,
+                         enabled as "enabled!: bool",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.project_id,
+            data.name,
+            tool_name,
+            path_glob,
+            command_regex,
+            data.decision,
+            reason,
+            data.enabled
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateApprovalRule,
+    ) -> Result<Self, sqlx::Error> {
+        let existing = Self::find_by_id(pool, id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+
+        let name = data.name.as_ref().unwrap_or(&existing.name);
+        let position = data.position.unwrap_or(existing.position);
+        let tool_name = updated_text(&data.tool_name, &existing.tool_name);
+        let path_glob = updated_text(&data.path_glob, &existing.path_glob);
+        let command_regex = updated_text(&data.command_regex, &existing.command_regex);
+        let decision = data.decision.unwrap_or(existing.decision);
+        let reason = updated_text(&data.reason, &existing.reason);
+        let enabled = data.enabled.unwrap_or(existing.enabled);
+
+        sqlx::query_as!(
+            ApprovalRule,
+            r#"UPDATE approval_rules
+               SET name = $2, position = $3, tool_name = $4, path_glob = $5, command_regex = $6,
+                   decision = $7, reason = $8, enabled = $9,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         name,
+                         position as "position!: i64",
+                         tool_name,
+                         path_glob,
+                         command_regex,
+                         decision as "decision!: ApprovalRuleDecision",
+                         reason,
+                         enabled as "enabled!: bool",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            name,
+            position,
+            tool_name,
+            path_glob,
+            command_regex,
+            decision,
+            reason,
+            enabled
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM approval_rules WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,166 @@
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Attempts of one task started together with different executor profiles to be compared
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct AttemptGroup {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub winner_workspace_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct AttemptGroupMember {
+    pub attempt_group_id: Uuid,
+    pub workspace_id: Uuid,
+    #[ts(type = "ExecutorProfileId")]
+    pub executor_profile_id: sqlx::types::Json<ExecutorProfileId>,
+    /// Set once another attempt of the group won; the workspace is then cleaned up
+    pub discarded_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AttemptGroup {
+    pub async fn create(pool: &SqlitePool, id: Uuid, task_id: Uuid) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptGroup,
+            r#"INSERT INTO attempt_groups (id, task_id)
+               VALUES ($1, $2)
+               RETURNING id as "id!: Uuid",
+                         task_id as "task_id!: Uuid",
+                         winner_workspace_id as "winner_workspace_id: Uuid",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            task_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptGroup,
+            r#"SELECT id as "id!: Uuid",
+                      task_id as "task_id!: Uuid",
+                      winner_workspace_id as "winner_workspace_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM attempt_groups
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptGroup,
+            r#"SELECT id as "id!: Uuid",
+                      task_id as "task_id!: Uuid",
+                      winner_workspace_id as "winner_workspace_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM attempt_groups
+               WHERE task_id = $1
+               ORDER BY created_at DESC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Record `workspace_id` as the winner and mark every other member as discarded
+    pub async fn set_winner(
+        pool: &SqlitePool,
+        id: Uuid,
+        workspace_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let group = sqlx::query_as!(
+            AttemptGroup,
+            r#"UPDATE attempt_groups
+               SET winner_workspace_id = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                         task_id as "task_id!: Uuid",
+                         winner_workspace_id as "winner_workspace_id: Uuid",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            workspace_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        sqlx::query!(
+            r#"UPDATE attempt_group_members
+               SET discarded_at = CASE
+                   WHEN workspace_id = $2 THEN NULL
+                   ELSE COALESCE(discarded_at, datetime('now', 'subsec'))
+               END
+               WHERE attempt_group_id = $1"#,
+            id,
+            workspace_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(group)
+    }
+}
+
+impl AttemptGroupMember {
+    pub async fn create(
+        pool: &SqlitePool,
+        attempt_group_id: Uuid,
+        workspace_id: Uuid,
+        executor_profile_id: &ExecutorProfileId,
+    ) -> Result<Self, sqlx::Error> {
+        let executor_profile_id = sqlx::types::Json(executor_profile_id);
+        sqlx::query_as!(
+            AttemptGroupMember,
+            r#"INSERT INTO attempt_group_members (attempt_group_id, workspace_id, executor_profile_id)
+               VALUES ($1, $2, $3)
+               RETURNING attempt_group_id as "attempt_group_id!: Uuid",
+                         workspace_id as "workspace_id!: Uuid",
+                         executor_profile_id as "executor_profile_id!: sqlx::types::Json<ExecutorProfileId>",
+                         discarded_at as "discarded_at: DateTime<Utc>",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            attempt_group_id,
+            workspace_id,
+            executor_profile_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_attempt_group_id(
+        pool: &SqlitePool,
+        attempt_group_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptGroupMember,
+            r#"SELECT attempt_group_id as "attempt_group_id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      executor_profile_id as "executor_profile_id!: sqlx::types::Json<ExecutorProfileId>",
+                      discarded_at as "discarded_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM attempt_group_members
+               WHERE attempt_group_id = $1
+               ORDER BY created_at ASC"#,
+            attempt_group_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -0,0 +1,102 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq)]
+#[sqlx(type_name = "conflict_resolution_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictResolutionStatus {
+    /// The coding agent is still working on the conflicts
+    Pending,
+    /// Markers were gone and the interrupted git operation was continued
+    Resolved,
+    /// The agent failed or left conflict markers behind; the operation is still in progress
+    Failed,
+}
+
+/// A coding agent run dispatched to resolve the conflicts of one workspace repo
+#[derive(Debug, Clone, FromRow)]
+pub struct ConflictResolution {
+    pub id: Uuid,
+    pub execution_process_id: Uuid,
+    pub repo_id: Uuid,
+    pub conflicted_files: sqlx::types::Json<Vec<String>>,
+    pub status: ConflictResolutionStatus,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ConflictResolution {
+    pub async fn create(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        repo_id: Uuid,
+        conflicted_files: &[String],
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let conflicted_files = sqlx::types::Json(conflicted_files);
+        sqlx::query_as!(
+            ConflictResolution,
+            r#"INSERT INTO conflict_resolutions (id, execution_process_id, repo_id, conflicted_files)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id as "id!: Uuid",
+                         execution_process_id as "execution_process_id!: Uuid",
+                         repo_id as "repo_id!: Uuid",
+                         conflicted_files as "conflicted_files!: sqlx::types::Json<Vec<String>>",
+                         status as "status!: ConflictResolutionStatus",
+                         error,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            execution_process_id,
+            repo_id,
+            conflicted_files
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// The still pending resolution handled by an execution process, if any
+    pub async fn find_pending_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ConflictResolution,
+            r#"SELECT id as "id!: Uuid",
+                      execution_process_id as "execution_process_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      conflicted_files as "conflicted_files!: sqlx::types::Json<Vec<String>>",
+                      status as "status!: ConflictResolutionStatus",
+                      error,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM conflict_resolutions
+               WHERE execution_process_id = $1 AND status = 'pending'"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn update_status(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: ConflictResolutionStatus,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE conflict_resolutions
+               SET status = $2, error = $3, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            status,
+            error
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,297 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Limits on coding agent executions, for every task of a project or for a single task
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ExecutionBudget {
+    pub id: Uuid,
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub max_duration_secs: Option<i64>,
+    pub max_follow_ups: Option<i64>,
+    pub max_tokens: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Limits of a budget, `None` meaning unlimited. A task's limits fall back to its project's
+/// one by one.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+pub struct BudgetLimits {
+    /// Longest a single coding agent execution may run
+    pub max_duration_secs: Option<i64>,
+    /// Most follow-ups sent to agents across the task's attempts, automatic ones included
+    pub max_follow_ups: Option<i64>,
+    /// Most input and output tokens, not counting cache reads, across the task's attempts.
+    /// Only agents that report usage are limited.
+    pub max_tokens: Option<i64>,
+}
+
+/// What a task has used of its budget so far
+#[derive(Debug, Clone, Default)]
+pub struct BudgetUsage {
+    /// Wall-clock time of the running execution
+    pub duration_secs: i64,
+    pub follow_ups: i64,
+    pub tokens: i64,
+}
+
+impl BudgetLimits {
+    pub fn is_unlimited(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Every limit that is set must be positive
+    pub fn is_valid(&self) -> bool {
+        [self.max_duration_secs, self.max_follow_ups, self.max_tokens]
+            .into_iter()
+            .flatten()
+            .all(|limit| limit > 0)
+    }
+
+    /// These limits, with the ones not set taken from `fallback`
+    pub fn or(self, fallback: BudgetLimits) -> BudgetLimits {
+        BudgetLimits {
+            max_duration_secs: self.max_duration_secs.or(fallback.max_duration_secs),
+            max_follow_ups: self.max_follow_ups.or(fallback.max_follow_ups),
+            max_tokens: self.max_tokens.or(fallback.max_tokens),
+        }
+    }
+
+    /// Why `usage` is over these limits, if it is
+    pub fn exceeded_by(&self, usage: &BudgetUsage) -> Option<String> {
+        if let Some(max) = self.max_duration_secs
+            && usage.duration_secs > max
+        {
+            return Some(format!(
+                "Stopped after running for {}s, the budget allows {max}s per execution.",
+                usage.duration_secs
+            ));
+        }
+        if let Some(max) = self.max_follow_ups
+            && usage.follow_ups > max
+        {
+            return Some(format!(
+                "Stopped because this is follow-up {} of the task, the budget allows {max}.",
+                usage.follow_ups
+            ));
+        }
+        if let Some(max) = self.max_tokens
+            && usage.tokens > max
+        {
+            return Some(format!(
+                "Stopped after the task used {} tokens, the budget allows {max}.",
+                usage.tokens
+            ));
+        }
+        None
+    }
+}
+
+impl ExecutionBudget {
+    pub fn limits(&self) -> BudgetLimits {
+        BudgetLimits {
+            max_duration_secs: self.max_duration_secs,
+            max_follow_ups: self.max_follow_ups,
+            max_tokens: self.max_tokens,
+        }
+    }
+
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionBudget,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id: Uuid",
+                      task_id as "task_id: Uuid",
+                      max_duration_secs,
+                      max_follow_ups,
+                      max_tokens,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM execution_budgets
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionBudget,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id: Uuid",
+                      task_id as "task_id: Uuid",
+                      max_duration_secs,
+                      max_follow_ups,
+                      max_tokens,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM execution_budgets
+               WHERE task_id = $1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Limits that apply to the task: its own, falling back to its project's
+    pub async fn limits_for_task(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        task_id: Uuid,
+    ) -> Result<BudgetLimits, sqlx::Error> {
+        let task_limits = Self::find_by_task_id(pool, task_id)
+            .await?
+            .map(|budget| budget.limits())
+            .unwrap_or_default();
+        let project_limits = Self::find_by_project_id(pool, project_id)
+            .await?
+            .map(|budget| budget.limits())
+            .unwrap_or_default();
+        Ok(task_limits.or(project_limits))
+    }
+
+    pub async fn set_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        limits: &BudgetLimits,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            ExecutionBudget,
+            r#"INSERT INTO execution_budgets (id, project_id, max_duration_secs, max_follow_ups, max_tokens)
+               VALUES ($1, $2, $3, $4, $5)
+               ON CONFLICT(project_id) DO UPDATE SET
+                max_duration_secs = excluded.max_duration_secs,
+                max_follow_ups = excluded.max_follow_ups,
+                max_tokens = excluded.max_tokens,
+                updated_at = datetime('now', 'subsec')
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id: Uuid",
+                         task_id as "task_id: Uuid",
+                         max_duration_secs,
+                         max_follow_ups,
+                         max_tokens,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            limits.max_duration_secs,
+            limits.max_follow_ups,
+            limits.max_tokens
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn set_for_task(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        limits: &BudgetLimits,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            ExecutionBudget,
+            r#"INSERT INTO execution_budgets (id, task_id, max_duration_secs, max_follow_ups, max_tokens)
+               VALUES ($1, $2, $3, $4, $5)
+               ON CONFLICT(task_id) DO UPDATE SET
+                max_duration_secs = excluded.max_duration_secs,
+                max_follow_ups = excluded.max_follow_ups,
+                max_tokens = excluded.max_tokens,
+                updated_at = datetime('now', 'subsec')
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id: Uuid",
+                         task_id as "task_id: Uuid",
+                         max_duration_secs,
+                         max_follow_ups,
+                         max_tokens,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            task_id,
+            limits.max_duration_secs,
+            limits.max_follow_ups,
+            limits.max_tokens
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM execution_budgets WHERE project_id = $1",
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete_for_task(pool: &SqlitePool, task_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM execution_budgets WHERE task_id = $1", task_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task_limits_fall_back_to_project() {
+        let task = BudgetLimits {
+            max_duration_secs: Some(600),
+            ..Default::default()
+        };
+        let project = BudgetLimits {
+            max_duration_secs: Some(3600),
+            max_follow_ups: None,
+            max_tokens: Some(1_000_000),
+        };
+        assert_eq!(
+            task.or(project),
+            BudgetLimits {
+                max_duration_secs: Some(600),
+                max_follow_ups: None,
+                max_tokens: Some(1_000_000),
+            }
+        );
+    }
+
+    #[test]
+    fn test_exceeded_by() {
+        let limits = BudgetLimits {
+            max_duration_secs: Some(600),
+            max_follow_ups: Some(3),
+            max_tokens: None,
+        };
+        let within = BudgetUsage {
+            duration_secs: 600,
+            follow_ups: 3,
+            tokens: 50_000_000,
+        };
+        assert_eq!(limits.exceeded_by(&within), None);
+
+        let over = BudgetUsage {
+            follow_ups: 4,
+            ..within
+        };
+        assert!(limits.exceeded_by(&over).unwrap().contains("follow-up 4"));
+        assert!(BudgetLimits::default().is_unlimited());
+    }
+}
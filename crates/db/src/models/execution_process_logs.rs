@@ -66,4 +66,19 @@ impl ExecutionProcessLogs {
 
         Ok(())
     }
+
+    /// Delete the logs of an execution process, e.g. before they are written again from a
+    /// complete copy
+    pub async fn delete_by_execution_id(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM execution_process_logs WHERE execution_id = $1",
+            execution_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
 }
@@ -0,0 +1,66 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, SqlitePool};
+use uuid::Uuid;
+
+/// Where a spooled execution process runs and writes its output, kept while it runs so a
+/// restarted server can re-attach to it
+#[derive(Debug, Clone, FromRow)]
+pub struct ExecutionProcessSpool {
+    pub execution_process_id: Uuid,
+    /// Process group of the process, led by its launcher
+    pub pgid: i64,
+    pub spool_dir: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ExecutionProcessSpool {
+    pub async fn create(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        pgid: i64,
+        spool_dir: &str,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcessSpool,
+            r#"INSERT INTO execution_process_spools (execution_process_id, pgid, spool_dir)
+               VALUES ($1, $2, $3)
+               RETURNING execution_process_id as "execution_process_id!: Uuid",
+                         pgid,
+                         spool_dir,
+                         created_at as "created_at!: DateTime<Utc>""#,
+            execution_process_id,
+            pgid,
+            spool_dir
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcessSpool,
+            r#"SELECT execution_process_id as "execution_process_id!: Uuid",
+                      pgid,
+                      spool_dir,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM execution_process_spools
+               WHERE execution_process_id = $1"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, execution_process_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM execution_process_spools WHERE execution_process_id = $1",
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
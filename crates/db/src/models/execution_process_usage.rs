@@ -0,0 +1,212 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use utils::log_msg::TokenUsage;
+use uuid::Uuid;
+
+/// Model name stored when the agent did not report one
+pub const UNKNOWN_MODEL: &str = "unknown";
+
+/// Cumulative token usage of one execution process for one model
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ExecutionProcessUsage {
+    pub id: Uuid,
+    pub execution_process_id: Uuid,
+    pub model: String,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_creation_input_tokens: i64,
+    pub cache_read_input_tokens: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Token totals grouped by task, model and day (UTC) of the execution process
+#[derive(Debug, Clone, FromRow)]
+pub struct UsageBucket {
+    pub task_id: Uuid,
+    pub model: String,
+    pub day: String,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_creation_input_tokens: i64,
+    pub cache_read_input_tokens: i64,
+}
+
+impl ExecutionProcessUsage {
+    /// Record the latest cumulative usage for a process and model, replacing the previous totals
+    pub async fn upsert(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        usage: &TokenUsage,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let model = usage.model.as_deref().unwrap_or(UNKNOWN_MODEL);
+        let input_tokens = usage.input_tokens as i64;
+        let output_tokens = usage.output_tokens as i64;
+        let cache_creation_input_tokens = usage.cache_creation_input_tokens as i64;
+        let cache_read_input_tokens = usage.cache_read_input_tokens as i64;
+        sqlx::query_as!(
+            ExecutionProcessUsage,
+            r#"INSERT INTO execution_process_usage (
+                id, execution_process_id, model, input_tokens, output_tokens,
+                cache_creation_input_tokens, cache_read_input_tokens
+               )
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               ON CONFLICT(execution_process_id, model) DO UPDATE SET
+                input_tokens = excluded.input_tokens,
+                output_tokens = excluded.output_tokens,
+                cache_creation_input_tokens = excluded.cache_creation_input_tokens,
+                cache_read_input_tokens = excluded.cache_read_input_tokens,
+                updated_at = datetime('now', 'subsec')
+               RETURNING
+                id as "id!: Uuid",
+                execution_process_id as "execution_process_id!: Uuid",
+                model,
+                input_tokens as "input_tokens!: i64",
+                output_tokens as "output_tokens!: i64",
+                cache_creation_input_tokens as "cache_creation_input_tokens!: i64",
+                cache_read_input_tokens as "cache_read_input_tokens!: i64",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            execution_process_id,
+            model,
+            input_tokens,
+            output_tokens,
+            cache_creation_input_tokens,
+            cache_read_input_tokens
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcessUsage,
+            r#"SELECT
+                id as "id!: Uuid",
+                execution_process_id as "execution_process_id!: Uuid",
+                model,
+                input_tokens as "input_tokens!: i64",
+                output_tokens as "output_tokens!: i64",
+                cache_creation_input_tokens as "cache_creation_input_tokens!: i64",
+                cache_read_input_tokens as "cache_read_input_tokens!: i64",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM execution_process_usage
+               WHERE execution_process_id = $1
+               ORDER BY model ASC"#,
+            execution_process_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Input and output tokens, not counting cache reads, used across the task's workspaces
+    pub async fn total_tokens_for_task(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT COALESCE(SUM(u.input_tokens + u.output_tokens + u.cache_creation_input_tokens), 0) as "total!: i64"
+               FROM execution_process_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               WHERE w.task_id = $1"#,
+            task_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Usage of every execution process in the project's tasks
+    pub async fn find_buckets_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<UsageBucket>, sqlx::Error> {
+        sqlx::query_as!(
+            UsageBucket,
+            r#"SELECT
+                t.id as "task_id!: Uuid",
+                u.model as "model!: String",
+                date(ep.created_at) as "day!: String",
+                SUM(u.input_tokens) as "input_tokens!: i64",
+                SUM(u.output_tokens) as "output_tokens!: i64",
+                SUM(u.cache_creation_input_tokens) as "cache_creation_input_tokens!: i64",
+                SUM(u.cache_read_input_tokens) as "cache_read_input_tokens!: i64"
+               FROM execution_process_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE t.project_id = $1
+               GROUP BY t.id, u.model, date(ep.created_at)
+               ORDER BY date(ep.created_at) ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Usage of every execution process across the task's workspaces
+    pub async fn find_buckets_for_task(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<UsageBucket>, sqlx::Error> {
+        sqlx::query_as!(
+            UsageBucket,
+            r#"SELECT
+                w.task_id as "task_id!: Uuid",
+                u.model as "model!: String",
+                date(ep.created_at) as "day!: String",
+                SUM(u.input_tokens) as "input_tokens!: i64",
+                SUM(u.output_tokens) as "output_tokens!: i64",
+                SUM(u.cache_creation_input_tokens) as "cache_creation_input_tokens!: i64",
+                SUM(u.cache_read_input_tokens) as "cache_read_input_tokens!: i64"
+               FROM execution_process_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               WHERE w.task_id = $1
+               GROUP BY w.task_id, u.model, date(ep.created_at)
+               ORDER BY date(ep.created_at) ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Usage of every execution process in one workspace
+    pub async fn find_buckets_for_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<UsageBucket>, sqlx::Error> {
+        sqlx::query_as!(
+            UsageBucket,
+            r#"SELECT
+                w.task_id as "task_id!: Uuid",
+                u.model as "model!: String",
+                date(ep.created_at) as "day!: String",
+                SUM(u.input_tokens) as "input_tokens!: i64",
+                SUM(u.output_tokens) as "output_tokens!: i64",
+                SUM(u.cache_creation_input_tokens) as "cache_creation_input_tokens!: i64",
+                SUM(u.cache_read_input_tokens) as "cache_read_input_tokens!: i64"
+               FROM execution_process_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               WHERE w.id = $1
+               GROUP BY w.task_id, u.model, date(ep.created_at)
+               ORDER BY date(ep.created_at) ASC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
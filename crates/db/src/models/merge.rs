@@ -14,6 +14,18 @@ pub enum MergeStatus {
     Unknown,
 }
 
+/// Combined result of the CI checks of a commit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, Type)]
+#[sqlx(type_name = "ci_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum CiStatus {
+    /// No check failed and some are still running
+    Pending,
+    Success,
+    /// At least one check failed
+    Failure,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum Merge {
@@ -40,6 +52,22 @@ pub struct PrMerge {
     pub created_at: DateTime<Utc>,
     pub target_branch_name: String,
     pub pr_info: PullRequestInfo,
+    /// CI state of the PR head, unknown until its checks report
+    pub ci: Option<PrCiState>,
+}
+
+/// CI state of the head commit of a PR, as last seen by the PR monitor
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct PrCiState {
+    pub status: CiStatus,
+    pub head_sha: String,
+    /// Names of the failed checks
+    pub failed_checks: Vec<String>,
+    pub updated_at: DateTime<Utc>,
+    /// Coding agent runs dispatched so far to fix failing checks
+    pub fix_attempts: i64,
+    /// Head commit the last of those runs was dispatched for
+    pub fix_head_sha: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -71,10 +99,30 @@ struct MergeRow {
     pr_status: Option<MergeStatus>,
     pr_merged_at: Option<DateTime<Utc>>,
     pr_merge_commit_sha: Option<String>,
+    pr_ci_status: Option<CiStatus>,
+    pr_ci_head_sha: Option<String>,
+    pr_ci_failed_checks: Option<sqlx::types::Json<Vec<String>>>,
+    pr_ci_updated_at: Option<DateTime<Utc>>,
+    pr_ci_fix_attempts: i64,
+    pr_ci_fix_head_sha: Option<String>,
     created_at: DateTime<Utc>,
 }
 
 impl Merge {
+    pub fn id(&self) -> Uuid {
+        match self {
+            Merge::Direct(direct) => direct.id,
+            Merge::Pr(pr) => pr.id,
+        }
+    }
+
+    pub fn workspace_id(&self) -> Uuid {
+        match self {
+            Merge::Direct(direct) => direct.workspace_id,
+            Merge::Pr(pr) => pr.workspace_id,
+        }
+    }
+
     pub fn merge_commit(&self) -> Option<String> {
         match self {
             Merge::Direct(direct) => Some(direct.merge_commit.clone()),
@@ -109,6 +157,12 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                pr_ci_status as "pr_ci_status?: CiStatus",
+                pr_ci_head_sha,
+                pr_ci_failed_checks as "pr_ci_failed_checks?: sqlx::types::Json<Vec<String>>",
+                pr_ci_updated_at as "pr_ci_updated_at?: DateTime<Utc>",
+                pr_ci_fix_attempts,
+                pr_ci_fix_head_sha,
                 created_at as "created_at!: DateTime<Utc>",
                 target_branch_name as "target_branch_name!: String"
             "#,
@@ -123,6 +177,23 @@ impl Merge {
         .await
         .map(Into::into)
     }
+
+    /// Point a direct merge at its commit after the commit was reworded
+    pub async fn update_merge_commit(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+        merge_commit: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE merges SET merge_commit = $1 WHERE id = $2 AND merge_type = 'direct'"#,
+            merge_commit,
+            merge_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     /// Create a new PR record (when PR is opened)
     pub async fn create_pr(
         pool: &SqlitePool,
@@ -151,6 +222,12 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                pr_ci_status as "pr_ci_status?: CiStatus",
+                pr_ci_head_sha,
+                pr_ci_failed_checks as "pr_ci_failed_checks?: sqlx::types::Json<Vec<String>>",
+                pr_ci_updated_at as "pr_ci_updated_at?: DateTime<Utc>",
+                pr_ci_fix_attempts,
+                pr_ci_fix_head_sha,
                 created_at as "created_at!: DateTime<Utc>",
                 target_branch_name as "target_branch_name!: String"
             "#,
@@ -182,6 +259,12 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                pr_ci_status as "pr_ci_status?: CiStatus",
+                pr_ci_head_sha,
+                pr_ci_failed_checks as "pr_ci_failed_checks?: sqlx::types::Json<Vec<String>>",
+                pr_ci_updated_at as "pr_ci_updated_at?: DateTime<Utc>",
+                pr_ci_fix_attempts,
+                pr_ci_fix_head_sha,
                 created_at as "created_at!: DateTime<Utc>",
                 target_branch_name as "target_branch_name!: String"
                FROM merges
@@ -223,6 +306,88 @@ impl Merge {
 
         Ok(())
     }
+
+    /// Record the CI state of the head commit of a PR
+    pub async fn update_ci_state(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+        status: CiStatus,
+        head_sha: &str,
+        failed_checks: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let failed_checks = sqlx::types::Json(failed_checks);
+        let now = Utc::now();
+        sqlx::query!(
+            r#"UPDATE merges
+            SET pr_ci_status = $1,
+                pr_ci_head_sha = $2,
+                pr_ci_failed_checks = $3,
+                pr_ci_updated_at = $4
+            WHERE id = $5"#,
+            status,
+            head_sha,
+            failed_checks,
+            now,
+            merge_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Count a coding agent run dispatched to fix the failing checks of `head_sha`
+    pub async fn record_ci_fix(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+        head_sha: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE merges
+            SET pr_ci_fix_attempts = pr_ci_fix_attempts + 1,
+                pr_ci_fix_head_sha = $1
+            WHERE id = $2"#,
+            head_sha,
+            merge_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn find_by_rowid(pool: &SqlitePool, rowid: i64) -> Result<Option<Self>, sqlx::Error> {
+        let row = sqlx::query_as!(
+            MergeRow,
+            r#"SELECT
+                id as "id!: Uuid",
+                workspace_id as "workspace_id!: Uuid",
+                repo_id as "repo_id!: Uuid",
+                merge_type as "merge_type!: MergeType",
+                merge_commit,
+                pr_number,
+                pr_url,
+                pr_status as "pr_status?: MergeStatus",
+                pr_merged_at as "pr_merged_at?: DateTime<Utc>",
+                pr_merge_commit_sha,
+                pr_ci_status as "pr_ci_status?: CiStatus",
+                pr_ci_head_sha,
+                pr_ci_failed_checks as "pr_ci_failed_checks?: sqlx::types::Json<Vec<String>>",
+                pr_ci_updated_at as "pr_ci_updated_at?: DateTime<Utc>",
+                pr_ci_fix_attempts,
+                pr_ci_fix_head_sha,
+                target_branch_name as "target_branch_name!: String",
+                created_at as "created_at!: DateTime<Utc>"
+            FROM merges
+            WHERE rowid = $1"#,
+            rowid
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(row.map(Into::into))
+    }
+
     /// Find all merges for a workspace (returns both direct and PR merges)
     pub async fn find_by_workspace_id(
         pool: &SqlitePool,
@@ -242,6 +407,12 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                pr_ci_status as "pr_ci_status?: CiStatus",
+                pr_ci_head_sha,
+                pr_ci_failed_checks as "pr_ci_failed_checks?: sqlx::types::Json<Vec<String>>",
+                pr_ci_updated_at as "pr_ci_updated_at?: DateTime<Utc>",
+                pr_ci_fix_attempts,
+                pr_ci_fix_head_sha,
                 target_branch_name as "target_branch_name!: String",
                 created_at as "created_at!: DateTime<Utc>"
             FROM merges
@@ -275,6 +446,12 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                pr_ci_status as "pr_ci_status?: CiStatus",
+                pr_ci_head_sha,
+                pr_ci_failed_checks as "pr_ci_failed_checks?: sqlx::types::Json<Vec<String>>",
+                pr_ci_updated_at as "pr_ci_updated_at?: DateTime<Utc>",
+                pr_ci_fix_attempts,
+                pr_ci_fix_head_sha,
                 target_branch_name as "target_branch_name!: String",
                 created_at as "created_at!: DateTime<Utc>"
             FROM merges
@@ -308,6 +485,20 @@ impl From<MergeRow> for DirectMerge {
 
 impl From<MergeRow> for PrMerge {
     fn from(row: MergeRow) -> Self {
+        let ci = match (row.pr_ci_status, row.pr_ci_head_sha) {
+            (Some(status), Some(head_sha)) => Some(PrCiState {
+                status,
+                head_sha,
+                failed_checks: row
+                    .pr_ci_failed_checks
+                    .map(|checks| checks.0)
+                    .unwrap_or_default(),
+                updated_at: row.pr_ci_updated_at.unwrap_or(row.created_at),
+                fix_attempts: row.pr_ci_fix_attempts,
+                fix_head_sha: row.pr_ci_fix_head_sha,
+            }),
+            _ => None,
+        };
         PrMerge {
             id: row.id,
             workspace_id: row.workspace_id,
@@ -320,6 +511,7 @@ impl From<MergeRow> for PrMerge {
                 merged_at: row.pr_merged_at,
                 merge_commit_sha: row.pr_merge_commit_sha,
             },
+            ci,
             created_at: row.created_at,
         }
     }
@@ -1,15 +1,32 @@
+pub mod approval_rule;
+pub mod attempt_group;
 pub mod coding_agent_turn;
+pub mod conflict_resolution;
+pub mod execution_budget;
 pub mod execution_process;
 pub mod execution_process_logs;
 pub mod execution_process_repo_state;
+pub mod execution_process_spool;
+pub mod execution_process_usage;
 pub mod image;
 pub mod merge;
+pub mod pipeline_template;
+pub mod pr_review_follow_up;
 pub mod project;
 pub mod project_repo;
+pub mod queued_message;
 pub mod repo;
 pub mod scratch;
+pub mod search;
 pub mod session;
 pub mod tag;
 pub mod task;
+pub mod task_archive;
+pub mod task_dependency;
+pub mod task_schedule;
+pub mod warm_workspace;
+pub mod webhook;
+pub mod webhook_delivery;
 pub mod workspace;
+pub mod workspace_checkpoint;
 pub mod workspace_repo;
@@ -0,0 +1,118 @@
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Placeholder replaced with the task's title and description
+pub const TASK_PLACEHOLDER: &str = "{task}";
+/// Placeholder replaced with the summary of the previous step's coding agent turn
+pub const PREVIOUS_SUMMARY_PLACEHOLDER: &str = "{previous_summary}";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct PipelineStep {
+    pub name: String,
+    pub executor_profile_id: ExecutorProfileId,
+    /// Prompt template; may reference `{task}` and `{previous_summary}`
+    pub prompt: String,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct PipelineTemplate {
+    pub id: Uuid,
+    pub name: String,
+    #[ts(type = "Array<PipelineStep>")]
+    pub steps: sqlx::types::Json<Vec<PipelineStep>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreatePipelineTemplate {
+    pub name: String,
+    pub steps: Vec<PipelineStep>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdatePipelineTemplate {
+    pub name: Option<String>,
+    pub steps: Option<Vec<PipelineStep>>,
+}
+
+impl PipelineTemplate {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PipelineTemplate,
+            r#"SELECT id as "id!: Uuid", name, steps as "steps!: sqlx::types::Json<Vec<PipelineStep>>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM pipeline_templates
+               ORDER BY name ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PipelineTemplate,
+            r#"SELECT id as "id!: Uuid", name, steps as "steps!: sqlx::types::Json<Vec<PipelineStep>>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM pipeline_templates
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreatePipelineTemplate,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let steps = sqlx::types::Json(&data.steps);
+        sqlx::query_as!(
+            PipelineTemplate,
+            r#"INSERT INTO pipeline_templates (id, name, steps)
+               VALUES ($1, $2, $3)
+               RETURNING id as "id!: Uuid", name, steps as "steps!: sqlx::types::Json<Vec<PipelineStep>>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.name,
+            steps
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdatePipelineTemplate,
+    ) -> Result<Self, sqlx::Error> {
+        let existing = Self::find_by_id(pool, id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+
+        let name = data.name.as_ref().unwrap_or(&existing.name);
+        let steps = sqlx::types::Json(data.steps.as_ref().unwrap_or(&existing.steps.0));
+
+        sqlx::query_as!(
+            PipelineTemplate,
+            r#"UPDATE pipeline_templates
+               SET name = $2, steps = $3, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", name, steps as "steps!: sqlx::types::Json<Vec<PipelineStep>>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            name,
+            steps
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM pipeline_templates WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,185 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq)]
+#[sqlx(type_name = "pr_review_follow_up_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum PrReviewFollowUpStatus {
+    /// The coding agent is still working on the comments or checks
+    Pending,
+    /// The agent committed changes and the branch was pushed
+    Pushed,
+    /// The agent failed, changed nothing, or the push failed
+    Failed,
+}
+
+/// A coding agent run dispatched to address review comments on a workspace repo's pull request,
+/// or to fix its failing checks, in which case it has no comment keys
+#[derive(Debug, Clone, FromRow)]
+pub struct PrReviewFollowUp {
+    pub id: Uuid,
+    pub execution_process_id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub pr_number: i64,
+    pub pr_url: String,
+    /// Keys of the addressed comments, see `UnifiedPrComment::key`
+    pub comment_keys: sqlx::types::Json<Vec<String>>,
+    pub reply_to_comments: bool,
+    pub status: PrReviewFollowUpStatus,
+    pub commit_sha: Option<String>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PrReviewFollowUp {
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        pr_number: i64,
+        pr_url: &str,
+        comment_keys: &[String],
+        reply_to_comments: bool,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let comment_keys = sqlx::types::Json(comment_keys);
+        sqlx::query_as!(
+            PrReviewFollowUp,
+            r#"INSERT INTO pr_review_follow_ups
+                   (id, execution_process_id, workspace_id, repo_id, pr_number, pr_url, comment_keys, reply_to_comments)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+               RETURNING id as "id!: Uuid",
+                         execution_process_id as "execution_process_id!: Uuid",
+                         workspace_id as "workspace_id!: Uuid",
+                         repo_id as "repo_id!: Uuid",
+                         pr_number,
+                         pr_url,
+                         comment_keys as "comment_keys!: sqlx::types::Json<Vec<String>>",
+                         reply_to_comments as "reply_to_comments!: bool",
+                         status as "status!: PrReviewFollowUpStatus",
+                         commit_sha,
+                         error,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            execution_process_id,
+            workspace_id,
+            repo_id,
+            pr_number,
+            pr_url,
+            comment_keys,
+            reply_to_comments
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// The still pending follow-up handled by an execution process, if any
+    pub async fn find_pending_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PrReviewFollowUp,
+            r#"SELECT id as "id!: Uuid",
+                      execution_process_id as "execution_process_id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      pr_number,
+                      pr_url,
+                      comment_keys as "comment_keys!: sqlx::types::Json<Vec<String>>",
+                      reply_to_comments as "reply_to_comments!: bool",
+                      status as "status!: PrReviewFollowUpStatus",
+                      commit_sha,
+                      error,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM pr_review_follow_ups
+               WHERE execution_process_id = $1 AND status = 'pending'"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Whether a follow-up is still running for the workspace
+    pub async fn has_pending_for_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let count = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64"
+               FROM pr_review_follow_ups
+               WHERE workspace_id = $1 AND status = 'pending'"#,
+            workspace_id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(count > 0)
+    }
+
+    /// Keys of the comments of a workspace repo that are addressed, or being addressed, by a
+    /// follow-up that has not failed
+    pub async fn addressed_comment_keys(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        let rows = sqlx::query_scalar!(
+            r#"SELECT comment_keys as "comment_keys!: sqlx::types::Json<Vec<String>>"
+               FROM pr_review_follow_ups
+               WHERE workspace_id = $1 AND repo_id = $2 AND status != 'failed'"#,
+            workspace_id,
+            repo_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().flat_map(|keys| keys.0).collect())
+    }
+
+    /// Keys of the comments of a workspace repo that any follow-up, failed or not, was
+    /// dispatched for
+    pub async fn attempted_comment_keys(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        let rows = sqlx::query_scalar!(
+            r#"SELECT comment_keys as "comment_keys!: sqlx::types::Json<Vec<String>>"
+               FROM pr_review_follow_ups
+               WHERE workspace_id = $1 AND repo_id = $2"#,
+            workspace_id,
+            repo_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().flat_map(|keys| keys.0).collect())
+    }
+
+    pub async fn update_status(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: PrReviewFollowUpStatus,
+        commit_sha: Option<&str>,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE pr_review_follow_ups
+               SET status = $2, commit_sha = $3, error = $4, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            status,
+            commit_sha,
+            error
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
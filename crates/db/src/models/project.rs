@@ -1,6 +1,7 @@
 use chrono::{DateTime, Utc};
+use executors::{profile::ExecutorProfileId, sandbox::SandboxConfig};
 use serde::{Deserialize, Serialize};
-use sqlx::{Executor, FromRow, Sqlite, SqlitePool};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool, Type};
 use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
@@ -17,6 +18,21 @@ pub enum ProjectError {
     CreateFailed(String),
 }
 
+/// How `merge_task_attempt` lands a task branch on its target branch
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS, Default)]
+#[sqlx(type_name = "merge_mode", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum MergeMode {
+    /// One commit on the target branch with the task's changes
+    #[default]
+    Squash,
+    /// A merge commit joining the task branch's commits into the target branch
+    MergeCommit,
+    /// Rebase the task branch onto the target branch and fast-forward the target to it,
+    /// keeping the task's commits and a linear history
+    Rebase,
+}
+
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct Project {
     pub id: Uuid,
@@ -25,6 +41,17 @@ pub struct Project {
     pub dev_script_working_dir: Option<String>,
     pub default_agent_working_dir: Option<String>,
     pub remote_project_id: Option<Uuid>,
+    #[ts(type = "SandboxConfig | null")]
+    pub sandbox_config: Option<sqlx::types::Json<SandboxConfig>>,
+    pub merge_mode: MergeMode,
+    /// Agent and merge commits get Conventional Commit messages written by an executor
+    pub conventional_commits: bool,
+    /// Worktrees kept ready for new attempts with the setup scripts already run; 0 disables
+    /// the pool
+    pub warm_workspace_count: i32,
+    /// Executor that writes commit messages; `None` uses the session's executor
+    #[ts(type = "ExecutorProfileId | null")]
+    pub commit_message_executor_profile_id: Option<sqlx::types::Json<ExecutorProfileId>>,
     #[ts(type = "Date")]
     pub created_at: DateTime<Utc>,
     #[ts(type = "Date")]
@@ -43,6 +70,19 @@ pub struct UpdateProject {
     pub dev_script: Option<String>,
     pub dev_script_working_dir: Option<String>,
     pub default_agent_working_dir: Option<String>,
+    /// `None` keeps the current sandbox settings
+    pub sandbox_config: Option<SandboxConfig>,
+    /// `None` keeps the current merge mode
+    pub merge_mode: Option<MergeMode>,
+    /// `None` keeps the current setting
+    pub conventional_commits: Option<bool>,
+    /// `None` keeps the current pool size
+    pub warm_workspace_count: Option<i32>,
+    /// `None` keeps the current profile
+    pub commit_message_executor_profile_id: Option<ExecutorProfileId>,
+    /// Go back to writing commit messages with the session's executor
+    #[serde(default)]
+    pub clear_commit_message_executor_profile: bool,
 }
 
 #[derive(Debug, Serialize, TS)]
@@ -75,6 +115,11 @@ impl Project {
                       dev_script_working_dir,
                       default_agent_working_dir,
                       remote_project_id as "remote_project_id: Uuid",
+                      sandbox_config as "sandbox_config: sqlx::types::Json<SandboxConfig>",
+                      merge_mode as "merge_mode!: MergeMode",
+                      conventional_commits as "conventional_commits!: bool",
+                      warm_workspace_count as "warm_workspace_count!: i32",
+                      commit_message_executor_profile_id as "commit_message_executor_profile_id: sqlx::types::Json<ExecutorProfileId>",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM projects
@@ -92,6 +137,11 @@ impl Project {
             SELECT p.id as "id!: Uuid", p.name, p.dev_script, p.dev_script_working_dir,
                    p.default_agent_working_dir,
                    p.remote_project_id as "remote_project_id: Uuid",
+                   p.sandbox_config as "sandbox_config: sqlx::types::Json<SandboxConfig>",
+                   p.merge_mode as "merge_mode!: MergeMode",
+                   p.conventional_commits as "conventional_commits!: bool",
+                   p.warm_workspace_count as "warm_workspace_count!: i32",
+                   p.commit_message_executor_profile_id as "commit_message_executor_profile_id: sqlx::types::Json<ExecutorProfileId>",
                    p.created_at as "created_at!: DateTime<Utc>", p.updated_at as "updated_at!: DateTime<Utc>"
             FROM projects p
             WHERE p.id IN (
@@ -117,6 +167,11 @@ impl Project {
                       dev_script_working_dir,
                       default_agent_working_dir,
                       remote_project_id as "remote_project_id: Uuid",
+                      sandbox_config as "sandbox_config: sqlx::types::Json<SandboxConfig>",
+                      merge_mode as "merge_mode!: MergeMode",
+                      conventional_commits as "conventional_commits!: bool",
+                      warm_workspace_count as "warm_workspace_count!: i32",
+                      commit_message_executor_profile_id as "commit_message_executor_profile_id: sqlx::types::Json<ExecutorProfileId>",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM projects
@@ -136,6 +191,11 @@ impl Project {
                       dev_script_working_dir,
                       default_agent_working_dir,
                       remote_project_id as "remote_project_id: Uuid",
+                      sandbox_config as "sandbox_config: sqlx::types::Json<SandboxConfig>",
+                      merge_mode as "merge_mode!: MergeMode",
+                      conventional_commits as "conventional_commits!: bool",
+                      warm_workspace_count as "warm_workspace_count!: i32",
+                      commit_message_executor_profile_id as "commit_message_executor_profile_id: sqlx::types::Json<ExecutorProfileId>",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM projects
@@ -158,6 +218,11 @@ impl Project {
                       dev_script_working_dir,
                       default_agent_working_dir,
                       remote_project_id as "remote_project_id: Uuid",
+                      sandbox_config as "sandbox_config: sqlx::types::Json<SandboxConfig>",
+                      merge_mode as "merge_mode!: MergeMode",
+                      conventional_commits as "conventional_commits!: bool",
+                      warm_workspace_count as "warm_workspace_count!: i32",
+                      commit_message_executor_profile_id as "commit_message_executor_profile_id: sqlx::types::Json<ExecutorProfileId>",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM projects
@@ -188,6 +253,11 @@ impl Project {
                           dev_script_working_dir,
                           default_agent_working_dir,
                           remote_project_id as "remote_project_id: Uuid",
+                          sandbox_config as "sandbox_config: sqlx::types::Json<SandboxConfig>",
+                          merge_mode as "merge_mode!: MergeMode",
+                          conventional_commits as "conventional_commits!: bool",
+                          warm_workspace_count as "warm_workspace_count!: i32",
+                          commit_message_executor_profile_id as "commit_message_executor_profile_id: sqlx::types::Json<ExecutorProfileId>",
                           created_at as "created_at!: DateTime<Utc>",
                           updated_at as "updated_at!: DateTime<Utc>""#,
             project_id,
@@ -210,11 +280,35 @@ impl Project {
         let dev_script = payload.dev_script.clone();
         let dev_script_working_dir = payload.dev_script_working_dir.clone();
         let default_agent_working_dir = payload.default_agent_working_dir.clone();
+        let sandbox_config = payload
+            .sandbox_config
+            .clone()
+            .map(sqlx::types::Json)
+            .or(existing.sandbox_config);
+        let merge_mode = payload.merge_mode.unwrap_or(existing.merge_mode);
+        let conventional_commits = payload
+            .conventional_commits
+            .unwrap_or(existing.conventional_commits);
+        let warm_workspace_count = payload
+            .warm_workspace_count
+            .unwrap_or(existing.warm_workspace_count)
+            .max(0);
+        let commit_message_executor_profile_id = if payload.clear_commit_message_executor_profile {
+            None
+        } else {
+            payload
+                .commit_message_executor_profile_id
+                .clone()
+                .map(sqlx::types::Json)
+                .or(existing.commit_message_executor_profile_id)
+        };
 
         sqlx::query_as!(
             Project,
             r#"UPDATE projects
-               SET name = $2, dev_script = $3, dev_script_working_dir = $4, default_agent_working_dir = $5
+               SET name = $2, dev_script = $3, dev_script_working_dir = $4, default_agent_working_dir = $5,
+                   sandbox_config = $6, merge_mode = $7, conventional_commits = $8,
+                   commit_message_executor_profile_id = $9, warm_workspace_count = $10
                WHERE id = $1
                RETURNING id as "id!: Uuid",
                          name,
@@ -222,6 +316,11 @@ impl Project {
                          dev_script_working_dir,
                          default_agent_working_dir,
                          remote_project_id as "remote_project_id: Uuid",
+                         sandbox_config as "sandbox_config: sqlx::types::Json<SandboxConfig>",
+                         merge_mode as "merge_mode!: MergeMode",
+                         conventional_commits as "conventional_commits!: bool",
+                         warm_workspace_count as "warm_workspace_count!: i32",
+                         commit_message_executor_profile_id as "commit_message_executor_profile_id: sqlx::types::Json<ExecutorProfileId>",
                          created_at as "created_at!: DateTime<Utc>",
                          updated_at as "updated_at!: DateTime<Utc>""#,
             id,
@@ -229,6 +328,11 @@ impl Project {
             dev_script,
             dev_script_working_dir,
             default_agent_working_dir,
+            sandbox_config,
+            merge_mode,
+            conventional_commits,
+            commit_message_executor_profile_id,
+            warm_workspace_count,
         )
         .fetch_one(pool)
         .await
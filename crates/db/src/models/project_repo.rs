@@ -28,6 +28,10 @@ pub struct ProjectRepo {
     pub cleanup_script: Option<String>,
     pub copy_files: Option<String>,
     pub parallel_setup_script: bool,
+    /// Run after each coding agent turn; a non-zero exit fails the turn
+    pub verify_script: Option<String>,
+    /// Follow-ups sent back to the agent with the failing output before giving up
+    pub verify_max_retries: i32,
 }
 
 /// ProjectRepo with the associated repo name (for script execution in worktrees)
@@ -41,6 +45,8 @@ pub struct ProjectRepoWithName {
     pub cleanup_script: Option<String>,
     pub copy_files: Option<String>,
     pub parallel_setup_script: bool,
+    pub verify_script: Option<String>,
+    pub verify_max_retries: i32,
 }
 
 #[derive(Debug, Clone, Deserialize, TS)]
@@ -56,6 +62,8 @@ pub struct UpdateProjectRepo {
     pub cleanup_script: Option<String>,
     pub copy_files: Option<String>,
     pub parallel_setup_script: Option<bool>,
+    pub verify_script: Option<String>,
+    pub verify_max_retries: Option<i32>,
 }
 
 impl ProjectRepo {
@@ -71,7 +79,9 @@ impl ProjectRepo {
                       setup_script,
                       cleanup_script,
                       copy_files,
-                      parallel_setup_script as "parallel_setup_script!: bool"
+                      parallel_setup_script as "parallel_setup_script!: bool",
+                      verify_script,
+                      verify_max_retries as "verify_max_retries!: i32"
                FROM project_repos
                WHERE project_id = $1"#,
             project_id
@@ -92,7 +102,9 @@ impl ProjectRepo {
                       setup_script,
                       cleanup_script,
                       copy_files,
-                      parallel_setup_script as "parallel_setup_script!: bool"
+                      parallel_setup_script as "parallel_setup_script!: bool",
+                      verify_script,
+                      verify_max_retries as "verify_max_retries!: i32"
                FROM project_repos
                WHERE repo_id = $1"#,
             repo_id
@@ -114,7 +126,9 @@ impl ProjectRepo {
                       pr.setup_script,
                       pr.cleanup_script,
                       pr.copy_files,
-                      pr.parallel_setup_script as "parallel_setup_script!: bool"
+                      pr.parallel_setup_script as "parallel_setup_script!: bool",
+                      pr.verify_script,
+                      pr.verify_max_retries as "verify_max_retries!: i32"
                FROM project_repos pr
                JOIN repos r ON r.id = pr.repo_id
                WHERE pr.project_id = $1
@@ -160,7 +174,9 @@ impl ProjectRepo {
                       setup_script,
                       cleanup_script,
                       copy_files,
-                      parallel_setup_script as "parallel_setup_script!: bool"
+                      parallel_setup_script as "parallel_setup_script!: bool",
+                      verify_script,
+                      verify_max_retries as "verify_max_retries!: i32"
                FROM project_repos
                WHERE project_id = $1 AND repo_id = $2"#,
             project_id,
@@ -235,7 +251,9 @@ impl ProjectRepo {
                          setup_script,
                          cleanup_script,
                          copy_files,
-                         parallel_setup_script as "parallel_setup_script!: bool""#,
+                         parallel_setup_script as "parallel_setup_script!: bool",
+                         verify_script,
+                         verify_max_retries as "verify_max_retries!: i32""#,
             id,
             project_id,
             repo_id
@@ -259,6 +277,11 @@ impl ProjectRepo {
         let parallel_setup_script = payload
             .parallel_setup_script
             .unwrap_or(existing.parallel_setup_script);
+        let verify_script = payload.verify_script.clone();
+        let verify_max_retries = payload
+            .verify_max_retries
+            .unwrap_or(existing.verify_max_retries)
+            .max(0);
 
         sqlx::query_as!(
             ProjectRepo,
@@ -266,19 +289,25 @@ impl ProjectRepo {
                SET setup_script = $1,
                    cleanup_script = $2,
                    copy_files = $3,
-                   parallel_setup_script = $4
-               WHERE project_id = $5 AND repo_id = $6
+                   parallel_setup_script = $4,
+                   verify_script = $5,
+                   verify_max_retries = $6
+               WHERE project_id = $7 AND repo_id = $8
                RETURNING id as "id!: Uuid",
                          project_id as "project_id!: Uuid",
                          repo_id as "repo_id!: Uuid",
                          setup_script,
                          cleanup_script,
                          copy_files,
-                         parallel_setup_script as "parallel_setup_script!: bool""#,
+                         parallel_setup_script as "parallel_setup_script!: bool",
+                         verify_script,
+                         verify_max_retries as "verify_max_retries!: i32""#,
             setup_script,
             cleanup_script,
             copy_files,
             parallel_setup_script,
+            verify_script,
+            verify_max_retries,
             project_id,
             repo_id
         )
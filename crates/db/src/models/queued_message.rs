@@ -0,0 +1,178 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::scratch::DraftFollowUpData;
+
+/// A follow-up message waiting for the agent of its session to finish
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct QueuedMessage {
+    pub id: Uuid,
+    pub session_id: Uuid,
+    /// The follow-up data (message + variant)
+    #[ts(type = "DraftFollowUpData")]
+    pub data: sqlx::types::Json<DraftFollowUpData>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl QueuedMessage {
+    /// Append a message to the end of the session's queue
+    pub async fn create(
+        pool: &SqlitePool,
+        id: Uuid,
+        session_id: Uuid,
+        data: &DraftFollowUpData,
+    ) -> Result<Self, sqlx::Error> {
+        let data = sqlx::types::Json(data);
+        sqlx::query_as!(
+            QueuedMessage,
+            r#"INSERT INTO queued_messages (id, session_id, data, position)
+               VALUES ($1, $2, $3,
+                       (SELECT COALESCE(MAX(position) + 1, 0) FROM queued_messages WHERE session_id = $2))
+               RETURNING id as "id!: Uuid",
+                         session_id as "session_id!: Uuid",
+                         data as "data!: sqlx::types::Json<DraftFollowUpData>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            session_id,
+            data
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// The session's queue in the order the messages will be sent
+    pub async fn find_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            QueuedMessage,
+            r#"SELECT id as "id!: Uuid",
+                      session_id as "session_id!: Uuid",
+                      data as "data!: sqlx::types::Json<DraftFollowUpData>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM queued_messages
+               WHERE session_id = $1
+               ORDER BY position ASC, created_at ASC"#,
+            session_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn exists_for_session(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM queued_messages WHERE session_id = $1) as "exists!: bool""#,
+            session_id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(result)
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        id: Uuid,
+        data: &DraftFollowUpData,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let data = sqlx::types::Json(data);
+        sqlx::query_as!(
+            QueuedMessage,
+            r#"UPDATE queued_messages
+               SET data = $3, updated_at = datetime('now', 'subsec')
+               WHERE session_id = $1 AND id = $2
+               RETURNING id as "id!: Uuid",
+                         session_id as "session_id!: Uuid",
+                         data as "data!: sqlx::types::Json<DraftFollowUpData>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            session_id,
+            id,
+            data
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Move the messages into the order of `ids`, which must list the whole queue
+    pub async fn reorder(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        ids: &[Uuid],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        for (position, id) in ids.iter().enumerate() {
+            let position = position as i64;
+            sqlx::query!(
+                r#"UPDATE queued_messages
+                   SET position = $3, updated_at = datetime('now', 'subsec')
+                   WHERE session_id = $1 AND id = $2"#,
+                session_id,
+                id,
+                position
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    pub async fn delete(pool: &SqlitePool, session_id: Uuid, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM queued_messages WHERE session_id = $1 AND id = $2",
+            session_id,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM queued_messages WHERE session_id = $1",
+            session_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Remove and return the message at the front of the session's queue
+    pub async fn take_next(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            QueuedMessage,
+            r#"DELETE FROM queued_messages
+               WHERE id = (
+                   SELECT id FROM queued_messages
+                   WHERE session_id = $1
+                   ORDER BY position ASC, created_at ASC
+                   LIMIT 1
+               )
+               RETURNING id as "id!: Uuid",
+                         session_id as "session_id!: Uuid",
+                         data as "data!: sqlx::types::Json<DraftFollowUpData>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            session_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+}
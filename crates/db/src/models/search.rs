@@ -0,0 +1,147 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use strum_macros::Display;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// What an indexed document was taken from: a task's title and description, a coding
+/// agent turn's prompt and summary, or an assistant message of an execution process
+#[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS, Display)]
+#[sqlx(type_name = "search_source", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum SearchSource {
+    Task,
+    Turn,
+    Message,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct SearchHit {
+    pub source: SearchSource,
+    pub project_id: Uuid,
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub workspace_id: Option<Uuid>,
+    pub execution_process_id: Option<Uuid>,
+    /// Matching text with matched terms wrapped in `**`
+    pub snippet: String,
+    /// bm25 score, lower is more relevant
+    pub rank: f64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Turn free text into an FTS5 query: every term must match, the last one as a prefix.
+/// Terms are quoted so that FTS5 operators and punctuation in the input are matched literally.
+pub fn to_match_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect();
+
+    (!terms.is_empty()).then(|| format!("{}*", terms.join(" ")))
+}
+
+pub struct SearchIndex;
+
+impl SearchIndex {
+    /// Ranked hits for `query` across tasks, agent turns and assistant messages
+    pub async fn search(
+        pool: &SqlitePool,
+        query: &str,
+        project_id: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<SearchHit>, sqlx::Error> {
+        let Some(match_query) = to_match_query(query) else {
+            return Ok(Vec::new());
+        };
+
+        sqlx::query_as!(
+            SearchHit,
+            r#"SELECT
+                d.source as "source!: SearchSource",
+                t.project_id as "project_id!: Uuid",
+                d.task_id as "task_id!: Uuid",
+                t.title as "task_title!: String",
+                d.workspace_id as "workspace_id: Uuid",
+                d.execution_process_id as "execution_process_id: Uuid",
+                snippet(search_index, -1, '**', '**', '…', 24) as "snippet!: String",
+                bm25(search_index) as "rank!: f64",
+                d.created_at as "created_at!: DateTime<Utc>"
+               FROM search_index
+               JOIN search_documents d ON d.id = search_index.rowid
+               JOIN tasks t ON t.id = d.task_id
+               WHERE search_index MATCH $1
+                 AND ($2 IS NULL OR t.project_id = $2)
+               ORDER BY bm25(search_index) ASC
+               LIMIT $3"#,
+            match_query,
+            project_id,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Index (or re-index) the assistant message at `entry_index` of an execution process
+    pub async fn upsert_message(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        entry_index: i64,
+        content: &str,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let document_id = sqlx::query_scalar!(
+            r#"INSERT INTO search_documents (source, source_id, entry_index, task_id, workspace_id, execution_process_id)
+               SELECT 'message', ep.id, $2, w.task_id, w.id, ep.id
+               FROM execution_processes ep
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               WHERE ep.id = $1
+               ON CONFLICT(source, source_id, entry_index) DO UPDATE SET entry_index = excluded.entry_index
+               RETURNING id as "id!: i64""#,
+            execution_process_id,
+            entry_index
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        // The execution process is gone, nothing to index
+        let Some(document_id) = document_id else {
+            return Ok(());
+        };
+
+        sqlx::query!("DELETE FROM search_index WHERE rowid = $1", document_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query!(
+            "INSERT INTO search_index (rowid, title, body) VALUES ($1, '', $2)",
+            document_id,
+            content
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_match_query_quotes_terms_and_prefixes_last() {
+        assert_eq!(
+            to_match_query("auth middleware").as_deref(),
+            Some(r#""auth" "middleware"*"#)
+        );
+        assert_eq!(
+            to_match_query(r#"  say "hi" OR -x "#).as_deref(),
+            Some(r#""say" """hi""" "OR" "-x"*"#)
+        );
+        assert_eq!(to_match_query("   "), None);
+    }
+}
@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use super::{
+    coding_agent_turn::CodingAgentTurn,
+    execution_process::{ExecutionProcess, ExecutionProcessStatus},
+    execution_process_repo_state::ExecutionProcessRepoState,
+    merge::Merge,
+    session::Session,
+    task::Task,
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
+
+/// A repository referenced by the archived records, matched by name on import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedRepo {
+    pub id: Uuid,
+    pub name: String,
+    pub display_name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedExecutionProcess {
+    pub execution_process: ExecutionProcess,
+    pub repo_states: Vec<ExecutionProcessRepoState>,
+    pub coding_agent_turn: Option<CodingAgentTurn>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedSession {
+    pub session: Session,
+    pub execution_processes: Vec<ArchivedExecutionProcess>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedWorkspace {
+    pub workspace: Workspace,
+    pub repos: Vec<WorkspaceRepo>,
+    pub sessions: Vec<ArchivedSession>,
+    pub merges: Vec<Merge>,
+}
+
+/// A task with its complete attempt history, as stored in a task archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskArchiveRecords {
+    pub task: Task,
+    pub repos: Vec<ArchivedRepo>,
+    pub workspaces: Vec<ArchivedWorkspace>,
+}
+
+impl TaskArchiveRecords {
+    /// Recreate the records under `project_id` with fresh ids, in one transaction.
+    /// `repo_ids` maps archived repo ids to local ones; rows of unmapped repos are left out.
+    /// `logs` holds the JSONL logs of each archived execution process.
+    /// Imported workspaces have no worktree, and processes that were still running are
+    /// recorded as killed. Returns the id of the new task.
+    pub async fn import(
+        &self,
+        pool: &SqlitePool,
+        project_id: Uuid,
+        repo_ids: &HashMap<Uuid, Uuid>,
+        logs: &HashMap<Uuid, String>,
+    ) -> Result<Uuid, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let task = &self.task;
+        let task_id = Uuid::new_v4();
+        sqlx::query!(
+            r#"INSERT INTO tasks (id, project_id, title, description, status, created_at, updated_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)"#,
+            task_id,
+            project_id,
+            task.title,
+            task.description,
+            task.status,
+            task.created_at,
+            task.updated_at
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        for archived in &self.workspaces {
+            let workspace = &archived.workspace;
+            let workspace_id = Uuid::new_v4();
+            sqlx::query!(
+                r#"INSERT INTO workspaces (id, task_id, container_ref, branch, agent_working_dir, setup_completed_at, created_at, updated_at)
+                   VALUES ($1, $2, NULL, $3, $4, $5, $6, $7)"#,
+                workspace_id,
+                task_id,
+                workspace.branch,
+                workspace.agent_working_dir,
+                workspace.setup_completed_at,
+                workspace.created_at,
+                workspace.updated_at
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            for workspace_repo in &archived.repos {
+                let Some(repo_id) = repo_ids.get(&workspace_repo.repo_id) else {
+                    continue;
+                };
+                let id = Uuid::new_v4();
+                sqlx::query!(
+                    r#"INSERT INTO workspace_repos (id, workspace_id, repo_id, target_branch, created_at, updated_at)
+                       VALUES ($1, $2, $3, $4, $5, $6)"#,
+                    id,
+                    workspace_id,
+                    repo_id,
+                    workspace_repo.target_branch,
+                    workspace_repo.created_at,
+                    workspace_repo.updated_at
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+
+            for merge in &archived.merges {
+                let id = Uuid::new_v4();
+                match merge {
+                    Merge::Direct(direct) => {
+                        let Some(repo_id) = repo_ids.get(&direct.repo_id) else {
+                            continue;
+                        };
+                        sqlx::query!(
+                            r#"INSERT INTO merges (id, workspace_id, repo_id, merge_type, merge_commit, target_branch_name, created_at)
+                               VALUES ($1, $2, $3, 'direct', $4, $5, $6)"#,
+                            id,
+                            workspace_id,
+                            repo_id,
+                            direct.merge_commit,
+                            direct.target_branch_name,
+                            direct.created_at
+                        )
+                        .execute(&mut *tx)
+                        .await?;
+                    }
+                    Merge::Pr(pr) => {
+                        let Some(repo_id) = repo_ids.get(&pr.repo_id) else {
+                            continue;
+                        };
+                        sqlx::query!(
+                            r#"INSERT INTO merges (id, workspace_id, repo_id, merge_type, pr_number, pr_url, pr_status, pr_merged_at, pr_merge_commit_sha, target_branch_name, created_at)
+                               VALUES ($1, $2, $3, 'pr', $4, $5, $6, $7, $8, $9, $10)"#,
+                            id,
+                            workspace_id,
+                            repo_id,
+                            pr.pr_info.number,
+                            pr.pr_info.url,
+                            pr.pr_info.status,
+                            pr.pr_info.merged_at,
+                            pr.pr_info.merge_commit_sha,
+                            pr.target_branch_name,
+                            pr.created_at
+                        )
+                        .execute(&mut *tx)
+                        .await?;
+                    }
+                }
+            }
+
+            for archived_session in &archived.sessions {
+                let session = &archived_session.session;
+                let session_id = Uuid::new_v4();
+                sqlx::query!(
+                    r#"INSERT INTO sessions (id, workspace_id, executor, created_at, updated_at)
+                       VALUES ($1, $2, $3, $4, $5)"#,
+                    session_id,
+                    workspace_id,
+                    session.executor,
+                    session.created_at,
+                    session.updated_at
+                )
+                .execute(&mut *tx)
+                .await?;
+
+                for archived_process in &archived_session.execution_processes {
+                    let process = &archived_process.execution_process;
+                    let process_id = Uuid::new_v4();
+                    let status = match &process.status {
+                        ExecutionProcessStatus::Running | ExecutionProcessStatus::Queued => {
+                            ExecutionProcessStatus::Killed
+                        }
+                        status => status.clone(),
+                    };
+                    sqlx::query!(
+                        r#"INSERT INTO execution_processes (
+                                id, session_id, run_reason, executor_action, status, exit_code,
+                                dropped, started_at, completed_at, created_at, updated_at
+                           ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)"#,
+                        process_id,
+                        session_id,
+                        process.run_reason,
+                        process.executor_action,
+                        status,
+                        process.exit_code,
+                        process.dropped,
+                        process.started_at,
+                        process.completed_at,
+                        process.created_at,
+                        process.updated_at
+                    )
+                    .execute(&mut *tx)
+                    .await?;
+
+                    for state in &archived_process.repo_states {
+                        let Some(repo_id) = repo_ids.get(&state.repo_id) else {
+                            continue;
+                        };
+                        let id = Uuid::new_v4();
+                        sqlx::query!(
+                            r#"INSERT INTO execution_process_repo_states (
+                                    id, execution_process_id, repo_id, before_head_commit,
+                                    after_head_commit, merge_commit, created_at, updated_at
+                               ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"#,
+                            id,
+                            process_id,
+                            repo_id,
+                            state.before_head_commit,
+                            state.after_head_commit,
+                            state.merge_commit,
+                            state.created_at,
+                            state.updated_at
+                        )
+                        .execute(&mut *tx)
+                        .await?;
+                    }
+
+                    if let Some(turn) = &archived_process.coding_agent_turn {
+                        let id = Uuid::new_v4();
+                        sqlx::query!(
+                            r#"INSERT INTO coding_agent_turns (id, execution_process_id, agent_session_id, prompt, summary, created_at, updated_at)
+                               VALUES ($1, $2, $3, $4, $5, $6, $7)"#,
+                            id,
+                            process_id,
+                            turn.agent_session_id,
+                            turn.prompt,
+                            turn.summary,
+                            turn.created_at,
+                            turn.updated_at
+                        )
+                        .execute(&mut *tx)
+                        .await?;
+                    }
+
+                    if let Some(jsonl) = logs.get(&process.id).filter(|jsonl| !jsonl.is_empty()) {
+                        let byte_size = jsonl.len() as i64;
+                        let inserted_at: DateTime<Utc> =
+                            process.completed_at.unwrap_or(process.started_at);
+                        sqlx::query!(
+                            r#"INSERT INTO execution_process_logs (execution_id, logs, byte_size, inserted_at)
+                               VALUES ($1, $2, $3, $4)"#,
+                            process_id,
+                            jsonl,
+                            byte_size,
+                            inserted_at
+                        )
+                        .execute(&mut *tx)
+                        .await?;
+                    }
+                }
+            }
+        }
+
+        tx.commit().await?;
+        Ok(task_id)
+    }
+}
@@ -0,0 +1,278 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task::{Task, TaskStatus};
+
+#[derive(Debug, Error)]
+pub enum TaskDependencyError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Task dependency not found")]
+    NotFound,
+    #[error("Blocking task not found")]
+    BlockingTaskNotFound,
+    #[error("A task cannot depend on itself")]
+    SelfDependency,
+    #[error("Tasks must belong to the same project")]
+    CrossProject,
+    #[error("Task dependency already exists")]
+    AlreadyExists,
+    #[error("Adding this dependency would create a cycle")]
+    Cycle,
+}
+
+/// `task_id` is blocked until `depends_on_task_id` reaches `done`
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskDependency {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub depends_on_task_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateTaskDependency {
+    pub depends_on_task_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TaskDependencies {
+    pub blocked_by: Vec<Task>, // Tasks that must be done before this one can start
+    pub blocks: Vec<Task>,     // Tasks waiting on this one
+}
+
+impl TaskDependency {
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskDependency,
+            r#"SELECT id as "id!: Uuid", task_id as "task_id!: Uuid", depends_on_task_id as "depends_on_task_id!: Uuid", created_at as "created_at!: DateTime<Utc>"
+               FROM task_dependencies
+               WHERE task_id = $1
+               ORDER BY created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// All dependency edges between tasks of a project
+    pub async fn find_by_project_id<'e, E>(
+        executor: E,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        sqlx::query_as!(
+            TaskDependency,
+            r#"SELECT td.id as "id!: Uuid", td.task_id as "task_id!: Uuid", td.depends_on_task_id as "depends_on_task_id!: Uuid", td.created_at as "created_at!: DateTime<Utc>"
+               FROM task_dependencies td
+               JOIN tasks t ON t.id = td.task_id
+               WHERE t.project_id = $1"#,
+            project_id
+        )
+        .fetch_all(executor)
+        .await
+    }
+
+    pub async fn find_for_task(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<TaskDependencies, sqlx::Error> {
+        let blocked_by = sqlx::query_as!(
+            Task,
+            r#"SELECT t.id as "id!: Uuid", t.project_id as "project_id!: Uuid", t.title, t.description, t.status as "status!: TaskStatus", t.parent_workspace_id as "parent_workspace_id: Uuid", t.shared_task_id as "shared_task_id: Uuid", t.created_at as "created_at!: DateTime<Utc>", t.updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_dependencies td
+               JOIN tasks t ON t.id = td.depends_on_task_id
+               WHERE td.task_id = $1
+               ORDER BY td.created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let blocks = sqlx::query_as!(
+            Task,
+            r#"SELECT t.id as "id!: Uuid", t.project_id as "project_id!: Uuid", t.title, t.description, t.status as "status!: TaskStatus", t.parent_workspace_id as "parent_workspace_id: Uuid", t.shared_task_id as "shared_task_id: Uuid", t.created_at as "created_at!: DateTime<Utc>", t.updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_dependencies td
+               JOIN tasks t ON t.id = td.task_id
+               WHERE td.depends_on_task_id = $1
+               ORDER BY td.created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(TaskDependencies { blocked_by, blocks })
+    }
+
+    /// Dependents of `task_id` that are still in `todo` and whose blockers are all `done`
+    pub async fn find_unblocked_dependents(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Task>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"SELECT t.id as "id!: Uuid", t.project_id as "project_id!: Uuid", t.title, t.description, t.status as "status!: TaskStatus", t.parent_workspace_id as "parent_workspace_id: Uuid", t.shared_task_id as "shared_task_id: Uuid", t.created_at as "created_at!: DateTime<Utc>", t.updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_dependencies td
+               JOIN tasks t ON t.id = td.task_id
+               WHERE td.depends_on_task_id = $1
+                 AND t.status = 'todo'
+                 AND NOT EXISTS (
+                     SELECT 1
+                     FROM task_dependencies other
+                     JOIN tasks blocker ON blocker.id = other.depends_on_task_id
+                     WHERE other.task_id = t.id
+                       AND blocker.status != 'done'
+                 )
+               ORDER BY t.created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        task: &Task,
+        data: &CreateTaskDependency,
+    ) -> Result<Self, TaskDependencyError> {
+        if task.id == data.depends_on_task_id {
+            return Err(TaskDependencyError::SelfDependency);
+        }
+
+        let blocking_task = Task::find_by_id(pool, data.depends_on_task_id)
+            .await?
+            .ok_or(TaskDependencyError::BlockingTaskNotFound)?;
+        if blocking_task.project_id != task.project_id {
+            return Err(TaskDependencyError::CrossProject);
+        }
+
+        // Concurrent inserts could each pass the cycle check and close a cycle together, so
+        // the write lock is taken before reading the edges
+        let mut tx = pool.begin_with("BEGIN IMMEDIATE").await?;
+        let edges = Self::find_by_project_id(&mut *tx, task.project_id).await?;
+        if edges
+            .iter()
+            .any(|e| e.task_id == task.id && e.depends_on_task_id == data.depends_on_task_id)
+        {
+            return Err(TaskDependencyError::AlreadyExists);
+        }
+        let edges: Vec<(Uuid, Uuid)> = edges
+            .into_iter()
+            .map(|e| (e.task_id, e.depends_on_task_id))
+            .collect();
+        if would_create_cycle(&edges, task.id, data.depends_on_task_id) {
+            return Err(TaskDependencyError::Cycle);
+        }
+
+        let id = Uuid::new_v4();
+        let dependency = sqlx::query_as!(
+            TaskDependency,
+            r#"INSERT INTO task_dependencies (id, task_id, depends_on_task_id)
+               VALUES ($1, $2, $3)
+               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", depends_on_task_id as "depends_on_task_id!: Uuid", created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            task.id,
+            data.depends_on_task_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(dependency)
+    }
+
+    pub async fn delete(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        depends_on_task_id: Uuid,
+    ) -> Result<(), TaskDependencyError> {
+        let result = sqlx::query!(
+            "DELETE FROM task_dependencies WHERE task_id = $1 AND depends_on_task_id = $2",
+            task_id,
+            depends_on_task_id
+        )
+        .execute(pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(TaskDependencyError::NotFound);
+        }
+        Ok(())
+    }
+}
+
+/// Returns true if adding the edge `task_id -> depends_on_task_id` to `edges`
+/// (given as `(task_id, depends_on_task_id)` pairs) would close a cycle, i.e.
+/// `depends_on_task_id` already transitively depends on `task_id`.
+pub fn would_create_cycle(edges: &[(Uuid, Uuid)], task_id: Uuid, depends_on_task_id: Uuid) -> bool {
+    if task_id == depends_on_task_id {
+        return true;
+    }
+
+    let mut adjacency: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    for (from, to) in edges {
+        adjacency.entry(*from).or_default().push(*to);
+    }
+
+    let mut visited = HashSet::new();
+    let mut stack = vec![depends_on_task_id];
+    while let Some(current) = stack.pop() {
+        if current == task_id {
+            return true;
+        }
+        if !visited.insert(current) {
+            continue;
+        }
+        if let Some(next) = adjacency.get(&current) {
+            stack.extend(next.iter().copied());
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_self_dependency_is_cycle() {
+        let a = Uuid::new_v4();
+        assert!(would_create_cycle(&[], a, a));
+    }
+
+    #[test]
+    fn test_direct_cycle() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        // a depends on b; b depending on a closes the loop
+        assert!(would_create_cycle(&[(a, b)], b, a));
+    }
+
+    #[test]
+    fn test_transitive_cycle() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let edges = [(a, b), (b, c)];
+        assert!(would_create_cycle(&edges, c, a));
+        assert!(!would_create_cycle(&edges, a, c));
+    }
+
+    #[test]
+    fn test_diamond_is_not_cycle() {
+        let (a, b, c, d) = (
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        );
+        let edges = [(a, b), (a, c), (b, d)];
+        assert!(!would_create_cycle(&edges, c, d));
+    }
+}
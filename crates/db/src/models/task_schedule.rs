@@ -0,0 +1,329 @@
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A repo the tasks of a schedule are started on, and the branch their workspace targets
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TaskScheduleRepo {
+    pub repo_id: Uuid,
+    pub target_branch: String,
+}
+
+/// Creates a task from its templates and starts it, once at `next_run_at` or on a cron
+/// schedule
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskSchedule {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub name: String,
+    /// Five-field cron expression in the server's local time; `None` for a one-off run
+    pub cron_expression: Option<String>,
+    /// `{{date}}`, `{{time}}` and `{{datetime}}` are replaced with the local time of the run
+    pub title_template: String,
+    pub description_template: Option<String>,
+    #[ts(type = "ExecutorProfileId")]
+    pub executor_profile_id: sqlx::types::Json<ExecutorProfileId>,
+    /// Empty starts the task on every project repo at its current branch
+    #[ts(type = "Array<TaskScheduleRepo>")]
+    pub repos: sqlx::types::Json<Vec<TaskScheduleRepo>>,
+    pub enabled: bool,
+    /// `None` once a one-off schedule has run
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub last_task_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateTaskSchedule {
+    pub project_id: Uuid,
+    pub name: String,
+    /// Exactly one of `cron_expression` and `run_at` must be set
+    pub cron_expression: Option<String>,
+    pub run_at: Option<DateTime<Utc>>,
+    pub title_template: String,
+    pub description_template: Option<String>,
+    pub executor_profile_id: ExecutorProfileId,
+    #[serde(default)]
+    pub repos: Vec<TaskScheduleRepo>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateTaskSchedule {
+    pub name: Option<String>,
+    /// Setting either `cron_expression` or `run_at` replaces the current timing
+    pub cron_expression: Option<String>,
+    pub run_at: Option<DateTime<Utc>>,
+    pub title_template: Option<String>,
+    /// An empty string removes the description
+    pub description_template: Option<String>,
+    pub executor_profile_id: Option<ExecutorProfileId>,
+    pub repos: Option<Vec<TaskScheduleRepo>>,
+    pub enabled: Option<bool>,
+}
+
+impl TaskSchedule {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      name,
+                      cron_expression,
+                      title_template,
+                      description_template,
+                      executor_profile_id as "executor_profile_id!: sqlx::types::Json<ExecutorProfileId>",
+                      repos as "repos!: sqlx::types::Json<Vec<TaskScheduleRepo>>",
+                      enabled as "enabled!: bool",
+                      next_run_at as "next_run_at: DateTime<Utc>",
+                      last_run_at as "last_run_at: DateTime<Utc>",
+                      last_task_id as "last_task_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      name,
+                      cron_expression,
+                      title_template,
+                      description_template,
+                      executor_profile_id as "executor_profile_id!: sqlx::types::Json<ExecutorProfileId>",
+                      repos as "repos!: sqlx::types::Json<Vec<TaskScheduleRepo>>",
+                      enabled as "enabled!: bool",
+                      next_run_at as "next_run_at: DateTime<Utc>",
+                      last_run_at as "last_run_at: DateTime<Utc>",
+                      last_task_id as "last_task_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               WHERE project_id = $1
+               ORDER BY created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      name,
+                      cron_expression,
+                      title_template,
+                      description_template,
+                      executor_profile_id as "executor_profile_id!: sqlx::types::Json<ExecutorProfileId>",
+                      repos as "repos!: sqlx::types::Json<Vec<TaskScheduleRepo>>",
+                      enabled as "enabled!: bool",
+                      next_run_at as "next_run_at: DateTime<Utc>",
+                      last_run_at as "last_run_at: DateTime<Utc>",
+                      last_task_id as "last_task_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Enabled schedules whose next run is at or before `now`
+    pub async fn find_due(pool: &SqlitePool, now: DateTime<Utc>) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      name,
+                      cron_expression,
+                      title_template,
+                      description_template,
+                      executor_profile_id as "executor_profile_id!: sqlx::types::Json<ExecutorProfileId>",
+                      repos as "repos!: sqlx::types::Json<Vec<TaskScheduleRepo>>",
+                      enabled as "enabled!: bool",
+                      next_run_at as "next_run_at: DateTime<Utc>",
+                      last_run_at as "last_run_at: DateTime<Utc>",
+                      last_task_id as "last_task_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               WHERE enabled = 1
+                 AND next_run_at IS NOT NULL
+                 AND datetime(next_run_at) <= datetime($1)
+               ORDER BY next_run_at ASC"#,
+            now
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// `next_run_at` is computed by the caller from `cron_expression` or `run_at`
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateTaskSchedule,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let description_template = data
+            .description_template
+            .as_deref()
+            .filter(|description| !description.is_empty());
+        let executor_profile_id = sqlx::types::Json(&data.executor_profile_id);
+        let repos = sqlx::types::Json(&data.repos);
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"INSERT INTO task_schedules (id, project_id, name, cron_expression, title_template,
+                                           description_template, executor_profile_id, repos,
+                                           enabled, next_run_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         name,
+                         cron_expression,
+                         title_template,
+                         description_template,
+                         executor_profile_id as "executor_profile_id!: sqlx::types::Json<ExecutorProfileId>",
+                         repos as "repos!: sqlx::types::Json<Vec<TaskScheduleRepo>>",
+                         enabled as "enabled!: bool",
+                         next_run_at as "next_run_at: DateTime<Utc>",
+                         last_run_at as "last_run_at: DateTime<Utc>",
+                         last_task_id as "last_task_id: Uuid",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.project_id,
+            data.name,
+            data.cron_expression,
+            data.title_template,
+            description_template,
+            executor_profile_id,
+            repos,
+            data.enabled,
+            next_run_at
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// The timing is resolved by the caller: `cron_expression` and `next_run_at` are stored
+    /// as given
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateTaskSchedule,
+        cron_expression: Option<&str>,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<Self, sqlx::Error> {
+        let existing = Self::find_by_id(pool, id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+
+        let name = data.name.as_ref().unwrap_or(&existing.name);
+        let title_template = data
+            .title_template
+            .as_ref()
+            .unwrap_or(&existing.title_template);
+        let description_template = match &data.description_template {
+            Some(description) => Some(description.as_str()).filter(|d| !d.is_empty()),
+            None => existing.description_template.as_deref(),
+        };
+        let executor_profile_id = sqlx::types::Json(
+            data.executor_profile_id
+                .as_ref()
+                .unwrap_or(&existing.executor_profile_id.0),
+        );
+        let repos = sqlx::types::Json(data.repos.as_ref().unwrap_or(&existing.repos.0));
+        let enabled = data.enabled.unwrap_or(existing.enabled);
+
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"UPDATE task_schedules
+               SET name = $2, cron_expression = $3, title_template = $4, description_template = $5,
+                   executor_profile_id = $6, repos = $7, enabled = $8, next_run_at = $9,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         name,
+                         cron_expression,
+                         title_template,
+                         description_template,
+                         executor_profile_id as "executor_profile_id!: sqlx::types::Json<ExecutorProfileId>",
+                         repos as "repos!: sqlx::types::Json<Vec<TaskScheduleRepo>>",
+                         enabled as "enabled!: bool",
+                         next_run_at as "next_run_at: DateTime<Utc>",
+                         last_run_at as "last_run_at: DateTime<Utc>",
+                         last_task_id as "last_task_id: Uuid",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            name,
+            cron_expression,
+            title_template,
+            description_template,
+            executor_profile_id,
+            repos,
+            enabled,
+            next_run_at
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Record a run and move the schedule to its next run. A schedule without a next run
+    /// is disabled.
+    pub async fn record_run(
+        pool: &SqlitePool,
+        id: Uuid,
+        task_id: Uuid,
+        ran_at: DateTime<Utc>,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE task_schedules
+               SET last_run_at = $3,
+                   last_task_id = $2,
+                   next_run_at = $4,
+                   enabled = CASE WHEN $4 IS NULL THEN 0 ELSE enabled END,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            task_id,
+            ran_at,
+            next_run_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM task_schedules WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,260 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq)]
+#[sqlx(type_name = "warm_workspace_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum WarmWorkspaceStatus {
+    /// Worktrees are being created, moved to a new target commit or set up
+    Preparing,
+    /// Set up and waiting to be claimed by a new attempt
+    Ready,
+    /// A setup script failed; retried with a backoff, and once the target branch or the
+    /// setup inputs change
+    Failed,
+    /// Being moved into a workspace
+    Claimed,
+}
+
+/// A directory of worktrees for all repos of a project, created ahead of the attempts that
+/// will use them
+#[derive(Debug, Clone, FromRow)]
+pub struct WarmWorkspace {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub container_ref: String,
+    pub branch: String,
+    pub status: WarmWorkspaceStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// The worktree of one repo in a warm workspace
+#[derive(Debug, Clone, FromRow)]
+pub struct WarmWorkspaceRepo {
+    pub warm_workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub target_branch: String,
+    pub base_commit: String,
+    /// Hash of the setup script and lockfiles the last setup ran with, see `setup_cache`
+    pub setup_key: Option<String>,
+    pub setup_exit_code: Option<i64>,
+    pub setup_output: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateWarmWorkspaceRepo {
+    pub repo_id: Uuid,
+    pub target_branch: String,
+    pub base_commit: String,
+}
+
+impl WarmWorkspaceRepo {
+    /// Whether the worktree is set up, i.e. its repo has no setup script or it succeeded
+    pub fn is_set_up(&self) -> bool {
+        self.setup_key.is_none() || self.setup_exit_code == Some(0)
+    }
+
+    pub async fn find_by_warm_workspace_id(
+        pool: &SqlitePool,
+        warm_workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WarmWorkspaceRepo,
+            r#"SELECT warm_workspace_id as "warm_workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      target_branch,
+                      base_commit,
+                      setup_key,
+                      setup_exit_code,
+                      setup_output
+               FROM warm_workspace_repos
+               WHERE warm_workspace_id = $1"#,
+            warm_workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn update_base_commit(
+        pool: &SqlitePool,
+        warm_workspace_id: Uuid,
+        repo_id: Uuid,
+        base_commit: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE warm_workspace_repos
+               SET base_commit = $3
+               WHERE warm_workspace_id = $1 AND repo_id = $2"#,
+            warm_workspace_id,
+            repo_id,
+            base_commit
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Record the outcome of running the setup script in the worktree
+    pub async fn update_setup(
+        pool: &SqlitePool,
+        warm_workspace_id: Uuid,
+        repo_id: Uuid,
+        setup_key: Option<&str>,
+        exit_code: Option<i64>,
+        output: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE warm_workspace_repos
+               SET setup_key = $3, setup_exit_code = $4, setup_output = $5
+               WHERE warm_workspace_id = $1 AND repo_id = $2"#,
+            warm_workspace_id,
+            repo_id,
+            setup_key,
+            exit_code,
+            output
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
+
+impl WarmWorkspace {
+    pub async fn create(
+        pool: &SqlitePool,
+        id: Uuid,
+        project_id: Uuid,
+        container_ref: &str,
+        branch: &str,
+        repos: &[CreateWarmWorkspaceRepo],
+    ) -> Result<Self, sqlx::Error> {
+        let warm_workspace = sqlx::query_as!(
+            WarmWorkspace,
+            r#"INSERT INTO warm_workspaces (id, project_id, container_ref, branch)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         container_ref,
+                         branch,
+                         status as "status!: WarmWorkspaceStatus",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            container_ref,
+            branch
+        )
+        .fetch_one(pool)
+        .await?;
+
+        for repo in repos {
+            sqlx::query!(
+                r#"INSERT INTO warm_workspace_repos (warm_workspace_id, repo_id, target_branch, base_commit)
+                   VALUES ($1, $2, $3, $4)"#,
+                id,
+                repo.repo_id,
+                repo.target_branch,
+                repo.base_commit
+            )
+            .execute(pool)
+            .await?;
+        }
+
+        Ok(warm_workspace)
+    }
+
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WarmWorkspace,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      container_ref,
+                      branch,
+                      status as "status!: WarmWorkspaceStatus",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM warm_workspaces
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Warm workspaces of a project, oldest first
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WarmWorkspace,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      container_ref,
+                      branch,
+                      status as "status!: WarmWorkspaceStatus",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM warm_workspaces
+               WHERE project_id = $1
+               ORDER BY created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn update_status(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: WarmWorkspaceStatus,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE warm_workspaces
+               SET status = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            status
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Take a ready warm workspace for an attempt. Returns false if it was claimed or is
+    /// being refreshed in the meantime.
+    pub async fn claim(pool: &SqlitePool, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE warm_workspaces
+               SET status = 'claimed', updated_at = datetime('now', 'subsec')
+               WHERE id = $1 AND status = 'ready'"#,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Mark a ready or failed warm workspace as being refreshed so that it can't be claimed
+    /// meanwhile. Returns false if it was claimed in the meantime.
+    pub async fn start_refresh(pool: &SqlitePool, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE warm_workspaces
+               SET status = 'preparing', updated_at = datetime('now', 'subsec')
+               WHERE id = $1 AND status IN ('ready', 'failed')"#,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM warm_workspaces WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,188 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use strum_macros::{Display, EnumString};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, TS, EnumString, Display)]
+#[sqlx(type_name = "webhook_event", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum WebhookEvent {
+    TaskStatusChanged,
+    ExecutionStarted,
+    ExecutionCompleted,
+    ExecutionFailed,
+    ApprovalRequested,
+    PrCreated,
+    PrMerged,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct Webhook {
+    pub id: Uuid,
+    pub name: String,
+    pub url: String,
+    /// Signs payloads with HMAC-SHA256 in the `X-Vibe-Kanban-Signature` header when set.
+    /// Never sent to clients, which only learn whether there is one from `has_secret`
+    #[serde(skip_serializing)]
+    #[ts(skip)]
+    pub secret: Option<String>,
+    pub has_secret: bool,
+    /// Subscribed events; empty subscribes to every event
+    #[ts(type = "Array<WebhookEvent>")]
+    pub events: sqlx::types::Json<Vec<WebhookEvent>>,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateWebhook {
+    pub name: String,
+    pub url: String,
+    pub secret: Option<String>,
+    #[serde(default)]
+    pub events: Vec<WebhookEvent>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateWebhook {
+    pub name: Option<String>,
+    pub url: Option<String>,
+    /// An empty string removes the secret
+    pub secret: Option<String>,
+    pub events: Option<Vec<WebhookEvent>>,
+    pub enabled: Option<bool>,
+}
+
+impl Webhook {
+    pub fn subscribes_to(&self, event: WebhookEvent) -> bool {
+        self.enabled && (self.events.is_empty() || self.events.contains(&event))
+    }
+
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Webhook,
+            r#"SELECT id as "id!: Uuid",
+                      name,
+                      url,
+                      secret,
+                      secret IS NOT NULL as "has_secret!: bool",
+                      events as "events!: sqlx::types::Json<Vec<WebhookEvent>>",
+                      enabled as "enabled!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM webhooks
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Webhook,
+            r#"SELECT id as "id!: Uuid",
+                      name,
+                      url,
+                      secret,
+                      secret IS NOT NULL as "has_secret!: bool",
+                      events as "events!: sqlx::types::Json<Vec<WebhookEvent>>",
+                      enabled as "enabled!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM webhooks
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(pool: &SqlitePool, data: &CreateWebhook) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let secret = data.secret.as_deref().filter(|secret| !secret.is_empty());
+        let events = sqlx::types::Json(&data.events);
+        sqlx::query_as!(
+            Webhook,
+            r#"INSERT INTO webhooks (id, name, url, secret, events, enabled)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id as "id!: Uuid",
+                         name,
+                         url,
+                         secret,
+                         secret IS NOT NULL as "has_secret!: bool",
+                         events as "events!: sqlx::types::Json<Vec<WebhookEvent>>",
+                         enabled as "enabled!: bool",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.name,
+            data.url,
+            secret,
+            events,
+            data.enabled
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateWebhook,
+    ) -> Result<Self, sqlx::Error> {
+        let existing = Self::find_by_id(pool, id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+
+        let name = data.name.as_ref().unwrap_or(&existing.name);
+        let url = data.url.as_ref().unwrap_or(&existing.url);
+        let secret = match &data.secret {
+            Some(secret) => Some(secret.as_str()).filter(|secret| !secret.is_empty()),
+            None => existing.secret.as_deref(),
+        };
+        let events = sqlx::types::Json(data.events.as_ref().unwrap_or(&existing.events.0));
+        let enabled = data.enabled.unwrap_or(existing.enabled);
+
+        sqlx::query_as!(
+            Webhook,
+            r#"UPDATE webhooks
+               SET name = $2, url = $3, secret = $4, events = $5, enabled = $6,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                         name,
+                         url,
+                         secret,
+                         secret IS NOT NULL as "has_secret!: bool",
+                         events as "events!: sqlx::types::Json<Vec<WebhookEvent>>",
+                         enabled as "enabled!: bool",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            name,
+            url,
+            secret,
+            events,
+            enabled
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM webhooks WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
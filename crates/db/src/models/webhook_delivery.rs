@@ -0,0 +1,174 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::webhook::WebhookEvent;
+
+#[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "webhook_delivery_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookDeliveryStatus {
+    /// Waiting for its first attempt or for a retry
+    Pending,
+    Delivered,
+    /// Gave up after the last retry
+    Failed,
+}
+
+/// One event sent to one webhook, with the outcome of its latest attempt
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub event: WebhookEvent,
+    /// The JSON body sent to the webhook
+    pub payload: String,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: i64,
+    pub next_attempt_at: DateTime<Utc>,
+    /// HTTP status of the latest attempt, if the endpoint answered
+    pub response_status: Option<i64>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl WebhookDelivery {
+    pub async fn create(
+        pool: &SqlitePool,
+        id: Uuid,
+        webhook_id: Uuid,
+        event: WebhookEvent,
+        payload: &str,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"INSERT INTO webhook_deliveries (id, webhook_id, event, payload)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id as "id!: Uuid",
+                         webhook_id as "webhook_id!: Uuid",
+                         event as "event!: WebhookEvent",
+                         payload,
+                         status as "status!: WebhookDeliveryStatus",
+                         attempts as "attempts!: i64",
+                         next_attempt_at as "next_attempt_at!: DateTime<Utc>",
+                         response_status,
+                         last_error,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            webhook_id,
+            event,
+            payload
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Latest deliveries of a webhook, newest first
+    pub async fn find_by_webhook_id(
+        pool: &SqlitePool,
+        webhook_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"SELECT id as "id!: Uuid",
+                      webhook_id as "webhook_id!: Uuid",
+                      event as "event!: WebhookEvent",
+                      payload,
+                      status as "status!: WebhookDeliveryStatus",
+                      attempts as "attempts!: i64",
+                      next_attempt_at as "next_attempt_at!: DateTime<Utc>",
+                      response_status,
+                      last_error,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM webhook_deliveries
+               WHERE webhook_id = $1
+               ORDER BY created_at DESC
+               LIMIT $2"#,
+            webhook_id,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Pending deliveries whose next attempt is due, oldest first
+    pub async fn find_due(pool: &SqlitePool, limit: i64) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"SELECT id as "id!: Uuid",
+                      webhook_id as "webhook_id!: Uuid",
+                      event as "event!: WebhookEvent",
+                      payload,
+                      status as "status!: WebhookDeliveryStatus",
+                      attempts as "attempts!: i64",
+                      next_attempt_at as "next_attempt_at!: DateTime<Utc>",
+                      response_status,
+                      last_error,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM webhook_deliveries
+               WHERE status = 'pending'
+                 AND datetime(next_attempt_at) <= datetime('now', 'subsec')
+               ORDER BY next_attempt_at ASC
+               LIMIT $1"#,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn mark_delivered(
+        pool: &SqlitePool,
+        id: Uuid,
+        response_status: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE webhook_deliveries
+               SET status = 'delivered', attempts = attempts + 1, response_status = $2,
+                   last_error = NULL, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            response_status
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Record a failed attempt. The delivery is retried at `retry_at`, or marked failed
+    /// when there is none.
+    pub async fn mark_attempt_failed(
+        pool: &SqlitePool,
+        id: Uuid,
+        response_status: Option<i64>,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<(), sqlx::Error> {
+        let status = if retry_at.is_some() {
+            WebhookDeliveryStatus::Pending
+        } else {
+            WebhookDeliveryStatus::Failed
+        };
+        let next_attempt_at = retry_at.unwrap_or_else(Utc::now);
+        sqlx::query!(
+            r#"UPDATE webhook_deliveries
+               SET status = $2, attempts = attempts + 1, next_attempt_at = $3,
+                   response_status = $4, last_error = $5, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            status,
+            next_attempt_at,
+            response_status,
+            error
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
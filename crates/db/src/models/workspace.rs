@@ -287,7 +287,8 @@ impl Workspace {
         Ok(result.exists)
     }
 
-    /// Find workspaces that are expired (72+ hours since last activity) and eligible for cleanup
+    /// Find workspaces that are expired (72+ hours since last activity) or were discarded in
+    /// favour of another attempt of their group, and are eligible for cleanup
     pub async fn find_expired_for_cleanup(
         pool: &SqlitePool,
     ) -> Result<Vec<Workspace>, sqlx::Error> {
@@ -322,6 +323,11 @@ impl Workspace {
                     END
                 )
             )
+                OR w.id IN (
+                    SELECT workspace_id
+                    FROM attempt_group_members
+                    WHERE discarded_at IS NOT NULL
+                )
             ORDER BY MAX(
                 CASE
                     WHEN ep.completed_at IS NOT NULL THEN ep.completed_at
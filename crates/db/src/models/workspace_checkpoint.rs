@@ -0,0 +1,105 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// State of one repo worktree when a checkpoint was taken
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct CheckpointRepoState {
+    pub repo_id: Uuid,
+    /// Commit the worktree was on
+    pub head_commit: String,
+    /// Commit on top of `head_commit` holding the worktree contents, uncommitted and
+    /// untracked changes included
+    pub snapshot_commit: String,
+}
+
+/// A named snapshot of every repo worktree of a workspace
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct WorkspaceCheckpoint {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub name: String,
+    #[ts(type = "Array<CheckpointRepoState>")]
+    pub repo_states: sqlx::types::Json<Vec<CheckpointRepoState>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateWorkspaceCheckpoint {
+    pub name: String,
+}
+
+impl WorkspaceCheckpoint {
+    /// Checkpoints of a workspace, oldest first
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceCheckpoint,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      name,
+                      repo_states as "repo_states!: sqlx::types::Json<Vec<CheckpointRepoState>>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM workspace_checkpoints
+               WHERE workspace_id = $1
+               ORDER BY created_at ASC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceCheckpoint,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      name,
+                      repo_states as "repo_states!: sqlx::types::Json<Vec<CheckpointRepoState>>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM workspace_checkpoints
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// `id` is chosen by the caller since it names the refs of the snapshots
+    pub async fn create(
+        pool: &SqlitePool,
+        id: Uuid,
+        workspace_id: Uuid,
+        name: &str,
+        repo_states: &[CheckpointRepoState],
+    ) -> Result<Self, sqlx::Error> {
+        let repo_states = sqlx::types::Json(repo_states);
+        sqlx::query_as!(
+            WorkspaceCheckpoint,
+            r#"INSERT INTO workspace_checkpoints (id, workspace_id, name, repo_states)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id as "id!: Uuid",
+                         workspace_id as "workspace_id!: Uuid",
+                         name,
+                         repo_states as "repo_states!: sqlx::types::Json<Vec<CheckpointRepoState>>",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            workspace_id,
+            name,
+            repo_states
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM workspace_checkpoints WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -19,6 +19,7 @@ use services::services::{
     analytics::{AnalyticsContext, AnalyticsService},
     approvals::Approvals,
     auth::AuthContext,
+    ci_fix::PendingCiFix,
     config::{Config, ConfigError},
     container::{ContainerError, ContainerService},
     events::{EventError, EventService},
@@ -28,16 +29,20 @@ use services::services::{
     git::{GitService, GitServiceError},
     image::{ImageError, ImageService},
     pr_monitor::PrMonitorService,
+    pr_review::PendingReviewComments,
     project::ProjectService,
     queued_message::QueuedMessageService,
     repo::RepoService,
     share::SharePublisher,
+    task_schedule::{ScheduledTask, TaskScheduleService},
+    webhook::WebhookService,
     worktree_manager::WorktreeError,
 };
 use sqlx::Error as SqlxError;
 use thiserror::Error;
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, mpsc::UnboundedSender};
 use utils::sentry as sentry_utils;
+use uuid::Uuid;
 
 #[derive(Debug, Clone, Copy, Error)]
 #[error("Remote client not configured")]
@@ -113,6 +118,8 @@ pub trait Deployment: Clone + Send + Sync + 'static {
 
     fn share_publisher(&self) -> Result<SharePublisher, RemoteClientNotConfigured>;
 
+    fn webhooks(&self) -> &WebhookService;
+
     async fn update_sentry_scope(&self) -> Result<(), DeploymentError> {
         let user_id = self.user_id();
         let config = self.config().read().await;
@@ -123,7 +130,15 @@ pub trait Deployment: Clone + Send + Sync + 'static {
         Ok(())
     }
 
-    async fn spawn_pr_monitor_service(&self) -> tokio::task::JoinHandle<()> {
+    /// `task_done_tx` receives the id of every task the monitor moves to Done,
+    /// `review_comments_tx` the review comments it finds to address automatically and
+    /// `ci_fix_tx` the failing checks it finds to fix automatically
+    async fn spawn_pr_monitor_service(
+        &self,
+        task_done_tx: UnboundedSender<Uuid>,
+        review_comments_tx: UnboundedSender<PendingReviewComments>,
+        ci_fix_tx: UnboundedSender<PendingCiFix>,
+    ) -> tokio::task::JoinHandle<()> {
         let db = self.db().clone();
         let analytics = self
             .analytics()
@@ -133,7 +148,26 @@ pub trait Deployment: Clone + Send + Sync + 'static {
                 analytics_service: analytics_service.clone(),
             });
         let publisher = self.share_publisher().ok();
-        PrMonitorService::spawn(db, analytics, publisher).await
+        let webhooks = self.webhooks().clone();
+        PrMonitorService::spawn(
+            db,
+            analytics,
+            publisher,
+            webhooks,
+            task_done_tx,
+            self.config().clone(),
+            review_comments_tx,
+            ci_fix_tx,
+        )
+        .await
+    }
+
+    /// `task_tx` receives every task a due schedule creates, to be started by the caller
+    async fn spawn_task_schedule_service(
+        &self,
+        task_tx: UnboundedSender<ScheduledTask>,
+    ) -> tokio::task::JoinHandle<()> {
+        TaskScheduleService::spawn(self.db().clone(), task_tx).await
     }
 
     async fn track_if_analytics_allowed(&self, event_name: &str, properties: Value) {
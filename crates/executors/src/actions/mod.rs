@@ -12,7 +12,8 @@ use crate::{
     },
     approvals::ExecutorApprovalService,
     env::ExecutionEnv,
-    executors::{BaseCodingAgent, ExecutorError, SpawnedChild},
+    executors::{BaseCodingAgent, ExecutorError, SpawnedChild, StandardCodingAgentExecutor},
+    profile::ExecutorConfigs,
 };
 pub mod coding_agent_follow_up;
 pub mod coding_agent_initial;
@@ -27,16 +28,56 @@ pub enum ExecutorActionType {
     ScriptRequest,
 }
 
+/// Position of an action within a multi-step pipeline
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct PipelineStepRef {
+    pub pipeline_name: String,
+    pub step_name: String,
+    /// Zero-based index of the step
+    pub index: u32,
+    pub total: u32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct ExecutorAction {
     pub typ: ExecutorActionType,
     pub next_action: Option<Box<ExecutorAction>>,
+    /// Set when the action is a step of a pipeline
+    #[serde(default)]
+    pub pipeline_step: Option<PipelineStepRef>,
+    /// Follow-ups already sent back to the agent because a verify script failed
+    #[serde(default)]
+    pub verify_retries: u32,
 }
 
 impl ExecutorAction {
     pub fn new(typ: ExecutorActionType, next_action: Option<Box<ExecutorAction>>) -> Self {
-        Self { typ, next_action }
+        Self {
+            typ,
+            next_action,
+            pipeline_step: None,
+            verify_retries: 0,
+        }
+    }
+
+    pub fn with_pipeline_step(mut self, pipeline_step: PipelineStepRef) -> Self {
+        self.pipeline_step = Some(pipeline_step);
+        self
+    }
+
+    pub fn pipeline_step(&self) -> Option<&PipelineStepRef> {
+        self.pipeline_step.as_ref()
     }
+
+    pub fn with_verify_retries(mut self, verify_retries: u32) -> Self {
+        self.verify_retries = verify_retries;
+        self
+    }
+
+    pub fn verify_retries(&self) -> u32 {
+        self.verify_retries
+    }
+
     pub fn append_action(mut self, action: ExecutorAction) -> Self {
         if let Some(next) = self.next_action {
             self.next_action = Some(Box::new(next.append_action(action)));
@@ -63,6 +104,19 @@ impl ExecutorAction {
             ExecutorActionType::ScriptRequest(_) => None,
         }
     }
+
+    /// Whether the process of this action is spooled, see
+    /// [`StandardCodingAgentExecutor::spools_output`]. Scripts always are.
+    pub fn spools_output(&self) -> bool {
+        let executor_profile_id = match self.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => &request.executor_profile_id,
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => &request.executor_profile_id,
+            ExecutorActionType::ScriptRequest(_) => return true,
+        };
+        ExecutorConfigs::get_cached()
+            .get_coding_agent(executor_profile_id)
+            .is_some_and(|agent| agent.spools_output())
+    }
 }
 
 #[async_trait]
@@ -1,19 +1,54 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, ffi::OsStr};
 
 use tokio::process::Command;
 
-use crate::command::CmdOverrides;
+use crate::{
+    command::CmdOverrides,
+    remote::{REMOTE_ENV_KEYS_VAR, RemoteWorkspace},
+    sandbox::Sandbox,
+    spool::ProcessSpool,
+};
 
-/// Environment variables to inject into executor processes
+/// Environment variables to inject into executor processes, the sandbox or remote host
+/// to run them in, and the spool their output is kept in
 #[derive(Debug, Clone, Default)]
 pub struct ExecutionEnv {
     pub vars: HashMap<String, String>,
+    pub sandbox: Option<Sandbox>,
+    pub remote: Option<RemoteWorkspace>,
+    pub spool: Option<ProcessSpool>,
 }
 
 impl ExecutionEnv {
     pub fn new() -> Self {
         Self {
             vars: HashMap::new(),
+            sandbox: None,
+            remote: None,
+            spool: None,
+        }
+    }
+
+    /// Command for the process of the execution, run on the remote host or inside the
+    /// sandbox when one is configured, with its output spooled when a spool is set
+    pub fn command(&self, program: impl AsRef<OsStr>) -> Command {
+        let command = self.helper_command(program);
+        match &self.spool {
+            Some(spool) => spool.wrap(command),
+            None => command,
+        }
+    }
+
+    /// Command for a short-lived helper the executor runs before its process, e.g. to prepare
+    /// a session. Runs where the process would, but is never spooled. A remote host replaces
+    /// the sandbox, so the container never sets both.
+    pub fn helper_command(&self, program: impl AsRef<OsStr>) -> Command {
+        if let Some(remote) = &self.remote {
+            return remote.command(program);
+        }
+        match &self.sandbox {
+            Some(sandbox) => sandbox.command(program),
+            None => Command::new(program),
         }
     }
 
@@ -48,6 +83,11 @@ impl ExecutionEnv {
         for (key, value) in &self.vars {
             command.env(key, value);
         }
+        // Remote processes only get the variables set here, not the whole local environment
+        if self.remote.is_some() {
+            let keys: Vec<&str> = self.vars.keys().map(String::as_str).collect();
+            command.env(REMOTE_ENV_KEYS_VAR, keys.join(" "));
+        }
     }
 
     pub fn contains_key(&self, key: &str) -> bool {
@@ -10,10 +10,12 @@ use command_group::AsyncCommandGroup;
 use futures::StreamExt;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tokio::process::Command;
 use ts_rs::TS;
 use workspace_utils::{
-    approvals::ApprovalStatus, diff::create_unified_diff, log_msg::LogMsg, msg_store::MsgStore,
+    approvals::ApprovalStatus,
+    diff::create_unified_diff,
+    log_msg::{LogMsg, TokenUsage},
+    msg_store::MsgStore,
     path::make_path_relative,
 };
 
@@ -241,7 +243,7 @@ impl ClaudeCode {
         let (program_path, args) = command_parts.into_resolved().await?;
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(program_path);
+        let mut command = env.command(program_path);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
@@ -368,7 +370,10 @@ impl ClaudeLogProcessor {
             while let Some(Ok(msg)) = stream.next().await {
                 let chunk = match msg {
                     LogMsg::Stdout(x) => x,
-                    LogMsg::JsonPatch(_) | LogMsg::SessionId(_) | LogMsg::Stderr(_) => continue,
+                    LogMsg::JsonPatch(_)
+                    | LogMsg::SessionId(_)
+                    | LogMsg::TokenUsage(_)
+                    | LogMsg::Stderr(_) => continue,
                     LogMsg::Finished => break,
                 };
 
@@ -404,6 +409,10 @@ impl ClaudeLogProcessor {
                                 session_id_extracted = true;
                             }
 
+                            for usage in processor.extract_token_usage(&claude_json) {
+                                msg_store.push_token_usage(usage);
+                            }
+
                             let patches = processor.normalize_entries(
                                 &claude_json,
                                 &worktree_path,
@@ -467,6 +476,41 @@ impl ClaudeLogProcessor {
         }
     }
 
+    /// Token usage of the whole run, reported once by the final `result` message.
+    /// Prefers the per-model breakdown and falls back to the aggregate for the main model.
+    fn extract_token_usage(&self, claude_json: &ClaudeJson) -> Vec<TokenUsage> {
+        let ClaudeJson::Result {
+            usage, model_usage, ..
+        } = claude_json
+        else {
+            return Vec::new();
+        };
+        if let Some(model_usage) = model_usage
+            && !model_usage.is_empty()
+        {
+            return model_usage
+                .iter()
+                .map(|(model, u)| TokenUsage {
+                    model: Some(model.clone()),
+                    input_tokens: u.input_tokens,
+                    output_tokens: u.output_tokens,
+                    cache_creation_input_tokens: u.cache_creation_input_tokens,
+                    cache_read_input_tokens: u.cache_read_input_tokens,
+                })
+                .collect();
+        }
+        usage
+            .iter()
+            .map(|u| TokenUsage {
+                model: self.model_name.clone(),
+                input_tokens: u.input_tokens.unwrap_or(0),
+                output_tokens: u.output_tokens.unwrap_or(0),
+                cache_creation_input_tokens: u.cache_creation_input_tokens.unwrap_or(0),
+                cache_read_input_tokens: u.cache_read_input_tokens.unwrap_or(0),
+            })
+            .collect()
+    }
+
     /// Generate warning entry if API key source is ANTHROPIC_API_KEY
     fn warn_if_unmanaged_key(src: &Option<String>) -> Option<NormalizedEntry> {
         match src.as_deref() {
@@ -1504,6 +1548,10 @@ pub enum ClaudeJson {
         num_turns: Option<u32>,
         #[serde(default, alias = "sessionId")]
         session_id: Option<String>,
+        #[serde(default)]
+        usage: Option<ClaudeUsage>,
+        #[serde(default, rename = "modelUsage")]
+        model_usage: Option<HashMap<String, ClaudeModelUsage>>,
     },
     #[serde(rename = "approval_response")]
     ApprovalResponse {
@@ -1600,6 +1648,20 @@ pub struct ClaudeMessageDelta {
     pub stop_sequence: Option<String>,
 }
 
+/// Per-model totals reported in the final `result` message
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeModelUsage {
+    #[serde(default)]
+    pub input_tokens: u64,
+    #[serde(default)]
+    pub output_tokens: u64,
+    #[serde(default)]
+    pub cache_creation_input_tokens: u64,
+    #[serde(default)]
+    pub cache_read_input_tokens: u64,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
 pub struct ClaudeUsage {
     #[serde(default)]
@@ -1903,6 +1965,35 @@ mod tests {
         assert_eq!(entries.len(), 0); // Should be ignored like in old implementation
     }
 
+    #[test]
+    fn test_result_token_usage_extraction() {
+        let mut processor = ClaudeLogProcessor::new();
+        let assistant_json = r#"{"type":"assistant","message":{"role":"assistant","model":"claude-sonnet-4-5","content":[{"type":"text","text":"Done"}]}}"#;
+        let parsed: ClaudeJson = serde_json::from_str(assistant_json).unwrap();
+        normalize_helper(&mut processor, &parsed, "");
+        assert!(processor.extract_token_usage(&parsed).is_empty());
+
+        let result_json = r#"{"type":"result","subtype":"success","is_error":false,"usage":{"input_tokens":12,"output_tokens":340,"cache_creation_input_tokens":1000,"cache_read_input_tokens":5000}}"#;
+        let parsed: ClaudeJson = serde_json::from_str(result_json).unwrap();
+        assert_eq!(
+            processor.extract_token_usage(&parsed),
+            vec![TokenUsage {
+                model: Some("claude-sonnet-4-5".to_string()),
+                input_tokens: 12,
+                output_tokens: 340,
+                cache_creation_input_tokens: 1000,
+                cache_read_input_tokens: 5000,
+            }]
+        );
+
+        let result_json = r#"{"type":"result","subtype":"success","usage":{"input_tokens":12,"output_tokens":340},"modelUsage":{"claude-haiku-4-5":{"inputTokens":800,"outputTokens":40,"cacheReadInputTokens":0,"cacheCreationInputTokens":0,"costUSD":0.001}}}"#;
+        let parsed: ClaudeJson = serde_json::from_str(result_json).unwrap();
+        let usage = processor.extract_token_usage(&parsed);
+        assert_eq!(usage.len(), 1);
+        assert_eq!(usage[0].model.as_deref(), Some("claude-haiku-4-5"));
+        assert_eq!(usage[0].input_tokens, 800);
+    }
+
     #[test]
     fn test_thinking_content() {
         let thinking_json = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"thinking","thinking":"Let me think about this..."}]}}"#;
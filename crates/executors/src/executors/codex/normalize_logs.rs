@@ -17,8 +17,8 @@ use codex_protocol::{
         ErrorEvent, EventMsg, ExecApprovalRequestEvent, ExecCommandBeginEvent, ExecCommandEndEvent,
         ExecCommandOutputDeltaEvent, ExecOutputStream, FileChange as CodexProtoFileChange,
         McpInvocation, McpToolCallBeginEvent, McpToolCallEndEvent, PatchApplyBeginEvent,
-        PatchApplyEndEvent, StreamErrorEvent, TokenUsageInfo, ViewImageToolCallEvent, WarningEvent,
-        WebSearchBeginEvent, WebSearchEndEvent,
+        PatchApplyEndEvent, StreamErrorEvent, TokenUsage as CodexTokenUsage, TokenUsageInfo,
+        ViewImageToolCallEvent, WarningEvent, WebSearchBeginEvent, WebSearchEndEvent,
     },
 };
 use futures::StreamExt;
@@ -26,8 +26,8 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use workspace_utils::{
-    approvals::ApprovalStatus, diff::normalize_unified_diff, msg_store::MsgStore,
-    path::make_path_relative,
+    approvals::ApprovalStatus, diff::normalize_unified_diff, log_msg::TokenUsage,
+    msg_store::MsgStore, path::make_path_relative,
 };
 
 use crate::{
@@ -216,6 +216,8 @@ struct LogState {
     patches: HashMap<String, PatchState>,
     web_searches: HashMap<String, WebSearchState>,
     token_usage_info: Option<TokenUsageInfo>,
+    token_usage_baseline: Option<TokenUsage>,
+    model: Option<String>,
 }
 
 enum StreamingTextKind {
@@ -234,9 +236,21 @@ impl LogState {
             patches: HashMap::new(),
             web_searches: HashMap::new(),
             token_usage_info: None,
+            token_usage_baseline: None,
+            model: None,
         }
     }
 
+    /// Codex reports totals for the whole conversation, which includes earlier processes
+    /// when resuming, so usage is counted from the state before this process's first turn
+    fn process_token_usage(&mut self, info: &TokenUsageInfo) -> TokenUsage {
+        let total = codex_token_usage(&info.total_token_usage, self.model.clone());
+        let baseline = self.token_usage_baseline.get_or_insert_with(|| {
+            total.saturating_sub(&codex_token_usage(&info.last_token_usage, None))
+        });
+        total.saturating_sub(baseline)
+    }
+
     fn streaming_text_update(
         &mut self,
         content: String,
@@ -380,7 +394,9 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
             }
 
             if let Ok(response) = serde_json::from_str::<JSONRPCResponse>(&line) {
-                handle_jsonrpc_response(response, &msg_store, &entry_index);
+                if let Some(model) = handle_jsonrpc_response(response, &msg_store, &entry_index) {
+                    state.model = Some(model);
+                }
                 continue;
             }
 
@@ -389,6 +405,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                     server_notification
                 {
                     msg_store.push_session_id(session_configured.session_id.to_string());
+                    state.model = Some(session_configured.model.clone());
                     handle_model_params(
                         session_configured.model,
                         session_configured.reasoning_effort,
@@ -427,6 +444,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
             match event {
                 EventMsg::SessionConfigured(payload) => {
                     msg_store.push_session_id(payload.session_id.to_string());
+                    state.model = Some(payload.model.clone());
                     handle_model_params(
                         payload.model,
                         payload.reasoning_effort,
@@ -971,6 +989,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                 }
                 EventMsg::TokenCount(payload) => {
                     if let Some(info) = payload.info {
+                        msg_store.push_token_usage(state.process_token_usage(&info));
                         state.token_usage_info = Some(info);
                     }
                 }
@@ -1017,14 +1036,15 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
     });
 }
 
+/// Returns the model of the new conversation
 fn handle_jsonrpc_response(
     response: JSONRPCResponse,
     msg_store: &Arc<MsgStore>,
     entry_index: &EntryIndexProvider,
-) {
+) -> Option<String> {
     let Ok(response) = serde_json::from_value::<NewConversationResponse>(response.result.clone())
     else {
-        return;
+        return None;
     };
 
     match SessionHandler::extract_session_id_from_rollout_path(response.rollout_path) {
@@ -1033,11 +1053,24 @@ fn handle_jsonrpc_response(
     }
 
     handle_model_params(
-        response.model,
+        response.model.clone(),
         response.reasoning_effort,
         msg_store,
         entry_index,
     );
+    Some(response.model)
+}
+
+/// Codex counts cached tokens as part of the input, split them out like other agents do
+fn codex_token_usage(usage: &CodexTokenUsage, model: Option<String>) -> TokenUsage {
+    let cached_input_tokens = usage.cached_input_tokens.max(0) as u64;
+    TokenUsage {
+        model,
+        input_tokens: (usage.input_tokens.max(0) as u64).saturating_sub(cached_input_tokens),
+        output_tokens: usage.output_tokens.max(0) as u64,
+        cache_creation_input_tokens: 0,
+        cache_read_input_tokens: cached_input_tokens,
+    }
 }
 
 fn handle_model_params(
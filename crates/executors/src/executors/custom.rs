@@ -0,0 +1,597 @@
+use std::{
+    collections::HashMap,
+    path::Path,
+    process::Stdio,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use async_trait::async_trait;
+use command_group::AsyncCommandGroup;
+use derivative::Derivative;
+use futures::StreamExt;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::io::AsyncWriteExt;
+use ts_rs::TS;
+use workspace_utils::{msg_store::MsgStore, shell::resolve_executable_path_blocking};
+
+use crate::{
+    approvals::ExecutorApprovalService,
+    command::{CmdOverrides, CommandBuilder, CommandParts, apply_overrides},
+    env::ExecutionEnv,
+    executors::{
+        AppendPrompt, AvailabilityInfo, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
+        acp::AcpAgentHarness,
+    },
+    logs::{
+        ActionType, NormalizedEntry, NormalizedEntryError, NormalizedEntryType, ToolStatus,
+        plain_text_processor::PlainTextLogProcessor,
+        stderr_processor::normalize_stderr_logs,
+        utils::{EntryIndexProvider, patch::ConversationPatch},
+    },
+};
+
+const SESSION_ID_PLACEHOLDER: &str = "{session_id}";
+
+/// How the prompt is handed to the agent
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename = "CustomAgentPromptInput")]
+pub enum PromptInput {
+    #[default]
+    Stdin,
+    Argument,
+}
+
+/// How the output of the agent is turned into conversation entries
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename = "CustomAgentOutputFormat")]
+pub enum OutputFormat {
+    #[default]
+    PlainText,
+    Acp,
+    JsonLines,
+}
+
+/// Conversation entry a JSON-lines event type is shown as
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename = "CustomAgentEntryType")]
+pub enum JsonLinesEntryType {
+    UserMessage,
+    AssistantMessage,
+    Thinking,
+    ToolUse,
+    SystemMessage,
+    ErrorMessage,
+    Ignore,
+}
+
+fn default_type_field() -> String {
+    "type".to_string()
+}
+
+fn default_content_field() -> String {
+    "content".to_string()
+}
+
+/// Where to find things in the JSON objects the agent prints. Fields are dot-separated paths,
+/// e.g. `message.text`; array elements are addressed by index, e.g. `content.0.text`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+#[ts(rename = "CustomAgentJsonLines")]
+pub struct JsonLinesMapping {
+    #[serde(default = "default_type_field")]
+    #[schemars(
+        title = "Type Field",
+        description = "Field holding the event type looked up in entry_types"
+    )]
+    pub type_field: String,
+    #[serde(default = "default_content_field")]
+    #[schemars(
+        title = "Content Field",
+        description = "Field holding the text of the entry. Non-string values are shown as JSON"
+    )]
+    pub content_field: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Tool Name Field",
+        description = "Field holding the tool name of tool_use events. Defaults to the event type"
+    )]
+    pub tool_name_field: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Session ID Field",
+        description = "Field holding the session id used to resume the conversation"
+    )]
+    pub session_id_field: Option<String>,
+    #[serde(default)]
+    #[schemars(
+        title = "Entry Types",
+        description = "Conversation entry each event type is shown as. Events of other types are not shown"
+    )]
+    pub entry_types: HashMap<String, JsonLinesEntryType>,
+}
+
+impl Default for JsonLinesMapping {
+    fn default() -> Self {
+        Self {
+            type_field: default_type_field(),
+            content_field: default_content_field(),
+            tool_name_field: None,
+            session_id_field: None,
+            entry_types: HashMap::new(),
+        }
+    }
+}
+
+impl JsonLinesMapping {
+    /// Session id and conversation entry carried by one line of output. Lines that are not
+    /// JSON objects are shown as system messages.
+    fn normalize_line(&self, line: &str) -> (Option<String>, Option<NormalizedEntry>) {
+        let line = line.trim();
+        if line.is_empty() {
+            return (None, None);
+        }
+        let value = match serde_json::from_str::<Value>(line) {
+            Ok(value) if value.is_object() => value,
+            _ => {
+                return (
+                    None,
+                    Some(entry(
+                        NormalizedEntryType::SystemMessage,
+                        strip_ansi_escapes::strip_str(line),
+                    )),
+                );
+            }
+        };
+
+        let session_id = self
+            .session_id_field
+            .as_deref()
+            .and_then(|path| field_text(&value, path));
+
+        let Some(event_type) = field(&value, &self.type_field).and_then(Value::as_str) else {
+            return (session_id, None);
+        };
+        let content = field(&value, &self.content_field);
+        let content_text = content.and_then(value_text);
+        let entry_type = match self.entry_types.get(event_type) {
+            None | Some(JsonLinesEntryType::Ignore) => return (session_id, None),
+            Some(JsonLinesEntryType::ToolUse) => {
+                let tool_name = self
+                    .tool_name_field
+                    .as_deref()
+                    .and_then(|path| field_text(&value, path))
+                    .unwrap_or_else(|| event_type.to_string());
+                let entry = entry(
+                    NormalizedEntryType::ToolUse {
+                        tool_name: tool_name.clone(),
+                        action_type: ActionType::Tool {
+                            tool_name: tool_name.clone(),
+                            arguments: content.cloned(),
+                            result: None,
+                        },
+                        status: ToolStatus::Success,
+                    },
+                    content_text.unwrap_or(tool_name),
+                );
+                return (session_id, Some(entry));
+            }
+            Some(JsonLinesEntryType::UserMessage) => NormalizedEntryType::UserMessage,
+            Some(JsonLinesEntryType::AssistantMessage) => NormalizedEntryType::AssistantMessage,
+            Some(JsonLinesEntryType::Thinking) => NormalizedEntryType::Thinking,
+            Some(JsonLinesEntryType::SystemMessage) => NormalizedEntryType::SystemMessage,
+            Some(JsonLinesEntryType::ErrorMessage) => NormalizedEntryType::ErrorMessage {
+                error_type: NormalizedEntryError::Other,
+            },
+        };
+        (session_id, content_text.map(|text| entry(entry_type, text)))
+    }
+}
+
+fn entry(entry_type: NormalizedEntryType, content: String) -> NormalizedEntry {
+    NormalizedEntry {
+        timestamp: None,
+        entry_type,
+        content,
+        metadata: None,
+    }
+}
+
+fn field<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    value.pointer(&format!("/{}", path.replace('.', "/")))
+}
+
+fn field_text(value: &Value, path: &str) -> Option<String> {
+    field(value, path).and_then(value_text)
+}
+
+fn value_text(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(text) => Some(text.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// An agent CLI described entirely by its profile, for agents without a dedicated executor
+#[derive(Derivative, Clone, Serialize, Deserialize, TS, JsonSchema)]
+#[derivative(Debug, PartialEq)]
+pub struct CustomAgent {
+    #[serde(default)]
+    pub append_prompt: AppendPrompt,
+    #[schemars(
+        title = "Command",
+        description = "Command line starting the agent, e.g. `aider --yes-always --message`"
+    )]
+    pub command: String,
+    #[serde(default)]
+    #[schemars(
+        title = "Prompt Input",
+        description = "Write the prompt to stdin, or append it to the command as the last argument"
+    )]
+    pub prompt_input: PromptInput,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Follow-up Parameters",
+        description = "Parameters appended to the command to resume a session, with {session_id} replaced by the session id. Follow-ups are not supported when unset"
+    )]
+    pub follow_up_params: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Session ID Regex",
+        description = "Regex matched against each output line; its first capture group is the session id"
+    )]
+    pub session_id_regex: Option<String>,
+    #[serde(default)]
+    #[schemars(
+        title = "Output Format",
+        description = "plain_text shows stdout as assistant messages, acp talks the Agent Client Protocol over stdio, json_lines maps every stdout line with the JSON lines mapping"
+    )]
+    pub output_format: OutputFormat,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "JSON Lines Mapping",
+        description = "How JSON-lines output maps to conversation entries"
+    )]
+    pub json_lines: Option<JsonLinesMapping>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "MCP Config Path",
+        description = "MCP server config file of the agent, `~` expands to the home directory"
+    )]
+    pub mcp_config_path: Option<String>,
+    #[serde(flatten)]
+    pub cmd: CmdOverrides,
+    #[serde(skip)]
+    #[ts(skip)]
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    pub approvals: Option<Arc<dyn ExecutorApprovalService>>,
+}
+
+impl CustomAgent {
+    fn build_command_builder(&self) -> CommandBuilder {
+        apply_overrides(CommandBuilder::new(self.command.clone()), &self.cmd)
+    }
+
+    fn harness() -> AcpAgentHarness {
+        AcpAgentHarness::with_session_namespace("custom_agent_sessions")
+    }
+
+    async fn spawn_command(
+        &self,
+        command_parts: CommandParts,
+        current_dir: &Path,
+        prompt: &str,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let (program_path, mut args) = command_parts.into_resolved().await?;
+        let combined_prompt = self.append_prompt.combine_prompt(prompt);
+        if self.prompt_input == PromptInput::Argument {
+            args.push(combined_prompt.clone());
+        }
+
+        let mut command = env.command(program_path);
+        command
+            .kill_on_drop(true)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .current_dir(current_dir)
+            .args(&args);
+
+        env.clone()
+            .with_profile(&self.cmd)
+            .apply_to_command(&mut command);
+
+        let mut child = command.group_spawn()?;
+
+        // Close stdin either way so the agent does not wait for input
+        if let Some(mut stdin) = child.inner().stdin.take() {
+            if self.prompt_input == PromptInput::Stdin {
+                stdin.write_all(combined_prompt.as_bytes()).await?;
+            }
+            stdin.shutdown().await?;
+        }
+
+        Ok(child.into())
+    }
+
+    fn session_id_regex(&self) -> Option<Regex> {
+        let pattern = self.session_id_regex.as_deref()?;
+        Regex::new(pattern)
+            .inspect_err(|e| tracing::warn!("Invalid session id regex {pattern:?}: {e}"))
+            .ok()
+    }
+}
+
+#[async_trait]
+impl StandardCodingAgentExecutor for CustomAgent {
+    fn use_approvals(&mut self, approvals: Arc<dyn ExecutorApprovalService>) {
+        self.approvals = Some(approvals);
+    }
+
+    async fn spawn(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let command_parts = self.build_command_builder().build_initial()?;
+        if self.output_format == OutputFormat::Acp {
+            return Self::harness()
+                .spawn_with_command(
+                    current_dir,
+                    self.append_prompt.combine_prompt(prompt),
+                    command_parts,
+                    env,
+                    &self.cmd,
+                    self.approvals.clone(),
+                )
+                .await;
+        }
+        self.spawn_command(command_parts, current_dir, prompt, env)
+            .await
+    }
+
+    async fn spawn_follow_up(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        session_id: &str,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        // ACP sessions are resumed by the harness itself
+        if self.output_format == OutputFormat::Acp {
+            let command_parts = self.build_command_builder().build_initial()?;
+            return Self::harness()
+                .spawn_follow_up_with_command(
+                    current_dir,
+                    self.append_prompt.combine_prompt(prompt),
+                    session_id,
+                    command_parts,
+                    env,
+                    &self.cmd,
+                    self.approvals.clone(),
+                )
+                .await;
+        }
+
+        let follow_up_params = self.follow_up_params.as_ref().ok_or_else(|| {
+            ExecutorError::FollowUpNotSupported(
+                "the custom agent has no follow_up_params configured".to_string(),
+            )
+        })?;
+        let follow_up_params: Vec<String> = follow_up_params
+            .iter()
+            .map(|param| param.replace(SESSION_ID_PLACEHOLDER, session_id))
+            .collect();
+        let command_parts = self
+            .build_command_builder()
+            .build_follow_up(&follow_up_params)?;
+        self.spawn_command(command_parts, current_dir, prompt, env)
+            .await
+    }
+
+    fn spools_output(&self) -> bool {
+        self.output_format != OutputFormat::Acp
+    }
+
+    fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &Path) {
+        if self.output_format == OutputFormat::Acp {
+            crate::executors::acp::normalize_logs(msg_store, worktree_path);
+            return;
+        }
+
+        let entry_index_provider = EntryIndexProvider::start_from(&msg_store);
+        normalize_stderr_logs(msg_store.clone(), entry_index_provider.clone());
+
+        let session_id_regex = self.session_id_regex();
+        let session_id_found = Arc::new(AtomicBool::new(false));
+        let push_session_id = {
+            let msg_store = msg_store.clone();
+            let session_id_found = session_id_found.clone();
+            move |session_id: String| {
+                if !session_id_found.swap(true, Ordering::Relaxed) {
+                    msg_store.push_session_id(session_id);
+                }
+            }
+        };
+        let match_session_id = move |line: &str| {
+            session_id_regex
+                .as_ref()
+                .and_then(|re| re.captures(line))
+                .and_then(|caps| caps.get(1))
+                .map(|m| m.as_str().to_string())
+        };
+
+        // stderr is shown by the stderr normalizer, only look for the session id in it
+        if self.session_id_regex.is_some() {
+            let msg_store = msg_store.clone();
+            let match_session_id = match_session_id.clone();
+            let push_session_id = push_session_id.clone();
+            tokio::spawn(async move {
+                let mut stderr_lines = msg_store.stderr_lines_stream();
+                while let Some(Ok(line)) = stderr_lines.next().await {
+                    if let Some(session_id) = match_session_id(&line) {
+                        push_session_id(session_id);
+                    }
+                }
+            });
+        }
+
+        let json_lines = match self.output_format {
+            OutputFormat::JsonLines => Some(self.json_lines.clone().unwrap_or_default()),
+            _ => None,
+        };
+        tokio::spawn(async move {
+            let mut stdout_lines = msg_store.stdout_lines_stream();
+            let mut processor = plain_text_normalizer(entry_index_provider.clone());
+
+            while let Some(Ok(line)) = stdout_lines.next().await {
+                if let Some(session_id) = match_session_id(&line) {
+                    push_session_id(session_id);
+                }
+
+                let Some(mapping) = &json_lines else {
+                    for patch in processor.process(line + "\n") {
+                        msg_store.push_patch(patch);
+                    }
+                    continue;
+                };
+                let (session_id, entry) = mapping.normalize_line(&line);
+                if let Some(session_id) = session_id {
+                    push_session_id(session_id);
+                }
+                if let Some(entry) = entry {
+                    msg_store.push_patch(ConversationPatch::add_normalized_entry(
+                        entry_index_provider.next(),
+                        entry,
+                    ));
+                }
+            }
+        });
+    }
+
+    // MCP configuration methods
+    fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
+        let path = self.mcp_config_path.as_deref()?;
+        match path.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir().map(|home| home.join(rest)),
+            None => Some(path.into()),
+        }
+    }
+
+    fn get_availability_info(&self) -> AvailabilityInfo {
+        let installed = self
+            .build_command_builder()
+            .build_initial()
+            .ok()
+            .and_then(|parts| resolve_executable_path_blocking(parts.program()))
+            .is_some();
+
+        if installed {
+            AvailabilityInfo::InstallationFound
+        } else {
+            AvailabilityInfo::NotFound
+        }
+    }
+}
+
+fn plain_text_normalizer(index_provider: EntryIndexProvider) -> PlainTextLogProcessor {
+    PlainTextLogProcessor::builder()
+        .normalized_entry_producer(Box::new(|content: String| {
+            entry(NormalizedEntryType::AssistantMessage, content)
+        }))
+        .transform_lines(Box::new(|lines| {
+            lines.iter_mut().for_each(|line| {
+                *line = strip_ansi_escapes::strip_str(&line);
+            })
+        }))
+        .index_provider(index_provider)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping() -> JsonLinesMapping {
+        serde_json::from_value(serde_json::json!({
+            "content_field": "message.text",
+            "tool_name_field": "tool.name",
+            "session_id_field": "session",
+            "entry_types": {
+                "reply": "assistant_message",
+                "reasoning": "thinking",
+                "tool": "tool_use",
+                "usage": "ignore",
+            },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_json_lines_mapping() {
+        let mapping = mapping();
+
+        let (session_id, entry) =
+            mapping.normalize_line(r#"{"type":"reply","session":"abc","message":{"text":"Done"}}"#);
+        assert_eq!(session_id.as_deref(), Some("abc"));
+        let entry = entry.unwrap();
+        assert!(matches!(
+            entry.entry_type,
+            NormalizedEntryType::AssistantMessage
+        ));
+        assert_eq!(entry.content, "Done");
+
+        let (_, entry) = mapping.normalize_line(
+            r#"{"type":"tool","tool":{"name":"shell"},"message":{"text":{"cmd":"ls"}}}"#,
+        );
+        let entry = entry.unwrap();
+        match entry.entry_type {
+            NormalizedEntryType::ToolUse {
+                tool_name,
+                action_type: ActionType::Tool { arguments, .. },
+                ..
+            } => {
+                assert_eq!(tool_name, "shell");
+                assert_eq!(arguments, Some(serde_json::json!({"cmd": "ls"})));
+            }
+            other => panic!("unexpected entry type {other:?}"),
+        }
+        assert_eq!(entry.content, r#"{"cmd":"ls"}"#);
+
+        assert!(
+            mapping
+                .normalize_line(r#"{"type":"usage","message":{"text":"1k tokens"}}"#)
+                .1
+                .is_none()
+        );
+        assert!(
+            mapping
+                .normalize_line(r#"{"type":"unknown","message":{"text":"?"}}"#)
+                .1
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_non_json_lines_are_system_messages() {
+        let (session_id, entry) = mapping().normalize_line("Starting agent...");
+        assert!(session_id.is_none());
+        let entry = entry.unwrap();
+        assert!(matches!(
+            entry.entry_type,
+            NormalizedEntryType::SystemMessage
+        ));
+        assert_eq!(entry.content, "Starting agent...");
+        assert!(matches!(mapping().normalize_line("  "), (None, None)));
+    }
+}
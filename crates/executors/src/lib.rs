@@ -6,4 +6,7 @@ pub mod executors;
 pub mod logs;
 pub mod mcp_config;
 pub mod profile;
+pub mod remote;
+pub mod sandbox;
+pub mod spool;
 pub mod stdout_dup;
@@ -0,0 +1,373 @@
+//! Remote execution of workspaces over SSH.
+//!
+//! Workspaces stay checked out locally, where diffs, commits and merges happen. Before a
+//! process starts, the workspace is mirrored with rsync to a directory on the remote host,
+//! where every repo gets a git repository with the synced state committed, so agents can
+//! use git as usual. The process then runs on the remote host through `ssh`, streaming its
+//! stdio back, while the remote directory is mirrored back into the local workspace every
+//! few seconds and once more when the process exits.
+//!
+//! Mirroring back never deletes local files, so nothing written locally in the meantime is
+//! lost. Only when the process has exited are the tracked files it deleted, compared to the
+//! synced state, deleted locally as well. Commits made on the remote host are not mirrored,
+//! their changes are committed locally like any other. Local writes to the workspace should
+//! wait for the process to finish, as the next mirroring overwrites changed files.
+//!
+//! Files ignored by `.gitignore` are not mirrored back, and are never deleted on either side,
+//! so dependencies and build artifacts stay on the host that produced them. Program names are
+//! looked up on the remote `PATH` of a login shell.
+
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    process::Stdio,
+};
+
+use thiserror::Error;
+use tokio::process::Command;
+use uuid::Uuid;
+use workspace_utils::shell::resolve_executable_path;
+
+/// Workspace directory on the remote host when `VK_SSH_WORKSPACE_DIR` is not set, relative
+/// to the home directory of the remote user
+const DEFAULT_REMOTE_WORKSPACE_DIR: &str = "vibe-kanban/worktrees";
+
+/// Seconds between mirrorings of the remote workspace while a process runs
+const MIRROR_INTERVAL_SECS: u64 = 3;
+
+/// Env var listing the variables a remote process gets, set by `ExecutionEnv`
+pub const REMOTE_ENV_KEYS_VAR: &str = "VK_REMOTE_ENV";
+
+/// Tag on the commit of the synced state in each remote repo, which deletions are found by
+const SYNC_TAG: &str = "vibe-kanban-sync";
+
+#[derive(Debug, Error)]
+pub enum RemoteError {
+    #[error("Remote execution requires `{0}` to be installed")]
+    ProgramNotFound(&'static str),
+    #[error("`{command}` failed on {destination}: {stderr}")]
+    CommandFailed {
+        command: &'static str,
+        destination: String,
+        stderr: String,
+    },
+    #[error(
+        "Project '{0}' runs agents in a sandbox, which remote hosts don't provide. Disable the sandbox or unset VK_SSH_HOST."
+    )]
+    SandboxedProject(String),
+    #[error("Io error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Remote Linux host that workspaces run on, reached with the local `ssh` configuration
+#[derive(Debug, Clone)]
+pub struct SshRemote {
+    /// `host` or `user@host`; aliases from `~/.ssh/config` work too
+    pub destination: String,
+    pub port: Option<u16>,
+    pub identity_file: Option<PathBuf>,
+    /// Known hosts file to check the host key against instead of the user's
+    pub known_hosts_file: Option<PathBuf>,
+    /// Directory the workspaces are mirrored into, relative to the remote home directory
+    /// unless absolute
+    pub workspace_dir: String,
+}
+
+impl SshRemote {
+    /// Remote host configured with `VK_SSH_HOST` and optionally `VK_SSH_PORT`,
+    /// `VK_SSH_IDENTITY_FILE`, `VK_SSH_KNOWN_HOSTS_FILE` and `VK_SSH_WORKSPACE_DIR`
+    pub fn from_env() -> Option<Self> {
+        let destination = std::env::var("VK_SSH_HOST").ok()?.trim().to_string();
+        if destination.is_empty() {
+            return None;
+        }
+        let port = std::env::var("VK_SSH_PORT")
+            .ok()
+            .and_then(|port| port.trim().parse().ok());
+        let identity_file = std::env::var_os("VK_SSH_IDENTITY_FILE").map(PathBuf::from);
+        let known_hosts_file = std::env::var_os("VK_SSH_KNOWN_HOSTS_FILE").map(PathBuf::from);
+        let workspace_dir = std::env::var("VK_SSH_WORKSPACE_DIR")
+            .ok()
+            .map(|dir| dir.trim().trim_end_matches('/').to_string())
+            .filter(|dir| !dir.is_empty())
+            .unwrap_or_else(|| DEFAULT_REMOTE_WORKSPACE_DIR.to_string());
+
+        Some(Self {
+            destination,
+            port,
+            identity_file,
+            known_hosts_file,
+            workspace_dir,
+        })
+    }
+
+    /// `ssh` and its options, without the destination
+    fn ssh_command(&self) -> Vec<String> {
+        let mut command: Vec<String> = [
+            "ssh",
+            "-T",
+            "-o",
+            "BatchMode=yes",
+            "-o",
+            "ServerAliveInterval=15",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+        if let Some(port) = self.port {
+            command.push("-p".to_string());
+            command.push(port.to_string());
+        }
+        if let Some(identity_file) = &self.identity_file {
+            command.push("-i".to_string());
+            command.push(identity_file.to_string_lossy().into_owned());
+        }
+        if let Some(known_hosts_file) = &self.known_hosts_file {
+            command.push("-o".to_string());
+            command.push(format!(
+                "UserKnownHostsFile={}",
+                known_hosts_file.to_string_lossy()
+            ));
+        }
+        command
+    }
+
+    /// Remote directory mirroring the local workspace directory `local_dir`
+    pub fn remote_dir(&self, local_dir: &Path) -> String {
+        let name = local_dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        format!("{}/{}", self.workspace_dir, name)
+    }
+
+    /// Run `script` with the remote user's shell
+    pub async fn run(&self, script: &str) -> Result<(), RemoteError> {
+        let ssh = resolve_executable_path("ssh")
+            .await
+            .ok_or(RemoteError::ProgramNotFound("ssh"))?;
+        let output = Command::new(ssh)
+            .args(&self.ssh_command()[1..])
+            .arg(&self.destination)
+            .arg(script)
+            .stdin(Stdio::null())
+            .output()
+            .await?;
+        self.check("ssh", output)
+    }
+
+    async fn rsync(&self, args: &[&str], from: &str, to: &str) -> Result<(), RemoteError> {
+        let rsync = resolve_executable_path("rsync")
+            .await
+            .ok_or(RemoteError::ProgramNotFound("rsync"))?;
+        let output = Command::new(rsync)
+            .arg("-a")
+            .args(args)
+            .arg("-e")
+            .arg(join_quoted(&self.ssh_command()))
+            .arg(from)
+            .arg(to)
+            .stdin(Stdio::null())
+            .output()
+            .await?;
+        self.check("rsync", output)
+    }
+
+    fn check(
+        &self,
+        command: &'static str,
+        output: std::process::Output,
+    ) -> Result<(), RemoteError> {
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(RemoteError::CommandFailed {
+                command,
+                destination: self.destination.clone(),
+                stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            })
+        }
+    }
+
+    /// Mirror the workspace in `local_dir` to the remote host and commit its state in the
+    /// remote repo of each of `repo_dirs`, so the next process starts from what is checked
+    /// out locally
+    pub async fn push(
+        &self,
+        local_dir: &Path,
+        repo_dirs: &[String],
+    ) -> Result<RemoteWorkspace, RemoteError> {
+        let local_dir = tokio::fs::canonicalize(local_dir).await?;
+        let remote_dir = self.remote_dir(&local_dir);
+        self.run(&format!("mkdir -p {}", quote(&remote_dir)))
+            .await?;
+
+        let from = format!("{}/", local_dir.to_string_lossy());
+        let to = format!("{}:{}/", self.destination, remote_dir);
+        // Tracked and unignored files are mirrored exactly, remote dependencies and build
+        // artifacts are left alone
+        self.rsync(
+            &["--delete", "--exclude=.git", "--filter=:- .gitignore"],
+            &from,
+            &to,
+        )
+        .await?;
+        // Ignored files that only exist locally, e.g. copied project files such as `.env`
+        self.rsync(&["--exclude=.git"], &from, &to).await?;
+
+        let commits: Vec<String> = repo_dirs
+            .iter()
+            .map(|repo| {
+                format!(
+                    "(cd {} && {{ [ -d .git ] || git init -q; }} && git add -A && git -c user.name='Vibe Kanban' -c user.email=noreply@vibekanban.com commit -q --allow-empty --no-verify -m 'Sync from Vibe Kanban' && git tag -f {SYNC_TAG} >/dev/null)",
+                    quote(&format!("{remote_dir}/{repo}"))
+                )
+            })
+            .collect();
+        if !commits.is_empty() {
+            self.run(&commits.join(" && ")).await?;
+        }
+
+        Ok(RemoteWorkspace {
+            remote: self.clone(),
+            local_dir,
+            remote_dir,
+            repo_dirs: repo_dirs.to_vec(),
+        })
+    }
+
+    /// Delete the mirror of the workspace in `local_dir` from the remote host
+    pub async fn remove(&self, local_dir: &Path) -> Result<(), RemoteError> {
+        let remote_dir = self.remote_dir(local_dir);
+        self.run(&format!("rm -rf {}", quote(&remote_dir))).await
+    }
+}
+
+/// A workspace mirrored to the remote host, ready to run processes in
+#[derive(Debug, Clone)]
+pub struct RemoteWorkspace {
+    remote: SshRemote,
+    local_dir: PathBuf,
+    remote_dir: String,
+    repo_dirs: Vec<String>,
+}
+
+impl RemoteWorkspace {
+    /// Command running `program` on the remote host. Arguments, the working directory and the
+    /// variables set through `ExecutionEnv` are carried over, the working directory mapped
+    /// from the local workspace to its remote mirror. Stopping the command stops the remote
+    /// process as well.
+    pub fn command(&self, program: impl AsRef<OsStr>) -> Command {
+        // Programs are resolved locally, the remote host has its own paths
+        let program = Path::new(program.as_ref())
+            .file_name()
+            .unwrap_or(program.as_ref())
+            .to_string_lossy()
+            .into_owned();
+        let pid_file = format!("/tmp/vibe-kanban-{}.pid", Uuid::new_v4());
+
+        let mut command = Command::new("bash");
+        command
+            .arg("-c")
+            .arg(self.launcher_script(&pid_file))
+            .arg("vibe-kanban-remote")
+            .arg(program);
+        command
+    }
+
+    fn launcher_script(&self, pid_file: &str) -> String {
+        let ssh = join_quoted(&self.remote.ssh_command());
+        let destination = quote(&self.remote.destination);
+        let local_dir = quote(&self.local_dir.to_string_lossy());
+        let remote_dir = quote(&self.remote_dir);
+        let rsync_shell = quote(&ssh);
+        let pid_file = quote(pid_file);
+        let repo_dirs = join_quoted(&self.repo_dirs);
+
+        format!(
+            r#"quote() {{ local s=${{1//\'/\'\\\'\'}}; printf "'%s'" "$s"; }}
+here=$(pwd -P)
+case $here in
+  {local_dir}|{local_dir}/*) dir={remote_dir}${{here#{local_dir}}} ;;
+  *) dir={remote_dir} ;;
+esac
+inner="echo \$\$ > {pid_file} && cd $(quote "$dir") && exec env"
+for key in ${{{REMOTE_ENV_KEYS_VAR}:-}}; do inner+=" $(quote "$key=${{!key}}")"; done
+for arg in "$@"; do inner+=" $(quote "$arg")"; done
+pull() {{ rsync -a --exclude=.git --filter=':- .gitignore' -e {rsync_shell} {destination}:{remote_dir}/ {local_dir}/; }}
+pull_deletions() {{
+  for repo in {repo_dirs}; do
+    {ssh} {destination} "git -C $(quote {remote_dir}/"$repo") diff -z --name-only --no-renames --diff-filter=D {SYNC_TAG} --" </dev/null 2>/dev/null |
+      while IFS= read -r -d '' path; do rm -f -- {local_dir}/"$repo/$path"; done
+  done
+}}
+(while sleep {MIRROR_INTERVAL_SECS}; do pull; done) </dev/null >/dev/null 2>&1 &
+mirror=$!
+trap 'stopped=1' INT TERM
+{ssh} {destination} "exec bash -lc $(quote "$inner")"
+status=$?
+kill $mirror 2>/dev/null
+if [ -n "${{stopped:-}}" ]; then
+  {ssh} {destination} "kill -TERM -- -\$(cat {pid_file}) 2>/dev/null" </dev/null >/dev/null 2>&1
+fi
+pull </dev/null >/dev/null 2>&1
+pull_deletions >/dev/null 2>&1
+{ssh} {destination} "rm -f {pid_file}" </dev/null >/dev/null 2>&1
+exit $status
+"#
+        )
+    }
+}
+
+fn quote(value: &str) -> String {
+    shlex::try_quote(value)
+        .map(|quoted| quoted.into_owned())
+        // Only NUL bytes cannot be quoted, and they cannot be in arguments either
+        .unwrap_or_else(|_| format!("'{}'", value.replace('\0', "")))
+}
+
+fn join_quoted(args: &[String]) -> String {
+    args.iter()
+        .map(|arg| quote(arg))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remote() -> SshRemote {
+        SshRemote {
+            destination: "dev@build-box".to_string(),
+            port: Some(2222),
+            identity_file: Some(PathBuf::from("/home/me/.ssh/id_build")),
+            known_hosts_file: None,
+            workspace_dir: "vibe-kanban/worktrees".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_remote_dir_and_ssh_options() {
+        let remote = remote();
+        assert_eq!(
+            remote.remote_dir(Path::new("/tmp/vibe-kanban/worktrees/1a2b-fix-login")),
+            "vibe-kanban/worktrees/1a2b-fix-login"
+        );
+        let ssh = remote.ssh_command();
+        assert!(ssh.windows(2).any(|pair| pair == ["-p", "2222"]));
+        assert!(
+            ssh.windows(2)
+                .any(|pair| pair == ["-i", "/home/me/.ssh/id_build"])
+        );
+    }
+
+    #[test]
+    fn test_quoted_args_round_trip() {
+        let args: Vec<String> = ["plain", "with space", "it's", "$(not run)", "\"q\""]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(shlex::split(&join_quoted(&args)), Some(args));
+    }
+}
@@ -0,0 +1,763 @@
+//! Optional filesystem and network sandbox for executor processes (Linux only).
+//!
+//! Processes are started through [bubblewrap](https://github.com/containers/bubblewrap) in
+//! fresh user, mount, PID, IPC and UTS namespaces. Inside the sandbox the workspace and the
+//! parts of its repositories' git directories that commits write to are the only writable
+//! project paths, system directories and toolchains are mounted read-only, the home directory
+//! is replaced by an empty tmpfs apart from the toolchains and per-workspace copies of the
+//! agent state directories, and network access is limited by [`SandboxNetwork`].
+
+mod proxy;
+
+use std::{
+    ffi::{OsStr, OsString},
+    io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::process::Command;
+use ts_rs::TS;
+use uuid::Uuid;
+use workspace_utils::shell::resolve_executable_path;
+
+pub use self::proxy::NetworkProxy;
+
+/// System directories mounted read-only when present
+const SYSTEM_PATHS: &[&str] = &[
+    "/usr", "/bin", "/sbin", "/lib", "/lib32", "/lib64", "/etc", "/opt", "/nix", "/snap",
+];
+
+/// Toolchain directories under `$HOME`, mounted read-only when present. Directories of
+/// `PATH` entries under `$HOME` are added to these automatically.
+const HOME_TOOLCHAIN_PATHS: &[&str] = &[
+    ".cargo",
+    ".rustup",
+    ".nvm",
+    ".volta",
+    ".fnm",
+    ".bun",
+    ".deno",
+    ".pyenv",
+    ".rbenv",
+    ".asdf",
+    ".sdkman",
+    ".local/bin",
+    ".local/share/mise",
+    "go",
+    ".gitconfig",
+    ".config/git",
+];
+
+/// Configuration and session directories of the supported coding agents. Each workspace gets
+/// its own copy, made when the workspace is first sandboxed, so agents can authenticate and
+/// resume sessions without persisting settings or hooks into other workspaces' runs.
+const HOME_AGENT_STATE_PATHS: &[&str] = &[
+    ".claude",
+    ".claude.json",
+    ".codex",
+    ".gemini",
+    ".qwen",
+    ".cursor",
+    ".config/cursor",
+    ".config/opencode",
+    ".local/share/opencode",
+    ".local/state/opencode",
+    ".config/amp",
+    ".local/share/amp",
+    ".factory",
+    ".copilot",
+    ".config/github-copilot",
+];
+
+/// Session transcripts of other runs, left out of the agent state copies
+const HOME_AGENT_STATE_SKIP_PATHS: &[&str] = &[".claude/projects", ".codex/sessions"];
+
+/// Package manager caches, replaced by empty per-workspace directories
+const HOME_CACHE_PATHS: &[&str] = &[".npm", ".cache"];
+
+/// Address the network proxy is reachable at inside the sandbox in `allowlist` mode
+const PROXY_LISTEN_PORT: u16 = 3128;
+
+#[derive(Debug, Error)]
+pub enum SandboxError {
+    #[error("Sandboxed execution is only supported on Linux")]
+    UnsupportedPlatform,
+    #[error("Sandboxed execution requires `bwrap` (bubblewrap) to be installed")]
+    BubblewrapNotFound,
+    #[error("Sandboxed execution with allowlisted network access requires `socat` to be installed")]
+    SocatNotFound,
+    #[error(
+        "Allowlisted network access needs at least one allowed host, such as the API host of the coding agent"
+    )]
+    EmptyAllowlist,
+    #[error("Home directory not found")]
+    HomeNotFound,
+    #[error("Failed to start sandbox network proxy: {0}")]
+    Proxy(std::io::Error),
+    #[error("Failed to prepare sandbox: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// Network access of sandboxed processes
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum SandboxNetwork {
+    /// Share the host network
+    Full,
+    /// Only connections to `allowed_hosts`, through a filtering HTTP(S) proxy. The sandbox has
+    /// no other network access, so clients that ignore the `HTTP(S)_PROXY` variables can't
+    /// connect at all.
+    #[default]
+    Allowlist,
+    /// No network access at all (a private loopback only)
+    None,
+}
+
+/// Per-project sandbox settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+pub struct SandboxConfig {
+    pub enabled: bool,
+    #[serde(default)]
+    pub network: SandboxNetwork,
+    /// Hosts reachable in `allowlist` mode; `*.example.com` also matches `example.com`
+    #[serde(default)]
+    pub allowed_hosts: Vec<String>,
+    /// Extra host paths mounted read-only, `~/` is expanded to the home directory
+    #[serde(default)]
+    pub read_only_paths: Vec<String>,
+    /// Extra host paths mounted read-write, e.g. `~/.cargo/registry` to let builds fetch crates
+    #[serde(default)]
+    pub read_write_paths: Vec<String>,
+}
+
+impl SandboxConfig {
+    /// An enabled sandbox in `allowlist` mode without allowed hosts couldn't reach the API of
+    /// any coding agent, so it is refused instead
+    pub fn validate(&self) -> Result<(), SandboxError> {
+        if self.enabled
+            && self.network == SandboxNetwork::Allowlist
+            && self.allowed_hosts.iter().all(|host| host.trim().is_empty())
+        {
+            return Err(SandboxError::EmptyAllowlist);
+        }
+        Ok(())
+    }
+}
+
+/// Whether `host` is matched by one of the `allowed` host patterns
+pub fn host_allowed(allowed: &[String], host: &str) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    allowed.iter().any(|pattern| {
+        let pattern = pattern.trim().trim_end_matches('.').to_ascii_lowercase();
+        match pattern.strip_prefix("*.") {
+            Some(domain) => {
+                host == domain
+                    || host
+                        .strip_suffix(domain)
+                        .is_some_and(|sub| sub.ends_with('.'))
+            }
+            None => host == pattern,
+        }
+    })
+}
+
+/// Launcher that runs programs inside a bubblewrap sandbox
+#[derive(Debug, Clone)]
+pub struct Sandbox {
+    bwrap: PathBuf,
+    args: Vec<OsString>,
+    /// `socat` and the proxy socket it bridges to a loopback port in `allowlist` mode
+    proxy_bridge: Option<(PathBuf, PathBuf)>,
+}
+
+impl Sandbox {
+    /// Sandbox for the workspace `workspace_id` in `workspace_dir`, whose repositories are
+    /// checked out at `worktrees`. `proxy_socket` must be the socket of a running
+    /// [`NetworkProxy`] when the network mode is `allowlist`.
+    pub async fn new(
+        config: &SandboxConfig,
+        workspace_id: Uuid,
+        workspace_dir: &Path,
+        worktrees: &[PathBuf],
+        proxy_socket: Option<&Path>,
+    ) -> Result<Self, SandboxError> {
+        if !cfg!(target_os = "linux") {
+            return Err(SandboxError::UnsupportedPlatform);
+        }
+        config.validate()?;
+        let bwrap = resolve_executable_path("bwrap")
+            .await
+            .ok_or(SandboxError::BubblewrapNotFound)?;
+        let proxy_bridge = match (config.network, proxy_socket) {
+            (SandboxNetwork::Allowlist, Some(socket)) => {
+                let socat = resolve_executable_path("socat")
+                    .await
+                    .ok_or(SandboxError::SocatNotFound)?;
+                Some((socat, socket.to_path_buf()))
+            }
+            _ => None,
+        };
+        let home = dirs::home_dir().ok_or(SandboxError::HomeNotFound)?;
+        let path_env = std::env::var_os("PATH").unwrap_or_default();
+
+        let worktrees = worktrees
+            .iter()
+            .map(|worktree| WorktreeGit::resolve(worktree))
+            .collect::<io::Result<Vec<_>>>()?;
+        let state_dir = state_dir(workspace_id);
+        {
+            let home = home.clone();
+            let state_dir = state_dir.clone();
+            tokio::task::spawn_blocking(move || prepare_state_dir(&home, &state_dir))
+                .await
+                .map_err(io::Error::other)??;
+        }
+
+        let args = bwrap_args(
+            config,
+            &home,
+            &path_env,
+            workspace_dir,
+            &worktrees,
+            &state_dir,
+            proxy_bridge.as_ref().map(|(_, socket)| socket.as_path()),
+        );
+        Ok(Self {
+            bwrap,
+            args,
+            proxy_bridge,
+        })
+    }
+
+    /// Command running `program` inside the sandbox. Arguments added to the returned command
+    /// are passed to `program`.
+    pub fn command(&self, program: impl AsRef<OsStr>) -> Command {
+        let mut command = Command::new(&self.bwrap);
+        command.args(&self.args).arg("--");
+        if let Some((socat, socket)) = &self.proxy_bridge {
+            // The sandbox has its own network namespace, where the proxy is only reachable
+            // through its socket. Bridge it to the loopback port the proxy variables point at
+            // and wait for the bridge to listen before starting the program.
+            let script = format!(
+                "\"$1\" TCP-LISTEN:{PROXY_LISTEN_PORT},bind=127.0.0.1,fork,reuseaddr \"UNIX-CONNECT:$2\" & \
+                 i=0; while [ $i -lt 200 ] && ! grep -q ' 0100007F:{PROXY_LISTEN_PORT:04X} 00000000:0000 0A ' /proc/net/tcp; \
+                 do sleep 0.01; i=$((i + 1)); done; shift 2; exec \"$@\""
+            );
+            command
+                .args(["/bin/sh", "-c", &script, "sh"])
+                .arg(socat)
+                .arg(socket);
+        }
+        command.arg(program);
+        command
+    }
+}
+
+/// Directory holding the workspace's copies of the agent state directories
+pub fn state_dir(workspace_id: Uuid) -> PathBuf {
+    workspace_utils::path::get_vibe_kanban_temp_dir()
+        .join("sandbox-home")
+        .join(workspace_id.to_string())
+}
+
+/// Git directories of a worktree
+#[derive(Debug, Clone)]
+struct WorktreeGit {
+    /// The worktree's `.git` file, pointing at `admin_dir`
+    dot_git: PathBuf,
+    /// `<repo>/.git/worktrees/<name>`, holding the worktree's `HEAD` and index
+    admin_dir: PathBuf,
+    /// The main repository's git directory, holding its config, hooks, refs and objects
+    common_dir: PathBuf,
+}
+
+impl WorktreeGit {
+    fn resolve(worktree: &Path) -> io::Result<Self> {
+        let dot_git = worktree.join(".git");
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+
+        let contents = std::fs::read_to_string(&dot_git)?;
+        let admin_dir = contents
+            .trim()
+            .strip_prefix("gitdir:")
+            .map(|path| worktree.join(path.trim()))
+            .ok_or_else(|| invalid(format!("{} is not a worktree", worktree.display())))?;
+        let admin_dir = std::fs::canonicalize(admin_dir)?;
+        let common_dir = match std::fs::read_to_string(admin_dir.join("commondir")) {
+            Ok(common_dir) => admin_dir.join(common_dir.trim()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => admin_dir.join("../.."),
+            Err(e) => return Err(e),
+        };
+        let common_dir = std::fs::canonicalize(common_dir)?;
+        if !admin_dir.starts_with(common_dir.join("worktrees")) {
+            return Err(invalid(format!(
+                "{} does not point into the worktrees of {}",
+                dot_git.display(),
+                common_dir.display()
+            )));
+        }
+
+        Ok(Self {
+            dot_git,
+            admin_dir,
+            common_dir,
+        })
+    }
+}
+
+/// Fill the workspace's state directory the first time the workspace is sandboxed: copies of
+/// the agent state directories and empty package manager caches
+fn prepare_state_dir(home: &Path, state_dir: &Path) -> io::Result<()> {
+    if state_dir.exists() {
+        return Ok(());
+    }
+
+    // Copied next to the final location first, as parallel scripts may start together
+    let staging = state_dir.with_file_name(format!(".{}", Uuid::new_v4()));
+    let result = fill_state_dir(home, &staging).and_then(|()| std::fs::rename(&staging, state_dir));
+    if result.is_err() {
+        let _ = std::fs::remove_dir_all(&staging);
+        // Another process of the workspace may have prepared it first
+        if state_dir.exists() {
+            return Ok(());
+        }
+    }
+    result
+}
+
+fn fill_state_dir(home: &Path, dir: &Path) -> io::Result<()> {
+    for path in HOME_AGENT_STATE_PATHS {
+        copy_state(&home.join(path), &dir.join(path), Path::new(path))?;
+    }
+    for path in HOME_CACHE_PATHS {
+        std::fs::create_dir_all(dir.join(path))?;
+    }
+    Ok(())
+}
+
+/// Copy a file, symlink or directory tree of agent state, leaving out
+/// [`HOME_AGENT_STATE_SKIP_PATHS`] and anything that is not a regular file
+fn copy_state(src: &Path, dest: &Path, relative: &Path) -> io::Result<()> {
+    if HOME_AGENT_STATE_SKIP_PATHS
+        .iter()
+        .any(|skip| relative == Path::new(skip))
+    {
+        return Ok(());
+    }
+    let metadata = match std::fs::symlink_metadata(src) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    if metadata.is_symlink() {
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(std::fs::read_link(src)?, dest)?;
+        return Ok(());
+    }
+    if metadata.is_dir() {
+        std::fs::create_dir_all(dest)?;
+        for entry in std::fs::read_dir(src)? {
+            let entry = entry?;
+            copy_state(
+                &entry.path(),
+                &dest.join(entry.file_name()),
+                &relative.join(entry.file_name()),
+            )?;
+        }
+        return Ok(());
+    }
+    if metadata.is_file() {
+        std::fs::copy(src, dest)?;
+    }
+    Ok(())
+}
+
+fn expand_home(path: &str, home: &Path) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => home.join(rest),
+        None => PathBuf::from(path),
+    }
+}
+
+fn bind(args: &mut Vec<OsString>, flag: &str, path: &Path) {
+    bind_to(args, flag, path, path);
+}
+
+fn bind_to(args: &mut Vec<OsString>, flag: &str, src: &Path, dest: &Path) {
+    args.push(flag.into());
+    args.push(src.into());
+    args.push(dest.into());
+}
+
+fn bwrap_args(
+    config: &SandboxConfig,
+    home: &Path,
+    path_env: &OsStr,
+    workspace_dir: &Path,
+    worktrees: &[WorktreeGit],
+    state_dir: &Path,
+    proxy_socket: Option<&Path>,
+) -> Vec<OsString> {
+    let mut args: Vec<OsString> = [
+        "--die-with-parent",
+        "--unshare-user",
+        "--unshare-pid",
+        "--unshare-ipc",
+        "--unshare-uts",
+        "--unshare-cgroup-try",
+        "--proc",
+        "/proc",
+        "--dev",
+        "/dev",
+        "--tmpfs",
+        "/tmp",
+    ]
+    .into_iter()
+    .map(OsString::from)
+    .collect();
+
+    // Only the full mode shares the host network; allowlisted hosts are reached through the
+    // proxy socket
+    if config.network != SandboxNetwork::Full {
+        args.push("--unshare-net".into());
+    }
+
+    for path in SYSTEM_PATHS {
+        bind(&mut args, "--ro-bind-try", Path::new(path));
+    }
+    // DNS resolution needs the target of /etc/resolv.conf, often under /run
+    bind(
+        &mut args,
+        "--ro-bind-try",
+        Path::new("/run/systemd/resolve"),
+    );
+
+    // Hide the home directory, then expose only what tools and agents need
+    args.push("--tmpfs".into());
+    args.push(home.into());
+
+    let mut toolchain_paths: Vec<PathBuf> = HOME_TOOLCHAIN_PATHS
+        .iter()
+        .map(|path| home.join(path))
+        .collect();
+    for entry in std::env::split_paths(path_env) {
+        if let Ok(relative) = entry.strip_prefix(home)
+            && let Some(top) = relative.components().next()
+        {
+            let top = home.join(top);
+            if !toolchain_paths.contains(&top) {
+                toolchain_paths.push(top);
+            }
+        }
+    }
+    toolchain_paths.extend(
+        config
+            .read_only_paths
+            .iter()
+            .map(|path| expand_home(path, home)),
+    );
+    for path in &toolchain_paths {
+        bind(&mut args, "--ro-bind-try", path);
+    }
+
+    for path in HOME_AGENT_STATE_PATHS.iter().chain(HOME_CACHE_PATHS) {
+        bind_to(
+            &mut args,
+            "--bind-try",
+            &state_dir.join(path),
+            &home.join(path),
+        );
+    }
+    for path in &config.read_write_paths {
+        bind(&mut args, "--bind-try", &expand_home(path, home));
+    }
+
+    bind(&mut args, "--bind", workspace_dir);
+    // Worktrees keep their git metadata in the main repository's git directory. Commits only
+    // need its objects, refs and reflogs and the worktree's own directory to be writable; its
+    // config, hooks and the worktree's `.git` file stay read-only, as git run by the server
+    // outside the sandbox would otherwise pick up hooks or config written by the agent.
+    for worktree in worktrees {
+        bind(&mut args, "--ro-bind", &worktree.common_dir);
+        for dir in ["objects", "refs", "logs"] {
+            bind(&mut args, "--bind-try", &worktree.common_dir.join(dir));
+        }
+        bind(&mut args, "--bind", &worktree.admin_dir);
+        for file in ["commondir", "gitdir"] {
+            bind(&mut args, "--ro-bind-try", &worktree.admin_dir.join(file));
+        }
+        bind(&mut args, "--ro-bind", &worktree.dot_git);
+    }
+
+    if config.network == SandboxNetwork::Allowlist {
+        // Without a proxy nothing is reachable
+        if let Some(socket) = proxy_socket {
+            bind(&mut args, "--bind", socket);
+        }
+        let proxy_url = format!("http://127.0.0.1:{PROXY_LISTEN_PORT}");
+        for var in [
+            "HTTP_PROXY",
+            "HTTPS_PROXY",
+            "ALL_PROXY",
+            "http_proxy",
+            "https_proxy",
+            "all_proxy",
+        ] {
+            args.push("--setenv".into());
+            args.push(var.into());
+            args.push(proxy_url.clone().into());
+        }
+        for var in ["NO_PROXY", "no_proxy"] {
+            args.push("--unsetenv".into());
+            args.push(var.into());
+        }
+    }
+
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args_to_strings(args: &[OsString]) -> Vec<String> {
+        args.iter()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    fn position(args: &[String], window: &[&str]) -> Option<usize> {
+        args.windows(window.len()).position(|w| w == window)
+    }
+
+    #[test]
+    fn test_host_allowed() {
+        let allowed = vec!["api.anthropic.com".to_string(), "*.github.com".to_string()];
+        assert!(host_allowed(&allowed, "api.anthropic.com"));
+        assert!(host_allowed(&allowed, "API.Anthropic.com."));
+        assert!(host_allowed(&allowed, "github.com"));
+        assert!(host_allowed(&allowed, "codeload.github.com"));
+        assert!(!host_allowed(&allowed, "evilgithub.com"));
+        assert!(!host_allowed(&allowed, "anthropic.com"));
+        assert!(!host_allowed(&[], "api.anthropic.com"));
+    }
+
+    #[test]
+    fn test_allowlist_needs_hosts() {
+        let mut config = SandboxConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        assert!(matches!(
+            config.validate(),
+            Err(SandboxError::EmptyAllowlist)
+        ));
+
+        config.allowed_hosts = vec!["api.anthropic.com".to_string()];
+        assert!(config.validate().is_ok());
+
+        config.allowed_hosts.clear();
+        config.network = SandboxNetwork::None;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_bwrap_args_hide_home_and_bind_workspace() {
+        let config = SandboxConfig {
+            enabled: true,
+            network: SandboxNetwork::Allowlist,
+            allowed_hosts: vec!["api.anthropic.com".to_string()],
+            read_only_paths: vec!["~/tools".to_string()],
+            read_write_paths: vec!["/srv/cache".to_string()],
+        };
+        let home = Path::new("/home/dev");
+        let path_env = OsString::from("/home/dev/.nvm/versions/node/v22/bin:/usr/bin");
+        let worktree = WorktreeGit {
+            dot_git: PathBuf::from("/var/tmp/vibe-kanban/worktrees/ws/app/.git"),
+            admin_dir: PathBuf::from("/home/dev/src/app/.git/worktrees/app"),
+            common_dir: PathBuf::from("/home/dev/src/app/.git"),
+        };
+        let args = args_to_strings(&bwrap_args(
+            &config,
+            home,
+            &path_env,
+            Path::new("/var/tmp/vibe-kanban/worktrees/ws"),
+            &[worktree],
+            Path::new("/var/tmp/vibe-kanban/sandbox-home/ws"),
+            Some(Path::new("/var/tmp/vibe-kanban/sandbox-proxy/ws.sock")),
+        ));
+
+        let tmpfs_home = position(&args, &["--tmpfs", "/home/dev"]).unwrap();
+        let nvm = position(
+            &args,
+            &["--ro-bind-try", "/home/dev/.nvm", "/home/dev/.nvm"],
+        )
+        .unwrap();
+        let tools = position(
+            &args,
+            &["--ro-bind-try", "/home/dev/tools", "/home/dev/tools"],
+        )
+        .unwrap();
+        let claude = position(
+            &args,
+            &[
+                "--bind-try",
+                "/var/tmp/vibe-kanban/sandbox-home/ws/.claude",
+                "/home/dev/.claude",
+            ],
+        )
+        .unwrap();
+        assert!(tmpfs_home < nvm && tmpfs_home < tools && tmpfs_home < claude);
+        assert!(position(&args, &["--bind-try", "/srv/cache", "/srv/cache"]).is_some());
+        assert!(
+            position(
+                &args,
+                &[
+                    "--bind",
+                    "/var/tmp/vibe-kanban/worktrees/ws",
+                    "/var/tmp/vibe-kanban/worktrees/ws"
+                ]
+            )
+            .is_some()
+        );
+        assert!(position(&args, &["--setenv", "HTTPS_PROXY", "http://127.0.0.1:3128"]).is_some());
+        assert!(
+            position(
+                &args,
+                &[
+                    "--bind",
+                    "/var/tmp/vibe-kanban/sandbox-proxy/ws.sock",
+                    "/var/tmp/vibe-kanban/sandbox-proxy/ws.sock"
+                ]
+            )
+            .is_some()
+        );
+        assert!(args.contains(&"--unshare-net".to_string()));
+    }
+
+    #[test]
+    fn test_bwrap_args_keep_git_config_and_hooks_read_only() {
+        let worktree = WorktreeGit {
+            dot_git: PathBuf::from("/tmp/ws/app/.git"),
+            admin_dir: PathBuf::from("/src/app/.git/worktrees/app"),
+            common_dir: PathBuf::from("/src/app/.git"),
+        };
+        let args = args_to_strings(&bwrap_args(
+            &SandboxConfig::default(),
+            Path::new("/home/dev"),
+            OsStr::new(""),
+            Path::new("/tmp/ws"),
+            &[worktree],
+            Path::new("/tmp/state"),
+            None,
+        ));
+
+        let workspace = position(&args, &["--bind", "/tmp/ws", "/tmp/ws"]).unwrap();
+        let common = position(&args, &["--ro-bind", "/src/app/.git", "/src/app/.git"]).unwrap();
+        let objects = position(
+            &args,
+            &[
+                "--bind-try",
+                "/src/app/.git/objects",
+                "/src/app/.git/objects",
+            ],
+        )
+        .unwrap();
+        let admin = position(
+            &args,
+            &[
+                "--bind",
+                "/src/app/.git/worktrees/app",
+                "/src/app/.git/worktrees/app",
+            ],
+        )
+        .unwrap();
+        let dot_git = position(
+            &args,
+            &["--ro-bind", "/tmp/ws/app/.git", "/tmp/ws/app/.git"],
+        )
+        .unwrap();
+        // Later mounts are stacked on top of earlier ones
+        assert!(workspace < dot_git && common < objects && common < admin);
+        assert!(
+            position(
+                &args,
+                &["--bind-try", "/src/app/.git/refs", "/src/app/.git/refs"]
+            )
+            .is_some()
+        );
+        assert!(
+            !args
+                .iter()
+                .any(|arg| arg.ends_with("/hooks") || arg.ends_with("/config"))
+        );
+    }
+
+    #[test]
+    fn test_worktree_git_resolve() {
+        let root = std::env::temp_dir().join(format!("vk-sandbox-test-{}", Uuid::new_v4()));
+        let admin_dir = root.join("repo/.git/worktrees/app");
+        let worktree = root.join("ws/app");
+        std::fs::create_dir_all(&admin_dir).unwrap();
+        std::fs::create_dir_all(&worktree).unwrap();
+        std::fs::write(admin_dir.join("commondir"), "../..\n").unwrap();
+        std::fs::write(
+            worktree.join(".git"),
+            format!("gitdir: {}\n", admin_dir.display()),
+        )
+        .unwrap();
+
+        let git = WorktreeGit::resolve(&worktree).unwrap();
+        assert_eq!(
+            git.common_dir,
+            root.join("repo/.git").canonicalize().unwrap()
+        );
+        assert_eq!(git.admin_dir, admin_dir.canonicalize().unwrap());
+
+        // A `.git` file pointing anywhere else is refused
+        std::fs::write(
+            worktree.join(".git"),
+            format!("gitdir: {}\n", root.join("repo/.git").display()),
+        )
+        .unwrap();
+        assert!(WorktreeGit::resolve(&worktree).is_err());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_bwrap_args_network_modes() {
+        let mut config = SandboxConfig {
+            enabled: true,
+            network: SandboxNetwork::None,
+            ..Default::default()
+        };
+        let build = |config: &SandboxConfig| {
+            args_to_strings(&bwrap_args(
+                config,
+                Path::new("/home/dev"),
+                OsStr::new(""),
+                Path::new("/tmp/ws"),
+                &[],
+                Path::new("/tmp/state"),
+                None,
+            ))
+        };
+
+        let args = build(&config);
+        assert!(args.contains(&"--unshare-net".to_string()));
+        assert!(!args.contains(&"HTTPS_PROXY".to_string()));
+
+        config.network = SandboxNetwork::Full;
+        let args = build(&config);
+        assert!(!args.contains(&"--unshare-net".to_string()));
+        assert!(!args.contains(&"HTTPS_PROXY".to_string()));
+    }
+}
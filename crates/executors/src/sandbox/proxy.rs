@@ -0,0 +1,263 @@
+//! Host-filtering HTTP proxy for sandboxes in `allowlist` network mode.
+//!
+//! Supports `CONNECT` tunnels (HTTPS and other TLS traffic) and plain HTTP requests in
+//! absolute form. Connections to hosts outside the allowlist are refused with `403`. The proxy
+//! listens on a unix socket, which is the sandbox's only way out of its network namespace.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader},
+    net::TcpStream,
+    task::JoinHandle,
+};
+
+use super::host_allowed;
+
+/// Upper bound on the size of a request head, to stop misbehaving clients early
+const MAX_HEAD_BYTES: usize = 64 * 1024;
+
+/// Filtering proxy listening on a unix socket. Stops and removes the socket when dropped.
+#[derive(Debug)]
+pub struct NetworkProxy {
+    socket_path: PathBuf,
+    task: JoinHandle<()>,
+}
+
+impl NetworkProxy {
+    pub async fn start(allowed_hosts: Vec<String>) -> io::Result<Self> {
+        let dir = workspace_utils::path::get_vibe_kanban_temp_dir().join("sandbox-proxy");
+        Self::start_in(&dir, allowed_hosts).await
+    }
+
+    #[cfg(not(unix))]
+    async fn start_in(_dir: &Path, _allowed_hosts: Vec<String>) -> io::Result<Self> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "the sandbox network proxy needs unix sockets",
+        ))
+    }
+
+    #[cfg(unix)]
+    async fn start_in(dir: &Path, allowed_hosts: Vec<String>) -> io::Result<Self> {
+        tokio::fs::create_dir_all(dir).await?;
+        let socket_path = dir.join(format!("{}.sock", uuid::Uuid::new_v4().simple()));
+        let listener = tokio::net::UnixListener::bind(&socket_path)?;
+        let allowed_hosts = Arc::new(allowed_hosts);
+
+        let task = tokio::spawn(async move {
+            loop {
+                let (stream, _) = match listener.accept().await {
+                    Ok(conn) => conn,
+                    Err(e) => {
+                        tracing::warn!("Sandbox proxy failed to accept connection: {}", e);
+                        continue;
+                    }
+                };
+                let allowed_hosts = allowed_hosts.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = handle_connection(stream, &allowed_hosts).await {
+                        tracing::debug!("Sandbox proxy connection ended: {}", e);
+                    }
+                });
+            }
+        });
+
+        Ok(Self { socket_path, task })
+    }
+
+    pub fn socket_path(&self) -> &Path {
+        &self.socket_path
+    }
+}
+
+impl Drop for NetworkProxy {
+    fn drop(&mut self) {
+        self.task.abort();
+        let _ = std::fs::remove_file(&self.socket_path);
+    }
+}
+
+async fn handle_connection<S>(stream: S, allowed_hosts: &[String]) -> io::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut client = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    client.read_line(&mut request_line).await?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target), Some(version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return respond(client.get_mut(), "400 Bad Request").await;
+    };
+
+    // Remaining header lines, without the proxy's hop-by-hop headers
+    let mut headers = String::new();
+    let mut head_len = request_line.len();
+    loop {
+        let mut line = String::new();
+        let read = client.read_line(&mut line).await?;
+        head_len += read;
+        if read == 0 || head_len > MAX_HEAD_BYTES {
+            return respond(client.get_mut(), "400 Bad Request").await;
+        }
+        if line == "\r\n" || line == "\n" {
+            break;
+        }
+        let name = line
+            .split(':')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if !matches!(
+            name.as_str(),
+            "connection" | "proxy-connection" | "proxy-authorization" | "keep-alive"
+        ) {
+            headers.push_str(&line);
+        }
+    }
+
+    if method.eq_ignore_ascii_case("CONNECT") {
+        let Some((host, port)) = split_host_port(target, 443) else {
+            return respond(client.get_mut(), "400 Bad Request").await;
+        };
+        if !host_allowed(allowed_hosts, host) {
+            tracing::info!("Sandbox proxy blocked connection to {}", host);
+            return respond(client.get_mut(), "403 Forbidden").await;
+        }
+        let Ok(mut upstream) = TcpStream::connect((host, port)).await else {
+            return respond(client.get_mut(), "502 Bad Gateway").await;
+        };
+        client
+            .get_mut()
+            .write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")
+            .await?;
+        // Forward anything the client pipelined after the CONNECT head
+        let buffered = client.buffer().to_vec();
+        upstream.write_all(&buffered).await?;
+        let mut client = client.into_inner();
+        tokio::io::copy_bidirectional(&mut client, &mut upstream).await?;
+        return Ok(());
+    }
+
+    // Plain HTTP: the target must be an absolute `http://host[:port]/path` URL
+    let Some((authority, path)) = target
+        .strip_prefix("http://")
+        .map(|rest| rest.split_at(rest.find('/').unwrap_or(rest.len())))
+    else {
+        return respond(client.get_mut(), "400 Bad Request").await;
+    };
+    let Some((host, port)) = split_host_port(authority, 80) else {
+        return respond(client.get_mut(), "400 Bad Request").await;
+    };
+    if !host_allowed(allowed_hosts, host) {
+        tracing::info!("Sandbox proxy blocked request to {}", host);
+        return respond(client.get_mut(), "403 Forbidden").await;
+    }
+    let Ok(mut upstream) = TcpStream::connect((host, port)).await else {
+        return respond(client.get_mut(), "502 Bad Gateway").await;
+    };
+
+    // One request per connection, so a kept-alive client connection cannot be reused for
+    // another host
+    let path = if path.is_empty() { "/" } else { path };
+    let head = format!("{method} {path} {version}\r\n{headers}Connection: close\r\n\r\n");
+    upstream.write_all(head.as_bytes()).await?;
+    let buffered = client.buffer().to_vec();
+    upstream.write_all(&buffered).await?;
+
+    let (mut client_read, mut client_write) = tokio::io::split(client.into_inner());
+    let (mut upstream_read, mut upstream_write) = upstream.split();
+    let request_body = async {
+        tokio::io::copy(&mut client_read, &mut upstream_write).await?;
+        upstream_write.shutdown().await
+    };
+    let response = async {
+        tokio::io::copy(&mut upstream_read, &mut client_write).await?;
+        client_write.shutdown().await
+    };
+    // The exchange is over once the response is complete; a client that half-closes after
+    // sending its request still gets the response
+    tokio::pin!(response);
+    tokio::select! {
+        result = &mut response => result,
+        result = request_body => {
+            result?;
+            response.await
+        }
+    }
+}
+
+/// Split `host:port` (or `[v6]:port`), falling back to `default_port`
+fn split_host_port(authority: &str, default_port: u16) -> Option<(&str, u16)> {
+    if let Some(rest) = authority.strip_prefix('[') {
+        let (host, rest) = rest.split_once(']')?;
+        let port = match rest.strip_prefix(':') {
+            Some(port) => port.parse().ok()?,
+            None => default_port,
+        };
+        return Some((host, port));
+    }
+    match authority.rsplit_once(':') {
+        Some((host, port)) => Some((host, port.parse().ok()?)),
+        None => Some((authority, default_port)),
+    }
+    .filter(|(host, _)| !host.is_empty())
+}
+
+async fn respond<S: AsyncWrite + Unpin>(stream: &mut S, status: &str) -> io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{status}\n",
+        status.len() + 1
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::AsyncReadExt;
+
+    use super::*;
+
+    #[test]
+    fn test_split_host_port() {
+        assert_eq!(
+            split_host_port("github.com:443", 80),
+            Some(("github.com", 443))
+        );
+        assert_eq!(split_host_port("github.com", 80), Some(("github.com", 80)));
+        assert_eq!(split_host_port("[::1]:8080", 80), Some(("::1", 8080)));
+        assert_eq!(split_host_port("github.com:https", 80), None);
+        assert_eq!(split_host_port(":443", 80), None);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_proxy_refuses_hosts_outside_allowlist() {
+        let dir = std::env::temp_dir().join(format!("vk-proxy-test-{}", uuid::Uuid::new_v4()));
+        let proxy = NetworkProxy::start_in(&dir, vec!["example.com".to_string()])
+            .await
+            .unwrap();
+        let mut stream = tokio::net::UnixStream::connect(proxy.socket_path())
+            .await
+            .unwrap();
+        stream
+            .write_all(b"CONNECT evil.test:443 HTTP/1.1\r\nHost: evil.test:443\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 403 Forbidden"));
+
+        let socket_path = proxy.socket_path().to_path_buf();
+        drop(proxy);
+        assert!(!socket_path.exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
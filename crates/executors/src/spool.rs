@@ -0,0 +1,195 @@
+//! Spooling of process output to files, so processes outlive the server.
+//!
+//! A spooled process is started by a small launcher that tees its stdout and stderr into
+//! files in the vibe-kanban temp directory while still passing them to the server, and records the exit
+//! code once the process finishes. The launcher ignores the server going away: output keeps
+//! landing in the spool files and the process keeps running in its own process group, so a
+//! restarted server can replay the files and follow the process to its end.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use tokio::{
+    fs::File,
+    io::{AsyncReadExt, AsyncSeekExt},
+    process::Command,
+};
+use uuid::Uuid;
+use workspace_utils::{log_msg::LogMsg, msg_store::MsgStore, path::get_vibe_kanban_temp_dir};
+
+/// Directory of the spools in the vibe-kanban temp directory. Spools stay out of workspaces,
+/// which are mirrored to and from remote hosts.
+const SPOOL_DIR: &str = "spool";
+
+/// Time between reads of the spool files while following a process
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Runs the command in its arguments after the spool file paths. The command runs in a
+/// subshell, so the exit code is recorded even when the launcher itself is killed, and the
+/// tees ignore `SIGPIPE` so a closed server pipe does not take the output down with it.
+const LAUNCHER: &str = r#"out=$1 err=$2 exit_file=$3
+shift 3
+exec 3<&0
+(
+  "$@" > >(trap '' PIPE; exec tee -a "$out") 2> >(trap '' PIPE; exec tee -a "$err" >&2)
+  code=$?
+  echo "$code" > "$exit_file.tmp" && mv -f "$exit_file.tmp" "$exit_file"
+  exit "$code"
+) <&3 3<&- &
+exec 3<&-
+wait $!
+"#;
+
+/// Output files of one execution process
+#[derive(Debug, Clone)]
+pub struct ProcessSpool {
+    dir: PathBuf,
+}
+
+impl ProcessSpool {
+    /// Spool of the execution process
+    pub fn new(execution_process_id: Uuid) -> Self {
+        Self {
+            dir: get_vibe_kanban_temp_dir()
+                .join(SPOOL_DIR)
+                .join(execution_process_id.to_string()),
+        }
+    }
+
+    /// Spool in `dir`, as recorded for a running process
+    pub fn from_dir(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn stdout_path(&self) -> PathBuf {
+        self.dir.join("stdout")
+    }
+
+    fn stderr_path(&self) -> PathBuf {
+        self.dir.join("stderr")
+    }
+
+    fn exit_path(&self) -> PathBuf {
+        self.dir.join("exit")
+    }
+
+    /// Create the spool directory, dropping output of an earlier run
+    pub async fn prepare(&self) -> io::Result<()> {
+        if tokio::fs::try_exists(&self.dir).await? {
+            tokio::fs::remove_dir_all(&self.dir).await?;
+        }
+        tokio::fs::create_dir_all(&self.dir).await
+    }
+
+    pub async fn remove(&self) -> io::Result<()> {
+        tokio::fs::remove_dir_all(&self.dir).await
+    }
+
+    /// Exit code of the process, once it has finished
+    pub async fn exit_code(&self) -> Option<i32> {
+        tokio::fs::read_to_string(self.exit_path())
+            .await
+            .ok()?
+            .trim()
+            .parse()
+            .ok()
+    }
+
+    /// `command` run through the launcher, with its environment and working directory.
+    /// Arguments added later are passed on to the spooled program.
+    pub fn wrap(&self, command: Command) -> Command {
+        let inner = command.as_std();
+        let mut launcher = Command::new("bash");
+        launcher
+            .arg("-c")
+            .arg(LAUNCHER)
+            .arg("vibe-kanban-spool")
+            .arg(self.stdout_path())
+            .arg(self.stderr_path())
+            .arg(self.exit_path())
+            .arg(inner.get_program())
+            .args(inner.get_args());
+        for (key, value) in inner.get_envs() {
+            match value {
+                Some(value) => launcher.env(key, value),
+                None => launcher.env_remove(key),
+            };
+        }
+        if let Some(dir) = inner.get_current_dir() {
+            launcher.current_dir(dir);
+        }
+        launcher
+    }
+
+    /// Replay the spooled output into `store`, then follow it until the process has exited
+    /// and its output is drained. `is_running` tells whether the process group still exists.
+    /// Returns the exit code, which is missing when the process was killed.
+    pub async fn follow(
+        &self,
+        store: &MsgStore,
+        is_running: impl Fn() -> bool,
+    ) -> io::Result<Option<i32>> {
+        let mut stdout = SpoolReader::new(self.stdout_path());
+        let mut stderr = SpoolReader::new(self.stderr_path());
+        let mut settled = false;
+        loop {
+            // Check before reading, so output written right before the exit is not missed
+            let exited = !is_running() || self.exit_code().await.is_some();
+            let mut read_any = false;
+            if let Some(chunk) = stdout.read_new().await? {
+                store.push(LogMsg::Stdout(chunk));
+                read_any = true;
+            }
+            if let Some(chunk) = stderr.read_new().await? {
+                store.push(LogMsg::Stderr(chunk));
+                read_any = true;
+            }
+            if exited && !read_any {
+                // The tees may still be flushing right after the exit code was written
+                if settled {
+                    return Ok(self.exit_code().await);
+                }
+                settled = true;
+            }
+            if !read_any {
+                tokio::time::sleep(FOLLOW_INTERVAL).await;
+            }
+        }
+    }
+}
+
+/// Reads what was appended to a spool file since the last read
+struct SpoolReader {
+    path: PathBuf,
+    offset: u64,
+}
+
+impl SpoolReader {
+    fn new(path: PathBuf) -> Self {
+        Self { path, offset: 0 }
+    }
+
+    async fn read_new(&mut self) -> io::Result<Option<String>> {
+        let mut file = match File::open(&self.path).await {
+            Ok(file) => file,
+            // Created by the launcher once the process starts
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        file.seek(io::SeekFrom::Start(self.offset)).await?;
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf).await?;
+        if buf.is_empty() {
+            return Ok(None);
+        }
+        self.offset += buf.len() as u64;
+        Ok(Some(String::from_utf8_lossy(&buf).into_owned()))
+    }
+}
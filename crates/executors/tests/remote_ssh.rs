@@ -0,0 +1,202 @@
+//! Remote execution against a real sshd, run in a docker container. Needs docker, and `ssh`,
+//! `ssh-keygen` and `rsync` on this machine:
+//!
+//! ```sh
+//! cargo test -p executors --test remote_ssh -- --ignored
+//! ```
+
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command as StdCommand, Stdio},
+    time::Duration,
+};
+
+use executors::remote::SshRemote;
+use uuid::Uuid;
+
+const IMAGE: &str = "vibe-kanban-test-sshd";
+
+const DOCKERFILE: &str = r#"FROM debian:bookworm-slim
+RUN apt-get update \
+ && apt-get install -y --no-install-recommends openssh-server rsync git \
+ && rm -rf /var/lib/apt/lists/* \
+ && mkdir -p /run/sshd \
+ && useradd -m -s /bin/bash dev \
+ && usermod -p '*' dev
+CMD ["sh", "-c", "ssh-keygen -A && exec /usr/sbin/sshd -D -e"]
+"#;
+
+fn run(program: &str, args: &[&str]) -> String {
+    let output = StdCommand::new(program).args(args).output().unwrap();
+    assert!(
+        output.status.success(),
+        "{program} {args:?} failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+/// sshd container accepting `dev` with one key, removed on drop
+struct SshdContainer {
+    id: String,
+    port: u16,
+}
+
+impl SshdContainer {
+    fn start(authorized_key: &str) -> Self {
+        let mut build = StdCommand::new("docker")
+            .args(["build", "-q", "-t", IMAGE, "-"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()
+            .unwrap();
+        build
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(DOCKERFILE.as_bytes())
+            .unwrap();
+        assert!(build.wait().unwrap().success(), "docker build failed");
+
+        let id = run(
+            "docker",
+            &["run", "-d", "--rm", "-p", "127.0.0.1::22", IMAGE],
+        );
+        let mut container = Self { id, port: 0 };
+        run(
+            "docker",
+            &[
+                "exec",
+                &container.id,
+                "sh",
+                "-c",
+                &format!(
+                    "mkdir -p /home/dev/.ssh && echo '{}' > /home/dev/.ssh/authorized_keys \
+                     && chown -R dev:dev /home/dev/.ssh && chmod 700 /home/dev/.ssh \
+                     && chmod 600 /home/dev/.ssh/authorized_keys",
+                    authorized_key.trim()
+                ),
+            ],
+        );
+        // e.g. `127.0.0.1:49153`
+        container.port = run("docker", &["port", &container.id, "22/tcp"])
+            .lines()
+            .find_map(|line| line.rsplit(':').next()?.parse().ok())
+            .expect("sshd port is published");
+        container
+    }
+
+    /// Host key line for a known hosts file
+    fn known_host(&self) -> String {
+        let key = run(
+            "docker",
+            &[
+                "exec",
+                &self.id,
+                "sh",
+                "-c",
+                "until [ -f /etc/ssh/ssh_host_ed25519_key.pub ]; do sleep 0.2; done; \
+                 cat /etc/ssh/ssh_host_ed25519_key.pub",
+            ],
+        );
+        format!("[127.0.0.1]:{} {}\n", self.port, key)
+    }
+}
+
+impl Drop for SshdContainer {
+    fn drop(&mut self) {
+        let _ = StdCommand::new("docker")
+            .args(["rm", "-f", &self.id])
+            .output();
+    }
+}
+
+async fn wait_until_reachable(remote: &SshRemote) {
+    for _ in 0..50 {
+        if remote.run("true").await.is_ok() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+    remote.run("true").await.unwrap();
+}
+
+fn write(path: &Path, content: &str) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+fn read(path: &Path) -> String {
+    fs::read_to_string(path).unwrap()
+}
+
+#[tokio::test]
+#[ignore = "starts an sshd container with docker"]
+async fn remote_run_mirrors_back_without_reverting_local_files() {
+    let root: PathBuf = std::env::temp_dir().join(format!("vk-remote-ssh-{}", Uuid::new_v4()));
+    fs::create_dir_all(&root).unwrap();
+    let key = root.join("id_ed25519");
+    run(
+        "ssh-keygen",
+        &[
+            "-q",
+            "-t",
+            "ed25519",
+            "-N",
+            "",
+            "-f",
+            &key.to_string_lossy(),
+        ],
+    );
+    let sshd = SshdContainer::start(&read(&key.with_extension("pub")));
+    let known_hosts = root.join("known_hosts");
+    write(&known_hosts, &sshd.known_host());
+
+    let remote = SshRemote {
+        destination: "dev@127.0.0.1".to_string(),
+        port: Some(sshd.port),
+        identity_file: Some(key),
+        known_hosts_file: Some(known_hosts),
+        workspace_dir: "worktrees".to_string(),
+    };
+    wait_until_reachable(&remote).await;
+
+    let workspace = root.join("workspace");
+    let repo = workspace.join("repo");
+    write(&repo.join("tracked.txt"), "local\n");
+    write(&repo.join("gone.txt"), "deleted remotely\n");
+
+    let remote_workspace = remote
+        .push(&workspace, &["repo".to_string()])
+        .await
+        .unwrap();
+    let child = remote_workspace
+        .command("bash")
+        .arg("-c")
+        .arg(
+            "echo from remote; sleep 8; echo remote > tracked.txt; echo new > added.txt; \
+             rm gone.txt",
+        )
+        .current_dir(&repo)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // Written locally after the first mirroring back, which must not delete it
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    write(&repo.join("local.txt"), "local only\n");
+
+    let output = child.wait_with_output().await.unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("from remote"));
+    assert_eq!(read(&repo.join("tracked.txt")), "remote\n");
+    assert_eq!(read(&repo.join("added.txt")), "new\n");
+    assert!(!repo.join("gone.txt").exists());
+    assert_eq!(read(&repo.join("local.txt")), "local only\n");
+
+    remote.remove(&workspace).await.unwrap();
+    let _ = fs::remove_dir_all(&root);
+}
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use command_group::AsyncGroupChild;
 #[cfg(unix)]
 use nix::{
@@ -42,3 +44,131 @@ pub async fn kill_process_group(child: &mut AsyncGroupChild) -> Result<(), Conta
     let _ = child.wait().await;
     Ok(())
 }
+
+/// Process group of a spooled process that is not a child of this server, e.g. one
+/// re-attached to after a restart. Its id comes from the database and process ids are
+/// reused, so the group is only followed or signalled while it still is the recorded one.
+#[derive(Debug, Clone)]
+pub struct DetachedProcessGroup {
+    pgid: i64,
+    spool_dir: PathBuf,
+}
+
+impl DetachedProcessGroup {
+    /// The group `pgid` led by the spool launcher writing to `spool_dir`
+    pub fn new(pgid: i64, spool_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            pgid,
+            spool_dir: spool_dir.into(),
+        }
+    }
+
+    pub fn pgid(&self) -> i64 {
+        self.pgid
+    }
+
+    /// Whether any process of the recorded group is left. A process with the group's id
+    /// that was not started with the spool in its arguments took over a reused id. Once
+    /// the launcher has exited its id can't be reused while other members are alive, so
+    /// the group is then recognised by its id alone.
+    pub fn exists(&self) -> bool {
+        if !process_group_exists(self.pgid) {
+            return false;
+        }
+        match process_command_line(self.pgid) {
+            Some(command_line) => command_line.contains(&*self.spool_dir.to_string_lossy()),
+            None => true,
+        }
+    }
+
+    /// Interrupt, terminate and finally kill the group
+    pub async fn kill(&self) -> Result<(), ContainerError> {
+        #[cfg(unix)]
+        {
+            let pgid = self.pgid;
+            for sig in [Signal::SIGINT, Signal::SIGTERM, Signal::SIGKILL] {
+                if !self.exists() {
+                    return Ok(());
+                }
+                if let Err(e) = killpg(Pid::from_raw(pgid as i32), sig) {
+                    tracing::warn!(
+                        "Failed to send signal {:?} to process group {}: {}",
+                        sig,
+                        pgid,
+                        e
+                    );
+                }
+                tokio::time::sleep(Duration::from_secs(2)).await;
+            }
+            if self.exists() {
+                return Err(ContainerError::KillFailed(std::io::Error::other(format!(
+                    "process group {pgid} is still running"
+                ))));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether any process is left in the process group `pgid`
+fn process_group_exists(pgid: i64) -> bool {
+    #[cfg(unix)]
+    {
+        // 0 and 1 would address our own process group and init
+        pgid > 1 && killpg(Pid::from_raw(pgid as i32), None).is_ok()
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = pgid;
+        false
+    }
+}
+
+/// Command line of the process `pid`, `None` when there is no such process
+fn process_command_line(pid: i64) -> Option<String> {
+    #[cfg(target_os = "linux")]
+    {
+        // Arguments are separated by NUL bytes
+        std::fs::read(format!("/proc/{pid}/cmdline"))
+            .ok()
+            .map(|cmdline| String::from_utf8_lossy(&cmdline).replace('\0', " "))
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let output = std::process::Command::new("ps")
+            .args(["-o", "command=", "-p", &pid.to_string()])
+            .output()
+            .ok()?;
+        let command_line = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        (output.status.success() && !command_line.is_empty()).then_some(command_line)
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_detached_group_is_recognised_by_its_spool() {
+        let spool_dir = std::env::temp_dir().join(format!("spool-{}", uuid::Uuid::new_v4()));
+        let mut child = tokio::process::Command::new("bash")
+            .arg("-c")
+            .arg("sleep 30; true")
+            .arg("vibe-kanban-spool")
+            .arg(spool_dir.join("stdout"))
+            .process_group(0)
+            .spawn()
+            .unwrap();
+        let pgid = child.id().unwrap() as i64;
+
+        assert!(DetachedProcessGroup::new(pgid, &spool_dir).exists());
+        // Same group id, but recorded for another process that has since gone
+        assert!(!DetachedProcessGroup::new(pgid, spool_dir.with_file_name("other")).exists());
+
+        DetachedProcessGroup::new(pgid, &spool_dir)
+            .kill()
+            .await
+            .unwrap();
+        let _ = child.wait().await;
+    }
+}
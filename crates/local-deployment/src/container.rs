@@ -13,14 +13,20 @@ use db::{
     DBService,
     models::{
         coding_agent_turn::CodingAgentTurn,
+        execution_budget::{BudgetLimits, BudgetUsage, ExecutionBudget},
         execution_process::{
             ExecutionContext, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
         },
+        execution_process_logs::ExecutionProcessLogs,
         execution_process_repo_state::ExecutionProcessRepoState,
+        execution_process_spool::ExecutionProcessSpool,
+        execution_process_usage::ExecutionProcessUsage,
+        project::Project,
         project_repo::ProjectRepo,
         repo::Repo,
-        scratch::{DraftFollowUpData, Scratch, ScratchType},
+        scratch::DraftFollowUpData,
         task::{Task, TaskStatus},
+        webhook::WebhookEvent,
         workspace::Workspace,
         workspace_repo::WorkspaceRepo,
     },
@@ -35,22 +41,34 @@ use executors::{
     approvals::{ExecutorApprovalService, NoopExecutorApprovalService},
     env::ExecutionEnv,
     executors::{BaseCodingAgent, ExecutorExitResult, ExecutorExitSignal, InterruptSender},
-    logs::{NormalizedEntryType, utils::patch::extract_normalized_entry_from_patch},
+    logs::{
+        NormalizedEntry, NormalizedEntryType,
+        utils::{
+            ConversationPatch, EntryIndexProvider, patch::extract_normalized_entry_from_patch,
+        },
+    },
     profile::ExecutorProfileId,
+    remote::{RemoteError, SshRemote},
+    sandbox::{self, NetworkProxy, Sandbox, SandboxConfig, SandboxError, SandboxNetwork},
+    spool::ProcessSpool,
 };
 use futures::{FutureExt, TryStreamExt, stream::select};
 use serde_json::json;
 use services::services::{
     analytics::AnalyticsContext,
     approvals::{Approvals, executor_approvals::ExecutorApprovalBridge},
+    commit_message,
     config::Config,
+    conflict_resolution::ConflictResolutionOutcome,
     container::{ContainerError, ContainerRef, ContainerService},
     diff_stream::{self, DiffStreamHandle},
     git::{Commit, GitCli, GitService},
     image::ImageService,
     notification::NotificationService,
     queued_message::QueuedMessageService,
+    scheduler::ExecutionScheduler,
     share::SharePublisher,
+    webhook::WebhookService,
     workspace_manager::{RepoWorkspaceInput, WorkspaceManager},
 };
 use tokio::{sync::RwLock, task::JoinHandle};
@@ -62,14 +80,28 @@ use utils::{
 };
 use uuid::Uuid;
 
-use crate::{command, copy};
+use crate::{
+    command::{self, DetachedProcessGroup},
+    copy,
+    workspace_pool::WorkspacePool,
+};
+
+/// Seconds between budget checks of a running coding agent
+const BUDGET_CHECK_INTERVAL_SECS: u64 = 5;
+/// How long commit message generation waits for a free agent slot before giving up
+const COMMIT_MESSAGE_SLOT_WAIT: Duration = Duration::from_secs(60);
 
 #[derive(Clone)]
 pub struct LocalContainerService {
     db: DBService,
     child_store: Arc<RwLock<HashMap<Uuid, Arc<RwLock<AsyncGroupChild>>>>>,
     interrupt_senders: Arc<RwLock<HashMap<Uuid, InterruptSender>>>,
+    /// Allowlist proxies of running sandboxed executions, stopped when the execution exits
+    network_proxies: Arc<RwLock<HashMap<Uuid, NetworkProxy>>>,
     msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>,
+    /// Process groups of spooled executions re-attached to after a restart, which are not
+    /// children of this server and so are missing from `child_store`
+    reattached: Arc<RwLock<HashMap<Uuid, DetachedProcessGroup>>>,
     config: Arc<RwLock<Config>>,
     git: GitService,
     image_service: ImageService,
@@ -78,6 +110,12 @@ pub struct LocalContainerService {
     queued_message_service: QueuedMessageService,
     publisher: Result<SharePublisher, RemoteClientNotConfigured>,
     notification_service: NotificationService,
+    scheduler: ExecutionScheduler,
+    webhooks: WebhookService,
+    /// Host that agents and scripts run on instead of this machine, when configured
+    remote: Option<SshRemote>,
+    /// Pre-warmed workspaces, unless agents and scripts run on a remote host
+    workspace_pool: Option<WorkspacePool>,
 }
 
 impl LocalContainerService {
@@ -92,16 +130,27 @@ impl LocalContainerService {
         approvals: Approvals,
         queued_message_service: QueuedMessageService,
         publisher: Result<SharePublisher, RemoteClientNotConfigured>,
+        scheduler: ExecutionScheduler,
+        webhooks: WebhookService,
+        remote: Option<SshRemote>,
     ) -> Self {
         let child_store = Arc::new(RwLock::new(HashMap::new()));
         let interrupt_senders = Arc::new(RwLock::new(HashMap::new()));
+        let network_proxies = Arc::new(RwLock::new(HashMap::new()));
+        let reattached = Arc::new(RwLock::new(HashMap::new()));
         let notification_service = NotificationService::new(config.clone());
+        // Warm workspaces are set up on this machine, which is of no use to a remote host
+        let workspace_pool = remote
+            .is_none()
+            .then(|| WorkspacePool::new(db.clone(), git.clone()));
 
         let container = LocalContainerService {
             db,
             child_store,
             interrupt_senders,
+            network_proxies,
             msg_stores,
+            reattached,
             config,
             git,
             image_service,
@@ -110,9 +159,16 @@ impl LocalContainerService {
             queued_message_service,
             publisher,
             notification_service,
+            scheduler,
+            webhooks,
+            remote,
+            workspace_pool,
         };
 
         container.spawn_workspace_cleanup().await;
+        if let Some(workspace_pool) = &container.workspace_pool {
+            workspace_pool.spawn();
+        }
 
         container
     }
@@ -174,6 +230,13 @@ impl LocalContainerService {
                 });
         }
 
+        let sandbox_state_dir = sandbox::state_dir(workspace.id);
+        if sandbox_state_dir.exists()
+            && let Err(e) = tokio::fs::remove_dir_all(&sandbox_state_dir).await
+        {
+            tracing::warn!("Failed to remove sandbox state directory: {}", e);
+        }
+
         // Clear container_ref so this workspace won't be picked up again
         let _ = Workspace::clear_container_ref(&db.pool, workspace.id).await;
     }
@@ -311,8 +374,13 @@ impl LocalContainerService {
     }
 
     /// Commit changes to each repo. Logs failures but continues with other repos.
-    fn commit_repos(&self, repos_with_changes: Vec<(Repo, PathBuf)>, message: &str) -> bool {
-        let mut any_committed = false;
+    /// Returns the repos that got a commit.
+    fn commit_repos(
+        &self,
+        repos_with_changes: Vec<(Repo, PathBuf)>,
+        message: &str,
+    ) -> Vec<(Repo, PathBuf)> {
+        let mut committed = Vec::new();
 
         for (repo, worktree_path) in repos_with_changes {
             tracing::debug!(
@@ -323,8 +391,8 @@ impl LocalContainerService {
 
             match self.git().commit(&worktree_path, message) {
                 Ok(true) => {
-                    any_committed = true;
                     tracing::info!("Committed changes in repo '{}'", repo.name);
+                    committed.push((repo, worktree_path));
                 }
                 Ok(false) => {
                     tracing::warn!("No changes committed in repo '{}' (unexpected)", repo.name);
@@ -335,7 +403,69 @@ impl LocalContainerService {
             }
         }
 
-        any_committed
+        committed
+    }
+
+    /// Run the commit message agent in a throwaway sandbox that mounts nothing but an empty
+    /// scratch directory, using the project's sandbox settings when it has any
+    async fn run_commit_message_agent(
+        &self,
+        project: &Project,
+        executor_profile_id: &ExecutorProfileId,
+        context: &str,
+        diff: &str,
+    ) -> anyhow::Result<String> {
+        // Without a project sandbox the agent still needs to reach its own API
+        let config = project
+            .sandbox_config
+            .as_deref()
+            .filter(|config| config.enabled)
+            .cloned()
+            .unwrap_or(SandboxConfig {
+                enabled: true,
+                network: SandboxNetwork::Full,
+                ..Default::default()
+            });
+        let proxy = match config.network {
+            SandboxNetwork::Allowlist => Some(
+                NetworkProxy::start(config.allowed_hosts.clone())
+                    .await
+                    .map_err(SandboxError::Proxy)?,
+            ),
+            SandboxNetwork::Full | SandboxNetwork::None => None,
+        };
+        let sandbox_id = Uuid::new_v4();
+        let scratch_dir = utils::path::get_vibe_kanban_temp_dir()
+            .join("commit-message")
+            .join(sandbox_id.to_string());
+        tokio::fs::create_dir_all(&scratch_dir).await?;
+        let mut env = ExecutionEnv::new();
+        let result = async {
+            env.sandbox = Some(
+                Sandbox::new(
+                    &config,
+                    sandbox_id,
+                    &scratch_dir,
+                    &[],
+                    proxy.as_ref().map(NetworkProxy::socket_path),
+                )
+                .await?,
+            );
+            Ok::<_, anyhow::Error>(
+                commit_message::generate(executor_profile_id, context, diff, &scratch_dir, &env)
+                    .await?,
+            )
+        }
+        .await;
+
+        for dir in [scratch_dir, sandbox::state_dir(sandbox_id)] {
+            if dir.exists()
+                && let Err(e) = tokio::fs::remove_dir_all(&dir).await
+            {
+                tracing::warn!("Failed to remove {}: {}", dir.display(), e);
+            }
+        }
+        result
     }
 
     /// Spawn a background task that polls the child process for completion and
@@ -344,9 +474,24 @@ impl LocalContainerService {
         &self,
         exec_id: &Uuid,
         exit_signal: Option<ExecutorExitSignal>,
+    ) -> JoinHandle<()> {
+        let process_exit_rx = self.spawn_os_exit_watcher(*exec_id);
+        self.spawn_exit_monitor_with(exec_id, exit_signal, process_exit_rx)
+    }
+
+    /// Like [`Self::spawn_exit_monitor`], with the process exit reported by `process_exit_rx`
+    /// instead of the child in the store
+    fn spawn_exit_monitor_with(
+        &self,
+        exec_id: &Uuid,
+        exit_signal: Option<ExecutorExitSignal>,
+        mut process_exit_rx: tokio::sync::oneshot::Receiver<
+            std::io::Result<std::process::ExitStatus>,
+        >,
     ) -> JoinHandle<()> {
         let exec_id = *exec_id;
         let child_store = self.child_store.clone();
+        let network_proxies = self.network_proxies.clone();
         let msg_stores = self.msg_stores.clone();
         let db = self.db.clone();
         let config = self.config.clone();
@@ -354,8 +499,6 @@ impl LocalContainerService {
         let analytics = self.analytics.clone();
         let publisher = self.publisher.clone();
 
-        let mut process_exit_rx = self.spawn_os_exit_watcher(exec_id);
-
         tokio::spawn(async move {
             let mut exit_signal_future = exit_signal
                 .map(|rx| rx.boxed()) // wait for result
@@ -410,7 +553,24 @@ impl LocalContainerService {
                 tracing::error!("Failed to update execution process completion: {}", e);
             }
 
+            // Hand the slot to the next queued coding agent before running follow-up steps,
+            // so work queued earlier isn't overtaken by this process's own next action
+            container.release_execution_slot(exec_id).await;
+
             if let Ok(ctx) = ExecutionProcess::load_context(&db.pool, exec_id).await {
+                let webhook_event = match ctx.execution_process.status {
+                    ExecutionProcessStatus::Completed => Some(WebhookEvent::ExecutionCompleted),
+                    ExecutionProcessStatus::Failed => Some(WebhookEvent::ExecutionFailed),
+                    _ => None,
+                };
+                if let Some(event) = webhook_event {
+                    container.webhooks.emit_execution(
+                        event,
+                        &ctx.execution_process,
+                        &ctx.workspace,
+                    );
+                }
+
                 // Update executor session summary if available
                 if let Err(e) = container.update_executor_session_summary(&exec_id).await {
                     tracing::warn!("Failed to update executor session summary: {}", e);
@@ -429,7 +589,12 @@ impl LocalContainerService {
                     ExecutionProcessStatus::Running
                 );
 
-                if success || cleanup_done {
+                // Conflict resolution runs continue the interrupted rebase or merge instead
+                // of committing, so nothing lands in the middle of the operation
+                let conflict_resolution = container.finish_conflict_resolution(&ctx, success).await;
+
+                let mut verification_started = false;
+                if conflict_resolution.is_none() && (success || cleanup_done) {
                     // Commit changes (if any) and get feedback about whether changes were made
                     let changes_committed = match container.try_commit_changes(&ctx).await {
                         Ok(committed) => committed,
@@ -444,12 +609,31 @@ impl LocalContainerService {
                         ctx.execution_process.run_reason,
                         ExecutionProcessRunReason::CodingAgent
                     ) {
-                        changes_committed
+                        // Pipeline steps such as planning or review may not change any files
+                        let next_is_pipeline_step = ctx
+                            .execution_process
+                            .executor_action()
+                            .ok()
+                            .and_then(|action| action.next_action())
+                            .is_some_and(|next| next.pipeline_step().is_some());
+                        changes_committed || next_is_pipeline_step
                     } else {
                         true
                     };
 
-                    if should_start_next {
+                    // Verify scripts gate the rest of the chain and start it once they pass
+                    verification_started = matches!(
+                        ctx.execution_process.run_reason,
+                        ExecutionProcessRunReason::CodingAgent
+                    ) && success
+                        && container.start_verification(&ctx, should_start_next).await;
+
+                    if verification_started {
+                        tracing::debug!(
+                            "Started verify scripts for workspace {}",
+                            ctx.workspace.id
+                        );
+                    } else if should_start_next {
                         // If the process exited successfully, start the next action
                         if let Err(e) = container.try_start_next_action(&ctx).await {
                             tracing::error!("Failed to start next action after completion: {}", e);
@@ -465,56 +649,89 @@ impl LocalContainerService {
                     }
                 }
 
-                if container.should_finalize(&ctx) {
+                // Review follow-ups push what the agent committed to the pull request
+                if matches!(
+                    ctx.execution_process.run_reason,
+                    ExecutionProcessRunReason::CodingAgent
+                ) {
+                    container.finish_pr_review_follow_up(&ctx, success).await;
+                }
+
+                // A failed verify script goes back to the agent while retries are left
+                let verification_retried = matches!(
+                    ctx.execution_process.run_reason,
+                    ExecutionProcessRunReason::Verify
+                ) && container.retry_failed_verification(&ctx).await;
+
+                if conflict_resolution != Some(ConflictResolutionOutcome::Continuing)
+                    && !verification_started
+                    && !verification_retried
+                    && container.should_finalize(&ctx)
+                {
                     // Only execute queued messages if the execution succeeded
                     // If it failed or was killed, just clear the queue and finalize
                     let should_execute_queued = !matches!(
                         ctx.execution_process.status,
-                        ExecutionProcessStatus::Failed | ExecutionProcessStatus::Killed
+                        ExecutionProcessStatus::Failed
+                            | ExecutionProcessStatus::Killed
+                            | ExecutionProcessStatus::BudgetExceeded
                     );
 
-                    if let Some(queued_msg) =
-                        container.queued_message_service.take_queued(ctx.session.id)
-                    {
-                        if should_execute_queued {
-                            tracing::info!(
-                                "Found queued message for session {}, starting follow-up execution",
-                                ctx.session.id
-                            );
-
-                            // Delete the scratch since we're consuming the queued message
-                            if let Err(e) = Scratch::delete(
-                                &db.pool,
-                                ctx.session.id,
-                                &ScratchType::DraftFollowUp,
-                            )
+                    if should_execute_queued {
+                        match container
+                            .queued_message_service
+                            .take_queued(ctx.session.id)
                             .await
-                            {
-                                tracing::warn!(
-                                    "Failed to delete scratch after consuming queued message: {}",
-                                    e
+                        {
+                            Ok(Some(queued_msg)) => {
+                                tracing::info!(
+                                    "Found queued message for session {}, starting follow-up execution",
+                                    ctx.session.id
                                 );
-                            }
 
-                            // Execute the queued follow-up
-                            if let Err(e) = container
-                                .start_queued_follow_up(&ctx, &queued_msg.data)
-                                .await
-                            {
-                                tracing::error!("Failed to start queued follow-up: {}", e);
-                                // Fall back to finalization if follow-up fails
+                                // Execute the queued follow-up; the rest of the queue is drained
+                                // the same way as each follow-up finishes
+                                if let Err(e) = container
+                                    .start_queued_follow_up(&ctx, &queued_msg.data)
+                                    .await
+                                {
+                                    tracing::error!("Failed to start queued follow-up: {}", e);
+                                    // Fall back to finalization if follow-up fails
+                                    container.finalize_task(publisher.as_ref().ok(), &ctx).await;
+                                }
+                            }
+                            Ok(None) => {
+                                container.finalize_task(publisher.as_ref().ok(), &ctx).await;
+                            }
+                            Err(e) => {
+                                tracing::error!(
+                                    "Failed to take queued message for session {}: {}",
+                                    ctx.session.id,
+                                    e
+                                );
                                 container.finalize_task(publisher.as_ref().ok(), &ctx).await;
                             }
-                        } else {
-                            // Execution failed or was killed - discard the queued message and finalize
-                            tracing::info!(
-                                "Discarding queued message for session {} due to execution status {:?}",
+                        }
+                    } else {
+                        // Execution failed or was killed - discard the queue and finalize
+                        match container
+                            .queued_message_service
+                            .cancel_queued(ctx.session.id)
+                            .await
+                        {
+                            Ok(0) => {}
+                            Ok(discarded) => tracing::info!(
+                                "Discarding {} queued message(s) for session {} due to execution status {:?}",
+                                discarded,
                                 ctx.session.id,
                                 ctx.execution_process.status
-                            );
-                            container.finalize_task(publisher.as_ref().ok(), &ctx).await;
+                            ),
+                            Err(e) => tracing::error!(
+                                "Failed to discard queued messages for session {}: {}",
+                                ctx.session.id,
+                                e
+                            ),
                         }
-                    } else {
                         container.finalize_task(publisher.as_ref().ok(), &ctx).await;
                     }
                 }
@@ -556,8 +773,11 @@ impl LocalContainerService {
                 }
             }
 
-            // Cleanup child handle
+            // Cleanup child handle, sandbox proxy and output spool
             child_store.write().await.remove(&exec_id);
+            network_proxies.write().await.remove(&exec_id);
+            container.reattached.write().await.remove(&exec_id);
+            container.remove_spool(exec_id).await;
         })
     }
 
@@ -598,6 +818,172 @@ impl LocalContainerService {
         rx
     }
 
+    /// Spawn a background task that stops a coding agent execution with
+    /// `ExecutionProcessStatus::BudgetExceeded` once it goes over the task's budget, and adds
+    /// a system message to its log saying which limit it hit. `running_for` is how long the
+    /// process has run already, e.g. before a restart.
+    pub fn spawn_budget_watchdog(
+        &self,
+        execution_process: &ExecutionProcess,
+        task_id: Uuid,
+        limits: BudgetLimits,
+        running_for: Duration,
+    ) -> JoinHandle<()> {
+        let exec_id = execution_process.id;
+        let is_follow_up = matches!(
+            execution_process.executor_action(),
+            Ok(action) if matches!(action.typ, ExecutorActionType::CodingAgentFollowUpRequest(_))
+        );
+        let now = tokio::time::Instant::now();
+        let started = now.checked_sub(running_for).unwrap_or(now);
+        let container = self.clone();
+
+        tokio::spawn(async move {
+            loop {
+                // The exit monitor removes the child once the process is done
+                if container.get_child_from_store(&exec_id).await.is_none()
+                    && !container.reattached.read().await.contains_key(&exec_id)
+                {
+                    break;
+                }
+
+                let pool = &container.db.pool;
+                let mut usage = BudgetUsage {
+                    duration_secs: started.elapsed().as_secs() as i64,
+                    ..Default::default()
+                };
+                if is_follow_up && limits.max_follow_ups.is_some() {
+                    usage.follow_ups = ExecutionProcess::count_follow_ups_for_task(pool, task_id)
+                        .await
+                        .unwrap_or_default();
+                }
+                if limits.max_tokens.is_some() {
+                    usage.tokens = ExecutionProcessUsage::total_tokens_for_task(pool, task_id)
+                        .await
+                        .unwrap_or_default();
+                }
+
+                if let Some(reason) = limits.exceeded_by(&usage) {
+                    container.stop_over_budget(exec_id, reason).await;
+                    break;
+                }
+
+                tokio::time::sleep(Duration::from_secs(BUDGET_CHECK_INTERVAL_SECS)).await;
+            }
+        })
+    }
+
+    async fn stop_over_budget(&self, exec_id: Uuid, reason: String) {
+        tracing::info!("Stopping execution process {}: {}", exec_id, reason);
+
+        if let Some(msg_store) = self.get_msg_store_by_id(&exec_id).await {
+            let entry = NormalizedEntry {
+                timestamp: None,
+                entry_type: NormalizedEntryType::SystemMessage,
+                content: reason,
+                metadata: None,
+            };
+            let index = EntryIndexProvider::start_from(&msg_store).next();
+            msg_store.push_patch(ConversationPatch::add_normalized_entry(index, entry));
+        }
+
+        match ExecutionProcess::find_by_id(&self.db.pool, exec_id).await {
+            Ok(Some(process)) if process.status == ExecutionProcessStatus::Running => {
+                if let Err(e) = self
+                    .stop_execution(&process, ExecutionProcessStatus::BudgetExceeded)
+                    .await
+                {
+                    tracing::error!(
+                        "Failed to stop execution process {} over budget: {}",
+                        exec_id,
+                        e
+                    );
+                }
+            }
+            Ok(_) => {}
+            Err(e) => tracing::error!("Failed to load execution process {}: {}", exec_id, e),
+        }
+    }
+
+    /// Follow a spooled execution process left running by a previous server: replay its
+    /// spool into a fresh msg store, then monitor it like a child of this server
+    async fn reattach_spooled(
+        &self,
+        execution_process: &ExecutionProcess,
+        spool: ProcessSpool,
+        group: DetachedProcessGroup,
+    ) -> Result<(), ContainerError> {
+        let exec_id = execution_process.id;
+        let ctx = ExecutionProcess::load_context(&self.db.pool, exec_id).await?;
+        let executor_action = execution_process.executor_action()?.clone();
+
+        // The spool holds the complete output, so the logs are stored again from it
+        ExecutionProcessLogs::delete_by_execution_id(&self.db.pool, exec_id).await?;
+        let store = Arc::new(MsgStore::new());
+        self.msg_stores.write().await.insert(exec_id, store.clone());
+        self.reattached.write().await.insert(exec_id, group.clone());
+
+        if let Some(executor) = executor_action.base_executor() {
+            self.scheduler.resume(exec_id, executor);
+        }
+
+        let (exit_tx, exit_rx) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            let result = spool
+                .follow(&store, || group.exists())
+                .await
+                .map(|code| code.map_or_else(failure_exit_status, exit_status_from_code));
+            let _ = exit_tx.send(result);
+        });
+
+        self.spawn_log_normalizer(&ctx.workspace, exec_id, &executor_action)
+            .await;
+        self.spawn_stream_raw_logs_to_db(&exec_id);
+        let _hn = self.spawn_exit_monitor_with(&exec_id, None, exit_rx);
+
+        if execution_process.run_reason == ExecutionProcessRunReason::CodingAgent {
+            match ExecutionBudget::limits_for_task(&self.db.pool, ctx.task.project_id, ctx.task.id)
+                .await
+            {
+                Ok(limits) if !limits.is_unlimited() => {
+                    let running_for = (chrono::Utc::now() - execution_process.started_at)
+                        .to_std()
+                        .unwrap_or_default();
+                    let _hn = self.spawn_budget_watchdog(
+                        execution_process,
+                        ctx.task.id,
+                        limits,
+                        running_for,
+                    );
+                }
+                Ok(_) => {}
+                Err(e) => tracing::error!("Failed to load execution budget: {}", e),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Forget the spool of an execution process that is done and delete its files
+    async fn remove_spool(&self, exec_id: Uuid) {
+        let record =
+            match ExecutionProcessSpool::find_by_execution_process_id(&self.db.pool, exec_id).await
+            {
+                Ok(Some(record)) => record,
+                Ok(None) => return,
+                Err(e) => {
+                    tracing::warn!("Failed to load spool of execution process {exec_id}: {e}");
+                    return;
+                }
+            };
+        if let Err(e) = ProcessSpool::from_dir(&record.spool_dir).remove().await {
+            tracing::debug!("Failed to remove spool {}: {}", record.spool_dir, e);
+        }
+        if let Err(e) = ExecutionProcessSpool::delete(&self.db.pool, exec_id).await {
+            tracing::warn!("Failed to delete spool of execution process {exec_id}: {e}");
+        }
+    }
+
     pub fn dir_name_from_workspace(workspace_id: &Uuid, task_title: &str) -> String {
         let task_title_id = git_branch_id(task_title);
         format!("{}-{}", short_uuid(workspace_id), task_title_id)
@@ -867,6 +1253,20 @@ fn failure_exit_status() -> std::process::ExitStatus {
     }
 }
 
+/// Exit status of a process that exited with `code`
+fn exit_status_from_code(code: i32) -> std::process::ExitStatus {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        ExitStatusExt::from_raw((code & 0xff) << 8)
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::ExitStatusExt;
+        ExitStatusExt::from_raw(code as u32)
+    }
+}
+
 #[async_trait]
 impl ContainerService for LocalContainerService {
     fn msg_stores(&self) -> &Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>> {
@@ -889,10 +1289,26 @@ impl ContainerService for LocalContainerService {
         &self.notification_service
     }
 
+    fn webhooks(&self) -> &WebhookService {
+        &self.webhooks
+    }
+
+    fn scheduler(&self) -> &ExecutionScheduler {
+        &self.scheduler
+    }
+
+    fn runs_remotely(&self) -> bool {
+        self.remote.is_some()
+    }
+
     async fn git_branch_prefix(&self) -> String {
         self.config.read().await.git_branch_prefix.clone()
     }
 
+    async fn default_executor_profile(&self) -> ExecutorProfileId {
+        self.config.read().await.executor_profile.clone()
+    }
+
     fn workspace_to_current_dir(&self, workspace: &Workspace) -> PathBuf {
         PathBuf::from(workspace.container_ref.clone().unwrap_or_default())
     }
@@ -958,8 +1374,55 @@ impl ContainerService for LocalContainerService {
             .to_string())
     }
 
+    async fn create_from_pool(
+        &self,
+        workspace: &Workspace,
+    ) -> Result<Option<HashMap<Uuid, String>>, ContainerError> {
+        let Some(workspace_pool) = &self.workspace_pool else {
+            return Ok(None);
+        };
+        let task = workspace
+            .parent_task(&self.db.pool)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+        let workspace_dir = WorkspaceManager::get_workspace_base_dir().join(
+            LocalContainerService::dir_name_from_workspace(&workspace.id, &task.title),
+        );
+
+        let Some(setups) = workspace_pool.claim(workspace, &workspace_dir).await? else {
+            return Ok(None);
+        };
+
+        // Adds the task's images and any project files matched since the warm workspace was
+        // prepared; files copied then are kept
+        self.copy_files_and_images(&workspace_dir, workspace)
+            .await?;
+        let repositories =
+            WorkspaceRepo::find_repos_for_workspace(&self.db.pool, workspace.id).await?;
+        Self::create_workspace_config_files(&workspace_dir, &repositories).await?;
+
+        Workspace::update_container_ref(
+            &self.db.pool,
+            workspace.id,
+            &workspace_dir.to_string_lossy(),
+        )
+        .await?;
+
+        Ok(Some(setups))
+    }
+
     async fn delete(&self, workspace: &Workspace) -> Result<(), ContainerError> {
         self.try_stop(workspace, true).await;
+        if let Some(remote) = &self.remote
+            && let Some(container_ref) = &workspace.container_ref
+            && let Err(e) = remote.remove(Path::new(container_ref)).await
+        {
+            tracing::warn!(
+                "Failed to remove remote workspace for workspace {}: {}",
+                workspace.id,
+                e
+            );
+        }
         Self::cleanup_workspace(&self.db, workspace).await;
         Ok(())
     }
@@ -1056,11 +1519,13 @@ impl ContainerService for LocalContainerService {
                     | BaseCodingAgent::ClaudeCode
                     | BaseCodingAgent::Gemini
                     | BaseCodingAgent::QwenCode
-                    | BaseCodingAgent::Opencode,
+                    | BaseCodingAgent::Opencode
+                    | BaseCodingAgent::CustomAgent,
                 ) => ExecutorApprovalBridge::new(
                     self.approvals.clone(),
                     self.db.clone(),
                     self.notification_service.clone(),
+                    self.webhooks.clone(),
                     execution_process.id,
                 ),
                 _ => Arc::new(NoopExecutorApprovalService {}),
@@ -1087,6 +1552,67 @@ impl ContainerService for LocalContainerService {
         env.insert("VK_WORKSPACE_ID", workspace.id.to_string());
         env.insert("VK_WORKSPACE_BRANCH", &workspace.branch);
 
+        // Dev servers must stay reachable from the host, so only agents and scripts are run
+        // remotely or sandboxed. The remote host can't confine processes, so projects that
+        // ask for a sandbox are refused rather than run unconfined.
+        let mut network_proxy = None;
+        if let Some(remote) = &self.remote
+            && execution_process.run_reason != ExecutionProcessRunReason::DevServer
+        {
+            if project
+                .sandbox_config
+                .as_deref()
+                .is_some_and(|config| config.enabled)
+            {
+                return Err(RemoteError::SandboxedProject(project.name.clone()).into());
+            }
+            let repo_names: Vec<String> =
+                WorkspaceRepo::find_repos_for_workspace(&self.db.pool, workspace.id)
+                    .await?
+                    .into_iter()
+                    .map(|repo| repo.name)
+                    .collect();
+            env.remote = Some(remote.push(&current_dir, &repo_names).await?);
+        } else if let Some(config) = project.sandbox_config.as_deref()
+            && config.enabled
+            && execution_process.run_reason != ExecutionProcessRunReason::DevServer
+        {
+            let worktrees: Vec<PathBuf> =
+                WorkspaceRepo::find_repos_for_workspace(&self.db.pool, workspace.id)
+                    .await?
+                    .into_iter()
+                    .map(|repo| current_dir.join(repo.name))
+                    .collect();
+
+            let proxy = match config.network {
+                SandboxNetwork::Allowlist => Some(
+                    NetworkProxy::start(config.allowed_hosts.clone())
+                        .await
+                        .map_err(SandboxError::Proxy)?,
+                ),
+                SandboxNetwork::Full | SandboxNetwork::None => None,
+            };
+            env.sandbox = Some(
+                Sandbox::new(
+                    config,
+                    workspace.id,
+                    &current_dir,
+                    &worktrees,
+                    proxy.as_ref().map(NetworkProxy::socket_path),
+                )
+                .await?,
+            );
+            network_proxy = proxy;
+        }
+
+        // Spooled processes outlive the server and are re-attached to after a restart
+        let spool = (cfg!(unix) && executor_action.spools_output())
+            .then(|| ProcessSpool::new(execution_process.id));
+        if let Some(spool) = &spool {
+            spool.prepare().await?;
+            env.spool = Some(spool.clone());
+        }
+
         // Create the child and stream, add to execution tracker with timeout
         let mut spawned = tokio::time::timeout(
             Duration::from_secs(30),
@@ -1099,12 +1625,37 @@ impl ContainerService for LocalContainerService {
             ))
         })??;
 
+        // The launcher leads the process group of the spooled process
+        if let Some(spool) = &spool
+            && let Some(pgid) = spawned.child.inner().id()
+            && let Err(e) = ExecutionProcessSpool::create(
+                &self.db.pool,
+                execution_process.id,
+                pgid as i64,
+                &spool.dir().to_string_lossy(),
+            )
+            .await
+        {
+            tracing::error!(
+                "Failed to record spool of execution process {}: {}",
+                execution_process.id,
+                e
+            );
+        }
+
         self.track_child_msgs_in_store(execution_process.id, &mut spawned.child)
             .await;
 
         self.add_child_to_store(execution_process.id, spawned.child)
             .await;
 
+        if let Some(proxy) = network_proxy {
+            self.network_proxies
+                .write()
+                .await
+                .insert(execution_process.id, proxy);
+        }
+
         // Store interrupt sender for graceful shutdown
         if let Some(interrupt_sender) = spawned.interrupt_sender {
             self.add_interrupt_sender(execution_process.id, interrupt_sender)
@@ -1114,6 +1665,22 @@ impl ContainerService for LocalContainerService {
         // Spawn unified exit monitor: watches OS exit and optional executor signal
         let _hn = self.spawn_exit_monitor(&execution_process.id, spawned.exit_signal);
 
+        // The process is already running, so a budget that fails to load only skips the watchdog
+        if execution_process.run_reason == ExecutionProcessRunReason::CodingAgent {
+            match ExecutionBudget::limits_for_task(&self.db.pool, project.id, task.id).await {
+                Ok(limits) if !limits.is_unlimited() => {
+                    let _hn = self.spawn_budget_watchdog(
+                        execution_process,
+                        task.id,
+                        limits,
+                        Duration::ZERO,
+                    );
+                }
+                Ok(_) => {}
+                Err(e) => tracing::error!("Failed to load execution budget: {}", e),
+            }
+        }
+
         Ok(())
     }
 
@@ -1122,12 +1689,25 @@ impl ContainerService for LocalContainerService {
         execution_process: &ExecutionProcess,
         status: ExecutionProcessStatus,
     ) -> Result<(), ContainerError> {
-        let child = self
-            .get_child_from_store(&execution_process.id)
+        // Queued processes have no child yet, just take them out of the queue
+        if execution_process.status == ExecutionProcessStatus::Queued {
+            return self
+                .cancel_queued_execution(execution_process, status)
+                .await;
+        }
+
+        let child = self.get_child_from_store(&execution_process.id).await;
+        let reattached = self
+            .reattached
+            .read()
             .await
-            .ok_or_else(|| {
-                ContainerError::Other(anyhow!("Child process not found for execution"))
-            })?;
+            .get(&execution_process.id)
+            .cloned();
+        if child.is_none() && reattached.is_none() {
+            return Err(ContainerError::Other(anyhow!(
+                "Child process not found for execution"
+            )));
+        }
         let exit_code = if status == ExecutionProcessStatus::Completed {
             Some(0)
         } else {
@@ -1138,7 +1718,9 @@ impl ContainerService for LocalContainerService {
             .await?;
 
         // Try graceful interrupt first, then force kill
-        if let Some(interrupt_sender) = self.take_interrupt_sender(&execution_process.id).await {
+        if let Some(child) = &child
+            && let Some(interrupt_sender) = self.take_interrupt_sender(&execution_process.id).await
+        {
             // Send interrupt signal (ignore error if receiver dropped)
             let _ = interrupt_sender.send(());
 
@@ -1168,16 +1750,21 @@ impl ContainerService for LocalContainerService {
         }
 
         // Kill the child process and remove from the store
-        {
-            let mut child_guard = child.write().await;
-            if let Err(e) = command::kill_process_group(&mut child_guard).await {
-                tracing::error!(
-                    "Failed to stop execution process {}: {}",
-                    execution_process.id,
-                    e
-                );
-                return Err(e);
+        let killed = match (&child, reattached) {
+            (Some(child), _) => {
+                let mut child_guard = child.write().await;
+                command::kill_process_group(&mut child_guard).await
             }
+            (None, Some(group)) => group.kill().await,
+            (None, None) => Ok(()),
+        };
+        if let Err(e) = killed {
+            tracing::error!(
+                "Failed to stop execution process {}: {}",
+                execution_process.id,
+                e
+            );
+            return Err(e);
         }
         self.remove_child_from_store(&execution_process.id).await;
 
@@ -1298,7 +1885,7 @@ impl ContainerService for LocalContainerService {
             return Ok(false);
         }
 
-        let message = self.get_commit_message(ctx).await;
+        let mut message = self.get_commit_message(ctx).await;
 
         let container_ref = ctx
             .workspace
@@ -1313,7 +1900,146 @@ impl ContainerService for LocalContainerService {
             return Ok(false);
         }
 
-        Ok(self.commit_repos(repos_with_changes, &message))
+        if !ctx.project.conventional_commits {
+            return Ok(!self.commit_repos(repos_with_changes, &message).is_empty());
+        }
+
+        let mut diff = String::new();
+        for (repo, worktree_path) in &repos_with_changes {
+            match self.git().staged_changes_diff(worktree_path) {
+                Ok(repo_diff) => diff.push_str(&repo_diff),
+                Err(e) => tracing::warn!("Failed to diff repo '{}': {}", repo.name, e),
+            }
+        }
+        let committed = self.commit_repos(
+            repos_with_changes,
+            &commit_message::to_conventional(&message),
+        );
+        if committed.is_empty() {
+            return Ok(false);
+        }
+
+        // Writing the message can take minutes, so the commits land with the fallback and
+        // are reworded once the agent answers, unless something was committed on top since
+        let heads: Vec<(Uuid, PathBuf, String)> = committed
+            .into_iter()
+            .filter_map(|(repo, worktree_path)| {
+                let head = self.git().get_head_info(&worktree_path).ok()?;
+                Some((repo.id, worktree_path, head.oid))
+            })
+            .collect();
+        let container = self.clone();
+        let project = ctx.project.clone();
+        let task_id = ctx.task.id;
+        let session_id = ctx.session.id;
+        let execution_process_id = ctx.execution_process.id;
+        let branch = ctx.workspace.branch.clone();
+        tokio::spawn(async move {
+            let Some(message) = container
+                .generate_commit_message(&project, task_id, Some(session_id), &diff)
+                .await
+            else {
+                return;
+            };
+            for (repo_id, worktree_path, commit) in heads {
+                match container
+                    .git()
+                    .reword_branch_head(&worktree_path, &branch, &commit, &message)
+                {
+                    Ok(Some(reworded)) => {
+                        if let Err(e) = ExecutionProcessRepoState::update_after_head_commit(
+                            &container.db.pool,
+                            execution_process_id,
+                            repo_id,
+                            &reworded,
+                        )
+                        .await
+                        {
+                            tracing::warn!("Failed to record reworded commit {}: {}", reworded, e);
+                        }
+                    }
+                    Ok(None) => tracing::debug!(
+                        "Branch {} moved on, keeping the message of commit {}",
+                        branch,
+                        commit
+                    ),
+                    Err(e) => tracing::warn!("Failed to reword commit {}: {}", commit, e),
+                }
+            }
+        });
+
+        Ok(true)
+    }
+
+    async fn generate_commit_message(
+        &self,
+        project: &Project,
+        task_id: Uuid,
+        session_id: Option<Uuid>,
+        diff: &str,
+    ) -> Option<String> {
+        // The remote host would have to be sent the diff, so the fallback message is kept
+        if diff.trim().is_empty() || self.remote.is_some() {
+            return None;
+        }
+        let executor_profile_id = self.commit_message_executor(project, session_id).await?;
+        let task = Task::find_by_id(&self.db.pool, task_id)
+            .await
+            .ok()
+            .flatten()?;
+
+        let max_tokens =
+            match ExecutionBudget::limits_for_task(&self.db.pool, project.id, task_id).await {
+                Ok(limits) => limits.max_tokens,
+                Err(e) => {
+                    tracing::warn!("Failed to load budget of task {}: {}", task_id, e);
+                    return None;
+                }
+            };
+        if let Some(max_tokens) = max_tokens
+            && !ExecutionProcessUsage::total_tokens_for_task(&self.db.pool, task_id)
+                .await
+                .is_ok_and(|used| used < max_tokens)
+        {
+            tracing::debug!(
+                "Token budget of task {} is used up, keeping the commit message",
+                task_id
+            );
+            return None;
+        }
+
+        // Counts against the agent limits like any other run, but is skipped instead of
+        // queued when they are reached
+        let slot_id = Uuid::new_v4();
+        let deadline = tokio::time::Instant::now() + COMMIT_MESSAGE_SLOT_WAIT;
+        while !self
+            .scheduler()
+            .try_start(slot_id, executor_profile_id.executor)
+            .await
+        {
+            if tokio::time::Instant::now() >= deadline {
+                tracing::debug!("No free agent slot, keeping the commit message");
+                return None;
+            }
+            tokio::time::sleep(Duration::from_secs(2)).await;
+        }
+
+        let context = commit_message::task_context(&task.title, task.description.as_deref());
+        let result = self
+            .run_commit_message_agent(project, &executor_profile_id, &context, diff)
+            .await;
+        self.release_execution_slot(slot_id).await;
+        match result {
+            Ok(message) => Some(message),
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to generate commit message with {}: {}",
+                    executor_profile_id,
+                    e
+                );
+                None
+            }
+        }
     }
 
     /// Copy files from the original project directory to the worktree.
@@ -1339,11 +2065,77 @@ impl ContainerService for LocalContainerService {
         .map_err(|e| ContainerError::Other(anyhow!("Copy files task failed: {e}")))?
     }
 
+    async fn reattach_execution(&self, execution_process: &ExecutionProcess) -> bool {
+        let record = match ExecutionProcessSpool::find_by_execution_process_id(
+            &self.db.pool,
+            execution_process.id,
+        )
+        .await
+        {
+            Ok(Some(record)) => record,
+            Ok(None) => return false,
+            Err(e) => {
+                tracing::error!(
+                    "Failed to load spool of execution process {}: {}",
+                    execution_process.id,
+                    e
+                );
+                return false;
+            }
+        };
+
+        // A process that finished while the server was down is re-attached to as well, so
+        // its exit is handled like any other
+        let spool = ProcessSpool::from_dir(&record.spool_dir);
+        let group = DetachedProcessGroup::new(record.pgid, &record.spool_dir);
+        if !group.exists() && spool.exit_code().await.is_none() {
+            self.remove_spool(execution_process.id).await;
+            return false;
+        }
+
+        match self
+            .reattach_spooled(execution_process, spool, group.clone())
+            .await
+        {
+            Ok(()) => {
+                tracing::info!(
+                    "Re-attached to execution process {} in process group {}",
+                    execution_process.id,
+                    record.pgid
+                );
+                true
+            }
+            Err(e) => {
+                tracing::error!(
+                    "Failed to re-attach to execution process {}: {}",
+                    execution_process.id,
+                    e
+                );
+                // Nothing would follow the process, so it is stopped and failed as an orphan
+                if let Err(e) = group.kill().await {
+                    tracing::error!("Failed to stop process group {}: {}", record.pgid, e);
+                }
+                self.remove_spool(execution_process.id).await;
+                false
+            }
+        }
+    }
+
     async fn kill_all_running_processes(&self) -> Result<(), ContainerError> {
         tracing::info!("Killing all running processes");
         let running_processes = ExecutionProcess::find_running(&self.db.pool).await?;
 
         for process in running_processes {
+            // Spooled processes keep running and are re-attached to on the next start
+            if let Ok(Some(_)) =
+                ExecutionProcessSpool::find_by_execution_process_id(&self.db.pool, process.id).await
+            {
+                tracing::info!(
+                    "Leaving execution process {} running to re-attach to after restart",
+                    process.id
+                );
+                continue;
+            }
             if let Err(error) = self
                 .stop_execution(&process, ExecutionProcessStatus::Killed)
                 .await
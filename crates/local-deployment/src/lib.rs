@@ -3,7 +3,7 @@ use std::{collections::HashMap, sync::Arc};
 use async_trait::async_trait;
 use db::DBService;
 use deployment::{Deployment, DeploymentError, RemoteClientNotConfigured};
-use executors::profile::ExecutorConfigs;
+use executors::{profile::ExecutorConfigs, remote::SshRemote};
 use services::services::{
     analytics::{AnalyticsConfig, AnalyticsContext, AnalyticsService, generate_user_id},
     approvals::Approvals,
@@ -20,7 +20,9 @@ use services::services::{
     queued_message::QueuedMessageService,
     remote_client::{RemoteClient, RemoteClientError},
     repo::RepoService,
+    scheduler::ExecutionScheduler,
     share::{ShareConfig, SharePublisher},
+    webhook::WebhookService,
 };
 use tokio::sync::RwLock;
 use utils::{
@@ -34,6 +36,7 @@ use crate::container::LocalContainerService;
 mod command;
 pub mod container;
 mod copy;
+mod workspace_pool;
 
 #[derive(Clone)]
 pub struct LocalDeployment {
@@ -55,6 +58,7 @@ pub struct LocalDeployment {
     share_config: Option<ShareConfig>,
     remote_client: Result<RemoteClient, RemoteClientNotConfigured>,
     auth_context: AuthContext,
+    webhooks: WebhookService,
     oauth_handoffs: Arc<RwLock<HashMap<Uuid, PendingHandoff>>>,
 }
 
@@ -105,11 +109,15 @@ impl Deployment for LocalDeployment {
         let events_entry_count = Arc::new(RwLock::new(0));
 
         // Create DB with event hooks
+        let hook_db = DBService::new().await?; // Temporary DB service for the hook
+        // Task status webhooks are emitted from the hook, so the service has to exist first
+        let webhooks = WebhookService::spawn(hook_db.clone());
         let db = {
             let hook = EventService::create_hook(
                 events_msg_store.clone(),
                 events_entry_count.clone(),
-                DBService::new().await?, // Temporary DB service for the hook
+                hook_db,
+                webhooks.clone(),
             );
             DBService::new_with_after_connect(hook).await?
         };
@@ -126,7 +134,8 @@ impl Deployment for LocalDeployment {
         }
 
         let approvals = Approvals::new(msg_stores.clone());
-        let queued_message_service = QueuedMessageService::new();
+        let queued_message_service = QueuedMessageService::new(db.clone());
+        let scheduler = ExecutionScheduler::new(config.clone(), events_msg_store.clone());
 
         let share_config = ShareConfig::from_env();
 
@@ -166,6 +175,14 @@ impl Deployment for LocalDeployment {
 
         let oauth_handoffs = Arc::new(RwLock::new(HashMap::new()));
 
+        let remote_host = SshRemote::from_env();
+        if let Some(remote) = &remote_host {
+            tracing::info!(
+                "Running agents and scripts on {} over SSH",
+                remote.destination
+            );
+        }
+
         // We need to make analytics accessible to the ContainerService
         // TODO: Handle this more gracefully
         let analytics_ctx = analytics.as_ref().map(|s| AnalyticsContext {
@@ -182,10 +199,13 @@ impl Deployment for LocalDeployment {
             approvals.clone(),
             queued_message_service.clone(),
             share_publisher.clone(),
+            scheduler.clone(),
+            webhooks.clone(),
+            remote_host,
         )
         .await;
 
-        let events = EventService::new(db.clone(), events_msg_store, events_entry_count);
+        let events = EventService::new(db.clone(), events_msg_store, events_entry_count, scheduler);
 
         let file_search_cache = Arc::new(FileSearchCache::new());
 
@@ -208,6 +228,7 @@ impl Deployment for LocalDeployment {
             share_config: share_config.clone(),
             remote_client,
             auth_context,
+            webhooks,
             oauth_handoffs,
         };
 
@@ -277,6 +298,10 @@ impl Deployment for LocalDeployment {
     fn auth_context(&self) -> &AuthContext {
         &self.auth_context
     }
+
+    fn webhooks(&self) -> &WebhookService {
+        &self.webhooks
+    }
 }
 
 impl LocalDeployment {
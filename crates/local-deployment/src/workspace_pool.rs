@@ -0,0 +1,771 @@
+//! Pool of workspaces created ahead of the attempts that will use them. For projects that ask
+//! for it, worktrees of all repos are kept on the branches new attempts target by default,
+//! with project files copied and setup scripts run, so that starting an attempt only has to
+//! move one into place.
+//!
+//! Setup scripts of warm workspaces run one at a time, outside of the execution scheduler like
+//! every setup script, and without the `VK_TASK_ID`, `VK_WORKSPACE_ID` and
+//! `VK_WORKSPACE_BRANCH` variables since no attempt exists yet.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use anyhow::anyhow;
+use command_group::AsyncCommandGroup;
+use db::{
+    DBService,
+    models::{
+        project::Project,
+        project_repo::{ProjectRepo, ProjectRepoWithName},
+        repo::Repo,
+        warm_workspace::{
+            CreateWarmWorkspaceRepo, WarmWorkspace, WarmWorkspaceRepo, WarmWorkspaceStatus,
+        },
+        workspace::Workspace,
+        workspace_repo::WorkspaceRepo,
+    },
+};
+use services::services::{
+    container::ContainerError,
+    git::{GitService, GitServiceError},
+    setup_cache,
+    workspace_manager::{RepoWorkspaceInput, WorkspaceManager},
+    worktree_manager::WorktreeManager,
+};
+use tokio::time::MissedTickBehavior;
+use utils::shell::get_shell_command;
+use uuid::Uuid;
+
+use crate::copy;
+
+/// Seconds between checks of the pools against their projects' settings and target branches
+const REFRESH_INTERVAL_SECS: u64 = 60;
+/// Setup scripts of warm workspaces running longer than this are killed and count as failed
+const SETUP_TIMEOUT_SECS: u64 = 30 * 60;
+/// Setup output kept per repo, from the end
+const MAX_SETUP_OUTPUT_BYTES: usize = 512 * 1024;
+/// Failed setups are retried after this long, doubling with each failure in a row
+const RETRY_BASE_SECS: u64 = 60;
+const RETRY_MAX_SECS: u64 = 60 * 60;
+
+/// Target branch of each repo, which warm workspaces and new attempts are matched on
+type TargetBranches = BTreeMap<Uuid, String>;
+
+#[derive(Clone)]
+pub struct WorkspacePool {
+    db: DBService,
+    git: GitService,
+    /// Failed setups in a row per warm workspace and when the last one failed
+    failures: Arc<Mutex<HashMap<Uuid, (u32, Instant)>>>,
+}
+
+impl WorkspacePool {
+    pub fn new(db: DBService, git: GitService) -> Self {
+        Self {
+            db,
+            git,
+            failures: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn base_dir() -> PathBuf {
+        utils::path::get_vibe_kanban_temp_dir().join("warm-worktrees")
+    }
+
+    /// Sandboxed agents run their setup scripts in the sandbox too, which warm workspaces
+    /// can't do before the attempt exists
+    fn is_enabled(project: &Project) -> bool {
+        project.warm_workspace_count > 0
+            && !project
+                .sandbox_config
+                .as_deref()
+                .is_some_and(|config| config.enabled)
+    }
+
+    fn target_branches_of(warm_repos: &[WarmWorkspaceRepo]) -> TargetBranches {
+        warm_repos
+            .iter()
+            .map(|warm_repo| (warm_repo.repo_id, warm_repo.target_branch.clone()))
+            .collect()
+    }
+
+    /// Whether a failed warm workspace has waited long enough to run its setup again
+    fn retry_due(&self, warm: &WarmWorkspace) -> bool {
+        // Failures before a restart are not known, so those are retried right away
+        let Some((failures, failed_at)) = self.failures.lock().unwrap().get(&warm.id).copied()
+        else {
+            return true;
+        };
+        let delay = (RETRY_BASE_SECS << failures.saturating_sub(1).min(6)).min(RETRY_MAX_SECS);
+        failed_at.elapsed() >= Duration::from_secs(delay)
+    }
+
+    fn record_setup(&self, id: Uuid, set_up: bool) {
+        let mut failures = self.failures.lock().unwrap();
+        if set_up {
+            failures.remove(&id);
+        } else {
+            let (count, failed_at) = failures.entry(id).or_insert((0, Instant::now()));
+            *count += 1;
+            *failed_at = Instant::now();
+        }
+    }
+
+    /// Remove what a previous run left behind, then keep the pools filled and up to date in
+    /// the background
+    pub fn spawn(&self) {
+        let pool = self.clone();
+        tokio::spawn(async move {
+            pool.remove_stale().await;
+            let mut interval = tokio::time::interval(Duration::from_secs(REFRESH_INTERVAL_SECS));
+            // Filling a pool runs setup scripts, which can take longer than the interval
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                pool.refresh_all().await;
+            }
+        });
+    }
+
+    /// Warm workspaces that were being prepared or claimed when the server stopped are in an
+    /// unknown state, and directories of deleted projects' warm workspaces have no row left
+    async fn remove_stale(&self) {
+        let warm_workspaces = match WarmWorkspace::find_all(&self.db.pool).await {
+            Ok(warm_workspaces) => warm_workspaces,
+            Err(e) => {
+                tracing::error!("Failed to load warm workspaces: {}", e);
+                return;
+            }
+        };
+
+        let mut known = HashSet::new();
+        for warm in warm_workspaces {
+            if matches!(
+                warm.status,
+                WarmWorkspaceStatus::Preparing | WarmWorkspaceStatus::Claimed
+            ) {
+                self.remove(&warm).await;
+            } else {
+                known.insert(PathBuf::from(&warm.container_ref));
+            }
+        }
+
+        let Ok(entries) = std::fs::read_dir(Self::base_dir()) else {
+            return;
+        };
+        for entry in entries.filter_map(Result::ok) {
+            let path = entry.path();
+            if !path.is_dir() || known.contains(&path) {
+                continue;
+            }
+            tracing::info!("Removing orphaned warm workspace {}", path.display());
+            for worktree in std::fs::read_dir(&path)
+                .into_iter()
+                .flatten()
+                .filter_map(Result::ok)
+            {
+                if let Err(e) = WorktreeManager::cleanup_suspected_worktree(&worktree.path()).await
+                {
+                    tracing::warn!("Failed to clean up warm worktree: {}", e);
+                }
+            }
+            let _ = tokio::fs::remove_dir_all(&path).await;
+        }
+    }
+
+    async fn refresh_all(&self) {
+        let projects = match Project::find_all(&self.db.pool).await {
+            Ok(projects) => projects,
+            Err(e) => {
+                tracing::error!("Failed to load projects for the workspace pool: {}", e);
+                return;
+            }
+        };
+
+        for project in &projects {
+            if let Err(e) = self.refresh_project(project).await {
+                tracing::warn!(
+                    "Failed to refresh warm workspaces of project {}: {}",
+                    project.id,
+                    e
+                );
+            }
+        }
+    }
+
+    /// Bring a project's warm workspaces in line with its pool size and the branches of its
+    /// repos: refresh the ones that match, remove the others and create the missing ones
+    async fn refresh_project(&self, project: &Project) -> Result<(), ContainerError> {
+        let pool = &self.db.pool;
+        let warm_workspaces = WarmWorkspace::find_by_project_id(pool, project.id).await?;
+        let wanted = if Self::is_enabled(project) {
+            project.warm_workspace_count as usize
+        } else {
+            0
+        };
+        if wanted == 0 && warm_workspaces.is_empty() {
+            return Ok(());
+        }
+
+        let project_repos = ProjectRepo::find_by_project_id_with_names(pool, project.id).await?;
+        let repos = ProjectRepo::find_repos_for_project(pool, project.id).await?;
+        // Without repos there is nothing to create worktrees of
+        let wanted = if repos.is_empty() { 0 } else { wanted };
+        let targets = if wanted > 0 {
+            self.default_target_branches(&repos)?
+        } else {
+            TargetBranches::new()
+        };
+
+        let mut kept = 0;
+        for warm in &warm_workspaces {
+            if warm.status == WarmWorkspaceStatus::Claimed {
+                continue;
+            }
+            let warm_repos = WarmWorkspaceRepo::find_by_warm_workspace_id(pool, warm.id).await?;
+            let keep = kept < wanted && Self::target_branches_of(&warm_repos) == targets;
+            // Claimed by an attempt in the meantime
+            if !WarmWorkspace::start_refresh(pool, warm.id).await? {
+                continue;
+            }
+
+            if keep {
+                kept += 1;
+                let retry_failed =
+                    warm.status == WarmWorkspaceStatus::Failed && self.retry_due(warm);
+                self.freshen(
+                    project,
+                    warm,
+                    &warm_repos,
+                    &project_repos,
+                    &repos,
+                    retry_failed,
+                )
+                .await;
+            } else {
+                self.remove(warm).await;
+            }
+        }
+
+        for _ in kept..wanted {
+            self.create(project, &project_repos, &repos, &targets)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// The branch checked out in each repo, which new attempts target unless told otherwise
+    fn default_target_branches(&self, repos: &[Repo]) -> Result<TargetBranches, ContainerError> {
+        repos
+            .iter()
+            .map(|repo| {
+                let branch = self
+                    .git
+                    .get_current_branch(&repo.path)
+                    .map_err(GitServiceError::from)?;
+                Ok::<_, ContainerError>((repo.id, branch))
+            })
+            .collect()
+    }
+
+    /// Create a warm workspace with worktrees on the target branches, copy the project files
+    /// into them and run the setup scripts
+    async fn create(
+        &self,
+        project: &Project,
+        project_repos: &[ProjectRepoWithName],
+        repos: &[Repo],
+        targets: &TargetBranches,
+    ) -> Result<(), ContainerError> {
+        let pool = &self.db.pool;
+        let id = Uuid::new_v4();
+        let dir = Self::base_dir().join(id.simple().to_string());
+        let branch = format!("vk-warm/{}", id.simple());
+
+        let mut inputs = Vec::with_capacity(repos.len());
+        let mut warm_repos = Vec::with_capacity(repos.len());
+        for repo in repos {
+            let target_branch = targets
+                .get(&repo.id)
+                .cloned()
+                .ok_or_else(|| anyhow!("No target branch for repo {}", repo.name))?;
+            warm_repos.push(CreateWarmWorkspaceRepo {
+                repo_id: repo.id,
+                base_commit: self.git.get_branch_oid(&repo.path, &target_branch)?,
+                target_branch: target_branch.clone(),
+            });
+            inputs.push(RepoWorkspaceInput::new(repo.clone(), target_branch));
+        }
+
+        // Recorded first, so a restart while it is prepared removes what was created
+        let warm = WarmWorkspace::create(
+            pool,
+            id,
+            project.id,
+            &dir.to_string_lossy(),
+            &branch,
+            &warm_repos,
+        )
+        .await?;
+        tracing::info!(
+            "Preparing warm workspace {} for project {}",
+            warm.id,
+            project.name
+        );
+
+        if let Err(e) = WorkspaceManager::create_workspace(&dir, &inputs, &branch).await {
+            self.remove(&warm).await;
+            return Err(e.into());
+        }
+
+        let mut set_up = true;
+        for project_repo in project_repos {
+            let Some(repo) = repos.iter().find(|repo| repo.id == project_repo.repo_id) else {
+                continue;
+            };
+            let worktree = dir.join(&repo.name);
+
+            if let Some(copy_files) = project_repo
+                .copy_files
+                .clone()
+                .filter(|files| !files.trim().is_empty())
+            {
+                let source = repo.path.clone();
+                let target = worktree.clone();
+                let copied = tokio::task::spawn_blocking(move || {
+                    copy::copy_project_files_impl(&source, &target, &copy_files)
+                })
+                .await;
+                if let Ok(Err(e)) = copied {
+                    tracing::warn!(
+                        "Failed to copy project files for repo '{}': {}",
+                        repo.name,
+                        e
+                    );
+                }
+            }
+
+            if let Some(script) = &project_repo.setup_script {
+                set_up &= self
+                    .set_up_repo(project, &warm, repo, &worktree, script)
+                    .await;
+            }
+        }
+
+        self.record_setup(warm.id, set_up);
+        let status = if set_up {
+            WarmWorkspaceStatus::Ready
+        } else {
+            WarmWorkspaceStatus::Failed
+        };
+        WarmWorkspace::update_status(pool, warm.id, status).await?;
+        Ok(())
+    }
+
+    /// Move the worktrees of a warm workspace to the current commits of their target branches
+    /// and rerun the setup scripts whose script or lockfiles changed, or that failed before
+    /// the target moved or `retry_failed` is set
+    async fn freshen(
+        &self,
+        project: &Project,
+        warm: &WarmWorkspace,
+        warm_repos: &[WarmWorkspaceRepo],
+        project_repos: &[ProjectRepoWithName],
+        repos: &[Repo],
+        retry_failed: bool,
+    ) {
+        let dir = PathBuf::from(&warm.container_ref);
+        let mut set_up = true;
+        for warm_repo in warm_repos {
+            let Some(repo) = repos.iter().find(|repo| repo.id == warm_repo.repo_id) else {
+                continue;
+            };
+            let setup_script = project_repos
+                .iter()
+                .find(|project_repo| project_repo.repo_id == repo.id)
+                .and_then(|project_repo| project_repo.setup_script.as_deref());
+            let worktree = dir.join(&repo.name);
+
+            set_up &= match self
+                .freshen_repo(
+                    project,
+                    warm,
+                    warm_repo,
+                    repo,
+                    &worktree,
+                    setup_script,
+                    retry_failed,
+                )
+                .await
+            {
+                Ok(set_up) => set_up,
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to refresh warm worktree of repo '{}' in {}: {}",
+                        repo.name,
+                        warm.id,
+                        e
+                    );
+                    false
+                }
+            };
+        }
+
+        // An unchanged failure keeps its backoff going
+        if set_up || retry_failed || warm.status != WarmWorkspaceStatus::Failed {
+            self.record_setup(warm.id, set_up);
+        }
+        let status = if set_up {
+            WarmWorkspaceStatus::Ready
+        } else {
+            WarmWorkspaceStatus::Failed
+        };
+        if let Err(e) = WarmWorkspace::update_status(&self.db.pool, warm.id, status).await {
+            tracing::error!("Failed to update warm workspace {}: {}", warm.id, e);
+        }
+    }
+
+    /// Returns whether the worktree is set up afterwards
+    #[allow(clippy::too_many_arguments)]
+    async fn freshen_repo(
+        &self,
+        project: &Project,
+        warm: &WarmWorkspace,
+        warm_repo: &WarmWorkspaceRepo,
+        repo: &Repo,
+        worktree: &Path,
+        setup_script: Option<&str>,
+        retry_failed: bool,
+    ) -> Result<bool, ContainerError> {
+        let pool = &self.db.pool;
+        let tip = self
+            .git
+            .get_branch_oid(&repo.path, &warm_repo.target_branch)?;
+        let moved = tip != warm_repo.base_commit;
+        if moved {
+            // The warm branch has no commits of its own, so rebasing it onto the target is
+            // moving it to the new tip. Ignored files, where setup output goes, are kept.
+            self.git.reset_worktree_to_commit(worktree, &tip, true)?;
+            WarmWorkspaceRepo::update_base_commit(pool, warm.id, repo.id, &tip).await?;
+        }
+
+        let Some(script) = setup_script else {
+            if warm_repo.setup_key.is_some() {
+                WarmWorkspaceRepo::update_setup(pool, warm.id, repo.id, None, None, None).await?;
+            }
+            return Ok(true);
+        };
+
+        let key = setup_cache::setup_key(worktree, script).map_err(GitServiceError::from)?;
+        let stale = warm_repo.setup_key.as_deref() != Some(key.as_str());
+        if stale || ((moved || retry_failed) && !warm_repo.is_set_up()) {
+            return Ok(self
+                .set_up_repo(project, warm, repo, worktree, script)
+                .await);
+        }
+        Ok(warm_repo.is_set_up())
+    }
+
+    /// Set up a repo's warm worktree, from the setup cache when it has a run with the same
+    /// script and lockfiles, and record the outcome. Returns whether it succeeded.
+    async fn set_up_repo(
+        &self,
+        project: &Project,
+        warm: &WarmWorkspace,
+        repo: &Repo,
+        worktree: &Path,
+        script: &str,
+    ) -> bool {
+        let (key, exit_code, output) =
+            match self.run_setup(project, warm, repo, worktree, script).await {
+                Ok(outcome) => outcome,
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to run setup script of repo '{}' in warm workspace {}: {}",
+                        repo.name,
+                        warm.id,
+                        e
+                    );
+                    return false;
+                }
+            };
+
+        if let Err(e) = WarmWorkspaceRepo::update_setup(
+            &self.db.pool,
+            warm.id,
+            repo.id,
+            Some(&key),
+            exit_code,
+            Some(&output),
+        )
+        .await
+        {
+            tracing::error!(
+                "Failed to record setup of warm workspace {}: {}",
+                warm.id,
+                e
+            );
+            return false;
+        }
+        exit_code == Some(0)
+    }
+
+    /// Returns the setup key, the exit code (`None` if the script was killed) and the output
+    async fn run_setup(
+        &self,
+        project: &Project,
+        warm: &WarmWorkspace,
+        repo: &Repo,
+        worktree: &Path,
+        script: &str,
+    ) -> Result<(String, Option<i64>, String), ContainerError> {
+        let key = setup_cache::setup_key(worktree, script).map_err(GitServiceError::from)?;
+
+        let restored = {
+            let (key, worktree) = (key.clone(), worktree.to_path_buf());
+            let repo_id = repo.id;
+            tokio::task::spawn_blocking(move || setup_cache::restore(repo_id, &key, &worktree))
+                .await
+                .map_err(|e| anyhow!("Setup cache task failed: {e}"))?
+        };
+        match restored {
+            Ok(Some(output)) => {
+                tracing::info!(
+                    "Reused cached setup of repo '{}' for warm workspace {}",
+                    repo.name,
+                    warm.id
+                );
+                return Ok((key, Some(0), output));
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Failed to restore cached setup of '{}': {}", repo.name, e),
+        }
+
+        let before = setup_cache::ignored_entries(worktree).map_err(GitServiceError::from)?;
+        let log_path = PathBuf::from(&warm.container_ref).join(format!(".setup-{}.log", repo.id));
+        let exit_code = run_setup_script(project, worktree, script, &log_path).await?;
+        let output = read_output_tail(&log_path).await?;
+        let _ = tokio::fs::remove_file(&log_path).await;
+
+        if exit_code == Some(0) {
+            let after = setup_cache::ignored_entries(worktree).map_err(GitServiceError::from)?;
+            let created: Vec<String> = after.difference(&before).cloned().collect();
+            let (key, worktree, output) = (key.clone(), worktree.to_path_buf(), output.clone());
+            let repo_id = repo.id;
+            let stored = tokio::task::spawn_blocking(move || {
+                setup_cache::store(repo_id, &key, &worktree, &created, &output)
+            })
+            .await;
+            if let Ok(Err(e)) = stored {
+                tracing::warn!("Failed to cache setup of repo '{}': {}", repo.name, e);
+            }
+        }
+
+        Ok((key, exit_code, output))
+    }
+
+    /// Remove a warm workspace's worktrees, branches and directory
+    async fn remove(&self, warm: &WarmWorkspace) {
+        let pool = &self.db.pool;
+        let mut repos = Vec::new();
+        for warm_repo in WarmWorkspaceRepo::find_by_warm_workspace_id(pool, warm.id)
+            .await
+            .unwrap_or_default()
+        {
+            if let Ok(Some(repo)) = Repo::find_by_id(pool, warm_repo.repo_id).await {
+                repos.push(repo);
+            }
+        }
+
+        let dir = PathBuf::from(&warm.container_ref);
+        if let Err(e) = WorkspaceManager::cleanup_workspace(&dir, &repos).await {
+            tracing::warn!("Failed to clean up warm workspace {}: {}", warm.id, e);
+        }
+        for repo in &repos {
+            let _ = self.git.delete_local_branch(&repo.path, &warm.branch);
+        }
+        if let Err(e) = WarmWorkspace::delete(pool, warm.id).await {
+            tracing::error!("Failed to delete warm workspace {}: {}", warm.id, e);
+        }
+        self.failures.lock().unwrap().remove(&warm.id);
+    }
+
+    /// Move a ready warm workspace of the workspace's project into `workspace_dir` if one
+    /// matches its repos and target branches, renaming its branch to the workspace's.
+    /// Returns the setup output of each repo whose setup script already ran and is still
+    /// current, or `None` when no warm workspace matched.
+    pub async fn claim(
+        &self,
+        workspace: &Workspace,
+        workspace_dir: &Path,
+    ) -> Result<Option<HashMap<Uuid, String>>, ContainerError> {
+        let pool = &self.db.pool;
+        let task = workspace
+            .parent_task(pool)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+        let project = task
+            .parent_project(pool)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+        if !Self::is_enabled(&project) {
+            return Ok(None);
+        }
+
+        let targets: TargetBranches = WorkspaceRepo::find_by_workspace_id(pool, workspace.id)
+            .await?
+            .into_iter()
+            .map(|workspace_repo| (workspace_repo.repo_id, workspace_repo.target_branch))
+            .collect();
+
+        for warm in WarmWorkspace::find_by_project_id(pool, project.id).await? {
+            if warm.status != WarmWorkspaceStatus::Ready {
+                continue;
+            }
+            let warm_repos = WarmWorkspaceRepo::find_by_warm_workspace_id(pool, warm.id).await?;
+            if Self::target_branches_of(&warm_repos) != targets
+                || !WarmWorkspace::claim(pool, warm.id).await?
+            {
+                continue;
+            }
+
+            return match self
+                .move_into(&project, &warm, &warm_repos, workspace, workspace_dir)
+                .await
+            {
+                Ok(setups) => {
+                    WarmWorkspace::delete(pool, warm.id).await?;
+                    tracing::info!(
+                        "Workspace {} started from warm workspace {}",
+                        workspace.id,
+                        warm.id
+                    );
+                    Ok(Some(setups))
+                }
+                Err(e) => {
+                    self.discard_claimed(&warm, workspace, workspace_dir).await;
+                    Err(e)
+                }
+            };
+        }
+
+        Ok(None)
+    }
+
+    async fn move_into(
+        &self,
+        project: &Project,
+        warm: &WarmWorkspace,
+        warm_repos: &[WarmWorkspaceRepo],
+        workspace: &Workspace,
+        workspace_dir: &Path,
+    ) -> Result<HashMap<Uuid, String>, ContainerError> {
+        let pool = &self.db.pool;
+        let project_repos = ProjectRepo::find_by_project_id_with_names(pool, project.id).await?;
+        let warm_dir = PathBuf::from(&warm.container_ref);
+        tokio::fs::create_dir_all(workspace_dir).await?;
+
+        let mut setups = HashMap::new();
+        for warm_repo in warm_repos {
+            let repo = Repo::find_by_id(pool, warm_repo.repo_id)
+                .await?
+                .ok_or(sqlx::Error::RowNotFound)?;
+            let from = warm_dir.join(&repo.name);
+            let worktree = workspace_dir.join(&repo.name);
+
+            self.git
+                .rename_local_branch(&from, &warm.branch, &workspace.branch)?;
+            WorktreeManager::move_worktree(&repo.path, &from, &worktree).await?;
+
+            // The target may have moved since the last refresh
+            let tip = self
+                .git
+                .get_branch_oid(&repo.path, &warm_repo.target_branch)?;
+            if tip != warm_repo.base_commit {
+                self.git.reset_worktree_to_commit(&worktree, &tip, true)?;
+            }
+
+            // A setup that ran for other lockfiles or another script is run again by the
+            // attempt itself
+            let setup_script = project_repos
+                .iter()
+                .find(|project_repo| project_repo.repo_id == repo.id)
+                .and_then(|project_repo| project_repo.setup_script.as_deref());
+            if let Some(script) = setup_script
+                && warm_repo.is_set_up()
+                && let Some(output) = &warm_repo.setup_output
+                && warm_repo.setup_key.as_deref()
+                    == setup_cache::setup_key(&worktree, script).ok().as_deref()
+            {
+                setups.insert(repo.id, output.clone());
+            }
+        }
+
+        // Only setup logs are left
+        let _ = tokio::fs::remove_dir_all(&warm_dir).await;
+        Ok(setups)
+    }
+
+    /// Remove what is left of a warm workspace that failed to move into a workspace, so that
+    /// the workspace can be created from scratch
+    async fn discard_claimed(&self, warm: &WarmWorkspace, workspace: &Workspace, dir: &Path) {
+        let pool = &self.db.pool;
+        let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id)
+            .await
+            .unwrap_or_default();
+        if let Err(e) = WorkspaceManager::cleanup_workspace(dir, &repos).await {
+            tracing::warn!("Failed to clean up workspace {}: {}", workspace.id, e);
+        }
+        for repo in &repos {
+            let _ = self.git.delete_local_branch(&repo.path, &workspace.branch);
+        }
+        self.remove(warm).await;
+    }
+}
+
+/// Run a setup script in a worktree with its output going to `log_path`. Returns its exit
+/// code, or `None` if it was killed for running too long.
+async fn run_setup_script(
+    project: &Project,
+    worktree: &Path,
+    script: &str,
+    log_path: &Path,
+) -> Result<Option<i64>, ContainerError> {
+    let log = std::fs::File::create(log_path)?;
+    let (shell_cmd, shell_arg) = get_shell_command();
+    let mut command = tokio::process::Command::new(shell_cmd);
+    command
+        .arg(shell_arg)
+        .arg(script)
+        .current_dir(worktree)
+        .stdin(Stdio::null())
+        .stdout(log.try_clone()?)
+        .stderr(log)
+        .env("VK_PROJECT_NAME", &project.name)
+        .env("VK_PROJECT_ID", project.id.to_string());
+
+    let mut child = command.group_spawn()?;
+    match tokio::time::timeout(Duration::from_secs(SETUP_TIMEOUT_SECS), child.wait()).await {
+        Ok(status) => Ok(status?.code().map(i64::from)),
+        Err(_) => {
+            let _ = child.kill().await;
+            Ok(None)
+        }
+    }
+}
+
+async fn read_output_tail(log_path: &Path) -> std::io::Result<String> {
+    let output = tokio::fs::read(log_path).await?;
+    let output = String::from_utf8_lossy(&output);
+    let mut start = output.len().saturating_sub(MAX_SETUP_OUTPUT_BYTES);
+    while !output.is_char_boundary(start) {
+        start += 1;
+    }
+    Ok(output[start..].to_string())
+}
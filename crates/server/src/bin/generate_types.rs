@@ -15,6 +15,7 @@ fn generate_types_content() -> String {
         remote::routes::tasks::AssigneesQuery::decl(),
         remote::db::tasks::SharedTask::decl(),
         remote::db::users::UserData::decl(),
+        db::models::project::MergeMode::decl(),
         db::models::project::Project::decl(),
         db::models::project::CreateProject::decl(),
         db::models::project::UpdateProject::decl(),
@@ -30,18 +31,47 @@ fn generate_types_content() -> String {
         db::models::tag::Tag::decl(),
         db::models::tag::CreateTag::decl(),
         db::models::tag::UpdateTag::decl(),
+        db::models::pipeline_template::PipelineStep::decl(),
+        db::models::pipeline_template::PipelineTemplate::decl(),
+        db::models::pipeline_template::CreatePipelineTemplate::decl(),
+        db::models::pipeline_template::UpdatePipelineTemplate::decl(),
         db::models::task::TaskStatus::decl(),
         db::models::task::Task::decl(),
         db::models::task::TaskWithAttemptStatus::decl(),
         db::models::task::TaskRelationships::decl(),
         db::models::task::CreateTask::decl(),
         db::models::task::UpdateTask::decl(),
+        db::models::task_dependency::TaskDependency::decl(),
+        db::models::task_dependency::CreateTaskDependency::decl(),
+        db::models::task_dependency::TaskDependencies::decl(),
+        db::models::webhook::WebhookEvent::decl(),
+        db::models::webhook::Webhook::decl(),
+        db::models::webhook::CreateWebhook::decl(),
+        db::models::webhook::UpdateWebhook::decl(),
+        db::models::webhook_delivery::WebhookDeliveryStatus::decl(),
+        db::models::webhook_delivery::WebhookDelivery::decl(),
+        db::models::attempt_group::AttemptGroup::decl(),
+        db::models::attempt_group::AttemptGroupMember::decl(),
+        db::models::task_schedule::TaskScheduleRepo::decl(),
+        db::models::task_schedule::TaskSchedule::decl(),
+        db::models::task_schedule::CreateTaskSchedule::decl(),
+        db::models::task_schedule::UpdateTaskSchedule::decl(),
+        db::models::approval_rule::ApprovalRuleDecision::decl(),
+        db::models::approval_rule::ApprovalRule::decl(),
+        db::models::approval_rule::CreateApprovalRule::decl(),
+        db::models::approval_rule::UpdateApprovalRule::decl(),
+        db::models::workspace_checkpoint::CheckpointRepoState::decl(),
+        db::models::workspace_checkpoint::WorkspaceCheckpoint::decl(),
+        db::models::workspace_checkpoint::CreateWorkspaceCheckpoint::decl(),
         db::models::scratch::DraftFollowUpData::decl(),
         db::models::scratch::ScratchPayload::decl(),
         db::models::scratch::ScratchType::decl(),
         db::models::scratch::Scratch::decl(),
         db::models::scratch::CreateScratch::decl(),
         db::models::scratch::UpdateScratch::decl(),
+        db::models::queued_message::QueuedMessage::decl(),
+        db::models::search::SearchSource::decl(),
+        db::models::search::SearchHit::decl(),
         db::models::image::Image::decl(),
         db::models::image::CreateImage::decl(),
         db::models::workspace::Workspace::decl(),
@@ -50,9 +80,12 @@ fn generate_types_content() -> String {
         db::models::execution_process::ExecutionProcessStatus::decl(),
         db::models::execution_process::ExecutionProcessRunReason::decl(),
         db::models::execution_process_repo_state::ExecutionProcessRepoState::decl(),
+        db::models::execution_budget::BudgetLimits::decl(),
         db::models::merge::Merge::decl(),
         db::models::merge::DirectMerge::decl(),
         db::models::merge::PrMerge::decl(),
+        db::models::merge::PrCiState::decl(),
+        db::models::merge::CiStatus::decl(),
         db::models::merge::MergeStatus::decl(),
         db::models::merge::PullRequestInfo::decl(),
         utils::approvals::ApprovalStatus::decl(),
@@ -94,6 +127,7 @@ fn generate_types_content() -> String {
         server::routes::repo::RegisterRepoRequest::decl(),
         server::routes::repo::InitRepoRequest::decl(),
         server::routes::tags::TagSearchParams::decl(),
+        server::routes::search::SearchQuery::decl(),
         server::routes::oauth::TokenResponse::decl(),
         server::routes::config::UserSystemInfo::decl(),
         server::routes::config::Environment::decl(),
@@ -121,11 +155,15 @@ fn generate_types_content() -> String {
         server::routes::images::ImageMetadata::decl(),
         server::routes::task_attempts::CreateTaskAttemptBody::decl(),
         server::routes::task_attempts::WorkspaceRepoInput::decl(),
+        server::routes::attempt_groups::CreateAttemptGroupBody::decl(),
+        server::routes::attempt_groups::CreateAttemptGroupResponse::decl(),
+        server::routes::attempt_groups::PickAttemptGroupWinnerBody::decl(),
         server::routes::task_attempts::RunAgentSetupRequest::decl(),
         server::routes::task_attempts::RunAgentSetupResponse::decl(),
         server::routes::task_attempts::gh_cli_setup::GhCliSetupError::decl(),
         server::routes::task_attempts::RebaseTaskAttemptRequest::decl(),
         server::routes::task_attempts::AbortConflictsRequest::decl(),
+        server::routes::task_attempts::ResolveConflictsRequest::decl(),
         server::routes::task_attempts::GitOperationError::decl(),
         server::routes::task_attempts::PushError::decl(),
         server::routes::task_attempts::pr::CreatePrError::decl(),
@@ -136,6 +174,7 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::pr::PrCommentsResponse::decl(),
         server::routes::task_attempts::pr::GetPrCommentsError::decl(),
         server::routes::task_attempts::pr::GetPrCommentsQuery::decl(),
+        server::routes::task_attempts::pr::AddressPrCommentsRequest::decl(),
         services::services::github::UnifiedPrComment::decl(),
         server::routes::task_attempts::RepoBranchStatus::decl(),
         services::services::filesystem::DirectoryEntry::decl(),
@@ -150,12 +189,27 @@ fn generate_types_content() -> String {
         services::services::config::SoundFile::decl(),
         services::services::config::UiLanguage::decl(),
         services::services::config::ShowcaseState::decl(),
+        services::services::config::ConcurrencyConfig::decl(),
+        services::services::config::ModelPrice::decl(),
+        services::services::config::PrReviewConfig::decl(),
+        services::services::config::CiConfig::decl(),
         services::services::git::GitBranch::decl(),
         services::services::share::SharedTaskDetails::decl(),
-        services::services::queued_message::QueuedMessage::decl(),
         services::services::queued_message::QueueStatus::decl(),
+        services::services::scheduler::QueuePosition::decl(),
+        services::services::usage::UsageTotals::decl(),
+        services::services::usage::ModelUsage::decl(),
+        services::services::usage::DailyUsage::decl(),
+        services::services::usage::TaskUsage::decl(),
+        services::services::usage::UsageSummary::decl(),
+        services::services::attempt_group::DiffStats::decl(),
+        services::services::attempt_group::AttemptComparisonEntry::decl(),
+        services::services::attempt_group::AttemptComparison::decl(),
+        services::services::checkpoint::CheckpointRepoDiff::decl(),
+        services::services::task_archive::ImportedTask::decl(),
         services::services::git::ConflictOp::decl(),
         executors::actions::ExecutorAction::decl(),
+        executors::actions::PipelineStepRef::decl(),
         executors::mcp_config::McpConfig::decl(),
         executors::actions::ExecutorActionType::decl(),
         executors::actions::script::ScriptContext::decl(),
@@ -168,6 +222,8 @@ fn generate_types_content() -> String {
         executors::profile::ExecutorProfileId::decl(),
         executors::profile::ExecutorConfig::decl(),
         executors::profile::ExecutorConfigs::decl(),
+        executors::sandbox::SandboxConfig::decl(),
+        executors::sandbox::SandboxNetwork::decl(),
         executors::executors::BaseAgentCapability::decl(),
         executors::executors::claude::ClaudeCode::decl(),
         executors::executors::gemini::Gemini::decl(),
@@ -185,6 +241,11 @@ fn generate_types_content() -> String {
         executors::executors::droid::Droid::decl(),
         executors::executors::droid::Autonomy::decl(),
         executors::executors::droid::ReasoningEffortLevel::decl(),
+        executors::executors::custom::CustomAgent::decl(),
+        executors::executors::custom::PromptInput::decl(),
+        executors::executors::custom::OutputFormat::decl(),
+        executors::executors::custom::JsonLinesMapping::decl(),
+        executors::executors::custom::JsonLinesEntryType::decl(),
         executors::executors::AppendPrompt::decl(),
         executors::actions::coding_agent_initial::CodingAgentInitialRequest::decl(),
         executors::actions::coding_agent_follow_up::CodingAgentFollowUpRequest::decl(),
@@ -286,6 +347,10 @@ fn generate_schemas() -> Result<HashMap<&'static str, String>, serde_json::Error
             "droid",
             generate_json_schema::<executors::executors::droid::Droid>()?,
         ),
+        (
+            "custom_agent",
+            generate_json_schema::<executors::executors::custom::CustomAgent>()?,
+        ),
     ]);
     println!(
         "✅ JSON schemas generated. {} schemas created.",
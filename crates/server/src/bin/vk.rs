@@ -0,0 +1,579 @@
+use std::{io::BufRead, str::FromStr, time::Duration};
+
+use clap::{Args, Parser, Subcommand};
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason},
+    project::Project,
+    repo::Repo,
+    task::{CreateTask, Task, TaskStatus, TaskWithAttemptStatus},
+    workspace::Workspace,
+    workspace_repo::RepoWithTargetBranch,
+};
+use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use server::{
+    cli::{
+        client::{ClientError, VkClient, WsStream},
+        render::{self, DiffCollector, LogPrinter, StreamMessage},
+    },
+    routes::task_attempts::{
+        CreateTaskAttemptBody, MergeTaskAttemptRequest, PushTaskAttemptRequest, WorkspaceRepoInput,
+        pr::CreateGitHubPrRequest,
+    },
+};
+use tokio_tungstenite::tungstenite::Message;
+use utils::approvals::{ApprovalResponse, ApprovalStatus};
+use uuid::Uuid;
+
+/// The diff stream stays open to push live changes, so the snapshot is considered complete
+/// once the stream has been quiet for this long
+const DIFF_SETTLE: Duration = Duration::from_secs(3);
+
+#[derive(Parser, Debug)]
+#[command(name = "vk")]
+#[command(about = "Drive a running Vibe Kanban server from the terminal")]
+#[command(version)]
+struct Cli {
+    /// Server URL; discovered from the port file of the running server when omitted
+    #[arg(long, global = true, env = "VIBE_BACKEND_URL")]
+    url: Option<String>,
+
+    /// Print raw JSON instead of tab-separated lines
+    #[arg(long, global = true, default_value_t = false)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// List projects
+    Projects,
+    /// List or create tasks
+    #[command(subcommand)]
+    Task(TaskCommand),
+    /// List or start attempts of a task
+    #[command(subcommand)]
+    Attempt(AttemptCommand),
+    /// Print the conversation of an attempt, following it while the agent runs
+    Logs {
+        attempt_id: Uuid,
+        /// Execution process to show instead of the latest coding agent run
+        #[arg(long)]
+        process: Option<Uuid>,
+    },
+    /// Approve a pending tool call
+    Approve {
+        approval_id: String,
+        /// Execution process that requested the approval
+        #[arg(long)]
+        process: Uuid,
+    },
+    /// Deny a pending tool call
+    Deny {
+        approval_id: String,
+        /// Execution process that requested the approval
+        #[arg(long)]
+        process: Uuid,
+        /// Reason passed back to the agent
+        #[arg(long)]
+        reason: Option<String>,
+    },
+    /// Show the changes of an attempt against its target branch
+    Diff {
+        attempt_id: Uuid,
+        /// Only print per-file line counts
+        #[arg(long, default_value_t = false)]
+        stat: bool,
+    },
+    /// Merge an attempt into its target branch
+    Merge {
+        attempt_id: Uuid,
+        #[command(flatten)]
+        repo: RepoArg,
+    },
+    /// Push the branch of an attempt
+    Push {
+        attempt_id: Uuid,
+        #[command(flatten)]
+        repo: RepoArg,
+        #[arg(long, default_value_t = false)]
+        force: bool,
+    },
+    /// Open a pull request for an attempt
+    Pr {
+        attempt_id: Uuid,
+        #[command(flatten)]
+        repo: RepoArg,
+        #[arg(long)]
+        title: String,
+        #[arg(long)]
+        body: Option<String>,
+        /// Branch to merge into; defaults to the attempt's target branch
+        #[arg(long)]
+        base: Option<String>,
+        #[arg(long, default_value_t = false)]
+        draft: bool,
+        /// Let the agent write the PR description
+        #[arg(long, default_value_t = false)]
+        auto_description: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum TaskCommand {
+    /// List the tasks of a project
+    List {
+        #[command(flatten)]
+        project: ProjectArg,
+        /// Only show tasks with this status (todo, inprogress, inreview, done, cancelled)
+        #[arg(long, value_parser = TaskStatus::from_str)]
+        status: Option<TaskStatus>,
+    },
+    /// Create a task, or one task per line of stdin with `--stdin`
+    Create {
+        #[command(flatten)]
+        project: ProjectArg,
+        #[arg(long, required_unless_present = "stdin", conflicts_with = "stdin")]
+        title: Option<String>,
+        #[arg(long, conflicts_with = "stdin")]
+        description: Option<String>,
+        /// Read task titles from stdin, one per line
+        #[arg(long, default_value_t = false)]
+        stdin: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum AttemptCommand {
+    /// List the attempts of a task
+    List { task_id: Uuid },
+    /// Start a coding agent on a task
+    Start {
+        task_id: Uuid,
+        /// Coding agent, e.g. CLAUDE_CODE or claude-code
+        #[arg(long, value_parser = parse_executor)]
+        executor: BaseCodingAgent,
+        /// Profile variant of the coding agent, e.g. PLAN
+        #[arg(long)]
+        variant: Option<String>,
+        /// `REPO[@BRANCH]` by id or name; defaults to every project repo on its current branch
+        #[arg(long = "repo")]
+        repos: Vec<String>,
+    },
+}
+
+#[derive(Args, Debug)]
+struct ProjectArg {
+    #[arg(long, env = "VK_PROJECT_ID")]
+    project: Uuid,
+}
+
+#[derive(Args, Debug)]
+struct RepoArg {
+    /// Repository by id or name; required when the attempt spans several repositories
+    #[arg(long)]
+    repo: Option<String>,
+}
+
+/// The subset of `GitBranch` needed to pick a default target branch
+#[derive(Debug, Deserialize)]
+struct BranchSummary {
+    name: String,
+    is_current: bool,
+    is_remote: bool,
+}
+
+fn parse_executor(value: &str) -> Result<BaseCodingAgent, String> {
+    let normalized = value.trim().replace('-', "_").to_ascii_uppercase();
+    BaseCodingAgent::from_str(&normalized).map_err(|_| format!("unknown executor '{value}'"))
+}
+
+fn main() -> anyhow::Result<()> {
+    rustls::crypto::aws_lc_rs::default_provider()
+        .install_default()
+        .expect("Failed to install rustls crypto provider");
+
+    let cli = Cli::parse();
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(async {
+            let client = match &cli.url {
+                Some(url) => VkClient::new(url),
+                None => VkClient::discover().await?,
+            };
+            run(&client, cli.command, cli.json).await?;
+            Ok(())
+        })
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<(), ClientError> {
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|e| ClientError::Api(format!("failed to serialize output: {e}")))?;
+    println!("{json}");
+    Ok(())
+}
+
+async fn run(client: &VkClient, command: Command, json: bool) -> Result<(), ClientError> {
+    match command {
+        Command::Projects => {
+            let projects: Vec<Project> = client.get("projects").await?;
+            if json {
+                return print_json(&projects);
+            }
+            for project in projects {
+                println!("{}\t{}", project.id, project.name);
+            }
+        }
+        Command::Task(TaskCommand::List { project, status }) => {
+            let tasks: Vec<TaskWithAttemptStatus> = client
+                .get(&format!("tasks?project_id={}", project.project))
+                .await?;
+            let tasks: Vec<_> = tasks
+                .into_iter()
+                .filter(|task| status.as_ref().is_none_or(|status| &task.status == status))
+                .collect();
+            if json {
+                return print_json(&tasks);
+            }
+            for task in tasks {
+                let activity = if task.has_in_progress_attempt {
+                    "running"
+                } else if task.last_attempt_failed {
+                    "failed"
+                } else {
+                    "-"
+                };
+                println!("{}\t{}\t{}\t{}", task.id, task.status, activity, task.title);
+            }
+        }
+        Command::Task(TaskCommand::Create {
+            project,
+            title,
+            description,
+            stdin,
+        }) => {
+            let tasks = if stdin {
+                std::io::stdin()
+                    .lock()
+                    .lines()
+                    .map_while(Result::ok)
+                    .map(|line| line.trim().to_string())
+                    .filter(|line| !line.is_empty())
+                    .map(|title| CreateTask::from_title_description(project.project, title, None))
+                    .collect()
+            } else {
+                let title = title.unwrap_or_default();
+                vec![CreateTask::from_title_description(
+                    project.project,
+                    title,
+                    description,
+                )]
+            };
+
+            let mut created = Vec::with_capacity(tasks.len());
+            for task in tasks {
+                let task: Task = client.post("tasks", &task).await?;
+                if !json {
+                    println!("{}\t{}", task.id, task.title);
+                }
+                created.push(task);
+            }
+            if json {
+                return print_json(&created);
+            }
+        }
+        Command::Attempt(AttemptCommand::List { task_id }) => {
+            let attempts: Vec<Workspace> = client
+                .get(&format!("task-attempts?task_id={task_id}"))
+                .await?;
+            if json {
+                return print_json(&attempts);
+            }
+            for attempt in attempts {
+                println!(
+                    "{}\t{}\t{}",
+                    attempt.id,
+                    attempt.branch,
+                    attempt.created_at.to_rfc3339()
+                );
+            }
+        }
+        Command::Attempt(AttemptCommand::Start {
+            task_id,
+            executor,
+            variant,
+            repos,
+        }) => {
+            let body = CreateTaskAttemptBody {
+                task_id,
+                executor_profile_id: ExecutorProfileId { executor, variant },
+                repos: resolve_attempt_repos(client, task_id, &repos).await?,
+                pipeline_template_id: None,
+            };
+            let attempt: Workspace = client.post("task-attempts", &body).await?;
+            if json {
+                return print_json(&attempt);
+            }
+            println!("{}\t{}", attempt.id, attempt.branch);
+        }
+        Command::Logs {
+            attempt_id,
+            process,
+        } => {
+            let process_id = match process {
+                Some(process_id) => process_id,
+                None => latest_coding_agent_process(client, attempt_id).await?,
+            };
+            let mut stream = client
+                .connect_ws(&format!(
+                    "execution-processes/{process_id}/normalized-logs/ws"
+                ))
+                .await?;
+            let mut printer = LogPrinter::new(process_id);
+            while let Some(message) = stream.next().await {
+                let Message::Text(text) = message? else {
+                    continue;
+                };
+                match render::parse_stream_message(&text) {
+                    StreamMessage::Patch(operations) => printer.apply(operations),
+                    StreamMessage::Finished => break,
+                    StreamMessage::Other => {}
+                }
+            }
+            printer.finish();
+        }
+        Command::Approve {
+            approval_id,
+            process,
+        } => {
+            respond_to_approval(client, &approval_id, process, ApprovalStatus::Approved).await?;
+        }
+        Command::Deny {
+            approval_id,
+            process,
+            reason,
+        } => {
+            respond_to_approval(
+                client,
+                &approval_id,
+                process,
+                ApprovalStatus::Denied { reason },
+            )
+            .await?;
+        }
+        Command::Diff { attempt_id, stat } => {
+            let stream = client
+                .connect_ws(&format!(
+                    "task-attempts/{attempt_id}/diff/ws?stats_only={stat}"
+                ))
+                .await?;
+            let diffs = collect_diffs(stream).await?;
+            if json {
+                return print_json(&diffs);
+            }
+            if diffs.is_empty() {
+                println!("No changes");
+            } else if stat {
+                render::print_diff_stat(&diffs);
+            } else {
+                render::print_diff(&diffs);
+            }
+        }
+        Command::Merge { attempt_id, repo } => {
+            let repo = resolve_attempt_repo(client, attempt_id, repo.repo.as_deref()).await?;
+            let body = MergeTaskAttemptRequest {
+                repo_id: repo.repo.id,
+            };
+            let () = client
+                .post(&format!("task-attempts/{attempt_id}/merge"), &body)
+                .await?;
+            println!("Merged into {}", repo.target_branch);
+        }
+        Command::Push {
+            attempt_id,
+            repo,
+            force,
+        } => {
+            let repo = resolve_attempt_repo(client, attempt_id, repo.repo.as_deref()).await?;
+            let body = PushTaskAttemptRequest {
+                repo_id: repo.repo.id,
+            };
+            let path = if force {
+                format!("task-attempts/{attempt_id}/push/force")
+            } else {
+                format!("task-attempts/{attempt_id}/push")
+            };
+            let () = client.post(&path, &body).await?;
+            println!("Pushed {}", repo.repo.name);
+        }
+        Command::Pr {
+            attempt_id,
+            repo,
+            title,
+            body,
+            base,
+            draft,
+            auto_description,
+        } => {
+            let repo = resolve_attempt_repo(client, attempt_id, repo.repo.as_deref()).await?;
+            let request = CreateGitHubPrRequest {
+                title,
+                body,
+                target_branch: base,
+                draft: Some(draft),
+                repo_id: repo.repo.id,
+                auto_generate_description: auto_description,
+            };
+            let url: String = client
+                .post(&format!("task-attempts/{attempt_id}/pr"), &request)
+                .await?;
+            println!("{url}");
+        }
+    }
+    Ok(())
+}
+
+async fn respond_to_approval(
+    client: &VkClient,
+    approval_id: &str,
+    execution_process_id: Uuid,
+    status: ApprovalStatus,
+) -> Result<(), ClientError> {
+    let response = ApprovalResponse {
+        execution_process_id,
+        status,
+    };
+    let status: ApprovalStatus = client
+        .post_raw(&format!("approvals/{approval_id}/respond"), &response)
+        .await?;
+    println!("{status:?}");
+    Ok(())
+}
+
+async fn latest_coding_agent_process(
+    client: &VkClient,
+    attempt_id: Uuid,
+) -> Result<Uuid, ClientError> {
+    let processes: Vec<ExecutionProcess> = client
+        .get(&format!("execution-processes?workspace_id={attempt_id}"))
+        .await?;
+    processes
+        .iter()
+        .rev()
+        .find(|process| process.run_reason == ExecutionProcessRunReason::CodingAgent)
+        .or(processes.last())
+        .map(|process| process.id)
+        .ok_or_else(|| ClientError::Api(format!("attempt {attempt_id} has not run anything yet")))
+}
+
+async fn collect_diffs(mut stream: WsStream) -> Result<Vec<utils::diff::Diff>, ClientError> {
+    let mut collector = DiffCollector::default();
+    while let Ok(Some(message)) = tokio::time::timeout(DIFF_SETTLE, stream.next()).await {
+        let Message::Text(text) = message? else {
+            continue;
+        };
+        match render::parse_stream_message(&text) {
+            StreamMessage::Patch(operations) => collector.apply(operations),
+            StreamMessage::Finished => break,
+            StreamMessage::Other => {}
+        }
+    }
+    let _ = stream.close(None).await;
+    Ok(collector.into_diffs())
+}
+
+/// Turn `REPO[@BRANCH]` arguments into attempt repos. Without any, every repo of the task's
+/// project is used on the branch currently checked out in it.
+async fn resolve_attempt_repos(
+    client: &VkClient,
+    task_id: Uuid,
+    args: &[String],
+) -> Result<Vec<WorkspaceRepoInput>, ClientError> {
+    let task: Task = client.get(&format!("tasks/{task_id}")).await?;
+    let project_repos: Vec<Repo> = client
+        .get(&format!("projects/{}/repositories", task.project_id))
+        .await?;
+
+    let selected: Vec<(&Repo, Option<&str>)> = if args.is_empty() {
+        project_repos.iter().map(|repo| (repo, None)).collect()
+    } else {
+        args.iter()
+            .map(|arg| {
+                let (name, branch) = match arg.split_once('@') {
+                    Some((name, branch)) => (name, Some(branch)),
+                    None => (arg.as_str(), None),
+                };
+                find_repo(project_repos.iter(), name, |repo| *repo).map(|repo| (repo, branch))
+            })
+            .collect::<Result<_, _>>()?
+    };
+
+    let mut inputs = Vec::with_capacity(selected.len());
+    for (repo, branch) in selected {
+        let target_branch = match branch {
+            Some(branch) => branch.to_string(),
+            None => current_branch(client, repo).await?,
+        };
+        inputs.push(WorkspaceRepoInput {
+            repo_id: repo.id,
+            target_branch,
+        });
+    }
+    Ok(inputs)
+}
+
+async fn current_branch(client: &VkClient, repo: &Repo) -> Result<String, ClientError> {
+    let branches: Vec<BranchSummary> = client.get(&format!("repos/{}/branches", repo.id)).await?;
+    branches
+        .into_iter()
+        .find(|branch| branch.is_current && !branch.is_remote)
+        .map(|branch| branch.name)
+        .ok_or_else(|| {
+            ClientError::Api(format!(
+                "{} has no checked out branch; pass --repo {}@<branch>",
+                repo.name, repo.name
+            ))
+        })
+}
+
+async fn resolve_attempt_repo(
+    client: &VkClient,
+    attempt_id: Uuid,
+    arg: Option<&str>,
+) -> Result<RepoWithTargetBranch, ClientError> {
+    let repos: Vec<RepoWithTargetBranch> = client
+        .get(&format!("task-attempts/{attempt_id}/repos"))
+        .await?;
+    match arg {
+        Some(arg) => find_repo(repos.into_iter(), arg, |repo| &repo.repo),
+        None if repos.len() == 1 => Ok(repos.into_iter().next().unwrap()),
+        None => Err(ClientError::Api(format!(
+            "attempt spans several repositories, pick one with --repo: {}",
+            repos
+                .iter()
+                .map(|repo| repo.repo.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))),
+    }
+}
+
+/// Find a repository by id or name
+fn find_repo<T>(
+    mut candidates: impl Iterator<Item = T>,
+    arg: &str,
+    repo: impl Fn(&T) -> &Repo,
+) -> Result<T, ClientError> {
+    let id = Uuid::parse_str(arg).ok();
+    candidates
+        .find(|candidate| {
+            let repo = repo(candidate);
+            Some(repo.id) == id || repo.name == arg || repo.display_name == arg
+        })
+        .ok_or_else(|| ClientError::Api(format!("no repository '{arg}'")))
+}
@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use thiserror::Error;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async};
+use utils::port_file::read_port_file;
+
+pub type WsStream = WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>;
+
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("Could not find a running Vibe Kanban server: {0}")]
+    ServerNotFound(String),
+    #[error("Failed to reach the Vibe Kanban server: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("Failed to open a stream: {0}")]
+    WebSocket(#[from] tokio_tungstenite::tungstenite::Error),
+    #[error("{0}")]
+    Api(String),
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiResponseEnvelope {
+    success: bool,
+    data: Option<serde_json::Value>,
+    error_data: Option<serde_json::Value>,
+    message: Option<String>,
+}
+
+/// HTTP and WebSocket client for the local server API
+#[derive(Debug, Clone)]
+pub struct VkClient {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl VkClient {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Locate the server the same way the MCP task server does: `VIBE_BACKEND_URL`, then
+    /// `BACKEND_PORT`/`PORT`, then the port file written on startup.
+    pub async fn discover() -> Result<Self, ClientError> {
+        if let Ok(url) = std::env::var("VIBE_BACKEND_URL") {
+            return Ok(Self::new(&url));
+        }
+
+        let host = std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+        let port = match std::env::var("BACKEND_PORT").or_else(|_| std::env::var("PORT")) {
+            Ok(port) => port.parse::<u16>().map_err(|e| {
+                ClientError::ServerNotFound(format!("invalid port value '{port}': {e}"))
+            })?,
+            Err(_) => read_port_file("vibe-kanban").await.map_err(|e| {
+                ClientError::ServerNotFound(format!("no port file ({e}); is the server running?"))
+            })?,
+        };
+
+        Ok(Self::new(&format!("http://{host}:{port}")))
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/api/{}", self.base_url, path.trim_start_matches('/'))
+    }
+
+    pub async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, ClientError> {
+        self.send(self.client.get(self.url(path))).await
+    }
+
+    pub async fn post<B: Serialize + ?Sized, T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T, ClientError> {
+        self.send(self.client.post(self.url(path)).json(body)).await
+    }
+
+    /// POST to an endpoint that answers with a bare JSON value instead of an `ApiResponse`
+    pub async fn post_raw<B: Serialize + ?Sized, T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T, ClientError> {
+        let response = self.client.post(self.url(path)).json(body).send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(ClientError::Api(format!("server returned {status}")));
+        }
+        Ok(response.json().await?)
+    }
+
+    async fn send<T: DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<T, ClientError> {
+        let response = request.send().await?;
+        let status = response.status();
+        let body = response.text().await?;
+        let envelope: ApiResponseEnvelope = serde_json::from_str(&body).map_err(|_| {
+            ClientError::Api(if status.is_success() {
+                "unexpected response from server".to_string()
+            } else {
+                format!("server returned {status}")
+            })
+        })?;
+
+        if !envelope.success {
+            let message = envelope
+                .message
+                .or_else(|| envelope.error_data.map(|data| data.to_string()))
+                .unwrap_or_else(|| format!("server returned {status}"));
+            return Err(ClientError::Api(message));
+        }
+
+        // `()` payloads are serialized as `null`, so a missing value is only an error for
+        // types that cannot be built from it
+        serde_json::from_value(envelope.data.unwrap_or_default())
+            .map_err(|e| ClientError::Api(format!("unexpected response from server: {e}")))
+    }
+
+    pub async fn connect_ws(&self, path: &str) -> Result<WsStream, ClientError> {
+        let url = self
+            .url(path)
+            .replacen("http://", "ws://", 1)
+            .replacen("https://", "wss://", 1);
+        let (stream, _) = connect_async(url).await?;
+        Ok(stream)
+    }
+}
@@ -0,0 +1,5 @@
+//! Support code for the `vk` terminal client, which drives a running server over its HTTP
+//! and WebSocket API.
+
+pub mod client;
+pub mod render;
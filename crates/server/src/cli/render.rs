@@ -0,0 +1,262 @@
+use std::collections::{BTreeMap, HashSet};
+
+use executors::logs::{NormalizedEntry, NormalizedEntryType, ToolStatus};
+use serde::Deserialize;
+use utils::diff::{Diff, compute_line_change_counts, create_unified_diff};
+use uuid::Uuid;
+
+/// A frame of the `/normalized-logs/ws` and `/diff/ws` streams
+pub enum StreamMessage {
+    Patch(Vec<PatchOperation>),
+    Finished,
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PatchOperation {
+    pub op: String,
+    pub path: String,
+    #[serde(default)]
+    pub value: Option<serde_json::Value>,
+}
+
+impl PatchOperation {
+    /// Key of the entry this operation targets, e.g. the index of a log entry
+    fn key(&self) -> Option<&str> {
+        self.path.strip_prefix("/entries/")
+    }
+
+    fn entry(self) -> Option<StreamEntry> {
+        if self.op == "remove" {
+            return None;
+        }
+        serde_json::from_value(self.value?).ok()
+    }
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE", tag = "type", content = "content")]
+pub enum StreamEntry {
+    NormalizedEntry(NormalizedEntry),
+    Stdout(String),
+    Stderr(String),
+    Diff(Diff),
+}
+
+#[derive(Deserialize)]
+struct JsonPatchFrame {
+    #[serde(rename = "JsonPatch")]
+    json_patch: Vec<PatchOperation>,
+}
+
+pub fn parse_stream_message(text: &str) -> StreamMessage {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(text) else {
+        return StreamMessage::Other;
+    };
+    if value.get("finished").is_some() {
+        return StreamMessage::Finished;
+    }
+    match serde_json::from_value::<JsonPatchFrame>(value) {
+        Ok(frame) => StreamMessage::Patch(frame.json_patch),
+        Err(_) => StreamMessage::Other,
+    }
+}
+
+/// Prints a conversation as it streams in. Entries are rewritten in place while the agent
+/// is producing them, so each one is printed once the next entry starts or the stream ends.
+/// Pending approvals are printed straight away so they can be answered.
+pub struct LogPrinter {
+    execution_process_id: Uuid,
+    entries: Vec<Option<StreamEntry>>,
+    printed: usize,
+    announced_approvals: HashSet<String>,
+}
+
+impl LogPrinter {
+    pub fn new(execution_process_id: Uuid) -> Self {
+        Self {
+            execution_process_id,
+            entries: Vec::new(),
+            printed: 0,
+            announced_approvals: HashSet::new(),
+        }
+    }
+
+    pub fn apply(&mut self, operations: Vec<PatchOperation>) {
+        for operation in operations {
+            let Some(index) = operation.key().and_then(|key| key.parse::<usize>().ok()) else {
+                continue;
+            };
+            if index >= self.entries.len() {
+                self.entries.resize_with(index + 1, || None);
+            }
+            self.entries[index] = operation.entry();
+        }
+
+        while self.printed + 1 < self.entries.len() {
+            self.print_entry(self.printed);
+            self.printed += 1;
+        }
+        self.announce_approvals();
+    }
+
+    pub fn finish(&mut self) {
+        while self.printed < self.entries.len() {
+            self.print_entry(self.printed);
+            self.printed += 1;
+        }
+    }
+
+    fn print_entry(&self, index: usize) {
+        match &self.entries[index] {
+            Some(StreamEntry::NormalizedEntry(entry)) => {
+                if let Some(line) = format_entry(entry) {
+                    println!("{line}");
+                }
+            }
+            Some(StreamEntry::Stdout(line)) => println!("{line}"),
+            Some(StreamEntry::Stderr(line)) => eprintln!("{line}"),
+            Some(StreamEntry::Diff(_)) | None => {}
+        }
+    }
+
+    fn announce_approvals(&mut self) {
+        for entry in self.entries.iter().flatten() {
+            let StreamEntry::NormalizedEntry(NormalizedEntry {
+                entry_type:
+                    NormalizedEntryType::ToolUse {
+                        tool_name,
+                        status: ToolStatus::PendingApproval { approval_id, .. },
+                        ..
+                    },
+                content,
+                ..
+            }) = entry
+            else {
+                continue;
+            };
+            if self.announced_approvals.insert(approval_id.clone()) {
+                println!("[approval needed] {tool_name}: {content}");
+                println!(
+                    "  vk approve {approval_id} --process {}   |   vk deny {approval_id} --process {}",
+                    self.execution_process_id, self.execution_process_id
+                );
+            }
+        }
+    }
+}
+
+fn format_entry(entry: &NormalizedEntry) -> Option<String> {
+    let content = entry.content.trim_end();
+    let line = match &entry.entry_type {
+        NormalizedEntryType::UserMessage => format!("> {content}"),
+        NormalizedEntryType::UserFeedback { denied_tool } => {
+            format!("> [feedback on {denied_tool}] {content}")
+        }
+        NormalizedEntryType::AssistantMessage => content.to_string(),
+        NormalizedEntryType::ToolUse {
+            tool_name, status, ..
+        } => match status {
+            ToolStatus::Failed => format!("[{tool_name}] {content} (failed)"),
+            ToolStatus::Denied { reason } => match reason {
+                Some(reason) => format!("[{tool_name}] {content} (denied: {reason})"),
+                None => format!("[{tool_name}] {content} (denied)"),
+            },
+            ToolStatus::TimedOut => format!("[{tool_name}] {content} (timed out)"),
+            _ => format!("[{tool_name}] {content}"),
+        },
+        NormalizedEntryType::SystemMessage => format!("[system] {content}"),
+        NormalizedEntryType::ErrorMessage { .. } => format!("[error] {content}"),
+        NormalizedEntryType::Thinking => format!("[thinking] {content}"),
+        NormalizedEntryType::Loading | NormalizedEntryType::NextAction { .. } => return None,
+    };
+    Some(line)
+}
+
+/// Diffs of a workspace keyed by file path, as sent by the diff stream
+#[derive(Default)]
+pub struct DiffCollector {
+    diffs: BTreeMap<String, Diff>,
+}
+
+impl DiffCollector {
+    pub fn apply(&mut self, operations: Vec<PatchOperation>) {
+        for operation in operations {
+            let Some(key) = operation.key().map(str::to_string) else {
+                continue;
+            };
+            match operation.entry() {
+                Some(StreamEntry::Diff(diff)) => {
+                    self.diffs.insert(key, diff);
+                }
+                _ => {
+                    self.diffs.remove(&key);
+                }
+            }
+        }
+    }
+
+    pub fn into_diffs(self) -> Vec<Diff> {
+        self.diffs.into_values().collect()
+    }
+}
+
+fn diff_path(diff: &Diff) -> &str {
+    diff.new_path
+        .as_deref()
+        .or(diff.old_path.as_deref())
+        .unwrap_or_default()
+}
+
+/// `(additions, deletions)` of a diff, from the precomputed stats when content was omitted
+pub fn diff_stats(diff: &Diff) -> (usize, usize) {
+    if diff.content_omitted {
+        return (
+            diff.additions.unwrap_or_default(),
+            diff.deletions.unwrap_or_default(),
+        );
+    }
+    compute_line_change_counts(
+        diff.old_content.as_deref().unwrap_or_default(),
+        diff.new_content.as_deref().unwrap_or_default(),
+    )
+}
+
+pub fn print_diff_stat(diffs: &[Diff]) {
+    let (mut total_additions, mut total_deletions) = (0, 0);
+    for diff in diffs {
+        let (additions, deletions) = diff_stats(diff);
+        total_additions += additions;
+        total_deletions += deletions;
+        println!(
+            "{:>6} {:>6}  {}",
+            format!("+{additions}"),
+            format!("-{deletions}"),
+            diff_path(diff)
+        );
+    }
+    println!(
+        "{} file(s) changed, {total_additions} insertion(s), {total_deletions} deletion(s)",
+        diffs.len()
+    );
+}
+
+pub fn print_diff(diffs: &[Diff]) {
+    for diff in diffs {
+        let path = diff_path(diff);
+        if diff.content_omitted {
+            let (additions, deletions) = diff_stats(diff);
+            println!("--- {path} (content omitted, +{additions} -{deletions})");
+            continue;
+        }
+        print!(
+            "{}",
+            create_unified_diff(
+                path,
+                diff.old_content.as_deref().unwrap_or_default(),
+                diff.new_content.as_deref().unwrap_or_default(),
+            )
+        );
+    }
+}
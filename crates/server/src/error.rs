@@ -7,12 +7,13 @@ use axum::{
 use db::models::{
     execution_process::ExecutionProcessError, project::ProjectError,
     project_repo::ProjectRepoError, repo::RepoError, scratch::ScratchError, session::SessionError,
-    workspace::WorkspaceError,
+    task_dependency::TaskDependencyError, workspace::WorkspaceError,
 };
 use deployment::{DeploymentError, RemoteClientNotConfigured};
 use executors::executors::ExecutorError;
 use git2::Error as Git2Error;
 use services::services::{
+    checkpoint::CheckpointError,
     config::{ConfigError, EditorOpenError},
     container::ContainerError,
     git::GitServiceError,
@@ -22,6 +23,7 @@ use services::services::{
     remote_client::RemoteClientError,
     repo::RepoError as RepoServiceError,
     share::ShareError,
+    task_archive::TaskArchiveError,
     worktree_manager::WorktreeError,
 };
 use thiserror::Error;
@@ -60,6 +62,8 @@ pub enum ApiError {
     Config(#[from] ConfigError),
     #[error(transparent)]
     Image(#[from] ImageError),
+    #[error(transparent)]
+    TaskArchive(#[from] TaskArchiveError),
     #[error("Multipart error: {0}")]
     Multipart(#[from] MultipartError),
     #[error("IO error: {0}")]
@@ -122,7 +126,10 @@ impl IntoResponse for ApiError {
             },
             ApiError::GitHubService(_) => (StatusCode::INTERNAL_SERVER_ERROR, "GitHubServiceError"),
             ApiError::Deployment(_) => (StatusCode::INTERNAL_SERVER_ERROR, "DeploymentError"),
-            ApiError::Container(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ContainerError"),
+            ApiError::Container(err) => match err {
+                ContainerError::BudgetExceeded(_) => (StatusCode::CONFLICT, "BudgetExceeded"),
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "ContainerError"),
+            },
             ApiError::Executor(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ExecutorError"),
             ApiError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, "DatabaseError"),
             ApiError::Worktree(_) => (StatusCode::INTERNAL_SERVER_ERROR, "WorktreeError"),
@@ -133,6 +140,12 @@ impl IntoResponse for ApiError {
                 ImageError::NotFound => (StatusCode::NOT_FOUND, "ImageNotFound"),
                 _ => (StatusCode::INTERNAL_SERVER_ERROR, "ImageError"),
             },
+            ApiError::TaskArchive(err) => match err {
+                TaskArchiveError::InvalidArchive(_)
+                | TaskArchiveError::UnsupportedVersion(_)
+                | TaskArchiveError::Json(_) => (StatusCode::BAD_REQUEST, "InvalidTaskArchive"),
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "TaskArchiveError"),
+            },
             ApiError::Io(_) => (StatusCode::INTERNAL_SERVER_ERROR, "IoError"),
             ApiError::EditorOpen(err) => match err {
                 EditorOpenError::LaunchFailed { .. } => {
@@ -199,6 +212,7 @@ impl IntoResponse for ApiError {
                 }
                 _ => format!("{}: {}", error_type, self),
             },
+            ApiError::Container(ContainerError::BudgetExceeded(msg)) => msg.clone(),
             ApiError::Multipart(_) => "Failed to upload file. Please ensure the file is valid and try again.".to_string(),
             ApiError::RemoteClient(err) => match err {
                 RemoteClientError::Auth => "Unauthorized. Please sign in again.".to_string(),
@@ -389,3 +403,38 @@ impl From<ProjectRepoError> for ApiError {
         }
     }
 }
+
+impl From<TaskDependencyError> for ApiError {
+    fn from(err: TaskDependencyError) -> Self {
+        match err {
+            TaskDependencyError::Database(db_err) => ApiError::Database(db_err),
+            TaskDependencyError::NotFound => {
+                ApiError::BadRequest("Task dependency not found".to_string())
+            }
+            TaskDependencyError::BlockingTaskNotFound => {
+                ApiError::BadRequest("Blocking task not found".to_string())
+            }
+            TaskDependencyError::SelfDependency => {
+                ApiError::BadRequest("A task cannot depend on itself".to_string())
+            }
+            TaskDependencyError::CrossProject => ApiError::BadRequest(
+                "A task can only depend on tasks in the same project".to_string(),
+            ),
+            TaskDependencyError::AlreadyExists => {
+                ApiError::Conflict("Task dependency already exists".to_string())
+            }
+            TaskDependencyError::Cycle => {
+                ApiError::Conflict("Adding this dependency would create a cycle".to_string())
+            }
+        }
+    }
+}
+
+impl From<CheckpointError> for ApiError {
+    fn from(err: CheckpointError) -> Self {
+        match err {
+            CheckpointError::GitService(git_err) => ApiError::GitService(git_err),
+            CheckpointError::Database(db_err) => ApiError::Database(db_err),
+        }
+    }
+}
@@ -67,7 +67,120 @@ async fn main() -> Result<(), VibeKanbanError> {
         .backfill_repo_names()
         .await
         .map_err(DeploymentError::from)?;
-    deployment.spawn_pr_monitor_service().await;
+    deployment
+        .container()
+        .resume_queued_executions()
+        .await
+        .map_err(DeploymentError::from)?;
+    // Tasks moved to Done by a merged PR may unblock their dependents
+    let (task_done_tx, mut task_done_rx) = tokio::sync::mpsc::unbounded_channel();
+    // New review comments on open PRs are addressed by the workspace's agent when enabled
+    let (review_comments_tx, mut review_comments_rx) = tokio::sync::mpsc::unbounded_channel();
+    // Failing checks on open PRs are fixed by the workspace's agent when enabled
+    let (ci_fix_tx, mut ci_fix_rx) = tokio::sync::mpsc::unbounded_channel();
+    deployment
+        .spawn_pr_monitor_service(task_done_tx, review_comments_tx, ci_fix_tx)
+        .await;
+    let deployment_for_dependents = deployment.clone();
+    tokio::spawn(async move {
+        while let Some(task_id) = task_done_rx.recv().await {
+            deployment_for_dependents.container().task_done(task_id);
+        }
+    });
+    let deployment_for_reviews = deployment.clone();
+    tokio::spawn(async move {
+        while let Some(pending) = review_comments_rx.recv().await {
+            match deployment_for_reviews
+                .container()
+                .start_pending_review_follow_up(&pending)
+                .await
+            {
+                Ok(_) => {
+                    deployment_for_reviews
+                        .track_if_analytics_allowed(
+                            "pr_review_follow_up_started",
+                            serde_json::json!({
+                                "workspace_id": pending.workspace_id.to_string(),
+                                "repo_id": pending.repo_id.to_string(),
+                                "comment_count": pending.comments.len(),
+                                "reply_to_comments": pending.reply_to_comments,
+                                "trigger": "pr_monitor",
+                            }),
+                        )
+                        .await;
+                }
+                Err(e) => tracing::error!(
+                    "Failed to address review comments on PR #{} for workspace {}: {}",
+                    pending.pr_number,
+                    pending.workspace_id,
+                    e
+                ),
+            }
+        }
+    });
+    let deployment_for_ci_fixes = deployment.clone();
+    tokio::spawn(async move {
+        while let Some(pending) = ci_fix_rx.recv().await {
+            match deployment_for_ci_fixes
+                .container()
+                .start_ci_fix_follow_up(&pending)
+                .await
+            {
+                Ok(_) => {
+                    deployment_for_ci_fixes
+                        .track_if_analytics_allowed(
+                            "pr_ci_fix_started",
+                            serde_json::json!({
+                                "workspace_id": pending.workspace_id.to_string(),
+                                "repo_id": pending.repo_id.to_string(),
+                                "failed_check_count": pending.failed_checks.len(),
+                            }),
+                        )
+                        .await;
+                }
+                Err(e) => tracing::error!(
+                    "Failed to fix failing checks on PR #{} for workspace {}: {}",
+                    pending.pr_number,
+                    pending.workspace_id,
+                    e
+                ),
+            }
+        }
+    });
+    // Tasks created by due schedules are started with the schedule's executor
+    let (scheduled_task_tx, mut scheduled_task_rx) = tokio::sync::mpsc::unbounded_channel();
+    deployment
+        .spawn_task_schedule_service(scheduled_task_tx)
+        .await;
+    let deployment_for_schedules = deployment.clone();
+    tokio::spawn(async move {
+        while let Some(scheduled) = scheduled_task_rx.recv().await {
+            match deployment_for_schedules
+                .container()
+                .start_scheduled_task(&scheduled)
+                .await
+            {
+                Ok(workspace) => {
+                    deployment_for_schedules
+                        .track_if_analytics_allowed(
+                            "scheduled_task_started",
+                            serde_json::json!({
+                                "task_schedule_id": scheduled.schedule.id.to_string(),
+                                "task_id": scheduled.task.id.to_string(),
+                                "workspace_id": workspace.id.to_string(),
+                            }),
+                        )
+                        .await;
+                }
+                Err(e) => tracing::error!(
+                    "Failed to start task {} from schedule {}: {}",
+                    scheduled.task.id,
+                    scheduled.schedule.id,
+                    e
+                ),
+            }
+        }
+    });
     deployment
         .track_if_analytics_allowed("session_start", serde_json::json!({}))
         .await;
@@ -3,6 +3,7 @@ use std::{future::Future, str::FromStr};
 use db::models::{
     project::Project,
     repo::Repo,
+    search::SearchHit,
     tag::Tag,
     task::{CreateTask, Task, TaskStatus, TaskWithAttemptStatus, UpdateTask},
     workspace::{Workspace, WorkspaceContext},
@@ -251,6 +252,55 @@ pub struct GetTaskResponse {
     pub task: TaskDetails,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SearchHistoryRequest {
+    #[schemars(
+        description = "Words to search for in task titles and descriptions, agent prompts and summaries, and agent messages"
+    )]
+    pub query: String,
+    #[schemars(description = "Optional project ID to restrict the search to")]
+    pub project_id: Option<Uuid>,
+    #[schemars(description = "Maximum number of hits to return (default: 20, max: 100)")]
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct SearchHistoryHit {
+    #[schemars(description = "Where the match was found: 'task', 'turn' or 'message'")]
+    pub source: String,
+    pub project_id: String,
+    pub task_id: String,
+    pub task_title: String,
+    #[schemars(description = "Workspace (task attempt) the match belongs to, if any")]
+    pub workspace_id: Option<String>,
+    #[schemars(description = "Execution process the match belongs to, if any")]
+    pub execution_process_id: Option<String>,
+    #[schemars(description = "Matching text, with matched terms wrapped in **")]
+    pub snippet: String,
+    pub created_at: String,
+}
+
+impl SearchHistoryHit {
+    fn from_search_hit(hit: SearchHit) -> Self {
+        Self {
+            source: hit.source.to_string(),
+            project_id: hit.project_id.to_string(),
+            task_id: hit.task_id.to_string(),
+            task_title: hit.task_title,
+            workspace_id: hit.workspace_id.map(|id| id.to_string()),
+            execution_process_id: hit.execution_process_id.map(|id| id.to_string()),
+            snippet: hit.snippet,
+            created_at: hit.created_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct SearchHistoryResponse {
+    pub hits: Vec<SearchHistoryHit>,
+    pub count: usize,
+}
+
 #[derive(Debug, Clone)]
 pub struct TaskServer {
     client: reqwest::Client,
@@ -701,6 +751,7 @@ impl TaskServer {
             task_id,
             executor_profile_id,
             repos: workspace_repos,
+            pipeline_template_id: None,
         };
 
         let url = self.url("/api/task-attempts");
@@ -808,12 +859,54 @@ impl TaskServer {
 
         TaskServer::success(&response)
     }
+
+    #[tool(
+        description = "Full-text search over past tasks, agent prompts, summaries and agent messages. Returns the best matches first, with the task, workspace and execution process they belong to."
+    )]
+    async fn search_history(
+        &self,
+        Parameters(SearchHistoryRequest {
+            query,
+            project_id,
+            limit,
+        }): Parameters<SearchHistoryRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if query.trim().is_empty() {
+            return Self::err("Search query must not be empty.".to_string(), None);
+        }
+
+        let url = self.url("/api/search");
+        let mut params = vec![("q", query)];
+        if let Some(project_id) = project_id {
+            params.push(("project_id", project_id.to_string()));
+        }
+        if let Some(limit) = limit {
+            params.push(("limit", limit.to_string()));
+        }
+
+        let hits: Vec<SearchHit> = match self.send_json(self.client.get(&url).query(&params)).await
+        {
+            Ok(hits) => hits,
+            Err(e) => return Ok(e),
+        };
+
+        let hits: Vec<SearchHistoryHit> = hits
+            .into_iter()
+            .map(SearchHistoryHit::from_search_hit)
+            .collect();
+        let response = SearchHistoryResponse {
+            count: hits.len(),
+            hits,
+        };
+
+        TaskServer::success(&response)
+    }
 }
 
 #[tool_handler]
 impl ServerHandler for TaskServer {
     fn get_info(&self) -> ServerInfo {
-        let mut instruction = "A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. You can get project ids by using `list projects`. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project`.. TOOLS: 'list_projects', 'list_tasks', 'create_task', 'start_workspace_session', 'get_task', 'update_task', 'delete_task', 'list_repos'. Make sure to pass `project_id` or `task_id` where required. You can use list tools to get the available ids.".to_string();
+        let mut instruction = "A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. You can get project ids by using `list projects`. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project`.. TOOLS: 'list_projects', 'list_tasks', 'create_task', 'start_workspace_session', 'get_task', 'update_task', 'delete_task', 'list_repos', 'search_history'. Use 'search_history' to find earlier work by keyword. Make sure to pass `project_id` or `task_id` where required. You can use list tools to get the available ids.".to_string();
         if self.context.is_some() {
             let context_instruction = "Use 'get_context' to fetch project/task/workspace metadata for the active Vibe Kanban workspace session when available.";
             instruction = format!("{} {}", context_instruction, instruction);
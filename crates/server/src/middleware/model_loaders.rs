@@ -5,8 +5,9 @@ use axum::{
     response::Response,
 };
 use db::models::{
-    execution_process::ExecutionProcess, project::Project, session::Session, tag::Tag, task::Task,
-    workspace::Workspace,
+    approval_rule::ApprovalRule, attempt_group::AttemptGroup, execution_process::ExecutionProcess,
+    pipeline_template::PipelineTemplate, project::Project, session::Session, tag::Tag, task::Task,
+    task_schedule::TaskSchedule, webhook::Webhook, workspace::Workspace,
 };
 use deployment::Deployment;
 use uuid::Uuid;
@@ -169,3 +170,134 @@ pub async fn load_session_middleware(
     request.extensions_mut().insert(session);
     Ok(next.run(request).await)
 }
+
+// Middleware that loads and injects PipelineTemplate based on the pipeline_template_id path
+// parameter
+pub async fn load_pipeline_template_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path(pipeline_template_id): Path<Uuid>,
+    request: axum::extract::Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let template =
+        match PipelineTemplate::find_by_id(&deployment.db().pool, pipeline_template_id).await {
+            Ok(Some(template)) => template,
+            Ok(None) => {
+                tracing::warn!("Pipeline template {} not found", pipeline_template_id);
+                return Err(StatusCode::NOT_FOUND);
+            }
+            Err(e) => {
+                tracing::error!(
+                    "Failed to fetch pipeline template {}: {}",
+                    pipeline_template_id,
+                    e
+                );
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        };
+
+    let mut request = request;
+    request.extensions_mut().insert(template);
+
+    Ok(next.run(request).await)
+}
+
+// Middleware that loads and injects Webhook based on the webhook_id path parameter
+pub async fn load_webhook_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path(webhook_id): Path<Uuid>,
+    request: axum::extract::Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let webhook = match Webhook::find_by_id(&deployment.db().pool, webhook_id).await {
+        Ok(Some(webhook)) => webhook,
+        Ok(None) => {
+            tracing::warn!("Webhook {} not found", webhook_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch webhook {}: {}", webhook_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let mut request = request;
+    request.extensions_mut().insert(webhook);
+
+    Ok(next.run(request).await)
+}
+
+// Middleware that loads and injects AttemptGroup based on the attempt_group_id path parameter
+pub async fn load_attempt_group_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path(attempt_group_id): Path<Uuid>,
+    request: axum::extract::Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let group = match AttemptGroup::find_by_id(&deployment.db().pool, attempt_group_id).await {
+        Ok(Some(group)) => group,
+        Ok(None) => {
+            tracing::warn!("Attempt group {} not found", attempt_group_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch attempt group {}: {}", attempt_group_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let mut request = request;
+    request.extensions_mut().insert(group);
+
+    Ok(next.run(request).await)
+}
+
+// Middleware that loads and injects TaskSchedule based on the task_schedule_id path parameter
+pub async fn load_task_schedule_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path(task_schedule_id): Path<Uuid>,
+    request: axum::extract::Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let schedule = match TaskSchedule::find_by_id(&deployment.db().pool, task_schedule_id).await {
+        Ok(Some(schedule)) => schedule,
+        Ok(None) => {
+            tracing::warn!("Task schedule {} not found", task_schedule_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch task schedule {}: {}", task_schedule_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let mut request = request;
+    request.extensions_mut().insert(schedule);
+
+    Ok(next.run(request).await)
+}
+
+// Middleware that loads and injects ApprovalRule based on the approval_rule_id path parameter
+pub async fn load_approval_rule_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path(approval_rule_id): Path<Uuid>,
+    request: axum::extract::Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let rule = match ApprovalRule::find_by_id(&deployment.db().pool, approval_rule_id).await {
+        Ok(Some(rule)) => rule,
+        Ok(None) => {
+            tracing::warn!("Approval rule {} not found", approval_rule_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch approval rule {}: {}", approval_rule_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let mut request = request;
+    request.extensions_mut().insert(rule);
+
+    Ok(next.run(request).await)
+}
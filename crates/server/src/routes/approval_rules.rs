@@ -0,0 +1,134 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Query, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::get,
+};
+use db::models::approval_rule::{ApprovalRule, CreateApprovalRule, UpdateApprovalRule};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::approvals::policy;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_approval_rule_middleware};
+
+#[derive(Debug, Deserialize)]
+pub struct ApprovalRuleQuery {
+    pub project_id: Uuid,
+}
+
+/// The pattern, unless it is unset or empty
+fn pattern(value: &Option<String>) -> Option<&str> {
+    value.as_deref().filter(|value| !value.is_empty())
+}
+
+fn validate_patterns(
+    tool_name: Option<&str>,
+    path_glob: Option<&str>,
+    command_regex: Option<&str>,
+) -> Result<(), ApiError> {
+    policy::validate(tool_name, path_glob, command_regex).map_err(ApiError::BadRequest)
+}
+
+pub async fn get_approval_rules(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ApprovalRuleQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<ApprovalRule>>>, ApiError> {
+    let rules = ApprovalRule::find_by_project_id(&deployment.db().pool, query.project_id).await?;
+    Ok(ResponseJson(ApiResponse::success(rules)))
+}
+
+pub async fn get_approval_rule(
+    Extension(rule): Extension<ApprovalRule>,
+) -> Result<ResponseJson<ApiResponse<ApprovalRule>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(rule)))
+}
+
+pub async fn create_approval_rule(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateApprovalRule>,
+) -> Result<ResponseJson<ApiResponse<ApprovalRule>>, ApiError> {
+    if payload.name.trim().is_empty() {
+        return Err(ApiError::BadRequest(
+            "An approval rule needs a name".to_string(),
+        ));
+    }
+    validate_patterns(
+        pattern(&payload.tool_name),
+        pattern(&payload.path_glob),
+        pattern(&payload.command_regex),
+    )?;
+
+    let rule = ApprovalRule::create(&deployment.db().pool, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "approval_rule_created",
+            serde_json::json!({
+                "approval_rule_id": rule.id.to_string(),
+                "project_id": rule.project_id.to_string(),
+                "decision": rule.decision,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(rule)))
+}
+
+pub async fn update_approval_rule(
+    Extension(rule): Extension<ApprovalRule>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateApprovalRule>,
+) -> Result<ResponseJson<ApiResponse<ApprovalRule>>, ApiError> {
+    if payload
+        .name
+        .as_deref()
+        .is_some_and(|name| name.trim().is_empty())
+    {
+        return Err(ApiError::BadRequest(
+            "An approval rule needs a name".to_string(),
+        ));
+    }
+    validate_patterns(
+        pattern(&payload.tool_name),
+        pattern(&payload.path_glob),
+        pattern(&payload.command_regex),
+    )?;
+
+    let updated = ApprovalRule::update(&deployment.db().pool, rule.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(updated)))
+}
+
+pub async fn delete_approval_rule(
+    Extension(rule): Extension<ApprovalRule>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = ApprovalRule::delete(&deployment.db().pool, rule.id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let approval_rule_router = Router::new()
+        .route(
+            "/",
+            get(get_approval_rule)
+                .put(update_approval_rule)
+                .delete(delete_approval_rule),
+        )
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_approval_rule_middleware,
+        ));
+
+    let inner = Router::new()
+        .route("/", get(get_approval_rules).post(create_approval_rule))
+        .nest("/{approval_rule_id}", approval_rule_router);
+
+    Router::new().nest("/approval-rules", inner)
+}
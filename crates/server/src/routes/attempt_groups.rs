@@ -0,0 +1,213 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Query, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::models::{
+    attempt_group::{AttemptGroup, AttemptGroupMember},
+    task::Task,
+    workspace::Workspace,
+};
+use deployment::Deployment;
+use executors::profile::ExecutorProfileId;
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+use services::services::{
+    attempt_group::{self, AttemptComparison},
+    container::ContainerService,
+};
+use sqlx::Error as SqlxError;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_attempt_group_middleware,
+    routes::task_attempts::{WorkspaceRepoInput, create_workspace},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct AttemptGroupQuery {
+    pub task_id: Uuid,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct CreateAttemptGroupBody {
+    pub task_id: Uuid,
+    /// One attempt is started per profile, all on the same repos and target branches
+    pub executor_profile_ids: Vec<ExecutorProfileId>,
+    pub repos: Vec<WorkspaceRepoInput>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct CreateAttemptGroupResponse {
+    pub group: AttemptGroup,
+    pub workspaces: Vec<Workspace>,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct PickAttemptGroupWinnerBody {
+    pub workspace_id: Uuid,
+}
+
+pub async fn get_attempt_groups(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<AttemptGroupQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<AttemptGroup>>>, ApiError> {
+    let groups = AttemptGroup::find_by_task_id(&deployment.db().pool, query.task_id).await?;
+    Ok(ResponseJson(ApiResponse::success(groups)))
+}
+
+pub async fn get_attempt_group(
+    Extension(group): Extension<AttemptGroup>,
+) -> Result<ResponseJson<ApiResponse<AttemptGroup>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(group)))
+}
+
+/// Start one attempt of the task per executor profile and run them side by side
+pub async fn create_attempt_group(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateAttemptGroupBody>,
+) -> Result<ResponseJson<ApiResponse<CreateAttemptGroupResponse>>, ApiError> {
+    if payload.executor_profile_ids.len() < 2 {
+        return Err(ApiError::BadRequest(
+            "At least two executor profiles are required to compare attempts".to_string(),
+        ));
+    }
+    if payload.repos.is_empty() {
+        return Err(ApiError::BadRequest(
+            "At least one repository is required".to_string(),
+        ));
+    }
+
+    let pool = &deployment.db().pool;
+    let task = Task::find_by_id(pool, payload.task_id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+
+    let group = AttemptGroup::create(pool, Uuid::new_v4(), task.id).await?;
+    let mut workspaces = Vec::with_capacity(payload.executor_profile_ids.len());
+    for executor_profile_id in &payload.executor_profile_ids {
+        let workspace = create_workspace(&deployment, &task, &payload.repos).await?;
+        AttemptGroupMember::create(pool, group.id, workspace.id, executor_profile_id).await?;
+        workspaces.push(workspace);
+    }
+
+    let starts = workspaces.iter().zip(&payload.executor_profile_ids).map(
+        |(workspace, executor_profile_id)| {
+            deployment
+                .container()
+                .start_workspace(workspace, executor_profile_id.clone(), None)
+        },
+    );
+    for (workspace, result) in workspaces.iter().zip(join_all(starts).await) {
+        if let Err(err) = result {
+            tracing::error!("Failed to start attempt {}: {}", workspace.id, err);
+        }
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "attempt_group_started",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "attempt_group_id": group.id.to_string(),
+                "attempt_count": workspaces.len(),
+                "executors": payload
+                    .executor_profile_ids
+                    .iter()
+                    .map(|profile| &profile.executor)
+                    .collect::<Vec<_>>(),
+                "repository_count": payload.repos.len(),
+            }),
+        )
+        .await;
+
+    tracing::info!(
+        "Started {} attempts for task {} in group {}",
+        workspaces.len(),
+        task.id,
+        group.id
+    );
+
+    Ok(ResponseJson(ApiResponse::success(
+        CreateAttemptGroupResponse { group, workspaces },
+    )))
+}
+
+/// Diff stats, verification outcome, duration and token usage of each attempt of the group
+pub async fn get_attempt_group_comparison(
+    Extension(group): Extension<AttemptGroup>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<AttemptComparison>>, ApiError> {
+    let prices = deployment.config().read().await.model_prices.clone();
+    let comparison =
+        attempt_group::compare(deployment.db(), deployment.git(), &prices, group).await?;
+    Ok(ResponseJson(ApiResponse::success(comparison)))
+}
+
+/// Keep one attempt of the group. The others are stopped and their worktrees are removed by
+/// the next workspace cleanup.
+pub async fn pick_attempt_group_winner(
+    Extension(group): Extension<AttemptGroup>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<PickAttemptGroupWinnerBody>,
+) -> Result<ResponseJson<ApiResponse<AttemptComparison>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let members = AttemptGroupMember::find_by_attempt_group_id(pool, group.id).await?;
+    if !members
+        .iter()
+        .any(|member| member.workspace_id == payload.workspace_id)
+    {
+        return Err(ApiError::BadRequest(
+            "The workspace is not an attempt of this group".to_string(),
+        ));
+    }
+
+    let group = AttemptGroup::set_winner(pool, group.id, payload.workspace_id).await?;
+    for member in members
+        .iter()
+        .filter(|member| member.workspace_id != payload.workspace_id)
+    {
+        if let Some(workspace) = Workspace::find_by_id(pool, member.workspace_id).await? {
+            deployment.container().try_stop(&workspace, true).await;
+        }
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "attempt_group_winner_picked",
+            serde_json::json!({
+                "attempt_group_id": group.id.to_string(),
+                "workspace_id": payload.workspace_id.to_string(),
+                "attempt_count": members.len(),
+            }),
+        )
+        .await;
+
+    let prices = deployment.config().read().await.model_prices.clone();
+    let comparison =
+        attempt_group::compare(deployment.db(), deployment.git(), &prices, group).await?;
+    Ok(ResponseJson(ApiResponse::success(comparison)))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let attempt_group_router = Router::new()
+        .route("/", get(get_attempt_group))
+        .route("/comparison", get(get_attempt_group_comparison))
+        .route("/winner", post(pick_attempt_group_winner))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_attempt_group_middleware,
+        ));
+
+    let inner = Router::new()
+        .route("/", get(get_attempt_groups).post(create_attempt_group))
+        .nest("/{attempt_group_id}", attempt_group_router);
+
+    Router::new().nest("/attempt-groups", inner)
+}
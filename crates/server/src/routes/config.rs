@@ -18,10 +18,13 @@ use executors::{
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use services::services::config::{
-    Config, ConfigError, SoundFile,
-    editor::{EditorConfig, EditorType},
-    save_config_to_file,
+use services::services::{
+    config::{
+        Config, ConfigError, SoundFile,
+        editor::{EditorConfig, EditorType},
+        save_config_to_file,
+    },
+    container::ContainerService,
 };
 use tokio::fs;
 use ts_rs::TS;
@@ -183,6 +186,12 @@ async fn handle_config_events(deployment: &DeploymentImpl, old: &Config, new: &C
             deployment_clone.trigger_auto_project_setup().await;
         });
     }
+
+    // Raised concurrency limits free slots that no finishing execution would hand out
+    let deployment_clone = deployment.clone();
+    tokio::spawn(async move {
+        deployment_clone.container().admit_queued_executions().await;
+    });
 }
 
 async fn get_sound(Path(sound): Path<SoundFile>) -> Result<Response, ApiError> {
@@ -5,7 +5,9 @@ use axum::{
 
 use crate::DeploymentImpl;
 
+pub mod approval_rules;
 pub mod approvals;
+pub mod attempt_groups;
 pub mod config;
 pub mod containers;
 pub mod filesystem;
@@ -17,14 +19,18 @@ pub mod health;
 pub mod images;
 pub mod oauth;
 pub mod organizations;
+pub mod pipeline_templates;
 pub mod projects;
 pub mod repo;
 pub mod scratch;
+pub mod search;
 pub mod sessions;
 pub mod shared_tasks;
 pub mod tags;
 pub mod task_attempts;
+pub mod task_schedules;
 pub mod tasks;
+pub mod webhooks;
 
 pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
     // Create routers with different middleware layers
@@ -36,15 +42,21 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .merge(tasks::router(&deployment))
         .merge(shared_tasks::router())
         .merge(task_attempts::router(&deployment))
+        .merge(attempt_groups::router(&deployment))
         .merge(execution_processes::router(&deployment))
         .merge(tags::router(&deployment))
+        .merge(pipeline_templates::router(&deployment))
+        .merge(webhooks::router(&deployment))
+        .merge(task_schedules::router(&deployment))
         .merge(oauth::router())
         .merge(organizations::router())
         .merge(filesystem::router())
         .merge(repo::router())
         .merge(events::router(&deployment))
         .merge(approvals::router())
+        .merge(approval_rules::router(&deployment))
         .merge(scratch::router(&deployment))
+        .merge(search::router())
         .merge(sessions::router(&deployment))
         .nest("/images", images::routes())
         .with_state(deployment);
@@ -0,0 +1,108 @@
+use axum::{
+    Extension, Json, Router,
+    extract::State,
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, put},
+};
+use db::models::pipeline_template::{
+    CreatePipelineTemplate, PipelineStep, PipelineTemplate, UpdatePipelineTemplate,
+};
+use deployment::Deployment;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_pipeline_template_middleware};
+
+fn validate_steps(steps: &[PipelineStep]) -> Result<(), ApiError> {
+    if steps.is_empty() {
+        return Err(ApiError::BadRequest(
+            "A pipeline needs at least one step".to_string(),
+        ));
+    }
+    if steps.iter().any(|step| step.prompt.trim().is_empty()) {
+        return Err(ApiError::BadRequest(
+            "Every pipeline step needs a prompt".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub async fn get_pipeline_templates(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<PipelineTemplate>>>, ApiError> {
+    let templates = PipelineTemplate::find_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(templates)))
+}
+
+pub async fn get_pipeline_template(
+    Extension(template): Extension<PipelineTemplate>,
+) -> Result<ResponseJson<ApiResponse<PipelineTemplate>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(template)))
+}
+
+pub async fn create_pipeline_template(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreatePipelineTemplate>,
+) -> Result<ResponseJson<ApiResponse<PipelineTemplate>>, ApiError> {
+    validate_steps(&payload.steps)?;
+    let template = PipelineTemplate::create(&deployment.db().pool, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "pipeline_template_created",
+            serde_json::json!({
+                "pipeline_template_id": template.id.to_string(),
+                "step_count": template.steps.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(template)))
+}
+
+pub async fn update_pipeline_template(
+    Extension(template): Extension<PipelineTemplate>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdatePipelineTemplate>,
+) -> Result<ResponseJson<ApiResponse<PipelineTemplate>>, ApiError> {
+    if let Some(steps) = &payload.steps {
+        validate_steps(steps)?;
+    }
+    let updated = PipelineTemplate::update(&deployment.db().pool, template.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(updated)))
+}
+
+pub async fn delete_pipeline_template(
+    Extension(template): Extension<PipelineTemplate>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = PipelineTemplate::delete(&deployment.db().pool, template.id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let template_router = Router::new()
+        .route(
+            "/",
+            put(update_pipeline_template)
+                .get(get_pipeline_template)
+                .delete(delete_pipeline_template),
+        )
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_pipeline_template_middleware,
+        ));
+
+    let inner = Router::new()
+        .route(
+            "/",
+            get(get_pipeline_templates).post(create_pipeline_template),
+        )
+        .nest("/{pipeline_template_id}", template_router);
+
+    Router::new().nest("/pipeline-templates", inner)
+}
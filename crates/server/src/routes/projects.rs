@@ -13,6 +13,8 @@ use axum::{
     routing::{get, post},
 };
 use db::models::{
+    execution_budget::{BudgetLimits, ExecutionBudget},
+    execution_process_usage::ExecutionProcessUsage,
     project::{CreateProject, Project, ProjectError, SearchResult, UpdateProject},
     project_repo::{CreateProjectRepo, ProjectRepo, UpdateProjectRepo},
     repo::Repo,
@@ -21,8 +23,10 @@ use deployment::Deployment;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::Deserialize;
 use services::services::{
-    file_search_cache::SearchQuery, project::ProjectServiceError,
+    file_search_cache::SearchQuery,
+    project::ProjectServiceError,
     remote_client::CreateRemoteProjectPayload,
+    usage::{self, UsageSummary},
 };
 use ts_rs::TS;
 use utils::{
@@ -266,6 +270,12 @@ pub async fn update_project(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<UpdateProject>,
 ) -> Result<ResponseJson<ApiResponse<Project>>, StatusCode> {
+    if let Some(sandbox_config) = &payload.sandbox_config
+        && let Err(e) = sandbox_config.validate()
+    {
+        return Ok(ResponseJson(ApiResponse::error(&e.to_string())));
+    }
+
     match deployment
         .project()
         .update_project(&deployment.db().pool, &existing_project, payload)
@@ -431,6 +441,63 @@ pub async fn get_project_repositories(
     Ok(ResponseJson(ApiResponse::success(repositories)))
 }
 
+/// Token usage and estimated cost across all tasks of the project
+pub async fn get_project_usage(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<UsageSummary>>, ApiError> {
+    let buckets =
+        ExecutionProcessUsage::find_buckets_for_project(&deployment.db().pool, project.id).await?;
+    let prices = deployment.config().read().await.model_prices.clone();
+    Ok(ResponseJson(ApiResponse::success(usage::summarize(
+        &buckets, &prices,
+    ))))
+}
+
+/// Execution budget shared by the project's tasks; all `None` when none is set
+pub async fn get_project_budget(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<BudgetLimits>>, ApiError> {
+    let limits = ExecutionBudget::find_by_project_id(&deployment.db().pool, project.id)
+        .await?
+        .map(|budget| budget.limits())
+        .unwrap_or_default();
+    Ok(ResponseJson(ApiResponse::success(limits)))
+}
+
+/// Replace the project's execution budget; all `None` removes it
+pub async fn set_project_budget(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(limits): Json<BudgetLimits>,
+) -> Result<ResponseJson<ApiResponse<BudgetLimits>>, ApiError> {
+    if !limits.is_valid() {
+        return Err(ApiError::BadRequest(
+            "Budget limits must be positive".to_string(),
+        ));
+    }
+
+    let pool = &deployment.db().pool;
+    if limits.is_unlimited() {
+        ExecutionBudget::delete_for_project(pool, project.id).await?;
+    } else {
+        ExecutionBudget::set_for_project(pool, project.id, &limits).await?;
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_budget_updated",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "unlimited": limits.is_unlimited(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(limits)))
+}
+
 pub async fn add_project_repository(
     Extension(project): Extension<Project>,
     State(deployment): State<DeploymentImpl>,
@@ -590,6 +657,8 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         )
         .route("/remote/members", get(get_project_remote_members))
         .route("/search", get(search_project_files))
+        .route("/usage", get(get_project_usage))
+        .route("/budget", get(get_project_budget).put(set_project_budget))
         .route("/open-editor", post(open_project_in_editor))
         .route(
             "/link",
@@ -1,6 +1,9 @@
 use axum::{
-    Extension, Json, Router, extract::State, middleware::from_fn_with_state,
-    response::Json as ResponseJson, routing::get,
+    Extension, Json, Router,
+    extract::{Path, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, post, put},
 };
 use db::models::{scratch::DraftFollowUpData, session::Session};
 use deployment::Deployment;
@@ -8,6 +11,7 @@ use serde::Deserialize;
 use services::services::queued_message::QueueStatus;
 use ts_rs::TS;
 use utils::response::ApiResponse;
+use uuid::Uuid;
 
 use crate::{DeploymentImpl, error::ApiError, middleware::load_session_middleware};
 
@@ -18,7 +22,14 @@ pub struct QueueMessageRequest {
     pub variant: Option<String>,
 }
 
-/// Queue a follow-up message to be executed when the current execution finishes
+/// Request body for reordering the queue; must list every queued message once
+#[derive(Debug, Deserialize, TS)]
+pub struct ReorderQueueRequest {
+    pub message_ids: Vec<Uuid>,
+}
+
+/// Add a follow-up message to the end of the queue, to be executed once the executions
+/// before it finish
 pub async fn queue_message(
     Extension(session): Extension<Session>,
     State(deployment): State<DeploymentImpl>,
@@ -29,9 +40,10 @@ pub async fn queue_message(
         variant: payload.variant,
     };
 
-    let queued = deployment
+    deployment
         .queued_message_service()
-        .queue_message(session.id, data);
+        .queue_message(session.id, data)
+        .await?;
 
     deployment
         .track_if_analytics_allowed(
@@ -43,19 +55,22 @@ pub async fn queue_message(
         )
         .await;
 
-    Ok(ResponseJson(ApiResponse::success(QueueStatus::Queued {
-        message: queued,
-    })))
+    let status = deployment
+        .queued_message_service()
+        .get_status(session.id)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(status)))
 }
 
-/// Cancel a queued follow-up message
+/// Cancel every queued follow-up message
 pub async fn cancel_queued_message(
     Extension(session): Extension<Session>,
     State(deployment): State<DeploymentImpl>,
 ) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
     deployment
         .queued_message_service()
-        .cancel_queued(session.id);
+        .cancel_queued(session.id)
+        .await?;
 
     deployment
         .track_if_analytics_allowed(
@@ -75,11 +90,86 @@ pub async fn get_queue_status(
     Extension(session): Extension<Session>,
     State(deployment): State<DeploymentImpl>,
 ) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
-    let status = deployment.queued_message_service().get_status(session.id);
+    let status = deployment
+        .queued_message_service()
+        .get_status(session.id)
+        .await?;
 
     Ok(ResponseJson(ApiResponse::success(status)))
 }
 
+/// The message was never queued for the session, or has been sent or removed since
+fn not_queued() -> ApiError {
+    ApiError::BadRequest("Message is not queued for this session".to_string())
+}
+
+/// Edit the message or variant of a queued follow-up
+pub async fn update_queued_message(
+    State(deployment): State<DeploymentImpl>,
+    Path((session_id, message_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<QueueMessageRequest>,
+) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
+    let data = DraftFollowUpData {
+        message: payload.message,
+        variant: payload.variant,
+    };
+
+    deployment
+        .queued_message_service()
+        .update_queued(session_id, message_id, data)
+        .await?
+        .ok_or_else(not_queued)?;
+
+    let status = deployment
+        .queued_message_service()
+        .get_status(session_id)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
+/// Remove a single queued follow-up
+pub async fn delete_queued_message(
+    State(deployment): State<DeploymentImpl>,
+    Path((session_id, message_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
+    let removed = deployment
+        .queued_message_service()
+        .remove_queued(session_id, message_id)
+        .await?;
+    if !removed {
+        return Err(not_queued());
+    }
+
+    let status = deployment
+        .queued_message_service()
+        .get_status(session_id)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
+/// Change the order in which the queued follow-ups are sent
+pub async fn reorder_queue(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ReorderQueueRequest>,
+) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
+    let reordered = deployment
+        .queued_message_service()
+        .reorder_queued(session.id, &payload.message_ids)
+        .await?;
+    if !reordered {
+        return Err(ApiError::BadRequest(
+            "The new order must list every queued message exactly once".to_string(),
+        ));
+    }
+
+    let status = deployment
+        .queued_message_service()
+        .get_status(session.id)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     Router::new()
         .route(
@@ -88,8 +178,13 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
                 .post(queue_message)
                 .delete(cancel_queued_message),
         )
+        .route("/reorder", post(reorder_queue))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_session_middleware,
         ))
+        .route(
+            "/{message_id}",
+            put(update_queued_message).delete(delete_queued_message),
+        )
 }
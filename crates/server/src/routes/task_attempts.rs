@@ -1,3 +1,4 @@
+pub mod checkpoints;
 pub mod codex_setup;
 pub mod cursor_setup;
 pub mod gh_cli_setup;
@@ -24,10 +25,12 @@ use axum::{
 use db::models::{
     execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
     merge::{Merge, MergeStatus, PrMerge, PullRequestInfo},
+    pipeline_template::PipelineTemplate,
+    project::{MergeMode, ProjectError},
     project_repo::ProjectRepo,
     repo::{Repo, RepoError},
     session::{CreateSession, Session},
-    task::{Task, TaskRelationships, TaskStatus},
+    task::{Task, TaskRelationships},
     workspace::{CreateWorkspace, Workspace, WorkspaceError},
     workspace_repo::{CreateWorkspaceRepo, RepoWithTargetBranch, WorkspaceRepo},
 };
@@ -43,17 +46,20 @@ use executors::{
 use git2::BranchType;
 use serde::{Deserialize, Serialize};
 use services::services::{
+    commit_message::to_conventional,
     container::ContainerService,
     git::{ConflictOp, GitCliError, GitServiceError},
     github::GitHubService,
 };
-use sqlx::Error as SqlxError;
+use sqlx::{Error as SqlxError, SqlitePool};
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
 use crate::{
-    DeploymentImpl, error::ApiError, middleware::load_workspace_middleware,
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_workspace_middleware,
     routes::task_attempts::gh_cli_setup::GhCliSetupError,
 };
 
@@ -69,6 +75,11 @@ pub struct AbortConflictsRequest {
     pub repo_id: Uuid,
 }
 
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct ResolveConflictsRequest {
+    pub repo_id: Uuid,
+}
+
 #[derive(Debug, Serialize, Deserialize, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
 #[ts(tag = "type", rename_all = "snake_case")]
@@ -108,6 +119,9 @@ pub struct CreateTaskAttemptBody {
     pub task_id: Uuid,
     pub executor_profile_id: ExecutorProfileId,
     pub repos: Vec<WorkspaceRepoInput>,
+    /// Run the steps of this pipeline template instead of a single coding agent request
+    #[serde(default)]
+    pub pipeline_template_id: Option<Uuid>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ts_rs::TS)]
@@ -124,24 +138,31 @@ pub struct RunAgentSetupRequest {
 #[derive(Debug, Serialize, TS)]
 pub struct RunAgentSetupResponse {}
 
-#[axum::debug_handler]
-pub async fn create_task_attempt(
-    State(deployment): State<DeploymentImpl>,
-    Json(payload): Json<CreateTaskAttemptBody>,
-) -> Result<ResponseJson<ApiResponse<Workspace>>, ApiError> {
-    let executor_profile_id = payload.executor_profile_id.clone();
-
-    if payload.repos.is_empty() {
-        return Err(ApiError::BadRequest(
-            "At least one repository is required".to_string(),
-        ));
-    }
-
-    let pool = &deployment.db().pool;
-    let task = Task::find_by_id(&deployment.db().pool, payload.task_id)
+/// Load a pipeline template to start a workspace with, rejecting templates without steps
+pub async fn load_pipeline_template(
+    pool: &SqlitePool,
+    id: Uuid,
+) -> Result<PipelineTemplate, ApiError> {
+    let template = PipelineTemplate::find_by_id(pool, id)
         .await?
         .ok_or(SqlxError::RowNotFound)?;
+    if template.steps.is_empty() {
+        return Err(ApiError::BadRequest(format!(
+            "Pipeline '{}' has no steps",
+            template.name
+        )));
+    }
+    Ok(template)
+}
 
+/// Create a workspace for `task` on a fresh branch, with the given repos attached. The
+/// workspace is not started.
+pub async fn create_workspace(
+    deployment: &DeploymentImpl,
+    task: &Task,
+    repos: &[WorkspaceRepoInput],
+) -> Result<Workspace, ApiError> {
+    let pool = &deployment.db().pool;
     let project = task
         .parent_project(pool)
         .await?
@@ -166,12 +187,11 @@ pub async fn create_task_attempt(
             agent_working_dir,
         },
         attempt_id,
-        payload.task_id,
+        task.id,
     )
     .await?;
 
-    let workspace_repos: Vec<CreateWorkspaceRepo> = payload
-        .repos
+    let workspace_repos: Vec<CreateWorkspaceRepo> = repos
         .iter()
         .map(|r| CreateWorkspaceRepo {
             repo_id: r.repo_id,
@@ -180,9 +200,37 @@ pub async fn create_task_attempt(
         .collect();
 
     WorkspaceRepo::create_many(pool, workspace.id, &workspace_repos).await?;
+
+    Ok(workspace)
+}
+
+#[axum::debug_handler]
+pub async fn create_task_attempt(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateTaskAttemptBody>,
+) -> Result<ResponseJson<ApiResponse<Workspace>>, ApiError> {
+    let executor_profile_id = payload.executor_profile_id.clone();
+
+    if payload.repos.is_empty() {
+        return Err(ApiError::BadRequest(
+            "At least one repository is required".to_string(),
+        ));
+    }
+
+    let pool = &deployment.db().pool;
+    let task = Task::find_by_id(&deployment.db().pool, payload.task_id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+
+    let pipeline = match payload.pipeline_template_id {
+        Some(id) => Some(load_pipeline_template(pool, id).await?),
+        None => None,
+    };
+
+    let workspace = create_workspace(&deployment, &task, &payload.repos).await?;
     if let Err(err) = deployment
         .container()
-        .start_workspace(&workspace, executor_profile_id.clone())
+        .start_workspace(&workspace, executor_profile_id.clone(), pipeline.as_ref())
         .await
     {
         tracing::error!("Failed to start task attempt: {}", err);
@@ -302,6 +350,54 @@ async fn handle_task_attempt_diff_ws(
     Ok(())
 }
 
+/// Stream the merges of the attempt, so PR status and CI state show up as the PR monitor
+/// records them
+pub async fn stream_task_attempt_merges_ws(
+    ws: WebSocketUpgrade,
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| async move {
+        if let Err(e) = handle_task_attempt_merges_ws(socket, deployment, workspace.id).await {
+            tracing::warn!("merges WS closed: {}", e);
+        }
+    })
+}
+
+async fn handle_task_attempt_merges_ws(
+    socket: WebSocket,
+    deployment: DeploymentImpl,
+    workspace_id: Uuid,
+) -> anyhow::Result<()> {
+    use futures_util::{SinkExt, StreamExt, TryStreamExt};
+
+    let mut stream = deployment
+        .events()
+        .stream_merges_for_workspace_raw(workspace_id)
+        .await?
+        .map_ok(|msg| msg.to_ws_message_unchecked());
+
+    let (mut sender, mut receiver) = socket.split();
+
+    // Drain (and ignore) any client->server messages so pings/pongs work
+    tokio::spawn(async move { while let Some(Ok(_)) = receiver.next().await {} });
+
+    while let Some(item) = stream.next().await {
+        match item {
+            Ok(msg) => {
+                if sender.send(msg).await.is_err() {
+                    break; // client disconnected
+                }
+            }
+            Err(e) => {
+                tracing::error!("stream error: {}", e);
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
 #[derive(Debug, Deserialize, Serialize, TS)]
 pub struct MergeTaskAttemptRequest {
     pub repo_id: Uuid,
@@ -320,6 +416,8 @@ pub async fn merge_task_attempt(
 ) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
     let pool = &deployment.db().pool;
 
+    util::ensure_no_remote_run(&deployment, &workspace).await?;
+
     let workspace_repo =
         WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, request.repo_id)
             .await?
@@ -353,15 +451,39 @@ pub async fn merge_task_attempt(
         commit_message.push_str(description);
     }
 
-    let merge_commit_id = deployment.git().merge_changes(
+    let project = task
+        .parent_project(pool)
+        .await?
+        .ok_or(ProjectError::ProjectNotFound)?;
+    // Fast-forwarding keeps the task branch's own commits, so there is no message to write
+    let conventional_commits =
+        project.conventional_commits && project.merge_mode != MergeMode::Rebase;
+    let mut diff = String::new();
+    if conventional_commits {
+        diff = deployment
+            .git()
+            .branch_diff(
+                &worktree_path,
+                &workspace.branch,
+                &workspace_repo.target_branch,
+            )
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to diff branch {}: {}", workspace.branch, e);
+                String::new()
+            });
+        commit_message = to_conventional(&commit_message);
+    }
+
+    let merge_commit_id = deployment.git().merge_changes_with_mode(
         &repo.path,
         &worktree_path,
         &workspace.branch,
         &workspace_repo.target_branch,
         &commit_message,
+        project.merge_mode,
     )?;
 
-    Merge::create_direct(
+    let merge = Merge::create_direct(
         pool,
         workspace.id,
         workspace_repo.repo_id,
@@ -369,7 +491,68 @@ pub async fn merge_task_attempt(
         &merge_commit_id,
     )
     .await?;
-    Task::update_status(pool, task.id, TaskStatus::Done).await?;
+
+    // Writing the message can take minutes, so the merge lands with the fallback and is
+    // reworded once the agent answers, unless the target branch has moved on since
+    if conventional_commits {
+        let deployment = deployment.clone();
+        let session = Session::find_latest_by_workspace_id(pool, workspace.id).await?;
+        let repo_path = repo.path.clone();
+        let task_branch = workspace.branch.clone();
+        let target_branch = workspace_repo.target_branch.clone();
+        let task_id = task.id;
+        let project = project.clone();
+        tokio::spawn(async move {
+            let Some(message) = deployment
+                .container()
+                .generate_commit_message(
+                    &project,
+                    task_id,
+                    session.map(|session| session.id),
+                    &diff,
+                )
+                .await
+            else {
+                return;
+            };
+            let reworded = match deployment.git().reword_branch_head(
+                &repo_path,
+                &target_branch,
+                &merge_commit_id,
+                &message,
+            ) {
+                Ok(Some(reworded)) => reworded,
+                Ok(None) => {
+                    tracing::debug!(
+                        "Branch {} moved on, keeping the message of merge {}",
+                        target_branch,
+                        merge_commit_id
+                    );
+                    return;
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to reword merge {}: {}", merge_commit_id, e);
+                    return;
+                }
+            };
+            // The task branch points at the merge as well. Rewording is deterministic, so
+            // this lands on the same commit.
+            if let Err(e) = deployment.git().reword_branch_head(
+                &repo_path,
+                &task_branch,
+                &merge_commit_id,
+                &message,
+            ) {
+                tracing::warn!("Failed to move branch {}: {}", task_branch, e);
+            }
+            if let Err(e) =
+                Merge::update_merge_commit(&deployment.db().pool, merge.id, &reworded).await
+            {
+                tracing::warn!("Failed to record reworded merge {}: {}", reworded, e);
+            }
+        });
+    }
+    deployment.container().mark_task_done(task.id).await?;
 
     // Stop any running dev servers for this workspace
     let dev_servers =
@@ -518,6 +701,8 @@ pub async fn open_task_attempt_in_editor(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<OpenEditorRequest>,
 ) -> Result<ResponseJson<ApiResponse<OpenEditorResponse>>, ApiError> {
+    util::ensure_no_remote_run(&deployment, &workspace).await?;
+
     let container_ref = deployment
         .container()
         .ensure_container_exists(&workspace)
@@ -979,6 +1164,8 @@ pub async fn rebase_task_attempt(
 ) -> Result<ResponseJson<ApiResponse<(), GitOperationError>>, ApiError> {
     let pool = &deployment.db().pool;
 
+    util::ensure_no_remote_run(&deployment, &workspace).await?;
+
     let workspace_repo =
         WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, payload.repo_id)
             .await?
@@ -1076,6 +1263,8 @@ pub async fn abort_conflicts_task_attempt(
 ) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
     let pool = &deployment.db().pool;
 
+    util::ensure_no_remote_run(&deployment, &workspace).await?;
+
     let repo = Repo::find_by_id(pool, payload.repo_id)
         .await?
         .ok_or(RepoError::NotFound)?;
@@ -1092,6 +1281,58 @@ pub async fn abort_conflicts_task_attempt(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+/// Dispatch a coding agent to resolve the conflicts of an interrupted rebase or merge.
+/// The operation is continued automatically once the agent leaves no conflict markers.
+#[axum::debug_handler]
+pub async fn resolve_conflicts_task_attempt(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ResolveConflictsRequest>,
+) -> Result<ResponseJson<ApiResponse<ExecutionProcess>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let repo = Repo::find_by_id(pool, payload.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    let worktree_path = Path::new(&container_ref).join(&repo.name);
+
+    if deployment
+        .git()
+        .get_conflicted_files(&worktree_path)?
+        .is_empty()
+    {
+        return Ok(ResponseJson(ApiResponse::error(
+            "There are no conflicts to resolve",
+        )));
+    }
+
+    // Reload so the container ref set by `ensure_container_exists` is visible
+    let workspace = Workspace::find_by_id(pool, workspace.id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    let execution_process = deployment
+        .container()
+        .start_conflict_resolution(&workspace, &repo)
+        .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempt_conflict_resolution_started",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "repo_id": repo.id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(execution_process)))
+}
+
 #[axum::debug_handler]
 pub async fn start_dev_server(
     Extension(workspace): Extension<Workspace>,
@@ -1493,13 +1734,16 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/branch-status", get(get_task_attempt_branch_status))
         .route("/diff/ws", get(stream_task_attempt_diff_ws))
         .route("/merge", post(merge_task_attempt))
+        .route("/merges/ws", get(stream_task_attempt_merges_ws))
         .route("/push", post(push_task_attempt_branch))
         .route("/push/force", post(force_push_task_attempt_branch))
         .route("/rebase", post(rebase_task_attempt))
         .route("/conflicts/abort", post(abort_conflicts_task_attempt))
+        .route("/conflicts/resolve", post(resolve_conflicts_task_attempt))
         .route("/pr", post(pr::create_github_pr))
         .route("/pr/attach", post(pr::attach_existing_pr))
         .route("/pr/comments", get(pr::get_pr_comments))
+        .route("/pr/address-comments", post(pr::address_pr_comments))
         .route("/open-editor", post(open_task_attempt_in_editor))
         .route("/children", get(get_task_attempt_children))
         .route("/stop", post(stop_task_attempt_execution))
@@ -1514,7 +1758,8 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let task_attempts_router = Router::new()
         .route("/", get(get_task_attempts).post(create_task_attempt))
         .nest("/{id}", task_attempt_id_router)
-        .nest("/{id}/images", images::router(deployment));
+        .nest("/{id}/images", images::router(deployment))
+        .nest("/{id}/checkpoints", checkpoints::router(deployment));
 
     Router::new().nest("/task-attempts", task_attempts_router)
 }
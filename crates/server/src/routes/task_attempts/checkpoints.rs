@@ -0,0 +1,211 @@
+use std::path::PathBuf;
+
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, Query, Request, State},
+    http::StatusCode,
+    middleware::{Next, from_fn_with_state},
+    response::{Json as ResponseJson, Response},
+    routing::{delete, get, post},
+};
+use db::models::{
+    execution_process::ExecutionProcess,
+    workspace::Workspace,
+    workspace_checkpoint::{CreateWorkspaceCheckpoint, WorkspaceCheckpoint},
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::{
+    checkpoint::{self, CheckpointRepoDiff},
+    container::ContainerService,
+};
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_workspace_middleware};
+
+#[derive(Debug, Deserialize)]
+pub struct CheckpointDiffQuery {
+    pub from: Uuid,
+    /// Omitted to compare against the current worktrees
+    pub to: Option<Uuid>,
+}
+
+async fn workspace_dir(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+) -> Result<PathBuf, ApiError> {
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(workspace)
+        .await?;
+    Ok(PathBuf::from(container_ref))
+}
+
+async fn find_workspace_checkpoint(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    checkpoint_id: Uuid,
+) -> Result<WorkspaceCheckpoint, ApiError> {
+    WorkspaceCheckpoint::find_by_id(&deployment.db().pool, checkpoint_id)
+        .await?
+        .filter(|checkpoint| checkpoint.workspace_id == workspace.id)
+        .ok_or_else(|| {
+            ApiError::BadRequest(format!(
+                "Checkpoint {checkpoint_id} does not belong to this attempt"
+            ))
+        })
+}
+
+pub async fn get_checkpoints(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<WorkspaceCheckpoint>>>, ApiError> {
+    let checkpoints =
+        WorkspaceCheckpoint::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(checkpoints)))
+}
+
+pub async fn create_checkpoint(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateWorkspaceCheckpoint>,
+) -> Result<ResponseJson<ApiResponse<WorkspaceCheckpoint>>, ApiError> {
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::BadRequest(
+            "A checkpoint needs a name".to_string(),
+        ));
+    }
+    let workspace_dir = workspace_dir(&deployment, &workspace).await?;
+    let checkpoint = checkpoint::create(
+        &deployment.db().pool,
+        deployment.git(),
+        &workspace,
+        &workspace_dir,
+        name,
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "workspace_checkpoint_created",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "repo_count": checkpoint.repo_states.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(checkpoint)))
+}
+
+pub async fn get_checkpoint_diff(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<CheckpointDiffQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<CheckpointRepoDiff>>>, ApiError> {
+    let from = find_workspace_checkpoint(&deployment, &workspace, query.from).await?;
+    let to = match query.to {
+        Some(to) => Some(find_workspace_checkpoint(&deployment, &workspace, to).await?),
+        None => None,
+    };
+    let workspace_dir = workspace_dir(&deployment, &workspace).await?;
+    let diffs = checkpoint::diff(
+        &deployment.db().pool,
+        deployment.git(),
+        &workspace,
+        &workspace_dir,
+        &from,
+        to.as_ref(),
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(diffs)))
+}
+
+/// Returns the checkpoint of the state the worktrees were in before the restore
+pub async fn restore_checkpoint(
+    Extension(workspace): Extension<Workspace>,
+    Extension(checkpoint): Extension<WorkspaceCheckpoint>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<WorkspaceCheckpoint>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+        .await?
+    {
+        return Err(ApiError::Conflict(
+            "Stop the running processes before restoring a checkpoint".to_string(),
+        ));
+    }
+    let workspace_dir = workspace_dir(&deployment, &workspace).await?;
+    let backup = checkpoint::restore(
+        pool,
+        deployment.git(),
+        &workspace,
+        &workspace_dir,
+        &checkpoint,
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "workspace_checkpoint_restored",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "checkpoint_id": checkpoint.id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(backup)))
+}
+
+pub async fn delete_checkpoint(
+    Extension(checkpoint): Extension<WorkspaceCheckpoint>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    checkpoint::delete(&deployment.db().pool, deployment.git(), &checkpoint).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+async fn load_workspace_checkpoint(
+    State(deployment): State<DeploymentImpl>,
+    Path((id, checkpoint_id)): Path<(Uuid, Uuid)>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let pool = &deployment.db().pool;
+    let workspace = match Workspace::find_by_id(pool, id).await {
+        Ok(Some(workspace)) => workspace,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+    let checkpoint = match WorkspaceCheckpoint::find_by_id(pool, checkpoint_id).await {
+        Ok(Some(checkpoint)) if checkpoint.workspace_id == workspace.id => checkpoint,
+        Ok(_) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+    request.extensions_mut().insert(workspace);
+    request.extensions_mut().insert(checkpoint);
+    Ok(next.run(request).await)
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let checkpoints_router = Router::new()
+        .route("/", get(get_checkpoints).post(create_checkpoint))
+        .route("/diff", get(get_checkpoint_diff))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_workspace_middleware,
+        ));
+
+    let checkpoint_router = Router::new()
+        .route("/{checkpoint_id}", delete(delete_checkpoint))
+        .route("/{checkpoint_id}/restore", post(restore_checkpoint))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_workspace_checkpoint,
+        ));
+
+    checkpoints_router.merge(checkpoint_router)
+}
@@ -7,10 +7,11 @@ use axum::{
 };
 use db::models::{
     execution_process::{ExecutionProcess, ExecutionProcessRunReason},
-    merge::{Merge, MergeStatus},
+    merge::{Merge, MergeStatus, PullRequestInfo},
+    pr_review_follow_up::PrReviewFollowUp,
     repo::{Repo, RepoError},
     session::{CreateSession, Session},
-    task::{Task, TaskStatus},
+    webhook::WebhookEvent,
     workspace::{Workspace, WorkspaceError},
     workspace_repo::WorkspaceRepo,
 };
@@ -25,6 +26,7 @@ use services::services::{
     container::ContainerService,
     git::{GitCliError, GitServiceError},
     github::UnifiedPrComment,
+    pr_review::needs_addressing,
     vcs_provider::{
         CreatePrRequest as VcsCreatePrRequest, VcsProviderError, VcsProviderRegistry,
         VcsProviderType,
@@ -58,6 +60,8 @@ pub enum CreatePrError {
     TargetBranchNotFound { branch: String },
     BitbucketAuthRequired,
     BitbucketAuthFailed { message: String },
+    GitlabAuthRequired,
+    GitlabAuthFailed { message: String },
     UnsupportedVcsProvider { message: String },
 }
 
@@ -88,7 +92,10 @@ pub enum GetPrCommentsError {
     GithubCliNotLoggedIn,
     BitbucketAuthRequired,
     BitbucketAuthFailed { message: String },
+    GitlabAuthRequired,
+    GitlabAuthFailed { message: String },
     UnsupportedVcsProvider { message: String },
+    NoCommentsToAddress,
 }
 
 #[derive(Debug, Deserialize, TS)]
@@ -96,6 +103,17 @@ pub struct GetPrCommentsQuery {
     pub repo_id: Uuid,
 }
 
+#[derive(Debug, Deserialize, TS)]
+pub struct AddressPrCommentsRequest {
+    pub repo_id: Uuid,
+    /// Keys of the comments to address, see `UnifiedPrComment::key`; all comments no earlier
+    /// follow-up addressed when unset
+    pub comment_ids: Option<Vec<String>>,
+    /// Reply to each addressed comment with the commit that addressed it
+    #[serde(default)]
+    pub reply_to_comments: bool,
+}
+
 pub const DEFAULT_PR_DESCRIPTION_PROMPT: &str = r#"Update the GitHub PR that was just created with a better title and description.
 The PR number is #{pr_number} and the URL is {pr_url}.
 
@@ -297,7 +315,7 @@ pub async fn create_github_pr(
         draft: request.draft,
     };
 
-    // Use GitService to get VCS repo info (auto-detects GitHub/Bitbucket)
+    // Use GitService to get VCS repo info (auto-detects GitHub/Bitbucket/GitLab)
     let repo_info = deployment.git().get_vcs_repo_info(&repo_path)?;
 
     // Get appropriate VCS provider based on remote URL
@@ -325,6 +343,17 @@ pub async fn create_github_pr(
             {
                 tracing::error!("Failed to update workspace PR status: {}", e);
             }
+            deployment.webhooks().emit(
+                WebhookEvent::PrCreated,
+                serde_json::json!({
+                    "task_id": workspace.task_id,
+                    "workspace_id": workspace.id,
+                    "repo_id": workspace_repo.repo_id,
+                    "pr_number": pr_info.number,
+                    "pr_url": pr_info.url,
+                    "target_branch": norm_target_branch_name,
+                }),
+            );
 
             // Auto-open PR in browser
             if let Err(e) = utils::browser::open_browser(&pr_info.url).await {
@@ -335,6 +364,7 @@ pub async fn create_github_pr(
             let event_name = match repo_info.provider_type {
                 VcsProviderType::GitHub => "github_pr_created",
                 VcsProviderType::BitbucketServer => "bitbucket_pr_created",
+                VcsProviderType::GitLab => "gitlab_mr_created",
             };
             deployment
                 .track_if_analytics_allowed(
@@ -386,11 +416,21 @@ pub async fn create_github_pr(
                                 message: e.to_string(),
                             }),
                         )),
+                        VcsProviderType::GitLab => Ok(ResponseJson(
+                            ApiResponse::error_with_data(CreatePrError::GitlabAuthFailed {
+                                message: e.to_string(),
+                            }),
+                        )),
                     }
                 }
-                VcsProviderError::AuthRequired(_) => Ok(ResponseJson(
-                    ApiResponse::error_with_data(CreatePrError::BitbucketAuthRequired),
-                )),
+                VcsProviderError::AuthRequired(_) => match repo_info.provider_type {
+                    VcsProviderType::GitLab => Ok(ResponseJson(ApiResponse::error_with_data(
+                        CreatePrError::GitlabAuthRequired,
+                    ))),
+                    _ => Ok(ResponseJson(ApiResponse::error_with_data(
+                        CreatePrError::BitbucketAuthRequired,
+                    ))),
+                },
                 VcsProviderError::UnsupportedProvider(msg) => Ok(ResponseJson(
                     ApiResponse::error_with_data(CreatePrError::UnsupportedVcsProvider {
                         message: msg.clone(),
@@ -478,7 +518,7 @@ pub async fn attach_existing_pr(
 
         // If PR is merged, mark task as done
         if matches!(pr_info.status, MergeStatus::Merged) {
-            Task::update_status(pool, task.id, TaskStatus::Done).await?;
+            deployment.container().mark_task_done(task.id).await?;
 
             // Try broadcast update to other users in organization
             if let Ok(publisher) = deployment.share_publisher() {
@@ -513,34 +553,30 @@ pub async fn attach_existing_pr(
     }
 }
 
-pub async fn get_pr_comments(
-    Extension(workspace): Extension<Workspace>,
-    State(deployment): State<DeploymentImpl>,
-    Query(query): Query<GetPrCommentsQuery>,
-) -> Result<ResponseJson<ApiResponse<PrCommentsResponse, GetPrCommentsError>>, ApiError> {
+/// The attached PR of a workspace repo and its comments, or why they can't be fetched
+async fn fetch_pr_comments(
+    deployment: &DeploymentImpl,
+    workspace_id: Uuid,
+    repo_id: Uuid,
+) -> Result<Result<(Repo, PullRequestInfo, Vec<UnifiedPrComment>), GetPrCommentsError>, ApiError> {
     let pool = &deployment.db().pool;
 
     // Look up the specific repo using the multi-repo pattern
-    let workspace_repo =
-        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, query.repo_id)
-            .await?
-            .ok_or(RepoError::NotFound)?;
+    let workspace_repo = WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace_id, repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
 
     let repo = Repo::find_by_id(pool, workspace_repo.repo_id)
         .await?
         .ok_or(RepoError::NotFound)?;
 
     // Find the merge/PR for this specific repo
-    let merges = Merge::find_by_workspace_and_repo_id(pool, workspace.id, query.repo_id).await?;
+    let merges = Merge::find_by_workspace_and_repo_id(pool, workspace_id, repo_id).await?;
 
     // Ensure there's an attached PR for this repo
     let pr_info = match merges.into_iter().next() {
         Some(Merge::Pr(pr_merge)) => pr_merge.pr_info,
-        _ => {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                GetPrCommentsError::NoPrAttached,
-            )));
-        }
+        _ => return Ok(Err(GetPrCommentsError::NoPrAttached)),
     };
 
     // Use VcsProviderRegistry to detect and use appropriate provider
@@ -557,42 +593,132 @@ pub async fn get_pr_comments(
 
     // Fetch comments from VCS provider
     match provider.get_pr_comments(&repo_info, pr_info.number).await {
-        Ok(comments) => Ok(ResponseJson(ApiResponse::success(PrCommentsResponse {
-            comments,
-        }))),
+        Ok(comments) => Ok(Ok((repo, pr_info, comments))),
         Err(e) => {
             tracing::error!(
                 "Failed to fetch PR comments for attempt {}, PR #{}: {}",
-                workspace.id,
+                workspace_id,
                 pr_info.number,
                 e
             );
             match &e {
-                VcsProviderError::GhCliNotInstalled => Ok(ResponseJson(
-                    ApiResponse::error_with_data(GetPrCommentsError::GithubCliNotInstalled),
-                )),
-                VcsProviderError::AuthFailed(_) => {
-                    match repo_info.provider_type {
-                        VcsProviderType::GitHub => Ok(ResponseJson(
-                            ApiResponse::error_with_data(GetPrCommentsError::GithubCliNotLoggedIn),
-                        )),
-                        VcsProviderType::BitbucketServer => Ok(ResponseJson(
-                            ApiResponse::error_with_data(GetPrCommentsError::BitbucketAuthFailed {
-                                message: e.to_string(),
-                            }),
-                        )),
-                    }
+                VcsProviderError::GhCliNotInstalled => {
+                    Ok(Err(GetPrCommentsError::GithubCliNotInstalled))
                 }
-                VcsProviderError::AuthRequired(_) => Ok(ResponseJson(
-                    ApiResponse::error_with_data(GetPrCommentsError::BitbucketAuthRequired),
-                )),
-                VcsProviderError::UnsupportedProvider(msg) => Ok(ResponseJson(
-                    ApiResponse::error_with_data(GetPrCommentsError::UnsupportedVcsProvider {
+                VcsProviderError::AuthFailed(_) => match repo_info.provider_type {
+                    VcsProviderType::GitHub => Ok(Err(GetPrCommentsError::GithubCliNotLoggedIn)),
+                    VcsProviderType::BitbucketServer => {
+                        Ok(Err(GetPrCommentsError::BitbucketAuthFailed {
+                            message: e.to_string(),
+                        }))
+                    }
+                    VcsProviderType::GitLab => Ok(Err(GetPrCommentsError::GitlabAuthFailed {
+                        message: e.to_string(),
+                    })),
+                },
+                VcsProviderError::AuthRequired(_) => match repo_info.provider_type {
+                    VcsProviderType::GitLab => Ok(Err(GetPrCommentsError::GitlabAuthRequired)),
+                    _ => Ok(Err(GetPrCommentsError::BitbucketAuthRequired)),
+                },
+                VcsProviderError::UnsupportedProvider(msg) => {
+                    Ok(Err(GetPrCommentsError::UnsupportedVcsProvider {
                         message: msg.clone(),
-                    }),
-                )),
+                    }))
+                }
                 _ => Err(ApiError::BadRequest(format!("VCS provider error: {}", e))),
             }
         }
     }
 }
+
+pub async fn get_pr_comments(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<GetPrCommentsQuery>,
+) -> Result<ResponseJson<ApiResponse<PrCommentsResponse, GetPrCommentsError>>, ApiError> {
+    match fetch_pr_comments(&deployment, workspace.id, query.repo_id).await? {
+        Ok((_, _, comments)) => Ok(ResponseJson(ApiResponse::success(PrCommentsResponse {
+            comments,
+        }))),
+        Err(error) => Ok(ResponseJson(ApiResponse::error_with_data(error))),
+    }
+}
+
+/// Dispatch the coding agent to address the review comments of a workspace repo's PR that no
+/// earlier follow-up addressed. Its commits are pushed to the PR once it finishes.
+pub async fn address_pr_comments(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<AddressPrCommentsRequest>,
+) -> Result<ResponseJson<ApiResponse<ExecutionProcess, GetPrCommentsError>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let (repo, pr_info, comments) =
+        match fetch_pr_comments(&deployment, workspace.id, payload.repo_id).await? {
+            Ok(fetched) => fetched,
+            Err(error) => return Ok(ResponseJson(ApiResponse::error_with_data(error))),
+        };
+
+    let addressed =
+        PrReviewFollowUp::addressed_comment_keys(pool, workspace.id, payload.repo_id).await?;
+    let comments: Vec<UnifiedPrComment> = comments
+        .into_iter()
+        .filter(needs_addressing)
+        .filter(|comment| !addressed.contains(&comment.key()))
+        .filter(|comment| {
+            payload
+                .comment_ids
+                .as_ref()
+                .is_none_or(|keys| keys.contains(&comment.key()))
+        })
+        .collect();
+    if comments.is_empty() {
+        return Ok(ResponseJson(ApiResponse::error_with_data(
+            GetPrCommentsError::NoCommentsToAddress,
+        )));
+    }
+
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+        .await?
+    {
+        return Err(ApiError::Conflict(
+            "Wait for the running processes to finish before addressing review comments"
+                .to_string(),
+        ));
+    }
+
+    deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    // Reload so the container ref set by `ensure_container_exists` is visible
+    let workspace = Workspace::find_by_id(pool, workspace.id)
+        .await?
+        .ok_or(sqlx::Error::RowNotFound)?;
+    let execution_process = deployment
+        .container()
+        .start_pr_review_follow_up(
+            &workspace,
+            &repo,
+            pr_info.number,
+            &pr_info.url,
+            &comments,
+            payload.reply_to_comments,
+        )
+        .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "pr_review_follow_up_started",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "repo_id": repo.id.to_string(),
+                "comment_count": comments.len(),
+                "reply_to_comments": payload.reply_to_comments,
+                "trigger": "manual",
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(execution_process)))
+}
@@ -11,6 +11,27 @@ use uuid::Uuid;
 
 use crate::{DeploymentImpl, error::ApiError};
 
+/// Refuse changes to the files of a workspace while a process runs on the remote host. The
+/// workspace is mirrored back from there, which would overwrite them.
+pub async fn ensure_no_remote_run(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+) -> Result<(), ApiError> {
+    if deployment.container().runs_remotely()
+        && ExecutionProcess::has_running_non_dev_server_processes_for_workspace(
+            &deployment.db().pool,
+            workspace.id,
+        )
+        .await?
+    {
+        return Err(ApiError::Conflict(
+            "Wait for the process running on the remote host to finish before changing the workspace"
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
+
 /// Reset all repository worktrees to the state before the given process.
 /// For each repo, finds the before_head_commit from the target process,
 /// or falls back to the previous process's after_head_commit.
@@ -22,6 +43,9 @@ pub async fn restore_worktrees_to_process(
     perform_git_reset: bool,
     force_when_dirty: bool,
 ) -> Result<(), ApiError> {
+    if perform_git_reset {
+        ensure_no_remote_run(deployment, workspace).await?;
+    }
     let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
 
     // Get all repo states for the target process
@@ -0,0 +1,200 @@
+use std::collections::HashSet;
+
+use axum::{
+    Extension, Json, Router,
+    extract::{Query, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::get,
+};
+use chrono::{DateTime, Utc};
+use db::models::{
+    project_repo::ProjectRepo,
+    task_schedule::{CreateTaskSchedule, TaskSchedule, TaskScheduleRepo, UpdateTaskSchedule},
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::task_schedule::next_run_after;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_task_schedule_middleware};
+
+#[derive(Debug, Deserialize)]
+pub struct TaskScheduleQuery {
+    pub project_id: Option<Uuid>,
+}
+
+fn next_cron_run(cron_expression: &str) -> Result<DateTime<Utc>, ApiError> {
+    next_run_after(cron_expression, Utc::now()).map_err(|e| ApiError::BadRequest(e.to_string()))
+}
+
+fn validate_run_at(run_at: DateTime<Utc>) -> Result<(), ApiError> {
+    if run_at <= Utc::now() {
+        return Err(ApiError::BadRequest(
+            "A one-off schedule must run in the future".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+async fn validate_repos(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    repos: &[TaskScheduleRepo],
+) -> Result<(), ApiError> {
+    let project_repo_ids: HashSet<Uuid> =
+        ProjectRepo::find_repos_for_project(&deployment.db().pool, project_id)
+            .await?
+            .into_iter()
+            .map(|repo| repo.id)
+            .collect();
+    if repos
+        .iter()
+        .any(|repo| !project_repo_ids.contains(&repo.repo_id))
+    {
+        return Err(ApiError::BadRequest(
+            "Every repository must belong to the schedule's project".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub async fn get_task_schedules(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<TaskScheduleQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskSchedule>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let schedules = match query.project_id {
+        Some(project_id) => TaskSchedule::find_by_project_id(pool, project_id).await?,
+        None => TaskSchedule::find_all(pool).await?,
+    };
+    Ok(ResponseJson(ApiResponse::success(schedules)))
+}
+
+pub async fn get_task_schedule(
+    Extension(schedule): Extension<TaskSchedule>,
+) -> Result<ResponseJson<ApiResponse<TaskSchedule>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+pub async fn create_task_schedule(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateTaskSchedule>,
+) -> Result<ResponseJson<ApiResponse<TaskSchedule>>, ApiError> {
+    if payload.name.trim().is_empty() || payload.title_template.trim().is_empty() {
+        return Err(ApiError::BadRequest(
+            "A task schedule needs a name and a title".to_string(),
+        ));
+    }
+    let next_run_at = match (&payload.cron_expression, payload.run_at) {
+        (Some(cron_expression), None) => next_cron_run(cron_expression)?,
+        (None, Some(run_at)) => {
+            validate_run_at(run_at)?;
+            run_at
+        }
+        _ => {
+            return Err(ApiError::BadRequest(
+                "Set either a cron expression or a one-off run time".to_string(),
+            ));
+        }
+    };
+    validate_repos(&deployment, payload.project_id, &payload.repos).await?;
+
+    let schedule = TaskSchedule::create(&deployment.db().pool, &payload, Some(next_run_at)).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_schedule_created",
+            serde_json::json!({
+                "task_schedule_id": schedule.id.to_string(),
+                "project_id": schedule.project_id.to_string(),
+                "recurring": schedule.cron_expression.is_some(),
+                "executor": schedule.executor_profile_id.executor.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+pub async fn update_task_schedule(
+    Extension(schedule): Extension<TaskSchedule>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateTaskSchedule>,
+) -> Result<ResponseJson<ApiResponse<TaskSchedule>>, ApiError> {
+    if let Some(repos) = &payload.repos {
+        validate_repos(&deployment, schedule.project_id, repos).await?;
+    }
+    let re_enabled = payload.enabled == Some(true) && !schedule.enabled;
+
+    let (cron_expression, next_run_at) = match (&payload.cron_expression, payload.run_at) {
+        (Some(_), Some(_)) => {
+            return Err(ApiError::BadRequest(
+                "Set either a cron expression or a one-off run time".to_string(),
+            ));
+        }
+        (Some(cron_expression), None) => (
+            Some(cron_expression.as_str()),
+            Some(next_cron_run(cron_expression)?),
+        ),
+        (None, Some(run_at)) => {
+            validate_run_at(run_at)?;
+            (None, Some(run_at))
+        }
+        // A recurring schedule that was paused resumes from now instead of catching up
+        (None, None) => match schedule.cron_expression.as_deref() {
+            Some(cron_expression) if re_enabled => {
+                (Some(cron_expression), Some(next_cron_run(cron_expression)?))
+            }
+            cron_expression => (cron_expression, schedule.next_run_at),
+        },
+    };
+    if re_enabled && next_run_at.is_none() {
+        return Err(ApiError::BadRequest(
+            "This one-off schedule already ran, give it a new run time to enable it".to_string(),
+        ));
+    }
+
+    let updated = TaskSchedule::update(
+        &deployment.db().pool,
+        schedule.id,
+        &payload,
+        cron_expression,
+        next_run_at,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(updated)))
+}
+
+pub async fn delete_task_schedule(
+    Extension(schedule): Extension<TaskSchedule>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = TaskSchedule::delete(&deployment.db().pool, schedule.id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let task_schedule_router = Router::new()
+        .route(
+            "/",
+            get(get_task_schedule)
+                .put(update_task_schedule)
+                .delete(delete_task_schedule),
+        )
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_task_schedule_middleware,
+        ));
+
+    let inner = Router::new()
+        .route("/", get(get_task_schedules).post(create_task_schedule))
+        .nest("/{task_schedule_id}", task_schedule_router);
+
+    Router::new().nest("/task-schedules", inner)
+}
@@ -3,20 +3,24 @@ use std::path::PathBuf;
 use anyhow;
 use axum::{
     Extension, Json, Router,
+    body::Bytes,
     extract::{
-        Query, State,
+        DefaultBodyLimit, Path, Query, State,
         ws::{WebSocket, WebSocketUpgrade},
     },
-    http::StatusCode,
+    http::{StatusCode, header},
     middleware::from_fn_with_state,
     response::{IntoResponse, Json as ResponseJson},
     routing::{delete, get, post, put},
 };
 use db::models::{
+    execution_budget::{BudgetLimits, ExecutionBudget},
+    execution_process_usage::ExecutionProcessUsage,
     image::TaskImage,
     project::{Project, ProjectError},
     repo::Repo,
-    task::{CreateTask, Task, TaskWithAttemptStatus, UpdateTask},
+    task::{CreateTask, Task, TaskStatus, TaskWithAttemptStatus, UpdateTask},
+    task_dependency::{CreateTaskDependency, TaskDependencies, TaskDependency},
     workspace::{CreateWorkspace, Workspace},
     workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
 };
@@ -25,7 +29,11 @@ use executors::profile::ExecutorProfileId;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
 use services::services::{
-    container::ContainerService, share::ShareError, workspace_manager::WorkspaceManager,
+    container::ContainerService,
+    share::ShareError,
+    task_archive::{self, ImportedTask},
+    usage::{self, UsageSummary},
+    workspace_manager::WorkspaceManager,
 };
 use sqlx::Error as SqlxError;
 use ts_rs::TS;
@@ -33,8 +41,10 @@ use utils::{api::oauth::LoginStatus, response::ApiResponse};
 use uuid::Uuid;
 
 use crate::{
-    DeploymentImpl, error::ApiError, middleware::load_task_middleware,
-    routes::task_attempts::WorkspaceRepoInput,
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_task_middleware,
+    routes::task_attempts::{self, WorkspaceRepoInput},
 };
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -145,6 +155,9 @@ pub struct CreateAndStartTaskRequest {
     pub task: CreateTask,
     pub executor_profile_id: ExecutorProfileId,
     pub repos: Vec<WorkspaceRepoInput>,
+    /// Run the steps of this pipeline template instead of a single coding agent request
+    #[serde(default)]
+    pub pipeline_template_id: Option<Uuid>,
 }
 
 pub async fn create_task_and_start(
@@ -159,6 +172,11 @@ pub async fn create_task_and_start(
 
     let pool = &deployment.db().pool;
 
+    let pipeline = match payload.pipeline_template_id {
+        Some(id) => Some(task_attempts::load_pipeline_template(pool, id).await?),
+        None => None,
+    };
+
     let task_id = Uuid::new_v4();
     let task = Task::create(pool, &payload.task, task_id).await?;
 
@@ -217,7 +235,11 @@ pub async fn create_task_and_start(
 
     let is_attempt_running = deployment
         .container()
-        .start_workspace(&workspace, payload.executor_profile_id.clone())
+        .start_workspace(
+            &workspace,
+            payload.executor_profile_id.clone(),
+            pipeline.as_ref(),
+        )
         .await
         .inspect_err(|err| tracing::error!("Failed to start task attempt: {}", err))
         .is_ok();
@@ -261,6 +283,7 @@ pub async fn update_task(
         Some(s) => Some(s),                     // Non-empty string = update description
         None => existing_task.description,      // Field omitted = keep existing
     };
+    let was_done = existing_task.status == TaskStatus::Done;
     let status = payload.status.unwrap_or(existing_task.status);
     let parent_workspace_id = payload
         .parent_workspace_id
@@ -290,6 +313,10 @@ pub async fn update_task(
         publisher.update_shared_task(&task).await?;
     }
 
+    if !was_done && task.status == TaskStatus::Done {
+        deployment.container().task_done(task.id);
+    }
+
     Ok(ResponseJson(ApiResponse::success(task)))
 }
 
@@ -460,11 +487,191 @@ pub async fn share_task(
     })))
 }
 
+pub async fn get_task_dependencies(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<TaskDependencies>>, ApiError> {
+    let dependencies = TaskDependency::find_for_task(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(dependencies)))
+}
+
+pub async fn create_task_dependency(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateTaskDependency>,
+) -> Result<ResponseJson<ApiResponse<TaskDependency>>, ApiError> {
+    let dependency = TaskDependency::create(&deployment.db().pool, &task, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_dependency_created",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "depends_on_task_id": payload.depends_on_task_id.to_string(),
+                "project_id": task.project_id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(dependency)))
+}
+
+pub async fn delete_task_dependency(
+    State(deployment): State<DeploymentImpl>,
+    Path((task_id, depends_on_task_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    TaskDependency::delete(&deployment.db().pool, task_id, depends_on_task_id).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_dependency_deleted",
+            serde_json::json!({
+                "task_id": task_id.to_string(),
+                "depends_on_task_id": depends_on_task_id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Token usage and estimated cost across all attempts of the task
+pub async fn get_task_usage(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<UsageSummary>>, ApiError> {
+    let buckets =
+        ExecutionProcessUsage::find_buckets_for_task(&deployment.db().pool, task.id).await?;
+    let prices = deployment.config().read().await.model_prices.clone();
+    Ok(ResponseJson(ApiResponse::success(usage::summarize(
+        &buckets, &prices,
+    ))))
+}
+
+/// Execution budget limits of the task itself, not including the ones it inherits from its
+/// project
+pub async fn get_task_budget(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<BudgetLimits>>, ApiError> {
+    let limits = ExecutionBudget::find_by_task_id(&deployment.db().pool, task.id)
+        .await?
+        .map(|budget| budget.limits())
+        .unwrap_or_default();
+    Ok(ResponseJson(ApiResponse::success(limits)))
+}
+
+/// Replace the task's execution budget; limits left `None` fall back to the project's
+pub async fn set_task_budget(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(limits): Json<BudgetLimits>,
+) -> Result<ResponseJson<ApiResponse<BudgetLimits>>, ApiError> {
+    if !limits.is_valid() {
+        return Err(ApiError::BadRequest(
+            "Budget limits must be positive".to_string(),
+        ));
+    }
+
+    let pool = &deployment.db().pool;
+    if limits.is_unlimited() {
+        ExecutionBudget::delete_for_task(pool, task.id).await?;
+    } else {
+        ExecutionBudget::set_for_task(pool, task.id, &limits).await?;
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_budget_updated",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "unlimited": limits.is_unlimited(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(limits)))
+}
+
+/// Download the task and its complete attempt history as a tar.gz task archive
+pub async fn export_task(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<impl IntoResponse, ApiError> {
+    let archive =
+        task_archive::export_task(deployment.db(), deployment.git(), deployment.image(), &task)
+            .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_exported",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "archive_bytes": archive.len(),
+            }),
+        )
+        .await;
+
+    let filename = format!(
+        "attachment; filename=\"task-{}.tar.gz\"",
+        &task.id.simple().to_string()[..8]
+    );
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "application/gzip".to_string()),
+            (header::CONTENT_DISPOSITION, filename),
+        ],
+        archive,
+    ))
+}
+
+/// Recreate the task of an uploaded task archive under `project_id`
+pub async fn import_task(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<TaskQuery>,
+    body: Bytes,
+) -> Result<ResponseJson<ApiResponse<ImportedTask>>, ApiError> {
+    Project::find_by_id(&deployment.db().pool, query.project_id)
+        .await?
+        .ok_or(ProjectError::ProjectNotFound)?;
+
+    let imported = task_archive::import_task(
+        deployment.db(),
+        deployment.git(),
+        deployment.image(),
+        query.project_id,
+        &body,
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_imported",
+            serde_json::json!({
+                "task_id": imported.task.id.to_string(),
+                "project_id": query.project_id.to_string(),
+                "unmatched_repos": imported.unmatched_repos.len(),
+                "unrestored_branches": imported.unrestored_branches.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(imported)))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let task_actions_router = Router::new()
         .route("/", put(update_task))
         .route("/", delete(delete_task))
-        .route("/share", post(share_task));
+        .route("/share", post(share_task))
+        .route("/usage", get(get_task_usage))
+        .route("/budget", get(get_task_budget).put(set_task_budget))
+        .route("/export", get(export_task))
+        .route(
+            "/dependencies",
+            get(get_task_dependencies).post(create_task_dependency),
+        );
 
     let task_id_router = Router::new()
         .route("/", get(get_task))
@@ -475,6 +682,14 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/", get(get_tasks).post(create_task))
         .route("/stream/ws", get(stream_tasks_ws))
         .route("/create-and-start", post(create_task_and_start))
+        .route(
+            "/import",
+            post(import_task).layer(DefaultBodyLimit::max(200 * 1024 * 1024)), // 200MB limit
+        )
+        .route(
+            "/{task_id}/dependencies/{depends_on_task_id}",
+            delete(delete_task_dependency),
+        )
         .nest("/{task_id}", task_id_router);
 
     // mount under /projects/:project_id/tasks
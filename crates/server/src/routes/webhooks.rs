@@ -0,0 +1,128 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Query, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, put},
+};
+use db::models::{
+    webhook::{CreateWebhook, UpdateWebhook, Webhook},
+    webhook_delivery::WebhookDelivery,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_webhook_middleware};
+
+const DEFAULT_DELIVERY_LIMIT: i64 = 50;
+const MAX_DELIVERY_LIMIT: i64 = 500;
+
+#[derive(Debug, Deserialize)]
+pub struct DeliveriesQuery {
+    pub limit: Option<i64>,
+}
+
+fn validate_url(url: &str) -> Result<(), ApiError> {
+    match url::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => Ok(()),
+        _ => Err(ApiError::BadRequest(
+            "Webhook URL must be an http or https URL".to_string(),
+        )),
+    }
+}
+
+pub async fn get_webhooks(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<Webhook>>>, ApiError> {
+    let webhooks = Webhook::find_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(webhooks)))
+}
+
+pub async fn get_webhook(
+    Extension(webhook): Extension<Webhook>,
+) -> Result<ResponseJson<ApiResponse<Webhook>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(webhook)))
+}
+
+pub async fn create_webhook(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateWebhook>,
+) -> Result<ResponseJson<ApiResponse<Webhook>>, ApiError> {
+    if payload.name.trim().is_empty() {
+        return Err(ApiError::BadRequest("A webhook needs a name".to_string()));
+    }
+    validate_url(&payload.url)?;
+    let webhook = Webhook::create(&deployment.db().pool, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "webhook_created",
+            serde_json::json!({
+                "webhook_id": webhook.id.to_string(),
+                "event_count": webhook.events.len(),
+                "signed": webhook.secret.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(webhook)))
+}
+
+pub async fn update_webhook(
+    Extension(webhook): Extension<Webhook>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateWebhook>,
+) -> Result<ResponseJson<ApiResponse<Webhook>>, ApiError> {
+    if let Some(url) = &payload.url {
+        validate_url(url)?;
+    }
+    let updated = Webhook::update(&deployment.db().pool, webhook.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(updated)))
+}
+
+pub async fn delete_webhook(
+    Extension(webhook): Extension<Webhook>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = Webhook::delete(&deployment.db().pool, webhook.id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+/// Latest deliveries of the webhook, newest first, with the outcome of their last attempt
+pub async fn get_webhook_deliveries(
+    Extension(webhook): Extension<Webhook>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<DeliveriesQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<WebhookDelivery>>>, ApiError> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_DELIVERY_LIMIT)
+        .clamp(1, MAX_DELIVERY_LIMIT);
+    let deliveries =
+        WebhookDelivery::find_by_webhook_id(&deployment.db().pool, webhook.id, limit).await?;
+    Ok(ResponseJson(ApiResponse::success(deliveries)))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let webhook_router = Router::new()
+        .route(
+            "/",
+            put(update_webhook).get(get_webhook).delete(delete_webhook),
+        )
+        .route("/deliveries", get(get_webhook_deliveries))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_webhook_middleware,
+        ));
+
+    let inner = Router::new()
+        .route("/", get(get_webhooks).post(create_webhook))
+        .nest("/{webhook_id}", webhook_router);
+
+    Router::new().nest("/webhooks", inner)
+}
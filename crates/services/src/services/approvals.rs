@@ -1,4 +1,5 @@
 pub mod executor_approvals;
+pub mod policy;
 
 use std::{collections::HashMap, sync::Arc, time::Duration as StdDuration};
 
@@ -11,7 +12,10 @@ use executors::{
     approvals::ToolCallMetadata,
     logs::{
         NormalizedEntry, NormalizedEntryType, ToolStatus,
-        utils::patch::{ConversationPatch, extract_normalized_entry_from_patch},
+        utils::{
+            EntryIndexProvider,
+            patch::{ConversationPatch, extract_normalized_entry_from_patch},
+        },
     },
 };
 use futures::future::{BoxFuture, FutureExt, Shared};
@@ -252,6 +256,58 @@ impl Approvals {
         });
     }
 
+    /// Tool use entry of a call that is waiting for a decision
+    pub async fn find_tool_use(
+        &self,
+        execution_process_id: &Uuid,
+        tool_call_id: &str,
+    ) -> Option<NormalizedEntry> {
+        let store = self.msg_store_by_id(execution_process_id).await?;
+        find_matching_tool_use(store, tool_call_id).map(|(_, entry)| entry)
+    }
+
+    /// Record in the conversation that `rule_name` decided a call with `status`, without
+    /// asking anyone
+    pub async fn record_automatic_decision(
+        &self,
+        execution_process_id: &Uuid,
+        tool_call_id: &str,
+        tool_name: &str,
+        status: &ApprovalStatus,
+        rule_name: &str,
+    ) {
+        let Some(store) = self.msg_store_by_id(execution_process_id).await else {
+            tracing::warn!(
+                "No msg_store found for execution_process_id: {}",
+                execution_process_id
+            );
+            return;
+        };
+
+        if let ApprovalStatus::Denied { .. } = status
+            && let Some(tool_status) = ToolStatus::from_approval_status(status)
+            && let Some((idx, entry)) = find_matching_tool_use(store.clone(), tool_call_id)
+            && let Some(denied_entry) = entry.with_tool_status(tool_status)
+        {
+            store.push_patch(ConversationPatch::replace(idx, denied_entry));
+        }
+
+        let content = match status {
+            ApprovalStatus::Approved => {
+                format!("Tool '{tool_name}' was approved by approval rule \"{rule_name}\"")
+            }
+            _ => format!("Tool '{tool_name}' was denied by approval rule \"{rule_name}\""),
+        };
+        let entry = NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::SystemMessage,
+            content,
+            metadata: None,
+        };
+        let index = EntryIndexProvider::start_from(&store).next();
+        store.push_patch(ConversationPatch::add_normalized_entry(index, entry));
+    }
+
     async fn msg_store_by_id(&self, execution_process_id: &Uuid) -> Option<Arc<MsgStore>> {
         let map = self.msg_stores.read().await;
         map.get(execution_process_id).cloned()
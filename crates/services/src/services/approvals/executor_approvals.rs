@@ -1,18 +1,36 @@
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
 use async_trait::async_trait;
-use db::{self, DBService};
-use executors::approvals::{ExecutorApprovalError, ExecutorApprovalService};
-use serde_json::Value;
+use db::{
+    self, DBService,
+    models::{
+        approval_rule::{ApprovalRule, ApprovalRuleDecision},
+        execution_process::ExecutionProcess,
+        webhook::WebhookEvent,
+    },
+};
+use executors::{
+    approvals::{ExecutorApprovalError, ExecutorApprovalService},
+    logs::NormalizedEntryType,
+};
+use serde_json::{Value, json};
 use utils::approvals::{ApprovalRequest, ApprovalStatus, CreateApprovalRequest};
 use uuid::Uuid;
 
-use crate::services::{approvals::Approvals, notification::NotificationService};
+use crate::services::{
+    approvals::{
+        Approvals,
+        policy::{ApprovalPolicy, ToolCall},
+    },
+    notification::NotificationService,
+    webhook::WebhookService,
+};
 
 pub struct ExecutorApprovalBridge {
     approvals: Approvals,
     db: DBService,
     notification_service: NotificationService,
+    webhooks: WebhookService,
     execution_process_id: Uuid,
 }
 
@@ -21,15 +39,57 @@ impl ExecutorApprovalBridge {
         approvals: Approvals,
         db: DBService,
         notification_service: NotificationService,
+        webhooks: WebhookService,
         execution_process_id: Uuid,
     ) -> Arc<Self> {
         Arc::new(Self {
             approvals,
             db,
             notification_service,
+            webhooks,
             execution_process_id,
         })
     }
+
+    /// Rule of the project that matches the call, if any
+    async fn matching_rule(
+        &self,
+        tool_name: &str,
+        tool_input: &Value,
+        tool_call_id: &str,
+    ) -> Result<Option<ApprovalRule>, sqlx::Error> {
+        let ctx = ExecutionProcess::load_context(&self.db.pool, self.execution_process_id).await?;
+        let rules = ApprovalRule::find_by_project_id(&self.db.pool, ctx.project.id).await?;
+        if rules.is_empty() {
+            return Ok(None);
+        }
+
+        // Prefer the normalized call, which agents with their own tool names map to paths
+        // and commands
+        let call = match self
+            .approvals
+            .find_tool_use(&self.execution_process_id, tool_call_id)
+            .await
+            .map(|entry| entry.entry_type)
+        {
+            Some(NormalizedEntryType::ToolUse { action_type, .. }) => {
+                ToolCall::from_action(tool_name, &action_type)
+            }
+            _ => ToolCall::from_input(tool_name, tool_input),
+        };
+        // Path globs are relative to where the agent runs
+        let mut worktree = PathBuf::from(ctx.workspace.container_ref.unwrap_or_default());
+        if let Some(dir) = ctx
+            .workspace
+            .agent_working_dir
+            .filter(|dir| !dir.is_empty())
+        {
+            worktree.push(dir);
+        }
+        Ok(ApprovalPolicy::new(rules, worktree)
+            .evaluate(&call)
+            .cloned())
+    }
 }
 
 #[async_trait]
@@ -40,6 +100,44 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
         tool_input: Value,
         tool_call_id: &str,
     ) -> Result<ApprovalStatus, ExecutorApprovalError> {
+        match self
+            .matching_rule(tool_name, &tool_input, tool_call_id)
+            .await
+        {
+            Ok(Some(rule)) if rule.decision != ApprovalRuleDecision::Ask => {
+                let status = match rule.decision {
+                    ApprovalRuleDecision::Deny => ApprovalStatus::Denied {
+                        reason: Some(rule.denial_reason()),
+                    },
+                    _ => ApprovalStatus::Approved,
+                };
+                tracing::debug!(
+                    "Approval rule '{}' decided tool '{}' ({}): {:?}",
+                    rule.name,
+                    tool_name,
+                    tool_call_id,
+                    status
+                );
+                self.approvals
+                    .record_automatic_decision(
+                        &self.execution_process_id,
+                        tool_call_id,
+                        tool_name,
+                        &status,
+                        &rule.name,
+                    )
+                    .await;
+                return Ok(status);
+            }
+            Ok(_) => {}
+            // A human decides when the rules can't
+            Err(e) => tracing::warn!(
+                "Failed to evaluate approval rules for execution process {}: {}",
+                self.execution_process_id,
+                e
+            ),
+        }
+
         super::ensure_task_in_review(&self.db.pool, self.execution_process_id).await;
 
         let request = ApprovalRequest::from_create(
@@ -51,12 +149,23 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
             self.execution_process_id,
         );
 
+        let approval_id = request.id.clone();
         let (_, waiter) = self
             .approvals
             .create_with_waiter(request)
             .await
             .map_err(ExecutorApprovalError::request_failed)?;
 
+        self.webhooks.emit(
+            WebhookEvent::ApprovalRequested,
+            json!({
+                "approval_id": approval_id,
+                "execution_process_id": self.execution_process_id,
+                "tool_name": tool_name,
+                "tool_call_id": tool_call_id,
+            }),
+        );
+
         // Play notification sound when approval is needed
         self.notification_service
             .notify(
@@ -0,0 +1,361 @@
+//! Approval rules deciding tool calls before a human is asked.
+
+use std::path::{Component, Path, PathBuf};
+
+use db::models::approval_rule::ApprovalRule;
+use executors::logs::ActionType;
+use globset::{Glob, GlobBuilder, GlobMatcher};
+use regex::Regex;
+use serde_json::Value;
+
+/// What a tool call does, as far as approval rules can match it
+#[derive(Debug, Clone, Default)]
+pub struct ToolCall {
+    pub tool_name: String,
+    /// Path read or edited by the call
+    pub path: Option<String>,
+    /// Command run by the call
+    pub command: Option<String>,
+}
+
+impl ToolCall {
+    /// Call described by its normalized `action_type`
+    pub fn from_action(tool_name: &str, action_type: &ActionType) -> Self {
+        let (path, command) = match action_type {
+            ActionType::FileRead { path } | ActionType::FileEdit { path, .. } => {
+                (Some(path.clone()), None)
+            }
+            ActionType::CommandRun { command, .. } => (None, Some(command.clone())),
+            _ => (None, None),
+        };
+        Self {
+            tool_name: tool_name.to_string(),
+            path,
+            command,
+        }
+    }
+
+    /// Call described by its raw input, for calls that have not been normalized yet
+    pub fn from_input(tool_name: &str, tool_input: &Value) -> Self {
+        let string_field = |keys: &[&str]| {
+            keys.iter()
+                .find_map(|key| tool_input.get(key).and_then(Value::as_str))
+                .map(String::from)
+        };
+        Self {
+            tool_name: tool_name.to_string(),
+            path: string_field(&["file_path", "path", "notebook_path"]),
+            command: string_field(&["command", "cmd"]),
+        }
+    }
+}
+
+/// Glob matching like `.gitignore` patterns: `*` stays within a path segment, `**` does not
+fn path_glob(pattern: &str) -> Result<GlobMatcher, globset::Error> {
+    Ok(GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()?
+        .compile_matcher())
+}
+
+/// `path` relative to `worktree` with `.` and `..` resolved, or `None` if it points
+/// outside of it. Agents report paths both relative to and absolute within the worktree.
+fn worktree_relative(path: &str, worktree: &Path) -> Option<String> {
+    let path = Path::new(path);
+    let path = if path.is_absolute() {
+        path.strip_prefix(worktree).ok()?
+    } else {
+        path
+    };
+    let mut segments = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::Normal(segment) => segments.push(segment.to_str()?),
+            Component::ParentDir => {
+                segments.pop()?;
+            }
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(segments.join("/"))
+}
+
+fn tool_name_glob(pattern: &str) -> Result<GlobMatcher, globset::Error> {
+    Ok(GlobBuilder::new(pattern)
+        .case_insensitive(true)
+        .build()?
+        .compile_matcher())
+}
+
+/// Why the patterns of a rule are invalid, if they are
+pub fn validate(
+    tool_name: Option<&str>,
+    path_glob: Option<&str>,
+    command_regex: Option<&str>,
+) -> Result<(), String> {
+    if let Some(pattern) = tool_name {
+        Glob::new(pattern).map_err(|e| format!("Invalid tool name pattern: {e}"))?;
+    }
+    if let Some(pattern) = path_glob {
+        Glob::new(pattern).map_err(|e| format!("Invalid path glob: {e}"))?;
+    }
+    if let Some(pattern) = command_regex {
+        Regex::new(pattern).map_err(|e| format!("Invalid command regex: {e}"))?;
+    }
+    Ok(())
+}
+
+struct CompiledRule {
+    rule: ApprovalRule,
+    tool_name: Option<GlobMatcher>,
+    path_glob: Option<GlobMatcher>,
+    command_regex: Option<Regex>,
+}
+
+impl CompiledRule {
+    fn compile(rule: ApprovalRule) -> Result<Self, String> {
+        let tool_name = rule
+            .tool_name
+            .as_deref()
+            .map(tool_name_glob)
+            .transpose()
+            .map_err(|e| e.to_string())?;
+        let path_glob = rule
+            .path_glob
+            .as_deref()
+            .map(path_glob)
+            .transpose()
+            .map_err(|e| e.to_string())?;
+        let command_regex = rule
+            .command_regex
+            .as_deref()
+            .map(Regex::new)
+            .transpose()
+            .map_err(|e| e.to_string())?;
+        Ok(Self {
+            rule,
+            tool_name,
+            path_glob,
+            command_regex,
+        })
+    }
+
+    fn matches(&self, call: &ToolCall, worktree: &Path) -> bool {
+        if let Some(glob) = &self.tool_name
+            && !glob.is_match(&call.tool_name)
+        {
+            return false;
+        }
+        if let Some(glob) = &self.path_glob {
+            let Some(path) = &call.path else {
+                return false;
+            };
+            // Never let `src/**` match `src/../../.ssh/id_rsa`
+            let Some(path) = worktree_relative(path, worktree) else {
+                return false;
+            };
+            if !glob.is_match(path) {
+                return false;
+            }
+        }
+        if let Some(regex) = &self.command_regex {
+            let Some(command) = &call.command else {
+                return false;
+            };
+            if !regex.is_match(command) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// A project's approval rules, ready to decide tool calls
+pub struct ApprovalPolicy {
+    rules: Vec<CompiledRule>,
+    worktree: PathBuf,
+}
+
+impl ApprovalPolicy {
+    /// Policy of the enabled `rules`, which must be in position order, for calls made in
+    /// `worktree`. Rules with invalid patterns are skipped, and path globs never match
+    /// paths outside of `worktree`.
+    pub fn new(rules: Vec<ApprovalRule>, worktree: impl Into<PathBuf>) -> Self {
+        let rules = rules
+            .into_iter()
+            .filter(|rule| rule.enabled)
+            .filter_map(|rule| {
+                let id = rule.id;
+                CompiledRule::compile(rule)
+                    .inspect_err(|e| tracing::warn!("Skipping approval rule {}: {}", id, e))
+                    .ok()
+            })
+            .collect();
+        Self {
+            rules,
+            worktree: worktree.into(),
+        }
+    }
+
+    /// First rule matching `call`, if any
+    pub fn evaluate(&self, call: &ToolCall) -> Option<&ApprovalRule> {
+        self.rules
+            .iter()
+            .find(|compiled| compiled.matches(call, &self.worktree))
+            .map(|compiled| &compiled.rule)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use db::models::approval_rule::ApprovalRuleDecision;
+    use uuid::Uuid;
+
+    use super::*;
+
+    const WORKTREE: &str = "/tmp/worktrees/task";
+
+    fn rule(
+        name: &str,
+        tool_name: Option<&str>,
+        path_glob: Option<&str>,
+        command_regex: Option<&str>,
+        decision: ApprovalRuleDecision,
+    ) -> ApprovalRule {
+        ApprovalRule {
+            id: Uuid::new_v4(),
+            project_id: Uuid::nil(),
+            name: name.to_string(),
+            position: 0,
+            tool_name: tool_name.map(String::from),
+            path_glob: path_glob.map(String::from),
+            command_regex: command_regex.map(String::from),
+            decision,
+            reason: None,
+            enabled: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_first_matching_rule_decides() {
+        let policy = ApprovalPolicy::new(
+            vec![
+                rule(
+                    "ask for migrations",
+                    None,
+                    Some("migrations/**"),
+                    None,
+                    ApprovalRuleDecision::Ask,
+                ),
+                rule(
+                    "edit sources",
+                    Some("edit"),
+                    Some("**/*.rs"),
+                    None,
+                    ApprovalRuleDecision::Approve,
+                ),
+                rule(
+                    "no force pushes",
+                    Some("bash"),
+                    None,
+                    Some(r"git\s+push\s+.*--force"),
+                    ApprovalRuleDecision::Deny,
+                ),
+            ],
+            WORKTREE,
+        );
+        let decide = |call: ToolCall| policy.evaluate(&call).map(|rule| rule.name.as_str());
+
+        let edit = |path: &str| {
+            ToolCall::from_action(
+                "Edit",
+                &ActionType::FileEdit {
+                    path: path.to_string(),
+                    changes: vec![],
+                },
+            )
+        };
+        assert_eq!(decide(edit("src/lib.rs")), Some("edit sources"));
+        assert_eq!(decide(edit("./src/lib.rs")), Some("edit sources"));
+        assert_eq!(
+            decide(edit("migrations/001.rs")),
+            Some("ask for migrations")
+        );
+        assert_eq!(decide(edit("README.md")), None);
+
+        let bash = |command: &str| {
+            ToolCall::from_action(
+                "Bash",
+                &ActionType::CommandRun {
+                    command: command.to_string(),
+                    result: None,
+                },
+            )
+        };
+        assert_eq!(
+            decide(bash("git push origin main --force")),
+            Some("no force pushes")
+        );
+        assert_eq!(decide(bash("git push origin main")), None);
+    }
+
+    #[test]
+    fn test_conditions_only_match_their_action() {
+        let policy = ApprovalPolicy::new(
+            vec![rule(
+                "read env files",
+                None,
+                Some("**/.env"),
+                None,
+                ApprovalRuleDecision::Deny,
+            )],
+            WORKTREE,
+        );
+        let command = ToolCall::from_input("Bash", &serde_json::json!({"command": "cat .env"}));
+        assert!(policy.evaluate(&command).is_none());
+
+        let read = ToolCall::from_input("Read", &serde_json::json!({"file_path": "app/.env"}));
+        assert!(policy.evaluate(&read).is_some());
+
+        let mut disabled = rule("all", None, None, None, ApprovalRuleDecision::Approve);
+        disabled.enabled = false;
+        assert!(
+            ApprovalPolicy::new(vec![disabled], WORKTREE)
+                .evaluate(&read)
+                .is_none()
+        );
+
+        assert!(validate(None, Some("src/[a-"), None).is_err());
+        assert!(validate(Some("mcp__*"), Some("**/*.rs"), Some(r"^rm\s")).is_ok());
+    }
+
+    #[test]
+    fn test_paths_outside_the_worktree_never_match() {
+        let policy = ApprovalPolicy::new(
+            vec![rule(
+                "edit sources",
+                None,
+                Some("src/**"),
+                None,
+                ApprovalRuleDecision::Approve,
+            )],
+            WORKTREE,
+        );
+        let edit = |path: &str| {
+            let call = ToolCall::from_input("Edit", &serde_json::json!({"file_path": path}));
+            policy.evaluate(&call).is_some()
+        };
+        assert!(edit("src/lib.rs"));
+        assert!(edit("src/a/../lib.rs"));
+        assert!(edit("/tmp/worktrees/task/src/lib.rs"));
+        assert!(!edit("src/../../.ssh/id_rsa"));
+        assert!(!edit("src/../../task/src/lib.rs"));
+        assert!(!edit("/tmp/worktrees/task/../other/src/lib.rs"));
+        assert!(!edit("/tmp/worktrees/other/src/lib.rs"));
+        assert!(!edit("/etc/passwd"));
+    }
+}
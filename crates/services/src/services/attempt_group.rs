@@ -0,0 +1,232 @@
+//! Best-of-N comparison of the attempts of an attempt group: what each one changed, whether
+//! it passed verification, how long it ran and what it cost.
+
+use std::{collections::HashMap, path::Path};
+
+use chrono::{DateTime, Utc};
+use db::{
+    DBService,
+    models::{
+        attempt_group::{AttemptGroup, AttemptGroupMember},
+        execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+        execution_process_usage::ExecutionProcessUsage,
+        session::Session,
+        workspace::Workspace,
+        workspace_repo::{RepoWithTargetBranch, WorkspaceRepo},
+    },
+};
+use executors::profile::ExecutorProfileId;
+use serde::Serialize;
+use ts_rs::TS;
+use utils::diff::Diff;
+use uuid::Uuid;
+
+use crate::services::{
+    config::ModelPrice,
+    git::{DiffTarget, GitService, GitServiceError},
+    usage::{self, UsageTotals},
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, TS)]
+pub struct DiffStats {
+    pub files_changed: usize,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+impl DiffStats {
+    fn add(&mut self, diffs: &[Diff]) {
+        self.files_changed += diffs.len();
+        for diff in diffs {
+            self.additions += diff.additions.unwrap_or(0);
+            self.deletions += diff.deletions.unwrap_or(0);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct AttemptComparisonEntry {
+    pub workspace_id: Uuid,
+    pub branch: String,
+    pub executor_profile_id: ExecutorProfileId,
+    /// Status of the latest coding agent run, `None` before the agent started
+    pub status: Option<ExecutionProcessStatus>,
+    /// Changes against the target branches of all repos, `None` when they could not be read
+    pub diff: Option<DiffStats>,
+    /// Status of the latest verify script run, `None` when no verify script ran
+    pub verification: Option<ExecutionProcessStatus>,
+    /// From the first process starting to the last one finishing, or until now while running
+    pub duration_seconds: Option<i64>,
+    pub usage: UsageTotals,
+    pub winner: bool,
+    pub discarded: bool,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct AttemptComparison {
+    pub group: AttemptGroup,
+    pub attempts: Vec<AttemptComparisonEntry>,
+}
+
+pub async fn compare(
+    db: &DBService,
+    git: &GitService,
+    prices: &HashMap<String, ModelPrice>,
+    group: AttemptGroup,
+) -> Result<AttemptComparison, sqlx::Error> {
+    let pool = &db.pool;
+    let members = AttemptGroupMember::find_by_attempt_group_id(pool, group.id).await?;
+
+    let mut attempts = Vec::with_capacity(members.len());
+    for member in members {
+        // Members are removed with their workspace, so this only races with a deletion
+        let Some(workspace) = Workspace::find_by_id(pool, member.workspace_id).await? else {
+            continue;
+        };
+
+        let mut processes = Vec::new();
+        for session in Session::find_by_workspace_id(pool, workspace.id).await? {
+            processes.extend(ExecutionProcess::find_by_session_id(pool, session.id, false).await?);
+        }
+        processes.sort_by_key(|process| process.created_at);
+        let latest_status = |run_reason: ExecutionProcessRunReason| {
+            processes
+                .iter()
+                .rev()
+                .find(|process| process.run_reason == run_reason)
+                .map(|process| process.status.clone())
+        };
+
+        let repos =
+            WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, workspace.id).await?;
+        let diff = match diff_stats(git, &workspace, &repos) {
+            Ok(stats) => Some(stats),
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to compute diff of workspace {}: {}",
+                    workspace.id,
+                    e
+                );
+                None
+            }
+        };
+
+        let buckets = ExecutionProcessUsage::find_buckets_for_workspace(pool, workspace.id).await?;
+
+        attempts.push(AttemptComparisonEntry {
+            workspace_id: workspace.id,
+            branch: workspace.branch.clone(),
+            executor_profile_id: member.executor_profile_id.0,
+            status: latest_status(ExecutionProcessRunReason::CodingAgent),
+            diff,
+            verification: latest_status(ExecutionProcessRunReason::Verify),
+            duration_seconds: duration(
+                processes
+                    .iter()
+                    .filter(|process| process.run_reason != ExecutionProcessRunReason::DevServer)
+                    .map(|process| (process.started_at, process.completed_at)),
+                Utc::now(),
+            ),
+            usage: usage::summarize(&buckets, prices).total,
+            winner: group.winner_workspace_id == Some(workspace.id),
+            discarded: member.discarded_at.is_some(),
+        });
+    }
+
+    Ok(AttemptComparison { group, attempts })
+}
+
+/// Diff of every repo of the workspace against its target branch. The worktree is used while
+/// it exists so uncommitted changes count; after cleanup the committed branch is compared.
+fn diff_stats(
+    git: &GitService,
+    workspace: &Workspace,
+    repos: &[RepoWithTargetBranch],
+) -> Result<DiffStats, GitServiceError> {
+    let mut stats = DiffStats::default();
+    for RepoWithTargetBranch {
+        repo,
+        target_branch,
+    } in repos
+    {
+        let worktree_path = workspace
+            .container_ref
+            .as_ref()
+            .map(|container_ref| Path::new(container_ref).join(&repo.name))
+            .filter(|path| path.exists());
+        let diffs = match worktree_path {
+            Some(worktree_path) => {
+                let base_commit =
+                    git.get_base_commit(&repo.path, &workspace.branch, target_branch)?;
+                git.get_diffs(
+                    DiffTarget::Worktree {
+                        worktree_path: &worktree_path,
+                        base_commit: &base_commit,
+                    },
+                    None,
+                )?
+            }
+            None => git.get_diffs(
+                DiffTarget::Branch {
+                    repo_path: &repo.path,
+                    branch_name: &workspace.branch,
+                    base_branch: target_branch,
+                },
+                None,
+            )?,
+        };
+        stats.add(&diffs);
+    }
+    Ok(stats)
+}
+
+/// Wall-clock time from the first process starting to the last one finishing, given the
+/// `(started_at, completed_at)` of each process. Dev servers are left out by the caller since
+/// they run until stopped.
+fn duration(
+    spans: impl Iterator<Item = (DateTime<Utc>, Option<DateTime<Utc>>)>,
+    now: DateTime<Utc>,
+) -> Option<i64> {
+    let mut started_at: Option<DateTime<Utc>> = None;
+    let mut finished_at: Option<DateTime<Utc>> = None;
+    for (start, end) in spans {
+        started_at = Some(started_at.map_or(start, |first| first.min(start)));
+        let end = end.unwrap_or(now);
+        finished_at = Some(finished_at.map_or(end, |last| last.max(end)));
+    }
+    Some((finished_at? - started_at?).num_seconds().max(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_duration_spans_first_start_to_last_finish() {
+        let t0 = Utc::now();
+        let spans = vec![
+            (t0, Some(t0 + Duration::seconds(30))),
+            (t0 + Duration::seconds(40), Some(t0 + Duration::seconds(90))),
+        ];
+        assert_eq!(
+            duration(spans.into_iter(), t0 + Duration::hours(1)),
+            Some(90)
+        );
+        assert_eq!(duration(std::iter::empty(), t0), None);
+    }
+
+    #[test]
+    fn test_duration_runs_until_now_while_a_process_is_running() {
+        let t0 = Utc::now();
+        let spans = vec![
+            (t0, Some(t0 + Duration::seconds(30))),
+            (t0 + Duration::seconds(40), None),
+        ];
+        assert_eq!(
+            duration(spans.into_iter(), t0 + Duration::seconds(100)),
+            Some(100)
+        );
+    }
+}
@@ -9,8 +9,8 @@ use reqwest::{Client, Response, StatusCode};
 use tracing::{debug, warn};
 
 use super::models::{
-    BitbucketActivity, BitbucketDiffComment, BitbucketError, BitbucketPullRequest,
-    CreatePullRequestRequest, PagedResponse,
+    BitbucketActivity, BitbucketBuildStatus, BitbucketDiffComment, BitbucketError,
+    BitbucketPullRequest, CreatePullRequestRequest, PagedResponse,
 };
 use crate::services::vcs_provider::VcsProviderError;
 
@@ -383,6 +383,81 @@ impl BitbucketApiClient {
         Ok(all_comments)
     }
 
+    /// Add a comment to a pull request, as a reply to `parent_id` when set
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_pull_request_comment(
+        &self,
+        base_url: &str,
+        token: &str,
+        project: &str,
+        repo: &str,
+        pr_id: i64,
+        text: &str,
+        parent_id: Option<i64>,
+    ) -> Result<(), VcsProviderError> {
+        let url = Self::api_url(
+            base_url,
+            &format!("/projects/{}/repos/{}/pull-requests/{}/comments", project, repo, pr_id),
+        );
+
+        debug!("Creating PR comment at {}", url);
+
+        let mut body = serde_json::json!({ "text": text });
+        if let Some(parent_id) = parent_id {
+            body["parent"] = serde_json::json!({ "id": parent_id });
+        }
+
+        let response = self
+            .http_client
+            .post(&url)
+            .bearer_auth(token)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| VcsProviderError::Network(e.to_string()))?;
+
+        self.handle_response(response).await?;
+        Ok(())
+    }
+
+    /// Get the build statuses CI servers reported for a commit
+    pub async fn get_build_statuses(
+        &self,
+        base_url: &str,
+        token: &str,
+        commit: &str,
+    ) -> Result<Vec<BitbucketBuildStatus>, VcsProviderError> {
+        // Build statuses have their own REST API next to the core one
+        let url = format!(
+            "{}/rest/build-status/1.0/commits/{}?limit=100",
+            base_url.trim_end_matches('/'),
+            commit
+        );
+
+        debug!("Getting build statuses from {}", url);
+
+        let page: PagedResponse<BitbucketBuildStatus> = self
+            .execute_with_retry(|| async {
+                let response = self
+                    .http_client
+                    .get(&url)
+                    .bearer_auth(token)
+                    .send()
+                    .await
+                    .map_err(|e| VcsProviderError::Network(e.to_string()))?;
+
+                let response = self.handle_response(response).await?;
+
+                response
+                    .json::<PagedResponse<BitbucketBuildStatus>>()
+                    .await
+                    .map_err(|e| VcsProviderError::PullRequest(format!("Failed to parse response: {}", e)))
+            })
+            .await?;
+
+        Ok(page.values)
+    }
+
     /// Verify token is valid by calling a simple API endpoint
     pub async fn verify_token(
         &self,
@@ -4,6 +4,7 @@
 //! - Pull request creation
 //! - PR status tracking
 //! - Comment fetching (general and inline review comments)
+//! - Build status of the PR head commit
 //!
 //! Authentication is done via HTTP access tokens (Personal Access Tokens).
 
@@ -21,7 +22,9 @@ use self::api_client::BitbucketApiClient;
 use self::credentials::{BitbucketCredentialStore, BitbucketCredentials};
 use self::models::{CreatePullRequestRequest, ProjectSpec, RefSpec, RepositorySpec};
 use super::github::UnifiedPrComment;
-use super::vcs_provider::{CreatePrRequest, VcsProvider, VcsProviderError, VcsProviderType, VcsRepoInfo};
+use super::vcs_provider::{
+    CiCheck, CreatePrRequest, PrCiReport, VcsProvider, VcsProviderError, VcsProviderType, VcsRepoInfo,
+};
 
 /// Bitbucket Server service implementing the VcsProvider trait.
 pub struct BitbucketService {
@@ -283,8 +286,9 @@ impl VcsProvider for BitbucketService {
                     if let Some(comment) = activity.comment {
                         unified_comments.push(comment.to_unified_comment(&pr_url));
 
-                        // Also include replies
-                        for reply in comment.comments {
+                        // Also include replies, which share the resolution of their thread
+                        for mut reply in comment.comments {
+                            reply.thread_resolved |= comment.thread_resolved;
                             unified_comments.push(reply.to_unified_comment(&pr_url));
                         }
                     }
@@ -317,6 +321,77 @@ impl VcsProvider for BitbucketService {
 
         Ok(unified_comments)
     }
+
+    async fn reply_to_pr_comment(
+        &self,
+        repo_info: &VcsRepoInfo,
+        pr_number: i64,
+        comment: &UnifiedPrComment,
+        body: &str,
+    ) -> Result<(), VcsProviderError> {
+        let creds = self.get_credentials().await?;
+
+        let parent_id = match comment {
+            UnifiedPrComment::General { id, .. } => id
+                .parse::<i64>()
+                .map_err(|_| VcsProviderError::PullRequest(format!("Invalid Bitbucket comment id: {}", id)))?,
+            UnifiedPrComment::Review { id, .. } => *id,
+        };
+
+        self.client
+            .create_pull_request_comment(
+                &creds.base_url,
+                &creds.access_token,
+                &repo_info.owner_or_project,
+                &repo_info.repo_name,
+                pr_number,
+                body,
+                Some(parent_id),
+            )
+            .await
+    }
+
+    async fn get_pr_ci_status(
+        &self,
+        repo_info: &VcsRepoInfo,
+        pr_number: i64,
+    ) -> Result<PrCiReport, VcsProviderError> {
+        let creds = self.get_credentials().await?;
+
+        let pr = self
+            .client
+            .get_pull_request(
+                &creds.base_url,
+                &creds.access_token,
+                &repo_info.owner_or_project,
+                &repo_info.repo_name,
+                pr_number,
+            )
+            .await?;
+
+        let head_sha = pr.from_ref.latest_commit.ok_or_else(|| {
+            VcsProviderError::PullRequest(format!("Bitbucket PR #{} has no latest commit", pr_number))
+        })?;
+
+        let builds = self
+            .client
+            .get_build_statuses(&creds.base_url, &creds.access_token, &head_sha)
+            .await?;
+
+        Ok(PrCiReport {
+            head_sha,
+            checks: builds.iter().map(|b| b.to_ci_check()).collect(),
+        })
+    }
+
+    async fn get_ci_check_log(
+        &self,
+        _repo_info: &VcsRepoInfo,
+        _check: &CiCheck,
+    ) -> Result<Option<String>, VcsProviderError> {
+        // Build logs live on the CI server, which Bitbucket only links to
+        Ok(None)
+    }
 }
 
 #[cfg(test)]
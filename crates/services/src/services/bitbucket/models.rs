@@ -4,10 +4,11 @@
 //! and provide conversion to the unified data models used by the application.
 
 use chrono::{TimeZone, Utc};
-use db::models::merge::{MergeStatus, PullRequestInfo};
+use db::models::merge::{CiStatus, MergeStatus, PullRequestInfo};
 use serde::{Deserialize, Serialize};
 
 use crate::services::github::UnifiedPrComment;
+use crate::services::vcs_provider::CiCheck;
 
 /// Bitbucket Server paged response wrapper
 #[derive(Debug, Clone, Deserialize)]
@@ -208,6 +209,9 @@ pub struct BitbucketComment {
     pub comments: Vec<BitbucketComment>, // nested replies
     #[serde(default)]
     pub anchor: Option<CommentAnchor>, // present for inline comments
+    /// Set on the root comment of a thread once the thread is resolved
+    #[serde(default)]
+    pub thread_resolved: bool,
 }
 
 impl BitbucketComment {
@@ -228,6 +232,7 @@ impl BitbucketComment {
                 path: anchor.path.clone(),
                 line: anchor.line,
                 diff_hunk: anchor.diff_type.clone().unwrap_or_default(),
+                resolved: self.thread_resolved,
             }
         } else {
             // General comment
@@ -270,6 +275,8 @@ pub struct BitbucketDiffComment {
     pub updated_date: Option<i64>,
     #[serde(default)]
     pub anchor: Option<CommentAnchor>,
+    #[serde(default)]
+    pub thread_resolved: bool,
 }
 
 impl BitbucketDiffComment {
@@ -288,6 +295,7 @@ impl BitbucketDiffComment {
                 path: anchor.path.clone(),
                 line: anchor.line,
                 diff_hunk: anchor.diff_type.clone().unwrap_or_default(),
+                resolved: self.thread_resolved,
             }
         } else {
             UnifiedPrComment::General {
@@ -302,6 +310,38 @@ impl BitbucketDiffComment {
     }
 }
 
+/// Build status reported for a commit by a CI server
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BitbucketBuildStatus {
+    pub state: String, // "SUCCESSFUL", "FAILED", "INPROGRESS"
+    pub key: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+impl BitbucketBuildStatus {
+    /// Convert to the provider-agnostic CI check
+    pub fn to_ci_check(&self) -> CiCheck {
+        let status = match self.state.as_str() {
+            "SUCCESSFUL" => CiStatus::Success,
+            "FAILED" => CiStatus::Failure,
+            _ => CiStatus::Pending,
+        };
+
+        CiCheck {
+            name: self.name.clone().unwrap_or_else(|| self.key.clone()),
+            status,
+            url: self.url.clone(),
+            description: self.description.clone(),
+        }
+    }
+}
+
 /// Bitbucket Server error response
 #[derive(Debug, Clone, Deserialize)]
 pub struct BitbucketError {
@@ -387,6 +427,7 @@ mod tests {
             updated_date: None,
             comments: vec![],
             anchor: None,
+            thread_resolved: false,
         };
 
         let unified = comment.to_unified_comment("https://bitbucket.example.com/pr/1");
@@ -422,18 +463,20 @@ mod tests {
                 file_type: Some("TO".to_string()),
                 diff_type: Some("@@ -10,5 +10,10 @@".to_string()),
             }),
+            thread_resolved: true,
         };
 
         let unified = comment.to_unified_comment("https://bitbucket.example.com/pr/1");
 
         match unified {
-            UnifiedPrComment::Review { id, author, body, path, line, diff_hunk, .. } => {
+            UnifiedPrComment::Review { id, author, body, path, line, diff_hunk, resolved, .. } => {
                 assert_eq!(id, 200);
                 assert_eq!(author, "Code Reviewer");
                 assert_eq!(body, "This is an inline comment");
                 assert_eq!(path, "src/main.rs");
                 assert_eq!(line, Some(42));
                 assert_eq!(diff_hunk, "@@ -10,5 +10,10 @@");
+                assert!(resolved);
             }
             _ => panic!("Expected Review comment"),
         }
@@ -452,6 +495,7 @@ mod tests {
             created_date: 1704067200000,
             updated_date: None,
             anchor: None,
+            thread_resolved: false,
         };
 
         let unified = comment.to_unified_comment("https://bitbucket.example.com/pr/2");
@@ -561,6 +605,28 @@ mod tests {
         assert!(!json.contains("description")); // skipped when None
     }
 
+    #[test]
+    fn test_build_status_to_ci_check() {
+        let json = r#"{
+            "state": "FAILED",
+            "key": "LINT-42",
+            "url": "https://ci.example.com/browse/LINT-42",
+            "description": "2 errors"
+        }"#;
+
+        let build: BitbucketBuildStatus = serde_json::from_str(json).unwrap();
+        let check = build.to_ci_check();
+        assert_eq!(check.name, "LINT-42"); // falls back to the key without a name
+        assert_eq!(check.status, CiStatus::Failure);
+        assert_eq!(check.url.as_deref(), Some("https://ci.example.com/browse/LINT-42"));
+
+        let build = BitbucketBuildStatus {
+            state: "INPROGRESS".to_string(),
+            ..build
+        };
+        assert_eq!(build.to_ci_check().status, CiStatus::Pending);
+    }
+
     // Helper function to create test PR
     fn create_test_pr(state: &str, open: bool, closed: bool) -> BitbucketPullRequest {
         BitbucketPullRequest {
@@ -0,0 +1,199 @@
+//! Named checkpoints of a workspace. A checkpoint snapshots every repo worktree, uncommitted
+//! and untracked changes included, into a commit kept alive by a ref under
+//! `refs/vibe-kanban/checkpoints/`, so it survives later resets and restores.
+
+use std::path::Path;
+
+use db::models::{
+    repo::Repo,
+    workspace::Workspace,
+    workspace_checkpoint::{CheckpointRepoState, WorkspaceCheckpoint},
+    workspace_repo::WorkspaceRepo,
+};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use ts_rs::TS;
+use utils::diff::Diff;
+use uuid::Uuid;
+
+use crate::services::git::{Commit, DiffTarget, GitService, GitServiceError};
+
+#[derive(Debug, Error)]
+pub enum CheckpointError {
+    #[error(transparent)]
+    GitService(#[from] GitServiceError),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+/// What changed in one repo between two checkpoints
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct CheckpointRepoDiff {
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    pub diffs: Vec<Diff>,
+}
+
+/// Ref keeping the snapshot commits of a checkpoint alive, the same in every repo
+pub fn checkpoint_ref(checkpoint_id: Uuid) -> String {
+    format!("refs/vibe-kanban/checkpoints/{checkpoint_id}")
+}
+
+/// Snapshot every repo worktree of the workspace checked out in `workspace_dir`
+pub async fn create(
+    pool: &SqlitePool,
+    git: &GitService,
+    workspace: &Workspace,
+    workspace_dir: &Path,
+    name: &str,
+) -> Result<WorkspaceCheckpoint, CheckpointError> {
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+    let id = Uuid::new_v4();
+    let refname = checkpoint_ref(id);
+    let message = format!("Checkpoint: {name}");
+
+    let mut repo_states = Vec::with_capacity(repos.len());
+    for repo in &repos {
+        let worktree_path = workspace_dir.join(&repo.name);
+        match git.snapshot_worktree(&worktree_path, &refname, &message) {
+            Ok((head_commit, snapshot_commit)) => repo_states.push(CheckpointRepoState {
+                repo_id: repo.id,
+                head_commit,
+                snapshot_commit,
+            }),
+            Err(e) => {
+                delete_refs(git, &repos, id);
+                return Err(e.into());
+            }
+        }
+    }
+
+    match WorkspaceCheckpoint::create(pool, id, workspace.id, name, &repo_states).await {
+        Ok(checkpoint) => Ok(checkpoint),
+        Err(e) => {
+            delete_refs(git, &repos, id);
+            Err(e.into())
+        }
+    }
+}
+
+/// Put the worktrees back to `checkpoint`. The current state is checkpointed first, so
+/// restoring never loses work; returns that checkpoint. Repos added to the workspace after
+/// `checkpoint` was taken are left as they are.
+pub async fn restore(
+    pool: &SqlitePool,
+    git: &GitService,
+    workspace: &Workspace,
+    workspace_dir: &Path,
+    checkpoint: &WorkspaceCheckpoint,
+) -> Result<WorkspaceCheckpoint, CheckpointError> {
+    let backup = create(
+        pool,
+        git,
+        workspace,
+        workspace_dir,
+        &format!("Before restoring \"{}\"", checkpoint.name),
+    )
+    .await?;
+
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+    for state in checkpoint.repo_states.iter() {
+        let Some(repo) = repos.iter().find(|repo| repo.id == state.repo_id) else {
+            continue;
+        };
+        git.restore_worktree_snapshot(
+            &workspace_dir.join(&repo.name),
+            &state.head_commit,
+            &state.snapshot_commit,
+        )?;
+    }
+    Ok(backup)
+}
+
+/// Changes from checkpoint `from` to checkpoint `to`, or to the current worktrees when `to`
+/// is `None`. Repos missing from either side are skipped.
+pub async fn diff(
+    pool: &SqlitePool,
+    git: &GitService,
+    workspace: &Workspace,
+    workspace_dir: &Path,
+    from: &WorkspaceCheckpoint,
+    to: Option<&WorkspaceCheckpoint>,
+) -> Result<Vec<CheckpointRepoDiff>, CheckpointError> {
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+    let mut repo_diffs = Vec::new();
+    for repo in repos {
+        let Some(from_state) = find_state(from, repo.id) else {
+            continue;
+        };
+        let diffs = match to {
+            Some(to) => {
+                let Some(to_state) = find_state(to, repo.id) else {
+                    continue;
+                };
+                git.get_diffs(
+                    DiffTarget::Commits {
+                        repo_path: &repo.path,
+                        from_sha: &from_state.snapshot_commit,
+                        to_sha: &to_state.snapshot_commit,
+                    },
+                    None,
+                )?
+            }
+            None => {
+                let base_commit = Commit::new(
+                    git2::Oid::from_str(&from_state.snapshot_commit)
+                        .map_err(GitServiceError::from)?,
+                );
+                git.get_diffs(
+                    DiffTarget::Worktree {
+                        worktree_path: &workspace_dir.join(&repo.name),
+                        base_commit: &base_commit,
+                    },
+                    None,
+                )?
+            }
+        };
+        repo_diffs.push(CheckpointRepoDiff {
+            repo_id: repo.id,
+            repo_name: repo.name,
+            diffs,
+        });
+    }
+    Ok(repo_diffs)
+}
+
+/// Delete a checkpoint along with the refs of its snapshots. Later checkpoints are
+/// unaffected since each one keeps its own snapshots.
+pub async fn delete(
+    pool: &SqlitePool,
+    git: &GitService,
+    checkpoint: &WorkspaceCheckpoint,
+) -> Result<(), CheckpointError> {
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, checkpoint.workspace_id).await?;
+    delete_refs(git, &repos, checkpoint.id);
+    WorkspaceCheckpoint::delete(pool, checkpoint.id).await?;
+    Ok(())
+}
+
+fn find_state(checkpoint: &WorkspaceCheckpoint, repo_id: Uuid) -> Option<&CheckpointRepoState> {
+    checkpoint
+        .repo_states
+        .iter()
+        .find(|state| state.repo_id == repo_id)
+}
+
+fn delete_refs(git: &GitService, repos: &[Repo], checkpoint_id: Uuid) {
+    let refname = checkpoint_ref(checkpoint_id);
+    for repo in repos {
+        if let Err(e) = git.delete_ref(&repo.path, &refname) {
+            tracing::warn!(
+                "Failed to delete checkpoint ref {} in {}: {}",
+                refname,
+                repo.path.display(),
+                e
+            );
+        }
+    }
+}
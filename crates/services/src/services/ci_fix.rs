@@ -0,0 +1,143 @@
+//! Prompts for coding agent runs dispatched to fix the failing CI checks of a pull request.
+
+use uuid::Uuid;
+
+use crate::services::vcs_provider::CiCheck;
+
+/// Lines kept from the end of a failed check's log; the errors are usually at the end
+const MAX_LOG_LINES: usize = 200;
+/// Longest log line kept, as minified output or progress bars can be huge single lines
+const MAX_LOG_LINE_CHARS: usize = 500;
+
+/// Failing checks the PR monitor found on the pull request of a workspace repo, once all of
+/// its checks finished. Fixing them needs the container, so they are handed over to the
+/// monitor's owner.
+#[derive(Debug, Clone)]
+pub struct PendingCiFix {
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub pr_number: i64,
+    pub pr_url: String,
+    pub head_sha: String,
+    pub failed_checks: Vec<CiCheck>,
+}
+
+/// The end of a check's log, trimmed to fit in a prompt
+fn log_tail(log: &str) -> String {
+    let lines: Vec<&str> = log.trim_end().lines().collect();
+    let skipped = lines.len().saturating_sub(MAX_LOG_LINES);
+
+    let mut tail = String::new();
+    if skipped > 0 {
+        tail.push_str(&format!("[{skipped} earlier lines omitted]\n"));
+    }
+    for line in &lines[skipped..] {
+        match line.char_indices().nth(MAX_LOG_LINE_CHARS) {
+            Some((end, _)) => tail.push_str(&format!("{} [line truncated]\n", &line[..end])),
+            None => tail.push_str(&format!("{line}\n")),
+        }
+    }
+    tail
+}
+
+/// Instructions for a coding agent to fix the failing checks of one pull request, with the
+/// log of each check where the provider could fetch it.
+/// The agent only edits files; committing and pushing is left to Vibe Kanban.
+pub fn build_ci_fix_prompt(
+    repo_name: &str,
+    pr_number: i64,
+    pr_url: &str,
+    head_sha: &str,
+    failed_checks: &[(CiCheck, Option<String>)],
+) -> String {
+    let mut prompt = format!(
+        "CI checks failed on commit {head_sha} of pull request #{pr_number} ({pr_url}) of the \
+         `{repo_name}` repository. Fix them.\n"
+    );
+
+    for (check, log) in failed_checks {
+        prompt.push_str(&format!("\n## {}\n\n", check.name));
+        if let Some(description) = &check.description {
+            prompt.push_str(&format!("{description}\n\n"));
+        }
+        if let Some(url) = &check.url {
+            prompt.push_str(&format!("Details: {url}\n\n"));
+        }
+        match log.as_deref().map(str::trim).filter(|log| !log.is_empty()) {
+            Some(log) => prompt.push_str(&format!(
+                "Log of the failed steps:\n\n```\n{}```\n",
+                log_tail(log)
+            )),
+            None => prompt.push_str(
+                "No log is available for this check. Run the equivalent check locally to see \
+                 what fails.\n",
+            ),
+        }
+    }
+
+    prompt.push_str(
+        "\nChange the code so that the checks pass, and run them locally where you can. If a \
+         failure is unrelated to this pull request, e.g. a flaky test or a broken CI runner, \
+         say so in your final message instead of changing the code. Do not commit or push: \
+         your changes are committed and pushed to the pull request automatically once you are \
+         done.",
+    );
+
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use db::models::merge::CiStatus;
+
+    use super::*;
+
+    fn failed_check(name: &str) -> CiCheck {
+        CiCheck {
+            name: name.to_string(),
+            status: CiStatus::Failure,
+            url: Some(format!("https://ci.example.com/{name}")),
+            description: None,
+        }
+    }
+
+    #[test]
+    fn test_prompt_includes_logs_of_failed_checks() {
+        let checks = vec![
+            (
+                failed_check("lint"),
+                Some("error: unused variable `x`\n".to_string()),
+            ),
+            (failed_check("jenkins"), None),
+        ];
+
+        let prompt = build_ci_fix_prompt(
+            "backend",
+            3,
+            "https://github.com/o/r/pull/3",
+            "abc123",
+            &checks,
+        );
+
+        assert!(prompt.contains("commit abc123 of pull request #3"));
+        assert!(prompt.contains("## lint\n\nDetails: https://ci.example.com/lint\n"));
+        assert!(prompt.contains("```\nerror: unused variable `x`\n```\n"));
+        assert!(prompt.contains("## jenkins"));
+        assert!(prompt.contains("No log is available for this check"));
+        assert!(prompt.contains("Do not commit or push"));
+    }
+
+    #[test]
+    fn test_log_tail_keeps_the_end() {
+        let log: String = (1..=250).map(|i| format!("line {i}\n")).collect();
+
+        let tail = log_tail(&log);
+
+        assert!(tail.starts_with("[50 earlier lines omitted]\nline 51\n"));
+        assert!(tail.ends_with("line 250\n"));
+
+        let tail = log_tail(&"x".repeat(MAX_LOG_LINE_CHARS + 10));
+        assert!(tail.ends_with(" [line truncated]\n"));
+        assert_eq!(tail.len(), MAX_LOG_LINE_CHARS + " [line truncated]\n".len());
+    }
+}
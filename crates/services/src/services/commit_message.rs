@@ -0,0 +1,207 @@
+use std::{path::Path, sync::Arc, time::Duration};
+
+use executors::{
+    env::ExecutionEnv,
+    executors::{ExecutorError, StandardCodingAgentExecutor},
+    logs::{NormalizedEntryType, utils::patch::extract_normalized_entry_from_patch},
+    profile::{ExecutorConfigs, ExecutorProfileId},
+};
+use futures::{TryStreamExt, stream::select};
+use regex::Regex;
+use thiserror::Error;
+use tokio_util::io::ReaderStream;
+use utils::{log_msg::LogMsg, msg_store::MsgStore};
+
+const GENERATION_TIMEOUT: Duration = Duration::from_secs(120);
+/// Time the log normalizer gets to catch up after the executor exits
+const NORMALIZATION_GRACE: Duration = Duration::from_millis(500);
+const MAX_DIFF_LENGTH: usize = 60_000;
+
+pub const CONVENTIONAL_COMMIT_PROMPT: &str = r#"Write a git commit message for the diff below, following the Conventional Commits specification.
+
+- The first line is `<type>(<optional scope>): <description>`, where type is one of feat, fix, docs, style, refactor, perf, test, build, ci, chore or revert, and is at most 72 characters long.
+- Add a body after a blank line only when the change needs explaining.
+- Do not run any tools or modify any files.
+- Reply with the commit message only, without code fences or commentary.
+
+{context}
+
+```diff
+{diff}
+```"#;
+
+#[derive(Debug, Error)]
+pub enum CommitMessageError {
+    #[error(transparent)]
+    Executor(#[from] ExecutorError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Executor {0} is not configured")]
+    UnknownExecutor(String),
+    #[error("Executor did not answer within {0:?}")]
+    Timeout(Duration),
+    #[error("Executor did not write a message")]
+    NoMessage,
+    #[error("Not a Conventional Commit message: {0}")]
+    NotConventional(String),
+}
+
+/// Whether the first line of `message` is a Conventional Commit header
+pub fn is_conventional(message: &str) -> bool {
+    let header = Regex::new(
+        r"^(feat|fix|docs|style|refactor|perf|test|build|ci|chore|revert)(\([^()\s]+\))?!?: \S",
+    )
+    .expect("valid regex");
+    message
+        .lines()
+        .next()
+        .is_some_and(|line| header.is_match(line))
+}
+
+/// `message` as a Conventional Commit, typed as a chore unless it already is one
+pub fn to_conventional(message: &str) -> String {
+    let message = message.trim();
+    if is_conventional(message) {
+        message.to_string()
+    } else {
+        format!("chore: {message}")
+    }
+}
+
+/// Prompt context describing the task the change was made for
+pub fn task_context(title: &str, description: Option<&str>) -> String {
+    match description.map(str::trim).filter(|d| !d.is_empty()) {
+        Some(description) => {
+            format!("The change was made for this task:\n\n{title}\n\n{description}")
+        }
+        None => format!("The change was made for this task: {title}"),
+    }
+}
+
+/// Ask the executor of `executor_profile_id` to write a Conventional Commit message for
+/// `diff`. The executor runs in the empty `scratch_dir` so it can only go by the prompt.
+/// The diff is untrusted input to the agent, so `env` should carry a sandbox that mounts
+/// nothing but `scratch_dir`.
+pub async fn generate(
+    executor_profile_id: &ExecutorProfileId,
+    context: &str,
+    diff: &str,
+    scratch_dir: &Path,
+    env: &ExecutionEnv,
+) -> Result<String, CommitMessageError> {
+    let agent = ExecutorConfigs::get_cached()
+        .get_coding_agent(executor_profile_id)
+        .ok_or_else(|| CommitMessageError::UnknownExecutor(executor_profile_id.to_string()))?;
+    let diff = if diff.len() > MAX_DIFF_LENGTH {
+        let mut end = MAX_DIFF_LENGTH;
+        while !diff.is_char_boundary(end) {
+            end -= 1;
+        }
+        format!("{}\n... (diff truncated)", &diff[..end])
+    } else {
+        diff.to_string()
+    };
+    let prompt = CONVENTIONAL_COMMIT_PROMPT
+        .replace("{context}", context)
+        .replace("{diff}", &diff);
+
+    let mut spawned = agent.spawn(scratch_dir, &prompt, env).await?;
+
+    let store = Arc::new(MsgStore::new());
+    let stdout = spawned.child.inner().stdout.take();
+    let stderr = spawned.child.inner().stderr.take();
+    if let (Some(stdout), Some(stderr)) = (stdout, stderr) {
+        let stdout = ReaderStream::new(stdout)
+            .map_ok(|chunk| LogMsg::Stdout(String::from_utf8_lossy(&chunk).into_owned()));
+        let stderr = ReaderStream::new(stderr)
+            .map_ok(|chunk| LogMsg::Stderr(String::from_utf8_lossy(&chunk).into_owned()));
+        store.clone().spawn_forwarder(select(stdout, stderr));
+    }
+    agent.normalize_logs(store.clone(), scratch_dir);
+
+    let exit_signal = spawned.exit_signal.take();
+    let mut child = spawned.child;
+    let finished = tokio::time::timeout(GENERATION_TIMEOUT, async {
+        match exit_signal {
+            Some(exit_signal) => {
+                tokio::select! {
+                    _ = exit_signal => {}
+                    _ = child.wait() => {}
+                }
+            }
+            None => {
+                let _ = child.wait().await;
+            }
+        }
+    })
+    .await;
+    let _ = child.kill().await;
+    if finished.is_err() {
+        return Err(CommitMessageError::Timeout(GENERATION_TIMEOUT));
+    }
+
+    tokio::time::sleep(NORMALIZATION_GRACE).await;
+    store.push_finished();
+
+    let message = last_assistant_message(&store).ok_or(CommitMessageError::NoMessage)?;
+    let message = strip_code_fence(&message);
+    if !is_conventional(&message) {
+        return Err(CommitMessageError::NotConventional(message));
+    }
+    Ok(message)
+}
+
+fn last_assistant_message(store: &MsgStore) -> Option<String> {
+    store.get_history().iter().rev().find_map(|msg| match msg {
+        LogMsg::JsonPatch(patch) => extract_normalized_entry_from_patch(patch)
+            .filter(|(_, entry)| {
+                matches!(entry.entry_type, NormalizedEntryType::AssistantMessage)
+                    && !entry.content.trim().is_empty()
+            })
+            .map(|(_, entry)| entry.content),
+        _ => None,
+    })
+}
+
+/// Models like to wrap the message in a code block despite being asked not to
+fn strip_code_fence(message: &str) -> String {
+    let trimmed = message.trim();
+    match trimmed
+        .strip_prefix("```")
+        .and_then(|rest| rest.strip_suffix("```"))
+    {
+        // Drop the info string of the opening fence, e.g. ```text
+        Some(inner) => inner
+            .split_once('\n')
+            .map_or(inner, |(_, body)| body)
+            .trim()
+            .to_string(),
+        None => trimmed.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conventional_headers() {
+        assert!(is_conventional("feat(api): add task schedules"));
+        assert!(is_conventional(
+            "fix!: drop legacy config\n\nBREAKING CHANGE: gone"
+        ));
+        assert!(!is_conventional("Add task schedules"));
+        assert!(!is_conventional("feature: add task schedules"));
+        assert!(!is_conventional("feat:missing space"));
+    }
+
+    #[test]
+    fn test_fallback_and_code_fences() {
+        assert_eq!(to_conventional("Add docs\n"), "chore: Add docs");
+        assert_eq!(to_conventional("docs: add docs"), "docs: add docs");
+        assert_eq!(
+            strip_code_fence("```text\nfix: handle empty diffs\n```"),
+            "fix: handle empty diffs"
+        );
+    }
+}
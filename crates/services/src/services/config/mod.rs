@@ -26,6 +26,10 @@ pub type EditorType = versions::v8::EditorType;
 pub type GitHubConfig = versions::v8::GitHubConfig;
 pub type UiLanguage = versions::v8::UiLanguage;
 pub type ShowcaseState = versions::v8::ShowcaseState;
+pub type ConcurrencyConfig = versions::v8::ConcurrencyConfig;
+pub type ModelPrice = versions::v8::ModelPrice;
+pub type PrReviewConfig = versions::v8::PrReviewConfig;
+pub type CiConfig = versions::v8::CiConfig;
 
 /// Will always return config, trying old schemas or eventually returning default
 pub async fn load_config_from_file(config_path: &PathBuf) -> Config {
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use anyhow::Error;
 use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
 use serde::{Deserialize, Serialize};
@@ -17,6 +19,92 @@ fn default_pr_auto_description_enabled() -> bool {
     true
 }
 
+/// Limits on how many coding agents may run at once. Executions beyond the
+/// limits are queued and started in FIFO order as slots free up.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS)]
+pub struct ConcurrencyConfig {
+    /// Maximum coding agents running across all executors, `None` for unlimited
+    #[serde(default)]
+    pub max_concurrent_agents: Option<u32>,
+    /// Optional per-executor caps, applied on top of the global limit
+    #[serde(default)]
+    pub per_executor: HashMap<BaseCodingAgent, u32>,
+}
+
+/// How review comments on pull requests are turned into coding agent follow-ups
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS)]
+#[serde(default)]
+pub struct PrReviewConfig {
+    /// Address new review comments on open pull requests as soon as the PR monitor sees them
+    pub auto_address_comments: bool,
+    /// Reply to each addressed comment with the commit that addressed it
+    pub reply_to_comments: bool,
+}
+
+fn default_max_ci_fix_attempts() -> u32 {
+    3
+}
+
+/// How failing CI checks on pull requests are turned into coding agent follow-ups
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct CiConfig {
+    /// Send the logs of failed checks to the coding agent once all checks of the PR head finished
+    #[serde(default)]
+    pub auto_fix_failures: bool,
+    /// Most fix runs per pull request, so an agent can't keep pushing broken fixes
+    #[serde(default = "default_max_ci_fix_attempts")]
+    pub max_auto_fix_attempts: u32,
+}
+
+impl Default for CiConfig {
+    fn default() -> Self {
+        Self {
+            auto_fix_failures: false,
+            max_auto_fix_attempts: default_max_ci_fix_attempts(),
+        }
+    }
+}
+
+/// Price of a model in USD per million tokens
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, TS)]
+pub struct ModelPrice {
+    pub input_per_mtok: f64,
+    pub output_per_mtok: f64,
+    #[serde(default)]
+    pub cache_write_per_mtok: f64,
+    #[serde(default)]
+    pub cache_read_per_mtok: f64,
+}
+
+impl ModelPrice {
+    const fn new(input: f64, output: f64, cache_write: f64, cache_read: f64) -> Self {
+        Self {
+            input_per_mtok: input,
+            output_per_mtok: output,
+            cache_write_per_mtok: cache_write,
+            cache_read_per_mtok: cache_read,
+        }
+    }
+}
+
+/// Keys are model name prefixes, the longest matching prefix wins
+fn default_model_prices() -> HashMap<String, ModelPrice> {
+    [
+        ("claude-opus-4-5", ModelPrice::new(5.0, 25.0, 6.25, 0.5)),
+        ("claude-opus-4", ModelPrice::new(15.0, 75.0, 18.75, 1.5)),
+        ("claude-sonnet-4", ModelPrice::new(3.0, 15.0, 3.75, 0.3)),
+        ("claude-haiku-4-5", ModelPrice::new(1.0, 5.0, 1.25, 0.1)),
+        ("claude-3-5-haiku", ModelPrice::new(0.8, 4.0, 1.0, 0.08)),
+        ("gpt-5", ModelPrice::new(1.25, 10.0, 0.0, 0.125)),
+        ("gpt-5-mini", ModelPrice::new(0.25, 2.0, 0.0, 0.025)),
+        ("gemini-2.5-pro", ModelPrice::new(1.25, 10.0, 0.0, 0.31)),
+        ("gemini-2.5-flash", ModelPrice::new(0.3, 2.5, 0.0, 0.075)),
+    ]
+    .into_iter()
+    .map(|(model, price)| (model.to_string(), price))
+    .collect()
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, TS)]
 pub struct Config {
     pub config_version: String,
@@ -41,6 +129,14 @@ pub struct Config {
     pub pr_auto_description_enabled: bool,
     #[serde(default)]
     pub pr_auto_description_prompt: Option<String>,
+    #[serde(default)]
+    pub concurrency: ConcurrencyConfig,
+    #[serde(default = "default_model_prices")]
+    pub model_prices: HashMap<String, ModelPrice>,
+    #[serde(default)]
+    pub pr_review: PrReviewConfig,
+    #[serde(default)]
+    pub ci: CiConfig,
 }
 
 impl Config {
@@ -66,6 +162,10 @@ impl Config {
             showcases: old_config.showcases,
             pr_auto_description_enabled: true,
             pr_auto_description_prompt: None,
+            concurrency: ConcurrencyConfig::default(),
+            model_prices: default_model_prices(),
+            pr_review: PrReviewConfig::default(),
+            ci: CiConfig::default(),
         }
     }
 
@@ -116,6 +216,10 @@ impl Default for Config {
             showcases: ShowcaseState::default(),
             pr_auto_description_enabled: true,
             pr_auto_description_prompt: None,
+            concurrency: ConcurrencyConfig::default(),
+            model_prices: default_model_prices(),
+            pr_review: PrReviewConfig::default(),
+            ci: CiConfig::default(),
         }
     }
 }
@@ -0,0 +1,81 @@
+//! Prompts and outcomes for coding agent runs dispatched to resolve git conflicts.
+
+use crate::services::git::ConflictOp;
+
+/// What the exit monitor should do once a conflict resolution run has been handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictResolutionOutcome {
+    /// The run is over, whether or not the conflicts were resolved; finalize as usual
+    Finished,
+    /// Git stopped on the next conflicting commit and another run was dispatched
+    Continuing,
+}
+
+/// Human readable name of the interrupted operation, as used in git commands
+pub fn conflict_op_label(op: &ConflictOp) -> &'static str {
+    match op {
+        ConflictOp::Rebase => "rebase",
+        ConflictOp::Merge => "merge",
+        ConflictOp::CherryPick => "cherry-pick",
+        ConflictOp::Revert => "revert",
+    }
+}
+
+/// Instructions for a coding agent to resolve the conflicts of one repository.
+/// The agent only edits and stages files; continuing the operation is left to Vibe Kanban,
+/// which first checks that no conflict markers remain.
+pub fn build_conflict_resolution_prompt(
+    op: &ConflictOp,
+    repo_name: &str,
+    conflicted_files: &[String],
+    head_subject: Option<&str>,
+    incoming_subject: Option<&str>,
+) -> String {
+    let label = conflict_op_label(op);
+    let mut prompt = format!(
+        "A git {label} in the `{repo_name}` repository stopped on conflicts. Resolve them.\n\n"
+    );
+
+    prompt.push_str(&format!(
+        "HEAD: {}\n{}: {}\n\n",
+        head_subject.unwrap_or("(unknown)"),
+        op.incoming_ref(),
+        incoming_subject.unwrap_or("(unknown)")
+    ));
+
+    prompt.push_str("Conflicted files:\n");
+    for file in conflicted_files {
+        prompt.push_str(&format!("- {file}\n"));
+    }
+
+    prompt.push_str(&format!(
+        "\nFor each file, combine both sides so that the intent of both changes is kept, and \
+         remove every conflict marker (`<<<<<<<`, `=======`, `>>>>>>>`). Stage the resolved \
+         files with `git add`. Do not run `git {label} --continue`, `git {label} --abort` or \
+         `git commit`: the {label} is continued automatically once no conflict markers remain."
+    ));
+
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prompt_lists_files_and_both_sides() {
+        let prompt = build_conflict_resolution_prompt(
+            &ConflictOp::Rebase,
+            "backend",
+            &["src/lib.rs".to_string(), "README.md".to_string()],
+            Some("Bump dependencies"),
+            None,
+        );
+
+        assert!(prompt.contains("git rebase in the `backend` repository"));
+        assert!(prompt.contains("HEAD: Bump dependencies\n"));
+        assert!(prompt.contains("REBASE_HEAD: (unknown)\n"));
+        assert!(prompt.contains("- src/lib.rs\n- README.md\n"));
+        assert!(prompt.contains("Do not run `git rebase --continue`"));
+    }
+}
@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, VecDeque},
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -10,6 +10,8 @@ use db::{
     DBService,
     models::{
         coding_agent_turn::{CodingAgentTurn, CreateCodingAgentTurn},
+        conflict_resolution::{ConflictResolution, ConflictResolutionStatus},
+        execution_budget::ExecutionBudget,
         execution_process::{
             CreateExecutionProcess, ExecutionContext, ExecutionProcess, ExecutionProcessRunReason,
             ExecutionProcessStatus,
@@ -18,24 +20,37 @@ use db::{
         execution_process_repo_state::{
             CreateExecutionProcessRepoState, ExecutionProcessRepoState,
         },
+        execution_process_usage::ExecutionProcessUsage,
+        merge::{Merge, MergeStatus},
+        pipeline_template::PipelineTemplate,
+        pr_review_follow_up::{PrReviewFollowUp, PrReviewFollowUpStatus},
         project::{Project, UpdateProject},
         project_repo::{ProjectRepo, ProjectRepoWithName},
         repo::Repo,
+        search::SearchIndex,
         session::{CreateSession, Session, SessionError},
         task::{Task, TaskStatus},
-        workspace::{Workspace, WorkspaceError},
-        workspace_repo::WorkspaceRepo,
+        task_dependency::TaskDependency,
+        webhook::WebhookEvent,
+        workspace::{CreateWorkspace, Workspace, WorkspaceError},
+        workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
     },
 };
 use executors::{
     actions::{
         ExecutorAction, ExecutorActionType,
+        coding_agent_follow_up::CodingAgentFollowUpRequest,
         coding_agent_initial::CodingAgentInitialRequest,
         script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
     },
     executors::{ExecutorError, StandardCodingAgentExecutor},
-    logs::{NormalizedEntry, NormalizedEntryError, NormalizedEntryType, utils::ConversationPatch},
+    logs::{
+        NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
+        utils::{ConversationPatch, patch::extract_normalized_entry_from_patch},
+    },
     profile::{ExecutorConfigs, ExecutorProfileId},
+    remote::RemoteError,
+    sandbox::SandboxError,
 };
 use futures::{StreamExt, future};
 use sqlx::Error as SqlxError;
@@ -49,9 +64,19 @@ use utils::{
 use uuid::Uuid;
 
 use crate::services::{
+    ci_fix::{PendingCiFix, build_ci_fix_prompt},
+    conflict_resolution::{ConflictResolutionOutcome, build_conflict_resolution_prompt},
     git::{GitService, GitServiceError},
+    github::UnifiedPrComment,
     notification::NotificationService,
+    pipeline::{build_pipeline_action, render_previous_summary},
+    pr_review::{PendingReviewComments, build_comment_reply, build_review_follow_up_prompt},
+    scheduler::{Admission, ExecutionScheduler},
     share::SharePublisher,
+    task_schedule::ScheduledTask,
+    vcs_provider::{VcsProviderRegistry, VcsRepoInfo},
+    verification::{action_after_verification, build_verify_failure_prompt, collect_verify_output},
+    webhook::WebhookService,
     workspace_manager::WorkspaceError as WorkspaceManagerError,
     worktree_manager::WorktreeError,
 };
@@ -73,14 +98,50 @@ pub enum ContainerError {
     WorkspaceManager(#[from] WorkspaceManagerError),
     #[error(transparent)]
     Session(#[from] SessionError),
+    #[error(transparent)]
+    Sandbox(#[from] SandboxError),
+    #[error(transparent)]
+    Remote(#[from] RemoteError),
     #[error("Io error: {0}")]
     Io(#[from] std::io::Error),
     #[error("Failed to kill process: {0}")]
     KillFailed(std::io::Error),
+    #[error("{0}")]
+    BudgetExceeded(String),
     #[error(transparent)]
     Other(#[from] AnyhowError), // Catches any unclassified errors
 }
 
+/// Logged for an agent that was stopped by a restart. Names the configured executors that do
+/// keep running across restarts, see [`StandardCodingAgentExecutor::spools_output`].
+fn stopped_by_restart_message() -> String {
+    let mut spooling: Vec<String> = ExecutorConfigs::get_cached()
+        .executors
+        .into_iter()
+        .filter(|(_, config)| {
+            config
+                .configurations
+                .values()
+                .any(|agent| agent.spools_output())
+        })
+        .map(|(executor, _)| executor.to_string())
+        .collect();
+    spooling.sort();
+
+    let survivors = if spooling.is_empty() {
+        String::new()
+    } else {
+        format!(
+            " Only {} agents keep running across restarts.",
+            spooling.join(", ")
+        )
+    };
+    format!(
+        "Vibe Kanban restarted while this agent was running, so it was stopped.{survivors} \
+         Send a follow-up to continue.\n"
+    )
+}
+
 #[async_trait]
 pub trait ContainerService {
     fn msg_stores(&self) -> &Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>;
@@ -93,15 +154,33 @@ pub trait ContainerService {
 
     fn notification_service(&self) -> &NotificationService;
 
+    fn webhooks(&self) -> &WebhookService;
+
+    fn scheduler(&self) -> &ExecutionScheduler;
+
     fn workspace_to_current_dir(&self, workspace: &Workspace) -> PathBuf;
 
+    /// Whether agents and scripts run on a remote host, with their workspace mirrored back
+    fn runs_remotely(&self) -> bool {
+        false
+    }
+
     async fn create(&self, workspace: &Workspace) -> Result<ContainerRef, ContainerError>;
 
+    /// Create the container of a new workspace from a pre-warmed one, if the deployment keeps
+    /// a pool of them and one matches the workspace's repos and target branches. Returns the
+    /// setup script output of each repo whose setup already ran in it, or `None` when the
+    /// container has to be created from scratch.
+    async fn create_from_pool(
+        &self,
+        workspace: &Workspace,
+    ) -> Result<Option<HashMap<Uuid, String>>, ContainerError>;
+
     async fn kill_all_running_processes(&self) -> Result<(), ContainerError>;
 
     async fn delete(&self, workspace: &Workspace) -> Result<(), ContainerError>;
 
-    /// Check if a task has any running execution processes
+    /// Check if a task has any running or queued execution processes
     async fn has_running_processes(&self, task_id: Uuid) -> Result<bool, ContainerError> {
         let workspaces = Workspace::fetch_all(&self.db().pool, Some(task_id)).await?;
 
@@ -112,7 +191,10 @@ pub trait ContainerService {
                     ExecutionProcess::find_by_session_id(&self.db().pool, session.id, false).await
                 {
                     for process in processes {
-                        if process.status == ExecutionProcessStatus::Running {
+                        if matches!(
+                            process.status,
+                            ExecutionProcessStatus::Running | ExecutionProcessStatus::Queued
+                        ) {
                             return Ok(true);
                         }
                     }
@@ -152,7 +234,9 @@ pub trait ContainerService {
         // Always finalize failed or killed executions, regardless of next action
         if matches!(
             ctx.execution_process.status,
-            ExecutionProcessStatus::Failed | ExecutionProcessStatus::Killed
+            ExecutionProcessStatus::Failed
+                | ExecutionProcessStatus::Killed
+                | ExecutionProcessStatus::BudgetExceeded
         ) {
             return true;
         }
@@ -199,6 +283,10 @@ pub trait ContainerService {
                 "❌ '{}' execution failed\nBranch: {:?}\nExecutor: {:?}",
                 ctx.task.title, ctx.workspace.branch, ctx.session.executor
             ),
+            ExecutionProcessStatus::BudgetExceeded => format!(
+                "⏱️ '{}' was stopped for exceeding its budget\nBranch: {:?}\nExecutor: {:?}",
+                ctx.task.title, ctx.workspace.branch, ctx.session.executor
+            ),
             _ => {
                 tracing::warn!(
                     "Tried to notify workspace completion for {} but process is still running!",
@@ -210,10 +298,293 @@ pub trait ContainerService {
         self.notification_service().notify(&title, &message).await;
     }
 
+    /// Move `task_id` to Done and start the dependents it unblocks
+    async fn mark_task_done(&self, task_id: Uuid) -> Result<(), ContainerError>
+    where
+        Self: Clone + Send + Sync + 'static,
+    {
+        Task::update_status(&self.db().pool, task_id, TaskStatus::Done).await?;
+        self.task_done(task_id);
+        Ok(())
+    }
+
+    /// Start the dependents of `task_id` whose blockers are now all done, in the background.
+    /// Every path that moves a task to Done ends here, and calling it again for the same task
+    /// starts nothing twice.
+    fn task_done(&self, task_id: Uuid)
+    where
+        Self: Clone + Send + Sync + 'static,
+    {
+        let container = self.clone();
+        tokio::spawn(async move {
+            container.start_unblocked_dependents(task_id).await;
+        });
+    }
+
+    /// Auto-start dependents of `task_id` whose blockers are now all done
+    async fn start_unblocked_dependents(&self, task_id: Uuid) {
+        let pool = &self.db().pool;
+        let dependents = match TaskDependency::find_unblocked_dependents(pool, task_id).await {
+            Ok(dependents) => dependents,
+            Err(e) => {
+                tracing::error!("Failed to find unblocked dependents of task {task_id}: {e}");
+                return;
+            }
+        };
+
+        for dependent in dependents {
+            // Claim the dependent so a blocker finishing twice, or two blockers finishing at
+            // once, start it only once
+            match Task::update_status_from(
+                pool,
+                dependent.id,
+                TaskStatus::Todo,
+                TaskStatus::InProgress,
+            )
+            .await
+            {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(e) => {
+                    tracing::error!("Failed to claim unblocked task {}: {}", dependent.id, e);
+                    continue;
+                }
+            }
+            if let Some(publisher) = self.share_publisher()
+                && let Err(err) = publisher.update_shared_task_by_id(dependent.id).await
+            {
+                tracing::warn!(
+                    ?err,
+                    "Failed to propagate shared task update for {}",
+                    dependent.id
+                );
+            }
+
+            match self.start_dependent_task(task_id, &dependent).await {
+                Ok(workspace) => tracing::info!(
+                    "Task {} unblocked by {}, started workspace {}",
+                    dependent.id,
+                    task_id,
+                    workspace.id
+                ),
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to auto-start unblocked task {}: {}",
+                        dependent.id,
+                        e
+                    );
+                    // Hand it back so it can be started by hand or by the next blocker
+                    if let Err(e) = Task::update_status_from(
+                        pool,
+                        dependent.id,
+                        TaskStatus::InProgress,
+                        TaskStatus::Todo,
+                    )
+                    .await
+                    {
+                        tracing::error!("Failed to release task {}: {}", dependent.id, e);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Create and start a workspace for a task that was just unblocked. Each repo targets
+    /// the branch the task's own latest workspace or its parent workspace targets, else the
+    /// branch the blocking task was merged into so the chain builds on its predecessor's
+    /// work, else the repo's current branch.
+    async fn start_dependent_task(
+        &self,
+        blocking_task_id: Uuid,
+        task: &Task,
+    ) -> Result<Workspace, ContainerError> {
+        let pool = &self.db().pool;
+        let project = task
+            .parent_project(pool)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+
+        let repos = ProjectRepo::find_repos_for_project(pool, project.id).await?;
+        if repos.is_empty() {
+            return Err(ContainerError::Other(anyhow!(
+                "Project {} has no repositories",
+                project.id
+            )));
+        }
+
+        let mut own_workspace_ids: Vec<Uuid> = Workspace::fetch_all(pool, Some(task.id))
+            .await?
+            .first()
+            .map(|workspace| workspace.id)
+            .into_iter()
+            .collect();
+        own_workspace_ids.extend(task.parent_workspace_id);
+        let mut own_targets: HashMap<Uuid, String> = HashMap::new();
+        for workspace_id in own_workspace_ids {
+            for workspace_repo in WorkspaceRepo::find_by_workspace_id(pool, workspace_id).await? {
+                own_targets
+                    .entry(workspace_repo.repo_id)
+                    .or_insert(workspace_repo.target_branch);
+            }
+        }
+
+        // Attempts and their merges come newest first
+        let mut merged_targets: HashMap<Uuid, String> = HashMap::new();
+        for workspace in Workspace::fetch_all(pool, Some(blocking_task_id)).await? {
+            for merge in Merge::find_by_workspace_id(pool, workspace.id).await? {
+                let (repo_id, target_branch) = match merge {
+                    Merge::Direct(merge) => (merge.repo_id, merge.target_branch_name),
+                    Merge::Pr(merge) if matches!(merge.pr_info.status, MergeStatus::Merged) => {
+                        (merge.repo_id, merge.target_branch_name)
+                    }
+                    Merge::Pr(_) => continue,
+                };
+                merged_targets.entry(repo_id).or_insert(target_branch);
+            }
+        }
+
+        let mut workspace_repos = Vec::with_capacity(repos.len());
+        for repo in repos {
+            let target_branch = match own_targets
+                .remove(&repo.id)
+                .or_else(|| merged_targets.remove(&repo.id))
+            {
+                Some(branch) => branch,
+                None => self
+                    .git()
+                    .get_current_branch(&repo.path)
+                    .map_err(GitServiceError::from)?,
+            };
+            workspace_repos.push(CreateWorkspaceRepo {
+                repo_id: repo.id,
+                target_branch,
+            });
+        }
+
+        let executor_profile_id = self.executor_profile_for_task(task.id).await;
+        self.create_and_start_workspace(task, executor_profile_id, &workspace_repos)
+            .await
+    }
+
+    /// Start the task created by a schedule with the schedule's executor profile.
+    /// Repos target the schedule's branches, or every project repo at its current
+    /// branch when the schedule doesn't list any.
+    async fn start_scheduled_task(
+        &self,
+        scheduled: &ScheduledTask,
+    ) -> Result<Workspace, ContainerError> {
+        let schedule = &scheduled.schedule;
+        let workspace_repos: Vec<CreateWorkspaceRepo> = if schedule.repos.is_empty() {
+            let repos =
+                ProjectRepo::find_repos_for_project(&self.db().pool, schedule.project_id).await?;
+            if repos.is_empty() {
+                return Err(ContainerError::Other(anyhow!(
+                    "Project {} has no repositories",
+                    schedule.project_id
+                )));
+            }
+            let mut workspace_repos = Vec::with_capacity(repos.len());
+            for repo in repos {
+                let target_branch = self
+                    .git()
+                    .get_current_branch(&repo.path)
+                    .map_err(GitServiceError::from)?;
+                workspace_repos.push(CreateWorkspaceRepo {
+                    repo_id: repo.id,
+                    target_branch,
+                });
+            }
+            workspace_repos
+        } else {
+            schedule
+                .repos
+                .iter()
+                .map(|repo| CreateWorkspaceRepo {
+                    repo_id: repo.repo_id,
+                    target_branch: repo.target_branch.clone(),
+                })
+                .collect()
+        };
+
+        self.create_and_start_workspace(
+            &scheduled.task,
+            schedule.executor_profile_id.0.clone(),
+            &workspace_repos,
+        )
+        .await
+    }
+
+    /// Create a workspace for the task on a fresh branch and start the coding agent in it.
+    /// Used for tasks started without a user, e.g. unblocked dependents and scheduled runs.
+    async fn create_and_start_workspace(
+        &self,
+        task: &Task,
+        executor_profile_id: ExecutorProfileId,
+        workspace_repos: &[CreateWorkspaceRepo],
+    ) -> Result<Workspace, ContainerError> {
+        let pool = &self.db().pool;
+        let project = task
+            .parent_project(pool)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        let agent_working_dir = project
+            .default_agent_working_dir
+            .as_ref()
+            .filter(|dir| !dir.is_empty())
+            .cloned();
+
+        let workspace_id = Uuid::new_v4();
+        let branch = self
+            .git_branch_from_workspace(&workspace_id, &task.title)
+            .await;
+        let workspace = Workspace::create(
+            pool,
+            &CreateWorkspace {
+                branch,
+                agent_working_dir,
+            },
+            workspace_id,
+            task.id,
+        )
+        .await?;
+        WorkspaceRepo::create_many(pool, workspace.id, workspace_repos).await?;
+
+        self.start_workspace(&workspace, executor_profile_id, None)
+            .await?;
+        Ok(workspace)
+    }
+
+    /// The executor profile last used for a task, or the configured default
+    async fn executor_profile_for_task(&self, task_id: Uuid) -> ExecutorProfileId {
+        let pool = &self.db().pool;
+        if let Ok(workspaces) = Workspace::fetch_all(pool, Some(task_id)).await {
+            for workspace in workspaces {
+                if let Ok(Some(session)) =
+                    Session::find_latest_by_workspace_id(pool, workspace.id).await
+                    && let Ok(profile) =
+                        ExecutionProcess::latest_executor_profile_for_session(pool, session.id)
+                            .await
+                {
+                    return profile;
+                }
+            }
+        }
+        self.default_executor_profile().await
+    }
+
+    /// Re-attach to an execution process a previous server left running, returns whether it
+    /// did. Processes that are not re-attached to are cleaned up as orphans.
+    async fn reattach_execution(&self, _execution_process: &ExecutionProcess) -> bool {
+        false
+    }
+
     /// Cleanup executions marked as running in the db, call at startup
     async fn cleanup_orphan_executions(&self) -> Result<(), ContainerError> {
         let running_processes = ExecutionProcess::find_running(&self.db().pool).await?;
         for process in running_processes {
+            if self.reattach_execution(&process).await {
+                continue;
+            }
             tracing::info!(
                 "Found orphaned execution process {} for session {}",
                 process.id,
@@ -235,6 +606,26 @@ pub trait ContainerService {
                 );
                 continue;
             }
+            // Only spooled agents survive a restart, so the log of any other says why it
+            // failed instead of just ending
+            if let Ok(action) = process.executor_action()
+                && action.base_executor().is_some()
+                && !action.spools_output()
+                && let Ok(line) =
+                    serde_json::to_string(&LogMsg::Stderr(stopped_by_restart_message()))
+                && let Err(e) = ExecutionProcessLogs::append_log_line(
+                    &self.db().pool,
+                    process.id,
+                    &format!("{line}\n"),
+                )
+                .await
+            {
+                tracing::warn!(
+                    "Failed to explain failure of execution process {}: {}",
+                    process.id,
+                    e
+                );
+            }
             // Capture after-head commit OID per repository
             if let Ok(ctx) = ExecutionProcess::load_context(&self.db().pool, process.id).await
                 && let Some(ref container_ref) = ctx.workspace.container_ref
@@ -268,6 +659,7 @@ pub trait ContainerService {
                 ExecutionProcessRunReason::CodingAgent
                     | ExecutionProcessRunReason::SetupScript
                     | ExecutionProcessRunReason::CleanupScript
+                    | ExecutionProcessRunReason::Verify
             ) && let Ok(Some(session)) =
                 Session::find_by_id(&self.db().pool, process.session_id).await
                 && let Ok(Some(workspace)) =
@@ -298,6 +690,64 @@ pub trait ContainerService {
         Ok(())
     }
 
+    /// Hand execution processes left queued by a previous run back to the scheduler,
+    /// call at startup after orphan cleanup
+    async fn resume_queued_executions(&self) -> Result<(), ContainerError> {
+        let queued_processes = ExecutionProcess::find_queued(&self.db().pool).await?;
+        for process in queued_processes {
+            let Some(executor) = process
+                .executor_action()
+                .ok()
+                .and_then(|action| action.base_executor())
+            else {
+                tracing::warn!(
+                    "Queued execution process {} is not a coding agent, marking as failed",
+                    process.id
+                );
+                ExecutionProcess::update_completion(
+                    &self.db().pool,
+                    process.id,
+                    ExecutionProcessStatus::Failed,
+                    None,
+                )
+                .await?;
+                continue;
+            };
+
+            match self
+                .scheduler()
+                .enqueue(process.id, process.session_id, executor)
+                .await
+            {
+                Admission::Start => {
+                    if let Err(e) = self.start_queued_execution(process.id).await {
+                        tracing::error!(
+                            "Failed to start queued execution process {}: {}",
+                            process.id,
+                            e
+                        );
+                        ExecutionProcess::update_completion(
+                            &self.db().pool,
+                            process.id,
+                            ExecutionProcessStatus::Failed,
+                            None,
+                        )
+                        .await?;
+                        self.release_execution_slot(process.id).await;
+                    }
+                }
+                Admission::Queued { position } => {
+                    tracing::info!(
+                        "Re-queued execution process {} at position {}",
+                        process.id,
+                        position
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Backfill before_head_commit for legacy execution processes.
     /// Rules:
     /// - If a process has after_head_commit and missing before_head_commit,
@@ -413,6 +863,12 @@ pub trait ContainerService {
                                 } else {
                                     project.default_agent_working_dir.clone()
                                 },
+                                sandbox_config: None,
+                                merge_mode: None,
+                                conventional_commits: None,
+                                warm_workspace_count: None,
+                                commit_message_executor_profile_id: None,
+                                clear_commit_message_executor_profile: false,
                             },
                         )
                         .await?;
@@ -461,6 +917,32 @@ pub trait ContainerService {
         Some(root_action)
     }
 
+    /// Verify scripts of every repo that has one, chained in repo order
+    fn verify_actions_for_repos(
+        &self,
+        repos: &[ProjectRepoWithName],
+        verify_retries: u32,
+    ) -> Option<ExecutorAction> {
+        repos
+            .iter()
+            .rev()
+            .filter_map(|repo| repo.verify_script.as_ref().map(|script| (repo, script)))
+            .fold(None, |next_action, (repo, script)| {
+                Some(
+                    ExecutorAction::new(
+                        ExecutorActionType::ScriptRequest(ScriptRequest {
+                            script: script.clone(),
+                            language: ScriptRequestLanguage::Bash,
+                            context: ScriptContext::VerifyScript,
+                            working_dir: Some(repo.repo_name.clone()),
+                        }),
+                        next_action.map(Box::new),
+                    )
+                    .with_verify_retries(verify_retries),
+                )
+            })
+    }
+
     fn setup_actions_for_repos(&self, repos: &[ProjectRepoWithName]) -> Option<ExecutorAction> {
         let repos_with_setup: Vec<_> = repos.iter().filter(|r| r.setup_script.is_some()).collect();
 
@@ -548,7 +1030,10 @@ pub trait ContainerService {
                     {
                         continue;
                     }
-                    if process.status == ExecutionProcessStatus::Running {
+                    if matches!(
+                        process.status,
+                        ExecutionProcessStatus::Running | ExecutionProcessStatus::Queued
+                    ) {
                         self.stop_execution(&process, ExecutionProcessStatus::Killed)
                             .await
                             .unwrap_or_else(|e| {
@@ -587,6 +1072,36 @@ pub trait ContainerService {
 
     async fn try_commit_changes(&self, ctx: &ExecutionContext) -> Result<bool, ContainerError>;
 
+    /// The executor that writes commit messages for the project: its commit message
+    /// executor, or else the one the session last ran
+    async fn commit_message_executor(
+        &self,
+        project: &Project,
+        session_id: Option<Uuid>,
+    ) -> Option<ExecutorProfileId> {
+        match (&project.commit_message_executor_profile_id, session_id) {
+            (Some(profile), _) => Some(profile.0.clone()),
+            (None, Some(session_id)) => {
+                ExecutionProcess::latest_executor_profile_for_session(&self.db().pool, session_id)
+                    .await
+                    .ok()
+            }
+            (None, None) => None,
+        }
+    }
+
+    /// A Conventional Commit message for `diff` of the task's workspace, written by
+    /// [`Self::commit_message_executor`]. The agent is sandboxed and only runs when the
+    /// scheduler has a free slot and the task's token budget is not used up, so this is
+    /// `None` whenever no message could be written and the fallback should stay.
+    async fn generate_commit_message(
+        &self,
+        project: &Project,
+        task_id: Uuid,
+        session_id: Option<Uuid>,
+        diff: &str,
+    ) -> Option<String>;
+
     async fn copy_project_files(
         &self,
         source_dir: &Path,
@@ -609,6 +1124,8 @@ pub trait ContainerService {
 
     async fn git_branch_prefix(&self) -> String;
 
+    async fn default_executor_profile(&self) -> ExecutorProfileId;
+
     async fn git_branch_from_workspace(&self, workspace_id: &Uuid, task_title: &str) -> String {
         let task_title_id = git_branch_id(task_title);
         let prefix = self.git_branch_prefix().await;
@@ -820,6 +1337,9 @@ pub trait ContainerService {
 
             if let Some(store) = store {
                 let mut stream = store.history_plus_stream();
+                // Assistant messages stream in as repeated replaces of one entry, so the
+                // latest one is only indexed for search once a different entry follows
+                let mut pending_message: Option<(usize, String)> = None;
 
                 while let Some(Ok(msg)) = stream.next().await {
                     match &msg {
@@ -870,23 +1390,69 @@ pub trait ContainerService {
                                 );
                             }
                         }
+                        LogMsg::TokenUsage(usage) => {
+                            if let Err(e) =
+                                ExecutionProcessUsage::upsert(&db.pool, execution_id, usage).await
+                            {
+                                tracing::error!(
+                                    "Failed to record token usage for execution process {}: {}",
+                                    execution_id,
+                                    e
+                                );
+                            }
+                        }
                         LogMsg::Finished => {
                             break;
                         }
-                        LogMsg::JsonPatch(_) => continue,
+                        LogMsg::JsonPatch(patch) => {
+                            let Some((index, entry)) = extract_normalized_entry_from_patch(patch)
+                            else {
+                                continue;
+                            };
+                            if pending_message.as_ref().is_some_and(|(i, _)| *i != index)
+                                && let Some((i, content)) = pending_message.take()
+                            {
+                                index_assistant_message(&db, execution_id, i, &content).await;
+                            }
+                            if matches!(entry.entry_type, NormalizedEntryType::AssistantMessage) {
+                                pending_message = Some((index, entry.content));
+                            }
+                        }
                     }
                 }
+
+                if let Some((index, content)) = pending_message {
+                    index_assistant_message(&db, execution_id, index, &content).await;
+                }
             }
         })
     }
 
+    /// Start the setup scripts and coding agent of a new workspace. With a pipeline, the
+    /// coding agent runs each of its steps in turn instead of a single request.
     async fn start_workspace(
         &self,
         workspace: &Workspace,
         executor_profile_id: ExecutorProfileId,
+        pipeline: Option<&PipelineTemplate>,
     ) -> Result<ExecutionProcess, ContainerError> {
-        // Create container
-        self.create(workspace).await?;
+        // Create container, from a pre-warmed one when the pool has one for these branches
+        let warm_setups = match self.create_from_pool(workspace).await {
+            Ok(Some(setups)) => setups,
+            Ok(None) => {
+                self.create(workspace).await?;
+                HashMap::new()
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to use a pre-warmed workspace for workspace {}: {}",
+                    workspace.id,
+                    e
+                );
+                self.create(workspace).await?;
+                HashMap::new()
+            }
+        };
 
         // Get parent task
         let task = workspace
@@ -920,9 +1486,25 @@ pub trait ContainerService {
 
         let prompt = task.to_prompt();
 
+        // Setup scripts that already ran in the pre-warmed workspace are not run again
+        for project_repo in &project_repos {
+            if let Some(output) = warm_setups.get(&project_repo.repo_id)
+                && let Some(action) = Self::setup_action_for_repo(project_repo)
+                && let Err(e) = self
+                    .record_completed_setup(&workspace, &session, &action, output)
+                    .await
+            {
+                tracing::warn!(
+                    "Failed to record the pre-warmed setup of repo '{}': {}",
+                    project_repo.repo_name,
+                    e
+                );
+            }
+        }
+
         let repos_with_setup: Vec<_> = project_repos
             .iter()
-            .filter(|pr| pr.setup_script.is_some())
+            .filter(|pr| pr.setup_script.is_some() && !warm_setups.contains_key(&pr.repo_id))
             .collect();
 
         let all_parallel = repos_with_setup.iter().all(|pr| pr.parallel_setup_script);
@@ -935,14 +1517,24 @@ pub trait ContainerService {
             .filter(|dir| !dir.is_empty())
             .cloned();
 
-        let coding_action = ExecutorAction::new(
-            ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
-                prompt,
-                executor_profile_id: executor_profile_id.clone(),
-                working_dir,
-            }),
-            cleanup_action.map(Box::new),
-        );
+        let coding_action = match pipeline {
+            Some(template) => {
+                let action = build_pipeline_action(template, &prompt, working_dir)
+                    .ok_or_else(|| anyhow!("Pipeline '{}' has no steps", template.name))?;
+                match cleanup_action {
+                    Some(cleanup_action) => action.append_action(cleanup_action),
+                    None => action,
+                }
+            }
+            None => ExecutorAction::new(
+                ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                    prompt,
+                    executor_profile_id: executor_profile_id.clone(),
+                    working_dir,
+                }),
+                cleanup_action.map(Box::new),
+            ),
+        };
 
         let execution_process = if all_parallel {
             // All parallel: start each setup independently, then start coding agent
@@ -982,6 +1574,68 @@ pub trait ContainerService {
         Ok(execution_process)
     }
 
+    /// Record a setup script that ran before the workspace existed as a completed execution
+    /// process with its output, so that it shows up like one that ran in the workspace
+    async fn record_completed_setup(
+        &self,
+        workspace: &Workspace,
+        session: &Session,
+        action: &ExecutorAction,
+        output: &str,
+    ) -> Result<(), ContainerError> {
+        let pool = &self.db().pool;
+        let workspace_root = self.workspace_to_current_dir(workspace);
+        let repo_states: Vec<_> = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id)
+            .await?
+            .iter()
+            .map(|repo| {
+                let head = self
+                    .git()
+                    .get_head_info(&workspace_root.join(&repo.name))
+                    .ok()
+                    .map(|h| h.oid);
+                CreateExecutionProcessRepoState {
+                    repo_id: repo.id,
+                    before_head_commit: head.clone(),
+                    after_head_commit: head,
+                    merge_commit: None,
+                }
+            })
+            .collect();
+
+        let execution_process = ExecutionProcess::create(
+            pool,
+            &CreateExecutionProcess {
+                session_id: session.id,
+                executor_action: action.clone(),
+                run_reason: ExecutionProcessRunReason::SetupScript,
+                status: ExecutionProcessStatus::Running,
+            },
+            Uuid::new_v4(),
+            &repo_states,
+        )
+        .await?;
+
+        for message in [LogMsg::Stdout(output.to_string()), LogMsg::Finished] {
+            let json_line = serde_json::to_string(&message).map_err(AnyhowError::from)?;
+            ExecutionProcessLogs::append_log_line(
+                pool,
+                execution_process.id,
+                &format!("{json_line}\n"),
+            )
+            .await?;
+        }
+
+        ExecutionProcess::update_completion(
+            pool,
+            execution_process.id,
+            ExecutionProcessStatus::Completed,
+            Some(0),
+        )
+        .await?;
+        Ok(())
+    }
+
     async fn start_execution(
         &self,
         workspace: &Workspace,
@@ -989,18 +1643,52 @@ pub trait ContainerService {
         executor_action: &ExecutorAction,
         run_reason: &ExecutionProcessRunReason,
     ) -> Result<ExecutionProcess, ContainerError> {
-        // Update task status to InProgress when starting an execution
+        let execution_process = self
+            .create_execution(workspace, session, executor_action, run_reason)
+            .await?;
+        self.dispatch_execution(workspace, session, execution_process, executor_action)
+            .await
+    }
+
+    /// Record an execution process without running it yet, so rows that refer to it can
+    /// exist before it can finish. `dispatch_execution` runs it.
+    async fn create_execution(
+        &self,
+        workspace: &Workspace,
+        session: &Session,
+        executor_action: &ExecutorAction,
+        run_reason: &ExecutionProcessRunReason,
+    ) -> Result<ExecutionProcess, ContainerError> {
         let task = workspace
             .parent_task(&self.db().pool)
             .await?
             .ok_or(SqlxError::RowNotFound)?;
-        if task.status != TaskStatus::InProgress
-            && run_reason != &ExecutionProcessRunReason::DevServer
-        {
-            Task::update_status(&self.db().pool, task.id, TaskStatus::InProgress).await?;
 
-            if let Some(publisher) = self.share_publisher()
-                && let Err(err) = publisher.update_shared_task_by_id(task.id).await
+        // A follow-up over the task's budget is refused rather than started and stopped
+        if run_reason == &ExecutionProcessRunReason::CodingAgent
+            && matches!(
+                executor_action.typ(),
+                ExecutorActionType::CodingAgentFollowUpRequest(_)
+            )
+            && let Some(max) =
+                ExecutionBudget::limits_for_task(&self.db().pool, task.project_id, task.id)
+                    .await?
+                    .max_follow_ups
+            && ExecutionProcess::count_follow_ups_for_task(&self.db().pool, task.id).await? >= max
+        {
+            return Err(ContainerError::BudgetExceeded(format!(
+                "The task has used all {max} follow-ups its budget allows."
+            )));
+        }
+
+        // Update task status to InProgress when starting an execution
+        if task.status != TaskStatus::InProgress
+            && run_reason != &ExecutionProcessRunReason::DevServer
+        {
+            Task::update_status(&self.db().pool, task.id, TaskStatus::InProgress).await?;
+
+            if let Some(publisher) = self.share_publisher()
+                && let Err(err) = publisher.update_shared_task_by_id(task.id).await
             {
                 tracing::warn!(
                     ?err,
@@ -1036,10 +1724,17 @@ pub trait ContainerService {
                 merge_commit: None,
             });
         }
+        // Coding agents are created as queued and only start once the scheduler grants a slot
+        let scheduled_executor = executor_action.base_executor();
         let create_execution_process = CreateExecutionProcess {
             session_id: session.id,
             executor_action: executor_action.clone(),
             run_reason: run_reason.clone(),
+            status: if scheduled_executor.is_some() {
+                ExecutionProcessStatus::Queued
+            } else {
+                ExecutionProcessStatus::Running
+            },
         };
 
         let execution_process = ExecutionProcess::create(
@@ -1073,9 +1768,65 @@ pub trait ContainerService {
             )
             .await?;
         }
+        Ok(execution_process)
+    }
+
+    /// Run an execution process recorded by `create_execution`, or queue it until the
+    /// scheduler has a slot for its executor
+    async fn dispatch_execution(
+        &self,
+        workspace: &Workspace,
+        session: &Session,
+        mut execution_process: ExecutionProcess,
+        executor_action: &ExecutorAction,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        if let Some(executor) = executor_action.base_executor() {
+            match self
+                .scheduler()
+                .enqueue(execution_process.id, session.id, executor)
+                .await
+            {
+                Admission::Start => {
+                    if let Err(e) =
+                        ExecutionProcess::mark_started(&self.db().pool, execution_process.id).await
+                    {
+                        self.release_execution_slot(execution_process.id).await;
+                        return Err(e.into());
+                    }
+                    execution_process.status = ExecutionProcessStatus::Running;
+                }
+                Admission::Queued { position } => {
+                    tracing::info!(
+                        "Execution process {} queued at position {}",
+                        execution_process.id,
+                        position
+                    );
+                    return Ok(execution_process);
+                }
+            }
+        }
+
+        if let Err(e) = self
+            .launch_execution(workspace, &execution_process, executor_action)
+            .await
+        {
+            self.release_execution_slot(execution_process.id).await;
+            return Err(e);
+        }
+        Ok(execution_process)
+    }
 
+    /// Spawn the executor for an execution process that holds a slot (or needs none) and
+    /// start streaming its logs. On failure the process is marked failed and the task
+    /// moved back to review.
+    async fn launch_execution(
+        &self,
+        workspace: &Workspace,
+        execution_process: &ExecutionProcess,
+        executor_action: &ExecutorAction,
+    ) -> Result<(), ContainerError> {
         if let Err(start_error) = self
-            .start_execution_inner(workspace, &execution_process, executor_action)
+            .start_execution_inner(workspace, execution_process, executor_action)
             .await
         {
             // Mark process as failed
@@ -1093,7 +1844,14 @@ pub trait ContainerService {
                     update_error
                 );
             }
-            Task::update_status(&self.db().pool, task.id, TaskStatus::InReview).await?;
+            let mut failed_process = execution_process.clone();
+            failed_process.status = ExecutionProcessStatus::Failed;
+            self.webhooks().emit_execution(
+                WebhookEvent::ExecutionFailed,
+                &failed_process,
+                workspace,
+            );
+            Task::update_status(&self.db().pool, workspace.task_id, TaskStatus::InReview).await?;
 
             // Emit stderr error message
             let log_message = LogMsg::Stderr(format!("Failed to start execution: {start_error}"));
@@ -1132,9 +1890,26 @@ pub trait ContainerService {
             return Err(start_error);
         }
 
-        // Start processing normalised logs for executor requests and follow ups
+        self.spawn_log_normalizer(workspace, execution_process.id, executor_action)
+            .await;
+        self.spawn_stream_raw_logs_to_db(&execution_process.id);
+        self.webhooks().emit_execution(
+            WebhookEvent::ExecutionStarted,
+            execution_process,
+            workspace,
+        );
+        Ok(())
+    }
+
+    /// Start processing normalised logs for executor requests and follow ups
+    async fn spawn_log_normalizer(
+        &self,
+        workspace: &Workspace,
+        execution_process_id: Uuid,
+        executor_action: &ExecutorAction,
+    ) {
         let workspace_root = self.workspace_to_current_dir(workspace);
-        if let Some(msg_store) = self.get_msg_store_by_id(&execution_process.id).await
+        if let Some(msg_store) = self.get_msg_store_by_id(&execution_process_id).await
             && let Some((executor_profile_id, working_dir)) = match executor_action.typ() {
                 ExecutorActionType::CodingAgentInitialRequest(request) => Some((
                     &request.executor_profile_id,
@@ -1158,9 +1933,98 @@ pub trait ContainerService {
                 );
             }
         }
+    }
 
-        self.spawn_stream_raw_logs_to_db(&execution_process.id);
-        Ok(execution_process)
+    /// Launch a queued execution process once the scheduler has given it a slot
+    async fn start_queued_execution(
+        &self,
+        execution_process_id: Uuid,
+    ) -> Result<(), ContainerError> {
+        let ctx = ExecutionProcess::load_context(&self.db().pool, execution_process_id).await?;
+        if ctx.execution_process.status != ExecutionProcessStatus::Queued {
+            // Cancelled while waiting, nothing to start
+            return Ok(());
+        }
+
+        // The workspace may have been cleaned up while the process was waiting
+        self.ensure_container_exists(&ctx.workspace).await?;
+        let workspace = Workspace::find_by_id(&self.db().pool, ctx.workspace.id)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+
+        ExecutionProcess::mark_started(&self.db().pool, execution_process_id).await?;
+        let mut execution_process = ctx.execution_process;
+        execution_process.status = ExecutionProcessStatus::Running;
+        let executor_action = execution_process.executor_action()?.clone();
+        self.launch_execution(&workspace, &execution_process, &executor_action)
+            .await
+    }
+
+    /// Free the scheduler slot held by an execution process and start the queued
+    /// executions that take its place. No-op for processes the scheduler doesn't track.
+    async fn release_execution_slot(&self, execution_process_id: Uuid) {
+        let admitted = self.scheduler().release(execution_process_id).await;
+        self.start_admitted_executions(admitted).await;
+    }
+
+    /// Start queued executions that fit within the current concurrency limits, e.g. after
+    /// the limits were raised
+    async fn admit_queued_executions(&self) {
+        let admitted = self.scheduler().admit_waiting().await;
+        self.start_admitted_executions(admitted).await;
+    }
+
+    /// Start executions the scheduler handed a slot, releasing the slot of any that fails
+    async fn start_admitted_executions(&self, admitted: Vec<Uuid>) {
+        let mut admitted: VecDeque<Uuid> = admitted.into();
+        while let Some(next_id) = admitted.pop_front() {
+            if let Err(e) = self.start_queued_execution(next_id).await {
+                tracing::error!("Failed to start queued execution process {next_id}: {e}");
+                if let Err(e) = ExecutionProcess::update_completion(
+                    &self.db().pool,
+                    next_id,
+                    ExecutionProcessStatus::Failed,
+                    None,
+                )
+                .await
+                {
+                    tracing::error!("Failed to mark execution process {next_id} as failed: {e}");
+                }
+                admitted.extend(self.scheduler().release(next_id).await);
+            }
+        }
+    }
+
+    /// Take a queued execution process out of the queue before it ever ran
+    async fn cancel_queued_execution(
+        &self,
+        execution_process: &ExecutionProcess,
+        status: ExecutionProcessStatus,
+    ) -> Result<(), ContainerError> {
+        ExecutionProcess::update_completion(&self.db().pool, execution_process.id, status, None)
+            .await?;
+        self.release_execution_slot(execution_process.id).await;
+
+        if let Ok(ctx) = ExecutionProcess::load_context(&self.db().pool, execution_process.id).await
+        {
+            match Task::update_status(&self.db().pool, ctx.task.id, TaskStatus::InReview).await {
+                Ok(_) => {
+                    if let Some(publisher) = self.share_publisher()
+                        && let Err(err) = publisher.update_shared_task_by_id(ctx.task.id).await
+                    {
+                        tracing::warn!(
+                            ?err,
+                            "Failed to propagate shared task update for {}",
+                            ctx.task.id
+                        );
+                    }
+                }
+                Err(e) => {
+                    tracing::error!("Failed to update task status to InReview: {e}");
+                }
+            }
+        }
+        Ok(())
     }
 
     async fn try_start_next_action(&self, ctx: &ExecutionContext) -> Result<(), ContainerError> {
@@ -1174,6 +2038,20 @@ pub trait ContainerService {
 
         // Determine the run reason of the next action
         let next_run_reason = match (action.typ(), next_action.typ()) {
+            (
+                _,
+                ExecutorActionType::ScriptRequest(ScriptRequest {
+                    context: ScriptContext::VerifyScript,
+                    ..
+                }),
+            ) => ExecutionProcessRunReason::Verify,
+            (
+                ExecutorActionType::ScriptRequest(ScriptRequest {
+                    context: ScriptContext::VerifyScript,
+                    ..
+                }),
+                ExecutorActionType::ScriptRequest(_),
+            ) => ExecutionProcessRunReason::CleanupScript,
             (ExecutorActionType::ScriptRequest(_), ExecutorActionType::ScriptRequest(_)) => {
                 ExecutionProcessRunReason::SetupScript
             }
@@ -1189,10 +2067,778 @@ pub trait ContainerService {
             ) => ExecutionProcessRunReason::CodingAgent,
         };
 
-        self.start_execution(&ctx.workspace, &ctx.session, next_action, &next_run_reason)
+        let next_action = self.prepare_next_action(ctx, next_action).await?;
+
+        self.start_execution(&ctx.workspace, &ctx.session, &next_action, &next_run_reason)
             .await?;
 
         tracing::debug!("Started next action: {:?}", next_action);
         Ok(())
     }
+
+    /// The next pipeline step may build on the summary of the step that just finished
+    async fn prepare_next_action(
+        &self,
+        ctx: &ExecutionContext,
+        next_action: &ExecutorAction,
+    ) -> Result<ExecutorAction, ContainerError> {
+        if next_action.pipeline_step().is_none() {
+            return Ok(next_action.clone());
+        }
+        let summary = CodingAgentTurn::find_by_execution_process_id(
+            &self.db().pool,
+            ctx.execution_process.id,
+        )
+        .await?
+        .and_then(|turn| turn.summary);
+        Ok(render_previous_summary(next_action, summary.as_deref()))
+    }
+
+    /// Continue the latest coding agent session of `session` with `prompt`, or start a new
+    /// one when the session has no coding agent turn yet
+    async fn coding_agent_follow_up_action(
+        &self,
+        workspace: &Workspace,
+        session: &Session,
+        prompt: String,
+    ) -> Result<ExecutorActionType, ContainerError> {
+        let executor_profile_id = self.executor_profile_for_task(workspace.task_id).await;
+        let latest_agent_session_id =
+            ExecutionProcess::find_latest_coding_agent_turn_session_id(&self.db().pool, session.id)
+                .await?;
+
+        let working_dir = workspace
+            .agent_working_dir
+            .as_ref()
+            .filter(|dir| !dir.is_empty())
+            .cloned();
+
+        Ok(match latest_agent_session_id {
+            Some(session_id) => {
+                ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
+                    prompt,
+                    session_id,
+                    executor_profile_id,
+                    working_dir,
+                })
+            }
+            None => ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                prompt,
+                executor_profile_id,
+                working_dir,
+            }),
+        })
+    }
+
+    /// Run the verify scripts of the project's repos after a successful coding agent turn.
+    /// The rest of the chain follows them when `include_next` is set, so it only starts
+    /// once verification passes. Returns false when no repo has a verify script.
+    async fn start_verification(&self, ctx: &ExecutionContext, include_next: bool) -> bool {
+        let result: Result<bool, ContainerError> = async {
+            let action = ctx.execution_process.executor_action()?;
+            let project_repos =
+                ProjectRepo::find_by_project_id_with_names(&self.db().pool, ctx.task.project_id)
+                    .await?;
+            let Some(mut verify_action) =
+                self.verify_actions_for_repos(&project_repos, action.verify_retries())
+            else {
+                return Ok(false);
+            };
+            if include_next && let Some(next_action) = action.next_action() {
+                let next_action = self.prepare_next_action(ctx, next_action).await?;
+                verify_action = verify_action.append_action(next_action);
+            }
+
+            self.start_execution(
+                &ctx.workspace,
+                &ctx.session,
+                &verify_action,
+                &ExecutionProcessRunReason::Verify,
+            )
+            .await?;
+            Ok(true)
+        }
+        .await;
+
+        result.unwrap_or_else(|e| {
+            tracing::error!(
+                "Failed to start verify scripts for workspace {}: {}",
+                ctx.workspace.id,
+                e
+            );
+            false
+        })
+    }
+
+    /// Send the output of a failed verify script back to the coding agent, as long as the
+    /// repo has retries left. The follow-up carries the rest of the chain, so verification
+    /// runs again once the agent is done. Returns true when a follow-up was started.
+    async fn retry_failed_verification(&self, ctx: &ExecutionContext) -> bool {
+        if !matches!(ctx.execution_process.status, ExecutionProcessStatus::Failed) {
+            return false;
+        }
+
+        let result: Result<bool, ContainerError> = async {
+            let action = ctx.execution_process.executor_action()?;
+            let ExecutorActionType::ScriptRequest(request) = action.typ() else {
+                return Ok(false);
+            };
+            let project_repos =
+                ProjectRepo::find_by_project_id_with_names(&self.db().pool, ctx.task.project_id)
+                    .await?;
+            let Some(repo) = project_repos
+                .iter()
+                .find(|repo| request.working_dir.as_ref() == Some(&repo.repo_name))
+            else {
+                return Ok(false);
+            };
+
+            let retries = action.verify_retries();
+            if retries >= repo.verify_max_retries.max(0) as u32 {
+                tracing::info!(
+                    "Verify script of repo '{}' failed after {} retries",
+                    repo.repo_name,
+                    retries
+                );
+                return Ok(false);
+            }
+
+            let output = match self.get_msg_store_by_id(&ctx.execution_process.id).await {
+                Some(store) => collect_verify_output(&store.get_history()),
+                None => String::new(),
+            };
+            let prompt = build_verify_failure_prompt(
+                &repo.repo_name,
+                &request.script,
+                ctx.execution_process.exit_code,
+                &output,
+            );
+            let action_type = self
+                .coding_agent_follow_up_action(&ctx.workspace, &ctx.session, prompt)
+                .await?;
+            let follow_up =
+                ExecutorAction::new(action_type, action_after_verification(action).map(Box::new))
+                    .with_verify_retries(retries + 1);
+
+            self.start_execution(
+                &ctx.workspace,
+                &ctx.session,
+                &follow_up,
+                &ExecutionProcessRunReason::CodingAgent,
+            )
+            .await?;
+            Ok(true)
+        }
+        .await;
+
+        result.unwrap_or_else(|e| {
+            tracing::error!(
+                "Failed to send verify output back to the agent for workspace {}: {}",
+                ctx.workspace.id,
+                e
+            );
+            false
+        })
+    }
+
+    /// The session follow-ups of the workspace continue, created with the task's executor
+    /// when the workspace has none yet
+    async fn latest_or_new_session(
+        &self,
+        workspace: &Workspace,
+    ) -> Result<Session, ContainerError> {
+        let pool = &self.db().pool;
+        if let Some(session) = Session::find_latest_by_workspace_id(pool, workspace.id).await? {
+            return Ok(session);
+        }
+        let executor_profile_id = self.executor_profile_for_task(workspace.task_id).await;
+        Ok(Session::create(
+            pool,
+            &CreateSession {
+                executor: Some(executor_profile_id.executor.to_string()),
+            },
+            Uuid::new_v4(),
+            workspace.id,
+        )
+        .await?)
+    }
+
+    /// Dispatch a coding agent to resolve the conflicts of an interrupted rebase, merge,
+    /// cherry-pick or revert in one repo of the workspace
+    async fn start_conflict_resolution(
+        &self,
+        workspace: &Workspace,
+        repo: &Repo,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        let pool = &self.db().pool;
+        let worktree_path = self.workspace_to_current_dir(workspace).join(&repo.name);
+
+        let op = self
+            .git()
+            .detect_conflict_op(&worktree_path)?
+            .ok_or_else(|| anyhow!("No conflicts to resolve in repo '{}'", repo.name))?;
+        let conflicted_files = self.git().get_conflicted_files(&worktree_path)?;
+        if conflicted_files.is_empty() {
+            return Err(ContainerError::Other(anyhow!(
+                "No conflicts to resolve in repo '{}'",
+                repo.name
+            )));
+        }
+        let (head_subject, incoming_subject) = self
+            .git()
+            .get_conflict_commit_subjects(&worktree_path, &op)?;
+        let prompt = build_conflict_resolution_prompt(
+            &op,
+            &repo.name,
+            &conflicted_files,
+            head_subject.as_deref(),
+            incoming_subject.as_deref(),
+        );
+
+        let session = self.latest_or_new_session(workspace).await?;
+        let action_type = self
+            .coding_agent_follow_up_action(workspace, &session, prompt)
+            .await?;
+
+        // The resolution has to be on record before the run can finish and look it up
+        let action = ExecutorAction::new(action_type, None);
+        let execution_process = self
+            .create_execution(
+                workspace,
+                &session,
+                &action,
+                &ExecutionProcessRunReason::CodingAgent,
+            )
+            .await?;
+        if let Err(e) =
+            ConflictResolution::create(pool, execution_process.id, repo.id, &conflicted_files).await
+        {
+            if let Err(update_error) = ExecutionProcess::update_completion(
+                pool,
+                execution_process.id,
+                ExecutionProcessStatus::Failed,
+                None,
+            )
+            .await
+            {
+                tracing::error!(
+                    "Failed to mark execution process {} as failed: {}",
+                    execution_process.id,
+                    update_error
+                );
+            }
+            return Err(e.into());
+        }
+
+        self.dispatch_execution(workspace, &session, execution_process, &action)
+            .await
+    }
+
+    /// Once a conflict resolution run exits, check that no conflict markers remain and
+    /// continue the interrupted operation. Returns `None` for processes that were not
+    /// dispatched to resolve conflicts.
+    async fn finish_conflict_resolution(
+        &self,
+        ctx: &ExecutionContext,
+        success: bool,
+    ) -> Option<ConflictResolutionOutcome> {
+        let pool = &self.db().pool;
+        let resolution = match ConflictResolution::find_pending_by_execution_process_id(
+            pool,
+            ctx.execution_process.id,
+        )
+        .await
+        {
+            Ok(Some(resolution)) => resolution,
+            Ok(None) => return None,
+            Err(e) => {
+                tracing::error!(
+                    "Failed to load conflict resolution for {}: {}",
+                    ctx.execution_process.id,
+                    e
+                );
+                return None;
+            }
+        };
+
+        let result = if success {
+            self.continue_resolved_conflicts(ctx, &resolution).await
+        } else {
+            Err(ContainerError::Other(anyhow!(
+                "The coding agent did not finish successfully"
+            )))
+        };
+
+        match result {
+            Ok(next_conflicts) => {
+                if let Err(e) = ConflictResolution::update_status(
+                    pool,
+                    resolution.id,
+                    ConflictResolutionStatus::Resolved,
+                    None,
+                )
+                .await
+                {
+                    tracing::error!("Failed to mark conflict resolution resolved: {}", e);
+                }
+
+                // A rebase replaying several commits can stop again on a later one
+                let Some(repo) = next_conflicts else {
+                    return Some(ConflictResolutionOutcome::Finished);
+                };
+                match self.start_conflict_resolution(&ctx.workspace, &repo).await {
+                    Ok(_) => Some(ConflictResolutionOutcome::Continuing),
+                    Err(e) => {
+                        tracing::error!(
+                            "Failed to dispatch conflict resolution for repo '{}': {}",
+                            repo.name,
+                            e
+                        );
+                        Some(ConflictResolutionOutcome::Finished)
+                    }
+                }
+            }
+            Err(e) => {
+                let error = e.to_string();
+                tracing::warn!(
+                    "Conflict resolution {} for workspace {} failed: {}",
+                    resolution.id,
+                    ctx.workspace.id,
+                    error
+                );
+                if let Err(e) = ConflictResolution::update_status(
+                    pool,
+                    resolution.id,
+                    ConflictResolutionStatus::Failed,
+                    Some(&error),
+                )
+                .await
+                {
+                    tracing::error!("Failed to mark conflict resolution failed: {}", e);
+                }
+
+                if !matches!(ctx.execution_process.status, ExecutionProcessStatus::Killed) {
+                    self.notification_service()
+                        .notify(
+                            &format!("Conflicts Unresolved: {}", ctx.task.title),
+                            &format!("⚠️ '{}' still has conflicts\n{}", ctx.task.title, error),
+                        )
+                        .await;
+                }
+                Some(ConflictResolutionOutcome::Finished)
+            }
+        }
+    }
+
+    /// Verify the agent's resolution and continue the interrupted operation. Returns the
+    /// repo when git stopped on new conflicts that need another run.
+    async fn continue_resolved_conflicts(
+        &self,
+        ctx: &ExecutionContext,
+        resolution: &ConflictResolution,
+    ) -> Result<Option<Repo>, ContainerError> {
+        let repo = Repo::find_by_id(&self.db().pool, resolution.repo_id)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        let worktree_path = self
+            .workspace_to_current_dir(&ctx.workspace)
+            .join(&repo.name);
+
+        let remaining = self
+            .git()
+            .files_with_conflict_markers(&worktree_path, &resolution.conflicted_files.0);
+        if !remaining.is_empty() {
+            return Err(ContainerError::Other(anyhow!(
+                "Conflict markers remain in: {}",
+                remaining.join(", ")
+            )));
+        }
+
+        match self
+            .git()
+            .continue_conflict_op(&worktree_path, &resolution.conflicted_files.0)
+        {
+            Ok(()) => Ok(None),
+            Err(GitServiceError::MergeConflicts(_)) => Ok(Some(repo)),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Dispatch a coding agent with a prompt about the pull request of one repo of the
+    /// workspace. Once it finishes, its commits are pushed to the pull request.
+    #[allow(clippy::too_many_arguments)]
+    async fn start_pr_follow_up(
+        &self,
+        workspace: &Workspace,
+        repo: &Repo,
+        pr_number: i64,
+        pr_url: &str,
+        prompt: String,
+        comment_keys: &[String],
+        reply_to_comments: bool,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        let pool = &self.db().pool;
+        let session = self.latest_or_new_session(workspace).await?;
+        let action_type = self
+            .coding_agent_follow_up_action(workspace, &session, prompt)
+            .await?;
+
+        // The follow-up has to be on record before the run can finish and look it up
+        let action = ExecutorAction::new(action_type, None);
+        let execution_process = self
+            .create_execution(
+                workspace,
+                &session,
+                &action,
+                &ExecutionProcessRunReason::CodingAgent,
+            )
+            .await?;
+        if let Err(e) = PrReviewFollowUp::create(
+            pool,
+            execution_process.id,
+            workspace.id,
+            repo.id,
+            pr_number,
+            pr_url,
+            comment_keys,
+            reply_to_comments,
+        )
+        .await
+        {
+            if let Err(update_error) = ExecutionProcess::update_completion(
+                pool,
+                execution_process.id,
+                ExecutionProcessStatus::Failed,
+                None,
+            )
+            .await
+            {
+                tracing::error!(
+                    "Failed to mark execution process {} as failed: {}",
+                    execution_process.id,
+                    update_error
+                );
+            }
+            return Err(e.into());
+        }
+
+        self.dispatch_execution(workspace, &session, execution_process, &action)
+            .await
+    }
+
+    /// Dispatch a coding agent to address review comments on the pull request of one repo
+    /// of the workspace. Once it finishes, its commits are pushed to the pull request.
+    async fn start_pr_review_follow_up(
+        &self,
+        workspace: &Workspace,
+        repo: &Repo,
+        pr_number: i64,
+        pr_url: &str,
+        comments: &[UnifiedPrComment],
+        reply_to_comments: bool,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        if comments.is_empty() {
+            return Err(ContainerError::Other(anyhow!(
+                "No review comments to address on PR #{}",
+                pr_number
+            )));
+        }
+        let prompt = build_review_follow_up_prompt(&repo.name, pr_number, pr_url, comments);
+        let comment_keys: Vec<String> = comments.iter().map(UnifiedPrComment::key).collect();
+        self.start_pr_follow_up(
+            workspace,
+            repo,
+            pr_number,
+            pr_url,
+            prompt,
+            &comment_keys,
+            reply_to_comments,
+        )
+        .await
+    }
+
+    /// The workspace and repo of a follow-up the PR monitor handed over, with the workspace's
+    /// container created if it was cleaned up in the meantime
+    async fn load_pr_follow_up_target(
+        &self,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+    ) -> Result<(Workspace, Repo), ContainerError> {
+        let pool = &self.db().pool;
+        let workspace = Workspace::find_by_id(pool, workspace_id)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        let repo = Repo::find_by_id(pool, repo_id)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+
+        self.ensure_container_exists(&workspace).await?;
+        // Reload so the container ref set by `ensure_container_exists` is visible
+        let workspace = Workspace::find_by_id(pool, workspace.id)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        Ok((workspace, repo))
+    }
+
+    /// Address the review comments the PR monitor found, with the same follow-up as the
+    /// manual action
+    async fn start_pending_review_follow_up(
+        &self,
+        pending: &PendingReviewComments,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        let (workspace, repo) = self
+            .load_pr_follow_up_target(pending.workspace_id, pending.repo_id)
+            .await?;
+        self.start_pr_review_follow_up(
+            &workspace,
+            &repo,
+            pending.pr_number,
+            &pending.pr_url,
+            &pending.comments,
+            pending.reply_to_comments,
+        )
+        .await
+    }
+
+    /// Dispatch a coding agent to fix the failing checks the PR monitor found, with the log
+    /// of each check where the provider can fetch it. Its changes are pushed like those of a
+    /// review follow-up, which it is recorded as, without comments.
+    async fn start_ci_fix_follow_up(
+        &self,
+        pending: &PendingCiFix,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        let (workspace, repo) = self
+            .load_pr_follow_up_target(pending.workspace_id, pending.repo_id)
+            .await?;
+
+        let repo_info = VcsRepoInfo::from_remote_url(&pending.pr_url).map_err(AnyhowError::from)?;
+        let registry = VcsProviderRegistry::new_with_loaded_credentials()
+            .await
+            .map_err(AnyhowError::from)?;
+        let provider = registry
+            .get_provider(repo_info.provider_type)
+            .ok_or_else(|| anyhow!("Unsupported VCS provider for URL: {}", pending.pr_url))?;
+
+        let mut failed_checks = Vec::with_capacity(pending.failed_checks.len());
+        for check in &pending.failed_checks {
+            let log = match provider.get_ci_check_log(&repo_info, check).await {
+                Ok(log) => log,
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to fetch the log of check '{}' on PR #{}: {}",
+                        check.name,
+                        pending.pr_number,
+                        e
+                    );
+                    None
+                }
+            };
+            failed_checks.push((check.clone(), log));
+        }
+
+        let prompt = build_ci_fix_prompt(
+            &repo.name,
+            pending.pr_number,
+            &pending.pr_url,
+            &pending.head_sha,
+            &failed_checks,
+        );
+        self.start_pr_follow_up(
+            &workspace,
+            &repo,
+            pending.pr_number,
+            &pending.pr_url,
+            prompt,
+            &[],
+            false,
+        )
+        .await
+    }
+
+    /// Once a PR review or CI fix follow-up exits and its changes are committed, push them to
+    /// the pull request and, if asked to, reply to the addressed comments. Does nothing for
+    /// processes that were not dispatched for a pull request.
+    async fn finish_pr_review_follow_up(&self, ctx: &ExecutionContext, success: bool) {
+        let pool = &self.db().pool;
+        let follow_up = match PrReviewFollowUp::find_pending_by_execution_process_id(
+            pool,
+            ctx.execution_process.id,
+        )
+        .await
+        {
+            Ok(Some(follow_up)) => follow_up,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::error!(
+                    "Failed to load PR review follow-up for {}: {}",
+                    ctx.execution_process.id,
+                    e
+                );
+                return;
+            }
+        };
+
+        let result = if success {
+            self.push_pr_review_changes(ctx, &follow_up).await
+        } else {
+            Err(ContainerError::Other(anyhow!(
+                "The coding agent did not finish successfully"
+            )))
+        };
+
+        match result {
+            Ok((repo, commit_sha)) => {
+                if let Err(e) = PrReviewFollowUp::update_status(
+                    pool,
+                    follow_up.id,
+                    PrReviewFollowUpStatus::Pushed,
+                    Some(&commit_sha),
+                    None,
+                )
+                .await
+                {
+                    tracing::error!("Failed to mark PR review follow-up pushed: {}", e);
+                }
+
+                if follow_up.reply_to_comments
+                    && let Err(e) = self
+                        .reply_to_addressed_comments(&follow_up, &repo, &commit_sha)
+                        .await
+                {
+                    tracing::warn!(
+                        "Failed to reply to review comments on PR #{}: {}",
+                        follow_up.pr_number,
+                        e
+                    );
+                }
+            }
+            Err(e) => {
+                let error = e.to_string();
+                tracing::warn!(
+                    "PR review follow-up {} for workspace {} failed: {}",
+                    follow_up.id,
+                    ctx.workspace.id,
+                    error
+                );
+                if let Err(e) = PrReviewFollowUp::update_status(
+                    pool,
+                    follow_up.id,
+                    PrReviewFollowUpStatus::Failed,
+                    None,
+                    Some(&error),
+                )
+                .await
+                {
+                    tracing::error!("Failed to mark PR review follow-up failed: {}", e);
+                }
+
+                if !matches!(ctx.execution_process.status, ExecutionProcessStatus::Killed) {
+                    // Only CI fixes are dispatched without comments
+                    let title = if follow_up.comment_keys.0.is_empty() {
+                        "Failing Checks Not Fixed"
+                    } else {
+                        "Review Comments Not Addressed"
+                    };
+                    self.notification_service()
+                        .notify(
+                            &format!("{}: {}", title, ctx.task.title),
+                            &format!(
+                                "⚠️ Nothing was pushed to PR #{} of '{}'\n{}",
+                                follow_up.pr_number, ctx.task.title, error
+                            ),
+                        )
+                        .await;
+                }
+            }
+        }
+    }
+
+    /// Push the commits the agent made for a PR review follow-up. Returns the repo and the
+    /// pushed HEAD commit.
+    async fn push_pr_review_changes(
+        &self,
+        ctx: &ExecutionContext,
+        follow_up: &PrReviewFollowUp,
+    ) -> Result<(Repo, String), ContainerError> {
+        let pool = &self.db().pool;
+        let repo = Repo::find_by_id(pool, follow_up.repo_id)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        let worktree_path = self
+            .workspace_to_current_dir(&ctx.workspace)
+            .join(&repo.name);
+
+        let head_oid = self.git().get_head_info(&worktree_path)?.oid;
+        let before_head =
+            ExecutionProcessRepoState::find_by_execution_process_id(pool, ctx.execution_process.id)
+                .await?
+                .into_iter()
+                .find(|state| state.repo_id == repo.id)
+                .and_then(|state| state.before_head_commit);
+        if before_head.as_deref() == Some(head_oid.as_str()) {
+            return Err(ContainerError::Other(anyhow!(
+                "The coding agent made no changes"
+            )));
+        }
+
+        self.git()
+            .push_to_github(&worktree_path, &ctx.workspace.branch, false)?;
+        Ok((repo, head_oid))
+    }
+
+    /// Reply to each comment a pushed follow-up addressed with the commit that addressed it
+    async fn reply_to_addressed_comments(
+        &self,
+        follow_up: &PrReviewFollowUp,
+        repo: &Repo,
+        commit_sha: &str,
+    ) -> Result<(), ContainerError> {
+        let repo_info = self.git().get_vcs_repo_info(&repo.path)?;
+        let remote_url = self.git().get_remote_url(&repo.path)?;
+        let registry = VcsProviderRegistry::new_with_loaded_credentials()
+            .await
+            .map_err(AnyhowError::from)?;
+        let provider = registry
+            .detect_from_url(&remote_url)
+            .ok_or_else(|| anyhow!("Unsupported VCS provider for URL: {}", remote_url))?;
+
+        // Comments are fetched again, as only their keys were stored
+        let comments = provider
+            .get_pr_comments(&repo_info, follow_up.pr_number)
+            .await
+            .map_err(AnyhowError::from)?;
+        let body = build_comment_reply(commit_sha);
+        for comment in comments
+            .iter()
+            .filter(|comment| follow_up.comment_keys.0.contains(&comment.key()))
+        {
+            if let Err(e) = provider
+                .reply_to_pr_comment(&repo_info, follow_up.pr_number, comment, &body)
+                .await
+            {
+                tracing::warn!(
+                    "Failed to reply to comment {} on PR #{}: {}",
+                    comment.key(),
+                    follow_up.pr_number,
+                    e
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+async fn index_assistant_message(db: &DBService, execution_id: Uuid, index: usize, content: &str) {
+    if content.trim().is_empty() {
+        return;
+    }
+    if let Err(e) = SearchIndex::upsert_message(&db.pool, execution_id, index as i64, content).await
+    {
+        tracing::error!(
+            "Failed to index assistant message {} for execution process {}: {}",
+            index,
+            execution_id,
+            e
+        );
+    }
 }
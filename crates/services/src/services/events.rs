@@ -1,18 +1,32 @@
-use std::{str::FromStr, sync::Arc};
+use std::{
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
 
 use db::{
     DBService,
     models::{
-        execution_process::ExecutionProcess, project::Project, scratch::Scratch, task::Task,
+        execution_process::ExecutionProcess,
+        merge::Merge,
+        project::Project,
+        scratch::Scratch,
+        task::{Task, TaskStatus},
+        webhook::WebhookEvent,
         workspace::Workspace,
     },
 };
 use serde_json::json;
-use sqlx::{Error as SqlxError, Sqlite, SqlitePool, decode::Decode, sqlite::SqliteOperation};
+use sqlx::{
+    Error as SqlxError, Sqlite, SqliteConnection, SqlitePool,
+    decode::Decode,
+    sqlite::{PreupdateHookResult, SqliteOperation},
+};
 use tokio::sync::RwLock;
 use utils::msg_store::MsgStore;
 use uuid::Uuid;
 
+use crate::services::{scheduler::ExecutionScheduler, webhook::WebhookService};
+
 #[path = "events/patches.rs"]
 pub mod patches;
 #[path = "events/streams.rs"]
@@ -21,25 +35,74 @@ mod streams;
 pub mod types;
 
 pub use patches::{
-    execution_process_patch, project_patch, scratch_patch, task_patch, workspace_patch,
+    execution_process_patch, merge_patch, project_patch, queue_position_patch, scratch_patch,
+    task_patch, workspace_patch,
 };
 pub use types::{EventError, EventPatch, EventPatchInner, HookTables, RecordTypes};
 
+/// Positions of the `tasks` columns read by the preupdate hook, which only addresses columns
+/// by position
+#[derive(Debug, Clone, Copy)]
+struct TaskColumns {
+    id: i32,
+    project_id: i32,
+    title: i32,
+    status: i32,
+}
+
+impl TaskColumns {
+    /// Look the columns up by name instead of relying on the order migrations left them in.
+    /// `None` when the table lacks one of them.
+    async fn load(conn: &mut SqliteConnection) -> Result<Option<Self>, SqlxError> {
+        let names: Vec<String> =
+            sqlx::query_scalar("SELECT name FROM pragma_table_info('tasks') ORDER BY cid")
+                .fetch_all(&mut *conn)
+                .await?;
+        let position = |column: &str| {
+            names
+                .iter()
+                .position(|name| name == column)
+                .map(|index| index as i32)
+        };
+        let (Some(id), Some(project_id), Some(title), Some(status)) = (
+            position("id"),
+            position("project_id"),
+            position("title"),
+            position("status"),
+        ) else {
+            return Ok(None);
+        };
+        Ok(Some(Self {
+            id,
+            project_id,
+            title,
+            status,
+        }))
+    }
+}
+
 #[derive(Clone)]
 pub struct EventService {
     msg_store: Arc<MsgStore>,
     db: DBService,
     #[allow(dead_code)]
     entry_count: Arc<RwLock<usize>>,
+    scheduler: ExecutionScheduler,
 }
 
 impl EventService {
     /// Creates a new EventService that will work with a DBService configured with hooks
-    pub fn new(db: DBService, msg_store: Arc<MsgStore>, entry_count: Arc<RwLock<usize>>) -> Self {
+    pub fn new(
+        db: DBService,
+        msg_store: Arc<MsgStore>,
+        entry_count: Arc<RwLock<usize>>,
+        scheduler: ExecutionScheduler,
+    ) -> Self {
         Self {
             msg_store,
             db,
             entry_count,
+            scheduler,
         }
     }
 
@@ -77,11 +140,51 @@ impl EventService {
         Ok(())
     }
 
+    /// Webhook payload for an update that changes the status of a task. Old and new values
+    /// are only both available before the update, so this runs in the preupdate hook.
+    fn task_status_change(
+        columns: TaskColumns,
+        preupdate: &PreupdateHookResult<'_>,
+    ) -> Option<serde_json::Value> {
+        let previous_status = preupdate
+            .get_old_column_value(columns.status)
+            .ok()
+            .and_then(|value| <TaskStatus as Decode<Sqlite>>::decode(value).ok())?;
+        let status = preupdate
+            .get_new_column_value(columns.status)
+            .ok()
+            .and_then(|value| <TaskStatus as Decode<Sqlite>>::decode(value).ok())?;
+        if previous_status == status {
+            return None;
+        }
+
+        let task_id = preupdate
+            .get_new_column_value(columns.id)
+            .ok()
+            .and_then(|value| <Uuid as Decode<Sqlite>>::decode(value).ok());
+        let project_id = preupdate
+            .get_new_column_value(columns.project_id)
+            .ok()
+            .and_then(|value| <Uuid as Decode<Sqlite>>::decode(value).ok());
+        let title = preupdate
+            .get_new_column_value(columns.title)
+            .ok()
+            .and_then(|value| <String as Decode<Sqlite>>::decode(value).ok());
+        Some(json!({
+            "task_id": task_id,
+            "project_id": project_id,
+            "title": title,
+            "previous_status": previous_status,
+            "status": status,
+        }))
+    }
+
     /// Creates the hook function that should be used with DBService::new_with_after_connect
     pub fn create_hook(
         msg_store: Arc<MsgStore>,
         entry_count: Arc<RwLock<usize>>,
         db_service: DBService,
+        webhooks: WebhookService,
     ) -> impl for<'a> Fn(
         &'a mut sqlx::sqlite::SqliteConnection,
     ) -> std::pin::Pin<
@@ -93,12 +196,36 @@ impl EventService {
             let msg_store_for_hook = msg_store.clone();
             let entry_count_for_hook = entry_count.clone();
             let db_for_hook = db_service.clone();
+            let webhooks_for_hook = webhooks.clone();
             Box::pin(async move {
+                let task_columns = TaskColumns::load(conn).await?;
+                // Status changes wait for their transaction, so rolled back ones are dropped
+                let task_status_changes = Arc::new(Mutex::new(Vec::new()));
                 let mut handle = conn.lock_handle().await?;
                 let runtime_handle = tokio::runtime::Handle::current();
+                handle.set_commit_hook({
+                    let task_status_changes = task_status_changes.clone();
+                    move || {
+                        for change in task_status_changes.lock().unwrap().drain(..) {
+                            webhooks_for_hook.emit(WebhookEvent::TaskStatusChanged, change);
+                        }
+                        true
+                    }
+                });
+                handle.set_rollback_hook({
+                    let task_status_changes = task_status_changes.clone();
+                    move || task_status_changes.lock().unwrap().clear()
+                });
                 handle.set_preupdate_hook({
                     let msg_store_for_preupdate = msg_store_for_hook.clone();
-                    move |preupdate: sqlx::sqlite::PreupdateHookResult<'_>| {
+                    move |preupdate: PreupdateHookResult<'_>| {
+                        if preupdate.operation == SqliteOperation::Update
+                            && preupdate.table == "tasks"
+                            && let Some(columns) = task_columns
+                            && let Some(change) = Self::task_status_change(columns, &preupdate)
+                        {
+                            task_status_changes.lock().unwrap().push(change);
+                        }
                         if preupdate.operation != SqliteOperation::Delete {
                             return;
                         }
@@ -172,6 +299,10 @@ impl EventService {
                                     // Deletions handled in preupdate hook for reliable data capture
                                     return;
                                 }
+                                (HookTables::Merges, SqliteOperation::Delete) => {
+                                    // Merges are only deleted along with their workspace
+                                    return;
+                                }
                                 (HookTables::Tasks, _) => {
                                     match Task::find_by_rowid(&db.pool, rowid).await {
                                         Ok(Some(task)) => RecordTypes::Task(task),
@@ -232,6 +363,16 @@ impl EventService {
                                         }
                                     }
                                 }
+                                (HookTables::Merges, _) => {
+                                    match Merge::find_by_rowid(&db.pool, rowid).await {
+                                        Ok(Some(merge)) => RecordTypes::Merge(merge),
+                                        Ok(None) => return,
+                                        Err(e) => {
+                                            tracing::error!("Failed to fetch merge: {:?}", e);
+                                            return;
+                                        }
+                                    }
+                                }
                                 (HookTables::Scratch, _) => {
                                     match Scratch::find_by_rowid(&db.pool, rowid).await {
                                         Ok(Some(scratch)) => RecordTypes::Scratch(scratch),
@@ -307,6 +448,15 @@ impl EventService {
                                     msg_store_for_hook.push_patch(patch);
                                     return;
                                 }
+                                RecordTypes::Merge(merge) => {
+                                    let patch = match hook.operation {
+                                        SqliteOperation::Insert => merge_patch::add(merge),
+                                        SqliteOperation::Update => merge_patch::replace(merge),
+                                        _ => merge_patch::replace(merge),
+                                    };
+                                    msg_store_for_hook.push_patch(patch);
+                                    return;
+                                }
                                 RecordTypes::DeletedScratch {
                                     scratch_id: Some(scratch_id),
                                     scratch_type: Some(scratch_type_str),
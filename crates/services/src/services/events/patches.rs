@@ -1,5 +1,5 @@
 use db::models::{
-    execution_process::ExecutionProcess, project::Project, scratch::Scratch,
+    execution_process::ExecutionProcess, merge::Merge, project::Project, scratch::Scratch,
     task::TaskWithAttemptStatus, workspace::Workspace,
 };
 use json_patch::{AddOperation, Patch, PatchOperation, RemoveOperation, ReplaceOperation};
@@ -217,3 +217,65 @@ pub mod scratch_patch {
         })])
     }
 }
+
+/// Helper functions for creating merge-specific patches
+pub mod merge_patch {
+    use super::*;
+
+    fn merge_path(merge_id: Uuid) -> String {
+        format!("/merges/{}", escape_pointer_segment(&merge_id.to_string()))
+    }
+
+    /// Create patch for adding a new merge
+    pub fn add(merge: &Merge) -> Patch {
+        Patch(vec![PatchOperation::Add(AddOperation {
+            path: merge_path(merge.id())
+                .try_into()
+                .expect("Merge path should be valid"),
+            value: serde_json::to_value(merge).expect("Merge serialization should not fail"),
+        })])
+    }
+
+    /// Create patch for updating an existing merge, e.g. the status or CI state of a PR
+    pub fn replace(merge: &Merge) -> Patch {
+        Patch(vec![PatchOperation::Replace(ReplaceOperation {
+            path: merge_path(merge.id())
+                .try_into()
+                .expect("Merge path should be valid"),
+            value: serde_json::to_value(merge).expect("Merge serialization should not fail"),
+        })])
+    }
+}
+
+/// Helper functions for creating queue position patches for queued execution processes
+pub mod queue_position_patch {
+    use super::*;
+    use crate::services::scheduler::QueuePosition;
+
+    fn queue_position_path(process_id: Uuid) -> String {
+        format!(
+            "/queue_positions/{}",
+            escape_pointer_segment(&process_id.to_string())
+        )
+    }
+
+    /// Create patch for setting the queue position of an execution process
+    pub fn add(position: &QueuePosition) -> Patch {
+        Patch(vec![PatchOperation::Add(AddOperation {
+            path: queue_position_path(position.execution_process_id)
+                .try_into()
+                .expect("Queue position path should be valid"),
+            value: serde_json::to_value(position)
+                .expect("Queue position serialization should not fail"),
+        })])
+    }
+
+    /// Create patch for removing an execution process from the queue
+    pub fn remove(process_id: Uuid) -> Patch {
+        Patch(vec![PatchOperation::Remove(RemoveOperation {
+            path: queue_position_path(process_id)
+                .try_into()
+                .expect("Queue position path should be valid"),
+        })])
+    }
+}
@@ -1,5 +1,6 @@
 use db::models::{
     execution_process::ExecutionProcess,
+    merge::Merge,
     project::Project,
     scratch::Scratch,
     session::Session,
@@ -16,6 +17,7 @@ use super::{
     patches::execution_process_patch,
     types::{EventError, EventPatch, RecordTypes},
 };
+use crate::services::scheduler::QueuePosition;
 
 impl EventService {
     /// Stream raw task messages for a specific project with initial snapshot
@@ -260,11 +262,32 @@ impl EventService {
             })
             .collect();
 
-        let initial_patch = json!([{
-            "op": "replace",
-            "path": "/execution_processes",
-            "value": processes_map
-        }]);
+        // Queue positions of this workspace's queued processes, keyed by process ID
+        let queue_positions_map: serde_json::Map<String, serde_json::Value> = self
+            .scheduler
+            .queue_positions()
+            .into_iter()
+            .filter(|position| session_ids.contains(&position.session_id))
+            .map(|position| {
+                (
+                    position.execution_process_id.to_string(),
+                    serde_json::to_value(position).unwrap(),
+                )
+            })
+            .collect();
+
+        let initial_patch = json!([
+            {
+                "op": "replace",
+                "path": "/execution_processes",
+                "value": processes_map
+            },
+            {
+                "op": "add",
+                "path": "/queue_positions",
+                "value": queue_positions_map
+            }
+        ]);
         let initial_msg = LogMsg::JsonPatch(serde_json::from_value(initial_patch).unwrap());
 
         // Get filtered event stream
@@ -322,6 +345,24 @@ impl EventService {
                                         }
                                         _ => {}
                                     }
+                                } else if patch_op.path().starts_with("/queue_positions/") {
+                                    match patch_op {
+                                        json_patch::PatchOperation::Add(op) => {
+                                            if let Ok(position) =
+                                                serde_json::from_value::<QueuePosition>(
+                                                    op.value.clone(),
+                                                )
+                                                && session_ids.contains(&position.session_id)
+                                            {
+                                                return Some(Ok(LogMsg::JsonPatch(patch)));
+                                            }
+                                        }
+                                        json_patch::PatchOperation::Remove(_) => {
+                                            // Same as execution process removals, let the client filter
+                                            return Some(Ok(LogMsg::JsonPatch(patch)));
+                                        }
+                                        _ => {}
+                                    }
                                 }
                                 // Fallback to legacy EventPatch format for backward compatibility
                                 else if let Ok(event_patch_value) = serde_json::to_value(patch_op)
@@ -368,6 +409,62 @@ impl EventService {
         Ok(combined_stream)
     }
 
+    /// Stream the merges of a workspace, including the status and CI state of its PRs, with
+    /// initial snapshot (raw LogMsg format for WebSocket)
+    pub async fn stream_merges_for_workspace_raw(
+        &self,
+        workspace_id: Uuid,
+    ) -> Result<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>, EventError>
+    {
+        let merges = Merge::find_by_workspace_id(&self.db.pool, workspace_id).await?;
+
+        // Convert merges array to object keyed by merge ID
+        let merges_map: serde_json::Map<String, serde_json::Value> = merges
+            .into_iter()
+            .map(|merge| (merge.id().to_string(), serde_json::to_value(merge).unwrap()))
+            .collect();
+
+        let initial_patch = json!([{
+            "op": "replace",
+            "path": "/merges",
+            "value": merges_map
+        }]);
+        let initial_msg = LogMsg::JsonPatch(serde_json::from_value(initial_patch).unwrap());
+
+        // Filter to this workspace's merges by the workspace_id in the patch value
+        let filtered_stream = BroadcastStream::new(self.msg_store.get_receiver()).filter_map(
+            move |msg_result| async move {
+                match msg_result {
+                    Ok(LogMsg::JsonPatch(patch)) => {
+                        if let Some(op) = patch.0.first()
+                            && op.path().starts_with("/merges/")
+                        {
+                            let value = match op {
+                                json_patch::PatchOperation::Add(a) => Some(&a.value),
+                                json_patch::PatchOperation::Replace(r) => Some(&r.value),
+                                _ => None,
+                            };
+
+                            if value
+                                .and_then(|v| serde_json::from_value::<Merge>(v.clone()).ok())
+                                .is_some_and(|merge| merge.workspace_id() == workspace_id)
+                            {
+                                return Some(Ok(LogMsg::JsonPatch(patch)));
+                            }
+                        }
+                        None
+                    }
+                    Ok(other) => Some(Ok(other)),
+                    Err(_) => None,
+                }
+            },
+        );
+
+        let initial_stream = futures::stream::once(async move { Ok(initial_msg) });
+        let combined_stream = initial_stream.chain(filtered_stream).boxed();
+        Ok(combined_stream)
+    }
+
     /// Stream a single scratch item with initial snapshot (raw LogMsg format for WebSocket)
     pub async fn stream_scratch_raw(
         &self,
@@ -1,6 +1,7 @@
 use std::{collections::HashMap, path::Path};
 
 use chrono::{DateTime, Utc};
+use db::models::project::MergeMode;
 use git2::{
     BranchType, Delta, DiffFindOptions, DiffOptions, Error as GitError, Reference, Remote,
     Repository, Sort,
@@ -57,6 +58,18 @@ pub enum ConflictOp {
     Revert,
 }
 
+impl ConflictOp {
+    /// The ref git records for the commit being applied by this operation
+    pub fn incoming_ref(&self) -> &'static str {
+        match self {
+            ConflictOp::Rebase => "REBASE_HEAD",
+            ConflictOp::Merge => "MERGE_HEAD",
+            ConflictOp::CherryPick => "CHERRY_PICK_HEAD",
+            ConflictOp::Revert => "REVERT_HEAD",
+        }
+    }
+}
+
 #[derive(Debug, Serialize, TS)]
 pub struct GitBranch {
     pub name: String,
@@ -138,6 +151,22 @@ pub enum DiffTarget<'p> {
         repo_path: &'p Path,
         commit_sha: &'p str,
     },
+    /// One commit vs another, e.g. between two worktree snapshots
+    Commits {
+        repo_path: &'p Path,
+        from_sha: &'p str,
+        to_sha: &'p str,
+    },
+}
+
+/// Whether `content` still has a conflict block opened or closed by git's markers
+pub fn contains_conflict_markers(content: &str) -> bool {
+    content.lines().any(|line| {
+        ["<<<<<<<", ">>>>>>>"].iter().any(|marker| {
+            line.strip_prefix(marker)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+        })
+    })
 }
 
 impl Default for GitService {
@@ -406,6 +435,36 @@ impl GitService {
                 let mut find_opts = git2::DiffFindOptions::new();
                 diff.find_similar(Some(&mut find_opts))?;
 
+                self.convert_diff_to_file_diffs(diff, &repo)
+            }
+            DiffTarget::Commits {
+                repo_path,
+                from_sha,
+                to_sha,
+            } => {
+                let repo = self.open_repo(repo_path)?;
+                let parse_oid = |sha: &str| {
+                    git2::Oid::from_str(sha).map_err(|_| {
+                        GitServiceError::InvalidRepository(format!("Invalid commit SHA: {sha}"))
+                    })
+                };
+                let from_tree = repo.find_commit(parse_oid(from_sha)?)?.tree()?;
+                let to_tree = repo.find_commit(parse_oid(to_sha)?)?.tree()?;
+
+                let mut diff_opts = DiffOptions::new();
+                diff_opts.include_typechange(true);
+                if let Some(paths) = path_filter {
+                    for path in paths {
+                        diff_opts.pathspec(*path);
+                    }
+                }
+
+                let mut diff =
+                    repo.diff_tree_to_tree(Some(&from_tree), Some(&to_tree), Some(&mut diff_opts))?;
+
+                let mut find_opts = DiffFindOptions::new();
+                diff.find_similar(Some(&mut find_opts))?;
+
                 self.convert_diff_to_file_diffs(diff, &repo)
             }
         }
@@ -781,7 +840,7 @@ impl GitService {
         Ok(None)
     }
 
-    /// Merge changes from a task branch into the base branch.
+    /// Squash-merge changes from a task branch into the base branch.
     pub fn merge_changes(
         &self,
         base_worktree_path: &Path,
@@ -789,6 +848,29 @@ impl GitService {
         task_branch_name: &str,
         base_branch_name: &str,
         commit_message: &str,
+    ) -> Result<String, GitServiceError> {
+        self.merge_changes_with_mode(
+            base_worktree_path,
+            task_worktree_path,
+            task_branch_name,
+            base_branch_name,
+            commit_message,
+            MergeMode::Squash,
+        )
+    }
+
+    /// Merge changes from a task branch into the base branch the way `mode` asks for.
+    /// Rebasing rebases the task worktree onto the base branch when the base moved on, then
+    /// fast-forwards the base, so `commit_message` is unused. Returns the new tip of the base
+    /// branch.
+    pub fn merge_changes_with_mode(
+        &self,
+        base_worktree_path: &Path,
+        task_worktree_path: &Path,
+        task_branch_name: &str,
+        base_branch_name: &str,
+        commit_message: &str,
+        mode: MergeMode,
     ) -> Result<String, GitServiceError> {
         // Open the repositories
         let task_repo = self.open_repo(task_worktree_path)?;
@@ -796,9 +878,22 @@ impl GitService {
 
         // Check if base branch is ahead of task branch - this indicates the base has moved
         // ahead since the task was created, which should block the merge
-        let (_, task_behind) =
+        let (_, mut task_behind) =
             self.get_branch_status(base_worktree_path, task_branch_name, base_branch_name)?;
 
+        // Unless rebasing: replay the task commits onto the moved base first
+        if mode == MergeMode::Rebase && task_behind > 0 {
+            self.rebase_branch(
+                base_worktree_path,
+                task_worktree_path,
+                base_branch_name,
+                base_branch_name,
+                task_branch_name,
+            )?;
+            (_, task_behind) =
+                self.get_branch_status(base_worktree_path, task_branch_name, base_branch_name)?;
+        }
+
         if task_behind > 0 {
             return Err(GitServiceError::BranchesDiverged(format!(
                 "Cannot merge: base branch '{base_branch_name}' is {task_behind} commits ahead of task branch '{task_branch_name}'. The base branch has moved forward since the task was created.",
@@ -826,24 +921,40 @@ impl GitService {
 
                 // Use CLI merge in base context
                 self.ensure_cli_commit_identity(&base_checkout_path)?;
-                let sha = git_cli
-                    .merge_squash_commit(
+                let sha = match mode {
+                    MergeMode::Squash => git_cli.merge_squash_commit(
                         &base_checkout_path,
                         base_branch_name,
                         task_branch_name,
                         commit_message,
-                    )
-                    .map_err(|e| {
-                        GitServiceError::InvalidRepository(format!("CLI merge failed: {e}"))
-                    })?;
+                    ),
+                    MergeMode::MergeCommit => git_cli.merge_no_ff_commit(
+                        &base_checkout_path,
+                        base_branch_name,
+                        task_branch_name,
+                        commit_message,
+                    ),
+                    MergeMode::Rebase => git_cli.merge_fast_forward(
+                        &base_checkout_path,
+                        base_branch_name,
+                        task_branch_name,
+                    ),
+                }
+                .map_err(|e| {
+                    GitServiceError::InvalidRepository(format!("CLI merge failed: {e}"))
+                })?;
 
                 // Update task branch ref for continuity
-                let task_refname = format!("refs/heads/{task_branch_name}");
-                git_cli
-                    .update_ref(base_worktree_path, &task_refname, &sha)
-                    .map_err(|e| {
-                        GitServiceError::InvalidRepository(format!("git update-ref failed: {e}"))
-                    })?;
+                if mode != MergeMode::Rebase {
+                    let task_refname = format!("refs/heads/{task_branch_name}");
+                    git_cli
+                        .update_ref(base_worktree_path, &task_refname, &sha)
+                        .map_err(|e| {
+                            GitServiceError::InvalidRepository(format!(
+                                "git update-ref failed: {e}"
+                            ))
+                        })?;
+                }
 
                 Ok(sha)
             }
@@ -856,31 +967,105 @@ impl GitService {
                 let base_commit = base_branch.get().peel_to_commit()?;
                 let task_commit = task_branch.get().peel_to_commit()?;
 
-                // Create the squash commit in-memory (no checkout) and update the base branch ref
+                if mode == MergeMode::Rebase {
+                    // The task branch contains the base branch, so it can be fast-forwarded
+                    let refname = format!("refs/heads/{base_branch_name}");
+                    task_repo.reference(&refname, task_commit.id(), true, "Fast-forward merge")?;
+                    return Ok(task_commit.id().to_string());
+                }
+
+                // Create the merge commit in-memory (no checkout) and update the base branch ref
                 let signature = self.signature_with_fallback(&task_repo)?;
-                let squash_commit_id = self.perform_squash_merge(
-                    &task_repo,
-                    &base_commit,
-                    &task_commit,
-                    &signature,
-                    commit_message,
-                    base_branch_name,
-                )?;
+                let merge_commit_id = match mode {
+                    MergeMode::MergeCommit => self.perform_merge_commit(
+                        &task_repo,
+                        &base_commit,
+                        &task_commit,
+                        &signature,
+                        commit_message,
+                        base_branch_name,
+                    )?,
+                    _ => self.perform_squash_merge(
+                        &task_repo,
+                        &base_commit,
+                        &task_commit,
+                        &signature,
+                        commit_message,
+                        base_branch_name,
+                    )?,
+                };
 
-                // Update the task branch to the new squash commit so follow-up
+                // Update the task branch to the new commit so follow-up
                 // work can continue from the merged state without conflicts.
                 let task_refname = format!("refs/heads/{task_branch_name}");
                 base_repo.reference(
                     &task_refname,
-                    squash_commit_id,
+                    merge_commit_id,
                     true,
-                    "Reset task branch after squash merge",
+                    "Reset task branch after merge",
                 )?;
 
-                Ok(squash_commit_id.to_string())
+                Ok(merge_commit_id.to_string())
             }
         }
     }
+
+    /// Textual diff of the changes the task branch makes since it forked from the base branch
+    pub fn branch_diff(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+        base_branch_name: &str,
+    ) -> Result<String, GitServiceError> {
+        GitCli::new()
+            .diff_since_fork(repo_path, base_branch_name, branch_name)
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git diff failed: {e}")))
+    }
+
+    /// Stage every change in the worktree and return the textual diff of what will be committed
+    pub fn staged_changes_diff(&self, worktree_path: &Path) -> Result<String, GitServiceError> {
+        let git_cli = GitCli::new();
+        git_cli.add_all(worktree_path)?;
+        Ok(git_cli.diff_staged(worktree_path)?)
+    }
+
+    /// Snapshot the worktree, uncommitted and untracked changes included, and keep the
+    /// snapshot alive under `refname`. Returns the SHAs of HEAD and of the snapshot commit.
+    pub fn snapshot_worktree(
+        &self,
+        worktree_path: &Path,
+        refname: &str,
+        message: &str,
+    ) -> Result<(String, String), GitServiceError> {
+        self.ensure_cli_commit_identity(worktree_path)?;
+        let git = GitCli::new();
+        let (head, snapshot) = git.snapshot_worktree(worktree_path, message)?;
+        git.update_ref(worktree_path, refname, &snapshot)?;
+        Ok((head, snapshot))
+    }
+
+    /// Restore a snapshot taken by `snapshot_worktree`, discarding the current state of
+    /// the worktree
+    pub fn restore_worktree_snapshot(
+        &self,
+        worktree_path: &Path,
+        head_sha: &str,
+        snapshot_sha: &str,
+    ) -> Result<(), GitServiceError> {
+        let git = GitCli::new();
+        git.restore_worktree_snapshot(worktree_path, head_sha, snapshot_sha)
+            .map_err(|e| {
+                GitServiceError::InvalidRepository(format!("Failed to restore snapshot: {e}"))
+            })?;
+        // Reapply sparse-checkout if configured (non-fatal)
+        let _ = git.git(worktree_path, ["sparse-checkout", "reapply"]);
+        Ok(())
+    }
+
+    pub fn delete_ref(&self, repo_path: &Path, refname: &str) -> Result<(), GitServiceError> {
+        Ok(GitCli::new().delete_ref(repo_path, refname)?)
+    }
+
     fn get_branch_status_inner(
         &self,
         repo: &Repository,
@@ -935,6 +1120,56 @@ impl GitService {
         Ok(Commit::new(oid))
     }
 
+    /// The commits `branch_name` adds on top of `base_branch_name`, as a patch series
+    pub fn branch_patch(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+        base_branch_name: &str,
+    ) -> Result<String, GitServiceError> {
+        let base = self.get_base_commit(repo_path, branch_name, base_branch_name)?;
+        GitCli::new()
+            .format_patch(repo_path, &base.to_string(), branch_name)
+            .map_err(|e| {
+                GitServiceError::InvalidRepository(format!("git format-patch failed: {e}"))
+            })
+    }
+
+    /// Create `branch_name` at the tip of `base_branch_name` and commit a patch series made
+    /// by `branch_patch` onto it, in a temporary worktree at `scratch_path`. The branch is
+    /// deleted again when the series does not apply.
+    pub fn create_branch_from_patch(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+        base_branch_name: &str,
+        patch: &str,
+        scratch_path: &Path,
+    ) -> Result<(), GitServiceError> {
+        {
+            let repo = self.open_repo(repo_path)?;
+            let base = Self::find_branch(&repo, base_branch_name)?
+                .get()
+                .peel_to_commit()?;
+            repo.branch(branch_name, &base, false)?;
+        }
+        if patch.trim().is_empty() {
+            return Ok(());
+        }
+
+        let applied = self.ensure_cli_commit_identity(repo_path).and_then(|()| {
+            let git = GitCli::new();
+            git.worktree_add(repo_path, scratch_path, branch_name, false)?;
+            let applied = git.am(scratch_path, patch);
+            let _ = git.worktree_remove(repo_path, scratch_path, true);
+            applied.map_err(|e| GitServiceError::InvalidRepository(format!("git am failed: {e}")))
+        });
+        if applied.is_err() {
+            let _ = self.delete_local_branch(repo_path, branch_name);
+        }
+        applied
+    }
+
     pub fn get_remote_branch_status(
         &self,
         repo_path: &Path,
@@ -1282,6 +1517,31 @@ impl GitService {
         commit_message: &str,
         base_branch_name: &str,
     ) -> Result<git2::Oid, GitServiceError> {
+        let tree = Self::merged_tree(repo, base_commit, task_commit)?;
+
+        // Create a squash commit: use merged tree with base_commit as sole parent
+        let squash_commit_id = repo.commit(
+            None,           // Don't update any reference yet
+            signature,      // Author
+            signature,      // Committer
+            commit_message, // Custom message
+            &tree,          // Merged tree content
+            &[base_commit], // Single parent: base branch commit
+        )?;
+
+        // Update the base branch reference to point to the new commit
+        let refname = format!("refs/heads/{base_branch_name}");
+        repo.reference(&refname, squash_commit_id, true, "Squash merge")?;
+
+        Ok(squash_commit_id)
+    }
+
+    /// Merge two commits in-memory, without touching the working tree
+    fn merged_tree<'a>(
+        repo: &'a Repository,
+        base_commit: &git2::Commit,
+        task_commit: &git2::Commit,
+    ) -> Result<git2::Tree<'a>, GitServiceError> {
         // In-memory merge to detect conflicts without touching the working tree
         let mut merge_opts = git2::MergeOptions::new();
         // Safety and correctness options
@@ -1298,23 +1558,34 @@ impl GitService {
 
         // Write the merged tree back to the repository
         let tree_id = index.write_tree_to(repo)?;
-        let tree = repo.find_tree(tree_id)?;
+        Ok(repo.find_tree(tree_id)?)
+    }
 
-        // Create a squash commit: use merged tree with base_commit as sole parent
-        let squash_commit_id = repo.commit(
-            None,           // Don't update any reference yet
-            signature,      // Author
-            signature,      // Committer
-            commit_message, // Custom message
-            &tree,          // Merged tree content
-            &[base_commit], // Single parent: base branch commit
+    /// Create a merge commit of `task_commit` into `base_commit` in-memory and point the base
+    /// branch at it
+    fn perform_merge_commit(
+        &self,
+        repo: &Repository,
+        base_commit: &git2::Commit,
+        task_commit: &git2::Commit,
+        signature: &git2::Signature,
+        commit_message: &str,
+        base_branch_name: &str,
+    ) -> Result<git2::Oid, GitServiceError> {
+        let tree = Self::merged_tree(repo, base_commit, task_commit)?;
+        let merge_commit_id = repo.commit(
+            None,
+            signature,
+            signature,
+            commit_message,
+            &tree,
+            &[base_commit, task_commit],
         )?;
 
-        // Update the base branch reference to point to the new commit
         let refname = format!("refs/heads/{base_branch_name}");
-        repo.reference(&refname, squash_commit_id, true, "Squash merge")?;
+        repo.reference(&refname, merge_commit_id, true, "Merge commit")?;
 
-        Ok(squash_commit_id)
+        Ok(merge_commit_id)
     }
 
     /// Rebase a worktree branch onto a new base
@@ -1491,6 +1762,65 @@ impl GitService {
         Ok(())
     }
 
+    /// Delete a local branch that is not checked out in any worktree
+    pub fn delete_local_branch(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<(), GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let mut branch = repo
+            .find_branch(branch_name, BranchType::Local)
+            .map_err(|_| GitServiceError::BranchNotFound(branch_name.to_string()))?;
+        branch.delete()?;
+        Ok(())
+    }
+
+    /// Replace the message of the commit `branch_name` points at, as long as that still is
+    /// `commit_sha`. Tree, parents, author and committer are kept. Returns the id of the new
+    /// commit, or `None` when the branch has moved on in the meantime.
+    pub fn reword_branch_head(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+        commit_sha: &str,
+        message: &str,
+    ) -> Result<Option<String>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let old_oid = git2::Oid::from_str(commit_sha)?;
+        let branch = repo
+            .find_branch(branch_name, BranchType::Local)
+            .map_err(|_| GitServiceError::BranchNotFound(branch_name.to_string()))?;
+        if branch.get().target() != Some(old_oid) {
+            return Ok(None);
+        }
+
+        let commit = repo.find_commit(old_oid)?;
+        let parents: Vec<git2::Commit> = commit.parents().collect();
+        let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+        let new_oid = repo.commit(
+            None,
+            &commit.author(),
+            &commit.committer(),
+            message,
+            &commit.tree()?,
+            &parent_refs,
+        )?;
+
+        // Only moves the branch if nothing was committed on it since the check above
+        match repo.reference_matching(
+            &format!("refs/heads/{branch_name}"),
+            new_oid,
+            true,
+            old_oid,
+            "reword: conventional commit message",
+        ) {
+            Ok(_) => Ok(Some(new_oid.to_string())),
+            Err(e) if e.code() == git2::ErrorCode::Modified => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
     /// Return true if a rebase is currently in progress in this worktree.
     pub fn is_rebase_in_progress(&self, worktree_path: &Path) -> Result<bool, GitServiceError> {
         let git = GitCli::new();
@@ -1578,6 +1908,84 @@ impl GitService {
         Ok(())
     }
 
+    /// Subjects of the two sides of an in-progress conflict: the commit being built on
+    /// (`HEAD`) and the commit being applied (`REBASE_HEAD`, `MERGE_HEAD`, ...).
+    pub fn get_conflict_commit_subjects(
+        &self,
+        worktree_path: &Path,
+        op: &ConflictOp,
+    ) -> Result<(Option<String>, Option<String>), GitServiceError> {
+        let repo = self.open_repo(worktree_path)?;
+        let subject = |rev: &str| {
+            repo.revparse_single(rev)
+                .and_then(|obj| obj.peel_to_commit())
+                .ok()
+                .map(|commit| commit.summary().unwrap_or("(no subject)").to_string())
+        };
+        Ok((subject("HEAD"), subject(op.incoming_ref())))
+    }
+
+    /// Files among `paths` (relative to the worktree) that still contain conflict markers.
+    /// Missing files count as resolved, e.g. when the resolution was to delete them.
+    pub fn files_with_conflict_markers(
+        &self,
+        worktree_path: &Path,
+        paths: &[String],
+    ) -> Vec<String> {
+        paths
+            .iter()
+            .filter(|path| {
+                std::fs::read(worktree_path.join(path))
+                    .map(|bytes| contains_conflict_markers(&String::from_utf8_lossy(&bytes)))
+                    .unwrap_or(false)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Stage the resolved `conflicted_files` and continue the interrupted operation. Other
+    /// changes in the worktree are left unstaged. Returns `MergeConflicts` when git stops
+    /// again on a later conflicting commit.
+    pub fn continue_conflict_op(
+        &self,
+        worktree_path: &Path,
+        conflicted_files: &[String],
+    ) -> Result<(), GitServiceError> {
+        let Some(op) = self.detect_conflict_op(worktree_path)? else {
+            return Ok(());
+        };
+        let git = GitCli::new();
+        git.add_paths(worktree_path, conflicted_files)
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git add failed: {e}")))?;
+        self.ensure_cli_commit_identity(worktree_path)?;
+
+        let (result, command) = match op {
+            ConflictOp::Rebase => (git.continue_rebase(worktree_path), "rebase --continue"),
+            ConflictOp::Merge => (git.continue_merge(worktree_path), "commit --no-edit"),
+            ConflictOp::CherryPick => (
+                git.continue_cherry_pick(worktree_path),
+                "cherry-pick --continue",
+            ),
+            ConflictOp::Revert => (git.continue_revert(worktree_path), "revert --continue"),
+        };
+        match result {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                let conflicts = self.get_conflicted_files(worktree_path).unwrap_or_default();
+                if conflicts.is_empty() {
+                    Err(GitServiceError::InvalidRepository(format!(
+                        "git {command} failed: {e}"
+                    )))
+                } else {
+                    Err(GitServiceError::MergeConflicts(format!(
+                        "git {command} stopped on new conflicts in: {}",
+                        conflicts.join(", ")
+                    )))
+                }
+            }
+        }
+    }
+
     pub fn find_branch<'a>(
         repo: &'a Repository,
         branch_name: &str,
@@ -296,6 +296,22 @@ impl GitCli {
         Ok(())
     }
 
+    /// Stage `paths`, including their deletion, and nothing else
+    pub fn add_paths(&self, worktree_path: &Path, paths: &[String]) -> Result<(), GitCliError> {
+        // Without a pathspec `git add -A` would stage the whole tree
+        if paths.is_empty() {
+            return Ok(());
+        }
+        let mut args: Vec<OsString> = vec!["add".into(), "-A".into(), "--".into()];
+        args.extend(
+            paths
+                .iter()
+                .map(|path| OsString::from(format!(":(literal){path}"))),
+        );
+        self.git(worktree_path, args)?;
+        Ok(())
+    }
+
     pub fn list_worktrees(&self, repo_path: &Path) -> Result<Vec<WorktreeEntry>, GitCliError> {
         let out = self.git(repo_path, ["worktree", "list", "--porcelain"])?;
         let mut entries = Vec::new();
@@ -594,6 +610,161 @@ impl GitCli {
         Ok(sha)
     }
 
+    /// Checkout base branch and merge from_branch with a merge commit. Returns new HEAD sha.
+    pub fn merge_no_ff_commit(
+        &self,
+        repo_path: &Path,
+        base_branch: &str,
+        from_branch: &str,
+        message: &str,
+    ) -> Result<String, GitCliError> {
+        self.git(repo_path, ["checkout", base_branch]).map(|_| ())?;
+        self.git(repo_path, ["merge", "--no-ff", "-m", message, from_branch])
+            .map(|_| ())?;
+        let sha = self
+            .git(repo_path, ["rev-parse", "HEAD"])?
+            .trim()
+            .to_string();
+        Ok(sha)
+    }
+
+    /// Checkout base branch and fast-forward it to from_branch. Returns new HEAD sha.
+    pub fn merge_fast_forward(
+        &self,
+        repo_path: &Path,
+        base_branch: &str,
+        from_branch: &str,
+    ) -> Result<String, GitCliError> {
+        self.git(repo_path, ["checkout", base_branch]).map(|_| ())?;
+        self.git(repo_path, ["merge", "--ff-only", from_branch])
+            .map(|_| ())?;
+        let sha = self
+            .git(repo_path, ["rev-parse", "HEAD"])?
+            .trim()
+            .to_string();
+        Ok(sha)
+    }
+
+    /// Textual diff of the changes `branch` makes since it forked from `base`
+    pub fn diff_since_fork(
+        &self,
+        repo_path: &Path,
+        base: &str,
+        branch: &str,
+    ) -> Result<String, GitCliError> {
+        let range = format!("{base}...{branch}");
+        self.git(repo_path, ["diff", "--no-color", range.as_str()])
+    }
+
+    /// Textual diff of everything staged in the worktree
+    pub fn diff_staged(&self, worktree_path: &Path) -> Result<String, GitCliError> {
+        self.git(worktree_path, ["diff", "--cached", "--no-color"])
+    }
+
+    /// Commit the worktree as it is, untracked files included, on top of HEAD using a
+    /// temporary index, so the real index, HEAD and branches are left alone.
+    /// Returns the SHAs of HEAD and of the snapshot commit.
+    pub fn snapshot_worktree(
+        &self,
+        worktree_path: &Path,
+        message: &str,
+    ) -> Result<(String, String), GitCliError> {
+        let tmp_dir = tempfile::TempDir::new()
+            .map_err(|e| GitCliError::CommandFailed(format!("temp dir create failed: {e}")))?;
+        let tmp_index = tmp_dir.path().join("index");
+        let envs = vec![(
+            OsString::from("GIT_INDEX_FILE"),
+            tmp_index.as_os_str().to_os_string(),
+        )];
+
+        let head = self
+            .git(worktree_path, ["rev-parse", "HEAD"])?
+            .trim()
+            .to_string();
+        self.git_with_env(worktree_path, ["read-tree", "HEAD"], &envs)?;
+        self.git_with_env(
+            worktree_path,
+            Self::apply_default_excludes(vec!["add", "-A"]),
+            &envs,
+        )?;
+        let tree = self
+            .git_with_env(worktree_path, ["write-tree"], &envs)?
+            .trim()
+            .to_string();
+        let snapshot = self
+            .git(
+                worktree_path,
+                [
+                    "commit-tree",
+                    tree.as_str(),
+                    "-p",
+                    head.as_str(),
+                    "-m",
+                    message,
+                ],
+            )?
+            .trim()
+            .to_string();
+        Ok((head, snapshot))
+    }
+
+    /// Bring back a snapshot taken by `snapshot_worktree`: HEAD moves to `head` and the
+    /// snapshot's changes become uncommitted changes again. Untracked files that are not in
+    /// the snapshot are removed, ignored files are kept.
+    pub fn restore_worktree_snapshot(
+        &self,
+        worktree_path: &Path,
+        head: &str,
+        snapshot: &str,
+    ) -> Result<(), GitCliError> {
+        self.git(worktree_path, ["reset", "--hard", head])?;
+        self.git(
+            worktree_path,
+            Self::apply_default_excludes(vec!["clean", "-fd"]),
+        )?;
+        self.git(worktree_path, ["read-tree", "-u", "--reset", snapshot])?;
+        // Unstage again so the index matches HEAD, as it did for the agent
+        self.git(worktree_path, ["reset", "-q"])?;
+        Ok(())
+    }
+
+    /// Delete a ref, doing nothing when it does not exist
+    pub fn delete_ref(&self, repo_path: &Path, refname: &str) -> Result<(), GitCliError> {
+        let exists = self
+            .git(repo_path, ["show-ref", "--verify", "--quiet", refname])
+            .is_ok();
+        if exists {
+            self.git(repo_path, ["update-ref", "-d", refname])?;
+        }
+        Ok(())
+    }
+
+    /// Commits of `base..branch` in mbox format, as applied by `git am`.
+    pub fn format_patch(
+        &self,
+        repo_path: &Path,
+        base: &str,
+        branch: &str,
+    ) -> Result<String, GitCliError> {
+        let range = format!("{base}..{branch}");
+        self.git(
+            repo_path,
+            ["format-patch", "--stdout", "--binary", range.as_str()],
+        )
+    }
+
+    /// Commit a patch series made by `format_patch` onto the checked out branch with
+    /// `git am`, aborting the series when a patch does not apply.
+    pub fn am(&self, worktree_path: &Path, patch: &str) -> Result<(), GitCliError> {
+        if let Err(e) =
+            self.git_with_stdin(worktree_path, ["am", "--keep-cr"], None, patch.as_bytes())
+        {
+            let _ = self.git(worktree_path, ["am", "--abort"]);
+            return Err(e);
+        }
+        Ok(())
+    }
+
     /// Update a ref to a specific sha in the repo.
     pub fn update_ref(
         &self,
@@ -627,6 +798,44 @@ impl GitCli {
         self.git(worktree_path, ["revert", "--abort"]).map(|_| ())
     }
 
+    /// Continue an interrupted rebase once its conflicts are resolved and staged.
+    pub fn continue_rebase(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.git_with_env(
+            worktree_path,
+            ["rebase", "--continue"],
+            &Self::non_interactive_editor_env(),
+        )
+        .map(|_| ())
+    }
+
+    /// Conclude an interrupted merge with git's prepared merge message.
+    pub fn continue_merge(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.git_with_env(
+            worktree_path,
+            ["commit", "--no-edit"],
+            &Self::non_interactive_editor_env(),
+        )
+        .map(|_| ())
+    }
+
+    pub fn continue_cherry_pick(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.git_with_env(
+            worktree_path,
+            ["cherry-pick", "--continue"],
+            &Self::non_interactive_editor_env(),
+        )
+        .map(|_| ())
+    }
+
+    pub fn continue_revert(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.git_with_env(
+            worktree_path,
+            ["revert", "--continue"],
+            &Self::non_interactive_editor_env(),
+        )
+        .map(|_| ())
+    }
+
     /// List files currently in a conflicted (unmerged) state in the worktree.
     pub fn get_conflicted_files(&self, worktree_path: &Path) -> Result<Vec<String>, GitCliError> {
         // `--diff-filter=U` lists paths with unresolved conflicts
@@ -804,6 +1013,11 @@ impl GitCli {
         Ok(String::from_utf8_lossy(&out).to_string())
     }
 
+    /// Accept git's prepared commit messages instead of opening an editor
+    fn non_interactive_editor_env() -> [(OsString, OsString); 1] {
+        [(OsString::from("GIT_EDITOR"), OsString::from("true"))]
+    }
+
     fn apply_default_excludes<I, S>(args: I) -> Vec<OsString>
     where
         I: IntoIterator<Item = S>,
@@ -1,4 +1,4 @@
-use std::{path::Path, time::Duration};
+use std::{collections::HashSet, path::Path, time::Duration};
 
 use backon::{ExponentialBuilder, Retryable};
 use chrono::{DateTime, Utc};
@@ -9,10 +9,12 @@ use tokio::task;
 use tracing::info;
 use ts_rs::TS;
 
+use crate::services::vcs_provider::PrCiReport;
+
 mod cli;
 
 use cli::{GhCli, GhCliError, PrComment, PrReviewComment};
-pub use cli::{PrCommentAuthor, ReviewCommentUser};
+pub use cli::{PrCommentAuthor, ReviewCommentUser, actions_job_id_from_url};
 
 /// Unified PR comment that can be either a general comment or review comment
 #[derive(Debug, Clone, Serialize, TS)]
@@ -39,6 +41,8 @@ pub enum UnifiedPrComment {
         path: String,
         line: Option<i64>,
         diff_hunk: String,
+        /// Whether the review thread of the comment was marked as resolved
+        resolved: bool,
     },
 }
 
@@ -49,6 +53,29 @@ impl UnifiedPrComment {
             UnifiedPrComment::Review { created_at, .. } => *created_at,
         }
     }
+
+    /// Identifies the comment within its PR. General and review comments are numbered
+    /// separately by some providers, so the kind is part of the key.
+    pub fn key(&self) -> String {
+        match self {
+            UnifiedPrComment::General { id, .. } => format!("general:{id}"),
+            UnifiedPrComment::Review { id, .. } => format!("review:{id}"),
+        }
+    }
+
+    pub fn body(&self) -> &str {
+        match self {
+            UnifiedPrComment::General { body, .. } => body,
+            UnifiedPrComment::Review { body, .. } => body,
+        }
+    }
+
+    pub fn author(&self) -> &str {
+        match self {
+            UnifiedPrComment::General { author, .. } => author,
+            UnifiedPrComment::Review { author, .. } => author,
+        }
+    }
 }
 
 #[derive(Debug, Error)]
@@ -338,7 +365,7 @@ impl GitHubService {
         );
 
         let general_comments = general_result?;
-        let review_comments = review_result?;
+        let (review_comments, resolved_ids) = review_result?;
 
         // Convert and merge into unified timeline
         let mut unified: Vec<UnifiedPrComment> = Vec::new();
@@ -365,6 +392,7 @@ impl GitHubService {
                 path: c.path,
                 line: c.line,
                 diff_hunk: c.diff_hunk,
+                resolved: resolved_ids.contains(&c.id),
             });
         }
 
@@ -374,6 +402,99 @@ impl GitHubService {
         Ok(unified)
     }
 
+    /// Reply to a comment. Review comments are answered in their thread; the conversation
+    /// has no threads, so general comments get a new comment linking back to them.
+    /// Not retried, as a retry after a timeout could post the reply twice.
+    pub async fn reply_to_pr_comment(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        pr_number: i64,
+        comment: &UnifiedPrComment,
+        body: &str,
+    ) -> Result<(), GitHubServiceError> {
+        let owner = repo_info.owner.clone();
+        let repo = repo_info.repo_name.clone();
+        let cli = self.gh_cli.clone();
+        let result = match comment {
+            UnifiedPrComment::Review { id, .. } => {
+                let id = *id;
+                let body = body.to_string();
+                task::spawn_blocking(move || {
+                    cli.reply_to_review_comment(&owner, &repo, pr_number, id, &body)
+                })
+                .await
+            }
+            UnifiedPrComment::General { url, .. } => {
+                let body = format!("{body}\n\nRe: {url}");
+                task::spawn_blocking(move || cli.comment_on_pr(&owner, &repo, pr_number, &body))
+                    .await
+            }
+        };
+        result
+            .map_err(|err| {
+                GitHubServiceError::PullRequest(format!(
+                    "Failed to execute GitHub CLI for replying on PR #{pr_number}: {err}"
+                ))
+            })?
+            .map_err(GitHubServiceError::from)
+    }
+
+    /// Fetch the checks of the head commit of a pull request
+    pub async fn get_pr_checks(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        pr_number: i64,
+    ) -> Result<PrCiReport, GitHubServiceError> {
+        (|| async {
+            let owner = repo_info.owner.clone();
+            let repo = repo_info.repo_name.clone();
+            let cli = self.gh_cli.clone();
+            let report = task::spawn_blocking(move || cli.get_pr_checks(&owner, &repo, pr_number))
+                .await
+                .map_err(|err| {
+                    GitHubServiceError::PullRequest(format!(
+                        "Failed to execute GitHub CLI for fetching PR #{pr_number} checks: {err}"
+                    ))
+                })?;
+            report.map_err(GitHubServiceError::from)
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|e: &GitHubServiceError| e.should_retry())
+        .notify(|err: &GitHubServiceError, dur: Duration| {
+            tracing::warn!(
+                "GitHub API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+
+    /// Fetch the log of the failed steps of a GitHub Actions job
+    pub async fn get_failed_job_log(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        job_id: i64,
+    ) -> Result<String, GitHubServiceError> {
+        let owner = repo_info.owner.clone();
+        let repo = repo_info.repo_name.clone();
+        let cli = self.gh_cli.clone();
+        task::spawn_blocking(move || cli.get_failed_job_log(&owner, &repo, job_id))
+            .await
+            .map_err(|err| {
+                GitHubServiceError::PullRequest(format!(
+                    "Failed to execute GitHub CLI for fetching the log of job {job_id}: {err}"
+                ))
+            })?
+            .map_err(GitHubServiceError::from)
+    }
+
     async fn fetch_general_comments(
         &self,
         repo_info: &GitHubRepoInfo,
@@ -414,11 +535,13 @@ impl GitHubService {
         .await
     }
 
+    /// Review comments along with the IDs of those in resolved threads, which only the
+    /// GraphQL API reports
     async fn fetch_review_comments(
         &self,
         repo_info: &GitHubRepoInfo,
         pr_number: i64,
-    ) -> Result<Vec<PrReviewComment>, GitHubServiceError> {
+    ) -> Result<(Vec<PrReviewComment>, HashSet<i64>), GitHubServiceError> {
         (|| async {
             let owner = repo_info.owner.clone();
             let repo = repo_info.repo_name.clone();
@@ -426,7 +549,12 @@ impl GitHubService {
             let comments = task::spawn_blocking({
                 let owner = owner.clone();
                 let repo = repo.clone();
-                move || cli.get_pr_review_comments(&owner, &repo, pr_number)
+                move || -> Result<_, GhCliError> {
+                    let comments = cli.get_pr_review_comments(&owner, &repo, pr_number)?;
+                    let resolved_ids =
+                        cli.get_resolved_review_comment_ids(&owner, &repo, pr_number)?;
+                    Ok((comments, resolved_ids))
+                }
             })
             .await
             .map_err(|err| {
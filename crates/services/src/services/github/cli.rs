@@ -5,6 +5,7 @@
 //! Future work will flesh out richer error handling and testing.
 
 use std::{
+    collections::HashSet,
     ffi::{OsStr, OsString},
     io::Write,
     path::Path,
@@ -12,7 +13,7 @@ use std::{
 };
 
 use chrono::{DateTime, Utc};
-use db::models::merge::{MergeStatus, PullRequestInfo};
+use db::models::merge::{CiStatus, MergeStatus, PullRequestInfo};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tempfile::NamedTempFile;
@@ -20,7 +21,10 @@ use thiserror::Error;
 use ts_rs::TS;
 use utils::shell::resolve_executable_path_blocking;
 
-use crate::services::github::{CreatePrRequest, GitHubRepoInfo};
+use crate::services::{
+    github::{CreatePrRequest, GitHubRepoInfo},
+    vcs_provider::{CiCheck, PrCiReport},
+};
 
 /// Author information for a PR comment
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -61,6 +65,39 @@ pub struct PrReviewComment {
     pub author_association: String,
 }
 
+/// Review threads of a pull request with the comments in them. `$endCursor` lets
+/// `gh api graphql --paginate` walk the threads.
+const REVIEW_THREADS_QUERY: &str = r#"
+query($owner: String!, $repo: String!, $number: Int!, $endCursor: String) {
+  repository(owner: $owner, name: $repo) {
+    pullRequest(number: $number) {
+      reviewThreads(first: 100, after: $endCursor) {
+        nodes {
+          isResolved
+          comments(first: 100) {
+            nodes {
+              databaseId
+            }
+          }
+        }
+        pageInfo {
+          hasNextPage
+          endCursor
+        }
+      }
+    }
+  }
+}
+"#;
+
+/// ID of the GitHub Actions job a check's details URL points to, e.g.
+/// `https://github.com/owner/repo/actions/runs/123/job/456`
+pub fn actions_job_id_from_url(url: &str) -> Option<i64> {
+    let (_, run) = url.split_once("/actions/runs/")?;
+    let (_, job) = run.split_once("/job/")?;
+    job.split(['/', '?', '#']).next()?.parse().ok()
+}
+
 /// High-level errors originating from the GitHub CLI.
 #[derive(Debug, Error)]
 pub enum GhCliError {
@@ -281,6 +318,122 @@ impl GhCli {
         )?;
         Self::parse_pr_review_comments(&raw)
     }
+
+    /// IDs of the inline review comments in threads marked as resolved. The REST API does not
+    /// report thread resolution, so this goes through GraphQL.
+    pub fn get_resolved_review_comment_ids(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+    ) -> Result<HashSet<i64>, GhCliError> {
+        let raw = self.run(
+            [
+                "api",
+                "graphql",
+                "--paginate",
+                "-f",
+                &format!("query={REVIEW_THREADS_QUERY}"),
+                "-f",
+                &format!("owner={owner}"),
+                "-f",
+                &format!("repo={repo}"),
+                "-F",
+                &format!("number={pr_number}"),
+            ],
+            None,
+        )?;
+        Self::parse_resolved_review_comment_ids(&raw)
+    }
+
+    /// Reply in the thread of an inline review comment.
+    pub fn reply_to_review_comment(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+        comment_id: i64,
+        body: &str,
+    ) -> Result<(), GhCliError> {
+        self.run(
+            [
+                "api",
+                "--method",
+                "POST",
+                &format!("repos/{owner}/{repo}/pulls/{pr_number}/comments/{comment_id}/replies"),
+                "-f",
+                &format!("body={body}"),
+            ],
+            None,
+        )?;
+        Ok(())
+    }
+
+    /// Add a comment to the conversation of a pull request.
+    pub fn comment_on_pr(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+        body: &str,
+    ) -> Result<(), GhCliError> {
+        self.run(
+            [
+                "pr",
+                "comment",
+                &pr_number.to_string(),
+                "--repo",
+                &format!("{owner}/{repo}"),
+                "--body",
+                body,
+            ],
+            None,
+        )?;
+        Ok(())
+    }
+
+    /// Fetch the check runs and commit statuses of the head commit of a pull request.
+    pub fn get_pr_checks(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+    ) -> Result<PrCiReport, GhCliError> {
+        let raw = self.run(
+            [
+                "pr",
+                "view",
+                &pr_number.to_string(),
+                "--repo",
+                &format!("{owner}/{repo}"),
+                "--json",
+                "headRefOid,statusCheckRollup",
+            ],
+            None,
+        )?;
+        Self::parse_pr_checks(&raw)
+    }
+
+    /// Fetch the log of the failed steps of a GitHub Actions job.
+    pub fn get_failed_job_log(
+        &self,
+        owner: &str,
+        repo: &str,
+        job_id: i64,
+    ) -> Result<String, GhCliError> {
+        self.run(
+            [
+                "run",
+                "view",
+                "--job",
+                &job_id.to_string(),
+                "--log-failed",
+                "--repo",
+                &format!("{owner}/{repo}"),
+            ],
+            None,
+        )
+    }
 }
 
 impl GhCli {
@@ -391,6 +544,111 @@ impl GhCli {
         })
     }
 
+    /// `gh api graphql --paginate` prints one response document per page
+    fn parse_resolved_review_comment_ids(raw: &str) -> Result<HashSet<i64>, GhCliError> {
+        let mut ids = HashSet::new();
+        for page in serde_json::Deserializer::from_str(raw).into_iter::<Value>() {
+            let page = page.map_err(|err| {
+                GhCliError::UnexpectedOutput(format!(
+                    "Failed to parse review threads GraphQL response: {err}; raw: {raw}"
+                ))
+            })?;
+            let threads = page
+                .pointer("/data/repository/pullRequest/reviewThreads/nodes")
+                .and_then(Value::as_array)
+                .ok_or_else(|| {
+                    GhCliError::UnexpectedOutput(format!(
+                        "Review threads GraphQL response missing 'reviewThreads': {page:#?}"
+                    ))
+                })?;
+            for thread in threads {
+                if thread.get("isResolved").and_then(Value::as_bool) != Some(true) {
+                    continue;
+                }
+                let comments = thread
+                    .pointer("/comments/nodes")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten();
+                ids.extend(
+                    comments
+                        .filter_map(|comment| comment.get("databaseId").and_then(Value::as_i64)),
+                );
+            }
+        }
+        Ok(ids)
+    }
+
+    fn parse_pr_checks(raw: &str) -> Result<PrCiReport, GhCliError> {
+        let value: Value = serde_json::from_str(raw.trim()).map_err(|err| {
+            GhCliError::UnexpectedOutput(format!(
+                "Failed to parse gh pr view --json statusCheckRollup response: {err}; raw: {raw}"
+            ))
+        })?;
+        let head_sha = value
+            .get("headRefOid")
+            .and_then(Value::as_str)
+            .ok_or_else(|| {
+                GhCliError::UnexpectedOutput(format!(
+                    "gh pr view response missing 'headRefOid': {value:#?}"
+                ))
+            })?
+            .to_string();
+        let checks = value
+            .get("statusCheckRollup")
+            .and_then(Value::as_array)
+            .map(|items| items.iter().filter_map(Self::extract_ci_check).collect())
+            .unwrap_or_default();
+        Ok(PrCiReport { head_sha, checks })
+    }
+
+    /// Convert a check run or a commit status of the status check rollup
+    fn extract_ci_check(value: &Value) -> Option<CiCheck> {
+        let str_field = |name: &str| {
+            value
+                .get(name)
+                .and_then(Value::as_str)
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string())
+        };
+
+        if value.get("__typename").and_then(Value::as_str) == Some("StatusContext") {
+            let status = match str_field("state")?.to_ascii_uppercase().as_str() {
+                "SUCCESS" => CiStatus::Success,
+                "FAILURE" | "ERROR" => CiStatus::Failure,
+                _ => CiStatus::Pending,
+            };
+            return Some(CiCheck {
+                name: str_field("context")?,
+                status,
+                url: str_field("targetUrl"),
+                description: str_field("description"),
+            });
+        }
+
+        // Cancelled, skipped and neutral check runs don't block the PR, so they count as passed
+        let status = if str_field("status")?.eq_ignore_ascii_case("COMPLETED") {
+            match str_field("conclusion")
+                .unwrap_or_default()
+                .to_ascii_uppercase()
+                .as_str()
+            {
+                "FAILURE" | "TIMED_OUT" | "STARTUP_FAILURE" | "ACTION_REQUIRED" => {
+                    CiStatus::Failure
+                }
+                _ => CiStatus::Success,
+            }
+        } else {
+            CiStatus::Pending
+        };
+        Some(CiCheck {
+            name: str_field("name")?,
+            status,
+            url: str_field("detailsUrl"),
+            description: str_field("workflowName"),
+        })
+    }
+
     fn extract_pr_info(value: &Value) -> Option<PullRequestInfo> {
         let number = value.get("number")?.as_i64()?;
         let url = value.get("url")?.as_str()?.to_string();
@@ -423,3 +681,96 @@ impl GhCli {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pr_checks() {
+        let raw = r#"{
+            "headRefOid": "0123abcd",
+            "statusCheckRollup": [
+                {
+                    "__typename": "CheckRun",
+                    "name": "lint",
+                    "status": "COMPLETED",
+                    "conclusion": "FAILURE",
+                    "detailsUrl": "https://github.com/o/r/actions/runs/11/job/22",
+                    "workflowName": "CI"
+                },
+                {
+                    "__typename": "CheckRun",
+                    "name": "test",
+                    "status": "IN_PROGRESS",
+                    "conclusion": "",
+                    "detailsUrl": "https://github.com/o/r/actions/runs/11/job/23",
+                    "workflowName": "CI"
+                },
+                {
+                    "__typename": "CheckRun",
+                    "name": "deploy-preview",
+                    "status": "COMPLETED",
+                    "conclusion": "SKIPPED",
+                    "detailsUrl": "https://github.com/o/r/actions/runs/11/job/24",
+                    "workflowName": "CI"
+                },
+                {
+                    "__typename": "StatusContext",
+                    "context": "ci/jenkins",
+                    "state": "ERROR",
+                    "targetUrl": "https://jenkins.example.com/job/1",
+                    "description": "Build errored"
+                }
+            ]
+        }"#;
+
+        let report = GhCli::parse_pr_checks(raw).unwrap();
+
+        assert_eq!(report.head_sha, "0123abcd");
+        let statuses: Vec<_> = report
+            .checks
+            .iter()
+            .map(|c| (c.name.as_str(), c.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("lint", CiStatus::Failure),
+                ("test", CiStatus::Pending),
+                ("deploy-preview", CiStatus::Success),
+                ("ci/jenkins", CiStatus::Failure),
+            ]
+        );
+        assert_eq!(
+            report.checks[3].description.as_deref(),
+            Some("Build errored")
+        );
+        assert_eq!(
+            report.checks[0]
+                .url
+                .as_deref()
+                .and_then(actions_job_id_from_url),
+            Some(22)
+        );
+        assert_eq!(
+            actions_job_id_from_url("https://jenkins.example.com/job/1"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_resolved_review_comment_ids() {
+        let raw = r#"{"data":{"repository":{"pullRequest":{"reviewThreads":{"nodes":[
+            {"isResolved":true,"comments":{"nodes":[{"databaseId":11},{"databaseId":12}]}},
+            {"isResolved":false,"comments":{"nodes":[{"databaseId":13}]}}
+        ],"pageInfo":{"hasNextPage":true,"endCursor":"abc"}}}}}}
+        {"data":{"repository":{"pullRequest":{"reviewThreads":{"nodes":[
+            {"isResolved":true,"comments":{"nodes":[{"databaseId":14}]}}
+        ],"pageInfo":{"hasNextPage":false,"endCursor":"def"}}}}}}"#;
+
+        let ids = GhCli::parse_resolved_review_comment_ids(raw).unwrap();
+
+        assert_eq!(ids, HashSet::from([11, 12, 14]));
+    }
+}
@@ -0,0 +1,336 @@
+//! GitLab REST API v4 client.
+//!
+//! Provides HTTP methods for the merge request endpoints used by the application.
+
+use std::time::Duration;
+
+use backon::{ExponentialBuilder, Retryable};
+use reqwest::{Client, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use tracing::{debug, warn};
+
+use super::models::{CreateMergeRequestRequest, GitLabDiscussion, GitLabError, GitLabMergeRequest};
+use crate::services::vcs_provider::VcsProviderError;
+
+const PER_PAGE: &str = "100";
+
+/// HTTP client for GitLab REST API v4
+pub struct GitLabApiClient {
+    http_client: Client,
+}
+
+impl GitLabApiClient {
+    pub fn new() -> Result<Self, VcsProviderError> {
+        let http_client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .connect_timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| {
+                VcsProviderError::Network(format!("Failed to create HTTP client: {}", e))
+            })?;
+
+        Ok(Self { http_client })
+    }
+
+    /// Build the API URL for a project-scoped path. Projects are addressed by their
+    /// URL-encoded full path (`group%2Fsubgroup%2Frepo`) so no id lookup is needed.
+    fn project_url(base_url: &str, namespace: &str, repo: &str, path: &str) -> String {
+        let project_id: String =
+            url::form_urlencoded::byte_serialize(format!("{}/{}", namespace, repo).as_bytes())
+                .collect();
+        format!(
+            "{}/api/v4/projects/{}{}",
+            base_url.trim_end_matches('/'),
+            project_id,
+            path
+        )
+    }
+
+    /// Execute a request with retry logic
+    async fn execute_with_retry<F, Fut, T>(&self, operation: F) -> Result<T, VcsProviderError>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T, VcsProviderError>>,
+    {
+        operation
+            .retry(
+                &ExponentialBuilder::default()
+                    .with_min_delay(Duration::from_secs(1))
+                    .with_max_delay(Duration::from_secs(30))
+                    .with_max_times(3)
+                    .with_jitter(),
+            )
+            .when(|e: &VcsProviderError| e.should_retry())
+            .notify(|err: &VcsProviderError, dur: Duration| {
+                warn!(
+                    "GitLab API call failed, retrying after {:.2}s: {}",
+                    dur.as_secs_f64(),
+                    err
+                );
+            })
+            .await
+    }
+
+    /// Handle response errors
+    async fn handle_response(&self, response: Response) -> Result<Response, VcsProviderError> {
+        let status = response.status();
+
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        let error_text = response.text().await.unwrap_or_default();
+        let message = serde_json::from_str::<GitLabError>(&error_text)
+            .map(|e| e.to_string())
+            .unwrap_or(error_text);
+
+        match status {
+            StatusCode::UNAUTHORIZED => Err(VcsProviderError::AuthFailed(
+                "GitLab authentication failed. Please check your access token.".into(),
+            )),
+            StatusCode::FORBIDDEN => Err(VcsProviderError::PermissionDenied(message)),
+            StatusCode::NOT_FOUND => Err(VcsProviderError::NotFound(message)),
+            StatusCode::CONFLICT => Err(VcsProviderError::PullRequest(format!(
+                "Conflict: {}",
+                message
+            ))),
+            _ if status.is_server_error() => Err(VcsProviderError::Network(format!(
+                "GitLab server error ({}): {}",
+                status.as_u16(),
+                message
+            ))),
+            _ => Err(VcsProviderError::PullRequest(format!(
+                "GitLab API error ({}): {}",
+                status.as_u16(),
+                message
+            ))),
+        }
+    }
+
+    /// Fetch every page of a list endpoint, following the `X-Next-Page` header
+    async fn get_all_pages<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        token: &str,
+        query: &[(&str, &str)],
+    ) -> Result<Vec<T>, VcsProviderError> {
+        let mut items = Vec::new();
+        let mut page = "1".to_string();
+
+        loop {
+            debug!("Fetching page {} of {}", page, url);
+
+            let (batch, next_page) = self
+                .execute_with_retry(|| async {
+                    let response = self
+                        .http_client
+                        .get(url)
+                        .bearer_auth(token)
+                        .query(query)
+                        .query(&[("per_page", PER_PAGE), ("page", page.as_str())])
+                        .send()
+                        .await
+                        .map_err(|e| VcsProviderError::Network(e.to_string()))?;
+
+                    let response = self.handle_response(response).await?;
+                    let next_page = response
+                        .headers()
+                        .get("x-next-page")
+                        .and_then(|v| v.to_str().ok())
+                        .filter(|v| !v.is_empty())
+                        .map(str::to_string);
+
+                    let batch = response.json::<Vec<T>>().await.map_err(|e| {
+                        VcsProviderError::PullRequest(format!("Failed to parse response: {}", e))
+                    })?;
+                    Ok((batch, next_page))
+                })
+                .await?;
+
+            items.extend(batch);
+
+            match next_page {
+                Some(next) => page = next,
+                None => break,
+            }
+        }
+
+        Ok(items)
+    }
+
+    /// Create a merge request
+    pub async fn create_merge_request(
+        &self,
+        base_url: &str,
+        token: &str,
+        namespace: &str,
+        repo: &str,
+        request: &CreateMergeRequestRequest,
+    ) -> Result<GitLabMergeRequest, VcsProviderError> {
+        let url = Self::project_url(base_url, namespace, repo, "/merge_requests");
+
+        debug!("Creating MR at {}", url);
+
+        self.execute_with_retry(|| async {
+            let response = self
+                .http_client
+                .post(&url)
+                .bearer_auth(token)
+                .json(request)
+                .send()
+                .await
+                .map_err(|e| VcsProviderError::Network(e.to_string()))?;
+
+            let response = self.handle_response(response).await?;
+
+            response.json::<GitLabMergeRequest>().await.map_err(|e| {
+                VcsProviderError::PullRequest(format!("Failed to parse response: {}", e))
+            })
+        })
+        .await
+    }
+
+    /// Get a merge request by its project-scoped iid
+    pub async fn get_merge_request(
+        &self,
+        base_url: &str,
+        token: &str,
+        namespace: &str,
+        repo: &str,
+        iid: i64,
+    ) -> Result<GitLabMergeRequest, VcsProviderError> {
+        let url = Self::project_url(
+            base_url,
+            namespace,
+            repo,
+            &format!("/merge_requests/{}", iid),
+        );
+
+        debug!("Getting MR from {}", url);
+
+        self.execute_with_retry(|| async {
+            let response = self
+                .http_client
+                .get(&url)
+                .bearer_auth(token)
+                .send()
+                .await
+                .map_err(|e| VcsProviderError::Network(e.to_string()))?;
+
+            let response = self.handle_response(response).await?;
+
+            response.json::<GitLabMergeRequest>().await.map_err(|e| {
+                VcsProviderError::PullRequest(format!("Failed to parse response: {}", e))
+            })
+        })
+        .await
+    }
+
+    /// List merge requests in any state whose source branch is `branch`
+    pub async fn list_merge_requests_for_branch(
+        &self,
+        base_url: &str,
+        token: &str,
+        namespace: &str,
+        repo: &str,
+        branch: &str,
+    ) -> Result<Vec<GitLabMergeRequest>, VcsProviderError> {
+        let url = Self::project_url(base_url, namespace, repo, "/merge_requests");
+
+        self.get_all_pages(&url, token, &[("source_branch", branch), ("state", "all")])
+            .await
+    }
+
+    /// Get all discussions (general and diff comment threads) of a merge request
+    pub async fn get_merge_request_discussions(
+        &self,
+        base_url: &str,
+        token: &str,
+        namespace: &str,
+        repo: &str,
+        iid: i64,
+    ) -> Result<Vec<GitLabDiscussion>, VcsProviderError> {
+        let url = Self::project_url(
+            base_url,
+            namespace,
+            repo,
+            &format!("/merge_requests/{}/discussions", iid),
+        );
+
+        self.get_all_pages(&url, token, &[]).await
+    }
+
+    /// Add a note to a discussion of a merge request, replying in its thread
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_discussion_note(
+        &self,
+        base_url: &str,
+        token: &str,
+        namespace: &str,
+        repo: &str,
+        iid: i64,
+        discussion_id: &str,
+        body: &str,
+    ) -> Result<(), VcsProviderError> {
+        let url = Self::project_url(
+            base_url,
+            namespace,
+            repo,
+            &format!(
+                "/merge_requests/{}/discussions/{}/notes",
+                iid, discussion_id
+            ),
+        );
+
+        debug!("Creating MR discussion note at {}", url);
+
+        let response = self
+            .http_client
+            .post(&url)
+            .bearer_auth(token)
+            .json(&serde_json::json!({ "body": body }))
+            .send()
+            .await
+            .map_err(|e| VcsProviderError::Network(e.to_string()))?;
+
+        self.handle_response(response).await?;
+        Ok(())
+    }
+
+    /// Verify token is valid by fetching the current user
+    pub async fn verify_token(&self, base_url: &str, token: &str) -> Result<(), VcsProviderError> {
+        let url = format!("{}/api/v4/user", base_url.trim_end_matches('/'));
+
+        debug!("Verifying GitLab token at {}", url);
+
+        let response = self
+            .http_client
+            .get(&url)
+            .bearer_auth(token)
+            .send()
+            .await
+            .map_err(|e| VcsProviderError::Network(e.to_string()))?;
+
+        self.handle_response(response).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_url_encodes_nested_namespace() {
+        let url = GitLabApiClient::project_url(
+            "https://gitlab.example.com/",
+            "platform/backend",
+            "api",
+            "/merge_requests",
+        );
+        assert_eq!(
+            url,
+            "https://gitlab.example.com/api/v4/projects/platform%2Fbackend%2Fapi/merge_requests"
+        );
+    }
+}
@@ -0,0 +1,370 @@
+//! GitLab credential management.
+//!
+//! Handles secure storage of GitLab personal access tokens,
+//! with support for both file-based and macOS Keychain storage.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+/// GitLab credentials containing the personal access token.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitLabCredentials {
+    /// Personal access token with the `api` scope
+    pub access_token: String,
+    /// Base URL of the GitLab instance, e.g. `https://gitlab.com`
+    pub base_url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredCredentials {
+    access_token: String,
+    base_url: String,
+}
+
+impl From<StoredCredentials> for GitLabCredentials {
+    fn from(value: StoredCredentials) -> Self {
+        Self {
+            access_token: value.access_token,
+            base_url: value.base_url,
+        }
+    }
+}
+
+/// Service for managing GitLab credentials in memory and persistent storage.
+pub struct GitLabCredentialStore {
+    backend: Backend,
+    inner: RwLock<Option<GitLabCredentials>>,
+}
+
+impl GitLabCredentialStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            backend: Backend::detect(path),
+            inner: RwLock::new(None),
+        }
+    }
+
+    /// Get the default path for storing GitLab credentials
+    pub fn default_path() -> PathBuf {
+        dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join(".vibe-kanban")
+            .join("gitlab_credentials.json")
+    }
+
+    pub async fn load(&self) -> std::io::Result<()> {
+        let creds = self.backend.load().await?.map(GitLabCredentials::from);
+        *self.inner.write().await = creds;
+        Ok(())
+    }
+
+    pub async fn save(&self, creds: &GitLabCredentials) -> std::io::Result<()> {
+        let stored = StoredCredentials {
+            access_token: creds.access_token.clone(),
+            base_url: creds.base_url.clone(),
+        };
+        self.backend.save(&stored).await?;
+        *self.inner.write().await = Some(creds.clone());
+        Ok(())
+    }
+
+    pub async fn clear(&self) -> std::io::Result<()> {
+        self.backend.clear().await?;
+        *self.inner.write().await = None;
+        Ok(())
+    }
+
+    pub async fn get(&self) -> Option<GitLabCredentials> {
+        self.inner.read().await.clone()
+    }
+
+    /// Check if credentials are configured
+    pub async fn is_configured(&self) -> bool {
+        self.inner.read().await.is_some()
+    }
+}
+
+trait StoreBackend {
+    async fn load(&self) -> std::io::Result<Option<StoredCredentials>>;
+    async fn save(&self, creds: &StoredCredentials) -> std::io::Result<()>;
+    async fn clear(&self) -> std::io::Result<()>;
+}
+
+enum Backend {
+    File(FileBackend),
+    #[cfg(target_os = "macos")]
+    Keychain(KeychainBackend),
+}
+
+impl Backend {
+    fn detect(path: PathBuf) -> Self {
+        #[cfg(target_os = "macos")]
+        {
+            let use_file = match std::env::var("GITLAB_CREDENTIALS_BACKEND") {
+                Ok(v) if v.eq_ignore_ascii_case("file") => true,
+                Ok(v) if v.eq_ignore_ascii_case("keychain") => false,
+                _ => cfg!(debug_assertions),
+            };
+            if use_file {
+                tracing::debug!("GitLab credentials backend: file");
+                Backend::File(FileBackend { path })
+            } else {
+                tracing::debug!("GitLab credentials backend: keychain");
+                Backend::Keychain(KeychainBackend)
+            }
+        }
+        #[cfg(not(target_os = "macos"))]
+        {
+            tracing::debug!("GitLab credentials backend: file");
+            Backend::File(FileBackend { path })
+        }
+    }
+}
+
+impl StoreBackend for Backend {
+    async fn load(&self) -> std::io::Result<Option<StoredCredentials>> {
+        match self {
+            Backend::File(b) => b.load().await,
+            #[cfg(target_os = "macos")]
+            Backend::Keychain(b) => b.load().await,
+        }
+    }
+
+    async fn save(&self, creds: &StoredCredentials) -> std::io::Result<()> {
+        match self {
+            Backend::File(b) => b.save(creds).await,
+            #[cfg(target_os = "macos")]
+            Backend::Keychain(b) => b.save(creds).await,
+        }
+    }
+
+    async fn clear(&self) -> std::io::Result<()> {
+        match self {
+            Backend::File(b) => b.clear().await,
+            #[cfg(target_os = "macos")]
+            Backend::Keychain(b) => b.clear().await,
+        }
+    }
+}
+
+struct FileBackend {
+    path: PathBuf,
+}
+
+impl FileBackend {
+    async fn load(&self) -> std::io::Result<Option<StoredCredentials>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+
+        let bytes = std::fs::read(&self.path)?;
+        match serde_json::from_slice::<StoredCredentials>(&bytes) {
+            Ok(creds) => Ok(Some(creds)),
+            Err(e) => {
+                tracing::warn!(
+                    ?e,
+                    "failed to parse GitLab credentials file, renaming to .bad"
+                );
+                let bad = self.path.with_extension("bad");
+                let _ = std::fs::rename(&self.path, bad);
+                Ok(None)
+            }
+        }
+    }
+
+    async fn save(&self, creds: &StoredCredentials) -> std::io::Result<()> {
+        // Ensure parent directory exists
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let tmp = self.path.with_extension("tmp");
+
+        let file = {
+            let mut opts = std::fs::OpenOptions::new();
+            opts.create(true).truncate(true).write(true);
+
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::OpenOptionsExt;
+                opts.mode(0o600);
+            }
+
+            opts.open(&tmp)?
+        };
+
+        serde_json::to_writer_pretty(&file, creds)?;
+        file.sync_all()?;
+        drop(file);
+
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+
+    async fn clear(&self) -> std::io::Result<()> {
+        let _ = std::fs::remove_file(&self.path);
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "macos")]
+struct KeychainBackend;
+
+#[cfg(target_os = "macos")]
+impl KeychainBackend {
+    const SERVICE_NAME: &'static str = "vibe-kanban:gitlab";
+    const ACCOUNT_NAME: &'static str = "default";
+    const ERR_SEC_ITEM_NOT_FOUND: i32 = -25300;
+
+    async fn load(&self) -> std::io::Result<Option<StoredCredentials>> {
+        use security_framework::passwords::get_generic_password;
+
+        match get_generic_password(Self::SERVICE_NAME, Self::ACCOUNT_NAME) {
+            Ok(bytes) => match serde_json::from_slice::<StoredCredentials>(&bytes) {
+                Ok(creds) => Ok(Some(creds)),
+                Err(error) => {
+                    tracing::warn!(
+                        ?error,
+                        "failed to parse GitLab keychain credentials; ignoring entry"
+                    );
+                    Ok(None)
+                }
+            },
+            Err(e) if e.code() == Self::ERR_SEC_ITEM_NOT_FOUND => Ok(None),
+            Err(e) => Err(std::io::Error::other(e)),
+        }
+    }
+
+    async fn save(&self, creds: &StoredCredentials) -> std::io::Result<()> {
+        use security_framework::passwords::set_generic_password;
+
+        let bytes = serde_json::to_vec_pretty(creds).map_err(std::io::Error::other)?;
+        set_generic_password(Self::SERVICE_NAME, Self::ACCOUNT_NAME, &bytes)
+            .map_err(std::io::Error::other)
+    }
+
+    async fn clear(&self) -> std::io::Result<()> {
+        use security_framework::passwords::delete_generic_password;
+
+        match delete_generic_password(Self::SERVICE_NAME, Self::ACCOUNT_NAME) {
+            Ok(()) => Ok(()),
+            Err(e) if e.code() == Self::ERR_SEC_ITEM_NOT_FOUND => Ok(()),
+            Err(e) => Err(std::io::Error::other(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn test_default_path() {
+        let path = GitLabCredentialStore::default_path();
+        assert!(path.to_string_lossy().contains("gitlab_credentials.json"));
+        assert!(path.to_string_lossy().contains(".vibe-kanban"));
+    }
+
+    #[tokio::test]
+    async fn test_credential_store_empty_initially() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("creds.json");
+        let store = GitLabCredentialStore::new(path);
+
+        assert!(!store.is_configured().await);
+        assert!(store.get().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_credential_store_save_and_load() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("creds.json");
+        let store = GitLabCredentialStore::new(path.clone());
+
+        let creds = GitLabCredentials {
+            access_token: "test-token-123".to_string(),
+            base_url: "https://git.example.com".to_string(),
+        };
+
+        // Save credentials
+        store.save(&creds).await.unwrap();
+        assert!(store.is_configured().await);
+
+        // Verify we can get them back
+        let loaded = store.get().await.unwrap();
+        assert_eq!(loaded.access_token, "test-token-123");
+        assert_eq!(loaded.base_url, "https://git.example.com");
+
+        // Verify file exists
+        assert!(path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_credential_store_load_from_file() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("creds.json");
+
+        // Write credentials file directly
+        let json = r#"{"access_token":"file-token","base_url":"https://gitlab.test.com"}"#;
+        std::fs::write(&path, json).unwrap();
+
+        let store = GitLabCredentialStore::new(path);
+        store.load().await.unwrap();
+
+        let loaded = store.get().await.unwrap();
+        assert_eq!(loaded.access_token, "file-token");
+        assert_eq!(loaded.base_url, "https://gitlab.test.com");
+    }
+
+    #[tokio::test]
+    async fn test_credential_store_load_nonexistent() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("nonexistent.json");
+        let store = GitLabCredentialStore::new(path);
+
+        // Load should succeed but not set credentials
+        store.load().await.unwrap();
+        assert!(!store.is_configured().await);
+    }
+
+    #[tokio::test]
+    async fn test_credential_store_clear() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("creds.json");
+        let store = GitLabCredentialStore::new(path.clone());
+
+        let creds = GitLabCredentials {
+            access_token: "token".to_string(),
+            base_url: "https://example.com".to_string(),
+        };
+
+        store.save(&creds).await.unwrap();
+        assert!(store.is_configured().await);
+
+        store.clear().await.unwrap();
+        assert!(!store.is_configured().await);
+        assert!(store.get().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_credential_store_invalid_json() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("bad_creds.json");
+
+        // Write invalid JSON
+        std::fs::write(&path, "not valid json").unwrap();
+
+        let store = GitLabCredentialStore::new(path.clone());
+        store.load().await.unwrap(); // Should not fail
+
+        // Credentials should not be loaded
+        assert!(!store.is_configured().await);
+
+        // Bad file should be renamed
+        assert!(path.with_extension("bad").exists());
+    }
+}
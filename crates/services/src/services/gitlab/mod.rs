@@ -0,0 +1,780 @@
+//! GitLab integration service.
+//!
+//! Provides support for the GitLab REST API v4 (gitlab.com and self-managed instances), including:
+//! - Merge request creation
+//! - MR status tracking
+//! - Comment fetching (MR discussions, both general and diff notes)
+//! - Pipeline status of the MR head commit
+//!
+//! Authentication is done via personal access tokens with the `api` scope.
+
+mod api_client;
+pub mod credentials;
+pub mod models;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use db::models::merge::PullRequestInfo;
+use tracing::{debug, info};
+use url::Url;
+
+use self::{
+    api_client::GitLabApiClient,
+    credentials::{GitLabCredentialStore, GitLabCredentials},
+    models::CreateMergeRequestRequest,
+};
+use super::{
+    github::UnifiedPrComment,
+    vcs_provider::{
+        CiCheck, CreatePrRequest, PrCiReport, VcsProvider, VcsProviderError, VcsProviderType,
+        VcsRepoInfo,
+    },
+};
+
+/// GitLab service implementing the VcsProvider trait.
+pub struct GitLabService {
+    client: GitLabApiClient,
+    credentials: Arc<GitLabCredentialStore>,
+}
+
+impl GitLabService {
+    /// Create a new GitLabService with the default credential store path
+    pub fn new() -> Result<Self, VcsProviderError> {
+        let credentials = Arc::new(GitLabCredentialStore::new(
+            GitLabCredentialStore::default_path(),
+        ));
+        Self::with_credentials(credentials)
+    }
+
+    /// Create a new GitLabService with a custom credential store
+    pub fn with_credentials(
+        credentials: Arc<GitLabCredentialStore>,
+    ) -> Result<Self, VcsProviderError> {
+        let client = GitLabApiClient::new()?;
+
+        Ok(Self {
+            client,
+            credentials,
+        })
+    }
+
+    /// Get the credential store for external configuration
+    pub fn credentials(&self) -> &Arc<GitLabCredentialStore> {
+        &self.credentials
+    }
+
+    /// Load credentials from storage
+    pub async fn load_credentials(&self) -> Result<(), VcsProviderError> {
+        self.credentials.load().await.map_err(VcsProviderError::Io)
+    }
+
+    /// Save credentials to storage
+    pub async fn save_credentials(
+        &self,
+        creds: &GitLabCredentials,
+    ) -> Result<(), VcsProviderError> {
+        self.credentials
+            .save(creds)
+            .await
+            .map_err(VcsProviderError::Io)
+    }
+
+    /// Get credentials, returning an error if not configured
+    async fn get_credentials(&self) -> Result<GitLabCredentials, VcsProviderError> {
+        self.credentials
+            .get()
+            .await
+            .ok_or_else(|| VcsProviderError::AuthRequired("GitLab".to_string()))
+    }
+
+    /// Get the credentials for the instance hosting `repo_info`. The access token belongs to
+    /// one instance, so repos on other hosts are refused rather than looked up there.
+    async fn credentials_for(
+        &self,
+        repo_info: &VcsRepoInfo,
+    ) -> Result<GitLabCredentials, VcsProviderError> {
+        let creds = self.get_credentials().await?;
+        let host = |base_url: &str| {
+            Url::parse(base_url)
+                .ok()
+                .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+        };
+        let repo_host = host(&repo_info.base_url);
+        if repo_host.is_none() || repo_host != host(&creds.base_url) {
+            return Err(VcsProviderError::AuthRequired(format!(
+                "GitLab ({})",
+                repo_info.base_url
+            )));
+        }
+        Ok(creds)
+    }
+}
+
+#[async_trait]
+impl VcsProvider for GitLabService {
+    fn provider_type(&self) -> VcsProviderType {
+        VcsProviderType::GitLab
+    }
+
+    fn matches_remote_url(&self, url: &str) -> bool {
+        VcsRepoInfo::parse_gitlab_url(url).is_ok()
+    }
+
+    async fn check_auth(&self) -> Result<(), VcsProviderError> {
+        let creds = self.get_credentials().await?;
+
+        self.client
+            .verify_token(&creds.base_url, &creds.access_token)
+            .await
+            .map_err(|e| match e {
+                VcsProviderError::AuthFailed(_) => VcsProviderError::AuthFailed(
+                    "GitLab access token is invalid or expired".to_string(),
+                ),
+                _ => e,
+            })
+    }
+
+    async fn create_pr(
+        &self,
+        repo_info: &VcsRepoInfo,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, VcsProviderError> {
+        let creds = self.credentials_for(repo_info).await?;
+
+        info!(
+            "Creating GitLab MR in {}/{}: {}",
+            repo_info.owner_or_project, repo_info.repo_name, request.title
+        );
+
+        // GitLab marks merge requests as drafts through the title prefix
+        let title = if request.draft.unwrap_or(false) {
+            format!("Draft: {}", request.title)
+        } else {
+            request.title.clone()
+        };
+
+        let gl_request = CreateMergeRequestRequest {
+            source_branch: request.head_branch.clone(),
+            target_branch: request.base_branch.clone(),
+            title,
+            description: request.body.clone(),
+        };
+
+        let mr = self
+            .client
+            .create_merge_request(
+                &creds.base_url,
+                &creds.access_token,
+                &repo_info.owner_or_project,
+                &repo_info.repo_name,
+                &gl_request,
+            )
+            .await?;
+
+        info!("Created GitLab MR !{}: {}", mr.iid, mr.web_url);
+
+        Ok(mr.to_pull_request_info())
+    }
+
+    async fn get_pr_status(
+        &self,
+        repo_info: &VcsRepoInfo,
+        pr_number: i64,
+    ) -> Result<PullRequestInfo, VcsProviderError> {
+        let creds = self.credentials_for(repo_info).await?;
+
+        debug!(
+            "Getting GitLab MR status for {}/{} !{}",
+            repo_info.owner_or_project, repo_info.repo_name, pr_number
+        );
+
+        let mr = self
+            .client
+            .get_merge_request(
+                &creds.base_url,
+                &creds.access_token,
+                &repo_info.owner_or_project,
+                &repo_info.repo_name,
+                pr_number,
+            )
+            .await?;
+
+        Ok(mr.to_pull_request_info())
+    }
+
+    async fn list_prs_for_branch(
+        &self,
+        repo_info: &VcsRepoInfo,
+        branch_name: &str,
+    ) -> Result<Vec<PullRequestInfo>, VcsProviderError> {
+        let creds = self.credentials_for(repo_info).await?;
+
+        debug!(
+            "Listing GitLab MRs for branch {} in {}/{}",
+            branch_name, repo_info.owner_or_project, repo_info.repo_name
+        );
+
+        let mrs = self
+            .client
+            .list_merge_requests_for_branch(
+                &creds.base_url,
+                &creds.access_token,
+                &repo_info.owner_or_project,
+                &repo_info.repo_name,
+                branch_name,
+            )
+            .await?;
+
+        Ok(mrs.iter().map(|mr| mr.to_pull_request_info()).collect())
+    }
+
+    async fn get_pr_comments(
+        &self,
+        repo_info: &VcsRepoInfo,
+        pr_number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, VcsProviderError> {
+        let creds = self.credentials_for(repo_info).await?;
+
+        debug!(
+            "Getting GitLab MR comments for {}/{} !{}",
+            repo_info.owner_or_project, repo_info.repo_name, pr_number
+        );
+
+        // Comment URLs are anchors on the MR page, so fetch the MR for its web URL
+        let (mr_result, discussions_result) = tokio::join!(
+            self.client.get_merge_request(
+                &creds.base_url,
+                &creds.access_token,
+                &repo_info.owner_or_project,
+                &repo_info.repo_name,
+                pr_number,
+            ),
+            self.client.get_merge_request_discussions(
+                &creds.base_url,
+                &creds.access_token,
+                &repo_info.owner_or_project,
+                &repo_info.repo_name,
+                pr_number,
+            )
+        );
+        let mr_url = mr_result?.web_url;
+
+        let mut unified_comments: Vec<UnifiedPrComment> = discussions_result?
+            .into_iter()
+            .flat_map(|discussion| discussion.notes)
+            .filter(|note| !note.system)
+            .map(|note| note.to_unified_comment(&mr_url))
+            .collect();
+
+        // Sort by creation time
+        unified_comments.sort_by_key(|c| match c {
+            UnifiedPrComment::General { created_at, .. } => *created_at,
+            UnifiedPrComment::Review { created_at, .. } => *created_at,
+        });
+
+        Ok(unified_comments)
+    }
+
+    async fn reply_to_pr_comment(
+        &self,
+        repo_info: &VcsRepoInfo,
+        pr_number: i64,
+        comment: &UnifiedPrComment,
+        body: &str,
+    ) -> Result<(), VcsProviderError> {
+        let creds = self.credentials_for(repo_info).await?;
+
+        let note_id = match comment {
+            UnifiedPrComment::General { id, .. } => id.parse::<i64>().map_err(|_| {
+                VcsProviderError::PullRequest(format!("Invalid GitLab note id: {id}"))
+            })?,
+            UnifiedPrComment::Review { id, .. } => *id,
+        };
+
+        // Notes are answered through their discussion, which the comment doesn't carry
+        let discussion = self
+            .client
+            .get_merge_request_discussions(
+                &creds.base_url,
+                &creds.access_token,
+                &repo_info.owner_or_project,
+                &repo_info.repo_name,
+                pr_number,
+            )
+            .await?
+            .into_iter()
+            .find(|discussion| discussion.notes.iter().any(|note| note.id == note_id))
+            .ok_or_else(|| {
+                VcsProviderError::NotFound(format!("GitLab note {note_id} on !{pr_number}"))
+            })?;
+
+        self.client
+            .create_discussion_note(
+                &creds.base_url,
+                &creds.access_token,
+                &repo_info.owner_or_project,
+                &repo_info.repo_name,
+                pr_number,
+                &discussion.id,
+                body,
+            )
+            .await
+    }
+
+    async fn get_pr_ci_status(
+        &self,
+        repo_info: &VcsRepoInfo,
+        pr_number: i64,
+    ) -> Result<PrCiReport, VcsProviderError> {
+        let creds = self.credentials_for(repo_info).await?;
+
+        let mr = self
+            .client
+            .get_merge_request(
+                &creds.base_url,
+                &creds.access_token,
+                &repo_info.owner_or_project,
+                &repo_info.repo_name,
+                pr_number,
+            )
+            .await?;
+
+        let head_sha = mr.sha.ok_or_else(|| {
+            VcsProviderError::PullRequest(format!("GitLab MR !{pr_number} has no head commit"))
+        })?;
+        let checks = mr
+            .head_pipeline
+            .filter(|pipeline| pipeline.sha == head_sha)
+            .map(|pipeline| vec![pipeline.to_ci_check()])
+            .unwrap_or_default();
+
+        Ok(PrCiReport { head_sha, checks })
+    }
+
+    async fn get_ci_check_log(
+        &self,
+        _repo_info: &VcsRepoInfo,
+        _check: &CiCheck,
+    ) -> Result<Option<String>, VcsProviderError> {
+        // Job logs would need a call per job of the pipeline; the agent gets the pipeline link
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use axum::{
+        Json, Router,
+        extract::{Path, Query, State},
+        http::{HeaderMap, StatusCode},
+        response::IntoResponse,
+        routing::{get, post},
+    };
+    use db::models::merge::{CiStatus, MergeStatus};
+    use serde_json::{Value, json};
+    use tempfile::TempDir;
+
+    use super::*;
+
+    const TOKEN: &str = "glpat-test";
+    const PROJECT: &str = "group/subgroup/repo";
+
+    #[derive(Clone, Default)]
+    struct MockState {
+        created: Arc<Mutex<Vec<Value>>>,
+        /// Discussion id and body of every note created
+        notes: Arc<Mutex<Vec<(String, Value)>>>,
+    }
+
+    fn authorized(headers: &HeaderMap) -> bool {
+        headers
+            .get("authorization")
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v == format!("Bearer {TOKEN}"))
+    }
+
+    fn mr_json(iid: i64, state: &str, source_branch: &str) -> Value {
+        json!({
+            "id": 1000 + iid,
+            "iid": iid,
+            "title": "Add feature",
+            "description": null,
+            "state": state,
+            "source_branch": source_branch,
+            "target_branch": "main",
+            "web_url": format!("https://gitlab.example.com/{PROJECT}/-/merge_requests/{iid}"),
+            "merged_at": if state == "merged" { json!("2025-01-02T03:04:05.000Z") } else { Value::Null },
+            "merge_commit_sha": if state == "merged" { json!("deadbeef") } else { Value::Null },
+            "squash_commit_sha": null,
+            "sha": "cafebabe",
+            "head_pipeline": {
+                "id": 77,
+                "sha": "cafebabe",
+                "status": "failed",
+                "web_url": format!("https://gitlab.example.com/{PROJECT}/-/pipelines/77")
+            }
+        })
+    }
+
+    fn note_json(id: i64, created_at: &str, system: bool, position: Value) -> Value {
+        json!({
+            "id": id,
+            "type": if position.is_null() { Value::Null } else { json!("DiffNote") },
+            "body": format!("note {id}"),
+            "author": {"id": 7, "username": "reviewer", "name": "Reviewer"},
+            "created_at": created_at,
+            "system": system,
+            "position": position
+        })
+    }
+
+    async fn create_mr(
+        State(state): State<MockState>,
+        Path(project): Path<String>,
+        headers: HeaderMap,
+        Json(body): Json<Value>,
+    ) -> impl IntoResponse {
+        if !authorized(&headers) {
+            return (
+                StatusCode::UNAUTHORIZED,
+                Json(json!({"message": "401 Unauthorized"})),
+            );
+        }
+        assert_eq!(project, PROJECT);
+        state.created.lock().unwrap().push(body.clone());
+        let source_branch = body["source_branch"].as_str().unwrap_or_default();
+        (
+            StatusCode::CREATED,
+            Json(mr_json(12, "opened", source_branch)),
+        )
+    }
+
+    async fn list_mrs(
+        Path(_project): Path<String>,
+        Query(query): Query<std::collections::HashMap<String, String>>,
+    ) -> impl IntoResponse {
+        assert_eq!(query.get("state").map(String::as_str), Some("all"));
+        let branch = query.get("source_branch").cloned().unwrap_or_default();
+        // Two pages of one MR each, linked by X-Next-Page
+        match query.get("page").map(String::as_str) {
+            Some("1") => (
+                [("x-next-page", "2")],
+                Json(json!([mr_json(3, "closed", &branch)])),
+            ),
+            _ => (
+                [("x-next-page", "")],
+                Json(json!([mr_json(12, "merged", &branch)])),
+            ),
+        }
+    }
+
+    async fn get_mr(Path((_project, iid)): Path<(String, i64)>) -> impl IntoResponse {
+        if iid == 404 {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(json!({"message": "404 Not found"})),
+            );
+        }
+        (StatusCode::OK, Json(mr_json(iid, "merged", "vk/feature")))
+    }
+
+    async fn get_discussions(Path((_project, _iid)): Path<(String, i64)>) -> Json<Value> {
+        Json(json!([
+            {
+                "id": "d1",
+                "individual_note": true,
+                "notes": [note_json(2, "2025-01-02T00:00:00.000Z", false, Value::Null)]
+            },
+            {
+                "id": "d2",
+                "individual_note": true,
+                "notes": [note_json(3, "2025-01-01T12:00:00.000Z", true, Value::Null)]
+            },
+            {
+                "id": "d3",
+                "individual_note": false,
+                "notes": [
+                    note_json(1, "2025-01-01T00:00:00.000Z", false, json!({
+                        "old_path": "src/main.rs",
+                        "new_path": "src/main.rs",
+                        "old_line": null,
+                        "new_line": 10
+                    })),
+                    note_json(4, "2025-01-03T00:00:00.000Z", false, Value::Null)
+                ]
+            }
+        ]))
+    }
+
+    async fn create_note(
+        State(state): State<MockState>,
+        Path((_project, _iid, discussion_id)): Path<(String, i64, String)>,
+        Json(body): Json<Value>,
+    ) -> impl IntoResponse {
+        state.notes.lock().unwrap().push((discussion_id, body));
+        (
+            StatusCode::CREATED,
+            Json(note_json(5, "2025-01-04T00:00:00.000Z", false, Value::Null)),
+        )
+    }
+
+    async fn spawn_mock_gitlab() -> (String, MockState) {
+        let state = MockState::default();
+        let app = Router::new()
+            .route(
+                "/api/v4/projects/{project}/merge_requests",
+                post(create_mr).get(list_mrs),
+            )
+            .route(
+                "/api/v4/projects/{project}/merge_requests/{iid}",
+                get(get_mr),
+            )
+            .route(
+                "/api/v4/projects/{project}/merge_requests/{iid}/discussions",
+                get(get_discussions),
+            )
+            .route(
+                "/api/v4/projects/{project}/merge_requests/{iid}/discussions/{discussion_id}/notes",
+                post(create_note),
+            )
+            .with_state(state.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        (format!("http://{addr}"), state)
+    }
+
+    async fn service_for(base_url: &str, token: &str) -> (GitLabService, TempDir) {
+        let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+
+        let temp_dir = TempDir::new().unwrap();
+        let store = Arc::new(GitLabCredentialStore::new(
+            temp_dir.path().join("gitlab_credentials.json"),
+        ));
+        store
+            .save(&GitLabCredentials {
+                access_token: token.to_string(),
+                base_url: base_url.to_string(),
+            })
+            .await
+            .unwrap();
+
+        (GitLabService::with_credentials(store).unwrap(), temp_dir)
+    }
+
+    /// `group/subgroup/repo` on the instance at `base_url`
+    fn repo_info(base_url: &str) -> VcsRepoInfo {
+        VcsRepoInfo {
+            provider_type: VcsProviderType::GitLab,
+            base_url: base_url.to_string(),
+            owner_or_project: "group/subgroup".to_string(),
+            repo_name: "repo".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_draft_mr() {
+        let (base_url, state) = spawn_mock_gitlab().await;
+        let (service, _dir) = service_for(&base_url, TOKEN).await;
+
+        let info = service
+            .create_pr(
+                &repo_info(&base_url),
+                &CreatePrRequest {
+                    title: "Add feature".to_string(),
+                    body: Some("Details".to_string()),
+                    head_branch: "vk/feature".to_string(),
+                    base_branch: "main".to_string(),
+                    draft: Some(true),
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(info.number, 12);
+        assert!(matches!(info.status, MergeStatus::Open));
+        let created = state.created.lock().unwrap();
+        assert_eq!(created[0]["title"], "Draft: Add feature");
+        assert_eq!(created[0]["source_branch"], "vk/feature");
+        assert_eq!(created[0]["target_branch"], "main");
+        assert_eq!(created[0]["description"], "Details");
+    }
+
+    #[tokio::test]
+    async fn test_create_mr_with_invalid_token() {
+        let (base_url, _state) = spawn_mock_gitlab().await;
+        let (service, _dir) = service_for(&base_url, "wrong").await;
+
+        let result = service
+            .create_pr(
+                &repo_info(&base_url),
+                &CreatePrRequest {
+                    title: "Add feature".to_string(),
+                    body: None,
+                    head_branch: "vk/feature".to_string(),
+                    base_branch: "main".to_string(),
+                    draft: None,
+                },
+            )
+            .await;
+
+        assert!(matches!(result, Err(VcsProviderError::AuthFailed(_))));
+    }
+
+    #[tokio::test]
+    async fn test_get_mr_status() {
+        let (base_url, _state) = spawn_mock_gitlab().await;
+        let (service, _dir) = service_for(&base_url, TOKEN).await;
+
+        let info = service
+            .get_pr_status(&repo_info(&base_url), 12)
+            .await
+            .unwrap();
+        assert!(matches!(info.status, MergeStatus::Merged));
+        assert_eq!(info.merge_commit_sha.as_deref(), Some("deadbeef"));
+
+        let missing = service.get_pr_status(&repo_info(&base_url), 404).await;
+        assert!(matches!(missing, Err(VcsProviderError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_get_mr_ci_status() {
+        let (base_url, _state) = spawn_mock_gitlab().await;
+        let (service, _dir) = service_for(&base_url, TOKEN).await;
+
+        let report = service
+            .get_pr_ci_status(&repo_info(&base_url), 12)
+            .await
+            .unwrap();
+        assert_eq!(report.head_sha, "cafebabe");
+        assert_eq!(report.status(), Some(CiStatus::Failure));
+        assert_eq!(
+            report.checks[0].url.as_deref(),
+            Some("https://gitlab.example.com/group/subgroup/repo/-/pipelines/77")
+        );
+        assert_eq!(
+            service
+                .get_ci_check_log(&repo_info(&base_url), &report.checks[0])
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_mrs_follows_pagination() {
+        let (base_url, _state) = spawn_mock_gitlab().await;
+        let (service, _dir) = service_for(&base_url, TOKEN).await;
+
+        let mrs = service
+            .list_prs_for_branch(&repo_info(&base_url), "vk/feature")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            mrs.iter().map(|mr| mr.number).collect::<Vec<_>>(),
+            vec![3, 12]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_mr_comments_skips_system_notes_and_sorts() {
+        let (base_url, _state) = spawn_mock_gitlab().await;
+        let (service, _dir) = service_for(&base_url, TOKEN).await;
+
+        let comments = service
+            .get_pr_comments(&repo_info(&base_url), 12)
+            .await
+            .unwrap();
+
+        assert_eq!(comments.len(), 3);
+        match &comments[0] {
+            UnifiedPrComment::Review {
+                id,
+                path,
+                line,
+                url,
+                ..
+            } => {
+                assert_eq!(*id, 1);
+                assert_eq!(path, "src/main.rs");
+                assert_eq!(*line, Some(10));
+                assert_eq!(
+                    url,
+                    "https://gitlab.example.com/group/subgroup/repo/-/merge_requests/12#note_1"
+                );
+            }
+            _ => panic!("Expected Review comment first"),
+        }
+        assert!(matches!(&comments[1], UnifiedPrComment::General { id, .. } if id == "2"));
+        assert!(matches!(&comments[2], UnifiedPrComment::General { id, .. } if id == "4"));
+    }
+
+    #[tokio::test]
+    async fn test_reply_to_comment_in_its_discussion() {
+        let (base_url, state) = spawn_mock_gitlab().await;
+        let (service, _dir) = service_for(&base_url, TOKEN).await;
+
+        let comments = service
+            .get_pr_comments(&repo_info(&base_url), 12)
+            .await
+            .unwrap();
+        let reply_to = comments
+            .iter()
+            .find(|c| matches!(c, UnifiedPrComment::General { id, .. } if id == "4"))
+            .unwrap();
+        service
+            .reply_to_pr_comment(&repo_info(&base_url), 12, reply_to, "Done")
+            .await
+            .unwrap();
+
+        let notes = state.notes.lock().unwrap();
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].0, "d3");
+        assert_eq!(notes[0].1["body"], "Done");
+    }
+
+    #[tokio::test]
+    async fn test_missing_credentials() {
+        let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+        let temp_dir = TempDir::new().unwrap();
+        let store = Arc::new(GitLabCredentialStore::new(
+            temp_dir.path().join("gitlab_credentials.json"),
+        ));
+        let service = GitLabService::with_credentials(store).unwrap();
+
+        let result = service
+            .get_pr_status(&repo_info("https://gitlab.example.com"), 1)
+            .await;
+        assert!(matches!(result, Err(VcsProviderError::AuthRequired(_))));
+    }
+
+    #[tokio::test]
+    async fn test_repo_on_another_host_is_refused() {
+        let (base_url, state) = spawn_mock_gitlab().await;
+        let (service, _dir) = service_for(&base_url, TOKEN).await;
+
+        let result = service
+            .create_pr(
+                &repo_info("https://gitlab.other.example.com"),
+                &CreatePrRequest {
+                    title: "Add feature".to_string(),
+                    body: None,
+                    head_branch: "vk/feature".to_string(),
+                    base_branch: "main".to_string(),
+                    draft: None,
+                },
+            )
+            .await;
+
+        assert!(matches!(result, Err(VcsProviderError::AuthRequired(_))));
+        assert!(state.created.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,350 @@
+//! GitLab REST API v4 response models.
+//!
+//! These types map to the GitLab merge request and discussion JSON responses
+//! and provide conversion to the unified data models used by the application.
+
+use chrono::{DateTime, Utc};
+use db::models::merge::{CiStatus, MergeStatus, PullRequestInfo};
+use serde::{Deserialize, Serialize};
+
+use crate::services::{github::UnifiedPrComment, vcs_provider::CiCheck};
+
+/// GitLab user
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitLabUser {
+    pub id: i64,
+    pub username: String,
+    pub name: String,
+}
+
+/// GitLab merge request
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitLabMergeRequest {
+    pub id: i64,
+    /// Project-scoped number shown in the UI (`!12`)
+    pub iid: i64,
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub state: String, // "opened", "closed", "locked", "merged"
+    pub source_branch: String,
+    pub target_branch: String,
+    pub web_url: String,
+    #[serde(default)]
+    pub merged_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub merge_commit_sha: Option<String>,
+    #[serde(default)]
+    pub squash_commit_sha: Option<String>,
+    /// Head commit of the source branch
+    #[serde(default)]
+    pub sha: Option<String>,
+    /// Latest pipeline of the head commit, only returned for a single MR
+    #[serde(default)]
+    pub head_pipeline: Option<GitLabPipeline>,
+}
+
+impl GitLabMergeRequest {
+    /// Convert to the unified PullRequestInfo model
+    pub fn to_pull_request_info(&self) -> PullRequestInfo {
+        let status = match self.state.as_str() {
+            "opened" | "locked" => MergeStatus::Open,
+            "merged" => MergeStatus::Merged,
+            "closed" => MergeStatus::Closed,
+            _ => MergeStatus::Unknown,
+        };
+
+        PullRequestInfo {
+            number: self.iid,
+            url: self.web_url.clone(),
+            status,
+            merged_at: self.merged_at,
+            // Squash merges leave merge_commit_sha empty
+            merge_commit_sha: self
+                .merge_commit_sha
+                .clone()
+                .or_else(|| self.squash_commit_sha.clone()),
+        }
+    }
+}
+
+/// GitLab CI pipeline
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitLabPipeline {
+    pub id: i64,
+    pub sha: String,
+    pub status: String, // "running", "success", "failed", "canceled", "skipped", "manual", ...
+    pub web_url: String,
+}
+
+impl GitLabPipeline {
+    /// Convert to the provider-agnostic CI check. A pipeline reports its jobs as a whole.
+    pub fn to_ci_check(&self) -> CiCheck {
+        let status = match self.status.as_str() {
+            "success" | "canceled" | "skipped" | "manual" => CiStatus::Success,
+            "failed" => CiStatus::Failure,
+            _ => CiStatus::Pending,
+        };
+
+        CiCheck {
+            name: "pipeline".to_string(),
+            status,
+            url: Some(self.web_url.clone()),
+            description: Some(format!("Pipeline #{} {}", self.id, self.status)),
+        }
+    }
+}
+
+/// Request body for creating a merge request
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateMergeRequestRequest {
+    pub source_branch: String,
+    pub target_branch: String,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// GitLab discussion thread on a merge request
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitLabDiscussion {
+    pub id: String,
+    #[serde(default)]
+    pub individual_note: bool,
+    #[serde(default)]
+    pub notes: Vec<GitLabNote>,
+}
+
+/// GitLab note (a single comment within a discussion)
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitLabNote {
+    pub id: i64,
+    #[serde(rename = "type", default)]
+    pub note_type: Option<String>, // null, "DiscussionNote", "DiffNote"
+    pub body: String,
+    pub author: GitLabUser,
+    pub created_at: DateTime<Utc>,
+    /// Notes generated by GitLab itself ("added 1 commit", "approved this merge request")
+    #[serde(default)]
+    pub system: bool,
+    #[serde(default)]
+    pub position: Option<GitLabNotePosition>, // present for diff notes
+    /// Set on the notes of a resolvable discussion once it is resolved
+    #[serde(default)]
+    pub resolved: bool,
+}
+
+impl GitLabNote {
+    /// Convert to unified PR comment
+    pub fn to_unified_comment(&self, mr_url: &str) -> UnifiedPrComment {
+        let comment_url = format!("{}#note_{}", mr_url, self.id);
+
+        if let Some(position) = &self.position {
+            // Inline review comment
+            UnifiedPrComment::Review {
+                id: self.id,
+                author: self.author.username.clone(),
+                author_association: "CONTRIBUTOR".to_string(), // GitLab doesn't have this concept
+                body: self.body.clone(),
+                created_at: self.created_at,
+                url: comment_url,
+                path: position
+                    .new_path
+                    .clone()
+                    .or_else(|| position.old_path.clone())
+                    .unwrap_or_default(),
+                line: position.new_line.or(position.old_line),
+                diff_hunk: String::new(), // GitLab doesn't return the hunk with the note
+                resolved: self.resolved,
+            }
+        } else {
+            // General comment
+            UnifiedPrComment::General {
+                id: self.id.to_string(),
+                author: self.author.username.clone(),
+                author_association: "CONTRIBUTOR".to_string(),
+                body: self.body.clone(),
+                created_at: self.created_at,
+                url: comment_url,
+            }
+        }
+    }
+}
+
+/// Location of a diff note
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitLabNotePosition {
+    #[serde(default)]
+    pub old_path: Option<String>,
+    #[serde(default)]
+    pub new_path: Option<String>,
+    #[serde(default)]
+    pub old_line: Option<i64>,
+    #[serde(default)]
+    pub new_line: Option<i64>,
+}
+
+/// GitLab error response. `message` may be a string, a list or a map of field errors.
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitLabError {
+    #[serde(default)]
+    pub message: Option<serde_json::Value>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl std::fmt::Display for GitLabError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.message, &self.error) {
+            (Some(serde_json::Value::String(message)), _) => write!(f, "{}", message),
+            (Some(serde_json::Value::Array(messages)), _) => {
+                let messages: Vec<_> = messages
+                    .iter()
+                    .map(|m| {
+                        m.as_str()
+                            .map(str::to_string)
+                            .unwrap_or_else(|| m.to_string())
+                    })
+                    .collect();
+                write!(f, "{}", messages.join("; "))
+            }
+            (Some(message), _) => write!(f, "{}", message),
+            (None, Some(error)) => write!(f, "{}", error),
+            (None, None) => write!(f, "Unknown GitLab error"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mr_json(state: &str) -> String {
+        format!(
+            r#"{{
+                "id": 1001,
+                "iid": 12,
+                "title": "Add feature",
+                "description": null,
+                "state": "{state}",
+                "source_branch": "vk/feature",
+                "target_branch": "main",
+                "web_url": "https://gitlab.example.com/group/repo/-/merge_requests/12",
+                "merged_at": "2025-01-02T03:04:05.000Z",
+                "merge_commit_sha": null,
+                "squash_commit_sha": "abc123"
+            }}"#
+        )
+    }
+
+    #[test]
+    fn test_mr_state_to_merge_status() {
+        let status = |state: &str| {
+            let mr: GitLabMergeRequest = serde_json::from_str(&mr_json(state)).unwrap();
+            mr.to_pull_request_info().status
+        };
+        assert!(matches!(status("opened"), MergeStatus::Open));
+        assert!(matches!(status("locked"), MergeStatus::Open));
+        assert!(matches!(status("merged"), MergeStatus::Merged));
+        assert!(matches!(status("closed"), MergeStatus::Closed));
+        assert!(matches!(status("weird"), MergeStatus::Unknown));
+    }
+
+    #[test]
+    fn test_mr_info_uses_iid_and_squash_commit() {
+        let mr: GitLabMergeRequest = serde_json::from_str(&mr_json("merged")).unwrap();
+        let info = mr.to_pull_request_info();
+        assert_eq!(info.number, 12);
+        assert_eq!(
+            info.url,
+            "https://gitlab.example.com/group/repo/-/merge_requests/12"
+        );
+        assert!(info.merged_at.is_some());
+        assert_eq!(info.merge_commit_sha.as_deref(), Some("abc123"));
+    }
+
+    #[test]
+    fn test_diff_note_conversion() {
+        let json = r#"{
+            "id": 55,
+            "type": "DiffNote",
+            "body": "Use a constant here",
+            "author": {"id": 1, "username": "reviewer", "name": "Code Reviewer"},
+            "created_at": "2025-01-02T03:04:05.000Z",
+            "system": false,
+            "position": {"old_path": "src/lib.rs", "new_path": "src/lib.rs", "old_line": null, "new_line": 42},
+            "resolvable": true,
+            "resolved": true
+        }"#;
+        let note: GitLabNote = serde_json::from_str(json).unwrap();
+
+        match note.to_unified_comment("https://gitlab.com/g/r/-/merge_requests/1") {
+            UnifiedPrComment::Review {
+                id,
+                author,
+                path,
+                line,
+                url,
+                resolved,
+                ..
+            } => {
+                assert_eq!(id, 55);
+                assert_eq!(author, "reviewer");
+                assert_eq!(path, "src/lib.rs");
+                assert_eq!(line, Some(42));
+                assert_eq!(url, "https://gitlab.com/g/r/-/merge_requests/1#note_55");
+                assert!(resolved);
+            }
+            _ => panic!("Expected Review comment"),
+        }
+    }
+
+    #[test]
+    fn test_general_note_conversion() {
+        let json = r#"{
+            "id": 56,
+            "type": null,
+            "body": "Looks good",
+            "author": {"id": 2, "username": "lead", "name": "Team Lead"},
+            "created_at": "2025-01-02T03:04:05.000Z"
+        }"#;
+        let note: GitLabNote = serde_json::from_str(json).unwrap();
+        assert!(!note.system);
+        assert!(matches!(
+            note.to_unified_comment("https://gitlab.com/g/r/-/merge_requests/1"),
+            UnifiedPrComment::General { .. }
+        ));
+    }
+
+    #[test]
+    fn test_gitlab_error_display() {
+        let error: GitLabError = serde_json::from_str(
+            r#"{"message": ["Another open merge request already exists for this source branch: !3"]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Another open merge request already exists for this source branch: !3"
+        );
+
+        let error: GitLabError =
+            serde_json::from_str(r#"{"message": "404 Project Not Found"}"#).unwrap();
+        assert_eq!(error.to_string(), "404 Project Not Found");
+
+        let error: GitLabError = serde_json::from_str(r#"{"error": "invalid_token"}"#).unwrap();
+        assert_eq!(error.to_string(), "invalid_token");
+    }
+
+    #[test]
+    fn test_create_mr_request_serialization() {
+        let request = CreateMergeRequestRequest {
+            source_branch: "feature".to_string(),
+            target_branch: "main".to_string(),
+            title: "Draft: Test MR".to_string(),
+            description: None,
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(json.contains("\"source_branch\":\"feature\""));
+        assert!(!json.contains("description")); // skipped when None
+    }
+}
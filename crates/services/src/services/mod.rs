@@ -1,8 +1,13 @@
 pub mod analytics;
 pub mod approvals;
+pub mod attempt_group;
 pub mod auth;
 pub mod bitbucket;
+pub mod checkpoint;
+pub mod ci_fix;
+pub mod commit_message;
 pub mod config;
+pub mod conflict_resolution;
 pub mod container;
 pub mod diff_stream;
 pub mod events;
@@ -12,15 +17,25 @@ pub mod filesystem;
 pub mod filesystem_watcher;
 pub mod git;
 pub mod github;
+pub mod gitlab;
 pub mod image;
 pub mod notification;
 pub mod oauth_credentials;
+pub mod pipeline;
 pub mod pr_monitor;
+pub mod pr_review;
 pub mod project;
 pub mod queued_message;
 pub mod remote_client;
 pub mod repo;
+pub mod scheduler;
+pub mod setup_cache;
 pub mod share;
+pub mod task_archive;
+pub mod task_schedule;
+pub mod usage;
 pub mod vcs_provider;
+pub mod verification;
+pub mod webhook;
 pub mod workspace_manager;
 pub mod worktree_manager;
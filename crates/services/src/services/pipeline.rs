@@ -0,0 +1,118 @@
+//! Chaining the steps of a pipeline template into coding agent actions.
+
+use db::models::pipeline_template::{
+    PREVIOUS_SUMMARY_PLACEHOLDER, PipelineTemplate, TASK_PLACEHOLDER,
+};
+use executors::actions::{
+    ExecutorAction, ExecutorActionType, PipelineStepRef,
+    coding_agent_initial::CodingAgentInitialRequest,
+};
+
+/// Build the action chain for `template`, one coding agent request per step.
+/// `{task}` is filled in now; `{previous_summary}` is filled in when each step starts,
+/// see [`render_previous_summary`]. Returns `None` for a template without steps.
+pub fn build_pipeline_action(
+    template: &PipelineTemplate,
+    task_prompt: &str,
+    working_dir: Option<String>,
+) -> Option<ExecutorAction> {
+    let total = template.steps.len() as u32;
+    template
+        .steps
+        .iter()
+        .enumerate()
+        .rev()
+        .fold(None, |next_action, (index, step)| {
+            let mut prompt = step.prompt.replace(TASK_PLACEHOLDER, task_prompt);
+            if index == 0 {
+                prompt = prompt.replace(PREVIOUS_SUMMARY_PLACEHOLDER, "");
+            }
+            let action = ExecutorAction::new(
+                ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                    prompt,
+                    executor_profile_id: step.executor_profile_id.clone(),
+                    working_dir: working_dir.clone(),
+                }),
+                next_action.map(Box::new),
+            )
+            .with_pipeline_step(PipelineStepRef {
+                pipeline_name: template.name.clone(),
+                step_name: step.name.clone(),
+                index: index as u32,
+                total,
+            });
+            Some(action)
+        })
+}
+
+/// Fill in `{previous_summary}` of a pipeline step about to start with the summary of the
+/// step that just finished
+pub fn render_previous_summary(action: &ExecutorAction, summary: Option<&str>) -> ExecutorAction {
+    let mut action = action.clone();
+    if action.pipeline_step().is_some()
+        && let ExecutorActionType::CodingAgentInitialRequest(request) = &mut action.typ
+    {
+        request.prompt = request
+            .prompt
+            .replace(PREVIOUS_SUMMARY_PLACEHOLDER, summary.unwrap_or_default());
+    }
+    action
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use db::models::pipeline_template::PipelineStep;
+    use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn step(name: &str, variant: &str, prompt: &str) -> PipelineStep {
+        PipelineStep {
+            name: name.to_string(),
+            executor_profile_id: ExecutorProfileId {
+                executor: BaseCodingAgent::ClaudeCode,
+                variant: Some(variant.to_string()),
+            },
+            prompt: prompt.to_string(),
+        }
+    }
+
+    fn prompt_of(action: &ExecutorAction) -> &str {
+        match action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => &request.prompt,
+            other => panic!("unexpected action {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_build_pipeline_action_chains_steps_in_order() {
+        let template = PipelineTemplate {
+            id: Uuid::new_v4(),
+            name: "plan-implement".to_string(),
+            steps: sqlx::types::Json(vec![
+                step("plan", "PLAN", "Plan {task}{previous_summary}"),
+                step(
+                    "implement",
+                    "DEFAULT",
+                    "Implement {task}: {previous_summary}",
+                ),
+            ]),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        let first = build_pipeline_action(&template, "Fix login", None).unwrap();
+        assert_eq!(prompt_of(&first), "Plan Fix login");
+        assert_eq!(first.pipeline_step().unwrap().index, 0);
+        assert_eq!(first.pipeline_step().unwrap().total, 2);
+
+        let second = first.next_action().unwrap();
+        assert_eq!(second.pipeline_step().unwrap().step_name, "implement");
+        assert!(second.next_action().is_none());
+
+        let rendered = render_previous_summary(second, Some("Use OAuth"));
+        assert_eq!(prompt_of(&rendered), "Implement Fix login: Use OAuth");
+    }
+}
@@ -1,23 +1,38 @@
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
 use db::{
     DBService,
     models::{
-        merge::{Merge, MergeStatus, PrMerge},
+        execution_process::ExecutionProcess,
+        merge::{CiStatus, Merge, MergeStatus, PrMerge},
+        pr_review_follow_up::PrReviewFollowUp,
         task::{Task, TaskStatus},
+        webhook::WebhookEvent,
         workspace::{Workspace, WorkspaceError},
     },
 };
 use serde_json::json;
 use sqlx::error::Error as SqlxError;
 use thiserror::Error;
-use tokio::time::interval;
-use tracing::{debug, error, info};
+use tokio::{
+    sync::{RwLock, mpsc::UnboundedSender},
+    time::interval,
+};
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
 
 use crate::services::{
     analytics::AnalyticsContext,
-    github::{GitHubService, GitHubServiceError},
+    ci_fix::PendingCiFix,
+    config::{CiConfig, Config},
+    github::{GitHubService, GitHubServiceError, UnifiedPrComment},
+    gitlab::GitLabService,
+    pr_review::{PendingReviewComments, needs_addressing},
     share::SharePublisher,
+    vcs_provider::{
+        VcsProvider, VcsProviderError, VcsProviderRegistry, VcsProviderType, VcsRepoInfo,
+    },
+    webhook::WebhookService,
 };
 
 #[derive(Debug, Error)]
@@ -25,30 +40,52 @@ enum PrMonitorError {
     #[error(transparent)]
     GitHubServiceError(#[from] GitHubServiceError),
     #[error(transparent)]
+    VcsProvider(#[from] VcsProviderError),
+    #[error(transparent)]
     WorkspaceError(#[from] WorkspaceError),
     #[error(transparent)]
     Sqlx(#[from] SqlxError),
 }
 
-/// Service to monitor GitHub PRs and update task status when they are merged
+/// Service to monitor GitHub PRs and GitLab MRs, update task status when they are merged and
+/// track the CI state of their head commits
 pub struct PrMonitorService {
     db: DBService,
     poll_interval: Duration,
     analytics: Option<AnalyticsContext>,
     publisher: Option<SharePublisher>,
+    webhooks: WebhookService,
+    /// Receives the id of every task moved to Done so its dependents can be started
+    task_done_tx: UnboundedSender<Uuid>,
+    config: Arc<RwLock<Config>>,
+    /// Receives new review comments on open PRs when they are to be addressed automatically
+    review_comments_tx: UnboundedSender<PendingReviewComments>,
+    /// Receives the failing checks of open PRs when they are to be fixed automatically
+    ci_fix_tx: UnboundedSender<PendingCiFix>,
 }
 
 impl PrMonitorService {
+    #[allow(clippy::too_many_arguments)]
     pub async fn spawn(
         db: DBService,
         analytics: Option<AnalyticsContext>,
         publisher: Option<SharePublisher>,
+        webhooks: WebhookService,
+        task_done_tx: UnboundedSender<Uuid>,
+        config: Arc<RwLock<Config>>,
+        review_comments_tx: UnboundedSender<PendingReviewComments>,
+        ci_fix_tx: UnboundedSender<PendingCiFix>,
     ) -> tokio::task::JoinHandle<()> {
         let service = Self {
             db,
             poll_interval: Duration::from_secs(60), // Check every minute
             analytics,
             publisher,
+            webhooks,
+            task_done_tx,
+            config,
+            review_comments_tx,
+            ci_fix_tx,
         };
         tokio::spawn(async move {
             service.start().await;
@@ -95,26 +132,59 @@ impl PrMonitorService {
 
     /// Check the status of a specific PR
     async fn check_pr_status(&self, pr_merge: &PrMerge) -> Result<(), PrMonitorError> {
-        // GitHubService now uses gh CLI, no token needed
-        let github_service = GitHubService::new()?;
-
-        let pr_status = github_service
-            .update_pr_status(&pr_merge.pr_info.url)
-            .await?;
+        let pr_status = match VcsRepoInfo::from_remote_url(&pr_merge.pr_info.url) {
+            Ok(repo_info) if repo_info.provider_type == VcsProviderType::GitLab => {
+                let gitlab_service = GitLabService::new()?;
+                gitlab_service.load_credentials().await?;
+                gitlab_service
+                    .get_pr_status(&repo_info, pr_merge.pr_info.number)
+                    .await?
+            }
+            _ => {
+                // GitHubService now uses gh CLI, no token needed
+                let github_service = GitHubService::new()?;
+                github_service
+                    .update_pr_status(&pr_merge.pr_info.url)
+                    .await?
+            }
+        };
 
         debug!(
             "PR #{} status: {:?} (was open)",
             pr_merge.pr_info.number, pr_status.status
         );
 
+        if matches!(&pr_status.status, MergeStatus::Open) {
+            let (pr_review, ci) = {
+                let config = self.config.read().await;
+                (config.pr_review.clone(), config.ci.clone())
+            };
+            // A CI failure must not keep review comments from being checked, so it is only logged
+            let fix_dispatched = match self.check_ci(pr_merge, &ci).await {
+                Ok(fix_dispatched) => fix_dispatched,
+                Err(e) => {
+                    warn!(
+                        "Error checking CI of PR #{} for workspace {}: {}",
+                        pr_merge.pr_info.number, pr_merge.workspace_id, e
+                    );
+                    false
+                }
+            };
+            // The fix run keeps the workspace busy, the comments are picked up after it
+            if pr_review.auto_address_comments && !fix_dispatched {
+                self.check_review_comments(pr_merge, pr_review.reply_to_comments)
+                    .await?;
+            }
+        }
+
         // Update the PR status in the database
         if !matches!(&pr_status.status, MergeStatus::Open) {
-            // Update merge status with the latest information from GitHub
+            // Update merge status with the latest information from the provider
             Merge::update_status(
                 &self.db.pool,
                 pr_merge.id,
                 pr_status.status.clone(),
-                pr_status.merge_commit_sha,
+                pr_status.merge_commit_sha.clone(),
             )
             .await?;
 
@@ -128,6 +198,18 @@ impl PrMonitorService {
                     pr_merge.pr_info.number, workspace.task_id
                 );
                 Task::update_status(&self.db.pool, workspace.task_id, TaskStatus::Done).await?;
+                let _ = self.task_done_tx.send(workspace.task_id);
+                self.webhooks.emit(
+                    WebhookEvent::PrMerged,
+                    json!({
+                        "task_id": workspace.task_id,
+                        "workspace_id": workspace.id,
+                        "repo_id": pr_merge.repo_id,
+                        "pr_number": pr_merge.pr_info.number,
+                        "pr_url": pr_merge.pr_info.url,
+                        "merge_commit_sha": pr_status.merge_commit_sha,
+                    }),
+                );
 
                 // Track analytics event
                 if let Some(analytics) = &self.analytics
@@ -158,4 +240,148 @@ impl PrMonitorService {
 
         Ok(())
     }
+
+    /// Whether a PR follow-up or any other non dev server process is running in the workspace
+    async fn workspace_is_busy(&self, workspace_id: Uuid) -> Result<bool, PrMonitorError> {
+        let pool = &self.db.pool;
+        Ok(
+            PrReviewFollowUp::has_pending_for_workspace(pool, workspace_id).await?
+                || ExecutionProcess::has_running_non_dev_server_processes_for_workspace(
+                    pool,
+                    workspace_id,
+                )
+                .await?,
+        )
+    }
+
+    /// Record the CI state of the head commit of an open PR when it changed, and hand over
+    /// its failing checks once all checks finished, if they are to be fixed automatically.
+    /// Each head commit gets at most one fix run. Returns whether a fix was handed over.
+    async fn check_ci(&self, pr_merge: &PrMerge, ci: &CiConfig) -> Result<bool, PrMonitorError> {
+        let repo_info = VcsRepoInfo::from_remote_url(&pr_merge.pr_info.url)?;
+        let registry = VcsProviderRegistry::new_with_loaded_credentials().await?;
+        let provider = registry
+            .get_provider(repo_info.provider_type)
+            .ok_or_else(|| {
+                VcsProviderError::UnsupportedProvider(format!(
+                    "No provider available for {}",
+                    pr_merge.pr_info.url
+                ))
+            })?;
+        let report = provider
+            .get_pr_ci_status(&repo_info, pr_merge.pr_info.number)
+            .await?;
+        let Some(status) = report.status() else {
+            return Ok(false);
+        };
+
+        let failed_checks: Vec<String> = report.failed_checks().map(|c| c.name.clone()).collect();
+        let previous = pr_merge.ci.as_ref();
+        if previous.is_none_or(|state| {
+            state.status != status
+                || state.head_sha != report.head_sha
+                || state.failed_checks != failed_checks
+        }) {
+            debug!(
+                "PR #{} CI status: {:?} at {}",
+                pr_merge.pr_info.number, status, report.head_sha
+            );
+            Merge::update_ci_state(
+                &self.db.pool,
+                pr_merge.id,
+                status,
+                &report.head_sha,
+                &failed_checks,
+            )
+            .await?;
+        }
+
+        let finished = report.checks.iter().all(|c| c.status != CiStatus::Pending);
+        if !ci.auto_fix_failures || status != CiStatus::Failure || !finished {
+            return Ok(false);
+        }
+        let fix_attempts = previous.map_or(0, |state| state.fix_attempts);
+        if previous.and_then(|state| state.fix_head_sha.as_deref())
+            == Some(report.head_sha.as_str())
+            || fix_attempts >= i64::from(ci.max_auto_fix_attempts)
+            || self.workspace_is_busy(pr_merge.workspace_id).await?
+        {
+            return Ok(false);
+        }
+
+        info!(
+            "Handing over {} failing checks of PR #{} for workspace {}",
+            failed_checks.len(),
+            pr_merge.pr_info.number,
+            pr_merge.workspace_id
+        );
+        // Counted before the run starts, so a run that fails to start is not retried forever
+        Merge::record_ci_fix(&self.db.pool, pr_merge.id, &report.head_sha).await?;
+        let _ = self.ci_fix_tx.send(PendingCiFix {
+            workspace_id: pr_merge.workspace_id,
+            repo_id: pr_merge.repo_id,
+            pr_number: pr_merge.pr_info.number,
+            pr_url: pr_merge.pr_info.url.clone(),
+            head_sha: report.head_sha.clone(),
+            failed_checks: report.failed_checks().cloned().collect(),
+        });
+        Ok(true)
+    }
+
+    /// Hand over the inline review comments of an open PR that no follow-up was dispatched for
+    /// yet, unless the workspace is busy. General comments are left to be addressed manually,
+    /// as they are often not change requests. Comments of failed follow-ups are not retried.
+    async fn check_review_comments(
+        &self,
+        pr_merge: &PrMerge,
+        reply_to_comments: bool,
+    ) -> Result<(), PrMonitorError> {
+        let pool = &self.db.pool;
+        if self.workspace_is_busy(pr_merge.workspace_id).await? {
+            return Ok(());
+        }
+
+        let repo_info = VcsRepoInfo::from_remote_url(&pr_merge.pr_info.url)?;
+        let registry = VcsProviderRegistry::new_with_loaded_credentials().await?;
+        let provider = registry
+            .get_provider(repo_info.provider_type)
+            .ok_or_else(|| {
+                VcsProviderError::UnsupportedProvider(format!(
+                    "No provider available for {}",
+                    pr_merge.pr_info.url
+                ))
+            })?;
+        let comments = provider
+            .get_pr_comments(&repo_info, pr_merge.pr_info.number)
+            .await?;
+
+        let attempted =
+            PrReviewFollowUp::attempted_comment_keys(pool, pr_merge.workspace_id, pr_merge.repo_id)
+                .await?;
+        let comments: Vec<_> = comments
+            .into_iter()
+            .filter(|comment| matches!(comment, UnifiedPrComment::Review { .. }))
+            .filter(needs_addressing)
+            .filter(|comment| !attempted.contains(&comment.key()))
+            .collect();
+        if comments.is_empty() {
+            return Ok(());
+        }
+
+        info!(
+            "Found {} new review comments on PR #{} for workspace {}",
+            comments.len(),
+            pr_merge.pr_info.number,
+            pr_merge.workspace_id
+        );
+        let _ = self.review_comments_tx.send(PendingReviewComments {
+            workspace_id: pr_merge.workspace_id,
+            repo_id: pr_merge.repo_id,
+            pr_number: pr_merge.pr_info.number,
+            pr_url: pr_merge.pr_info.url.clone(),
+            comments,
+            reply_to_comments,
+        });
+        Ok(())
+    }
 }
@@ -0,0 +1,173 @@
+//! Prompts and replies for coding agent runs dispatched to address pull request review comments.
+
+use uuid::Uuid;
+
+use crate::services::github::UnifiedPrComment;
+
+/// Ends every reply posted for an addressed comment, so replies are not picked up as new
+/// review comments themselves
+pub const REPLY_FOOTER: &str = "_Addressed by Vibe Kanban_";
+
+/// New review comments the PR monitor found on the pull request of a workspace repo.
+/// Addressing them needs the container, so they are handed over to the monitor's owner.
+#[derive(Debug, Clone)]
+pub struct PendingReviewComments {
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub pr_number: i64,
+    pub pr_url: String,
+    pub comments: Vec<UnifiedPrComment>,
+    pub reply_to_comments: bool,
+}
+
+/// Whether a comment is a reply posted by Vibe Kanban
+pub fn is_review_reply(body: &str) -> bool {
+    body.trim_end().ends_with(REPLY_FOOTER)
+}
+
+/// Whether a comment was left by a bot account rather than a reviewer. GitHub app logins end
+/// in `[bot]`; GitLab access tokens act as `project_<id>_bot_<suffix>` or
+/// `group_<id>_bot_<suffix>` users.
+pub fn is_bot_author(author: &str) -> bool {
+    if author.ends_with("[bot]") {
+        return true;
+    }
+    let Some((scope, rest)) = author.split_once('_') else {
+        return false;
+    };
+    matches!(scope, "project" | "group")
+        && rest
+            .split_once("_bot")
+            .is_some_and(|(id, _)| !id.is_empty() && id.bytes().all(|byte| byte.is_ascii_digit()))
+}
+
+/// Whether a comment still asks a coding agent for changes: it is neither a reply of ours nor
+/// left by a bot, and its review thread is not resolved
+pub fn needs_addressing(comment: &UnifiedPrComment) -> bool {
+    let resolved = matches!(comment, UnifiedPrComment::Review { resolved: true, .. });
+    !resolved && !is_review_reply(comment.body()) && !is_bot_author(comment.author())
+}
+
+/// Instructions for a coding agent to address review comments on one pull request.
+/// The agent only edits files; committing and pushing is left to Vibe Kanban.
+pub fn build_review_follow_up_prompt(
+    repo_name: &str,
+    pr_number: i64,
+    pr_url: &str,
+    comments: &[UnifiedPrComment],
+) -> String {
+    let mut prompt = format!(
+        "Reviewers left comments on pull request #{pr_number} ({pr_url}) of the `{repo_name}` \
+         repository. Address each of them.\n"
+    );
+
+    for (index, comment) in comments.iter().enumerate() {
+        prompt.push_str(&format!("\n## Comment {}\n\n", index + 1));
+        match comment {
+            UnifiedPrComment::General { author, body, .. } => {
+                prompt.push_str(&format!("General comment by {author}:\n\n{body}\n"));
+            }
+            UnifiedPrComment::Review {
+                author,
+                body,
+                path,
+                line,
+                diff_hunk,
+                ..
+            } => {
+                let location = match line {
+                    Some(line) => format!("{path}:{line}"),
+                    None => path.clone(),
+                };
+                prompt.push_str(&format!("Review comment by {author} on `{location}`:\n\n"));
+                if !diff_hunk.is_empty() {
+                    prompt.push_str(&format!("```diff\n{}\n```\n\n", diff_hunk.trim_end()));
+                }
+                prompt.push_str(&format!("{body}\n"));
+            }
+        }
+    }
+
+    prompt.push_str(
+        "\nChange the code so that every comment is addressed. When a comment asks a question \
+         or you disagree with it, say so in your final message instead of changing the code. \
+         Do not commit or push: your changes are committed and pushed to the pull request \
+         automatically once you are done.",
+    );
+
+    prompt
+}
+
+/// Reply posted to a comment once the commit addressing it was pushed
+pub fn build_comment_reply(commit_sha: &str) -> String {
+    format!("Addressed in {commit_sha}.\n\n{REPLY_FOOTER}")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    #[test]
+    fn test_prompt_includes_location_and_diff_hunk() {
+        let comments = vec![
+            UnifiedPrComment::Review {
+                id: 7,
+                author: "alice".to_string(),
+                author_association: "MEMBER".to_string(),
+                body: "Handle the error here".to_string(),
+                created_at: Utc::now(),
+                url: "https://github.com/o/r/pull/3#discussion_r7".to_string(),
+                path: "src/lib.rs".to_string(),
+                line: Some(42),
+                diff_hunk: "@@ -40,3 +40,3 @@\n-let x = f();\n+let x = f().unwrap();".to_string(),
+                resolved: false,
+            },
+            UnifiedPrComment::General {
+                id: "9".to_string(),
+                author: "bob".to_string(),
+                author_association: "NONE".to_string(),
+                body: "Please add a test".to_string(),
+                created_at: Utc::now(),
+                url: "https://github.com/o/r/pull/3#issuecomment-9".to_string(),
+            },
+        ];
+
+        let prompt =
+            build_review_follow_up_prompt("backend", 3, "https://github.com/o/r/pull/3", &comments);
+
+        assert!(prompt.contains("pull request #3 (https://github.com/o/r/pull/3)"));
+        assert!(prompt.contains("Review comment by alice on `src/lib.rs:42`"));
+        assert!(prompt.contains("```diff\n@@ -40,3 +40,3 @@\n"));
+        assert!(prompt.contains("General comment by bob:\n\nPlease add a test\n"));
+        assert!(prompt.contains("Do not commit or push"));
+    }
+
+    #[test]
+    fn test_replies_are_recognized() {
+        assert!(is_review_reply(&build_comment_reply("abc1234")));
+        assert!(!is_review_reply("Addressed in abc1234."));
+    }
+    #[test]
+    fn test_resolved_and_bot_comments_need_no_addressing() {
+        let review = |author: &str, resolved: bool| UnifiedPrComment::Review {
+            id: 1,
+            author: author.to_string(),
+            author_association: "MEMBER".to_string(),
+            body: "Rename this".to_string(),
+            created_at: Utc::now(),
+            url: "https://github.com/o/r/pull/3#discussion_r1".to_string(),
+            path: "src/lib.rs".to_string(),
+            line: Some(1),
+            diff_hunk: String::new(),
+            resolved,
+        };
+
+        assert!(needs_addressing(&review("alice", false)));
+        assert!(!needs_addressing(&review("alice", true)));
+        assert!(!needs_addressing(&review("github-actions[bot]", false)));
+        assert!(!needs_addressing(&review("project_42_bot_3f1c", false)));
+        assert!(needs_addressing(&review("project_lead", false)));
+    }
+}
@@ -132,6 +132,12 @@ impl ProjectService {
                     dev_script: None,
                     dev_script_working_dir: None,
                     default_agent_working_dir: Some(repo.name),
+                    sandbox_config: None,
+                    merge_mode: None,
+                    conventional_commits: None,
+                    warm_workspace_count: None,
+                    commit_message_executor_profile_id: None,
+                    clear_commit_message_executor_profile: false,
                 },
             )
             .await?;
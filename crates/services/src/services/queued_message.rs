@@ -1,24 +1,11 @@
-use std::sync::Arc;
-
-use chrono::{DateTime, Utc};
-use dashmap::DashMap;
-use db::models::scratch::DraftFollowUpData;
+use db::{
+    DBService,
+    models::{queued_message::QueuedMessage, scratch::DraftFollowUpData},
+};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 use uuid::Uuid;
 
-/// Represents a queued follow-up message for a session
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
-#[ts(export)]
-pub struct QueuedMessage {
-    /// The session this message is queued for
-    pub session_id: Uuid,
-    /// The follow-up data (message + variant)
-    pub data: DraftFollowUpData,
-    /// Timestamp when the message was queued
-    pub queued_at: DateTime<Utc>,
-}
-
 /// Status of the queue for a session (for frontend display)
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(tag = "status", rename_all = "snake_case")]
@@ -26,67 +13,125 @@ pub struct QueuedMessage {
 pub enum QueueStatus {
     /// No message queued
     Empty,
-    /// Message is queued and waiting for execution to complete
-    Queued { message: QueuedMessage },
+    /// Messages are queued and sent in order, one each time an execution completes
+    Queued { messages: Vec<QueuedMessage> },
 }
 
-/// In-memory service for managing queued follow-up messages.
-/// One queued message per session.
+/// Service for managing queued follow-up messages.
+/// Each session has an ordered queue that is persisted, so it survives restarts.
 #[derive(Clone)]
 pub struct QueuedMessageService {
-    queue: Arc<DashMap<Uuid, QueuedMessage>>,
+    db: DBService,
 }
 
 impl QueuedMessageService {
-    pub fn new() -> Self {
-        Self {
-            queue: Arc::new(DashMap::new()),
-        }
+    pub fn new(db: DBService) -> Self {
+        Self { db }
+    }
+
+    /// Append a message to the end of the session's queue
+    pub async fn queue_message(
+        &self,
+        session_id: Uuid,
+        data: DraftFollowUpData,
+    ) -> Result<QueuedMessage, sqlx::Error> {
+        QueuedMessage::create(&self.db.pool, Uuid::new_v4(), session_id, &data).await
+    }
+
+    /// Replace the content of a queued message, `None` if it is not queued for the session
+    pub async fn update_queued(
+        &self,
+        session_id: Uuid,
+        message_id: Uuid,
+        data: DraftFollowUpData,
+    ) -> Result<Option<QueuedMessage>, sqlx::Error> {
+        QueuedMessage::update(&self.db.pool, session_id, message_id, &data).await
     }
 
-    /// Queue a message for a session. Replaces any existing queued message.
-    pub fn queue_message(&self, session_id: Uuid, data: DraftFollowUpData) -> QueuedMessage {
-        let queued = QueuedMessage {
-            session_id,
-            data,
-            queued_at: Utc::now(),
-        };
-        self.queue.insert(session_id, queued.clone());
-        queued
+    /// Remove one queued message, returning whether it was queued for the session
+    pub async fn remove_queued(
+        &self,
+        session_id: Uuid,
+        message_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        Ok(QueuedMessage::delete(&self.db.pool, session_id, message_id).await? > 0)
     }
 
-    /// Cancel/remove a queued message for a session
-    pub fn cancel_queued(&self, session_id: Uuid) -> Option<QueuedMessage> {
-        self.queue.remove(&session_id).map(|(_, v)| v)
+    /// Put the session's queue into the order of `message_ids`.
+    /// Returns false without changing anything unless they list exactly the queued messages.
+    pub async fn reorder_queued(
+        &self,
+        session_id: Uuid,
+        message_ids: &[Uuid],
+    ) -> Result<bool, sqlx::Error> {
+        let queued_ids: Vec<Uuid> = self
+            .get_queued(session_id)
+            .await?
+            .iter()
+            .map(|message| message.id)
+            .collect();
+        if !is_permutation(&queued_ids, message_ids) {
+            return Ok(false);
+        }
+        QueuedMessage::reorder(&self.db.pool, session_id, message_ids).await?;
+        Ok(true)
     }
 
-    /// Get the queued message for a session (if any)
-    pub fn get_queued(&self, session_id: Uuid) -> Option<QueuedMessage> {
-        self.queue.get(&session_id).map(|r| r.clone())
+    /// Cancel/remove every queued message for a session
+    pub async fn cancel_queued(&self, session_id: Uuid) -> Result<u64, sqlx::Error> {
+        QueuedMessage::delete_by_session_id(&self.db.pool, session_id).await
     }
 
-    /// Take (remove and return) the queued message for a session.
-    /// Used by finalization flow to consume the queued message.
-    pub fn take_queued(&self, session_id: Uuid) -> Option<QueuedMessage> {
-        self.queue.remove(&session_id).map(|(_, v)| v)
+    /// Get the queued messages for a session in the order they will be sent
+    pub async fn get_queued(&self, session_id: Uuid) -> Result<Vec<QueuedMessage>, sqlx::Error> {
+        QueuedMessage::find_by_session_id(&self.db.pool, session_id).await
+    }
+
+    /// Take (remove and return) the next queued message for a session.
+    /// Used by finalization flow to consume the queue one message at a time.
+    pub async fn take_queued(
+        &self,
+        session_id: Uuid,
+    ) -> Result<Option<QueuedMessage>, sqlx::Error> {
+        QueuedMessage::take_next(&self.db.pool, session_id).await
     }
 
     /// Check if a session has a queued message
-    pub fn has_queued(&self, session_id: Uuid) -> bool {
-        self.queue.contains_key(&session_id)
+    pub async fn has_queued(&self, session_id: Uuid) -> Result<bool, sqlx::Error> {
+        QueuedMessage::exists_for_session(&self.db.pool, session_id).await
     }
 
     /// Get queue status for frontend display
-    pub fn get_status(&self, session_id: Uuid) -> QueueStatus {
-        match self.get_queued(session_id) {
-            Some(msg) => QueueStatus::Queued { message: msg },
-            None => QueueStatus::Empty,
-        }
+    pub async fn get_status(&self, session_id: Uuid) -> Result<QueueStatus, sqlx::Error> {
+        let messages = self.get_queued(session_id).await?;
+        Ok(if messages.is_empty() {
+            QueueStatus::Empty
+        } else {
+            QueueStatus::Queued { messages }
+        })
     }
 }
 
-impl Default for QueuedMessageService {
-    fn default() -> Self {
-        Self::new()
+fn is_permutation(queued_ids: &[Uuid], message_ids: &[Uuid]) -> bool {
+    let mut queued_ids = queued_ids.to_vec();
+    let mut message_ids = message_ids.to_vec();
+    queued_ids.sort();
+    message_ids.sort();
+    queued_ids == message_ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reorder_must_list_every_queued_message_once() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let queued = [a, b, c];
+
+        assert!(is_permutation(&queued, &[c, a, b]));
+        assert!(!is_permutation(&queued, &[c, a]));
+        assert!(!is_permutation(&queued, &[c, a, a]));
+        assert!(!is_permutation(&queued, &[c, a, b, Uuid::new_v4()]));
     }
 }
@@ -0,0 +1,456 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+use executors::executors::BaseCodingAgent;
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use ts_rs::TS;
+use utils::msg_store::MsgStore;
+use uuid::Uuid;
+
+use crate::services::{
+    config::{ConcurrencyConfig, Config},
+    events::queue_position_patch,
+};
+
+/// Position of a queued execution process, streamed at `/queue_positions/{id}`
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct QueuePosition {
+    pub execution_process_id: Uuid,
+    pub session_id: Uuid,
+    /// 1-based position in the queue
+    pub position: u32,
+}
+
+/// Outcome of asking the scheduler for a slot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Admission {
+    /// A slot is free and the execution may start now
+    Start,
+    /// The execution has to wait until earlier ones release their slots
+    Queued { position: u32 },
+}
+
+#[derive(Debug, Clone)]
+struct QueuedExecution {
+    execution_process_id: Uuid,
+    session_id: Uuid,
+    executor: BaseCodingAgent,
+}
+
+#[derive(Debug, Default)]
+struct SchedulerState {
+    running: HashMap<Uuid, BaseCodingAgent>,
+    queue: VecDeque<QueuedExecution>,
+}
+
+impl SchedulerState {
+    fn has_capacity(&self, executor: BaseCodingAgent, limits: &ConcurrencyConfig) -> bool {
+        if let Some(max) = limits.max_concurrent_agents
+            && self.running.len() >= max.max(1) as usize
+        {
+            return false;
+        }
+        match limits.per_executor.get(&executor) {
+            Some(max) => {
+                self.running.values().filter(|e| **e == executor).count() < (*max).max(1) as usize
+            }
+            None => true,
+        }
+    }
+
+    fn enqueue(&mut self, entry: QueuedExecution, limits: &ConcurrencyConfig) -> Admission {
+        // Entries still waiting are blocked on the global cap or their own executor's cap,
+        // so a newcomer with free capacity cannot overtake an entry that could run instead
+        if self.has_capacity(entry.executor, limits) {
+            self.running
+                .insert(entry.execution_process_id, entry.executor);
+            Admission::Start
+        } else {
+            self.queue.push_back(entry);
+            Admission::Queued {
+                position: self.queue.len() as u32,
+            }
+        }
+    }
+
+    /// Drop `id` from the running set or the queue, then admit waiting entries
+    fn release(&mut self, id: Uuid, limits: &ConcurrencyConfig) -> Vec<QueuedExecution> {
+        self.running.remove(&id);
+        self.queue.retain(|q| q.execution_process_id != id);
+        self.admit(limits)
+    }
+
+    /// Give free slots to waiting entries in FIFO order. An entry blocked by its
+    /// per-executor cap does not hold back other executors.
+    fn admit(&mut self, limits: &ConcurrencyConfig) -> Vec<QueuedExecution> {
+        let mut admitted = Vec::new();
+        let mut i = 0;
+        while i < self.queue.len() {
+            if self.has_capacity(self.queue[i].executor, limits) {
+                let entry = self.queue.remove(i).expect("index is in bounds");
+                self.running
+                    .insert(entry.execution_process_id, entry.executor);
+                admitted.push(entry);
+            } else {
+                i += 1;
+            }
+        }
+        admitted
+    }
+
+    /// Take a slot only when one is free and nothing is waiting for it
+    fn try_start(
+        &mut self,
+        id: Uuid,
+        executor: BaseCodingAgent,
+        limits: &ConcurrencyConfig,
+    ) -> bool {
+        let start = self.queue.is_empty() && self.has_capacity(executor, limits);
+        if start {
+            self.running.insert(id, executor);
+        }
+        start
+    }
+
+    /// Take a slot for an execution that is already running, even over the limits
+    fn resume(&mut self, id: Uuid, executor: BaseCodingAgent) {
+        self.running.insert(id, executor);
+    }
+
+    fn is_queued(&self, id: Uuid) -> bool {
+        self.queue.iter().any(|q| q.execution_process_id == id)
+    }
+
+    fn positions(&self) -> Vec<QueuePosition> {
+        self.queue
+            .iter()
+            .enumerate()
+            .map(|(i, q)| QueuePosition {
+                execution_process_id: q.execution_process_id,
+                session_id: q.session_id,
+                position: i as u32 + 1,
+            })
+            .collect()
+    }
+}
+
+/// Limits how many coding agents run at once, globally and per executor.
+/// Only coding agent actions are scheduled, scripts and dev servers always start
+/// immediately. Executions over the limit wait in a FIFO queue and are handed back
+/// to the container by [`ExecutionScheduler::release`] as slots free up.
+#[derive(Clone)]
+pub struct ExecutionScheduler {
+    state: Arc<Mutex<SchedulerState>>,
+    config: Arc<RwLock<Config>>,
+    msg_store: Arc<MsgStore>,
+}
+
+impl ExecutionScheduler {
+    /// `msg_store` is the events store, queue positions are published there
+    pub fn new(config: Arc<RwLock<Config>>, msg_store: Arc<MsgStore>) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(SchedulerState::default())),
+            config,
+            msg_store,
+        }
+    }
+
+    /// Claim a slot for an execution process, or queue it behind earlier ones
+    pub async fn enqueue(
+        &self,
+        execution_process_id: Uuid,
+        session_id: Uuid,
+        executor: BaseCodingAgent,
+    ) -> Admission {
+        let limits = self.config.read().await.concurrency.clone();
+        let (admission, positions) = {
+            let mut state = self.state.lock().unwrap();
+            let admission = state.enqueue(
+                QueuedExecution {
+                    execution_process_id,
+                    session_id,
+                    executor,
+                },
+                &limits,
+            );
+            (admission, state.positions())
+        };
+        if matches!(admission, Admission::Queued { .. }) {
+            self.publish(&[], &positions);
+        }
+        admission
+    }
+
+    /// Free the slot (or queue entry) held by an execution process. Returns the
+    /// queued execution processes that now hold a slot and should be started.
+    pub async fn release(&self, execution_process_id: Uuid) -> Vec<Uuid> {
+        let limits = self.config.read().await.concurrency.clone();
+        let (mut removed, admitted, positions) = {
+            let mut state = self.state.lock().unwrap();
+            let was_queued = state.is_queued(execution_process_id);
+            let admitted: Vec<Uuid> = state
+                .release(execution_process_id, &limits)
+                .into_iter()
+                .map(|e| e.execution_process_id)
+                .collect();
+            let removed = if was_queued {
+                vec![execution_process_id]
+            } else {
+                Vec::new()
+            };
+            (removed, admitted, state.positions())
+        };
+        removed.extend(admitted.iter().copied());
+        if !removed.is_empty() {
+            self.publish(&removed, &positions);
+        }
+        admitted
+    }
+
+    /// Give free slots to queued executions, e.g. after the limits were raised. Returns the
+    /// execution processes that now hold a slot and should be started.
+    pub async fn admit_waiting(&self) -> Vec<Uuid> {
+        let limits = self.config.read().await.concurrency.clone();
+        let (admitted, positions) = {
+            let mut state = self.state.lock().unwrap();
+            let admitted: Vec<Uuid> = state
+                .admit(&limits)
+                .into_iter()
+                .map(|e| e.execution_process_id)
+                .collect();
+            (admitted, state.positions())
+        };
+        if !admitted.is_empty() {
+            self.publish(&admitted, &positions);
+        }
+        admitted
+    }
+
+    /// Claim a slot for background agent work that is skipped rather than queued, e.g.
+    /// commit message generation. Never overtakes queued executions. The slot is freed
+    /// by [`ExecutionScheduler::release`].
+    pub async fn try_start(&self, id: Uuid, executor: BaseCodingAgent) -> bool {
+        let limits = self.config.read().await.concurrency.clone();
+        self.state.lock().unwrap().try_start(id, executor, &limits)
+    }
+
+    /// Count an execution process that is already running against the limits, e.g. one
+    /// re-attached to after a restart. Its slot is freed by [`ExecutionScheduler::release`].
+    pub fn resume(&self, execution_process_id: Uuid, executor: BaseCodingAgent) {
+        self.state
+            .lock()
+            .unwrap()
+            .resume(execution_process_id, executor);
+    }
+
+    /// Current queue, in the order executions will be started
+    pub fn queue_positions(&self) -> Vec<QueuePosition> {
+        self.state.lock().unwrap().positions()
+    }
+
+    fn publish(&self, removed: &[Uuid], positions: &[QueuePosition]) {
+        for id in removed {
+            self.msg_store.push_patch(queue_position_patch::remove(*id));
+        }
+        for position in positions {
+            self.msg_store
+                .push_patch(queue_position_patch::add(position));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(global: Option<u32>, per_executor: &[(BaseCodingAgent, u32)]) -> ConcurrencyConfig {
+        ConcurrencyConfig {
+            max_concurrent_agents: global,
+            per_executor: per_executor.iter().copied().collect(),
+        }
+    }
+
+    fn entry(executor: BaseCodingAgent) -> QueuedExecution {
+        QueuedExecution {
+            execution_process_id: Uuid::new_v4(),
+            session_id: Uuid::new_v4(),
+            executor,
+        }
+    }
+
+    #[test]
+    fn test_global_limit_queues_in_fifo_order() {
+        let limits = limits(Some(2), &[]);
+        let mut state = SchedulerState::default();
+        let entries: Vec<_> = (0..4).map(|_| entry(BaseCodingAgent::ClaudeCode)).collect();
+
+        let admissions: Vec<_> = entries
+            .iter()
+            .map(|e| state.enqueue(e.clone(), &limits))
+            .collect();
+        assert_eq!(
+            admissions,
+            vec![
+                Admission::Start,
+                Admission::Start,
+                Admission::Queued { position: 1 },
+                Admission::Queued { position: 2 },
+            ]
+        );
+
+        let admitted = state.release(entries[0].execution_process_id, &limits);
+        assert_eq!(admitted.len(), 1);
+        assert_eq!(
+            admitted[0].execution_process_id,
+            entries[2].execution_process_id
+        );
+        assert_eq!(state.positions()[0].position, 1);
+        assert_eq!(
+            state.positions()[0].execution_process_id,
+            entries[3].execution_process_id
+        );
+    }
+
+    #[test]
+    fn test_per_executor_limit_does_not_block_other_executors() {
+        let limits = limits(Some(3), &[(BaseCodingAgent::Codex, 1)]);
+        let mut state = SchedulerState::default();
+        let codex_a = entry(BaseCodingAgent::Codex);
+        let codex_b = entry(BaseCodingAgent::Codex);
+        let claude = entry(BaseCodingAgent::ClaudeCode);
+
+        assert_eq!(state.enqueue(codex_a.clone(), &limits), Admission::Start);
+        assert_eq!(
+            state.enqueue(codex_b.clone(), &limits),
+            Admission::Queued { position: 1 }
+        );
+        assert_eq!(state.enqueue(claude, &limits), Admission::Start);
+
+        let admitted = state.release(codex_a.execution_process_id, &limits);
+        assert_eq!(admitted.len(), 1);
+        assert_eq!(
+            admitted[0].execution_process_id,
+            codex_b.execution_process_id
+        );
+    }
+
+    #[test]
+    fn test_release_skips_entries_blocked_by_their_executor() {
+        let limits = limits(Some(2), &[(BaseCodingAgent::Codex, 1)]);
+        let mut state = SchedulerState::default();
+        let codex = entry(BaseCodingAgent::Codex);
+        let claude = entry(BaseCodingAgent::ClaudeCode);
+        let codex_waiting = entry(BaseCodingAgent::Codex);
+        let gemini_waiting = entry(BaseCodingAgent::Gemini);
+
+        state.enqueue(codex.clone(), &limits);
+        state.enqueue(claude.clone(), &limits);
+        state.enqueue(codex_waiting.clone(), &limits);
+        state.enqueue(gemini_waiting.clone(), &limits);
+
+        // Codex is still at its cap, so the Gemini entry behind it takes the slot
+        let admitted = state.release(claude.execution_process_id, &limits);
+        assert_eq!(admitted.len(), 1);
+        assert_eq!(
+            admitted[0].execution_process_id,
+            gemini_waiting.execution_process_id
+        );
+        assert!(state.is_queued(codex_waiting.execution_process_id));
+    }
+
+    #[test]
+    fn test_release_of_queued_entry_removes_it() {
+        let limits = limits(Some(1), &[]);
+        let mut state = SchedulerState::default();
+        let running = entry(BaseCodingAgent::Amp);
+        let queued = entry(BaseCodingAgent::Amp);
+
+        state.enqueue(running, &limits);
+        state.enqueue(queued.clone(), &limits);
+        assert!(
+            state
+                .release(queued.execution_process_id, &limits)
+                .is_empty()
+        );
+        assert!(state.positions().is_empty());
+    }
+
+    #[test]
+    fn test_resumed_executions_take_slots() {
+        let limits = limits(Some(1), &[]);
+        let mut state = SchedulerState::default();
+        let resumed = entry(BaseCodingAgent::Amp);
+        let waiting = entry(BaseCodingAgent::Amp);
+
+        state.resume(resumed.execution_process_id, resumed.executor);
+        assert_eq!(
+            state.enqueue(waiting.clone(), &limits),
+            Admission::Queued { position: 1 }
+        );
+
+        let admitted = state.release(resumed.execution_process_id, &limits);
+        assert_eq!(admitted.len(), 1);
+        assert_eq!(
+            admitted[0].execution_process_id,
+            waiting.execution_process_id
+        );
+    }
+
+    #[test]
+    fn test_try_start_never_overtakes_the_queue() {
+        let limits = limits(Some(3), &[(BaseCodingAgent::Codex, 1)]);
+        let mut state = SchedulerState::default();
+        let codex = entry(BaseCodingAgent::Codex);
+
+        state.enqueue(codex.clone(), &limits);
+        assert!(!state.try_start(Uuid::new_v4(), BaseCodingAgent::Codex, &limits));
+        let background = Uuid::new_v4();
+        assert!(state.try_start(background, BaseCodingAgent::Amp, &limits));
+
+        // A global slot is still free, but it belongs to the waiting execution
+        let waiting = entry(BaseCodingAgent::Codex);
+        state.enqueue(waiting.clone(), &limits);
+        assert!(!state.try_start(Uuid::new_v4(), BaseCodingAgent::Amp, &limits));
+
+        state.release(background, &limits);
+        let admitted = state.release(codex.execution_process_id, &limits);
+        assert_eq!(
+            admitted[0].execution_process_id,
+            waiting.execution_process_id
+        );
+    }
+
+    #[test]
+    fn test_raised_limits_admit_waiting_entries() {
+        let mut state = SchedulerState::default();
+        let entries: Vec<_> = (0..3).map(|_| entry(BaseCodingAgent::ClaudeCode)).collect();
+        for e in &entries {
+            state.enqueue(e.clone(), &limits(Some(1), &[]));
+        }
+        assert!(state.admit(&limits(Some(1), &[])).is_empty());
+
+        let admitted = state.admit(&limits(Some(2), &[]));
+        assert_eq!(admitted.len(), 1);
+        assert_eq!(
+            admitted[0].execution_process_id,
+            entries[1].execution_process_id
+        );
+        assert_eq!(state.admit(&limits(None, &[])).len(), 1);
+        assert!(state.positions().is_empty());
+    }
+
+    #[test]
+    fn test_unlimited_starts_everything() {
+        let limits = limits(None, &[]);
+        let mut state = SchedulerState::default();
+        for _ in 0..20 {
+            assert_eq!(
+                state.enqueue(entry(BaseCodingAgent::ClaudeCode), &limits),
+                Admission::Start
+            );
+        }
+    }
+}
@@ -0,0 +1,304 @@
+//! Results of setup scripts, kept per repo so that a worktree whose setup script and lockfiles
+//! match an earlier successful setup gets a copy of the ignored files it produced, such as
+//! `node_modules`, instead of running the script again.
+
+use std::{
+    collections::BTreeSet,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use super::git::{GitCli, GitCliError};
+
+/// Files pinning the dependencies a setup script installs, at any depth of the repo
+const LOCKFILES: &[&str] = &[
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "pnpm-lock.yaml",
+    "yarn.lock",
+    "bun.lock",
+    "bun.lockb",
+    "Cargo.lock",
+    "go.sum",
+    "poetry.lock",
+    "uv.lock",
+    "Pipfile.lock",
+    "requirements.txt",
+    "Gemfile.lock",
+    "composer.lock",
+    "mix.lock",
+    "pubspec.lock",
+    "Package.resolved",
+    "flake.lock",
+];
+/// Cached setups kept per repo; older ones are removed as new ones are stored
+const MAX_KEYS_PER_REPO: usize = 3;
+const FILES_DIR: &str = "files";
+const ENTRIES_FILE: &str = "entries";
+const OUTPUT_FILE: &str = "output.log";
+
+fn cache_dir() -> PathBuf {
+    utils::path::get_vibe_kanban_temp_dir().join("setup-cache")
+}
+
+/// Key of the result of running `setup_script` in the worktree. It stays the same as long as
+/// the script and the committed contents of the worktree's lockfiles do.
+pub fn setup_key(worktree: &Path, setup_script: &str) -> Result<String, GitCliError> {
+    let mut args = vec![
+        "ls-files".to_string(),
+        "--stage".to_string(),
+        "--".to_string(),
+    ];
+    args.extend(LOCKFILES.iter().map(|name| format!(":(glob)**/{name}")));
+    let ls_files = GitCli::new().git(worktree, &args)?;
+    Ok(key_from_ls_files(setup_script, &ls_files))
+}
+
+/// Hash the script with the mode, blob id and path of each lockfile listed by
+/// `git ls-files --stage`
+fn key_from_ls_files(setup_script: &str, ls_files: &str) -> String {
+    let mut lines: Vec<&str> = ls_files
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    lines.sort_unstable();
+
+    let mut hasher = Sha256::new();
+    hasher.update(setup_script.as_bytes());
+    hasher.update([0]);
+    for line in lines {
+        hasher.update(line.as_bytes());
+        hasher.update(b"\n");
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// Ignored files and directories of the worktree, relative to it. Directories whose whole
+/// content is ignored are listed once, with a trailing `/`.
+pub fn ignored_entries(worktree: &Path) -> Result<BTreeSet<String>, GitCliError> {
+    let out = GitCli::new().git(
+        worktree,
+        [
+            "ls-files",
+            "--others",
+            "--ignored",
+            "--exclude-standard",
+            "--directory",
+            "-z",
+        ],
+    )?;
+    Ok(out
+        .split('\0')
+        .filter(|entry| !entry.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Keep a copy of the `entries` a successful setup created in the worktree, along with its
+/// output. Nothing is done if the key is cached already.
+pub fn store(
+    repo_id: Uuid,
+    key: &str,
+    worktree: &Path,
+    entries: &[String],
+    output: &str,
+) -> io::Result<()> {
+    store_in(&cache_dir(), repo_id, key, worktree, entries, output)
+}
+
+fn store_in(
+    cache_dir: &Path,
+    repo_id: Uuid,
+    key: &str,
+    worktree: &Path,
+    entries: &[String],
+    output: &str,
+) -> io::Result<()> {
+    let repo_dir = cache_dir.join(repo_id.to_string());
+    let target = repo_dir.join(key);
+    if target.is_dir() {
+        return Ok(());
+    }
+
+    // Copied next to the final location first, so a half written copy is never restored
+    let staging = repo_dir.join(format!(".{key}-{}", Uuid::new_v4()));
+    let result = write_cached_setup(&staging, worktree, entries, output)
+        .and_then(|()| fs::rename(&staging, &target));
+    if result.is_err() || staging.exists() {
+        let _ = fs::remove_dir_all(&staging);
+    }
+    // Another worktree may have stored the same key first
+    if result.is_err() && target.is_dir() {
+        return Ok(());
+    }
+    result?;
+
+    prune(&repo_dir)
+}
+
+fn write_cached_setup(
+    dir: &Path,
+    worktree: &Path,
+    entries: &[String],
+    output: &str,
+) -> io::Result<()> {
+    let files = dir.join(FILES_DIR);
+    fs::create_dir_all(&files)?;
+    for entry in entries {
+        let relative = entry.trim_end_matches('/');
+        copy_entry(&worktree.join(relative), &files.join(relative))?;
+    }
+    fs::write(dir.join(ENTRIES_FILE), entries.join("\n"))?;
+    fs::write(dir.join(OUTPUT_FILE), output)
+}
+
+/// Copy the files of the cached setup for `key` into the worktree, replacing what is there,
+/// and return its output. Returns `None` when the key is not cached.
+pub fn restore(repo_id: Uuid, key: &str, worktree: &Path) -> io::Result<Option<String>> {
+    restore_from(&cache_dir(), repo_id, key, worktree)
+}
+
+fn restore_from(
+    cache_dir: &Path,
+    repo_id: Uuid,
+    key: &str,
+    worktree: &Path,
+) -> io::Result<Option<String>> {
+    let dir = cache_dir.join(repo_id.to_string()).join(key);
+    if !dir.is_dir() {
+        return Ok(None);
+    }
+
+    let entries = fs::read_to_string(dir.join(ENTRIES_FILE))?;
+    for entry in entries.lines().filter(|entry| !entry.is_empty()) {
+        let relative = entry.trim_end_matches('/');
+        let dest = worktree.join(relative);
+        remove_entry(&dest)?;
+        copy_entry(&dir.join(FILES_DIR).join(relative), &dest)?;
+    }
+
+    fs::read_to_string(dir.join(OUTPUT_FILE)).map(Some)
+}
+
+/// Remove all but the newest cached setups of a repo
+fn prune(repo_dir: &Path) -> io::Result<()> {
+    let mut cached: Vec<(std::time::SystemTime, PathBuf)> = fs::read_dir(repo_dir)?
+        .filter_map(Result::ok)
+        .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|entry| {
+            let modified = entry.metadata().and_then(|m| m.modified()).ok()?;
+            Some((modified, entry.path()))
+        })
+        .collect();
+    cached.sort_by(|a, b| b.0.cmp(&a.0));
+
+    for (_, path) in cached.into_iter().skip(MAX_KEYS_PER_REPO) {
+        fs::remove_dir_all(path)?;
+    }
+    Ok(())
+}
+
+fn remove_entry(path: &Path) -> io::Result<()> {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.is_dir() => fs::remove_dir_all(path),
+        Ok(_) => fs::remove_file(path),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Copy a file, symlink or directory tree. Symlinks are recreated rather than followed, as
+/// package managers link between installed packages.
+fn copy_entry(src: &Path, dest: &Path) -> io::Result<()> {
+    let metadata = fs::symlink_metadata(src)?;
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    if metadata.is_symlink() {
+        let link = fs::read_link(src)?;
+        #[cfg(unix)]
+        return std::os::unix::fs::symlink(link, dest);
+        #[cfg(windows)]
+        return if src.is_dir() {
+            std::os::windows::fs::symlink_dir(link, dest)
+        } else {
+            std::os::windows::fs::symlink_file(link, dest)
+        };
+    }
+
+    if metadata.is_dir() {
+        fs::create_dir_all(dest)?;
+        for entry in fs::read_dir(src)? {
+            let entry = entry?;
+            copy_entry(&entry.path(), &dest.join(entry.file_name()))?;
+        }
+        return Ok(());
+    }
+
+    fs::copy(src, dest).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_setup_key_depends_on_script_and_lockfiles_only() {
+        let ls_files = "100644 1111111111111111111111111111111111111111 0\tpackage-lock.json\n\
+                        100644 2222222222222222222222222222222222222222 0\tfrontend/pnpm-lock.yaml\n";
+        let reordered = "100644 2222222222222222222222222222222222222222 0\tfrontend/pnpm-lock.yaml\n\
+                         100644 1111111111111111111111111111111111111111 0\tpackage-lock.json\n";
+        let changed = "100644 3333333333333333333333333333333333333333 0\tpackage-lock.json\n\
+                       100644 2222222222222222222222222222222222222222 0\tfrontend/pnpm-lock.yaml\n";
+
+        let key = key_from_ls_files("npm ci", ls_files);
+        assert_eq!(key, key_from_ls_files("npm ci", reordered));
+        assert_ne!(key, key_from_ls_files("npm ci", changed));
+        assert_ne!(key, key_from_ls_files("npm install", ls_files));
+    }
+
+    #[test]
+    fn test_store_and_restore_setup_files() {
+        let cache = TempDir::new().unwrap();
+        let source = TempDir::new().unwrap();
+        let target = TempDir::new().unwrap();
+        let repo_id = Uuid::new_v4();
+
+        fs::create_dir_all(source.path().join("node_modules/left-pad")).unwrap();
+        fs::write(source.path().join("node_modules/left-pad/index.js"), "pad").unwrap();
+        fs::write(source.path().join("build.log"), "built").unwrap();
+        let entries = vec!["node_modules/".to_string(), "build.log".to_string()];
+        store_in(
+            cache.path(),
+            repo_id,
+            "key",
+            source.path(),
+            &entries,
+            "added 1 package",
+        )
+        .unwrap();
+
+        // Stale files from an earlier setup are replaced
+        fs::create_dir_all(target.path().join("node_modules/stale")).unwrap();
+        let output = restore_from(cache.path(), repo_id, "key", target.path()).unwrap();
+
+        assert_eq!(output.as_deref(), Some("added 1 package"));
+        assert_eq!(
+            fs::read_to_string(target.path().join("node_modules/left-pad/index.js")).unwrap(),
+            "pad"
+        );
+        assert!(target.path().join("build.log").is_file());
+        assert!(!target.path().join("node_modules/stale").exists());
+        assert_eq!(
+            restore_from(cache.path(), repo_id, "other", target.path()).unwrap(),
+            None
+        );
+    }
+}
@@ -0,0 +1,539 @@
+//! Export of a task with its complete attempt history into a portable tar.gz archive, and
+//! import of such an archive under another project.
+//!
+//! Archive layout:
+//! - `manifest.json`: the task, its workspaces, sessions, execution processes and merges
+//! - `logs/{execution_process_id}.jsonl`: the raw logs of each execution process
+//! - `images/{file_path}`: the images attached to the task
+//! - `patches/{workspace_id}/{repo_name}.patch`: the commits of each attempt branch, as
+//!   produced by `git format-patch`; replayed onto a recreated branch on import
+
+use std::{collections::HashMap, io::Read};
+
+use chrono::{DateTime, Utc};
+use db::{
+    DBService,
+    models::{
+        coding_agent_turn::CodingAgentTurn,
+        execution_process::ExecutionProcess,
+        execution_process_logs::ExecutionProcessLogs,
+        execution_process_repo_state::ExecutionProcessRepoState,
+        image::{Image, TaskImage},
+        merge::Merge,
+        project_repo::ProjectRepo,
+        repo::Repo,
+        session::Session,
+        task::Task,
+        task_archive::{
+            ArchivedExecutionProcess, ArchivedRepo, ArchivedSession, ArchivedWorkspace,
+            TaskArchiveRecords,
+        },
+        workspace::{Workspace, WorkspaceError},
+        workspace_repo::WorkspaceRepo,
+    },
+};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::{
+    git::GitService,
+    image::{ImageError, ImageService},
+};
+
+pub const TASK_ARCHIVE_VERSION: u32 = 1;
+
+const MANIFEST_PATH: &str = "manifest.json";
+
+/// Limits on what an archive may decompress to, so that a small upload cannot exhaust
+/// memory
+const MAX_ENTRIES: usize = 100_000;
+const MAX_ENTRY_BYTES: u64 = 256 * 1024 * 1024;
+const MAX_TOTAL_BYTES: u64 = 1024 * 1024 * 1024;
+
+#[derive(Debug, Error)]
+pub enum TaskArchiveError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Workspace(#[from] WorkspaceError),
+    #[error(transparent)]
+    Image(#[from] ImageError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("Invalid task archive: {0}")]
+    InvalidArchive(String),
+    #[error("Unsupported task archive version {0}")]
+    UnsupportedVersion(u32),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedImage {
+    pub image: Image,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedPatch {
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskArchiveManifest {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub records: TaskArchiveRecords,
+    pub images: Vec<ArchivedImage>,
+    pub patches: Vec<ArchivedPatch>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ImportedTask {
+    pub task: Task,
+    /// Repositories of the archive with no repository of the same name in the target
+    /// project; their workspace repos, merges and repo states were left out
+    pub unmatched_repos: Vec<String>,
+    /// Attempt branches that could not be recreated, as `branch (repo)`, e.g. because the
+    /// target branch is missing or the commits no longer apply. The attempt keeps its
+    /// history but not its workspace repo for that repository.
+    pub unrestored_branches: Vec<String>,
+}
+
+/// Pack `task` and everything recorded about its attempts into a tar.gz archive
+pub async fn export_task(
+    db: &DBService,
+    git: &GitService,
+    images: &ImageService,
+    task: &Task,
+) -> Result<Vec<u8>, TaskArchiveError> {
+    let pool = &db.pool;
+    let mut files: Vec<(String, Vec<u8>)> = Vec::new();
+    let mut repos: HashMap<Uuid, Repo> = HashMap::new();
+    let mut patches = Vec::new();
+    let mut workspaces = Vec::new();
+
+    for workspace in Workspace::fetch_all(pool, Some(task.id)).await? {
+        let workspace_repos = WorkspaceRepo::find_by_workspace_id(pool, workspace.id).await?;
+        for workspace_repo in &workspace_repos {
+            if !repos.contains_key(&workspace_repo.repo_id) {
+                let Some(repo) = Repo::find_by_id(pool, workspace_repo.repo_id).await? else {
+                    continue;
+                };
+                repos.insert(repo.id, repo);
+            }
+            let repo = &repos[&workspace_repo.repo_id];
+            match git.branch_patch(&repo.path, &workspace.branch, &workspace_repo.target_branch) {
+                Ok(patch) if !patch.is_empty() => {
+                    let path = format!("patches/{}/{}.patch", workspace.id, repo.name);
+                    files.push((path.clone(), patch.into_bytes()));
+                    patches.push(ArchivedPatch {
+                        workspace_id: workspace.id,
+                        repo_id: repo.id,
+                        path,
+                    });
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!(
+                    "Skipping patch of branch {} in repo {}: {}",
+                    workspace.branch,
+                    repo.name,
+                    e
+                ),
+            }
+        }
+
+        let mut sessions = Vec::new();
+        for session in Session::find_by_workspace_id(pool, workspace.id).await? {
+            let mut execution_processes = Vec::new();
+            for process in ExecutionProcess::find_by_session_id(pool, session.id, true).await? {
+                let logs: String = ExecutionProcessLogs::find_by_execution_id(pool, process.id)
+                    .await?
+                    .into_iter()
+                    .map(|record| record.logs)
+                    .collect();
+                if !logs.is_empty() {
+                    files.push((format!("logs/{}.jsonl", process.id), logs.into_bytes()));
+                }
+                execution_processes.push(ArchivedExecutionProcess {
+                    repo_states: ExecutionProcessRepoState::find_by_execution_process_id(
+                        pool, process.id,
+                    )
+                    .await?,
+                    coding_agent_turn: CodingAgentTurn::find_by_execution_process_id(
+                        pool, process.id,
+                    )
+                    .await?,
+                    execution_process: process,
+                });
+            }
+            sessions.push(ArchivedSession {
+                session,
+                execution_processes,
+            });
+        }
+
+        workspaces.push(ArchivedWorkspace {
+            merges: Merge::find_by_workspace_id(pool, workspace.id).await?,
+            repos: workspace_repos,
+            sessions,
+            workspace,
+        });
+    }
+
+    let mut archived_images = Vec::new();
+    for image in Image::find_by_task_id(pool, task.id).await? {
+        let data = match std::fs::read(images.get_absolute_path(&image)) {
+            Ok(data) => data,
+            Err(e) => {
+                tracing::warn!("Skipping missing image {}: {}", image.file_path, e);
+                continue;
+            }
+        };
+        let path = format!("images/{}", image.file_path);
+        files.push((path.clone(), data));
+        archived_images.push(ArchivedImage { image, path });
+    }
+
+    let manifest = TaskArchiveManifest {
+        version: TASK_ARCHIVE_VERSION,
+        exported_at: Utc::now(),
+        records: TaskArchiveRecords {
+            task: task.clone(),
+            repos: repos
+                .into_values()
+                .map(|repo| ArchivedRepo {
+                    id: repo.id,
+                    name: repo.name,
+                    display_name: repo.display_name,
+                })
+                .collect(),
+            workspaces,
+        },
+        images: archived_images,
+        patches,
+    };
+    files.insert(
+        0,
+        (
+            MANIFEST_PATH.to_string(),
+            serde_json::to_vec_pretty(&manifest)?,
+        ),
+    );
+
+    Ok(pack(&files)?)
+}
+
+/// Recreate the task of a task archive under `project_id`. Repositories are matched by name
+/// against the repositories of the project. The branch of each attempt is recreated from
+/// its target branch with the archived commits replayed onto it, under a new name when the
+/// original one is taken.
+pub async fn import_task(
+    db: &DBService,
+    git: &GitService,
+    images: &ImageService,
+    project_id: Uuid,
+    archive: &[u8],
+) -> Result<ImportedTask, TaskArchiveError> {
+    let pool = &db.pool;
+    let mut files = unpack(archive)?;
+
+    let manifest_bytes = files
+        .remove(MANIFEST_PATH)
+        .ok_or_else(|| TaskArchiveError::InvalidArchive(format!("missing {MANIFEST_PATH}")))?;
+    let manifest: TaskArchiveManifest = serde_json::from_slice(&manifest_bytes)?;
+    if manifest.version != TASK_ARCHIVE_VERSION {
+        return Err(TaskArchiveError::UnsupportedVersion(manifest.version));
+    }
+    let mut records = manifest.records;
+
+    let project_repos = ProjectRepo::find_repos_for_project(pool, project_id).await?;
+    let mut repo_ids = HashMap::new();
+    let mut unmatched_repos = Vec::new();
+    for archived_repo in &records.repos {
+        match project_repos
+            .iter()
+            .find(|repo| repo.name == archived_repo.name)
+        {
+            Some(repo) => {
+                repo_ids.insert(archived_repo.id, repo.id);
+            }
+            None => unmatched_repos.push(archived_repo.name.clone()),
+        }
+    }
+
+    let mut image_ids = Vec::new();
+    let mut renamed_images = Vec::new();
+    for archived_image in &manifest.images {
+        let Some(data) = files.get(&archived_image.path) else {
+            tracing::warn!("Task archive is missing {}", archived_image.path);
+            continue;
+        };
+        let image = images
+            .store_image(data, &archived_image.image.original_name)
+            .await?;
+        renamed_images.push((archived_image.image.file_path.clone(), image.file_path));
+        image_ids.push(image.id);
+    }
+    if let Some(description) = &records.task.description {
+        records.task.description = Some(rewrite_image_paths(description, &renamed_images));
+    }
+
+    // A listed patch that is missing from the archive cannot be restored, unlike a branch
+    // without commits of its own, which has no patch at all
+    let patches: HashMap<(Uuid, Uuid), Option<String>> = manifest
+        .patches
+        .iter()
+        .map(|archived_patch| {
+            let patch = files
+                .remove(&archived_patch.path)
+                .and_then(|data| String::from_utf8(data).ok());
+            ((archived_patch.workspace_id, archived_patch.repo_id), patch)
+        })
+        .collect();
+
+    let scratch_dir = utils::path::get_vibe_kanban_temp_dir().join("task-import");
+    let mut created_branches = Vec::new();
+    let mut unrestored_branches = Vec::new();
+    for archived in &mut records.workspaces {
+        let local_repos: Vec<&Repo> = archived
+            .repos
+            .iter()
+            .filter_map(|workspace_repo| repo_ids.get(&workspace_repo.repo_id))
+            .filter_map(|repo_id| project_repos.iter().find(|repo| repo.id == *repo_id))
+            .collect();
+        let branch = unused_branch_name(git, &archived.workspace.branch, &local_repos);
+
+        let mut restored_repos = Vec::new();
+        for workspace_repo in archived.repos.drain(..) {
+            let Some(repo) = repo_ids
+                .get(&workspace_repo.repo_id)
+                .and_then(|repo_id| project_repos.iter().find(|repo| repo.id == *repo_id))
+            else {
+                continue;
+            };
+            let patch = match patches.get(&(archived.workspace.id, workspace_repo.repo_id)) {
+                Some(patch) => patch.as_deref(),
+                None => Some(""),
+            };
+            let restored = patch
+                .ok_or_else(|| "patch missing from the archive".to_string())
+                .and_then(|patch| {
+                    git.create_branch_from_patch(
+                        &repo.path,
+                        &branch,
+                        &workspace_repo.target_branch,
+                        patch,
+                        &scratch_dir.join(Uuid::new_v4().to_string()),
+                    )
+                    .map_err(|e| e.to_string())
+                });
+            match restored {
+                Ok(()) => {
+                    created_branches.push((repo.path.clone(), branch.clone()));
+                    restored_repos.push(workspace_repo);
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "Could not recreate branch {} in repo {}: {}",
+                        branch,
+                        repo.name,
+                        e
+                    );
+                    unrestored_branches.push(format!("{} ({})", branch, repo.name));
+                }
+            }
+        }
+        archived.repos = restored_repos;
+        archived.workspace.branch = branch;
+    }
+
+    let logs: HashMap<Uuid, String> = files
+        .into_iter()
+        .filter_map(|(path, data)| {
+            let id = path.strip_prefix("logs/")?.strip_suffix(".jsonl")?;
+            Some((Uuid::parse_str(id).ok()?, String::from_utf8(data).ok()?))
+        })
+        .collect();
+
+    let task_id = match records.import(pool, project_id, &repo_ids, &logs).await {
+        Ok(task_id) => task_id,
+        Err(e) => {
+            for (repo_path, branch) in &created_branches {
+                let _ = git.delete_local_branch(repo_path, branch);
+            }
+            return Err(e.into());
+        }
+    };
+    TaskImage::associate_many_dedup(pool, task_id, &image_ids).await?;
+    // Rows inserted within the import transaction are not broadcast; touch the task so
+    // that it shows up on the board
+    Task::update_status(pool, task_id, records.task.status.clone()).await?;
+
+    let task = Task::find_by_id(pool, task_id)
+        .await?
+        .ok_or(sqlx::Error::RowNotFound)?;
+    Ok(ImportedTask {
+        task,
+        unmatched_repos,
+        unrestored_branches,
+    })
+}
+
+/// `branch`, or `branch-2`, `branch-3` and so on when it already exists in one of `repos`
+fn unused_branch_name(git: &GitService, branch: &str, repos: &[&Repo]) -> String {
+    let taken = |name: &str| {
+        repos
+            .iter()
+            .any(|repo| git.check_branch_exists(&repo.path, name).unwrap_or(false))
+    };
+    if !taken(branch) {
+        return branch.to_string();
+    }
+    (2..)
+        .map(|n| format!("{branch}-{n}"))
+        .find(|name| !taken(name))
+        .expect("some numbered branch name is free")
+}
+
+/// Point `.vibe-images/` references at the file names the images were stored under
+fn rewrite_image_paths(description: &str, renamed: &[(String, String)]) -> String {
+    renamed
+        .iter()
+        .fold(description.to_string(), |description, (old, new)| {
+            description.replace(
+                &format!(".vibe-images/{old}"),
+                &format!(".vibe-images/{new}"),
+            )
+        })
+}
+
+fn pack(files: &[(String, Vec<u8>)]) -> Result<Vec<u8>, std::io::Error> {
+    let mut buffer = Vec::new();
+    {
+        let encoder = GzEncoder::new(&mut buffer, Compression::default());
+        let mut archive = tar::Builder::new(encoder);
+        for (path, data) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_mtime(Utc::now().timestamp() as u64);
+            header.set_cksum();
+            archive.append_data(&mut header, path, data.as_slice())?;
+        }
+        archive.into_inner()?.finish()?;
+    }
+    Ok(buffer)
+}
+
+fn unpack(archive: &[u8]) -> Result<HashMap<String, Vec<u8>>, TaskArchiveError> {
+    unpack_within(archive, MAX_ENTRY_BYTES, MAX_TOTAL_BYTES)
+}
+
+fn unpack_within(
+    archive: &[u8],
+    max_entry_bytes: u64,
+    max_total_bytes: u64,
+) -> Result<HashMap<String, Vec<u8>>, TaskArchiveError> {
+    let mut files = HashMap::new();
+    let mut total_bytes = 0;
+    let mut archive = tar::Archive::new(GzDecoder::new(archive));
+    let entries = archive
+        .entries()
+        .map_err(|e| TaskArchiveError::InvalidArchive(e.to_string()))?;
+    for entry in entries {
+        let mut entry = entry.map_err(|e| TaskArchiveError::InvalidArchive(e.to_string()))?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        if files.len() >= MAX_ENTRIES {
+            return Err(TaskArchiveError::InvalidArchive(format!(
+                "more than {MAX_ENTRIES} files"
+            )));
+        }
+        let path = entry
+            .path()
+            .map_err(|e| TaskArchiveError::InvalidArchive(e.to_string()))?
+            .to_string_lossy()
+            .into_owned();
+        // The size in the header is only trusted as far as the reads are bounded by it
+        let mut data = Vec::new();
+        (&mut entry)
+            .take(max_entry_bytes + 1)
+            .read_to_end(&mut data)
+            .map_err(|e| TaskArchiveError::InvalidArchive(e.to_string()))?;
+        let size = data.len() as u64;
+        if size > max_entry_bytes {
+            return Err(TaskArchiveError::InvalidArchive(format!(
+                "{path} is larger than {max_entry_bytes} bytes"
+            )));
+        }
+        total_bytes += size;
+        if total_bytes > max_total_bytes {
+            return Err(TaskArchiveError::InvalidArchive(format!(
+                "contents are larger than {max_total_bytes} bytes"
+            )));
+        }
+        files.insert(path, data);
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack_roundtrip() {
+        let files = vec![
+            (MANIFEST_PATH.to_string(), b"{}".to_vec()),
+            (
+                "logs/a.jsonl".to_string(),
+                b"{\"Stdout\":\"hi\"}\n".to_vec(),
+            ),
+        ];
+
+        let unpacked = unpack(&pack(&files).unwrap()).unwrap();
+        assert_eq!(unpacked.len(), 2);
+        assert_eq!(unpacked[MANIFEST_PATH], b"{}");
+        assert_eq!(unpacked["logs/a.jsonl"], b"{\"Stdout\":\"hi\"}\n");
+        assert!(matches!(
+            unpack(b"not an archive"),
+            Err(TaskArchiveError::InvalidArchive(_))
+        ));
+    }
+
+    #[test]
+    fn test_unpack_refuses_oversized_contents() {
+        let files = vec![
+            ("a".to_string(), vec![0; 600]),
+            ("b".to_string(), vec![0; 600]),
+        ];
+        let archive = pack(&files).unwrap();
+
+        assert_eq!(unpack_within(&archive, 1000, 2000).unwrap().len(), 2);
+        assert!(matches!(
+            unpack_within(&archive, 500, 2000),
+            Err(TaskArchiveError::InvalidArchive(_))
+        ));
+        assert!(matches!(
+            unpack_within(&archive, 1000, 1000),
+            Err(TaskArchiveError::InvalidArchive(_))
+        ));
+    }
+
+    #[test]
+    fn test_rewrite_image_paths() {
+        let renamed = vec![("old.png".to_string(), "new.png".to_string())];
+        assert_eq!(
+            rewrite_image_paths("See ![shot](.vibe-images/old.png)", &renamed),
+            "See ![shot](.vibe-images/new.png)"
+        );
+    }
+}
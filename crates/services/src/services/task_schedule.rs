@@ -0,0 +1,268 @@
+use std::{str::FromStr, time::Duration};
+
+use chrono::{DateTime, Local, Utc};
+use cron::Schedule;
+use db::{
+    DBService,
+    models::{
+        task::{CreateTask, Task},
+        task_schedule::TaskSchedule,
+    },
+};
+use thiserror::Error;
+use tokio::{sync::mpsc::UnboundedSender, time::interval};
+use tracing::{debug, error, info};
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum TaskScheduleError {
+    #[error("Invalid cron expression '{0}': {1}")]
+    InvalidCron(String, String),
+    #[error("Cron expression '{0}' has no upcoming run")]
+    NoUpcomingRun(String),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+/// A task created by a schedule, waiting to be started with the schedule's executor and repos
+#[derive(Debug, Clone)]
+pub struct ScheduledTask {
+    pub task: Task,
+    pub schedule: TaskSchedule,
+}
+
+/// Service that creates the tasks of due schedules. Starting them needs the container, so
+/// they are handed over through `task_tx`.
+pub struct TaskScheduleService {
+    db: DBService,
+    poll_interval: Duration,
+    task_tx: UnboundedSender<ScheduledTask>,
+}
+
+impl TaskScheduleService {
+    pub async fn spawn(
+        db: DBService,
+        task_tx: UnboundedSender<ScheduledTask>,
+    ) -> tokio::task::JoinHandle<()> {
+        let service = Self {
+            db,
+            poll_interval: Duration::from_secs(30),
+            task_tx,
+        };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(&self) {
+        info!(
+            "Starting task schedule service with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.run_due_schedules().await {
+                error!("Error running due task schedules: {}", e);
+            }
+        }
+    }
+
+    async fn run_due_schedules(&self) -> Result<(), TaskScheduleError> {
+        let now = Utc::now();
+        let due = TaskSchedule::find_due(&self.db.pool, now).await?;
+        if due.is_empty() {
+            debug!("No task schedules due");
+            return Ok(());
+        }
+
+        for schedule in due {
+            let schedule_id = schedule.id;
+            if let Err(e) = self.run_schedule(schedule, now).await {
+                error!("Error running task schedule {}: {}", schedule_id, e);
+            }
+        }
+        Ok(())
+    }
+
+    async fn run_schedule(
+        &self,
+        schedule: TaskSchedule,
+        now: DateTime<Utc>,
+    ) -> Result<(), TaskScheduleError> {
+        // Runs missed while the server was down collapse into this one
+        let next_run_at = match &schedule.cron_expression {
+            Some(cron_expression) => Some(next_run_after(cron_expression, now)?),
+            None => None,
+        };
+
+        let local_now = now.with_timezone(&Local);
+        let title = render_template(&schedule.title_template, local_now);
+        let description = schedule
+            .description_template
+            .as_deref()
+            .map(|template| render_template(template, local_now));
+        let task = Task::create(
+            &self.db.pool,
+            &CreateTask::from_title_description(schedule.project_id, title, description),
+            Uuid::new_v4(),
+        )
+        .await?;
+        TaskSchedule::record_run(&self.db.pool, schedule.id, task.id, now, next_run_at).await?;
+
+        info!(
+            "Task schedule '{}' created task {}, next run {:?}",
+            schedule.name, task.id, next_run_at
+        );
+        if self.task_tx.send(ScheduledTask { task, schedule }).is_err() {
+            error!("Scheduled task receiver dropped, the task will not be started");
+        }
+        Ok(())
+    }
+}
+
+const WEEKDAYS: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// Crontab day of week `field`, numbered 0-7 from Sunday, with the numbers replaced by the
+/// names the cron crate numbers 1-7 from Sunday
+fn crontab_day_of_week(field: &str) -> Result<String, String> {
+    let day = |value: &str| match value.parse::<usize>() {
+        Ok(number) if number <= 7 => Ok(WEEKDAYS[number % 7].to_string()),
+        Ok(number) => Err(format!("day of week {number} is not in 0-7")),
+        Err(_) => Ok(value.to_string()),
+    };
+    let items = field
+        .split(',')
+        .map(|item| {
+            let (range, step) = match item.split_once('/') {
+                Some((range, step)) => (range, Some(step)),
+                None => (item, None),
+            };
+            let step_suffix = step.map(|step| format!("/{step}")).unwrap_or_default();
+            match range.split_once('-') {
+                None => Ok(format!("{}{step_suffix}", day(range)?)),
+                // Sunday is 7 as well as 0, but ranges of names cannot wrap around to it
+                Some((start, "7")) if start != "7" => {
+                    let start_name = day(start)?;
+                    let first = start.parse::<usize>().map_err(|_| {
+                        format!("day of week range {range} mixes names and numbers")
+                    })?;
+                    let step_size = match step {
+                        Some(step) => step
+                            .parse::<usize>()
+                            .ok()
+                            .filter(|step| *step > 0)
+                            .ok_or_else(|| format!("invalid step {step}"))?,
+                        None => 1,
+                    };
+                    let mut items = vec![format!("{start_name}-SAT{step_suffix}")];
+                    if (7 - first) % step_size == 0 {
+                        items.push("SUN".to_string());
+                    }
+                    Ok(items.join(","))
+                }
+                Some((start, end)) => Ok(format!("{}-{}{step_suffix}", day(start)?, day(end)?)),
+            }
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(items.join(","))
+}
+
+/// Parse a cron expression. Standard five-field crontab expressions are accepted, with days
+/// of the week numbered 0-7 from Sunday, as well as the cron crate's six and seven-field
+/// forms with seconds and years, whose days of the week are numbered 1-7 from Sunday.
+pub fn parse_cron(cron_expression: &str) -> Result<Schedule, TaskScheduleError> {
+    let invalid = |e: String| TaskScheduleError::InvalidCron(cron_expression.to_string(), e);
+    let fields: Vec<&str> = cron_expression.split_whitespace().collect();
+    let normalized = match fields.as_slice() {
+        [minute, hour, day_of_month, month, day_of_week] => format!(
+            "0 {minute} {hour} {day_of_month} {month} {}",
+            crontab_day_of_week(day_of_week).map_err(invalid)?
+        ),
+        _ => fields.join(" "),
+    };
+    Schedule::from_str(&normalized).map_err(|e| invalid(e.to_string()))
+}
+
+/// The first run of `cron_expression` after `after`, evaluated in the server's local time
+pub fn next_run_after(
+    cron_expression: &str,
+    after: DateTime<Utc>,
+) -> Result<DateTime<Utc>, TaskScheduleError> {
+    parse_cron(cron_expression)?
+        .after(&after.with_timezone(&Local))
+        .next()
+        .map(|run| run.with_timezone(&Utc))
+        .ok_or_else(|| TaskScheduleError::NoUpcomingRun(cron_expression.to_string()))
+}
+
+/// Fill in `{{date}}`, `{{time}}` and `{{datetime}}` with the time of the run
+pub fn render_template(template: &str, at: DateTime<Local>) -> String {
+    template
+        .replace("{{datetime}}", &at.format("%Y-%m-%d %H:%M").to_string())
+        .replace("{{date}}", &at.format("%Y-%m-%d").to_string())
+        .replace("{{time}}", &at.format("%H:%M").to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Datelike, TimeZone, Timelike, Weekday};
+
+    use super::*;
+
+    #[test]
+    fn test_five_field_cron_runs_at_the_given_local_time() {
+        let after = Utc::now();
+        let next = next_run_after("30 3 * * *", after).unwrap();
+        let local = next.with_timezone(&Local);
+
+        assert!(next > after);
+        assert!(next - after <= chrono::Duration::days(1));
+        assert_eq!((local.hour(), local.minute(), local.second()), (3, 30, 0));
+    }
+
+    #[test]
+    fn test_invalid_cron_is_rejected() {
+        assert!(matches!(
+            parse_cron("every night"),
+            Err(TaskScheduleError::InvalidCron(_, _))
+        ));
+        assert!(parse_cron("0 9 * * MON").is_ok());
+    }
+
+    #[test]
+    fn test_five_field_cron_numbers_days_of_the_week_from_sunday() {
+        // A Saturday afternoon
+        let after = Local
+            .with_ymd_and_hms(2025, 3, 8, 12, 0, 0)
+            .unwrap()
+            .with_timezone(&Utc);
+        let next_day = |cron_expression: &str| {
+            let next = next_run_after(cron_expression, after)
+                .unwrap()
+                .with_timezone(&Local);
+            (next.weekday(), next.day())
+        };
+
+        assert_eq!(next_day("0 9 * * 1-5"), (Weekday::Mon, 10));
+        assert_eq!(next_day("0 9 * * 0"), (Weekday::Sun, 9));
+        assert_eq!(next_day("0 9 * * 7"), (Weekday::Sun, 9));
+        assert_eq!(next_day("0 9 * * 5-7"), (Weekday::Sun, 9));
+        assert_eq!(next_day("0 9 * * 2,4"), (Weekday::Tue, 11));
+        assert!(parse_cron("0 9 * * 8").is_err());
+        // The seven-field form keeps the cron crate's numbering, where 2 is Monday
+        assert_eq!(next_day("0 0 9 * * 2 *"), (Weekday::Mon, 10));
+    }
+
+    #[test]
+    fn test_render_template_fills_in_the_run_time() {
+        let at = Local.with_ymd_and_hms(2025, 3, 7, 2, 5, 0).unwrap();
+        assert_eq!(
+            render_template("Nightly upgrade {{date}} ({{time}})", at),
+            "Nightly upgrade 2025-03-07 (02:05)"
+        );
+        assert_eq!(render_template("{{datetime}}", at), "2025-03-07 02:05");
+    }
+}
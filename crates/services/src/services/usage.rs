@@ -0,0 +1,203 @@
+use std::collections::{BTreeMap, HashMap};
+
+use db::models::execution_process_usage::UsageBucket;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::services::config::ModelPrice;
+
+/// Token counts and their estimated cost in USD
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+pub struct UsageTotals {
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_creation_input_tokens: i64,
+    pub cache_read_input_tokens: i64,
+    pub cost_usd: f64,
+}
+
+impl UsageTotals {
+    fn add(&mut self, bucket: &UsageBucket, cost_usd: f64) {
+        self.input_tokens += bucket.input_tokens;
+        self.output_tokens += bucket.output_tokens;
+        self.cache_creation_input_tokens += bucket.cache_creation_input_tokens;
+        self.cache_read_input_tokens += bucket.cache_read_input_tokens;
+        self.cost_usd += cost_usd;
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ModelUsage {
+    pub model: String,
+    /// False when no price is configured for the model, its cost is counted as zero
+    pub priced: bool,
+    pub usage: UsageTotals,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct DailyUsage {
+    /// UTC date, `YYYY-MM-DD`
+    pub day: String,
+    pub usage: UsageTotals,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TaskUsage {
+    pub task_id: Uuid,
+    pub usage: UsageTotals,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+pub struct UsageSummary {
+    pub total: UsageTotals,
+    pub by_model: Vec<ModelUsage>,
+    pub by_day: Vec<DailyUsage>,
+    pub by_task: Vec<TaskUsage>,
+}
+
+/// Price for `model`, matching configured keys as prefixes and preferring the longest one
+pub fn find_price<'a>(
+    prices: &'a HashMap<String, ModelPrice>,
+    model: &str,
+) -> Option<&'a ModelPrice> {
+    prices
+        .iter()
+        .filter(|(prefix, _)| model.starts_with(prefix.as_str()))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(_, price)| price)
+}
+
+fn cost_usd(bucket: &UsageBucket, price: &ModelPrice) -> f64 {
+    (bucket.input_tokens as f64 * price.input_per_mtok
+        + bucket.output_tokens as f64 * price.output_per_mtok
+        + bucket.cache_creation_input_tokens as f64 * price.cache_write_per_mtok
+        + bucket.cache_read_input_tokens as f64 * price.cache_read_per_mtok)
+        / 1_000_000.0
+}
+
+/// Aggregate usage buckets into totals per model, day and task
+pub fn summarize(buckets: &[UsageBucket], prices: &HashMap<String, ModelPrice>) -> UsageSummary {
+    let mut total = UsageTotals::default();
+    let mut by_model: BTreeMap<&str, ModelUsage> = BTreeMap::new();
+    let mut by_day: BTreeMap<&str, UsageTotals> = BTreeMap::new();
+    let mut by_task: HashMap<Uuid, UsageTotals> = HashMap::new();
+
+    for bucket in buckets {
+        let price = find_price(prices, &bucket.model);
+        let cost = price.map(|p| cost_usd(bucket, p)).unwrap_or(0.0);
+
+        total.add(bucket, cost);
+        by_model
+            .entry(&bucket.model)
+            .or_insert_with(|| ModelUsage {
+                model: bucket.model.clone(),
+                priced: price.is_some(),
+                usage: UsageTotals::default(),
+            })
+            .usage
+            .add(bucket, cost);
+        by_day.entry(&bucket.day).or_default().add(bucket, cost);
+        by_task.entry(bucket.task_id).or_default().add(bucket, cost);
+    }
+
+    let mut by_task: Vec<TaskUsage> = by_task
+        .into_iter()
+        .map(|(task_id, usage)| TaskUsage { task_id, usage })
+        .collect();
+    by_task.sort_by(|a, b| b.usage.cost_usd.total_cmp(&a.usage.cost_usd));
+
+    UsageSummary {
+        total,
+        by_model: by_model.into_values().collect(),
+        by_day: by_day
+            .into_iter()
+            .map(|(day, usage)| DailyUsage {
+                day: day.to_string(),
+                usage,
+            })
+            .collect(),
+        by_task,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bucket(task_id: Uuid, model: &str, day: &str, input: i64, output: i64) -> UsageBucket {
+        UsageBucket {
+            task_id,
+            model: model.to_string(),
+            day: day.to_string(),
+            input_tokens: input,
+            output_tokens: output,
+            cache_creation_input_tokens: 0,
+            cache_read_input_tokens: 0,
+        }
+    }
+
+    fn prices() -> HashMap<String, ModelPrice> {
+        HashMap::from([
+            (
+                "gpt-5".to_string(),
+                ModelPrice {
+                    input_per_mtok: 1.0,
+                    output_per_mtok: 10.0,
+                    cache_write_per_mtok: 0.0,
+                    cache_read_per_mtok: 0.1,
+                },
+            ),
+            (
+                "gpt-5-mini".to_string(),
+                ModelPrice {
+                    input_per_mtok: 0.5,
+                    output_per_mtok: 2.0,
+                    cache_write_per_mtok: 0.0,
+                    cache_read_per_mtok: 0.05,
+                },
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_find_price_prefers_longest_prefix() {
+        let prices = prices();
+        assert_eq!(
+            find_price(&prices, "gpt-5-mini-2025-08-07").map(|p| p.input_per_mtok),
+            Some(0.5)
+        );
+        assert_eq!(
+            find_price(&prices, "gpt-5-codex").map(|p| p.input_per_mtok),
+            Some(1.0)
+        );
+        assert!(find_price(&prices, "claude-sonnet-4-5").is_none());
+    }
+
+    #[test]
+    fn test_summarize_groups_by_model_day_and_task() {
+        let task_a = Uuid::new_v4();
+        let task_b = Uuid::new_v4();
+        let buckets = vec![
+            bucket(task_a, "gpt-5", "2025-12-01", 1_000_000, 100_000),
+            bucket(task_a, "gpt-5-mini", "2025-12-02", 2_000_000, 0),
+            bucket(task_b, "mystery-model", "2025-12-02", 500, 500),
+        ];
+
+        let summary = summarize(&buckets, &prices());
+
+        assert_eq!(summary.total.input_tokens, 3_000_500);
+        assert!((summary.total.cost_usd - 3.0).abs() < 1e-9);
+        assert_eq!(summary.by_day.len(), 2);
+        assert_eq!(summary.by_day[0].day, "2025-12-01");
+        assert!((summary.by_day[1].usage.cost_usd - 1.0).abs() < 1e-9);
+        assert_eq!(summary.by_task[0].task_id, task_a);
+        let unpriced = summary
+            .by_model
+            .iter()
+            .find(|m| m.model == "mystery-model")
+            .unwrap();
+        assert!(!unpriced.priced);
+        assert_eq!(unpriced.usage.cost_usd, 0.0);
+    }
+}
@@ -0,0 +1,129 @@
+//! Verify scripts that gate each coding agent turn, and the follow-ups sent back to the
+//! agent when they fail.
+
+use executors::actions::{
+    ExecutorAction, ExecutorActionType,
+    script::{ScriptContext, ScriptRequest},
+};
+use utils::log_msg::LogMsg;
+
+/// Only the end of a failing run is sent back; that's where test runners and compilers
+/// report what went wrong
+const MAX_OUTPUT_BYTES: usize = 8 * 1024;
+
+pub fn is_verify_action(action: &ExecutorAction) -> bool {
+    matches!(
+        action.typ(),
+        ExecutorActionType::ScriptRequest(ScriptRequest {
+            context: ScriptContext::VerifyScript,
+            ..
+        })
+    )
+}
+
+/// What follows the verify scripts chained after `action`, i.e. the rest of the chain
+/// once verification has passed
+pub fn action_after_verification(action: &ExecutorAction) -> Option<ExecutorAction> {
+    let mut next = action.next_action();
+    while let Some(candidate) = next {
+        if !is_verify_action(candidate) {
+            return Some(candidate.clone());
+        }
+        next = candidate.next_action();
+    }
+    None
+}
+
+/// Stdout and stderr of a finished process, trimmed to the last [`MAX_OUTPUT_BYTES`]
+pub fn collect_verify_output(history: &[LogMsg]) -> String {
+    let output: String = history
+        .iter()
+        .filter_map(|msg| match msg {
+            LogMsg::Stdout(content) | LogMsg::Stderr(content) => Some(content.as_str()),
+            _ => None,
+        })
+        .collect();
+
+    if output.len() <= MAX_OUTPUT_BYTES {
+        return output;
+    }
+    let mut start = output.len() - MAX_OUTPUT_BYTES;
+    while !output.is_char_boundary(start) {
+        start += 1;
+    }
+    format!("[... output truncated ...]\n{}", &output[start..])
+}
+
+/// Follow-up asking the agent to fix what the verify script of `repo_name` reported
+pub fn build_verify_failure_prompt(
+    repo_name: &str,
+    script: &str,
+    exit_code: Option<i64>,
+    output: &str,
+) -> String {
+    let exit_code = exit_code
+        .map(|code| code.to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    let output = if output.trim().is_empty() {
+        "(no output)"
+    } else {
+        output.trim_end()
+    };
+
+    format!(
+        "The verify script of the `{repo_name}` repository failed with exit code {exit_code} \
+         after your changes. Fix the problems it reports; the script runs again once you are \
+         done.\n\nScript:\n```\n{}\n```\n\nOutput:\n```\n{output}\n```",
+        script.trim_end()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::actions::script::ScriptRequestLanguage;
+
+    use super::*;
+
+    fn script(context: ScriptContext, script: &str) -> ExecutorAction {
+        ExecutorAction::new(
+            ExecutorActionType::ScriptRequest(ScriptRequest {
+                script: script.to_string(),
+                language: ScriptRequestLanguage::Bash,
+                context,
+                working_dir: None,
+            }),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_action_after_verification_skips_remaining_verify_scripts() {
+        let chain = script(ScriptContext::VerifyScript, "cargo check")
+            .append_action(script(ScriptContext::VerifyScript, "npm test"))
+            .append_action(script(ScriptContext::CleanupScript, "rm -rf tmp"));
+
+        let after = action_after_verification(&chain).unwrap();
+        assert!(matches!(
+            after.typ(),
+            ExecutorActionType::ScriptRequest(ScriptRequest {
+                context: ScriptContext::CleanupScript,
+                ..
+            })
+        ));
+        assert!(action_after_verification(&script(ScriptContext::VerifyScript, "ls")).is_none());
+    }
+
+    #[test]
+    fn test_collect_verify_output_keeps_the_tail() {
+        let history = vec![
+            LogMsg::Stdout("a".repeat(MAX_OUTPUT_BYTES)),
+            LogMsg::SessionId("ignored".to_string()),
+            LogMsg::Stderr("error: tests failed\n".to_string()),
+        ];
+
+        let output = collect_verify_output(&history);
+        assert!(output.starts_with("[... output truncated ...]\n"));
+        assert!(output.ends_with("error: tests failed\n"));
+        assert!(!output.contains("ignored"));
+    }
+}
@@ -0,0 +1,267 @@
+//! Outbound webhooks. Events are queued over a channel so they can be emitted from
+//! anywhere, including the database hooks; a background worker records one delivery per
+//! subscribed webhook and sends it, retrying failed attempts with exponential backoff.
+
+use std::time::Duration;
+
+use chrono::Utc;
+use db::{
+    DBService,
+    models::{
+        execution_process::ExecutionProcess,
+        webhook::{Webhook, WebhookEvent},
+        webhook_delivery::WebhookDelivery,
+        workspace::Workspace,
+    },
+};
+use hmac::{Hmac, Mac};
+use serde_json::{Value, json};
+use sha2::Sha256;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
+use uuid::Uuid;
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub const EVENT_HEADER: &str = "X-Vibe-Kanban-Event";
+pub const DELIVERY_HEADER: &str = "X-Vibe-Kanban-Delivery";
+pub const SIGNATURE_HEADER: &str = "X-Vibe-Kanban-Signature";
+
+/// Attempts per delivery before it is marked failed
+const MAX_ATTEMPTS: i64 = 6;
+const BASE_RETRY_DELAY: Duration = Duration::from_secs(30);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60 * 60);
+/// How often the worker looks for retries that became due
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+const DELIVERY_BATCH_SIZE: i64 = 50;
+/// Response bodies are only kept for the delivery log, so long ones are cut short
+const MAX_ERROR_BODY_CHARS: usize = 500;
+
+#[derive(Debug)]
+struct QueuedEvent {
+    event: WebhookEvent,
+    data: Value,
+}
+
+#[derive(Clone)]
+pub struct WebhookService {
+    tx: UnboundedSender<QueuedEvent>,
+}
+
+impl WebhookService {
+    /// Create the service and start its delivery worker
+    pub fn spawn(db: DBService) -> Self {
+        let (tx, rx) = unbounded_channel();
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(15))
+            .user_agent(concat!("vibe-kanban-webhooks/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap();
+        let worker = WebhookWorker { db, client, rx };
+        tokio::spawn(worker.run());
+        Self { tx }
+    }
+
+    /// Queue `event` for every enabled webhook subscribed to it. Never blocks, so it is
+    /// safe to call from synchronous contexts such as SQLite hooks.
+    pub fn emit(&self, event: WebhookEvent, data: Value) {
+        if self.tx.send(QueuedEvent { event, data }).is_err() {
+            tracing::warn!("Webhook worker stopped; dropping {} event", event);
+        }
+    }
+
+    pub fn emit_execution(
+        &self,
+        event: WebhookEvent,
+        execution_process: &ExecutionProcess,
+        workspace: &Workspace,
+    ) {
+        self.emit(
+            event,
+            json!({
+                "execution_process_id": execution_process.id,
+                "session_id": execution_process.session_id,
+                "workspace_id": workspace.id,
+                "task_id": workspace.task_id,
+                "run_reason": execution_process.run_reason,
+                "status": execution_process.status,
+                "exit_code": execution_process.exit_code,
+            }),
+        );
+    }
+}
+
+struct WebhookWorker {
+    db: DBService,
+    client: reqwest::Client,
+    rx: UnboundedReceiver<QueuedEvent>,
+}
+
+impl WebhookWorker {
+    async fn run(mut self) {
+        loop {
+            tokio::select! {
+                queued = self.rx.recv() => match queued {
+                    Some(queued) => self.record_deliveries(queued).await,
+                    None => return,
+                },
+                _ = tokio::time::sleep(POLL_INTERVAL) => {}
+            }
+            self.deliver_due().await;
+        }
+    }
+
+    async fn record_deliveries(&self, queued: QueuedEvent) {
+        let webhooks = match Webhook::find_all(&self.db.pool).await {
+            Ok(webhooks) => webhooks,
+            Err(e) => {
+                tracing::error!("Failed to load webhooks: {}", e);
+                return;
+            }
+        };
+
+        for webhook in webhooks
+            .iter()
+            .filter(|webhook| webhook.subscribes_to(queued.event))
+        {
+            let id = Uuid::new_v4();
+            let payload = json!({
+                "id": id,
+                "event": queued.event,
+                "created_at": Utc::now(),
+                "data": queued.data,
+            })
+            .to_string();
+            if let Err(e) =
+                WebhookDelivery::create(&self.db.pool, id, webhook.id, queued.event, &payload).await
+            {
+                tracing::error!(
+                    "Failed to record {} delivery for webhook {}: {}",
+                    queued.event,
+                    webhook.id,
+                    e
+                );
+            }
+        }
+    }
+
+    async fn deliver_due(&self) {
+        let due = match WebhookDelivery::find_due(&self.db.pool, DELIVERY_BATCH_SIZE).await {
+            Ok(due) => due,
+            Err(e) => {
+                tracing::error!("Failed to load due webhook deliveries: {}", e);
+                return;
+            }
+        };
+
+        for delivery in due {
+            if let Err(e) = self.deliver(&delivery).await {
+                tracing::error!("Failed to update webhook delivery {}: {}", delivery.id, e);
+            }
+        }
+    }
+
+    async fn deliver(&self, delivery: &WebhookDelivery) -> Result<(), sqlx::Error> {
+        let pool = &self.db.pool;
+        let Some(webhook) = Webhook::find_by_id(pool, delivery.webhook_id)
+            .await?
+            .filter(|webhook| webhook.enabled)
+        else {
+            return WebhookDelivery::mark_attempt_failed(
+                pool,
+                delivery.id,
+                None,
+                "Webhook was disabled",
+                None,
+            )
+            .await;
+        };
+
+        let mut request = self
+            .client
+            .post(&webhook.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, delivery.event.to_string())
+            .header(DELIVERY_HEADER, delivery.id.to_string());
+        if let Some(secret) = &webhook.secret {
+            request = request.header(SIGNATURE_HEADER, sign(secret, &delivery.payload));
+        }
+
+        let (response_status, error) = match request.body(delivery.payload.clone()).send().await {
+            Ok(response) if response.status().is_success() => {
+                return WebhookDelivery::mark_delivered(
+                    pool,
+                    delivery.id,
+                    response.status().as_u16() as i64,
+                )
+                .await;
+            }
+            Ok(response) => {
+                let status = response.status();
+                let body: String = response
+                    .text()
+                    .await
+                    .unwrap_or_default()
+                    .chars()
+                    .take(MAX_ERROR_BODY_CHARS)
+                    .collect();
+                (
+                    Some(status.as_u16() as i64),
+                    format!("HTTP {status}: {body}"),
+                )
+            }
+            Err(e) => (None, e.to_string()),
+        };
+
+        let attempt = delivery.attempts + 1;
+        let retry_at = (attempt < MAX_ATTEMPTS).then(|| {
+            Utc::now() + chrono::Duration::from_std(retry_delay(attempt)).unwrap_or_default()
+        });
+        tracing::warn!(
+            "Webhook delivery {} to {} failed (attempt {}/{}): {}",
+            delivery.id,
+            webhook.url,
+            attempt,
+            MAX_ATTEMPTS,
+            error
+        );
+        WebhookDelivery::mark_attempt_failed(pool, delivery.id, response_status, &error, retry_at)
+            .await
+    }
+}
+
+/// `sha256=` followed by the hex HMAC-SHA256 of `payload` keyed with `secret`
+pub fn sign(secret: &str, payload: &str) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(payload.as_bytes());
+    format!("sha256={:x}", mac.finalize().into_bytes())
+}
+
+/// Delay before the retry that follows failed attempt number `attempt` (1-based)
+fn retry_delay(attempt: i64) -> Duration {
+    let exponent = attempt.saturating_sub(1).clamp(0, 16) as u32;
+    BASE_RETRY_DELAY
+        .saturating_mul(2u32.pow(exponent))
+        .min(MAX_RETRY_DELAY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_matches_hmac_sha256() {
+        assert_eq!(
+            sign("key", "The quick brown fox jumps over the lazy dog"),
+            "sha256=f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+    }
+
+    #[test]
+    fn test_retry_delay_backs_off_exponentially() {
+        assert_eq!(retry_delay(1), Duration::from_secs(30));
+        assert_eq!(retry_delay(2), Duration::from_secs(60));
+        assert_eq!(retry_delay(5), Duration::from_secs(480));
+        assert_eq!(retry_delay(20), MAX_RETRY_DELAY);
+    }
+}
@@ -5,7 +5,7 @@ use std::{
 };
 
 use git2::{PushOptions, Repository, build::CheckoutBuilder};
-use services::services::git::{GitCli, GitCliError, GitService};
+use services::services::git::{ConflictOp, GitCli, GitCliError, GitService};
 use tempfile::TempDir;
 // Avoid direct git CLI usage in tests; exercise GitService instead.
 
@@ -713,6 +713,71 @@ fn rebase_refuses_to_abort_existing_rebase() {
     // Note: We do not auto-abort; user should resolve or abort explicitly
 }
 
+#[test]
+fn continue_after_resolving_rebase_conflict_completes_rebase() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_conflict_repo_with_worktree(&td);
+
+    let service = GitService::new();
+    let _ = service
+        .rebase_branch(
+            &repo_path,
+            &worktree_path,
+            "new-base",
+            "old-base",
+            "feature",
+        )
+        .expect_err("rebase should stop on the conflict");
+    assert_eq!(
+        service.detect_conflict_op(&worktree_path).unwrap(),
+        Some(ConflictOp::Rebase)
+    );
+
+    let (head, incoming) = service
+        .get_conflict_commit_subjects(&worktree_path, &ConflictOp::Rebase)
+        .unwrap();
+    assert_eq!(head.as_deref(), Some("new-base change"));
+    assert_eq!(incoming.as_deref(), Some("feature conflicting change"));
+
+    let conflicted = service.get_conflicted_files(&worktree_path).unwrap();
+    assert_eq!(conflicted, vec!["conflict.txt".to_string()]);
+    assert_eq!(
+        service.files_with_conflict_markers(&worktree_path, &conflicted),
+        conflicted
+    );
+
+    // Resolve the way an agent would: edit the file and drop the markers
+    write_file(&worktree_path, "conflict.txt", "resolved version\n");
+    assert!(
+        service
+            .files_with_conflict_markers(&worktree_path, &conflicted)
+            .is_empty()
+    );
+
+    // Whatever else is lying around in the worktree stays out of the rebased commit
+    write_file(&worktree_path, "stray.txt", "not part of the resolution\n");
+
+    service
+        .continue_conflict_op(&worktree_path, &conflicted)
+        .expect("continue should finish the rebase");
+    assert_eq!(service.detect_conflict_op(&worktree_path).unwrap(), None);
+    assert_eq!(
+        service.get_head_info(&worktree_path).unwrap().branch,
+        "feature"
+    );
+    let content = fs::read_to_string(worktree_path.join("conflict.txt")).unwrap();
+    assert_eq!(content, "resolved version\n");
+    let head_tree = Repository::open(&worktree_path)
+        .unwrap()
+        .head()
+        .unwrap()
+        .peel_to_tree()
+        .unwrap();
+    assert!(head_tree.get_name("conflict.txt").is_some());
+    assert!(head_tree.get_name("stray.txt").is_none());
+    assert!(worktree_path.join("stray.txt").exists());
+}
+
 #[test]
 fn rebase_fast_forwards_when_no_unique_commits() {
     let td = TempDir::new().unwrap();
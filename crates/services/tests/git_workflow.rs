@@ -4,6 +4,7 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use db::models::project::MergeMode;
 use git2::{Repository, build::CheckoutBuilder};
 use services::services::git::{DiffTarget, GitCli, GitService};
 use tempfile::TempDir;
@@ -520,3 +521,269 @@ fn squash_merge_libgit2_sets_author_without_user() {
         assert_eq!(email.as_deref(), Some("noreply@vibekanban.com"));
     }
 }
+
+#[test]
+fn merge_commit_and_rebase_modes_keep_task_commits() {
+    let td = TempDir::new().unwrap();
+    let s = GitService::new();
+
+    for (i, mode) in [MergeMode::MergeCommit, MergeMode::Rebase]
+        .into_iter()
+        .enumerate()
+    {
+        let repo_path = td.path().join(format!("repo_{i}"));
+        let worktree_path = td.path().join(format!("wt_{i}"));
+        s.initialize_repo_with_main_branch(&repo_path).unwrap();
+        configure_user(&repo_path, "Test User", "test@example.com");
+
+        create_branch(&repo_path, "feature");
+        s.add_worktree(&repo_path, &worktree_path, "feature", false)
+            .unwrap();
+        write_file(&worktree_path, "a.txt", "a\n");
+        s.commit(&worktree_path, "feat: add a").unwrap();
+        write_file(&worktree_path, "b.txt", "b\n");
+        s.commit(&worktree_path, "feat: add b").unwrap();
+        let feature_tip = Repository::open(&worktree_path)
+            .unwrap()
+            .head()
+            .unwrap()
+            .peel_to_commit()
+            .unwrap()
+            .id();
+
+        // Keep main unchecked out so the libgit2 path is taken
+        create_branch(&repo_path, "dev");
+        checkout_branch(&repo_path, "dev");
+
+        let merge_sha = s
+            .merge_changes_with_mode(
+                &repo_path,
+                &worktree_path,
+                "feature",
+                "main",
+                "chore: merge feature",
+                mode,
+            )
+            .unwrap();
+
+        let repo = Repository::open(&repo_path).unwrap();
+        let main_tip = repo
+            .find_branch("main", git2::BranchType::Local)
+            .unwrap()
+            .get()
+            .peel_to_commit()
+            .unwrap();
+        assert_eq!(main_tip.id().to_string(), merge_sha);
+        match mode {
+            MergeMode::MergeCommit => {
+                assert_eq!(main_tip.parent_count(), 2);
+                assert_eq!(main_tip.parent_id(1).unwrap(), feature_tip);
+                assert_eq!(main_tip.message(), Some("chore: merge feature"));
+            }
+            _ => assert_eq!(main_tip.id(), feature_tip),
+        }
+    }
+}
+
+#[test]
+fn rebase_mode_replays_task_commits_onto_moved_base() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    write_file(&repo_path, "base.txt", "base\n");
+    s.commit(&repo_path, "init").unwrap();
+
+    create_branch(&repo_path, "feature");
+    let worktree_path = td.path().join("wt");
+    s.add_worktree(&repo_path, &worktree_path, "feature", false)
+        .unwrap();
+    write_file(&worktree_path, "feature.txt", "feature\n");
+    s.commit(&worktree_path, "feat: add feature").unwrap();
+
+    // Move main on while it is checked out, so the task branch falls behind
+    write_file(&repo_path, "main.txt", "main\n");
+    s.commit(&repo_path, "chore: move main").unwrap();
+    let main_before = s.get_head_info(&repo_path).unwrap().oid;
+
+    let merge_sha = s
+        .merge_changes_with_mode(
+            &repo_path,
+            &worktree_path,
+            "feature",
+            "main",
+            "unused",
+            MergeMode::Rebase,
+        )
+        .unwrap();
+
+    let repo = Repository::open(&repo_path).unwrap();
+    let main_tip = repo
+        .find_branch("main", git2::BranchType::Local)
+        .unwrap()
+        .get()
+        .peel_to_commit()
+        .unwrap();
+    assert_eq!(main_tip.id().to_string(), merge_sha);
+    assert_eq!(main_tip.summary(), Some("feat: add feature"));
+    assert_eq!(main_tip.parent_id(0).unwrap().to_string(), main_before);
+    assert!(worktree_path.join("main.txt").exists());
+}
+
+#[test]
+fn worktree_snapshot_restores_uncommitted_changes() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    write_file(&repo_path, "tracked.txt", "v1\n");
+    write_file(&repo_path, "removed.txt", "keep me\n");
+    s.commit(&repo_path, "init").unwrap();
+
+    create_branch(&repo_path, "feature");
+    let worktree_path = td.path().join("wt");
+    s.add_worktree(&repo_path, &worktree_path, "feature", false)
+        .unwrap();
+    write_file(&worktree_path, "tracked.txt", "v2\n");
+    write_file(&worktree_path, "untracked.txt", "new\n");
+    fs::remove_file(worktree_path.join("removed.txt")).unwrap();
+
+    let refname = "refs/vibe-kanban/checkpoints/test";
+    let (head, snapshot) = s
+        .snapshot_worktree(&worktree_path, refname, "Checkpoint: test")
+        .unwrap();
+    // Taking the snapshot leaves the worktree and index alone
+    assert!(worktree_path.join("untracked.txt").exists());
+    let status = GitCli::new().get_worktree_status(&worktree_path).unwrap();
+    assert!(status.entries.iter().any(|e| e.is_untracked));
+
+    // The agent keeps going and commits something else
+    write_file(&worktree_path, "tracked.txt", "v3\n");
+    write_file(&worktree_path, "later.txt", "later\n");
+    s.commit(&worktree_path, "later").unwrap();
+    write_file(&worktree_path, "scratch.txt", "scratch\n");
+
+    s.restore_worktree_snapshot(&worktree_path, &head, &snapshot)
+        .unwrap();
+
+    let repo = Repository::open(&worktree_path).unwrap();
+    assert_eq!(
+        repo.head()
+            .unwrap()
+            .peel_to_commit()
+            .unwrap()
+            .id()
+            .to_string(),
+        head
+    );
+    assert_eq!(
+        fs::read_to_string(worktree_path.join("tracked.txt")).unwrap(),
+        "v2\n"
+    );
+    assert!(worktree_path.join("untracked.txt").exists());
+    assert!(!worktree_path.join("removed.txt").exists());
+    assert!(!worktree_path.join("later.txt").exists());
+    assert!(!worktree_path.join("scratch.txt").exists());
+    assert!(
+        Repository::open(&repo_path)
+            .unwrap()
+            .refname_to_id(refname)
+            .is_ok()
+    );
+
+    let diffs = s
+        .get_diffs(
+            DiffTarget::Commits {
+                repo_path: &repo_path,
+                from_sha: &head,
+                to_sha: &snapshot,
+            },
+            None,
+        )
+        .unwrap();
+    assert_eq!(diffs.len(), 3);
+
+    s.delete_ref(&repo_path, refname).unwrap();
+    assert!(
+        Repository::open(&repo_path)
+            .unwrap()
+            .refname_to_id(refname)
+            .is_err()
+    );
+}
+
+#[test]
+fn reword_branch_head_only_when_branch_did_not_move() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+
+    write_file(&repo_path, "foo.txt", "hello\n");
+    s.commit(&repo_path, "chore: add foo").unwrap();
+    let original = s.get_branch_oid(&repo_path, "main").unwrap();
+
+    let reworded = s
+        .reword_branch_head(&repo_path, "main", &original, "feat: greet the world")
+        .unwrap()
+        .unwrap();
+    assert_ne!(reworded, original);
+    assert_eq!(s.get_branch_oid(&repo_path, "main").unwrap(), reworded);
+    assert_eq!(
+        s.get_commit_subject(&repo_path, &reworded).unwrap(),
+        "feat: greet the world"
+    );
+    // Same content, so the checked out worktree stays clean
+    assert!(s.is_worktree_clean(&repo_path).unwrap());
+
+    // A commit made in the meantime is never rewritten
+    write_file(&repo_path, "bar.txt", "bar\n");
+    s.commit(&repo_path, "chore: add bar").unwrap();
+    let head = s.get_branch_oid(&repo_path, "main").unwrap();
+    assert!(
+        s.reword_branch_head(&repo_path, "main", &reworded, "feat: too late")
+            .unwrap()
+            .is_none()
+    );
+    assert_eq!(s.get_branch_oid(&repo_path, "main").unwrap(), head);
+}
+
+#[test]
+fn branch_recreated_from_patch_keeps_commits() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+
+    create_branch(&repo_path, "feature");
+    checkout_branch(&repo_path, "feature");
+    write_file(&repo_path, "foo.txt", "hello\n");
+    s.commit(&repo_path, "feat: add foo").unwrap();
+    checkout_branch(&repo_path, "main");
+    let patch = s.branch_patch(&repo_path, "feature", "main").unwrap();
+
+    s.create_branch_from_patch(
+        &repo_path,
+        "feature-2",
+        "main",
+        &patch,
+        &td.path().join("scratch"),
+    )
+    .unwrap();
+    let head = s.get_branch_oid(&repo_path, "feature-2").unwrap();
+    assert_eq!(
+        s.get_commit_subject(&repo_path, &head).unwrap(),
+        "feat: add foo"
+    );
+    // The scratch worktree is gone again
+    assert!(!td.path().join("scratch").exists());
+
+    // A series that does not apply leaves no branch behind
+    assert!(
+        s.create_branch_from_patch(
+            &repo_path,
+            "feature-3",
+            "main",
+            "not a patch\n",
+            &td.path().join("scratch"),
+        )
+        .is_err()
+    );
+    assert!(!s.check_branch_exists(&repo_path, "feature-3").unwrap());
+}
@@ -9,6 +9,16 @@ import {
   DialogTitle,
 } from '@/components/ui/dialog';
 import { Button } from '@/components/ui/button';
+import { Label } from '@/components/ui/label';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
+import { useQuery } from '@tanstack/react-query';
+import { pipelineTemplatesApi } from '@/lib/api';
 import RepoBranchSelector from '@/components/tasks/RepoBranchSelector';
 import { ExecutorProfileSelector } from '@/components/settings';
 import { useAttemptCreation } from '@/hooks/useAttemptCreation';
@@ -28,6 +38,8 @@ import { defineModal } from '@/lib/modals';
 import type { ExecutorProfileId, BaseCodingAgent } from 'shared/types';
 import { useKeySubmitTask, Scope } from '@/keyboard';
 
+const NO_PIPELINE = 'none';
+
 export interface CreateAttemptDialogProps {
   taskId: string;
 }
@@ -50,6 +62,15 @@ const CreateAttemptDialogImpl = NiceModal.create<CreateAttemptDialogProps>(
 
     const [userSelectedProfile, setUserSelectedProfile] =
       useState<ExecutorProfileId | null>(null);
+    const [pipelineTemplateId, setPipelineTemplateId] = useState<
+      string | null
+    >(null);
+
+    const { data: pipelineTemplates = [] } = useQuery({
+      queryKey: ['pipelineTemplates'],
+      queryFn: () => pipelineTemplatesApi.list(),
+      enabled: modal.visible,
+    });
 
     const { data: attempts = [], isLoading: isLoadingAttempts } =
       useTaskAttemptsWithSessions(taskId, {
@@ -94,6 +115,7 @@ const CreateAttemptDialogImpl = NiceModal.create<CreateAttemptDialogProps>(
     useEffect(() => {
       if (!modal.visible) {
         setUserSelectedProfile(null);
+        setPipelineTemplateId(null);
         resetBranchSelection();
       }
     }, [modal.visible, resetBranchSelection]);
@@ -152,6 +174,7 @@ const CreateAttemptDialogImpl = NiceModal.create<CreateAttemptDialogProps>(
         await createAttempt({
           profile: effectiveProfile,
           repos,
+          pipelineTemplateId,
         });
 
         modal.hide();
@@ -192,6 +215,34 @@ const CreateAttemptDialogImpl = NiceModal.create<CreateAttemptDialogProps>(
               </div>
             )}
 
+            {pipelineTemplates.length > 0 && (
+              <div className="space-y-2">
+                <Label htmlFor="pipeline-select">
+                  {t('createAttemptDialog.pipeline')}
+                </Label>
+                <Select
+                  value={pipelineTemplateId ?? NO_PIPELINE}
+                  onValueChange={(value) =>
+                    setPipelineTemplateId(value === NO_PIPELINE ? null : value)
+                  }
+                >
+                  <SelectTrigger id="pipeline-select">
+                    <SelectValue />
+                  </SelectTrigger>
+                  <SelectContent>
+                    <SelectItem value={NO_PIPELINE}>
+                      {t('createAttemptDialog.noPipeline')}
+                    </SelectItem>
+                    {pipelineTemplates.map((template) => (
+                      <SelectItem key={template.id} value={template.id}>
+                        {template.name}
+                      </SelectItem>
+                    ))}
+                  </SelectContent>
+                </Select>
+              </div>
+            )}
+
             <RepoBranchSelector
               configs={repoBranchConfigs}
               onBranchChange={setRepoBranch}
@@ -1,5 +1,10 @@
-import { Link, useLocation, useSearchParams } from 'react-router-dom';
-import { useCallback } from 'react';
+import {
+  Link,
+  useLocation,
+  useNavigate,
+  useSearchParams,
+} from 'react-router-dom';
+import { useCallback, useRef } from 'react';
 import { siDiscord } from 'simple-icons';
 import { Button } from '@/components/ui/button';
 import {
@@ -17,6 +22,7 @@ import {
   MessageCircle,
   Menu,
   Plus,
+  Upload,
   LogOut,
   LogIn,
 } from 'lucide-react';
@@ -39,7 +45,7 @@ import {
 } from '@/components/ui/tooltip';
 import { OAuthDialog } from '@/components/dialogs/global/OAuthDialog';
 import { useUserSystem } from '@/components/ConfigProvider';
-import { oauthApi } from '@/lib/api';
+import { oauthApi, tasksApi } from '@/lib/api';
 
 const INTERNAL_NAV = [{ label: 'Projects', icon: FolderOpen, to: '/projects' }];
 
@@ -73,6 +79,8 @@ function NavDivider() {
 
 export function Navbar() {
   const location = useLocation();
+  const navigate = useNavigate();
+  const importInputRef = useRef<HTMLInputElement>(null);
   const [searchParams, setSearchParams] = useSearchParams();
   const { projectId, project } = useProject();
   const { query, setQuery, active, clear, registerInputRef } = useSearch();
@@ -115,6 +123,31 @@ export function Navbar() {
     }
   };
 
+  const handleImportTask = async (e: React.ChangeEvent<HTMLInputElement>) => {
+    const file = e.target.files?.[0];
+    e.target.value = '';
+    if (!projectId || !file) return;
+    try {
+      const { task, unmatched_repos, unrestored_branches } =
+        await tasksApi.importArchive(projectId, file);
+      if (unmatched_repos.length > 0) {
+        console.warn(
+          'Imported task without history for repositories:',
+          unmatched_repos
+        );
+      }
+      if (unrestored_branches.length > 0) {
+        console.warn(
+          'Imported task without recreating branches:',
+          unrestored_branches
+        );
+      }
+      navigate(`/projects/${projectId}/tasks/${task.id}`);
+    } catch (err) {
+      console.error('Failed to import task:', err);
+    }
+  };
+
   const handleOpenInIDE = () => {
     handleOpenInEditor();
   };
@@ -228,6 +261,22 @@ export function Navbar() {
                   >
                     <Plus className="h-4 w-4" />
                   </Button>
+                  <Button
+                    variant="ghost"
+                    size="icon"
+                    className="h-9 w-9"
+                    onClick={() => importInputRef.current?.click()}
+                    aria-label="Import task"
+                  >
+                    <Upload className="h-4 w-4" />
+                  </Button>
+                  <input
+                    ref={importInputRef}
+                    type="file"
+                    accept=".tar.gz,.tgz,application/gzip"
+                    className="hidden"
+                    onChange={handleImportTask}
+                  />
                 </div>
                 <NavDivider />
               </>
@@ -14,7 +14,11 @@ import { ProfileVariantBadge } from '@/components/common/ProfileVariantBadge.tsx
 import { useExecutionProcesses } from '@/hooks/useExecutionProcesses';
 import { useLogStream } from '@/hooks/useLogStream';
 import { ProcessLogsViewerContent } from './ProcessLogsViewer';
-import type { ExecutionProcessStatus, ExecutionProcess } from 'shared/types';
+import type {
+  ExecutionProcessStatus,
+  ExecutionProcess,
+  PipelineStepRef,
+} from 'shared/types';
 
 import { useProcessSelection } from '@/contexts/ProcessSelectionContext';
 import { useRetryUi } from '@/contexts/RetryUiContext';
@@ -28,11 +32,21 @@ function ProcessesTab({ attemptId }: ProcessesTabProps) {
   const {
     executionProcesses,
     executionProcessesById,
+    queuePositionsById,
     isLoading: processesLoading,
     isConnected,
     error: processesError,
   } = useExecutionProcesses(attemptId ?? '', { showSoftDeleted: true });
   const { selectedProcessId, setSelectedProcessId } = useProcessSelection();
+
+  const formatPipelineStep = (step: PipelineStepRef) =>
+    t('processes.pipelineStep', {
+      pipeline: step.pipeline_name,
+      step: step.step_name,
+      current: step.index + 1,
+      total: step.total,
+    });
+
   const [loadingProcessId, setLoadingProcessId] = useState<string | null>(null);
   const [localProcessDetails, setLocalProcessDetails] = useState<
     Record<string, ExecutionProcess>
@@ -74,6 +88,8 @@ function ProcessesTab({ attemptId }: ProcessesTabProps) {
         return <AlertCircle className="h-4 w-4 text-destructive" />;
       case 'killed':
         return <Square className="h-4 w-4 text-gray-500" />;
+      case 'budgetexceeded':
+        return <Square className="h-4 w-4 text-orange-500" />;
       default:
         return <Clock className="h-4 w-4 text-gray-400" />;
     }
@@ -89,6 +105,8 @@ function ProcessesTab({ attemptId }: ProcessesTabProps) {
         return 'bg-red-50 border-red-200 text-red-800';
       case 'killed':
         return 'bg-gray-50 border-gray-200 text-gray-800';
+      case 'budgetexceeded':
+        return 'bg-orange-50 border-orange-200 text-orange-800';
       default:
         return 'bg-gray-50 border-gray-200 text-gray-800';
     }
@@ -233,6 +251,13 @@ function ProcessesTab({ attemptId }: ProcessesTabProps) {
                             ) : null}
                           </p>
                         }
+                        {process.executor_action.pipeline_step && (
+                          <p className="text-sm text-muted-foreground mt-1">
+                            {formatPipelineStep(
+                              process.executor_action.pipeline_step
+                            )}
+                          </p>
+                        )}
                       </div>
                     </div>
                     <div className="text-right">
@@ -243,6 +268,14 @@ function ProcessesTab({ attemptId }: ProcessesTabProps) {
                       >
                         {process.status}
                       </span>
+                      {process.status === 'queued' &&
+                        queuePositionsById[process.id] && (
+                          <p className="text-xs text-muted-foreground mt-1">
+                            {t('processes.queuePosition', {
+                              position: queuePositionsById[process.id].position,
+                            })}
+                          </p>
+                        )}
                       {process.exit_code !== null && (
                         <p className="text-xs text-muted-foreground mt-1">
                           {t('processes.exit', {
@@ -87,6 +87,7 @@ export function NoServerContent({
           dev_script: script,
           dev_script_working_dir: project.dev_script_working_dir ?? null,
           default_agent_working_dir: project.default_agent_working_dir ?? null,
+          sandbox_config: null,
         },
       },
       {
@@ -137,6 +138,7 @@ export function NoServerContent({
       },
       executor_profile_id: config.executor_profile,
       repos,
+      pipeline_template_id: null,
     });
   };
 
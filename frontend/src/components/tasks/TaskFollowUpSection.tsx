@@ -260,9 +260,10 @@ export function TaskFollowUpSection({
   // Queue status for queuing follow-up messages while agent is running
   const {
     isQueued,
-    queuedMessage,
+    queuedMessages,
     isLoading: isQueueLoading,
     queueMessage,
+    removeQueuedMessage,
     cancelQueue,
     refresh: refreshQueueStatus,
   } = useQueueStatus(sessionId);
@@ -286,21 +287,8 @@ export function TaskFollowUpSection({
     // Refresh when a new process starts (could be queued message consumption or follow-up)
     if (processes.length > prevCount) {
       refreshQueueStatus();
-      // Re-sync local message from current scratch state
-      // If scratch was deleted, scratchData will be undefined, so localMessage becomes ''
-      setLocalMessage(scratchData?.message ?? '');
     }
-  }, [
-    isAttemptRunning,
-    workspaceId,
-    processes.length,
-    refreshQueueStatus,
-    scratchData?.message,
-  ]);
-
-  // When queued, display the queued message content so user can edit it
-  const displayMessage =
-    isQueued && queuedMessage ? queuedMessage.data.message : localMessage;
+  }, [isAttemptRunning, workspaceId, processes.length, refreshQueueStatus]);
 
   // Check if there's a pending approval - users shouldn't be able to type during approvals
   const { entries } = useEntries();
@@ -341,7 +329,7 @@ export function TaskFollowUpSection({
 
     if (isRetryActive) return false; // disable typing while retry editor is active
     if (hasPendingApproval) return false; // disable typing during approval
-    // Note: isQueued no longer blocks typing - editing auto-cancels the queue
+    // Queued messages are kept apart from the draft, so the next one can be typed
     return true;
   }, [
     workspaceId,
@@ -403,10 +391,8 @@ export function TaskFollowUpSection({
       return;
     }
 
-    // Cancel any pending debounced save and save immediately before queueing
-    // This prevents the race condition where the debounce fires after queueing
+    // Cancel any pending debounced save so it can't restore the draft after queueing
     cancelDebouncedSave();
-    await saveToScratch(localMessage, selectedVariant);
 
     // Combine all the content that would be sent (same as follow-up send)
     const parts = [
@@ -417,6 +403,12 @@ export function TaskFollowUpSection({
     ].filter(Boolean);
     const combinedMessage = parts.join('\n\n');
     await queueMessage(combinedMessage, selectedVariant);
+
+    // The message now lives in the queue, so clear the draft for the next one
+    clearComments();
+    clearClickedElements();
+    setLocalMessage('');
+    await saveToScratch('', selectedVariant);
   }, [
     localMessage,
     conflictResolutionInstructions,
@@ -424,6 +416,8 @@ export function TaskFollowUpSection({
     clickedMarkdown,
     selectedVariant,
     queueMessage,
+    clearComments,
+    clearClickedElements,
     cancelDebouncedSave,
     saveToScratch,
   ]);
@@ -433,15 +427,13 @@ export function TaskFollowUpSection({
     (e?: KeyboardEvent) => {
       e?.preventDefault();
       if (isAttemptRunning) {
-        // When running, CMD+Enter queues the message (if not already queued)
-        if (!isQueued) {
-          handleQueueMessage();
-        }
+        // When running, CMD+Enter adds the message to the queue
+        handleQueueMessage();
       } else {
         onSendFollowUp();
       }
     },
-    [isAttemptRunning, isQueued, handleQueueMessage, onSendFollowUp]
+    [isAttemptRunning, handleQueueMessage, onSendFollowUp]
   );
 
   // Ref to access setFollowUpMessage without adding it as a dependency
@@ -456,22 +448,6 @@ export function TaskFollowUpSection({
     followUpErrorRef.current = followUpError;
   }, [followUpError]);
 
-  // Refs for queue state to use in stable onChange handler
-  const isQueuedRef = useRef(isQueued);
-  useEffect(() => {
-    isQueuedRef.current = isQueued;
-  }, [isQueued]);
-
-  const cancelQueueRef = useRef(cancelQueue);
-  useEffect(() => {
-    cancelQueueRef.current = cancelQueue;
-  }, [cancelQueue]);
-
-  const queuedMessageRef = useRef(queuedMessage);
-  useEffect(() => {
-    queuedMessageRef.current = queuedMessage;
-  }, [queuedMessage]);
-
   // Handle image paste - upload to container and insert markdown
   const handlePasteFiles = useCallback(
     async (files: File[]) => {
@@ -483,24 +459,13 @@ export function TaskFollowUpSection({
           // Append markdown image to current message
           const imageMarkdown = `![${response.original_name}](${response.file_path})`;
 
-          // If queued, cancel queue and use queued message as base (same as editor change behavior)
-          if (isQueuedRef.current && queuedMessageRef.current) {
-            cancelQueueRef.current();
-            const base = queuedMessageRef.current.data.message;
-            const newMessage = base
-              ? `${base}\n\n${imageMarkdown}`
+          setLocalMessage((prev) => {
+            const newMessage = prev
+              ? `${prev}\n\n${imageMarkdown}`
               : imageMarkdown;
-            setLocalMessage(newMessage);
-            setFollowUpMessageRef.current(newMessage);
-          } else {
-            setLocalMessage((prev) => {
-              const newMessage = prev
-                ? `${prev}\n\n${imageMarkdown}`
-                : imageMarkdown;
-              setFollowUpMessageRef.current(newMessage); // Debounced save to scratch
-              return newMessage;
-            });
-          }
+            setFollowUpMessageRef.current(newMessage); // Debounced save to scratch
+            return newMessage;
+          });
         } catch (error) {
           console.error('Failed to upload image:', error);
         }
@@ -564,29 +529,17 @@ export function TaskFollowUpSection({
       const markdown = markdownBlocks.join('\n\n');
 
       // Same pattern as image paste
-      if (isQueuedRef.current && queuedMessageRef.current) {
-        cancelQueueRef.current();
-        const base = queuedMessageRef.current.data.message;
-        const newMessage = base ? `${base}\n\n${markdown}` : markdown;
-        setLocalMessage(newMessage);
+      setLocalMessage((prev) => {
+        const newMessage = prev ? `${prev}\n\n${markdown}` : markdown;
         setFollowUpMessageRef.current(newMessage);
-      } else {
-        setLocalMessage((prev) => {
-          const newMessage = prev ? `${prev}\n\n${markdown}` : markdown;
-          setFollowUpMessageRef.current(newMessage);
-          return newMessage;
-        });
-      }
+        return newMessage;
+      });
     }
   }, [workspaceId, getSelectedRepoId]);
 
   // Stable onChange handler for WYSIWYGEditor
   const handleEditorChange = useCallback(
     (value: string) => {
-      // Auto-cancel queue when user starts editing
-      if (isQueuedRef.current) {
-        cancelQueueRef.current();
-      }
       setLocalMessage(value); // Immediate update for UI responsiveness
       setFollowUpMessageRef.current(value); // Debounced save to scratch
       if (followUpErrorRef.current) setFollowUpError(null);
@@ -708,15 +661,41 @@ export function TaskFollowUpSection({
             <ClickedElementsBanner />
 
             {/* Queued message indicator */}
-            {isQueued && queuedMessage && (
-              <div className="flex items-center gap-2 text-sm text-muted-foreground bg-muted p-3 rounded-md border">
-                <Clock className="h-4 w-4 flex-shrink-0" />
-                <div className="font-medium">
-                  {t(
-                    'followUp.queuedMessage',
-                    'Message queued - will execute when current run finishes'
-                  )}
+            {isQueued && (
+              <div className="flex flex-col gap-2 text-sm text-muted-foreground bg-muted p-3 rounded-md border">
+                <div className="flex items-center gap-2">
+                  <Clock className="h-4 w-4 flex-shrink-0" />
+                  <div className="font-medium">
+                    {t(
+                      'followUp.queuedMessage',
+                      'Message queued - will execute when current run finishes'
+                    )}
+                  </div>
                 </div>
+                <ol className="flex flex-col gap-1 list-decimal pl-8">
+                  {queuedMessages.map((queued) => (
+                    <li key={queued.id}>
+                      <div className="flex items-center gap-2">
+                        <span className="flex-1 truncate">
+                          {queued.data.message}
+                        </span>
+                        <Button
+                          variant="ghost"
+                          size="sm"
+                          className="h-6 w-6 p-0"
+                          onClick={() => removeQueuedMessage(queued.id)}
+                          disabled={isQueueLoading}
+                          aria-label={t(
+                            'followUp.removeQueuedMessage',
+                            'Remove from queue'
+                          )}
+                        >
+                          <X className="h-3 w-3" />
+                        </Button>
+                      </div>
+                    </li>
+                  ))}
+                </ol>
               </div>
             )}
 
@@ -732,7 +711,7 @@ export function TaskFollowUpSection({
             >
               <WYSIWYGEditor
                 placeholder={editorPlaceholder}
-                value={displayMessage}
+                value={localMessage}
                 onChange={handleEditorChange}
                 disabled={!isEditable}
                 onPasteFiles={handlePasteFiles}
@@ -829,8 +808,8 @@ export function TaskFollowUpSection({
 
           {isAttemptRunning ? (
             <div className="flex items-center gap-2">
-              {/* Queue/Cancel Queue button when running */}
-              {isQueued ? (
+              {/* Queue button when running, plus Cancel Queue once messages are queued */}
+              {isQueued && (
                 <Button
                   onClick={cancelQueue}
                   disabled={isQueueLoading}
@@ -846,28 +825,27 @@ export function TaskFollowUpSection({
                     </>
                   )}
                 </Button>
-              ) : (
-                <Button
-                  onClick={handleQueueMessage}
-                  disabled={
-                    isQueueLoading ||
-                    (!localMessage.trim() &&
-                      !conflictResolutionInstructions &&
-                      !reviewMarkdown &&
-                      !clickedMarkdown)
-                  }
-                  size="sm"
-                >
-                  {isQueueLoading ? (
-                    <Loader2 className="animate-spin h-4 w-4 mr-2" />
-                  ) : (
-                    <>
-                      <Clock className="h-4 w-4 mr-2" />
-                      {t('followUp.queue', 'Queue')}
-                    </>
-                  )}
-                </Button>
               )}
+              <Button
+                onClick={handleQueueMessage}
+                disabled={
+                  isQueueLoading ||
+                  (!localMessage.trim() &&
+                    !conflictResolutionInstructions &&
+                    !reviewMarkdown &&
+                    !clickedMarkdown)
+                }
+                size="sm"
+              >
+                {isQueueLoading ? (
+                  <Loader2 className="animate-spin h-4 w-4 mr-2" />
+                ) : (
+                  <>
+                    <Clock className="h-4 w-4 mr-2" />
+                    {t('followUp.queue', 'Queue')}
+                  </>
+                )}
+              </Button>
               <Button
                 onClick={stopExecution}
                 disabled={isStopping}
@@ -7,6 +7,8 @@ import {
   AlertTriangle,
   CheckCircle,
   ExternalLink,
+  Loader2,
+  XCircle,
 } from 'lucide-react';
 import { Button } from '@/components/ui/button.tsx';
 import {
@@ -30,6 +32,7 @@ import { useTranslation } from 'react-i18next';
 import { useAttemptRepo } from '@/hooks/useAttemptRepo';
 import { useGitOperations } from '@/hooks/useGitOperations';
 import { useRepoBranches } from '@/hooks';
+import { useAttemptMerges } from '@/hooks/useAttemptMerges';
 
 interface GitOperationsProps {
   selectedAttempt: Workspace;
@@ -57,6 +60,7 @@ function GitOperations({
   );
   const git = useGitOperations(selectedAttempt.id, selectedRepoId ?? undefined);
   const { data: branches = [] } = useRepoBranches(selectedRepoId);
+  const { mergesById } = useAttemptMerges(selectedAttempt.id);
   const isChangingTargetBranch = git.states.changeTargetBranchPending;
 
   // Local state for git operations
@@ -307,6 +311,9 @@ function GitOperations({
 
         if (mergeInfo.hasOpenPR && mergeInfo.openPR?.type === 'pr') {
           const prMerge = mergeInfo.openPR;
+          // The stream has the latest CI state, branch status only catches up on its next poll
+          const streamed = mergesById[prMerge.id];
+          const ci = streamed?.type === 'pr' ? streamed.ci : prMerge.ci;
           return (
             <button
               onClick={() => window.open(prMerge.pr_info.url, '_blank')}
@@ -319,6 +326,29 @@ function GitOperations({
               {t('git.pr.number', {
                 number: Number(prMerge.pr_info.number),
               })}
+              {ci?.status === 'pending' && (
+                <Loader2
+                  className="h-3.5 w-3.5 animate-spin"
+                  aria-label={t('git.pr.ci.pending')}
+                />
+              )}
+              {ci?.status === 'success' && (
+                <CheckCircle
+                  className="h-3.5 w-3.5 text-emerald-600 dark:text-emerald-400"
+                  aria-label={t('git.pr.ci.success')}
+                />
+              )}
+              {ci?.status === 'failure' && (
+                <span
+                  title={t('git.pr.ci.failure', {
+                    checks: ci.failed_checks.join(', '),
+                  })}
+                >
+                  <XCircle
+                    className="h-3.5 w-3.5 text-red-600 dark:text-red-400"
+                  />
+                </span>
+              )}
               <ExternalLink className="h-3.5 w-3.5" />
             </button>
           );
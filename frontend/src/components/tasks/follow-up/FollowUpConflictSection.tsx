@@ -30,7 +30,10 @@ export function FollowUpConflictSection({
   const op = repoWithConflicts?.conflict_op ?? null;
   const openInEditor = useOpenInEditor(workspaceId);
   const repoId = repoWithConflicts?.repo_id;
-  const { abortConflicts } = useAttemptConflicts(workspaceId, repoId);
+  const { abortConflicts, resolveConflicts } = useAttemptConflicts(
+    workspaceId,
+    repoId
+  );
 
   // write using setAborting and read through abortingRef in async handlers
   const [aborting, setAborting] = useState(false);
@@ -38,6 +41,7 @@ export function FollowUpConflictSection({
   useEffect(() => {
     abortingRef.current = aborting;
   }, [aborting]);
+  const [resolving, setResolving] = useState(false);
 
   if (!repoWithConflicts) return null;
 
@@ -49,7 +53,18 @@ export function FollowUpConflictSection({
         conflictedFiles={repoWithConflicts.conflicted_files || []}
         op={op}
         onResolve={onResolve}
-        enableResolve={enableResolve && !aborting}
+        onResolveWithAgent={async () => {
+          if (!workspaceId || !enableResolve || resolving) return;
+          try {
+            setResolving(true);
+            await resolveConflicts();
+          } catch (e) {
+            console.error('Failed to start conflict resolution', e);
+          } finally {
+            setResolving(false);
+          }
+        }}
+        enableResolve={enableResolve && !aborting && !resolving}
         onOpenEditor={() => {
           if (!workspaceId) return;
           const first = repoWithConflicts.conflicted_files?.[0];
@@ -67,7 +82,7 @@ export function FollowUpConflictSection({
             setAborting(false);
           }
         }}
-        enableAbort={enableAbort && !aborting}
+        enableAbort={enableAbort && !aborting && !resolving}
       />
       {/* Conflict instructions preview (non-editable) */}
       {conflictResolutionInstructions && enableResolve && (
@@ -23,6 +23,7 @@ import { ReassignDialog } from '@/components/dialogs/tasks/ReassignDialog';
 import { StopShareTaskDialog } from '@/components/dialogs/tasks/StopShareTaskDialog';
 import { useProject } from '@/contexts/ProjectContext';
 import { openTaskForm } from '@/lib/openTaskForm';
+import { tasksApi } from '@/lib/api';
 
 import { useNavigate } from 'react-router-dom';
 import type { SharedTaskRecord } from '@/hooks/useProjectTasks';
@@ -62,6 +63,22 @@ export function ActionsDropdown({
     openTaskForm({ mode: 'duplicate', projectId, initialTask: task });
   };
 
+  const handleExport = async (e: React.MouseEvent) => {
+    e.stopPropagation();
+    if (!task) return;
+    try {
+      const blob = await tasksApi.exportArchive(task.id);
+      const url = URL.createObjectURL(blob);
+      const link = document.createElement('a');
+      link.href = url;
+      link.download = `task-${task.id.slice(0, 8)}.tar.gz`;
+      link.click();
+      URL.revokeObjectURL(url);
+    } catch (err) {
+      console.error('Failed to export task:', err);
+    }
+  };
+
   const handleDelete = async (e: React.MouseEvent) => {
     e.stopPropagation();
     if (!projectId || !task) return;
@@ -258,6 +275,9 @@ export function ActionsDropdown({
               <DropdownMenuItem disabled={!projectId} onClick={handleDuplicate}>
                 {t('actionsMenu.duplicate')}
               </DropdownMenuItem>
+              <DropdownMenuItem disabled={!task} onClick={handleExport}>
+                {t('actionsMenu.export')}
+              </DropdownMenuItem>
               <DropdownMenuItem
                 disabled={!projectId || !canEditShared}
                 onClick={handleDelete}
@@ -1,11 +1,12 @@
 import React, { createContext, useContext, useMemo } from 'react';
 import { useExecutionProcesses } from '@/hooks/useExecutionProcesses';
-import type { ExecutionProcess } from 'shared/types';
+import type { ExecutionProcess, QueuePosition } from 'shared/types';
 
 type ExecutionProcessesContextType = {
   executionProcessesAll: ExecutionProcess[];
   executionProcessesByIdAll: Record<string, ExecutionProcess>;
   isAttemptRunningAll: boolean;
+  queuePositionsById: Record<string, QueuePosition>;
 
   executionProcessesVisible: ExecutionProcess[];
   executionProcessesByIdVisible: Record<string, ExecutionProcess>;
@@ -26,6 +27,7 @@ export const ExecutionProcessesProvider: React.FC<{
   const {
     executionProcesses,
     executionProcessesById,
+    queuePositionsById,
     isAttemptRunning,
     isLoading,
     isConnected,
@@ -49,8 +51,9 @@ export const ExecutionProcessesProvider: React.FC<{
         (process) =>
           (process.run_reason === 'codingagent' ||
             process.run_reason === 'setupscript' ||
-            process.run_reason === 'cleanupscript') &&
-          process.status === 'running'
+            process.run_reason === 'cleanupscript' ||
+            process.run_reason === 'verify') &&
+          (process.status === 'running' || process.status === 'queued')
       ),
     [visible]
   );
@@ -60,6 +63,7 @@ export const ExecutionProcessesProvider: React.FC<{
       executionProcessesAll: executionProcesses,
       executionProcessesByIdAll: executionProcessesById,
       isAttemptRunningAll: isAttemptRunning,
+      queuePositionsById,
       executionProcessesVisible: visible,
       executionProcessesByIdVisible,
       isAttemptRunningVisible,
@@ -71,6 +75,7 @@ export const ExecutionProcessesProvider: React.FC<{
       executionProcesses,
       executionProcessesById,
       isAttemptRunning,
+      queuePositionsById,
       visible,
       executionProcessesByIdVisible,
       isAttemptRunningVisible,
@@ -9,6 +9,7 @@ import type {
 type CreateAttemptArgs = {
   profile: ExecutorProfileId;
   repos: WorkspaceRepoInput[];
+  pipelineTemplateId?: string | null;
 };
 
 type UseAttemptCreationArgs = {
@@ -23,11 +24,12 @@ export function useAttemptCreation({
   const queryClient = useQueryClient();
 
   const mutation = useMutation({
-    mutationFn: ({ profile, repos }: CreateAttemptArgs) =>
+    mutationFn: ({ profile, repos, pipelineTemplateId }: CreateAttemptArgs) =>
       attemptsApi.create({
         task_id: taskId,
         executor_profile_id: profile,
         repos,
+        pipeline_template_id: pipelineTemplateId ?? null,
       }),
     onSuccess: (newAttempt: Workspace) => {
       queryClient.setQueryData(
@@ -0,0 +1,40 @@
+import { useCallback } from 'react';
+import { useJsonPatchWsStream } from './useJsonPatchWsStream';
+import type { Merge } from 'shared/types';
+
+type MergesState = {
+  merges: Record<string, Merge>;
+};
+
+interface UseAttemptMergesResult {
+  mergesById: Record<string, Merge>;
+  isConnected: boolean;
+  error: string | null;
+}
+
+/**
+ * Stream the merges of a task attempt via WebSocket (JSON Patch).
+ * Server sends initial snapshot: replace /merges with an object keyed by id.
+ * Live updates arrive at /merges/<id>, e.g. when the PR monitor records a new CI status.
+ */
+export const useAttemptMerges = (
+  attemptId: string | undefined
+): UseAttemptMergesResult => {
+  const endpoint = attemptId
+    ? `/api/task-attempts/${attemptId}/merges/ws`
+    : undefined;
+
+  const initialData = useCallback((): MergesState => ({ merges: {} }), []);
+
+  const { data, isConnected, error } = useJsonPatchWsStream<MergesState>(
+    endpoint,
+    !!attemptId,
+    initialData
+  );
+
+  return {
+    mergesById: data?.merges ?? {},
+    isConnected,
+    error,
+  };
+};
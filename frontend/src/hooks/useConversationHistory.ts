@@ -118,6 +118,7 @@ export const useConversationHistory = ({
       (ep) =>
         ep.run_reason === 'setupscript' ||
         ep.run_reason === 'cleanupscript' ||
+        ep.run_reason === 'verify' ||
         ep.run_reason === 'codingagent'
     );
   }, [executionProcessesRaw]);
@@ -279,7 +280,8 @@ export const useConversationHistory = ({
               liveProcessStatus === ExecutionProcessStatus.running;
             const processFailedOrKilled =
               liveProcessStatus === ExecutionProcessStatus.failed ||
-              liveProcessStatus === ExecutionProcessStatus.killed;
+              liveProcessStatus === ExecutionProcessStatus.killed ||
+              liveProcessStatus === ExecutionProcessStatus.budgetexceeded;
 
             if (isProcessRunning) {
               hasRunningProcess = true;
@@ -315,7 +317,7 @@ export const useConversationHistory = ({
           } else if (
             p.executionProcess.executor_action.typ.type === 'ScriptRequest'
           ) {
-            // Add setup and cleanup script as a tool call
+            // Add setup, cleanup and verify scripts as a tool call
             let toolName = '';
             switch (p.executionProcess.executor_action.typ.context) {
               case 'SetupScript':
@@ -327,6 +329,9 @@ export const useConversationHistory = ({
               case 'ToolInstallScript':
                 toolName = 'Tool Install Script';
                 break;
+              case 'VerifyScript':
+                toolName = 'Verify Script';
+                break;
               default:
                 return [];
             }
@@ -341,7 +346,9 @@ export const useConversationHistory = ({
 
             if (
               (executionProcess?.status === ExecutionProcessStatus.failed ||
-                executionProcess?.status === ExecutionProcessStatus.killed) &&
+                executionProcess?.status === ExecutionProcessStatus.killed ||
+                executionProcess?.status ===
+                  ExecutionProcessStatus.budgetexceeded) &&
               index === Object.keys(executionProcessState).length - 1
             ) {
               lastProcessFailedOrKilled = true;
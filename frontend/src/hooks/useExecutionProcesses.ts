@@ -1,14 +1,16 @@
 import { useCallback } from 'react';
 import { useJsonPatchWsStream } from './useJsonPatchWsStream';
-import type { ExecutionProcess } from 'shared/types';
+import type { ExecutionProcess, QueuePosition } from 'shared/types';
 
 type ExecutionProcessState = {
   execution_processes: Record<string, ExecutionProcess>;
+  queue_positions: Record<string, QueuePosition>;
 };
 
 interface UseExecutionProcessesResult {
   executionProcesses: ExecutionProcess[];
   executionProcessesById: Record<string, ExecutionProcess>;
+  queuePositionsById: Record<string, QueuePosition>;
   isAttemptRunning: boolean;
   isLoading: boolean;
   isConnected: boolean;
@@ -19,6 +21,7 @@ interface UseExecutionProcessesResult {
  * Stream execution processes for a task attempt via WebSocket (JSON Patch) and expose as array + map.
  * Server sends initial snapshot: replace /execution_processes with an object keyed by id.
  * Live updates arrive at /execution_processes/<id> via add/replace/remove operations.
+ * Queued coding agents also get /queue_positions/<id> entries until they start.
  */
 export const useExecutionProcesses = (
   taskAttemptId: string | undefined,
@@ -36,7 +39,10 @@ export const useExecutionProcesses = (
   }
 
   const initialData = useCallback(
-    (): ExecutionProcessState => ({ execution_processes: {} }),
+    (): ExecutionProcessState => ({
+      execution_processes: {},
+      queue_positions: {},
+    }),
     []
   );
 
@@ -48,6 +54,7 @@ export const useExecutionProcesses = (
     );
 
   const executionProcessesById = data?.execution_processes ?? {};
+  const queuePositionsById = data?.queue_positions ?? {};
   const executionProcesses = Object.values(executionProcessesById).sort(
     (a, b) =>
       new Date(a.created_at as unknown as string).getTime() -
@@ -57,14 +64,16 @@ export const useExecutionProcesses = (
     (process) =>
       (process.run_reason === 'codingagent' ||
         process.run_reason === 'setupscript' ||
-        process.run_reason === 'cleanupscript') &&
-      process.status === 'running'
+        process.run_reason === 'cleanupscript' ||
+        process.run_reason === 'verify') &&
+      (process.status === 'running' || process.status === 'queued')
   );
   const isLoading = !!taskAttemptId && !data && !error; // until first snapshot
 
   return {
     executionProcesses,
     executionProcessesById,
+    queuePositionsById,
     isAttemptRunning,
     isLoading,
     isConnected,
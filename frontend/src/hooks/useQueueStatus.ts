@@ -5,15 +5,17 @@ import type { QueueStatus, QueuedMessage } from 'shared/types';
 interface UseQueueStatusResult {
   /** Current queue status */
   queueStatus: QueueStatus;
-  /** Whether a message is currently queued */
+  /** Whether any message is currently queued */
   isQueued: boolean;
-  /** The queued message if any */
-  queuedMessage: QueuedMessage | null;
+  /** The queued messages in the order they will be sent */
+  queuedMessages: QueuedMessage[];
   /** Whether an operation is in progress */
   isLoading: boolean;
-  /** Queue a new message */
+  /** Add a message to the end of the queue */
   queueMessage: (message: string, variant: string | null) => Promise<void>;
-  /** Cancel the queued message */
+  /** Remove a single queued message */
+  removeQueuedMessage: (messageId: string) => Promise<void>;
+  /** Cancel every queued message */
   cancelQueue: () => Promise<void>;
   /** Refresh the queue status from the server */
   refresh: () => Promise<void>;
@@ -49,6 +51,20 @@ export function useQueueStatus(sessionId?: string): UseQueueStatusResult {
     [sessionId]
   );
 
+  const removeQueuedMessage = useCallback(
+    async (messageId: string) => {
+      if (!sessionId) return;
+      setIsLoading(true);
+      try {
+        const status = await queueApi.remove(sessionId, messageId);
+        setQueueStatus(status);
+      } finally {
+        setIsLoading(false);
+      }
+    },
+    [sessionId]
+  );
+
   const cancelQueue = useCallback(async () => {
     if (!sessionId) return;
     setIsLoading(true);
@@ -69,17 +85,16 @@ export function useQueueStatus(sessionId?: string): UseQueueStatusResult {
     }
   }, [sessionId, refresh]);
 
-  const isQueued = queueStatus.status === 'queued';
-  const queuedMessage = isQueued
-    ? (queueStatus as Extract<QueueStatus, { status: 'queued' }>).message
-    : null;
+  const queuedMessages =
+    queueStatus.status === 'queued' ? queueStatus.messages : [];
 
   return {
     queueStatus,
-    isQueued,
-    queuedMessage,
+    isQueued: queuedMessages.length > 0,
+    queuedMessages,
     isLoading,
     queueMessage,
+    removeQueuedMessage,
     cancelQueue,
     refresh,
   };
@@ -19,5 +19,7 @@ export function useScriptPlaceholders(): ScriptPlaceholders {
     dev: '#!/bin/bash\nnpm run dev\n# Add dev server start command here...',
     cleanup:
       '#!/bin/bash\n# Add cleanup commands here...\n# This runs after coding agent execution',
+    verify:
+      '#!/bin/bash\nnpm test\n# A non-zero exit code fails the coding agent turn',
   };
 }
@@ -33,6 +33,31 @@ import {
   UpdateProject,
   UpdateTask,
   UpdateTag,
+  PipelineTemplate,
+  CreatePipelineTemplate,
+  UpdatePipelineTemplate,
+  Webhook,
+  CreateWebhook,
+  UpdateWebhook,
+  WebhookDelivery,
+  AttemptGroup,
+  AttemptComparison,
+  CreateAttemptGroupBody,
+  CreateAttemptGroupResponse,
+  PickAttemptGroupWinnerBody,
+  TaskSchedule,
+  CreateTaskSchedule,
+  UpdateTaskSchedule,
+  ApprovalRule,
+  CreateApprovalRule,
+  UpdateApprovalRule,
+  WorkspaceCheckpoint,
+  CreateWorkspaceCheckpoint,
+  CheckpointRepoDiff,
+  UsageSummary,
+  BudgetLimits,
+  ImportedTask,
+  SearchHit,
   UserSystemInfo,
   McpServerQuery,
   UpdateMcpServersBody,
@@ -83,10 +108,13 @@ import {
   SharedTaskDetails,
   QueueStatus,
   PrCommentsResponse,
+  AddressPrCommentsRequest,
+  GetPrCommentsError,
   MergeTaskAttemptRequest,
   PushTaskAttemptRequest,
   RepoBranchStatus,
   AbortConflictsRequest,
+  ResolveConflictsRequest,
   Session,
   Workspace,
 } from 'shared/types';
@@ -332,6 +360,27 @@ export const projectsApi = {
     return handleApiResponse<Repo[]>(response);
   },
 
+  getUsage: async (projectId: string): Promise<UsageSummary> => {
+    const response = await makeRequest(`/api/projects/${projectId}/usage`);
+    return handleApiResponse<UsageSummary>(response);
+  },
+
+  getBudget: async (projectId: string): Promise<BudgetLimits> => {
+    const response = await makeRequest(`/api/projects/${projectId}/budget`);
+    return handleApiResponse<BudgetLimits>(response);
+  },
+
+  setBudget: async (
+    projectId: string,
+    data: BudgetLimits
+  ): Promise<BudgetLimits> => {
+    const response = await makeRequest(`/api/projects/${projectId}/budget`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<BudgetLimits>(response);
+  },
+
   addRepository: async (
     projectId: string,
     data: CreateProjectRepo
@@ -392,6 +441,27 @@ export const tasksApi = {
     return handleApiResponse<Task>(response);
   },
 
+  getUsage: async (taskId: string): Promise<UsageSummary> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/usage`);
+    return handleApiResponse<UsageSummary>(response);
+  },
+
+  getBudget: async (taskId: string): Promise<BudgetLimits> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/budget`);
+    return handleApiResponse<BudgetLimits>(response);
+  },
+
+  setBudget: async (
+    taskId: string,
+    data: BudgetLimits
+  ): Promise<BudgetLimits> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/budget`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<BudgetLimits>(response);
+  },
+
   create: async (data: CreateTask): Promise<Task> => {
     const response = await makeRequest(`/api/tasks`, {
       method: 'POST',
@@ -432,6 +502,34 @@ export const tasksApi = {
     return handleApiResponse<ShareTaskResponse>(response);
   },
 
+  exportArchive: async (taskId: string): Promise<Blob> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/export`);
+    if (!response.ok) {
+      const errorText = await response.text();
+      throw new ApiError(
+        `Failed to export task: ${errorText}`,
+        response.status,
+        response
+      );
+    }
+    return response.blob();
+  },
+
+  importArchive: async (
+    projectId: string,
+    file: File
+  ): Promise<ImportedTask> => {
+    const response = await makeRequest(
+      `/api/tasks/import?project_id=${encodeURIComponent(projectId)}`,
+      {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/gzip' },
+        body: file,
+      }
+    );
+    return handleApiResponse<ImportedTask>(response);
+  },
+
   reassign: async (
     sharedTaskId: string,
     data: { new_assignee_user_id: string | null }
@@ -685,6 +783,20 @@ export const attemptsApi = {
     return handleApiResponse<void>(response);
   },
 
+  resolveConflicts: async (
+    attemptId: string,
+    data: ResolveConflictsRequest
+  ): Promise<ExecutionProcess> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/conflicts/resolve`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ExecutionProcess>(response);
+  },
+
   createPR: async (
     attemptId: string,
     data: CreateGitHubPrRequest
@@ -753,6 +865,20 @@ export const attemptsApi = {
     );
     return handleApiResponse<PrCommentsResponse>(response);
   },
+
+  addressPrComments: async (
+    attemptId: string,
+    data: AddressPrCommentsRequest
+  ): Promise<ExecutionProcess> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/pr/address-comments`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ExecutionProcess, GetPrCommentsError>(response);
+  },
 };
 
 // Execution Process APIs
@@ -896,6 +1022,285 @@ export const tagsApi = {
   },
 };
 
+export const pipelineTemplatesApi = {
+  list: async (): Promise<PipelineTemplate[]> => {
+    const response = await makeRequest('/api/pipeline-templates');
+    return handleApiResponse<PipelineTemplate[]>(response);
+  },
+
+  create: async (data: CreatePipelineTemplate): Promise<PipelineTemplate> => {
+    const response = await makeRequest('/api/pipeline-templates', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<PipelineTemplate>(response);
+  },
+
+  update: async (
+    templateId: string,
+    data: UpdatePipelineTemplate
+  ): Promise<PipelineTemplate> => {
+    const response = await makeRequest(
+      `/api/pipeline-templates/${templateId}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<PipelineTemplate>(response);
+  },
+
+  delete: async (templateId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/pipeline-templates/${templateId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+};
+
+export const webhooksApi = {
+  list: async (): Promise<Webhook[]> => {
+    const response = await makeRequest('/api/webhooks');
+    return handleApiResponse<Webhook[]>(response);
+  },
+
+  create: async (data: CreateWebhook): Promise<Webhook> => {
+    const response = await makeRequest('/api/webhooks', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Webhook>(response);
+  },
+
+  update: async (webhookId: string, data: UpdateWebhook): Promise<Webhook> => {
+    const response = await makeRequest(`/api/webhooks/${webhookId}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Webhook>(response);
+  },
+
+  delete: async (webhookId: string): Promise<void> => {
+    const response = await makeRequest(`/api/webhooks/${webhookId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  getDeliveries: async (
+    webhookId: string,
+    limit?: number
+  ): Promise<WebhookDelivery[]> => {
+    const query = limit ? `?limit=${limit}` : '';
+    const response = await makeRequest(
+      `/api/webhooks/${webhookId}/deliveries${query}`
+    );
+    return handleApiResponse<WebhookDelivery[]>(response);
+  },
+};
+
+// Scheduled and recurring tasks
+export const taskSchedulesApi = {
+  list: async (projectId?: string): Promise<TaskSchedule[]> => {
+    const query = projectId ? `?project_id=${projectId}` : '';
+    const response = await makeRequest(`/api/task-schedules${query}`);
+    return handleApiResponse<TaskSchedule[]>(response);
+  },
+
+  create: async (data: CreateTaskSchedule): Promise<TaskSchedule> => {
+    const response = await makeRequest('/api/task-schedules', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<TaskSchedule>(response);
+  },
+
+  update: async (
+    taskScheduleId: string,
+    data: UpdateTaskSchedule
+  ): Promise<TaskSchedule> => {
+    const response = await makeRequest(
+      `/api/task-schedules/${taskScheduleId}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<TaskSchedule>(response);
+  },
+
+  delete: async (taskScheduleId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/task-schedules/${taskScheduleId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Approval rules deciding tool calls of a project's agents before a human is asked
+export const approvalRulesApi = {
+  list: async (projectId: string): Promise<ApprovalRule[]> => {
+    const response = await makeRequest(
+      `/api/approval-rules?project_id=${projectId}`
+    );
+    return handleApiResponse<ApprovalRule[]>(response);
+  },
+
+  create: async (data: CreateApprovalRule): Promise<ApprovalRule> => {
+    const response = await makeRequest('/api/approval-rules', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ApprovalRule>(response);
+  },
+
+  update: async (
+    approvalRuleId: string,
+    data: UpdateApprovalRule
+  ): Promise<ApprovalRule> => {
+    const response = await makeRequest(
+      `/api/approval-rules/${approvalRuleId}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ApprovalRule>(response);
+  },
+
+  delete: async (approvalRuleId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/approval-rules/${approvalRuleId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Best-of-N attempts: one task run by several executors side by side
+export const attemptGroupsApi = {
+  create: async (
+    data: CreateAttemptGroupBody
+  ): Promise<CreateAttemptGroupResponse> => {
+    const response = await makeRequest('/api/attempt-groups', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<CreateAttemptGroupResponse>(response);
+  },
+
+  getAll: async (taskId: string): Promise<AttemptGroup[]> => {
+    const response = await makeRequest(`/api/attempt-groups?task_id=${taskId}`);
+    return handleApiResponse<AttemptGroup[]>(response);
+  },
+
+  getComparison: async (attemptGroupId: string): Promise<AttemptComparison> => {
+    const response = await makeRequest(
+      `/api/attempt-groups/${attemptGroupId}/comparison`
+    );
+    return handleApiResponse<AttemptComparison>(response);
+  },
+
+  pickWinner: async (
+    attemptGroupId: string,
+    data: PickAttemptGroupWinnerBody
+  ): Promise<AttemptComparison> => {
+    const response = await makeRequest(
+      `/api/attempt-groups/${attemptGroupId}/winner`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<AttemptComparison>(response);
+  },
+};
+
+// Named snapshots of an attempt's worktrees, uncommitted changes included
+export const checkpointsApi = {
+  getAll: async (attemptId: string): Promise<WorkspaceCheckpoint[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/checkpoints`
+    );
+    return handleApiResponse<WorkspaceCheckpoint[]>(response);
+  },
+
+  create: async (
+    attemptId: string,
+    data: CreateWorkspaceCheckpoint
+  ): Promise<WorkspaceCheckpoint> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/checkpoints`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<WorkspaceCheckpoint>(response);
+  },
+
+  /** Omit `toCheckpointId` to compare against the current worktrees */
+  diff: async (
+    attemptId: string,
+    fromCheckpointId: string,
+    toCheckpointId?: string
+  ): Promise<CheckpointRepoDiff[]> => {
+    const params = new URLSearchParams({ from: fromCheckpointId });
+    if (toCheckpointId) params.set('to', toCheckpointId);
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/checkpoints/diff?${params.toString()}`
+    );
+    return handleApiResponse<CheckpointRepoDiff[]>(response);
+  },
+
+  /** Returns the checkpoint of the state before the restore */
+  restore: async (
+    attemptId: string,
+    checkpointId: string
+  ): Promise<WorkspaceCheckpoint> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/checkpoints/${checkpointId}/restore`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<WorkspaceCheckpoint>(response);
+  },
+
+  delete: async (attemptId: string, checkpointId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/checkpoints/${checkpointId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Full-text search over tasks, agent turns and assistant messages
+export const searchApi = {
+  search: async (
+    q: string,
+    projectId?: string,
+    limit?: number
+  ): Promise<SearchHit[]> => {
+    const params = new URLSearchParams({ q });
+    if (projectId) params.set('project_id', projectId);
+    if (limit !== undefined) params.set('limit', String(limit));
+    const response = await makeRequest(`/api/search?${params.toString()}`);
+    return handleApiResponse<SearchHit[]>(response);
+  },
+};
+
 // MCP Servers APIs
 export const mcpServersApi = {
   load: async (query: McpServerQuery): Promise<GetMcpServerResponse> => {
@@ -1259,7 +1664,7 @@ export const scratchApi = {
 // Queue API for session follow-up messages
 export const queueApi = {
   /**
-   * Queue a follow-up message to be executed when current execution finishes
+   * Add a follow-up message to the end of the queue
    */
   queue: async (
     sessionId: string,
@@ -1273,7 +1678,58 @@ export const queueApi = {
   },
 
   /**
-   * Cancel a queued follow-up message
+   * Edit a queued follow-up message
+   */
+  update: async (
+    sessionId: string,
+    messageId: string,
+    data: { message: string; variant: string | null }
+  ): Promise<QueueStatus> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/queue/${messageId}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<QueueStatus>(response);
+  },
+
+  /**
+   * Remove a single queued follow-up message
+   */
+  remove: async (
+    sessionId: string,
+    messageId: string
+  ): Promise<QueueStatus> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/queue/${messageId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<QueueStatus>(response);
+  },
+
+  /**
+   * Reorder the queue; messageIds must list every queued message once
+   */
+  reorder: async (
+    sessionId: string,
+    messageIds: string[]
+  ): Promise<QueueStatus> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/queue/reorder`,
+      {
+        method: 'POST',
+        body: JSON.stringify({ message_ids: messageIds }),
+      }
+    );
+    return handleApiResponse<QueueStatus>(response);
+  },
+
+  /**
+   * Cancel every queued follow-up message
    */
   cancel: async (sessionId: string): Promise<QueueStatus> => {
     const response = await makeRequest(`/api/sessions/${sessionId}/queue`, {
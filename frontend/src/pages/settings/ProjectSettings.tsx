@@ -29,21 +29,40 @@ import { useScriptPlaceholders } from '@/hooks/useScriptPlaceholders';
 import { CopyFilesField } from '@/components/projects/CopyFilesField';
 import { AutoExpandingTextarea } from '@/components/ui/auto-expanding-textarea';
 import { RepoPickerDialog } from '@/components/dialogs/shared/RepoPickerDialog';
+import { ExecutorProfileSelector } from '@/components/settings';
+import { useUserSystem } from '@/components/ConfigProvider';
 import { projectsApi } from '@/lib/api';
 import { repoBranchKeys } from '@/hooks/useRepoBranches';
-import type { Project, ProjectRepo, Repo, UpdateProject } from 'shared/types';
+import type {
+  ExecutorProfileId,
+  MergeMode,
+  Project,
+  ProjectRepo,
+  Repo,
+  SandboxNetwork,
+  UpdateProject,
+} from 'shared/types';
 
 interface ProjectFormState {
   name: string;
   dev_script: string;
   dev_script_working_dir: string;
   default_agent_working_dir: string;
+  sandbox_enabled: boolean;
+  sandbox_network: SandboxNetwork;
+  sandbox_allowed_hosts: string;
+  merge_mode: MergeMode;
+  conventional_commits: boolean;
+  warm_workspace_count: number;
+  commit_message_profile: ExecutorProfileId | null;
 }
 
 interface RepoScriptsFormState {
   setup_script: string;
   parallel_setup_script: boolean;
   cleanup_script: string;
+  verify_script: string;
+  verify_max_retries: number;
   copy_files: string;
 }
 
@@ -53,6 +72,14 @@ function projectToFormState(project: Project): ProjectFormState {
     dev_script: project.dev_script ?? '',
     dev_script_working_dir: project.dev_script_working_dir ?? '',
     default_agent_working_dir: project.default_agent_working_dir ?? '',
+    sandbox_enabled: project.sandbox_config?.enabled ?? false,
+    sandbox_network: project.sandbox_config?.network ?? 'allowlist',
+    sandbox_allowed_hosts:
+      project.sandbox_config?.allowed_hosts.join('\n') ?? '',
+    merge_mode: project.merge_mode,
+    conventional_commits: project.conventional_commits,
+    warm_workspace_count: project.warm_workspace_count,
+    commit_message_profile: project.commit_message_executor_profile_id,
   };
 }
 
@@ -63,6 +90,8 @@ function projectRepoToScriptsFormState(
     setup_script: projectRepo?.setup_script ?? '',
     parallel_setup_script: projectRepo?.parallel_setup_script ?? false,
     cleanup_script: projectRepo?.cleanup_script ?? '',
+    verify_script: projectRepo?.verify_script ?? '',
+    verify_max_retries: projectRepo?.verify_max_retries ?? 0,
     copy_files: projectRepo?.copy_files ?? '',
   };
 }
@@ -115,6 +144,7 @@ export function ProjectSettings() {
 
   // Get OS-appropriate script placeholders
   const placeholders = useScriptPlaceholders();
+  const { profiles, config } = useUserSystem();
 
   // Check for unsaved changes (project name)
   const hasUnsavedProjectChanges = useMemo(() => {
@@ -397,6 +427,23 @@ export function ProjectSettings() {
         dev_script_working_dir: draft.dev_script_working_dir.trim() || null,
         default_agent_working_dir:
           draft.default_agent_working_dir.trim() || null,
+        sandbox_config: {
+          enabled: draft.sandbox_enabled,
+          network: draft.sandbox_network,
+          allowed_hosts: draft.sandbox_allowed_hosts
+            .split(/[\s,]+/)
+            .filter((host) => host.length > 0),
+          read_only_paths:
+            selectedProject.sandbox_config?.read_only_paths ?? [],
+          read_write_paths:
+            selectedProject.sandbox_config?.read_write_paths ?? [],
+        },
+        merge_mode: draft.merge_mode,
+        conventional_commits: draft.conventional_commits,
+        warm_workspace_count: draft.warm_workspace_count,
+        commit_message_executor_profile_id: draft.commit_message_profile,
+        clear_commit_message_executor_profile:
+          draft.commit_message_profile === null,
       };
 
       updateProject.mutate({
@@ -424,6 +471,8 @@ export function ProjectSettings() {
         {
           setup_script: scriptsDraft.setup_script.trim() || null,
           cleanup_script: scriptsDraft.cleanup_script.trim() || null,
+          verify_script: scriptsDraft.verify_script.trim() || null,
+          verify_max_retries: scriptsDraft.verify_max_retries,
           copy_files: scriptsDraft.copy_files.trim() || null,
           parallel_setup_script: scriptsDraft.parallel_setup_script,
         }
@@ -630,6 +679,200 @@ export function ProjectSettings() {
                 </p>
               </div>
 
+              <div className="space-y-2">
+                <Label htmlFor="warm-workspace-count">
+                  {t('settings.projects.warmWorkspaces.label')}
+                </Label>
+                <Input
+                  id="warm-workspace-count"
+                  type="number"
+                  min={0}
+                  value={draft.warm_workspace_count}
+                  onChange={(e) =>
+                    updateDraft({
+                      warm_workspace_count: Math.max(
+                        0,
+                        Math.floor(Number(e.target.value) || 0)
+                      ),
+                    })
+                  }
+                  disabled={draft.sandbox_enabled}
+                  className="w-32"
+                />
+                <p className="text-sm text-muted-foreground">
+                  {t('settings.projects.warmWorkspaces.helper')}
+                </p>
+              </div>
+
+              <div className="space-y-2">
+                <div className="flex items-center space-x-2">
+                  <Checkbox
+                    id="sandbox-enabled"
+                    checked={draft.sandbox_enabled}
+                    onCheckedChange={(checked) =>
+                      updateDraft({ sandbox_enabled: checked === true })
+                    }
+                  />
+                  <Label
+                    htmlFor="sandbox-enabled"
+                    className="text-sm font-normal cursor-pointer"
+                  >
+                    {t('settings.projects.sandbox.enabled.label')}
+                  </Label>
+                </div>
+                <p className="text-sm text-muted-foreground pl-6">
+                  {t('settings.projects.sandbox.enabled.helper')}
+                </p>
+              </div>
+
+              {draft.sandbox_enabled && (
+                <>
+                  <div className="space-y-2">
+                    <Label htmlFor="sandbox-network">
+                      {t('settings.projects.sandbox.network.label')}
+                    </Label>
+                    <Select
+                      value={draft.sandbox_network}
+                      onValueChange={(value) =>
+                        updateDraft({
+                          sandbox_network: value as SandboxNetwork,
+                        })
+                      }
+                    >
+                      <SelectTrigger id="sandbox-network">
+                        <SelectValue />
+                      </SelectTrigger>
+                      <SelectContent>
+                        <SelectItem value="allowlist">
+                          {t('settings.projects.sandbox.network.allowlist')}
+                        </SelectItem>
+                        <SelectItem value="none">
+                          {t('settings.projects.sandbox.network.none')}
+                        </SelectItem>
+                        <SelectItem value="full">
+                          {t('settings.projects.sandbox.network.full')}
+                        </SelectItem>
+                      </SelectContent>
+                    </Select>
+                    <p className="text-sm text-muted-foreground">
+                      {t('settings.projects.sandbox.network.helper')}
+                    </p>
+                  </div>
+
+                  {draft.sandbox_network === 'allowlist' && (
+                    <div className="space-y-2">
+                      <Label htmlFor="sandbox-allowed-hosts">
+                        {t('settings.projects.sandbox.allowedHosts.label')}
+                      </Label>
+                      <AutoExpandingTextarea
+                        id="sandbox-allowed-hosts"
+                        value={draft.sandbox_allowed_hosts}
+                        onChange={(e) =>
+                          updateDraft({ sandbox_allowed_hosts: e.target.value })
+                        }
+                        placeholder={t(
+                          'settings.projects.sandbox.allowedHosts.placeholder'
+                        )}
+                        maxRows={12}
+                        className="w-full px-3 py-2 border border-input bg-background text-foreground rounded-md focus:outline-none focus:ring-2 focus:ring-ring font-mono"
+                      />
+                      <p className="text-sm text-muted-foreground">
+                        {t('settings.projects.sandbox.allowedHosts.helper')}
+                      </p>
+                    </div>
+                  )}
+                </>
+              )}
+
+              <div className="space-y-2">
+                <Label htmlFor="merge-mode">
+                  {t('settings.projects.merge.mode.label')}
+                </Label>
+                <Select
+                  value={draft.merge_mode}
+                  onValueChange={(value) =>
+                    updateDraft({ merge_mode: value as MergeMode })
+                  }
+                >
+                  <SelectTrigger id="merge-mode">
+                    <SelectValue />
+                  </SelectTrigger>
+                  <SelectContent>
+                    <SelectItem value="squash">
+                      {t('settings.projects.merge.mode.squash')}
+                    </SelectItem>
+                    <SelectItem value="merge_commit">
+                      {t('settings.projects.merge.mode.mergeCommit')}
+                    </SelectItem>
+                    <SelectItem value="rebase">
+                      {t('settings.projects.merge.mode.rebase')}
+                    </SelectItem>
+                  </SelectContent>
+                </Select>
+                <p className="text-sm text-muted-foreground">
+                  {t('settings.projects.merge.mode.helper')}
+                </p>
+              </div>
+
+              <div className="space-y-2">
+                <div className="flex items-center space-x-2">
+                  <Checkbox
+                    id="conventional-commits"
+                    checked={draft.conventional_commits}
+                    onCheckedChange={(checked) =>
+                      updateDraft({ conventional_commits: checked === true })
+                    }
+                  />
+                  <Label
+                    htmlFor="conventional-commits"
+                    className="text-sm font-normal cursor-pointer"
+                  >
+                    {t('settings.projects.merge.conventionalCommits.label')}
+                  </Label>
+                </div>
+                <p className="text-sm text-muted-foreground pl-6">
+                  {t('settings.projects.merge.conventionalCommits.helper')}
+                </p>
+              </div>
+
+              {draft.conventional_commits && (
+                <div className="space-y-2">
+                  <div className="flex items-center space-x-2">
+                    <Checkbox
+                      id="commit-message-profile"
+                      checked={draft.commit_message_profile !== null}
+                      onCheckedChange={(checked) =>
+                        updateDraft({
+                          commit_message_profile:
+                            checked === true
+                              ? (config?.executor_profile ?? null)
+                              : null,
+                        })
+                      }
+                    />
+                    <Label
+                      htmlFor="commit-message-profile"
+                      className="text-sm font-normal cursor-pointer"
+                    >
+                      {t('settings.projects.merge.commitMessageProfile.label')}
+                    </Label>
+                  </div>
+                  <p className="text-sm text-muted-foreground pl-6">
+                    {t('settings.projects.merge.commitMessageProfile.helper')}
+                  </p>
+                  {draft.commit_message_profile && (
+                    <ExecutorProfileSelector
+                      profiles={profiles}
+                      selectedProfile={draft.commit_message_profile}
+                      onProfileSelect={(profile) =>
+                        updateDraft({ commit_message_profile: profile })
+                      }
+                      className="pl-6"
+                    />
+                  )}
+                </div>
+              )}
+
               {/* Save Button */}
               <div className="flex items-center justify-between pt-4 border-t">
                 {hasUnsavedProjectChanges ? (
@@ -875,6 +1118,50 @@ export function ProjectSettings() {
                         </p>
                       </div>
 
+                      <div className="space-y-2">
+                        <Label htmlFor="verify-script">
+                          {t('settings.projects.scripts.verify.label')}
+                        </Label>
+                        <AutoExpandingTextarea
+                          id="verify-script"
+                          value={scriptsDraft.verify_script}
+                          onChange={(e) =>
+                            updateScriptsDraft({
+                              verify_script: e.target.value,
+                            })
+                          }
+                          placeholder={placeholders.verify}
+                          maxRows={12}
+                          className="w-full px-3 py-2 border border-input bg-background text-foreground rounded-md focus:outline-none focus:ring-2 focus:ring-ring font-mono"
+                        />
+                        <p className="text-sm text-muted-foreground">
+                          {t('settings.projects.scripts.verify.helper')}
+                        </p>
+
+                        <Label htmlFor="verify-max-retries" className="pt-2">
+                          {t('settings.projects.scripts.verify.retriesLabel')}
+                        </Label>
+                        <Input
+                          id="verify-max-retries"
+                          type="number"
+                          min={0}
+                          value={scriptsDraft.verify_max_retries}
+                          onChange={(e) =>
+                            updateScriptsDraft({
+                              verify_max_retries: Math.max(
+                                0,
+                                Math.floor(Number(e.target.value) || 0)
+                              ),
+                            })
+                          }
+                          disabled={!scriptsDraft.verify_script.trim()}
+                          className="w-32"
+                        />
+                        <p className="text-sm text-muted-foreground">
+                          {t('settings.projects.scripts.verify.retriesHelper')}
+                        </p>
+                      </div>
+
                       <div className="space-y-2">
                         <Label>
                           {t('settings.projects.scripts.copyFiles.label')}
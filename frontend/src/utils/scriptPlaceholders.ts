@@ -2,6 +2,7 @@ interface ScriptPlaceholders {
   setup: string;
   dev: string;
   cleanup: string;
+  verify: string;
 }
 
 interface ScriptPlaceholderStrategy {
@@ -20,6 +21,9 @@ REM Add dev server start command here...`,
       cleanup: `@echo off
 REM Add cleanup commands here...
 REM This runs after coding agent execution - only if changes were made`,
+      verify: `@echo off
+npm test
+REM A non-zero exit code fails the coding agent turn`,
     };
   }
 }
@@ -36,6 +40,9 @@ npm run dev
       cleanup: `#!/bin/bash
 # Add cleanup commands here...
 # This runs after coding agent execution - only if changes were made`,
+      verify: `#!/bin/bash
+npm test
+# A non-zero exit code fails the coding agent turn`,
     };
   }
 }
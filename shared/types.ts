@@ -12,11 +12,50 @@ export type SharedTask = { id: string, organization_id: string, project_id: stri
 
 export type UserData = { user_id: string, first_name: string | null, last_name: string | null, username: string | null, };
 
-export type Project = { id: string, name: string, dev_script: string | null, dev_script_working_dir: string | null, default_agent_working_dir: string | null, remote_project_id: string | null, created_at: Date, updated_at: Date, };
+export type MergeMode = "squash" | "merge_commit" | "rebase";
+
+export type Project = { id: string, name: string, dev_script: string | null, dev_script_working_dir: string | null, default_agent_working_dir: string | null, remote_project_id: string | null, sandbox_config: SandboxConfig | null, merge_mode: MergeMode, 
+/**
+ * Agent and merge commits get Conventional Commit messages written by an executor
+ */
+conventional_commits: boolean, 
+/**
+ * Worktrees kept ready for new attempts with the setup scripts already run; 0 disables
+ * the pool
+ */
+warm_workspace_count: number, 
+/**
+ * Executor that writes commit messages; `None` uses the session's executor
+ */
+commit_message_executor_profile_id: ExecutorProfileId | null, created_at: Date, updated_at: Date, };
 
 export type CreateProject = { name: string, repositories: Array<CreateProjectRepo>, };
 
-export type UpdateProject = { name: string | null, dev_script: string | null, dev_script_working_dir: string | null, default_agent_working_dir: string | null, };
+export type UpdateProject = { name: string | null, dev_script: string | null, dev_script_working_dir: string | null, default_agent_working_dir: string | null, 
+/**
+ * `None` keeps the current sandbox settings
+ */
+sandbox_config: SandboxConfig | null, 
+/**
+ * `None` keeps the current merge mode
+ */
+merge_mode: MergeMode | null, 
+/**
+ * `None` keeps the current setting
+ */
+conventional_commits: boolean | null, 
+/**
+ * `None` keeps the current pool size
+ */
+warm_workspace_count: number | null, 
+/**
+ * `None` keeps the current profile
+ */
+commit_message_executor_profile_id: ExecutorProfileId | null, 
+/**
+ * Go back to writing commit messages with the session's executor
+ */
+clear_commit_message_executor_profile: boolean, };
 
 export type SearchResult = { path: string, is_file: boolean, match_type: SearchMatchType, };
 
@@ -24,11 +63,19 @@ export type SearchMatchType = "FileName" | "DirectoryName" | "FullPath";
 
 export type Repo = { id: string, path: string, name: string, display_name: string, created_at: Date, updated_at: Date, };
 
-export type ProjectRepo = { id: string, project_id: string, repo_id: string, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean, };
+export type ProjectRepo = { id: string, project_id: string, repo_id: string, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean, 
+/**
+ * Run after each coding agent turn; a non-zero exit fails the turn
+ */
+verify_script: string | null, 
+/**
+ * Follow-ups sent back to the agent with the failing output before giving up
+ */
+verify_max_retries: number, };
 
 export type CreateProjectRepo = { display_name: string, git_repo_path: string, };
 
-export type UpdateProjectRepo = { setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean | null, };
+export type UpdateProjectRepo = { setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean | null, verify_script: string | null, verify_max_retries: number | null, };
 
 export type WorkspaceRepo = { id: string, workspace_id: string, repo_id: string, target_branch: string, created_at: Date, updated_at: Date, };
 
@@ -42,6 +89,18 @@ export type CreateTag = { tag_name: string, content: string, };
 
 export type UpdateTag = { tag_name: string | null, content: string | null, };
 
+export type PipelineStep = { name: string, executor_profile_id: ExecutorProfileId, 
+/**
+ * Prompt template; may reference `{task}` and `{previous_summary}`
+ */
+prompt: string, };
+
+export type PipelineTemplate = { id: string, name: string, steps: Array<PipelineStep>, created_at: string, updated_at: string, };
+
+export type CreatePipelineTemplate = { name: string, steps: Array<PipelineStep>, };
+
+export type UpdatePipelineTemplate = { name: string | null, steps: Array<PipelineStep> | null, };
+
 export type TaskStatus = "todo" | "inprogress" | "inreview" | "done" | "cancelled";
 
 export type Task = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, shared_task_id: string | null, created_at: string, updated_at: string, };
@@ -54,6 +113,143 @@ export type CreateTask = { project_id: string, title: string, description: strin
 
 export type UpdateTask = { title: string | null, description: string | null, status: TaskStatus | null, parent_workspace_id: string | null, image_ids: Array<string> | null, };
 
+export type TaskDependency = { id: string, task_id: string, depends_on_task_id: string, created_at: string, };
+
+export type CreateTaskDependency = { depends_on_task_id: string, };
+
+export type TaskDependencies = { blocked_by: Array<Task>, blocks: Array<Task>, };
+
+export type WebhookEvent = "task_status_changed" | "execution_started" | "execution_completed" | "execution_failed" | "approval_requested" | "pr_created" | "pr_merged";
+
+export type Webhook = { id: string, name: string, url: string, has_secret: boolean, 
+/**
+ * Subscribed events; empty subscribes to every event
+ */
+events: Array<WebhookEvent>, enabled: boolean, created_at: string, updated_at: string, };
+
+export type CreateWebhook = { name: string, url: string, secret: string | null, events: Array<WebhookEvent>, enabled: boolean, };
+
+export type UpdateWebhook = { name: string | null, url: string | null, 
+/**
+ * An empty string removes the secret
+ */
+secret: string | null, events: Array<WebhookEvent> | null, enabled: boolean | null, };
+
+export type WebhookDeliveryStatus = "pending" | "delivered" | "failed";
+
+export type WebhookDelivery = { id: string, webhook_id: string, event: WebhookEvent, 
+/**
+ * The JSON body sent to the webhook
+ */
+payload: string, status: WebhookDeliveryStatus, attempts: bigint, next_attempt_at: string, 
+/**
+ * HTTP status of the latest attempt, if the endpoint answered
+ */
+response_status: bigint | null, last_error: string | null, created_at: string, updated_at: string, };
+
+export type AttemptGroup = { id: string, task_id: string, winner_workspace_id: string | null, created_at: string, updated_at: string, };
+
+export type AttemptGroupMember = { attempt_group_id: string, workspace_id: string, executor_profile_id: ExecutorProfileId, 
+/**
+ * Set once another attempt of the group won; the workspace is then cleaned up
+ */
+discarded_at: string | null, created_at: string, };
+
+export type TaskScheduleRepo = { repo_id: string, target_branch: string, };
+
+export type TaskSchedule = { id: string, project_id: string, name: string, 
+/**
+ * Five-field cron expression in the server's local time; `None` for a one-off run
+ */
+cron_expression: string | null, 
+/**
+ * `{{date}}`, `{{time}}` and `{{datetime}}` are replaced with the local time of the run
+ */
+title_template: string, description_template: string | null, executor_profile_id: ExecutorProfileId, 
+/**
+ * Empty starts the task on every project repo at its current branch
+ */
+repos: Array<TaskScheduleRepo>, enabled: boolean, 
+/**
+ * `None` once a one-off schedule has run
+ */
+next_run_at: string | null, last_run_at: string | null, last_task_id: string | null, created_at: string, updated_at: string, };
+
+export type CreateTaskSchedule = { project_id: string, name: string, 
+/**
+ * Exactly one of `cron_expression` and `run_at` must be set
+ */
+cron_expression: string | null, run_at: string | null, title_template: string, description_template: string | null, executor_profile_id: ExecutorProfileId, repos: Array<TaskScheduleRepo>, enabled: boolean, };
+
+export type UpdateTaskSchedule = { name: string | null, 
+/**
+ * Setting either `cron_expression` or `run_at` replaces the current timing
+ */
+cron_expression: string | null, run_at: string | null, title_template: string | null, 
+/**
+ * An empty string removes the description
+ */
+description_template: string | null, executor_profile_id: ExecutorProfileId | null, repos: Array<TaskScheduleRepo> | null, enabled: boolean | null, };
+
+export type ApprovalRuleDecision = "approve" | "deny" | "ask";
+
+export type ApprovalRule = { id: string, project_id: string, name: string, position: bigint, 
+/**
+ * Glob matched against the tool name, case-insensitively; `None` matches every tool
+ */
+tool_name: string | null, 
+/**
+ * Glob matched against the path of file reads and edits; other calls never match
+ */
+path_glob: string | null, 
+/**
+ * Regex searched for in the command of command runs; other calls never match
+ */
+command_regex: string | null, decision: ApprovalRuleDecision, 
+/**
+ * Sent to the agent when the rule denies a call
+ */
+reason: string | null, enabled: boolean, created_at: string, updated_at: string, };
+
+export type CreateApprovalRule = { project_id: string, name: string, tool_name: string | null, path_glob: string | null, command_regex: string | null, decision: ApprovalRuleDecision, reason: string | null, enabled: boolean, };
+
+export type UpdateApprovalRule = { name: string | null, 
+/**
+ * Moves the rule; rules with equal positions keep their creation order
+ */
+position: bigint | null, 
+/**
+ * An empty string removes the condition
+ */
+tool_name: string | null, 
+/**
+ * An empty string removes the condition
+ */
+path_glob: string | null, 
+/**
+ * An empty string removes the condition
+ */
+command_regex: string | null, decision: ApprovalRuleDecision | null, 
+/**
+ * An empty string removes the reason
+ */
+reason: string | null, enabled: boolean | null, };
+
+export type CheckpointRepoState = { repo_id: string, 
+/**
+ * Commit the worktree was on
+ */
+head_commit: string, 
+/**
+ * Commit on top of `head_commit` holding the worktree contents, uncommitted and
+ * untracked changes included
+ */
+snapshot_commit: string, };
+
+export type WorkspaceCheckpoint = { id: string, workspace_id: string, name: string, repo_states: Array<CheckpointRepoState>, created_at: string, };
+
+export type CreateWorkspaceCheckpoint = { name: string, };
+
 export type DraftFollowUpData = { message: string, variant: string | null, };
 
 export type ScratchPayload = { "type": "DRAFT_TASK", "data": string } | { "type": "DRAFT_FOLLOW_UP", "data": DraftFollowUpData };
@@ -66,6 +262,24 @@ export type CreateScratch = { payload: ScratchPayload, };
 
 export type UpdateScratch = { payload: ScratchPayload, };
 
+export type QueuedMessage = { id: string, session_id: string, 
+/**
+ * The follow-up data (message + variant)
+ */
+data: DraftFollowUpData, created_at: string, updated_at: string, };
+
+export type SearchSource = "task" | "turn" | "message";
+
+export type SearchHit = { source: SearchSource, project_id: string, task_id: string, task_title: string, workspace_id: string | null, execution_process_id: string | null, 
+/**
+ * Matching text with matched terms wrapped in `**`
+ */
+snippet: string, 
+/**
+ * bm25 score, lower is more relevant
+ */
+rank: number, created_at: string, };
+
 export type Image = { id: string, file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, created_at: string, updated_at: string, };
 
 export type CreateImage = { file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, };
@@ -82,17 +296,52 @@ export type ExecutionProcess = { id: string, session_id: string, run_reason: Exe
  */
 dropped: boolean, started_at: string, completed_at: string | null, created_at: string, updated_at: string, };
 
-export enum ExecutionProcessStatus { running = "running", completed = "completed", failed = "failed", killed = "killed" }
+export enum ExecutionProcessStatus { queued = "queued", running = "running", completed = "completed", failed = "failed", killed = "killed", budgetexceeded = "budgetexceeded" }
 
-export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codingagent" | "devserver";
+export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codingagent" | "devserver" | "verify";
 
 export type ExecutionProcessRepoState = { id: string, execution_process_id: string, repo_id: string, before_head_commit: string | null, after_head_commit: string | null, merge_commit: string | null, created_at: Date, updated_at: Date, };
 
+export type BudgetLimits = { 
+/**
+ * Longest a single coding agent execution may run
+ */
+max_duration_secs: bigint | null, 
+/**
+ * Most follow-ups sent to agents across the task's attempts, automatic ones included
+ */
+max_follow_ups: bigint | null, 
+/**
+ * Most input and output tokens, not counting cache reads, across the task's attempts.
+ * Only agents that report usage are limited.
+ */
+max_tokens: bigint | null, };
+
 export type Merge = { "type": "direct" } & DirectMerge | { "type": "pr" } & PrMerge;
 
 export type DirectMerge = { id: string, workspace_id: string, repo_id: string, merge_commit: string, target_branch_name: string, created_at: string, };
 
-export type PrMerge = { id: string, workspace_id: string, repo_id: string, created_at: string, target_branch_name: string, pr_info: PullRequestInfo, };
+export type PrMerge = { id: string, workspace_id: string, repo_id: string, created_at: string, target_branch_name: string, pr_info: PullRequestInfo, 
+/**
+ * CI state of the PR head, unknown until its checks report
+ */
+ci: PrCiState | null, };
+
+export type PrCiState = { status: CiStatus, head_sha: string, 
+/**
+ * Names of the failed checks
+ */
+failed_checks: Array<string>, updated_at: string, 
+/**
+ * Coding agent runs dispatched so far to fix failing checks
+ */
+fix_attempts: bigint, 
+/**
+ * Head commit the last of those runs was dispatched for
+ */
+fix_head_sha: string | null, };
+
+export type CiStatus = "pending" | "success" | "failure";
 
 export type MergeStatus = "open" | "merged" | "closed" | "unknown";
 
@@ -184,6 +433,12 @@ export type InitRepoRequest = { parent_path: string, folder_name: string, };
 
 export type TagSearchParams = { search: string | null, };
 
+export type SearchQuery = { q: string, 
+/**
+ * Restrict hits to a single project
+ */
+project_id: string | null, limit: bigint | null, };
+
 export type TokenResponse = { access_token: string, expires_at: string | null, };
 
 export type UserSystemInfo = { config: Config, analytics_user_id: string, login_status: LoginStatus, environment: Environment, 
@@ -230,7 +485,11 @@ export type AssignSharedTaskRequest = { new_assignee_user_id: string | null, };
 
 export type ShareTaskResponse = { shared_task_id: string, };
 
-export type CreateAndStartTaskRequest = { task: CreateTask, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, };
+export type CreateAndStartTaskRequest = { task: CreateTask, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, 
+/**
+ * Run the steps of this pipeline template instead of a single coding agent request
+ */
+pipeline_template_id: string | null, };
 
 export type CreateGitHubPrRequest = { title: string, body: string | null, target_branch: string | null, draft: boolean | null, repo_id: string, auto_generate_description: boolean, };
 
@@ -238,10 +497,24 @@ export type ImageResponse = { id: string, file_path: string, original_name: stri
 
 export type ImageMetadata = { exists: boolean, file_name: string | null, path: string | null, size_bytes: bigint | null, format: string | null, proxy_url: string | null, };
 
-export type CreateTaskAttemptBody = { task_id: string, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, };
+export type CreateTaskAttemptBody = { task_id: string, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, 
+/**
+ * Run the steps of this pipeline template instead of a single coding agent request
+ */
+pipeline_template_id: string | null, };
 
 export type WorkspaceRepoInput = { repo_id: string, target_branch: string, };
 
+export type CreateAttemptGroupBody = { task_id: string, 
+/**
+ * One attempt is started per profile, all on the same repos and target branches
+ */
+executor_profile_ids: Array<ExecutorProfileId>, repos: Array<WorkspaceRepoInput>, };
+
+export type CreateAttemptGroupResponse = { group: AttemptGroup, workspaces: Array<Workspace>, };
+
+export type PickAttemptGroupWinnerBody = { workspace_id: string, };
+
 export type RunAgentSetupRequest = { executor_profile_id: ExecutorProfileId, };
 
 export type RunAgentSetupResponse = Record<string, never>;
@@ -252,11 +525,13 @@ export type RebaseTaskAttemptRequest = { repo_id: string, old_base_branch: strin
 
 export type AbortConflictsRequest = { repo_id: string, };
 
+export type ResolveConflictsRequest = { repo_id: string, };
+
 export type GitOperationError = { "type": "merge_conflicts", message: string, op: ConflictOp, } | { "type": "rebase_in_progress" };
 
 export type PushError = { "type": "force_push_required" };
 
-export type CreatePrError = { "type": "github_cli_not_installed" } | { "type": "github_cli_not_logged_in" } | { "type": "git_cli_not_logged_in" } | { "type": "git_cli_not_installed" } | { "type": "target_branch_not_found", branch: string, };
+export type CreatePrError = { "type": "github_cli_not_installed" } | { "type": "github_cli_not_logged_in" } | { "type": "git_cli_not_logged_in" } | { "type": "git_cli_not_installed" } | { "type": "target_branch_not_found", branch: string, } | { "type": "bitbucket_auth_required" } | { "type": "bitbucket_auth_failed", message: string, } | { "type": "gitlab_auth_required" } | { "type": "gitlab_auth_failed", message: string, } | { "type": "unsupported_vcs_provider", message: string, };
 
 export type BranchStatus = { commits_behind: number | null, commits_ahead: number | null, has_uncommitted_changes: boolean | null, head_oid: string | null, uncommitted_count: number | null, untracked_count: number | null, target_branch_name: string, remote_commits_behind: number | null, remote_commits_ahead: number | null, merges: Array<Merge>, 
 /**
@@ -280,11 +555,26 @@ export type AttachExistingPrRequest = { repo_id: string, };
 
 export type PrCommentsResponse = { comments: Array<UnifiedPrComment>, };
 
-export type GetPrCommentsError = { "type": "no_pr_attached" } | { "type": "github_cli_not_installed" } | { "type": "github_cli_not_logged_in" };
+export type GetPrCommentsError = { "type": "no_pr_attached" } | { "type": "github_cli_not_installed" } | { "type": "github_cli_not_logged_in" } | { "type": "bitbucket_auth_required" } | { "type": "bitbucket_auth_failed", message: string, } | { "type": "gitlab_auth_required" } | { "type": "gitlab_auth_failed", message: string, } | { "type": "unsupported_vcs_provider", message: string, } | { "type": "no_comments_to_address" };
 
 export type GetPrCommentsQuery = { repo_id: string, };
 
-export type UnifiedPrComment = { "comment_type": "general", id: string, author: string, author_association: string, body: string, created_at: string, url: string, } | { "comment_type": "review", id: bigint, author: string, author_association: string, body: string, created_at: string, url: string, path: string, line: bigint | null, diff_hunk: string, };
+export type AddressPrCommentsRequest = { repo_id: string, 
+/**
+ * Keys of the comments to address, see `UnifiedPrComment::key`; all comments no earlier
+ * follow-up addressed when unset
+ */
+comment_ids: Array<string> | null, 
+/**
+ * Reply to each addressed comment with the commit that addressed it
+ */
+reply_to_comments: boolean, };
+
+export type UnifiedPrComment = { "comment_type": "general", id: string, author: string, author_association: string, body: string, created_at: string, url: string, } | { "comment_type": "review", id: bigint, author: string, author_association: string, body: string, created_at: string, url: string, path: string, line: bigint | null, diff_hunk: string, 
+/**
+ * Whether the review thread of the comment was marked as resolved
+ */
+resolved: boolean, };
 
 export type RepoBranchStatus = { repo_id: string, repo_name: string, commits_behind: number | null, commits_ahead: number | null, has_uncommitted_changes: boolean | null, head_oid: string | null, uncommitted_count: number | null, untracked_count: number | null, target_branch_name: string, remote_commits_behind: number | null, remote_commits_ahead: number | null, merges: Array<Merge>, 
 /**
@@ -304,7 +594,7 @@ export type DirectoryEntry = { name: string, path: string, is_directory: boolean
 
 export type DirectoryListResponse = { entries: Array<DirectoryEntry>, current_path: string, };
 
-export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, };
+export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, concurrency: ConcurrencyConfig, model_prices: { [key in string]?: ModelPrice }, pr_review: PrReviewConfig, ci: CiConfig, };
 
 export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, };
 
@@ -324,35 +614,128 @@ export type UiLanguage = "BROWSER" | "EN" | "JA" | "ES" | "KO" | "ZH_HANS" | "ZH
 
 export type ShowcaseState = { seen_features: Array<string>, };
 
+export type ConcurrencyConfig = { 
+/**
+ * Maximum coding agents running across all executors, `None` for unlimited
+ */
+max_concurrent_agents: number | null, 
+/**
+ * Optional per-executor caps, applied on top of the global limit
+ */
+per_executor: { [key in BaseCodingAgent]?: number }, };
+
+export type ModelPrice = { input_per_mtok: number, output_per_mtok: number, cache_write_per_mtok: number, cache_read_per_mtok: number, };
+
+export type PrReviewConfig = { 
+/**
+ * Address new review comments on open pull requests as soon as the PR monitor sees them
+ */
+auto_address_comments: boolean, 
+/**
+ * Reply to each addressed comment with the commit that addressed it
+ */
+reply_to_comments: boolean, };
+
+export type CiConfig = { 
+/**
+ * Send the logs of failed checks to the coding agent once all checks of the PR head finished
+ */
+auto_fix_failures: boolean, 
+/**
+ * Most fix runs per pull request, so an agent can't keep pushing broken fixes
+ */
+max_auto_fix_attempts: number, };
+
 export type GitBranch = { name: string, is_current: boolean, is_remote: boolean, last_commit_date: Date, };
 
 export type SharedTaskDetails = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, };
 
-export type QueuedMessage = { 
+export type QueueStatus = { "status": "empty" } | { "status": "queued", messages: Array<QueuedMessage>, };
+
+export type QueuePosition = { execution_process_id: string, session_id: string, 
 /**
- * The session this message is queued for
+ * 1-based position in the queue
  */
-session_id: string, 
+position: number, };
+
+export type UsageTotals = { input_tokens: bigint, output_tokens: bigint, cache_creation_input_tokens: bigint, cache_read_input_tokens: bigint, cost_usd: number, };
+
+export type ModelUsage = { model: string, 
 /**
- * The follow-up data (message + variant)
+ * False when no price is configured for the model, its cost is counted as zero
  */
-data: DraftFollowUpData, 
+priced: boolean, usage: UsageTotals, };
+
+export type DailyUsage = { 
 /**
- * Timestamp when the message was queued
+ * UTC date, `YYYY-MM-DD`
  */
-queued_at: string, };
+day: string, usage: UsageTotals, };
+
+export type TaskUsage = { task_id: string, usage: UsageTotals, };
+
+export type UsageSummary = { total: UsageTotals, by_model: Array<ModelUsage>, by_day: Array<DailyUsage>, by_task: Array<TaskUsage>, };
+
+export type DiffStats = { files_changed: number, additions: number, deletions: number, };
+
+export type AttemptComparisonEntry = { workspace_id: string, branch: string, executor_profile_id: ExecutorProfileId, 
+/**
+ * Status of the latest coding agent run, `None` before the agent started
+ */
+status: ExecutionProcessStatus | null, 
+/**
+ * Changes against the target branches of all repos, `None` when they could not be read
+ */
+diff: DiffStats | null, 
+/**
+ * Status of the latest verify script run, `None` when no verify script ran
+ */
+verification: ExecutionProcessStatus | null, 
+/**
+ * From the first process starting to the last one finishing, or until now while running
+ */
+duration_seconds: bigint | null, usage: UsageTotals, winner: boolean, discarded: boolean, };
+
+export type AttemptComparison = { group: AttemptGroup, attempts: Array<AttemptComparisonEntry>, };
 
-export type QueueStatus = { "status": "empty" } | { "status": "queued", message: QueuedMessage, };
+export type CheckpointRepoDiff = { repo_id: string, repo_name: string, diffs: Array<Diff>, };
+
+export type ImportedTask = { task: Task, 
+/**
+ * Repositories of the archive with no repository of the same name in the target
+ * project; their workspace repos, merges and repo states were left out
+ */
+unmatched_repos: Array<string>, 
+/**
+ * Attempt branches that could not be recreated, as `branch (repo)`, e.g. because the
+ * target branch is missing or the commits no longer apply. The attempt keeps its
+ * history but not its workspace repo for that repository.
+ */
+unrestored_branches: Array<string>, };
 
 export type ConflictOp = "rebase" | "merge" | "cherry_pick" | "revert";
 
-export type ExecutorAction = { typ: ExecutorActionType, next_action: ExecutorAction | null, };
+export type ExecutorAction = { typ: ExecutorActionType, next_action: ExecutorAction | null, 
+/**
+ * Set when the action is a step of a pipeline
+ */
+pipeline_step: PipelineStepRef | null, 
+/**
+ * Follow-ups already sent back to the agent because a verify script failed
+ */
+verify_retries: number, };
+
+export type PipelineStepRef = { pipeline_name: string, step_name: string, 
+/**
+ * Zero-based index of the step
+ */
+index: number, total: number, };
 
 export type McpConfig = { servers: { [key in string]?: JsonValue }, servers_path: Array<string>, template: JsonValue, preconfigured: JsonValue, is_toml_config: boolean, };
 
 export type ExecutorActionType = { "type": "CodingAgentInitialRequest" } & CodingAgentInitialRequest | { "type": "CodingAgentFollowUpRequest" } & CodingAgentFollowUpRequest | { "type": "ScriptRequest" } & ScriptRequest;
 
-export type ScriptContext = "SetupScript" | "CleanupScript" | "DevServer" | "ToolInstallScript";
+export type ScriptContext = "SetupScript" | "CleanupScript" | "DevServer" | "ToolInstallScript" | "VerifyScript";
 
 export type ScriptRequest = { script: string, language: ScriptRequestLanguage, context: ScriptContext, 
 /**
@@ -363,9 +746,9 @@ working_dir: string | null, };
 
 export type ScriptRequestLanguage = "Bash";
 
-export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT", DROID = "DROID" }
+export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT", DROID = "DROID", CUSTOM_AGENT = "CUSTOM_AGENT" }
 
-export type CodingAgent = { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid };
+export type CodingAgent = { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "CUSTOM_AGENT": CustomAgent };
 
 export type AvailabilityInfo = { "type": "LOGIN_DETECTED", last_auth_timestamp: bigint, } | { "type": "INSTALLATION_FOUND" } | { "type": "NOT_FOUND" };
 
@@ -389,10 +772,26 @@ executor: BaseCodingAgent,
  */
 variant: string | null, };
 
-export type ExecutorConfig = { [key in string]?: { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } };
+export type ExecutorConfig = { [key in string]?: { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "CUSTOM_AGENT": CustomAgent } };
 
 export type ExecutorConfigs = { executors: { [key in BaseCodingAgent]?: ExecutorConfig }, };
 
+export type SandboxConfig = { enabled: boolean, network: SandboxNetwork, 
+/**
+ * Hosts reachable in `allowlist` mode; `*.example.com` also matches `example.com`
+ */
+allowed_hosts: Array<string>, 
+/**
+ * Extra host paths mounted read-only, `~/` is expanded to the home directory
+ */
+read_only_paths: Array<string>, 
+/**
+ * Extra host paths mounted read-write, e.g. `~/.cargo/registry` to let builds fetch crates
+ */
+read_write_paths: Array<string>, };
+
+export type SandboxNetwork = "full" | "allowlist" | "none";
+
 export enum BaseAgentCapability { SESSION_FORK = "SESSION_FORK", SETUP_HELPER = "SETUP_HELPER" }
 
 export type ClaudeCode = { append_prompt: AppendPrompt, claude_code_router?: boolean | null, plan?: boolean | null, approvals?: boolean | null, model?: string | null, dangerously_skip_permissions?: boolean | null, disable_api_key?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
@@ -431,6 +830,16 @@ export type Autonomy = "normal" | "low" | "medium" | "high" | "skip-permissions-
 
 export type DroidReasoningEffort = "none" | "dynamic" | "off" | "low" | "medium" | "high";
 
+export type CustomAgent = { append_prompt: AppendPrompt, command: string, prompt_input: CustomAgentPromptInput, follow_up_params?: Array<string> | null, session_id_regex?: string | null, output_format: CustomAgentOutputFormat, json_lines?: CustomAgentJsonLines | null, mcp_config_path?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+
+export type CustomAgentPromptInput = "stdin" | "argument";
+
+export type CustomAgentOutputFormat = "plain_text" | "acp" | "json_lines";
+
+export type CustomAgentJsonLines = { type_field: string, content_field: string, tool_name_field?: string | null, session_id_field?: string | null, entry_types: { [key in string]?: CustomAgentEntryType }, };
+
+export type CustomAgentEntryType = "user_message" | "assistant_message" | "thinking" | "tool_use" | "system_message" | "error_message" | "ignore";
+
 export type AppendPrompt = string | null;
 
 export type CodingAgentInitialRequest = { prompt: string, 